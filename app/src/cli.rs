@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 
 /// Parsed representation of `logline fold` CLI arguments.
@@ -81,6 +82,722 @@ impl FoldCommand {
     }
 }
 
+/// Parsed representation of `logline compare` CLI arguments.
+#[derive(Debug, Clone)]
+pub struct CompareCommand {
+    pub model: PathBuf,
+    pub reference: PathBuf,
+    pub lddt_csv: Option<PathBuf>,
+    pub bfactor_output: Option<PathBuf>,
+}
+
+impl CompareCommand {
+    /// Parses the `logline compare` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline compare <FOLDED.pdb> <REFERENCE.pdb> [--lddt-csv <CSV>] [--bfactors <PDB>]`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut lddt_csv: Option<PathBuf> = None;
+        let mut bfactor_output: Option<PathBuf> = None;
+
+        let mut index = 0;
+        while index < args.len() {
+            match args[index].as_str() {
+                "--lddt-csv" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--lddt-csv expects a path".to_string())?;
+                    lddt_csv = Some(PathBuf::from(value));
+                }
+                "--bfactors" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--bfactors expects a path".to_string())?;
+                    bfactor_output = Some(PathBuf::from(value));
+                }
+                other if other.starts_with('-') => {
+                    return Err(format!("unknown compare argument: {other}"));
+                }
+                other => positional.push(PathBuf::from(other)),
+            }
+            index += 1;
+        }
+
+        match <[PathBuf; 2]>::try_from(positional) {
+            Ok([model, reference]) => Ok(Self {
+                model,
+                reference,
+                lddt_csv,
+                bfactor_output,
+            }),
+            Err(_) => Err("compare expects <folded.pdb> <reference.pdb>".into()),
+        }
+    }
+}
+
+/// Parsed representation of `logline align` CLI arguments.
+#[derive(Debug, Clone)]
+pub struct AlignCommand {
+    pub mobile: PathBuf,
+    pub target: PathBuf,
+    /// 1-based residue positions (CA order) used for the fit; empty means all.
+    pub residues: Vec<RangeInclusive<usize>>,
+    pub output: PathBuf,
+}
+
+impl AlignCommand {
+    /// Parses the `logline align` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline align <MOBILE> <TARGET> [--residues 1-20,35-40] [--output <ALIGNED>]`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut residues = Vec::new();
+        let mut output: Option<PathBuf> = None;
+
+        let mut index = 0;
+        while index < args.len() {
+            match args[index].as_str() {
+                "--residues" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--residues expects ranges like 1-20,30-45".to_string())?;
+                    residues.extend(parse_residue_ranges(value)?);
+                }
+                "--output" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--output expects a path".to_string())?;
+                    output = Some(PathBuf::from(value));
+                }
+                other if other.starts_with('-') => {
+                    return Err(format!("unknown align argument: {other}"));
+                }
+                other => positional.push(PathBuf::from(other)),
+            }
+            index += 1;
+        }
+
+        let [mobile, target] = <[PathBuf; 2]>::try_from(positional)
+            .map_err(|_| "align expects <mobile> <target> structures".to_string())?;
+        let output = output.unwrap_or_else(|| default_aligned_path(&mobile));
+        Ok(Self {
+            mobile,
+            target,
+            residues,
+            output,
+        })
+    }
+
+    /// Zero-based indices selected by `residues`, clipped to `length`.
+    pub fn selection(&self, length: usize) -> Vec<usize> {
+        if self.residues.is_empty() {
+            return (0..length).collect();
+        }
+        let mut indices: Vec<usize> = self
+            .residues
+            .iter()
+            .flat_map(|range| range.clone())
+            .filter(|&residue| residue >= 1 && residue <= length)
+            .map(|residue| residue - 1)
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+/// Parsed representation of `logline derive-contract` CLI arguments.
+#[derive(Debug, Clone)]
+pub struct DeriveContractCommand {
+    pub target: PathBuf,
+    /// Sequence for the starting chain; defaults to the target's residues.
+    pub sequence: Option<PathBuf>,
+    pub output: PathBuf,
+    pub max_step_degrees: Option<f64>,
+}
+
+impl DeriveContractCommand {
+    /// Parses the `logline derive-contract` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline derive-contract <TARGET> [--sequence <FASTA|JSON>] [--output <LLL>] [--max-step <DEG>]`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut target: Option<PathBuf> = None;
+        let mut sequence: Option<PathBuf> = None;
+        let mut output: Option<PathBuf> = None;
+        let mut max_step_degrees: Option<f64> = None;
+
+        let mut index = 0;
+        while index < args.len() {
+            match args[index].as_str() {
+                "--sequence" | "--fasta" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--sequence expects a path".to_string())?;
+                    sequence = Some(PathBuf::from(value));
+                }
+                "--output" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--output expects a path".to_string())?;
+                    output = Some(PathBuf::from(value));
+                }
+                "--max-step" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--max-step expects degrees".to_string())?;
+                    let degrees: f64 = value
+                        .parse()
+                        .map_err(|_| format!("invalid --max-step value '{value}'"))?;
+                    if degrees <= 0.0 {
+                        return Err("--max-step must be positive".into());
+                    }
+                    max_step_degrees = Some(degrees);
+                }
+                other if other.starts_with('-') => {
+                    return Err(format!("unknown derive-contract argument: {other}"));
+                }
+                other => {
+                    if target.is_some() {
+                        return Err(format!("unexpected positional argument '{other}'"));
+                    }
+                    target = Some(PathBuf::from(other));
+                }
+            }
+            index += 1;
+        }
+
+        let target = target.ok_or_else(|| "missing target structure (PDB or mmCIF)".to_string())?;
+        let output = output.unwrap_or_else(|| target.with_extension("lll"));
+        Ok(Self {
+            target,
+            sequence,
+            output,
+            max_step_degrees,
+        })
+    }
+}
+
+/// Table formats supported by `logline analyze --export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Parquet,
+}
+
+/// Parsed representation of `logline analyze` CLI arguments.
+#[derive(Debug, Clone)]
+pub struct AnalyzeCommand {
+    pub log: PathBuf,
+    pub export: Option<ExportFormat>,
+    /// Directory for exported tables; defaults to the log's directory.
+    pub output_dir: PathBuf,
+    /// Temperature (K) for the quasi-harmonic entropy of GROMACS trajectories.
+    pub temperature: f64,
+    /// Other span logs of the same sequence whose energy samples pool with
+    /// the log's into one foldability index.
+    pub pool: Vec<PathBuf>,
+}
+
+impl AnalyzeCommand {
+    /// Parses the `logline analyze` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline analyze <LOG|TRAJ.xtc|TRAJ.trr> [--export parquet] [--output-dir <DIR>] [--temp <K>] [--pool <LOG>]...`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut log: Option<PathBuf> = None;
+        let mut export: Option<ExportFormat> = None;
+        let mut output_dir: Option<PathBuf> = None;
+        let mut temperature = 300.0;
+        let mut pool = Vec::new();
+
+        let mut index = 0;
+        while index < args.len() {
+            match args[index].as_str() {
+                "--export" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--export expects a format".to_string())?;
+                    export = match value.to_ascii_lowercase().as_str() {
+                        "parquet" => Some(ExportFormat::Parquet),
+                        other => return Err(format!("unsupported export format '{other}'")),
+                    };
+                }
+                "--output-dir" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--output-dir expects a path".to_string())?;
+                    output_dir = Some(PathBuf::from(value));
+                }
+                "--temp" | "--temperature" => {
+                    index += 1;
+                    temperature = args
+                        .get(index)
+                        .and_then(|value| value.parse().ok())
+                        .ok_or_else(|| "--temp expects a temperature in K".to_string())?;
+                }
+                "--pool" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--pool expects a span log".to_string())?;
+                    pool.push(PathBuf::from(value));
+                }
+                other if other.starts_with('-') => {
+                    return Err(format!("unknown analyze argument: {other}"));
+                }
+                other => {
+                    if log.is_some() {
+                        return Err(format!("unexpected positional argument '{other}'"));
+                    }
+                    log = Some(PathBuf::from(other));
+                }
+            }
+            index += 1;
+        }
+
+        let log = log.ok_or_else(|| "missing span log path".to_string())?;
+        let output_dir = output_dir.unwrap_or_else(|| {
+            log.parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from("."))
+        });
+        Ok(Self {
+            log,
+            export,
+            output_dir,
+            temperature,
+            pool,
+        })
+    }
+}
+
+/// Parsed representation of `logline demux` CLI arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemuxCommand {
+    pub log: PathBuf,
+    /// Where to write the replica occupying each temperature slot over time.
+    pub csv: Option<PathBuf>,
+}
+
+impl DemuxCommand {
+    /// Parses the `logline demux` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline demux <MD.log> [--csv <CSV>]`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut log: Option<PathBuf> = None;
+        let mut csv: Option<PathBuf> = None;
+
+        let mut index = 0;
+        while index < args.len() {
+            match args[index].as_str() {
+                "--csv" => {
+                    index += 1;
+                    let value = args
+                        .get(index)
+                        .ok_or_else(|| "--csv expects a path".to_string())?;
+                    csv = Some(PathBuf::from(value));
+                }
+                other if other.starts_with('-') => {
+                    return Err(format!("unknown demux argument: {other}"));
+                }
+                other => {
+                    if log.is_some() {
+                        return Err("demux expects a single replica-exchange log".into());
+                    }
+                    log = Some(PathBuf::from(other));
+                }
+            }
+            index += 1;
+        }
+
+        let log = log.ok_or_else(|| "demux expects <md.log>".to_string())?;
+        Ok(Self { log, csv })
+    }
+}
+
+/// Parsed representation of `logline db` CLI arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbCommand {
+    /// `logline db query <DB> <SQL>`
+    Query { database: PathBuf, sql: String },
+    /// `logline db runs <DB>`
+    Runs { database: PathBuf },
+    /// `logline db spans <DB> <RUN_ID>`
+    Spans { database: PathBuf, run_id: String },
+    /// `logline db violations <DB>`
+    Violations { database: PathBuf },
+}
+
+impl DbCommand {
+    /// Parses the `logline db` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline db <query|runs|spans|violations> <DB> [SQL|RUN_ID]`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let action = args
+            .first()
+            .ok_or_else(|| "db expects an action (query, runs, spans, violations)".to_string())?;
+        let database = args
+            .get(1)
+            .map(PathBuf::from)
+            .ok_or_else(|| format!("db {action} expects a database path"))?;
+        let rest = &args[2..];
+        match (action.to_ascii_lowercase().as_str(), rest) {
+            ("query", [_, ..]) => Ok(Self::Query {
+                database,
+                sql: rest.join(" "),
+            }),
+            ("query", []) => Err("db query expects an SQL statement".into()),
+            ("runs", []) => Ok(Self::Runs { database }),
+            ("spans", [run_id]) => Ok(Self::Spans {
+                database,
+                run_id: run_id.clone(),
+            }),
+            ("spans", _) => Err("db spans expects a single run id".into()),
+            ("violations", []) => Ok(Self::Violations { database }),
+            (other, _) => Err(format!("unknown db action or arguments: {other}")),
+        }
+    }
+
+    pub fn database(&self) -> &Path {
+        match self {
+            Self::Query { database, .. }
+            | Self::Runs { database }
+            | Self::Spans { database, .. }
+            | Self::Violations { database } => database,
+        }
+    }
+}
+
+/// Parsed representation of `logline run` CLI arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunCommand {
+    pub manifest: PathBuf,
+}
+
+impl RunCommand {
+    /// Parses the `logline run` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline run --manifest <RUN.MANIFEST.TOML>`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        match args {
+            [flag, path] if flag == "--manifest" => Ok(Self {
+                manifest: PathBuf::from(path),
+            }),
+            _ => Err("run expects --manifest <path>".into()),
+        }
+    }
+}
+
+/// Parsed representation of `logline contracts` CLI arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContractsCommand {
+    /// `logline contracts list [DIR]`
+    List { dir: PathBuf },
+}
+
+impl ContractsCommand {
+    /// Parses the `logline contracts` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline contracts list [DIR]` (defaults to `contracts/`)
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        match args {
+            [action, rest @ ..] if action.eq_ignore_ascii_case("list") => match rest {
+                [] => Ok(Self::List {
+                    dir: PathBuf::from("contracts"),
+                }),
+                [dir] => Ok(Self::List {
+                    dir: PathBuf::from(dir),
+                }),
+                _ => Err("contracts list accepts at most one directory".into()),
+            },
+            [other, ..] => Err(format!("unknown contracts action: {other}")),
+            [] => Err("contracts expects an action (list)".into()),
+        }
+    }
+}
+
+/// Parsed representation of `logline presets` CLI arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetsCommand {
+    /// `logline presets list`
+    List,
+}
+
+impl PresetsCommand {
+    /// Parses the `logline presets` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline presets list`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        match args {
+            [action] if action.eq_ignore_ascii_case("list") => Ok(Self::List),
+            [other, ..] => Err(format!("unknown presets action or arguments: {other}")),
+            [] => Err("presets expects an action (list)".into()),
+        }
+    }
+}
+
+/// Parsed representation of `logline bench` CLI arguments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BenchCommand {
+    /// `logline bench --nve [--steps N] [--dt PS] [--residues N] [--temp K] [--max-drift KCAL]`
+    Nve {
+        steps: Option<usize>,
+        timestep_ps: Option<f64>,
+        residues: Option<usize>,
+        /// Temperature of the initial velocities.
+        temperature: Option<f64>,
+        /// Fail when any integrator drifts by more than this many kcal/mol per ns.
+        max_drift: Option<f64>,
+    },
+}
+
+impl BenchCommand {
+    /// Parses the `logline bench` subcommand arguments.
+    ///
+    /// The expected syntax is:
+    /// `logline bench --nve [--steps N] [--dt PS] [--residues N] [--temp K] [--max-drift KCAL]`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut nve = false;
+        let mut steps = None;
+        let mut timestep_ps = None;
+        let mut residues = None;
+        let mut temperature = None;
+        let mut max_drift = None;
+
+        let mut index = 0;
+        while index < args.len() {
+            let flag = args[index].as_str();
+            let mut value = || {
+                index += 1;
+                args.get(index)
+                    .ok_or_else(|| format!("{flag} expects a value"))
+            };
+            match flag {
+                "--nve" => nve = true,
+                "--steps" => {
+                    steps = Some(value()?.parse().map_err(|_| "--steps expects a count")?);
+                }
+                "--dt" | "--timestep" => {
+                    let dt: f64 = value()?.parse().map_err(|_| "--dt expects picoseconds")?;
+                    if !(dt.is_finite() && dt > 0.0) {
+                        return Err("--dt must be a positive time step in ps".into());
+                    }
+                    timestep_ps = Some(dt);
+                }
+                "--residues" => {
+                    let count: usize =
+                        value()?.parse().map_err(|_| "--residues expects a count")?;
+                    if count < 2 {
+                        return Err("--residues needs at least 2 residues".into());
+                    }
+                    residues = Some(count);
+                }
+                "--temp" | "--temperature" => {
+                    let kelvin: f64 = value()?.parse().map_err(|_| "--temp expects kelvin")?;
+                    if !(kelvin.is_finite() && kelvin >= 0.0) {
+                        return Err("--temp must be a non-negative temperature in K".into());
+                    }
+                    temperature = Some(kelvin);
+                }
+                "--max-drift" => {
+                    let drift = value()?
+                        .parse()
+                        .map_err(|_| "--max-drift expects kcal/mol per ns")?;
+                    max_drift = Some(drift);
+                }
+                other => return Err(format!("unknown bench argument: {other}")),
+            }
+            index += 1;
+        }
+
+        if !nve {
+            return Err("bench expects a benchmark (--nve)".into());
+        }
+        Ok(Self::Nve {
+            steps,
+            timestep_ps,
+            residues,
+            temperature,
+            max_drift,
+        })
+    }
+}
+
+/// How commands report their result on stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    /// One JSON document with the final summary; text goes away.
+    Json,
+}
+
+impl OutputFormat {
+    /// Removes `--output json|text` (or `--output=json`, `--json`) from
+    /// `args`, wherever they sit. `--output` followed by anything else is
+    /// left alone, since `fold` uses it for its structure path.
+    pub fn take_from(args: &mut Vec<String>) -> Result<Self, String> {
+        let mut format = Self::Text;
+        let mut index = 0;
+        while index < args.len() {
+            let (value, width) = match args[index].as_str() {
+                "--json" => ("json", 1),
+                "--output" => match args.get(index + 1).map(String::as_str) {
+                    Some(value @ ("json" | "text")) => (value, 2),
+                    _ => {
+                        index += 1;
+                        continue;
+                    }
+                },
+                flag => match flag.strip_prefix("--output=") {
+                    Some(value) => (value, 1),
+                    None => {
+                        index += 1;
+                        continue;
+                    }
+                },
+            };
+            format = match value {
+                "json" => Self::Json,
+                "text" => Self::Text,
+                other => return Err(format!("unknown output format '{other}' (text, json)")),
+            };
+            args.drain(index..index + width);
+        }
+        Ok(format)
+    }
+}
+
+/// Options that take no value; every other `--option` is followed by one.
+const SWITCHES: [&str; 10] = [
+    "--real-time",
+    "--overwrite-log",
+    "--require-backend",
+    "--thermo-check",
+    "--mem-report",
+    "--ghosts",
+    "--rollback",
+    "--no-rollback",
+    "--nve",
+    "--json",
+];
+
+/// How much the CLI reports on stderr, from `-q` up to `-vvv`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// `-q`: errors only, and no text summary on stdout.
+    Quiet,
+    /// Warnings and errors.
+    #[default]
+    Normal,
+    /// `-v`: also run progress (log paths, physics level switches).
+    Verbose,
+    /// `-vv`: also one line per span and per rejection.
+    Debug,
+    /// `-vvv`: everything the crates log.
+    Trace,
+}
+
+impl Verbosity {
+    /// Removes `-q`/`--quiet` and `-v`/`-vv`/`-vvv`/`--verbose` from `args`;
+    /// repeated `-v` flags add up. The value after an option is left alone,
+    /// and so is everything from a `--` on.
+    pub fn take_from(args: &mut Vec<String>) -> Result<Self, String> {
+        let mut quiet = false;
+        let mut verbose = 0;
+        let mut index = 0;
+        while index < args.len() {
+            match args[index].as_str() {
+                "--" => break,
+                "-q" | "--quiet" => quiet = true,
+                "--verbose" => verbose += 1,
+                flag if flag.len() > 1
+                    && flag.starts_with('-')
+                    && flag[1..].chars().all(|c| c == 'v') =>
+                {
+                    verbose += flag.len() - 1;
+                }
+                option => {
+                    let takes_value = option.starts_with("--")
+                        && !option.contains('=')
+                        && !SWITCHES.contains(&option);
+                    index += if takes_value { 2 } else { 1 };
+                    continue;
+                }
+            }
+            args.remove(index);
+        }
+        match (quiet, verbose) {
+            (true, 0) => Ok(Self::Quiet),
+            (true, _) => Err("-q and -v cannot be combined".into()),
+            (false, 0) => Ok(Self::Normal),
+            (false, 1) => Ok(Self::Verbose),
+            (false, 2) => Ok(Self::Debug),
+            (false, _) => Ok(Self::Trace),
+        }
+    }
+
+    pub fn level_filter(self) -> log::LevelFilter {
+        match self {
+            Self::Quiet => log::LevelFilter::Error,
+            Self::Normal => log::LevelFilter::Warn,
+            Self::Verbose => log::LevelFilter::Info,
+            Self::Debug => log::LevelFilter::Debug,
+            Self::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+fn parse_residue_ranges(raw: &str) -> Result<Vec<RangeInclusive<usize>>, String> {
+    raw.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let part = part.trim();
+            let (start, end) = part.split_once('-').unwrap_or((part, part));
+            let start: usize = start
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid residue range '{part}'"))?;
+            let end: usize = end
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid residue range '{part}'"))?;
+            if start == 0 || end < start {
+                return Err(format!("invalid residue range '{part}'"));
+            }
+            Ok(start..=end)
+        })
+        .collect()
+}
+
+fn default_aligned_path(mobile: &Path) -> PathBuf {
+    let stem = mobile
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "structure".into());
+    let extension = mobile
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "pdb".into());
+    mobile.with_file_name(format!("{stem}_aligned.{extension}"))
+}
+
 fn default_output_path(input: &Path) -> PathBuf {
     let mut path = PathBuf::from(input);
     path.set_extension("pdb");
@@ -97,6 +814,26 @@ fn default_contract_path(output: &Path) -> PathBuf {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parses_bench_nve() {
+        let args: Vec<String> = ["--nve", "--steps", "500", "--max-drift", "0.5"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            BenchCommand::parse(&args).unwrap(),
+            BenchCommand::Nve {
+                steps: Some(500),
+                timestep_ps: None,
+                residues: None,
+                temperature: None,
+                max_drift: Some(0.5),
+            }
+        );
+        assert!(BenchCommand::parse(&["--steps".into(), "5".into()]).is_err());
+        assert!(BenchCommand::parse(&["--nve".into(), "--dt".into(), "0".into()]).is_err());
+    }
+
     #[test]
     fn parses_minimal_arguments() {
         let args = vec!["input.fasta".to_string()];
@@ -132,4 +869,195 @@ mod tests {
         let args = vec!["input.fa".into(), "--weird".into()];
         assert!(FoldCommand::parse(&args).is_err());
     }
+
+    #[test]
+    fn compare_requires_two_structures() {
+        let args = vec!["folded.pdb".into(), "reference.pdb".into()];
+        let cmd = CompareCommand::parse(&args).unwrap();
+        assert_eq!(cmd.model, PathBuf::from("folded.pdb"));
+        assert_eq!(cmd.reference, PathBuf::from("reference.pdb"));
+        assert!(cmd.lddt_csv.is_none());
+        assert!(CompareCommand::parse(&args[..1]).is_err());
+    }
+
+    #[test]
+    fn align_parses_residue_ranges_and_default_output() {
+        let args = vec![
+            "model.cif".into(),
+            "target.pdb".into(),
+            "--residues".into(),
+            "2-4,7".into(),
+        ];
+        let cmd = AlignCommand::parse(&args).unwrap();
+        assert_eq!(cmd.output, PathBuf::from("model_aligned.cif"));
+        assert_eq!(cmd.selection(6), vec![1, 2, 3]);
+        assert_eq!(cmd.selection(10), vec![1, 2, 3, 6]);
+        assert!(
+            AlignCommand::parse(&[
+                "a.pdb".into(),
+                "b.pdb".into(),
+                "--residues".into(),
+                "5-2".into()
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn derive_contract_defaults_output_next_to_target() {
+        let args = vec!["targets/1abc.pdb".into(), "--max-step".into(), "15".into()];
+        let cmd = DeriveContractCommand::parse(&args).unwrap();
+        assert_eq!(cmd.output, PathBuf::from("targets/1abc.lll"));
+        assert_eq!(cmd.max_step_degrees, Some(15.0));
+        assert!(cmd.sequence.is_none());
+        assert!(DeriveContractCommand::parse(&[]).is_err());
+    }
+
+    #[test]
+    fn compare_accepts_lddt_outputs() {
+        let args = vec![
+            "folded.pdb".into(),
+            "reference.pdb".into(),
+            "--lddt-csv".into(),
+            "lddt.csv".into(),
+            "--bfactors".into(),
+            "scored.pdb".into(),
+        ];
+        let cmd = CompareCommand::parse(&args).unwrap();
+        assert_eq!(cmd.lddt_csv, Some(PathBuf::from("lddt.csv")));
+        assert_eq!(cmd.bfactor_output, Some(PathBuf::from("scored.pdb")));
+    }
+
+    #[test]
+    fn analyze_parses_export_format() {
+        let args = vec!["logs/run.log".into(), "--export".into(), "Parquet".into()];
+        let cmd = AnalyzeCommand::parse(&args).unwrap();
+        assert_eq!(cmd.export, Some(ExportFormat::Parquet));
+        assert_eq!(cmd.output_dir, PathBuf::from("logs"));
+        assert_eq!(cmd.temperature, 300.0);
+        assert!(cmd.pool.is_empty());
+        assert!(
+            AnalyzeCommand::parse(&["run.log".into(), "--export".into(), "xlsx".into()]).is_err()
+        );
+    }
+
+    #[test]
+    fn analyze_collects_the_logs_to_pool() {
+        let args: Vec<String> = ["a.log", "--pool", "b.log", "--pool", "c.log"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let cmd = AnalyzeCommand::parse(&args).unwrap();
+        assert_eq!(cmd.log, PathBuf::from("a.log"));
+        assert_eq!(cmd.pool, [PathBuf::from("b.log"), PathBuf::from("c.log")]);
+        assert!(AnalyzeCommand::parse(&["a.log".into(), "--pool".into()]).is_err());
+    }
+
+    #[test]
+    fn demux_takes_a_log_and_an_optional_csv() {
+        let args: Vec<String> = ["md.log", "--csv", "index.csv"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            DemuxCommand::parse(&args).unwrap(),
+            DemuxCommand {
+                log: PathBuf::from("md.log"),
+                csv: Some(PathBuf::from("index.csv"))
+            }
+        );
+        assert!(DemuxCommand::parse(&[]).is_err());
+        assert!(DemuxCommand::parse(&["a.log".into(), "b.log".into()]).is_err());
+    }
+
+    #[test]
+    fn db_parses_actions() {
+        let args: Vec<String> = ["query", "runs.db", "SELECT", "count(*)", "FROM", "runs"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            DbCommand::parse(&args).unwrap(),
+            DbCommand::Query {
+                database: PathBuf::from("runs.db"),
+                sql: "SELECT count(*) FROM runs".into()
+            }
+        );
+        assert!(DbCommand::parse(&["runs".into(), "runs.db".into(), "extra".into()]).is_err());
+        assert!(DbCommand::parse(&["spans".into(), "runs.db".into()]).is_err());
+    }
+
+    #[test]
+    fn run_requires_manifest_flag() {
+        let cmd = RunCommand::parse(&["--manifest".into(), "run.manifest.toml".into()]).unwrap();
+        assert_eq!(cmd.manifest, PathBuf::from("run.manifest.toml"));
+        assert!(RunCommand::parse(&["run.manifest.toml".into()]).is_err());
+    }
+
+    #[test]
+    fn contracts_list_defaults_to_contracts_dir() {
+        assert_eq!(
+            ContractsCommand::parse(&["list".into()]).unwrap(),
+            ContractsCommand::List {
+                dir: PathBuf::from("contracts")
+            }
+        );
+        assert!(ContractsCommand::parse(&["show".into()]).is_err());
+    }
+
+    #[test]
+    fn output_format_is_taken_out_of_the_arguments() {
+        let mut args: Vec<String> = ["fold", "seq.fa", "--output", "out.pdb", "--output", "json"]
+            .map(String::from)
+            .into();
+        assert_eq!(OutputFormat::take_from(&mut args), Ok(OutputFormat::Json));
+        assert_eq!(args, ["fold", "seq.fa", "--output", "out.pdb"]);
+        let mut args = vec!["--json".to_string(), "--output=text".into()];
+        assert_eq!(OutputFormat::take_from(&mut args), Ok(OutputFormat::Text));
+        assert!(args.is_empty());
+        assert!(OutputFormat::take_from(&mut vec!["--output=yaml".into()]).is_err());
+    }
+
+    #[test]
+    fn verbosity_flags_are_counted_and_removed() {
+        let mut args: Vec<String> = ["--preset", "demo", "-v", "--seed", "7", "-v"]
+            .map(String::from)
+            .into();
+        assert_eq!(Verbosity::take_from(&mut args), Ok(Verbosity::Debug));
+        assert_eq!(args, ["--preset", "demo", "--seed", "7"]);
+        let mut args = vec!["-q".to_string()];
+        assert_eq!(Verbosity::take_from(&mut args), Ok(Verbosity::Quiet));
+        assert_eq!(
+            Verbosity::take_from(&mut vec!["-vvv".into()]),
+            Ok(Verbosity::Trace)
+        );
+        assert_eq!(Verbosity::take_from(&mut Vec::new()), Ok(Verbosity::Normal));
+        assert!(Verbosity::take_from(&mut vec!["-q".into(), "-vv".into()]).is_err());
+    }
+
+    #[test]
+    fn verbosity_flags_skip_option_values_and_stop_at_double_dash() {
+        // Switches take no value, so the flags after them count
+        let mut args: Vec<String> = ["--mem-report", "-v", "--json", "-q"]
+            .map(String::from)
+            .into();
+        assert!(Verbosity::take_from(&mut args).is_err());
+        let mut args: Vec<String> = ["--run-name", "-v", "--mem-report", "-v", "--", "-q"]
+            .map(String::from)
+            .into();
+        assert_eq!(Verbosity::take_from(&mut args), Ok(Verbosity::Verbose));
+        assert_eq!(args, ["--run-name", "-v", "--mem-report", "--", "-q"]);
+        let mut args: Vec<String> = ["--seed=7", "-q"].map(String::from).into();
+        assert_eq!(Verbosity::take_from(&mut args), Ok(Verbosity::Quiet));
+        assert_eq!(args, ["--seed=7"]);
+    }
+
+    #[test]
+    fn presets_accepts_only_list() {
+        assert_eq!(
+            PresetsCommand::parse(&["LIST".into()]).unwrap(),
+            PresetsCommand::List
+        );
+        assert!(PresetsCommand::parse(&["list".into(), "extra".into()]).is_err());
+    }
 }
@@ -3,7 +3,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use folding_molecule::PeptideChain;
+use folding_molecule::{Dihedral, PeptideChain};
 
 use crate::cli::FoldCommand;
 use crate::protein::ProteinSequence;
@@ -18,7 +18,7 @@ pub struct FoldingArtifacts {
 
 /// Entry point for the `logline fold` CLI.
 pub fn run_fold(command: &FoldCommand) -> Result<FoldingArtifacts, String> {
-    if command.engine.to_ascii_lowercase() != "logline" {
+    if !command.engine.eq_ignore_ascii_case("logline") {
         return Err(format!(
             "unsupported engine '{}'. Only 'logline' is available in v0.1.",
             command.engine
@@ -46,7 +46,7 @@ fn refine_geometry(chain: &mut PeptideChain) {
     }
     let pitch = 1.5_f64;
     let radius = 3.2_f64;
-    for (index, residue) in chain.residues_mut().iter_mut().enumerate() {
+    for (index, mut residue) in chain.residues_mut().enumerate() {
         let idx = index as f64;
         let angle = idx * 2.0 * std::f64::consts::PI / 3.6; // Rough alpha-helix cadence
         let base_z = idx * pitch;
@@ -55,8 +55,9 @@ fn refine_geometry(chain: &mut PeptideChain) {
         let y = (radius + radial_offset) * angle.sin();
         let z = base_z;
         residue.set_position([x, y, z]);
-        residue.phi = angle.to_degrees() % 360.0;
-        residue.psi = (angle + std::f64::consts::FRAC_PI_2).to_degrees() % 360.0;
+        *residue.torsion_mut(Dihedral::Phi) = angle.to_degrees() % 360.0;
+        *residue.torsion_mut(Dihedral::Psi) =
+            (angle + std::f64::consts::FRAC_PI_2).to_degrees() % 360.0;
     }
 }
 
@@ -132,8 +133,8 @@ mod tests {
             sequence: "ACDEFG".into(),
         };
         let mut chain = sequence.to_chain();
-        let original = chain.residues()[0].position();
+        let original = chain.positions()[0];
         refine_geometry(&mut chain);
-        assert_ne!(chain.residues()[0].position()[0], original[0]);
+        assert_ne!(chain.positions()[0][0], original[0]);
     }
 }
@@ -1,35 +1,178 @@
 mod cli;
 mod folding;
+mod manifest;
+mod output;
 mod protein;
+mod rules;
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::env;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader};
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use cli::FoldCommand;
+use cli::{
+    AlignCommand, AnalyzeCommand, BenchCommand, CompareCommand, ContractsCommand, DbCommand,
+    DemuxCommand, DeriveContractCommand, ExportFormat, FoldCommand, OutputFormat, PresetsCommand,
+    RunCommand, Verbosity,
+};
+use folding_core::physics_bridge::{PhysicsEngine, check_md_timestep};
+use folding_core::{
+    CollectiveVariable, ContractInstruction, DEFAULT_THERMO_TOLERANCE,
+    DEFAULT_WALL_CLOCK_BUDGET_SECS, EnergySamples, ExecutionReport, FoldingContract,
+    GeometryValidator, JAMMING_FRACTION, NativeVelocities, PhysicsLevel, RecoveryPolicy,
+    RngCheckpoint, Ruleset, RunOutcome, ScoreExpression, TargetContractOptions, TrajectoryFrame,
+    backbone_dihedrals, backmap, choose_physics_level, contract_from_target, peak_rss_bytes,
+    physics_nve, physics_registry, place_ca_trace,
+};
 use folding_interface::{
-    CommandShell, EnvironmentPreset, FoldSpan, InformationToRotation, InputLoader, LogLineWriter,
-    LogMetadata, PresetLoader, ShellConfig, TempScheduleConfig,
+    CommandShell, ContactPrior, CrowdingModel, DEFAULT_PLDDT_CUTOFF, DielectricModel, Environment,
+    FrustrationClass, FrustrationOptions, FrustrationReport, GhostBudget, GhostLimit,
+    GhostOverflow, InformationToRotation, InputLoader, LogLineWriter, ModelInput, ModelScoreConfig,
+    PointGroup, PredictedModel, PresetLoader, RUNS_QUERY, ShellConfig, SideChainBead, SqliteSink,
+    Symmetry, TempScheduleConfig, VIOLATIONS_QUERY, alphafold_file_name, energy_components_csv,
+    frustration_csv, geometry_lines, is_uniprot_accession, residue_energies_csv,
 };
-use folding_sim::{FoldingMetrics, TrajectoryVisualizer};
+use folding_molecule::{PeptideChain, Residue, ResidueId};
+use folding_sim::{
+    ACCEPTANCE_COLLAPSE, ACCEPTANCE_SATURATION, AcceptanceRegime, ColorMetric, DisorderProfile,
+    FoldingMetrics, MotionSignal, ReplicaExchangeLog, RunAnalysis, Superposition,
+    TrajectoryVisualizer, VisualScene, chimerax_script, compare_structures, contact_count,
+    encode_binary_cif, encode_dcd, export_parquet, frustration_pymol_script,
+    gromacs_trajectory_frames, is_gromacs_trajectory, is_replica_exchange_log, lddt,
+    load_gromacs_trajectory, mutual_information_matrix, psf_topology, pymol_script,
+    quasi_harmonic_entropy, radius_of_gyration, rmsf, smooth_frames, transfer_entropy_matrix,
+    viewer_json, vmd_script,
+};
+use manifest::{AnnealParameters, InputDigest, RunManifest, RunParameters, RunSection};
+use output::say;
+use serde_json::json;
+
+/// Gap (Å) left between neighbouring protomers when `--symmetry-radius` is not given.
+const SYMMETRY_CLEARANCE: f64 = 4.0;
+
+/// Probability below which `--contacts` pairs are dropped unless
+/// `--contact-min-prob` says otherwise.
+const DEFAULT_CONTACT_MIN_PROBABILITY: f64 = 0.5;
+
+/// Directory AlphaFold DB models fetched by `--predicted <accession>` are
+/// kept in; a model already there is reused.
+const PREDICTED_MODEL_DIR: &str = "models";
+
+/// Bytes per MiB, for `--mem-cap` and `--mem-report`.
+const MIB: f64 = 1024.0 * 1024.0;
 
 struct CliOptions {
     preset: Option<String>,
     fasta: Option<PathBuf>,
+    /// `--mmcif`: start from the first chain of an experimental structure.
+    mmcif: Option<PathBuf>,
+    /// `--pdb`: start from a known structure, every chain of its first model.
+    pdb: Option<PathBuf>,
     contract: Option<PathBuf>,
     temperature: Option<f64>,
+    /// `--accept-temp`: Metropolis temperature apart from the thermostat.
+    acceptance_temperature: Option<f64>,
     time_step_ms: Option<u64>,
+    /// `--real-time`: release spans `time_step_ms` apart on the wall clock.
+    real_time: bool,
     rng_seed: Option<u64>,
+    /// `--rng-state`: resume the move stream from a logged `rng|` state.
+    rng_state: Option<RngCheckpoint>,
+    /// `--rng-log-every`: log the RNG state every N accepted spans.
+    rng_log_interval: Option<usize>,
+    /// `--acceptance-window`: Metropolis decisions per rolling acceptance point.
+    acceptance_window: Option<usize>,
     log_path: Option<PathBuf>,
+    /// `--overwrite-log`: replace an existing `--log` file.
+    overwrite_log: bool,
+    /// `--run-name`: logged beside the run id and naming default logs.
+    run_name: Option<String>,
     replay: Option<PathBuf>,
     info_scale: f64,
     environment: Option<String>,
+    viscosity: Option<f64>,
+    dielectric: Option<f64>,
+    crowding: Option<f64>,
+    crowding_model: Option<CrowdingModel>,
+    dielectric_model: Option<DielectricModel>,
     diamond_threshold: Option<f64>,
     diamond_dir: Option<PathBuf>,
     show_ghosts: bool,
+    /// `--tag`: replay lists only the spans carrying this contract tag.
+    replay_tag: Option<String>,
     temp_schedule: Option<(f64, f64, usize)>,
+    reference: Option<PathBuf>,
+    database: Option<PathBuf>,
+    upload: Option<String>,
+    frames_path: Option<PathBuf>,
+    smooth_frames: usize,
+    pymol_script: Option<PathBuf>,
+    chimerax_script: Option<PathBuf>,
+    vmd_script: Option<PathBuf>,
+    color_by: ColorMetric,
+    /// `--energy-csv`: force-field energy by term, sampled every
+    /// `--energy-every` accepted spans (default 1).
+    energy_csv: Option<PathBuf>,
+    energy_every: Option<usize>,
+    /// `--residue-energy-csv`: the same samples attributed to each residue.
+    residue_energy_csv: Option<PathBuf>,
+    /// `--frustration`: contact frustration of the final structure as CSV,
+    /// with a PyMOL script drawing it.
+    frustration: Option<PathBuf>,
+    /// `--disorder`: per-residue disorder propensity as CSV, with the final
+    /// structure carrying it in its B-factors.
+    disorder: Option<PathBuf>,
+    mi_matrix: Option<PathBuf>,
+    mi_signal: MotionSignal,
+    te_matrix: Option<PathBuf>,
+    pathway: Option<(usize, usize)>,
+    symmetry: Option<PointGroup>,
+    symmetry_radius: Option<f64>,
+    side_chains: Option<SideChainBead>,
+    /// `--contacts`: predicted contact or distance map turned into restraints.
+    contacts: Option<PathBuf>,
+    contact_min_probability: f64,
+    /// `--predicted`: AlphaFold/ESMFold PDB, or a UniProt accession to fetch
+    /// from AlphaFold DB, used as the start and held by pLDDT restraints.
+    predicted: Option<String>,
+    plddt_min: f64,
+    assembly_path: Option<PathBuf>,
+    /// `--final-pdb`: final Cα structure, one PDB chain per chain of a complex.
+    final_pdb: Option<PathBuf>,
+    backmap_path: Option<PathBuf>,
+    physics_level: Option<PhysicsLevel>,
+    physics_engine: PhysicsEngine,
+    /// `--require-backend`: abort when a physics backend fails.
+    require_backend: bool,
+    /// `--thermo-check`: verify energy bookkeeping after every span.
+    thermo_check: bool,
+    auto_level: bool,
+    wall_clock_budget: Option<f64>,
+    rules_path: Option<PathBuf>,
+    ghost_limit: Option<GhostLimit>,
+    ghost_overflow: GhostOverflow,
+    force_field: Option<String>,
+    integrator: Option<String>,
+    /// `--md-timestep`: MD timestep of physics spans, in ps.
+    md_timestep: Option<f64>,
+    plugins: Vec<PathBuf>,
+    scores: Vec<(String, ScoreExpression)>,
+    /// `--cv`: collective variables appended to every accepted span line.
+    collective_variables: Vec<CollectiveVariable>,
+    model_url: Option<String>,
+    model_weight: f64,
+    model_input: ModelInput,
+    openmm_cache: Option<PathBuf>,
+    /// `--native-trajectories`: directory for native physics spans' MD frames.
+    native_trajectories: Option<PathBuf>,
+    /// GROMACS trajectory and frame (default: last) seeding the Cα positions.
+    start_from: Option<(PathBuf, Option<usize>)>,
+    /// `--mem-report`: print peak RSS and per-subsystem memory after the run.
+    mem_report: bool,
+    /// `--mem-cap`, in MiB: thin the kept frames to stay under it.
+    mem_cap_mb: Option<f64>,
 }
 
 impl CliOptions {
@@ -37,18 +180,84 @@ impl CliOptions {
         let mut options = Self {
             preset: None,
             fasta: None,
+            mmcif: None,
+            pdb: None,
             contract: None,
             temperature: None,
+            acceptance_temperature: None,
             time_step_ms: None,
+            real_time: false,
             rng_seed: None,
+            rng_state: None,
+            rng_log_interval: None,
+            acceptance_window: None,
             log_path: None,
+            overwrite_log: false,
+            run_name: None,
             replay: None,
             info_scale: 0.01,
             environment: None,
+            viscosity: None,
+            dielectric: None,
+            crowding: None,
+            crowding_model: None,
+            dielectric_model: None,
             diamond_threshold: None,
             diamond_dir: None,
             show_ghosts: false,
+            replay_tag: None,
             temp_schedule: None,
+            reference: None,
+            database: None,
+            upload: None,
+            frames_path: None,
+            smooth_frames: 0,
+            pymol_script: None,
+            chimerax_script: None,
+            vmd_script: None,
+            color_by: ColorMetric::Entropy,
+            energy_csv: None,
+            energy_every: None,
+            residue_energy_csv: None,
+            frustration: None,
+            disorder: None,
+            mi_matrix: None,
+            mi_signal: MotionSignal::Displacement,
+            te_matrix: None,
+            pathway: None,
+            symmetry: None,
+            side_chains: None,
+            symmetry_radius: None,
+            contacts: None,
+            contact_min_probability: DEFAULT_CONTACT_MIN_PROBABILITY,
+            predicted: None,
+            plddt_min: DEFAULT_PLDDT_CUTOFF,
+            assembly_path: None,
+            final_pdb: None,
+            backmap_path: None,
+            physics_level: None,
+            physics_engine: PhysicsEngine::Auto,
+            require_backend: false,
+            thermo_check: false,
+            auto_level: false,
+            wall_clock_budget: None,
+            rules_path: None,
+            ghost_limit: None,
+            ghost_overflow: GhostOverflow::Prune,
+            force_field: None,
+            integrator: None,
+            md_timestep: None,
+            plugins: Vec::new(),
+            scores: Vec::new(),
+            collective_variables: Vec::new(),
+            model_url: None,
+            model_weight: 1.0,
+            model_input: ModelInput::Coordinates,
+            openmm_cache: None,
+            native_trajectories: None,
+            start_from: None,
+            mem_report: false,
+            mem_cap_mb: None,
         };
 
         let mut i = 0;
@@ -63,6 +272,8 @@ impl CliOptions {
             match arg.as_str() {
                 "--preset" => options.preset = Some(next()?),
                 "--fasta" => options.fasta = Some(PathBuf::from(next()?)),
+                "--mmcif" => options.mmcif = Some(PathBuf::from(next()?)),
+                "--pdb" => options.pdb = Some(PathBuf::from(next()?)),
                 "--contract" => options.contract = Some(PathBuf::from(next()?)),
                 "--temp" | "--temperature" => {
                     options.temperature = Some(
@@ -71,6 +282,15 @@ impl CliOptions {
                             .map_err(|_| "invalid temperature".to_string())?,
                     )
                 }
+                "--accept-temp" | "--acceptance-temperature" => {
+                    let kelvin: f64 = next()?
+                        .parse()
+                        .map_err(|_| "invalid acceptance temperature".to_string())?;
+                    if !(kelvin.is_finite() && kelvin > 0.0) {
+                        return Err("acceptance temperature must be a positive number".into());
+                    }
+                    options.acceptance_temperature = Some(kelvin);
+                }
                 "--dt" | "--time-step" => {
                     options.time_step_ms = Some(
                         next()?
@@ -78,11 +298,39 @@ impl CliOptions {
                             .map_err(|_| "invalid time step".to_string())?,
                     )
                 }
+                "--real-time" => options.real_time = true,
                 "--seed" => {
                     options.rng_seed =
                         Some(next()?.parse().map_err(|_| "invalid seed".to_string())?)
                 }
+                "--rng-state" => options.rng_state = Some(parse_rng_state(&next()?)?),
+                "--rng-log-every" => {
+                    let spans: usize = next()?
+                        .parse()
+                        .map_err(|_| "invalid RNG log interval".to_string())?;
+                    if spans == 0 {
+                        return Err("--rng-log-every expects a positive span count".into());
+                    }
+                    options.rng_log_interval = Some(spans);
+                }
+                "--acceptance-window" => {
+                    let decisions: usize = next()?
+                        .parse()
+                        .map_err(|_| "invalid acceptance window".to_string())?;
+                    if decisions == 0 {
+                        return Err("--acceptance-window expects a positive decision count".into());
+                    }
+                    options.acceptance_window = Some(decisions);
+                }
                 "--log" => options.log_path = Some(PathBuf::from(next()?)),
+                "--overwrite-log" => options.overwrite_log = true,
+                "--run-name" => {
+                    let name = next()?;
+                    if name.is_empty() || name.contains(['/', '\\', '|', '=']) {
+                        return Err(format!("invalid run name '{name}'"));
+                    }
+                    options.run_name = Some(name);
+                }
                 "--replay" => options.replay = Some(PathBuf::from(next()?)),
                 "--info-scale" => {
                     options.info_scale = next()?
@@ -90,6 +338,43 @@ impl CliOptions {
                         .map_err(|_| "invalid info scale".to_string())?
                 }
                 "--env" | "--environment" => options.environment = Some(next()?),
+                "--viscosity" => {
+                    options.viscosity = Some(
+                        next()?
+                            .parse()
+                            .map_err(|_| "invalid viscosity".to_string())?,
+                    )
+                }
+                "--dielectric" => {
+                    options.dielectric = Some(
+                        next()?
+                            .parse()
+                            .map_err(|_| "invalid dielectric".to_string())?,
+                    )
+                }
+                "--crowding" => {
+                    let fraction: f64 = next()?
+                        .parse()
+                        .map_err(|_| "invalid crowding fraction".to_string())?;
+                    if !(0.0..=0.95).contains(&fraction) {
+                        return Err("crowding fraction must be between 0 and 0.95".into());
+                    }
+                    options.crowding = Some(fraction);
+                }
+                "--dielectric-model" => {
+                    let raw = next()?;
+                    options.dielectric_model = Some(
+                        DielectricModel::parse(&raw)
+                            .ok_or_else(|| format!("unknown dielectric model '{raw}'"))?,
+                    )
+                }
+                "--crowding-model" => {
+                    let raw = next()?;
+                    options.crowding_model = Some(
+                        CrowdingModel::parse(&raw)
+                            .ok_or_else(|| format!("unknown crowding model '{raw}'"))?,
+                    )
+                }
                 "--diamond-threshold" => {
                     options.diamond_threshold = Some(
                         next()?
@@ -115,7 +400,192 @@ impl CliOptions {
                         .map_err(|_| "invalid anneal steps".to_string())?;
                     options.temp_schedule = Some((start, end, steps));
                 }
+                "--reference" => options.reference = Some(PathBuf::from(next()?)),
+                "--db" => options.database = Some(PathBuf::from(next()?)),
+                "--upload" => {
+                    if !cfg!(feature = "upload") {
+                        return Err("--upload requires building with `--features upload`".into());
+                    }
+                    options.upload = Some(next()?);
+                }
+                "--frames" => options.frames_path = Some(PathBuf::from(next()?)),
+                "--smooth" => {
+                    options.smooth_frames = next()?
+                        .parse()
+                        .map_err(|_| "invalid smooth frame count".to_string())?
+                }
+                "--pymol" => options.pymol_script = Some(PathBuf::from(next()?)),
+                "--chimerax" => options.chimerax_script = Some(PathBuf::from(next()?)),
+                "--vmd" => options.vmd_script = Some(PathBuf::from(next()?)),
+                "--energy-csv" => options.energy_csv = Some(PathBuf::from(next()?)),
+                "--energy-every" => {
+                    let spans: usize = next()?
+                        .parse()
+                        .map_err(|_| "invalid energy sampling stride".to_string())?;
+                    if spans == 0 {
+                        return Err("--energy-every expects a positive span count".into());
+                    }
+                    options.energy_every = Some(spans);
+                }
+                "--residue-energy-csv" => options.residue_energy_csv = Some(PathBuf::from(next()?)),
+                "--frustration" => options.frustration = Some(PathBuf::from(next()?)),
+                "--disorder" => options.disorder = Some(PathBuf::from(next()?)),
+                "--mi-matrix" => options.mi_matrix = Some(PathBuf::from(next()?)),
+                "--mi-signal" => {
+                    let raw = next()?;
+                    options.mi_signal = MotionSignal::parse(&raw)
+                        .ok_or_else(|| format!("unknown motion signal '{raw}'"))?;
+                }
+                "--te-matrix" => options.te_matrix = Some(PathBuf::from(next()?)),
+                "--pathway" => {
+                    let raw = next()?;
+                    let (from, to) = raw
+                        .split_once([':', '-'])
+                        .ok_or_else(|| "--pathway expects FROM:TO".to_string())?;
+                    let parse = |value: &str| {
+                        value
+                            .trim()
+                            .parse::<usize>()
+                            .map_err(|_| format!("invalid pathway residue '{value}'"))
+                    };
+                    options.pathway = Some((parse(from)?, parse(to)?));
+                }
+                "--symmetry" => {
+                    let raw = next()?;
+                    options.symmetry =
+                        Some(PointGroup::parse(&raw).ok_or_else(|| {
+                            format!("unknown point group '{raw}' (C2, C3, D2, ...)")
+                        })?)
+                }
+                "--side-chains" => {
+                    let raw = next()?;
+                    options.side_chains =
+                        Some(SideChainBead::parse(&raw).ok_or_else(|| {
+                            format!("unknown side-chain bead '{raw}' (cb, centroid)")
+                        })?)
+                }
+                "--symmetry-radius" => {
+                    options.symmetry_radius = Some(
+                        next()?
+                            .parse()
+                            .map_err(|_| "invalid symmetry radius".to_string())?,
+                    )
+                }
+                "--contacts" => options.contacts = Some(PathBuf::from(next()?)),
+                "--contact-min-prob" => {
+                    let probability: f64 = next()?
+                        .parse()
+                        .map_err(|_| "invalid contact probability".to_string())?;
+                    if !(0.0..=1.0).contains(&probability) {
+                        return Err("--contact-min-prob must be between 0 and 1".into());
+                    }
+                    options.contact_min_probability = probability;
+                }
+                "--predicted" => options.predicted = Some(next()?),
+                "--plddt-min" => {
+                    let plddt: f64 = next()?
+                        .parse()
+                        .map_err(|_| "invalid pLDDT cutoff".to_string())?;
+                    if !(0.0..=100.0).contains(&plddt) {
+                        return Err("--plddt-min must be between 0 and 100".into());
+                    }
+                    options.plddt_min = plddt;
+                }
+                "--assembly" => options.assembly_path = Some(PathBuf::from(next()?)),
+                "--final-pdb" => options.final_pdb = Some(PathBuf::from(next()?)),
+                "--backmap" => options.backmap_path = Some(PathBuf::from(next()?)),
+                "--rules" => options.rules_path = Some(PathBuf::from(next()?)),
+                "--ghost-budget" => {
+                    let raw = next()?;
+                    options.ghost_limit = Some(GhostLimit::parse(&raw).ok_or_else(|| {
+                        format!("invalid ghost budget '{raw}' (count:N or entropy:F)")
+                    })?);
+                }
+                "--ghost-overflow" => {
+                    let raw = next()?;
+                    options.ghost_overflow = GhostOverflow::parse(&raw).ok_or_else(|| {
+                        format!("unknown ghost overflow '{raw}' (stop, escalate[:F], prune)")
+                    })?;
+                }
+                "--force-field" => options.force_field = Some(next()?.to_lowercase()),
+                "--integrator" => options.integrator = Some(next()?.to_lowercase()),
+                "--md-timestep" => {
+                    let picoseconds: f64 = next()?
+                        .parse()
+                        .map_err(|_| "--md-timestep expects a timestep in ps".to_string())?;
+                    if !(picoseconds.is_finite() && picoseconds > 0.0) {
+                        return Err("--md-timestep must be positive".into());
+                    }
+                    options.md_timestep = Some(picoseconds);
+                }
+                "--physics-engine" => {
+                    let raw = next()?;
+                    options.physics_engine = PhysicsEngine::parse(&raw).ok_or_else(|| {
+                        format!("unknown physics engine '{raw}' (openmm, native, auto)")
+                    })?;
+                }
+                "--require-backend" => options.require_backend = true,
+                "--thermo-check" => options.thermo_check = true,
+                "--plugin" => options.plugins.push(PathBuf::from(next()?)),
+                "--score" => options.scores.push(parse_score(&next()?)?),
+                "--cv" => options
+                    .collective_variables
+                    .push(CollectiveVariable::parse(&next()?)?),
+                "--model-score" => options.model_url = Some(next()?),
+                "--openmm-cache" => options.openmm_cache = Some(PathBuf::from(next()?)),
+                "--native-trajectories" => {
+                    options.native_trajectories = Some(PathBuf::from(next()?))
+                }
+                "--start-from" => options.start_from = Some(parse_start_from(&next()?)?),
+                "--mem-report" => options.mem_report = true,
+                "--mem-cap" => {
+                    let cap: f64 = next()?
+                        .parse()
+                        .map_err(|_| "invalid memory cap".to_string())?;
+                    if cap <= 0.0 {
+                        return Err("--mem-cap expects a positive size in MiB".into());
+                    }
+                    options.mem_cap_mb = Some(cap);
+                }
+                "--model-weight" => {
+                    options.model_weight = next()?
+                        .parse()
+                        .map_err(|_| "invalid model weight".to_string())?
+                }
+                "--model-input" => {
+                    let raw = next()?;
+                    options.model_input = ModelInput::parse(&raw).ok_or_else(|| {
+                        format!("unknown model input '{raw}' (coordinates, features)")
+                    })?;
+                }
+                "--level" => {
+                    let raw = next()?;
+                    if raw.eq_ignore_ascii_case("auto") {
+                        options.auto_level = true;
+                    } else {
+                        options.physics_level =
+                            Some(PhysicsLevel::parse(&raw).ok_or_else(|| {
+                                format!(
+                                    "unknown physics level '{raw}' (toy, coarse, gb, full, auto)"
+                                )
+                            })?);
+                    }
+                }
+                "--budget" => {
+                    options.wall_clock_budget = Some(
+                        next()?
+                            .parse()
+                            .map_err(|_| "invalid wall-clock budget".to_string())?,
+                    )
+                }
+                "--color-by" => {
+                    let raw = next()?;
+                    options.color_by = ColorMetric::parse(&raw).ok_or_else(|| {
+                        format!("unknown colour metric '{raw}' (entropy, rmsf, disorder)")
+                    })?;
+                }
                 "--ghosts" => options.show_ghosts = true,
+                "--tag" => options.replay_tag = Some(next()?.trim_start_matches('#').to_string()),
                 other if other.starts_with('-') => {
                     return Err(format!("unknown argument: {}", other));
                 }
@@ -128,222 +598,736 @@ impl CliOptions {
 
         Ok(options)
     }
+
+    /// Options that repeat a recorded run; the log goes to a fresh path.
+    fn from_manifest(manifest: &RunManifest) -> Result<Self, String> {
+        let params = &manifest.parameters;
+        Ok(Self {
+            preset: params.preset.clone(),
+            fasta: params.fasta.clone(),
+            mmcif: params.mmcif.clone(),
+            pdb: params.pdb.clone(),
+            contract: params.contract.clone(),
+            temperature: Some(params.temperature),
+            acceptance_temperature: params.acceptance_temperature,
+            time_step_ms: Some(params.time_step_ms),
+            real_time: false,
+            rng_seed: Some(params.rng_seed),
+            rng_state: params
+                .rng_state
+                .as_deref()
+                .map(|state| {
+                    Ok::<_, String>(RngCheckpoint {
+                        step: params.rng_step.unwrap_or(0),
+                        velocities: params.rng_velocities.as_deref().unwrap_or("").parse()?,
+                        ..parse_rng_state(state)?
+                    })
+                })
+                .transpose()?,
+            rng_log_interval: params.rng_log_every,
+            acceptance_window: params.acceptance_window,
+            log_path: None,
+            overwrite_log: false,
+            run_name: manifest.run.run_name.clone(),
+            replay: None,
+            info_scale: params.info_scale,
+            environment: Some(params.environment.clone()),
+            viscosity: params.viscosity,
+            dielectric: params.dielectric,
+            crowding: params.crowding_fraction,
+            crowding_model: params
+                .crowding_model
+                .as_deref()
+                .map(|raw| {
+                    CrowdingModel::parse(raw)
+                        .ok_or_else(|| format!("unknown crowding model '{raw}'"))
+                })
+                .transpose()?,
+            dielectric_model: params
+                .dielectric_model
+                .as_deref()
+                .map(|raw| {
+                    DielectricModel::parse(raw)
+                        .ok_or_else(|| format!("unknown dielectric model '{raw}'"))
+                })
+                .transpose()?,
+            diamond_threshold: params.diamond_threshold,
+            diamond_dir: params.diamond_dir.clone(),
+            show_ghosts: false,
+            replay_tag: None,
+            temp_schedule: params
+                .anneal
+                .as_ref()
+                .map(|anneal| (anneal.start, anneal.end, anneal.steps)),
+            reference: params.reference.clone(),
+            database: params.database.clone(),
+            upload: params.upload.clone(),
+            frames_path: params.frames.clone(),
+            smooth_frames: params.smooth_frames,
+            pymol_script: params.pymol.clone(),
+            chimerax_script: params.chimerax.clone(),
+            vmd_script: params.vmd.clone(),
+            color_by: ColorMetric::parse(&params.color_by)
+                .ok_or_else(|| format!("unknown colour metric '{}'", params.color_by))?,
+            energy_csv: params.energy_csv.clone(),
+            energy_every: params.energy_every,
+            residue_energy_csv: params.residue_energy_csv.clone(),
+            frustration: params.frustration.clone(),
+            disorder: params.disorder.clone(),
+            mi_matrix: params.mi_matrix.clone(),
+            mi_signal: match params.mi_signal.as_deref() {
+                Some(raw) => MotionSignal::parse(raw)
+                    .ok_or_else(|| format!("unknown motion signal '{raw}'"))?,
+                None => MotionSignal::Displacement,
+            },
+            te_matrix: params.te_matrix.clone(),
+            pathway: params.pathway.map(|[from, to]| (from, to)),
+            symmetry: params
+                .symmetry
+                .as_deref()
+                .map(|raw| {
+                    PointGroup::parse(raw).ok_or_else(|| format!("unknown point group '{raw}'"))
+                })
+                .transpose()?,
+            symmetry_radius: params.symmetry_radius,
+            side_chains: params
+                .side_chains
+                .as_deref()
+                .map(|raw| {
+                    SideChainBead::parse(raw)
+                        .ok_or_else(|| format!("unknown side-chain bead '{raw}'"))
+                })
+                .transpose()?,
+            contacts: params.contacts.clone(),
+            contact_min_probability: params
+                .contact_min_probability
+                .unwrap_or(DEFAULT_CONTACT_MIN_PROBABILITY),
+            predicted: params
+                .predicted
+                .as_ref()
+                .map(|path| path.display().to_string()),
+            plddt_min: params.plddt_min.unwrap_or(DEFAULT_PLDDT_CUTOFF),
+            assembly_path: params.assembly.clone(),
+            final_pdb: params.final_pdb.clone(),
+            backmap_path: params.backmap.clone(),
+            physics_level: params
+                .level
+                .as_deref()
+                .map(|raw| {
+                    PhysicsLevel::parse(raw).ok_or_else(|| format!("unknown physics level '{raw}'"))
+                })
+                .transpose()?,
+            physics_engine: match params.physics_engine.as_deref() {
+                Some(raw) => PhysicsEngine::parse(raw)
+                    .ok_or_else(|| format!("unknown physics engine '{raw}'"))?,
+                None => PhysicsEngine::Auto,
+            },
+            require_backend: params.require_backend,
+            thermo_check: params.thermo_check,
+            auto_level: false,
+            wall_clock_budget: params.wall_clock_budget,
+            rules_path: params.rules.clone(),
+            ghost_limit: params
+                .ghost_budget
+                .as_deref()
+                .map(|raw| {
+                    GhostLimit::parse(raw).ok_or_else(|| format!("invalid ghost budget '{raw}'"))
+                })
+                .transpose()?,
+            ghost_overflow: match params.ghost_overflow.as_deref() {
+                Some(raw) => GhostOverflow::parse(raw)
+                    .ok_or_else(|| format!("unknown ghost overflow '{raw}'"))?,
+                None => GhostOverflow::Prune,
+            },
+            force_field: params.force_field.clone(),
+            integrator: params.integrator.clone(),
+            md_timestep: params.md_timestep,
+            plugins: params.plugins.clone(),
+            scores: params
+                .scores
+                .iter()
+                .map(|raw| parse_score(raw))
+                .collect::<Result<_, _>>()?,
+            collective_variables: params
+                .collective_variables
+                .iter()
+                .map(|raw| CollectiveVariable::parse(raw))
+                .collect::<Result<_, _>>()?,
+            model_url: params.model_score.clone(),
+            model_weight: params.model_weight.unwrap_or(1.0),
+            model_input: match params.model_input.as_deref() {
+                Some(raw) => {
+                    ModelInput::parse(raw).ok_or_else(|| format!("unknown model input '{raw}'"))?
+                }
+                None => ModelInput::Coordinates,
+            },
+            openmm_cache: params.openmm_cache.clone(),
+            native_trajectories: params.native_trajectories.clone(),
+            start_from: params
+                .start_from
+                .clone()
+                .map(|path| (path, params.start_frame)),
+            mem_report: false,
+            mem_cap_mb: params.mem_cap_mb,
+        })
+    }
+}
+
+/// Reads a `--contacts` map (`.json`, anything else as CSV/RR rows) and keeps
+/// the pairs at or above `min_probability`.
+fn load_contact_prior(
+    path: &Path,
+    min_probability: f64,
+    residues: usize,
+) -> Result<ContactPrior, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read contacts {}: {err}", path.display()))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let prior = if is_json {
+        ContactPrior::parse_json(&text)
+    } else {
+        ContactPrior::parse_csv(&text)
+    }
+    .map_err(|err| format!("invalid contacts {}: {err}", path.display()))?
+    .filtered(min_probability);
+    prior
+        .check(residues)
+        .map_err(|err| format!("invalid contacts {}: {err}", path.display()))?;
+    if prior.restraints.is_empty() {
+        log::warn!(
+            "no contacts in {} reach probability {min_probability}",
+            path.display()
+        );
+    }
+    Ok(prior)
 }
 
-fn run_replay(path: &Path, show_ghosts: bool) -> Result<(), String> {
-    let file =
-        File::open(path).map_err(|err| format!("failed to open log {}: {err}", path.display()))?;
-    let mut lines = BufReader::new(file).lines();
+/// `--predicted` model: a local PDB, or a UniProt accession fetched from
+/// AlphaFold DB into [`PREDICTED_MODEL_DIR`].
+fn resolve_predicted_model(raw: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(raw);
+    if path.exists() || !is_uniprot_accession(raw) {
+        return Ok(path);
+    }
+    let cached = Path::new(PREDICTED_MODEL_DIR).join(alphafold_file_name(raw));
+    if cached.exists() {
+        return Ok(cached);
+    }
+    #[cfg(feature = "alphafold")]
+    {
+        let model = folding_interface::fetch_alphafold_model(raw)?;
+        ensure_parent_dir(&cached)?;
+        fs::write(&cached, model)
+            .map_err(|err| format!("failed to write model {}: {err}", cached.display()))?;
+        say!("Fetched AlphaFold DB model {raw} into {}", cached.display());
+        Ok(cached)
+    }
+    #[cfg(not(feature = "alphafold"))]
+    Err(format!(
+        "fetching {raw} from AlphaFold DB needs a build with the `alphafold` feature"
+    ))
+}
 
-    let metadata_line = lines
-        .next()
-        .ok_or_else(|| "log file is empty".to_string())?
-        .map_err(|err| err.to_string())?;
-    let metadata = parse_metadata_line(&metadata_line)?;
+/// `--start-from traj.xtc[:FRAME]` or `--start-from model.pdb`; the frame
+/// index is zero-based.
+fn parse_start_from(raw: &str) -> Result<(PathBuf, Option<usize>), String> {
+    let (path, frame) = match raw.rsplit_once(':') {
+        Some((path, frame)) if frame.chars().all(|c| c.is_ascii_digit()) && !frame.is_empty() => (
+            path,
+            Some(frame.parse().map_err(|_| "invalid start frame")?),
+        ),
+        _ => (raw, None),
+    };
+    let path = PathBuf::from(path);
+    if is_gromacs_trajectory(&path) {
+        return Ok((path, frame));
+    }
+    if !is_structure_model(&path) {
+        return Err(format!(
+            "--start-from expects a .trr/.xtc trajectory or a PDB/mmCIF model, got {}",
+            path.display()
+        ));
+    }
+    if frame.is_some() {
+        return Err("--start-from takes a frame only for trajectories".into());
+    }
+    Ok((path, None))
+}
 
-    let mut spans: Vec<FoldSpan> = Vec::new();
-    let mut violation_details = Vec::new();
+fn is_structure_model(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    matches!(extension.as_deref(), Some("pdb" | "ent" | "cif" | "mmcif"))
+}
 
-    for line in lines {
-        let line = line.map_err(|err| err.to_string())?;
-        if line.trim().is_empty() {
-            continue;
+/// Places the chain's Cα atoms at one frame of a GROMACS trajectory or at the
+/// first model of a PDB/mmCIF file (e.g. an earlier run or a predicted model).
+fn seed_start_positions(
+    chain: &mut PeptideChain,
+    path: &Path,
+    frame: Option<usize>,
+) -> Result<(), String> {
+    let positions = if is_gromacs_trajectory(path) {
+        let mut frames = load_gromacs_trajectory(path)?;
+        let index = frame.unwrap_or(frames.len().saturating_sub(1));
+        if index >= frames.len() {
+            return Err(format!(
+                "{} has {} frames; frame {index} is out of range",
+                path.display(),
+                frames.len()
+            ));
         }
-        if line.starts_with("violation|") {
-            violation_details.push(parse_violation_detail(&line));
-        } else if line.starts_with("span|") {
-            spans.push(parse_span_line(&line)?);
+        let selected = frames.swap_remove(index);
+        say!(
+            "Start: frame {index} of {} (step {}, {:.3} ps)",
+            path.display(),
+            selected.step,
+            selected.time_ps
+        );
+        selected.positions
+    } else {
+        let positions = protein::read_ca_coordinates(path)?;
+        say!("Start: Cα model {}", path.display());
+        positions
+    };
+    if positions.len() != chain.len() {
+        return Err(format!(
+            "{} has {} Cα positions but the chain has {} residues; trajectories must be \
+             Cα-only (e.g. `gmx trjconv` with the C-alpha group)",
+            path.display(),
+            positions.len(),
+            chain.len()
+        ));
+    }
+    place_ca_trace(chain, &positions)
+}
+
+/// `--rng-state`: the hexadecimal state of an `rng|` log line, or a span log
+/// whose last `rng|` line is resumed, physics velocities included.
+fn parse_rng_state(raw: &str) -> Result<RngCheckpoint, String> {
+    let path = Path::new(raw.trim());
+    if path.is_file() {
+        let analysis = RunAnalysis::load(path)?;
+        let row = analysis
+            .rng_states
+            .last()
+            .ok_or_else(|| format!("{} has no rng| lines", path.display()))?;
+        return Ok(RngCheckpoint {
+            step: row.step,
+            state: row.state,
+            velocities: row.velocities.parse()?,
+        });
+    }
+    let digits = raw.trim().trim_start_matches("0x");
+    let state =
+        u64::from_str_radix(digits, 16).map_err(|_| format!("invalid RNG state '{raw}'"))?;
+    Ok(RngCheckpoint {
+        step: 0,
+        state,
+        velocities: NativeVelocities::default(),
+    })
+}
+
+/// `--score name=expression`, e.g. `compact=0.5*rg`.
+fn parse_score(raw: &str) -> Result<(String, ScoreExpression), String> {
+    let (name, expression) = raw
+        .split_once('=')
+        .filter(|(name, _)| !name.trim().is_empty())
+        .ok_or_else(|| format!("invalid score '{raw}' (name=expression)"))?;
+    Ok((name.trim().to_string(), ScoreExpression::parse(expression)?))
+}
+
+#[cfg(feature = "plugins")]
+fn load_plugin(path: &Path) -> Result<Vec<String>, String> {
+    physics_registry::load_plugin(path)
+}
+
+#[cfg(not(feature = "plugins"))]
+fn load_plugin(path: &Path) -> Result<Vec<String>, String> {
+    Err(format!(
+        "cannot load plugin {}: built without the `plugins` feature",
+        path.display()
+    ))
+}
+
+/// Every force field and integrator named on the command line or in the
+/// contract must be registered before the run starts, and the MD timestep in
+/// force must be positive and within the stability limit of the force field
+/// spans run with at every point of the contract: the named one, or else the
+/// default of the physics level in force.
+fn check_physics_components(
+    opts: &CliOptions,
+    contract: &FoldingContract,
+    physics_level: PhysicsLevel,
+) -> Result<(), String> {
+    let mut force_field = opts.force_field.as_deref();
+    let mut level = physics_level;
+    let mut timestep = opts.md_timestep;
+    let check_timestep = |level: PhysicsLevel, force_field: Option<&str>, timestep: Option<f64>| {
+        let Some(timestep) = timestep else {
+            return Ok(());
+        };
+        check_md_timestep(level, force_field, timestep).map_err(|err| match force_field {
+            Some(name) => format!("{err} (force field '{name}')"),
+            None => format!("{err} (default force field at level {})", level.as_str()),
+        })
+    };
+    if let Some(name) = force_field {
+        physics_registry::check_force_field(name)?;
+    }
+    if let Some(name) = &opts.integrator {
+        physics_registry::check_integrator(name)?;
+    }
+    check_timestep(level, force_field, timestep)?;
+    for instruction in &contract.instructions {
+        match instruction {
+            ContractInstruction::SetForceField(name) => {
+                physics_registry::check_force_field(name)?;
+                force_field = Some(name);
+            }
+            ContractInstruction::SetIntegrator(name) => physics_registry::check_integrator(name)?,
+            ContractInstruction::SetTimestep(picoseconds) => timestep = *picoseconds,
+            ContractInstruction::SetPhysicsLevel(new_level) => level = *new_level,
+            ContractInstruction::Refine {
+                level: span_level, ..
+            }
+            | ContractInstruction::Simulate {
+                level: Some(span_level),
+                ..
+            } => check_timestep(*span_level, force_field, timestep)?,
+            _ => continue,
         }
+        check_timestep(level, force_field, timestep)?;
     }
+    Ok(())
+}
 
-    let applied = spans.iter().filter(|s| !s.ghost_flag).count();
-    let ghost = spans.len().saturating_sub(applied);
-    let total_work: f64 = spans
-        .iter()
-        .filter(|s| !s.ghost_flag)
-        .map(|s| s.delta_E.abs() * (metadata.time_step_ms as f64 / 1000.0))
-        .sum();
+/// Per-phase totals, nested phases indented under their parent.
+fn print_phases(analysis: &RunAnalysis) {
+    if analysis.phases.is_empty() {
+        return;
+    }
+    say!("\nPhases:");
+    for phase in &analysis.phases {
+        let name = phase.path.rsplit('/').next().unwrap_or(&phase.path);
+        say!(
+            "  {:indent$}{name}: {} spans, ΔE={:.6} ΔS={:.6} over {} ms ({:.6} ps)",
+            "",
+            phase.spans,
+            phase.delta_energy,
+            phase.delta_entropy,
+            phase.duration_ms,
+            phase.simulated_ps,
+            indent = 2 * phase.depth
+        );
+    }
+}
+
+/// Columns of the acceptance chart; longer series are averaged into this many.
+const ACCEPTANCE_CHART_WIDTH: usize = 60;
+
+/// Charts the rolling acceptance rate as one line of block characters
+/// (empty = 0, full = 1) and lists the stretches where it collapsed or
+/// saturated.
+fn print_acceptance(analysis: &RunAnalysis) {
+    let rates: Vec<f64> = analysis.acceptance.iter().map(|row| row.rate).collect();
+    let (Some(first), Some(last)) = (analysis.acceptance.first(), analysis.acceptance.last())
+    else {
+        return;
+    };
+    const LEVELS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let per_column = rates.len().div_ceil(ACCEPTANCE_CHART_WIDTH);
+    let chart: String = rates
+        .chunks(per_column)
+        .map(|chunk| {
+            let mean = chunk.iter().sum::<f64>() / chunk.len() as f64;
+            LEVELS[(mean.clamp(0.0, 1.0) * 8.0).round() as usize]
+        })
+        .collect();
+    let mean = rates.iter().sum::<f64>() / rates.len() as f64;
+    say!(
+        "\nAcceptance (rolling {} decisions, steps {}–{}):",
+        analysis.acceptance_window(),
+        first.step,
+        last.step
+    );
+    say!("  |{chart}|");
+    say!(
+        "  mean {mean:.2}, min {:.2}, max {:.2}",
+        rates.iter().copied().fold(f64::INFINITY, f64::min),
+        rates.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+    );
+    for alert in analysis.acceptance_alerts() {
+        let (verb, bound) = match alert.regime {
+            AcceptanceRegime::Collapsed => ("collapsed to", format!("≤ {ACCEPTANCE_COLLAPSE}")),
+            AcceptanceRegime::Saturated => ("saturated at", format!("≥ {ACCEPTANCE_SATURATION}")),
+        };
+        say!(
+            "  ! steps {}–{}: acceptance {verb} {:.2} ({bound})",
+            alert.first_step,
+            alert.last_step,
+            alert.rate
+        );
+    }
+}
+
+/// Prints the run summary; `--ghosts` lists every span, and `tag` lists only
+/// the spans carrying it, ghosts included only with `--ghosts`.
+fn run_replay(path: &Path, show_ghosts: bool, tag: Option<&str>) -> Result<(), String> {
+    let analysis = RunAnalysis::load(path)?;
+    let metadata = &analysis.metadata;
+    let summary = analysis.summary();
 
-    println!("Replay summary for {}", path.display());
-    println!(
+    say!("Replay summary for {}", path.display());
+    match metadata.run_name.as_deref() {
+        Some(name) => say!("  Run: {} ({name})", metadata.run_id),
+        None => say!("  Run: {}", metadata.run_id),
+    }
+    say!("  Started: {}", metadata.timestamp);
+    say!(
         "  Contract: {}",
-        metadata.contract_name.unwrap_or_else(|| "unknown".into())
+        metadata.contract_name.as_deref().unwrap_or("unknown")
     );
-    println!("  Environment: {}", metadata.environment);
-    println!("  Temperature: {:.2} K", metadata.temperature);
-    println!("  Accepted spans: {}", metadata.accepted_spans);
-    println!("  Rejected spans: {}", metadata.rejected_spans);
-    println!(
+    say!("  Environment: {}", metadata.environment);
+    say!("  Temperature: {:.2} K", metadata.temperature);
+    say!("  Accepted spans: {}", metadata.accepted_spans);
+    say!("  Rejected spans: {}", metadata.rejected_spans);
+    say!(
         "  Acceptance rate: {:.2}%",
         metadata.acceptance_rate * 100.0
     );
-    println!("  Applied spans: {}", applied);
-    println!("  Ghost spans: {}", ghost);
-    println!("  Violations recorded: {}", violation_details.len());
-    println!(
+    say!("  Applied spans: {}", summary.applied_spans);
+    say!("  Ghost spans: {}", summary.ghost_spans);
+    say!("  Violations recorded: {}", summary.violations);
+    say!(
+        "  Span time: {} ms ({:.6} ps simulated)",
+        summary.elapsed_ms,
+        summary.simulated_ps
+    );
+    if let Some(wall_time_ms) = metadata.wall_time_ms {
+        say!("  Wall time: {wall_time_ms:.1} ms");
+    }
+    say!(
         "  Final potential energy: {:.4}",
         metadata.final_potential_energy
     );
-    println!("  Final Gibbs energy: {:.4}", metadata.final_gibbs_energy);
-    println!(
+    say!("  Final Gibbs energy: {:.4}", metadata.final_gibbs_energy);
+    say!(
         "  Informational efficiency η: {:.6}",
         metadata.informational_efficiency
     );
-    println!(
+    say!(
         "  Total work (approx): {:.6}",
-        total_work.max(metadata.total_work)
+        summary.total_work.max(metadata.total_work)
     );
+    if let Some(tm_score) = metadata.tm_score {
+        say!("  TM-score vs reference: {:.4}", tm_score);
+    }
+    if let Some(gdt_ts) = metadata.gdt_ts {
+        say!("  GDT_TS vs reference: {:.4}", gdt_ts);
+    }
+    if let Some(entropy) = metadata.heuristic_entropy {
+        say!("  Span entropy (heuristic ΣΔS): {:.6}", entropy);
+    }
+    if let Some(entropy) = metadata.quasi_harmonic_entropy {
+        say!("  Quasi-harmonic entropy: {:.6} kcal/mol/K", entropy);
+    }
+    if let (Some(index), Some(gap)) = (metadata.foldability, metadata.energy_gap) {
+        say!("  Foldability: {index:.3} (energy gap {gap:.4} kcal/mol)");
+    }
+    if let Some(remote_uri) = metadata.remote_uri.as_deref() {
+        say!("  Remote artifacts: {}", remote_uri);
+    }
+    if let (Some(version), Some(commit)) = (
+        metadata.crate_version.as_deref(),
+        metadata.git_commit.as_deref(),
+    ) {
+        if metadata.features.is_empty() {
+            say!("  Built from: {} @ {}", version, commit);
+        } else {
+            say!(
+                "  Built from: {} @ {} (features: {})",
+                version,
+                commit,
+                metadata.features.join(",")
+            );
+        }
+    }
+    if let (Some(hostname), Some(os)) = (metadata.hostname.as_deref(), metadata.os.as_deref()) {
+        say!("  Host: {} ({})", hostname, os);
+    }
+    if let Some(backend) = metadata.physics_backend.as_deref() {
+        say!("  Physics backend: {}", backend);
+    }
+    if let Some(choice) = metadata.level_choice.as_deref() {
+        say!("  Physics level choice: {}", choice);
+    }
 
-    if show_ghosts {
-        println!("\nSpans:");
-        for (idx, span) in spans.iter().enumerate() {
-            let status = if span.ghost_flag { "GHOST" } else { "ACCEPT" };
-            println!(
+    let tags = analysis.tags();
+    if !tags.is_empty() {
+        say!("  Span tags: {}", tags.join(", "));
+    }
+    print_phases(&analysis);
+
+    let mut listed = Vec::new();
+    if show_ghosts || tag.is_some() {
+        let spans: Vec<_> = match tag {
+            Some(tag) => analysis
+                .tagged(tag)
+                .filter(|span| show_ghosts || !span.ghost)
+                .collect(),
+            None => analysis.spans.iter().collect(),
+        };
+        match tag {
+            Some(tag) => say!(
+                "\nSpans tagged #{tag} ({}, ΔE={:.6}, ΔS={:.6}):",
+                spans.len(),
+                spans.iter().map(|span| span.delta_energy).sum::<f64>(),
+                spans.iter().map(|span| span.delta_entropy).sum::<f64>()
+            ),
+            None => say!("\nSpans:"),
+        }
+        for span in spans {
+            let status = if span.ghost { "GHOST" } else { "ACCEPT" };
+            say!(
                 "  Step {:04} [{}] Δθ={:.4} ΔE={:.6} ΔS={:.6} G={:.6}",
-                idx + 1,
+                span.index + 1,
                 status,
                 span.delta_theta,
-                span.delta_E,
-                span.delta_S,
-                span.G
+                span.delta_energy,
+                span.delta_entropy,
+                span.gibbs_energy
             );
+            listed.push(span);
         }
     }
 
-    if !violation_details.is_empty() {
-        println!("\nViolations:");
-        for detail in violation_details {
-            println!("  - {}", detail);
+    if !analysis.violations.is_empty() {
+        say!("\nViolations:");
+        for violation in &analysis.violations {
+            match (violation.step, &violation.code, &violation.severity) {
+                (Some(step), Some(code), Some(severity)) => {
+                    say!("  - [step {step}] {code} {severity}: {}", violation.detail)
+                }
+                _ => say!("  - {}", violation.detail),
+            }
         }
     }
 
-    Ok(())
-}
-
-fn parse_metadata_line(raw: &str) -> Result<LogMetadata, String> {
-    if !raw.starts_with("metadata|") {
-        return Err("missing metadata prefix".into());
-    }
-    let fields = parse_fields(raw)?;
-    let contract = fields
-        .get("contract_name")
-        .map(|value| {
-            if value.is_empty() {
-                None
-            } else {
-                Some(value.clone())
-            }
-        })
-        .unwrap_or(None);
-    Ok(LogMetadata {
-        run_id: fields
-            .get("run_id")
-            .cloned()
-            .unwrap_or_else(|| "unknown".into()),
-        timestamp: fields
-            .get("timestamp")
-            .cloned()
-            .unwrap_or_else(|| "0".into()),
-        contract_name: contract,
-        environment: fields
-            .get("environment")
-            .cloned()
-            .unwrap_or_else(|| "unknown".into()),
-        temperature: parse_f64_field(&fields, "temperature")?,
-        time_step_ms: parse_u64_field(&fields, "time_step_ms")?,
-        accepted_spans: parse_usize_field(&fields, "accepted_spans")?,
-        rejected_spans: parse_usize_field(&fields, "rejected_spans")?,
-        acceptance_rate: parse_f64_field(&fields, "acceptance_rate")?,
-        final_potential_energy: parse_f64_field(&fields, "final_potential_energy")?,
-        final_gibbs_energy: parse_f64_field(&fields, "final_gibbs_energy")?,
-        informational_efficiency: parse_f64_field(&fields, "informational_efficiency")?,
-        total_work: parse_f64_field(&fields, "total_work")?,
-    })
-}
-
-fn parse_span_line(raw: &str) -> Result<FoldSpan, String> {
-    let fields = parse_fields(raw)?;
-    Ok(FoldSpan {
-        id: fields
-            .get("id")
-            .cloned()
-            .unwrap_or_else(|| "unknown".into()),
-        delta_theta: parse_f64_field(&fields, "delta_theta")?,
-        delta_S: parse_f64_field(&fields, "delta_S")?,
-        delta_I: parse_f64_field(&fields, "delta_I")?,
-        delta_E: parse_f64_field(&fields, "delta_E")?,
-        duration_ms: parse_u64_field(&fields, "duration_ms")?,
-        ghost_flag: matches!(fields.get("ghost_flag"), Some(v) if v == "1"),
-        G: parse_f64_field(&fields, "G")?,
-    })
-}
-
-fn parse_violation_detail(raw: &str) -> String {
-    raw.split('|')
-        .skip(1)
-        .find_map(|segment| segment.strip_prefix("detail="))
-        .unwrap_or("unknown violation")
-        .to_string()
-}
-
-fn parse_fields(raw: &str) -> Result<HashMap<String, String>, String> {
-    let mut map = HashMap::new();
-    for segment in raw.split('|').skip(1) {
-        if segment.is_empty() {
-            continue;
+    if !analysis.geometry_checks.is_empty() {
+        say!(
+            "\nGeometry validation ({} issues):",
+            summary.geometry_issues
+        );
+        for check in &analysis.geometry_checks {
+            say!(
+                "  - {}: {} ({} / {} failing)",
+                check.check,
+                check.status,
+                check.failures,
+                check.checked,
+            );
         }
-        let (key, value) = segment
-            .split_once('=')
-            .ok_or_else(|| format!("invalid field: {segment}"))?;
-        map.insert(key.to_string(), value.to_string());
     }
-    Ok(map)
-}
+    output::emit(
+        "replay",
+        json!({
+            "log": path,
+            "metadata": metadata,
+            "summary": summary,
+            "tags": tags,
+            "phases": analysis.phases,
+            "spans": listed,
+            "violations": analysis.violations,
+            "geometry_checks": analysis.geometry_checks,
+        }),
+    );
 
-fn parse_f64_field(fields: &HashMap<String, String>, key: &str) -> Result<f64, String> {
-    fields
-        .get(key)
-        .ok_or_else(|| format!("missing field {key}"))?
-        .parse()
-        .map_err(|_| format!("invalid float for {key}"))
+    Ok(())
 }
 
-fn parse_u64_field(fields: &HashMap<String, String>, key: &str) -> Result<u64, String> {
-    fields
-        .get(key)
-        .ok_or_else(|| format!("missing field {key}"))?
-        .parse()
-        .map_err(|_| format!("invalid integer for {key}"))
-}
+/// Exit status when a command fails outright (bad input, I/O, backend errors).
+const EXIT_FAILED: u8 = 1;
+/// Exit status for arguments that do not parse.
+const EXIT_USAGE: u8 = 2;
 
-fn parse_usize_field(fields: &HashMap<String, String>, key: &str) -> Result<usize, String> {
-    fields
-        .get(key)
-        .ok_or_else(|| format!("missing field {key}"))?
-        .parse()
-        .map_err(|_| format!("invalid integer for {key}"))
+/// Exit status for a contract run that got to produce a report.
+fn outcome_exit_code(outcome: RunOutcome) -> u8 {
+    match outcome {
+        RunOutcome::Completed => 0,
+        RunOutcome::CompletedWithViolations => 3,
+        RunOutcome::BackendFallback => 4,
+        RunOutcome::Aborted => 5,
+        RunOutcome::Truncated => 6,
+    }
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().collect();
+    match Verbosity::take_from(&mut args) {
+        Ok(verbosity) => output::init_logging(verbosity),
+        Err(err) => {
+            eprintln!("argument error: {err}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    }
+    match OutputFormat::take_from(&mut args) {
+        Ok(format) => output::set_format(format),
+        Err(err) => {
+            log::error!("argument error: {err}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    }
 
-    if args.len() > 1 && args[1].eq_ignore_ascii_case("fold") {
-        if let Err(err) = run_fold_cli(&args[2..]) {
-            eprintln!("fold command failed: {err}");
+    if args.len() > 1 {
+        let subcommand = args[1].to_ascii_lowercase();
+        let completed = |result: Result<(), String>| result.map(|()| RunOutcome::Completed);
+        let result = match subcommand.as_str() {
+            "fold" => Some(completed(run_fold_cli(&args[2..]))),
+            "compare" => Some(completed(run_compare_cli(&args[2..]))),
+            "align" => Some(completed(run_align_cli(&args[2..]))),
+            "derive-contract" => Some(completed(run_derive_contract_cli(&args[2..]))),
+            "analyze" => Some(completed(run_analyze_cli(&args[2..]))),
+            "demux" => Some(completed(run_demux_cli(&args[2..]))),
+            "db" => Some(completed(run_db_cli(&args[2..]))),
+            "run" => Some(run_manifest_cli(&args[2..])),
+            "contracts" => Some(completed(run_contracts_cli(&args[2..]))),
+            "presets" => Some(completed(run_presets_cli(&args[2..]))),
+            "bench" => Some(completed(run_bench_cli(&args[2..]))),
+            _ => None,
+        };
+        if let Some(result) = result {
+            return match result {
+                Ok(outcome) => ExitCode::from(outcome_exit_code(outcome)),
+                Err(err) => {
+                    log::error!("{subcommand} command failed: {err}");
+                    output::emit_error(&subcommand, &err);
+                    ExitCode::from(EXIT_FAILED)
+                }
+            };
         }
-        return;
     }
 
     let opts = match CliOptions::parse_from(&args[1..]) {
         Ok(o) => o,
         Err(err) => {
-            eprintln!("argument error: {err}");
-            return;
+            log::error!("argument error: {err}");
+            output::emit_error("run", &err);
+            return ExitCode::from(EXIT_USAGE);
         }
     };
 
-    if let Err(err) = run_legacy(opts) {
-        eprintln!("{err}");
+    match run_legacy(opts) {
+        Ok(outcome) => ExitCode::from(outcome_exit_code(outcome)),
+        Err(err) => {
+            log::error!("{err}");
+            output::emit_error("run", &err);
+            ExitCode::from(EXIT_FAILED)
+        }
     }
 }
 
@@ -351,100 +1335,796 @@ fn run_fold_cli(args: &[String]) -> Result<(), String> {
     let command = FoldCommand::parse(args)?;
     let artifacts = folding::run_fold(&command)?;
 
-    if let Some(parent) = command.output.parent() {
-        if !parent.as_os_str().is_empty() {
-            fs::create_dir_all(parent).map_err(|err| {
-                format!(
-                    "failed to create output directory {}: {err}",
-                    parent.display()
-                )
-            })?;
-        }
+    if let Some(parent) = command.output.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(|err| {
+            format!(
+                "failed to create output directory {}: {err}",
+                parent.display()
+            )
+        })?;
     }
-    if let Some(parent) = command.contract_path.parent() {
-        if !parent.as_os_str().is_empty() {
-            fs::create_dir_all(parent).map_err(|err| {
-                format!(
-                    "failed to create contract directory {}: {err}",
-                    parent.display()
-                )
-            })?;
-        }
+    if let Some(parent) = command.contract_path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(|err| {
+            format!(
+                "failed to create contract directory {}: {err}",
+                parent.display()
+            )
+        })?;
     }
 
-    protein::write_pdb(&artifacts.chain, &command.output, &artifacts.sequence)?;
+    if command
+        .output
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("bcif"))
+    {
+        let positions = artifacts
+            .chain
+            .residues()
+            .map(|residue| residue.position())
+            .collect();
+        let entry_id = artifacts
+            .sequence
+            .identifier
+            .as_deref()
+            .unwrap_or("logline_fold");
+        let bytes = encode_binary_cif(&artifacts.chain, &[positions], entry_id);
+        fs::write(&command.output, bytes).map_err(|err| {
+            format!(
+                "failed to write BinaryCIF {}: {err}",
+                command.output.display()
+            )
+        })?;
+    } else {
+        protein::write_pdb(&artifacts.chain, &command.output, &artifacts.sequence)?;
+    }
     folding::persist_contract(&artifacts.contract, &command.contract_path)?;
 
-    println!(
+    say!(
         "LogLine fold completed for {} residues.",
         artifacts.sequence.len()
     );
-    println!("PDB written to {}", command.output.display());
-    println!("Contract saved to {}", command.contract_path.display());
+    say!("Structure written to {}", command.output.display());
+    say!("Contract saved to {}", command.contract_path.display());
     if command.rollback {
-        println!("Rollback enabled for this workflow.");
+        say!("Rollback enabled for this workflow.");
     }
     if let Some(ref embeddings) = artifacts.embeddings {
-        println!("PyTorch embeddings length: {}", embeddings.len());
+        say!("PyTorch embeddings length: {}", embeddings.len());
     } else {
-        println!("PyTorch embeddings unavailable; using geometric heuristic.");
+        say!("PyTorch embeddings unavailable; using geometric heuristic.");
+    }
+
+    let geometry = GeometryValidator::default().validate(&artifacts.chain);
+    say!(
+        "Geometry validation: {} ({} issues)",
+        if geometry.passed() { "pass" } else { "fail" },
+        geometry.failure_count()
+    );
+    for line in geometry_lines(&geometry) {
+        say!("{line}");
     }
+    output::emit(
+        "fold",
+        json!({
+            "residues": artifacts.sequence.len(),
+            "rollback": command.rollback,
+            "embeddings": artifacts.embeddings.as_ref().map(|embeddings| embeddings.len()),
+            "geometry": {
+                "passed": geometry.passed(),
+                "issues": geometry.failure_count(),
+            },
+            "files": {
+                "structure": command.output,
+                "contract": command.contract_path,
+            },
+        }),
+    );
 
     Ok(())
 }
 
-fn run_legacy(opts: CliOptions) -> Result<(), String> {
-    if let Some(path) = opts.replay.as_ref() {
-        run_replay(path, opts.show_ghosts)?;
-        return Ok(());
+fn run_analyze_cli(args: &[String]) -> Result<(), String> {
+    let command = AnalyzeCommand::parse(args)?;
+    if is_gromacs_trajectory(&command.log) {
+        if !command.pool.is_empty() {
+            return Err("--pool needs span logs, not a GROMACS trajectory".into());
+        }
+        return analyze_gromacs_trajectory(&command);
+    }
+    if is_replica_exchange_log(&command.log) {
+        return Err(format!(
+            "{} is a replica-exchange log; follow its replicas with `logline demux`",
+            command.log.display()
+        ));
     }
+    let analysis = RunAnalysis::load(&command.log)?;
+    let summary = analysis.summary();
 
-    let mut chain = None;
-    let mut contract = None;
-    let mut label = opts.preset.clone();
+    say!("Analysis of {}", command.log.display());
+    match analysis.metadata.run_name.as_deref() {
+        Some(name) => say!("  Run: {} ({name})", analysis.metadata.run_id),
+        None => say!("  Run: {}", analysis.metadata.run_id),
+    }
+    say!("  Started: {}", analysis.metadata.timestamp);
+    say!(
+        "  Spans: {} applied, {} ghost over {} ms",
+        summary.applied_spans,
+        summary.ghost_spans,
+        summary.elapsed_ms
+    );
+    say!(
+        "  ΔS applied: {:.6} (ghost {:.6})",
+        summary.total_entropy,
+        summary.ghost_entropy
+    );
+    say!("  ΔE applied: {:.6}", summary.total_energy);
+    for entry in analysis.violation_counts() {
+        say!("  Violations {}: {}", entry.kind, entry.count);
+    }
+    print_phases(&analysis);
+    print_acceptance(&analysis);
+    let pooled = if command.pool.is_empty() {
+        None
+    } else {
+        let runs = command.pool.len() + 1;
+        let foldability =
+            pooled_energy_samples(&analysis, &command.log, &command.pool)?.foldability();
+        match foldability {
+            Some(foldability) => say!(
+                "  Foldability over {runs} runs: {:.3} (best {:.4} kcal/mol, gap {:.4} over {} decoys)",
+                foldability.index,
+                foldability.best_energy,
+                foldability.gap,
+                foldability.decoys
+            ),
+            None => say!("  Foldability over {runs} runs: too few samples"),
+        }
+        Some(json!({ "runs": runs, "foldability": foldability }))
+    };
 
-    if let Some(preset) = opts.preset.as_deref() {
-        if let Some(preset_pack) = PresetLoader::load_preset(preset) {
-            chain = Some(preset_pack.chain);
-            contract = Some(preset_pack.contract);
-        } else {
-            eprintln!("preset '{}' not found; falling back to demo", preset);
-            label = Some("demo".into());
+    let mut tables = Vec::new();
+    if let Some(ExportFormat::Parquet) = command.export {
+        let stem = command
+            .log
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "run".into());
+        for path in export_parquet(&analysis, &command.output_dir, &stem)? {
+            say!("Table written to {}", path.display());
+            tables.push(path);
         }
     }
+    output::emit(
+        "analyze",
+        json!({
+            "log": command.log,
+            "metadata": analysis.metadata,
+            "summary": summary,
+            "violation_counts": analysis.violation_counts(),
+            "phases": analysis.phases,
+            "acceptance": analysis.acceptance,
+            "acceptance_alerts": analysis.acceptance_alerts(),
+            "pooled": pooled,
+            "files": { "tables": tables },
+        }),
+    );
+    Ok(())
+}
 
-    if chain.is_none() || contract.is_none() {
-        if let Some(preset_pack) = PresetLoader::load_preset(label.as_deref().unwrap_or("demo")) {
-            chain.get_or_insert(preset_pack.chain.clone());
-            contract.get_or_insert(preset_pack.contract.clone());
-            if label.is_none() {
-                label = Some("demo".into());
-            }
-        }
+/// Energy samples of the analysed run at `log` and of the runs in `pool`,
+/// merged as if they came from one run.
+fn pooled_energy_samples(
+    analysis: &RunAnalysis,
+    log: &Path,
+    pool: &[PathBuf],
+) -> Result<EnergySamples, String> {
+    let missing = |path: &Path| format!("{} records no energy samples", path.display());
+    let mut pooled = analysis
+        .metadata
+        .energy_samples
+        .ok_or_else(|| missing(log))?;
+    for path in pool {
+        let samples = RunAnalysis::load(path)?
+            .metadata
+            .energy_samples
+            .ok_or_else(|| missing(path))?;
+        pooled.merge(&samples);
     }
+    Ok(pooled)
+}
 
-    if let Some(fasta_path) = opts.fasta.as_ref() {
-        match InputLoader::load_fasta(fasta_path) {
-            Ok(loaded_chain) => chain = Some(loaded_chain),
-            Err(err) => {
-                return Err(format!(
-                    "failed to load FASTA {}: {err}",
-                    fasta_path.display()
-                ));
-            }
-        }
+/// Contacts, compaction, fluctuations and quasi-harmonic entropy of an
+/// existing MD trajectory; span-level metrics need a LogLineFold span log.
+fn analyze_gromacs_trajectory(command: &AnalyzeCommand) -> Result<(), String> {
+    if command.export.is_some() {
+        return Err("--export needs a span log, not a GROMACS trajectory".into());
     }
+    let frames = load_gromacs_trajectory(&command.log)?;
+    let (Some(first), Some(last)) = (frames.first(), frames.last()) else {
+        return Err(format!(
+            "{} holds no coordinate frames",
+            command.log.display()
+        ));
+    };
+    let trajectory = gromacs_trajectory_frames(&frames);
+    let contacts: Vec<usize> = frames
+        .iter()
+        .map(|frame| contact_count(&frame.positions))
+        .collect();
+    let mean_contacts = contacts.iter().sum::<usize>() as f64 / contacts.len() as f64;
+    let fluctuations = rmsf(&trajectory);
+    let largest_rmsf = fluctuations
+        .into_iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    let entropy = quasi_harmonic_entropy(&trajectory, command.temperature);
 
-    if let Some(contract_path) = opts.contract.as_ref() {
-        match InputLoader::load_contract(contract_path) {
-            Ok(loaded_contract) => contract = Some(loaded_contract),
-            Err(err) => {
+    say!("Analysis of {}", command.log.display());
+    say!(
+        "  Frames: {} (steps {}–{}, {:.3}–{:.3} ps), {} atoms",
+        frames.len(),
+        first.step,
+        last.step,
+        first.time_ps,
+        last.time_ps,
+        first.positions.len()
+    );
+    say!(
+        "  Cα contacts: {} → {} (mean {mean_contacts:.2})",
+        contacts[0],
+        contacts[contacts.len() - 1]
+    );
+    say!(
+        "  Radius of gyration: {:.3} → {:.3} Å",
+        radius_of_gyration(&first.positions),
+        radius_of_gyration(&last.positions)
+    );
+    if let Some((atom, value)) = largest_rmsf {
+        say!("  Largest RMSF: atom {} ({value:.3} Å)", atom + 1);
+    }
+    match entropy {
+        Some(entropy) => say!(
+            "  Quasi-harmonic entropy at {:.1} K: {entropy:.6} kcal/mol/K",
+            command.temperature
+        ),
+        None => say!("  Quasi-harmonic entropy: needs two frames and three atoms"),
+    }
+    output::emit(
+        "analyze",
+        json!({
+            "log": command.log,
+            "frames": frames.len(),
+            "atoms": first.positions.len(),
+            "steps": [first.step, last.step],
+            "time_ps": [first.time_ps, last.time_ps],
+            "contacts": { "first": contacts[0], "last": contacts[contacts.len() - 1], "mean": mean_contacts },
+            "radius_of_gyration": [radius_of_gyration(&first.positions), radius_of_gyration(&last.positions)],
+            "largest_rmsf": largest_rmsf.map(|(atom, value)| json!({ "atom": atom + 1, "rmsf": value })),
+            "temperature": command.temperature,
+            "quasi_harmonic_entropy": entropy,
+        }),
+    );
+    Ok(())
+}
+
+/// Follows each replica of a replica-exchange run through the temperature
+/// ladder and reports neighbour-pair acceptance and round-trip times.
+fn run_demux_cli(args: &[String]) -> Result<(), String> {
+    let command = DemuxCommand::parse(args)?;
+    let log = ReplicaExchangeLog::load(&command.log)?;
+    let demux = log.demux();
+    let slot_label = |slot: usize| match log.temperatures.get(slot) {
+        Some(temperature) => format!("{slot} ({temperature:.1} K)"),
+        None => slot.to_string(),
+    };
+
+    say!("Replica demux of {}", command.log.display());
+    say!(
+        "  Replicas: {}, exchange attempts: {}",
+        log.replicas,
+        log.attempts.len()
+    );
+    say!("\nNeighbour-pair exchange:");
+    for pair in &demux.pairs {
+        say!(
+            "  {} ↔ {}: {}/{} accepted ({:.2}), mean probability {:.2}",
+            slot_label(pair.lower),
+            slot_label(pair.lower + 1),
+            pair.accepted,
+            pair.attempted,
+            pair.acceptance(),
+            pair.mean_probability
+        );
+    }
+    say!("\nRound trips:");
+    for replica in &demux.replicas {
+        match replica.mean_ps {
+            Some(mean) => say!(
+                "  Replica {}: {} round trips, mean {mean:.3} ps, {} slots visited",
+                replica.replica,
+                replica.round_trips,
+                replica.slots_visited
+            ),
+            None => say!(
+                "  Replica {}: no round trip, {} slots visited",
+                replica.replica,
+                replica.slots_visited
+            ),
+        }
+    }
+
+    if let Some(csv_path) = command.csv.as_ref() {
+        fs::write(csv_path, demux.to_csv())
+            .map_err(|err| format!("failed to write demux CSV {}: {err}", csv_path.display()))?;
+        say!("\nReplica index written to {}", csv_path.display());
+    }
+    output::emit(
+        "demux",
+        json!({
+            "log": command.log,
+            "replicas": log.replicas,
+            "temperatures": log.temperatures,
+            "attempts": log.attempts.len(),
+            "pairs": demux.pairs.iter().map(|pair| json!({
+                "lower": pair.lower,
+                "attempted": pair.attempted,
+                "accepted": pair.accepted,
+                "acceptance": pair.acceptance(),
+                "mean_probability": pair.mean_probability,
+            })).collect::<Vec<_>>(),
+            "round_trips": demux.replicas,
+            "files": { "csv": command.csv },
+        }),
+    );
+    Ok(())
+}
+
+fn run_db_cli(args: &[String]) -> Result<(), String> {
+    let command = DbCommand::parse(args)?;
+    let sink = SqliteSink::open(command.database())?;
+    let result = match &command {
+        DbCommand::Query { sql, .. } => sink.query(sql)?,
+        DbCommand::Runs { .. } => sink.query(RUNS_QUERY)?,
+        DbCommand::Violations { .. } => sink.query(VIOLATIONS_QUERY)?,
+        DbCommand::Spans { run_id, .. } => sink.query(&format!(
+            "SELECT span_index, span_id, ghost, delta_theta, delta_entropy, delta_energy, gibbs_energy FROM spans WHERE run_id = '{}' ORDER BY span_index",
+            run_id.replace('\'', "''")
+        ))?,
+    };
+    if output::is_json() {
+        let rows: Vec<serde_json::Map<String, serde_json::Value>> = result
+            .rows
+            .iter()
+            .map(|row| {
+                result
+                    .columns
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().map(|cell| json!(cell)))
+                    .collect()
+            })
+            .collect();
+        output::emit("db", json!({ "columns": result.columns, "rows": rows }));
+    } else {
+        print!("{}", result.to_tsv());
+    }
+    Ok(())
+}
+
+fn run_manifest_cli(args: &[String]) -> Result<RunOutcome, String> {
+    let command = RunCommand::parse(args)?;
+    let manifest = RunManifest::load(&command.manifest)?;
+    manifest.verify_inputs()?;
+    say!(
+        "Reproducing run {} (seed {})",
+        manifest.run.run_id,
+        manifest.parameters.rng_seed
+    );
+    run_legacy(CliOptions::from_manifest(&manifest)?)
+}
+
+fn run_contracts_cli(args: &[String]) -> Result<(), String> {
+    let ContractsCommand::List { dir } = ContractsCommand::parse(args)?;
+    say!("name\trequires_physics\ttags\tauthor\tdescription");
+    let mut contracts = Vec::new();
+    for entry in PresetLoader::contracts_in(&dir)? {
+        let metadata = &entry.metadata;
+        contracts.push(json!({
+            "name": entry.name,
+            "requires_physics": metadata.requires_physics.map(|level| level.as_str()),
+            "tags": metadata.tags,
+            "author": metadata.author,
+            "description": metadata.description,
+        }));
+        say!(
+            "{}\t{}\t{}\t{}\t{}",
+            entry.name,
+            metadata
+                .requires_physics
+                .map(|level| level.as_str())
+                .unwrap_or("-"),
+            metadata.tags.join(","),
+            metadata.author.as_deref().unwrap_or("-"),
+            metadata.description.as_deref().unwrap_or("")
+        );
+    }
+    output::emit("contracts", json!({ "dir": dir, "contracts": contracts }));
+    Ok(())
+}
+
+fn run_presets_cli(args: &[String]) -> Result<(), String> {
+    let PresetsCommand::List = PresetsCommand::parse(args)?;
+    let search_paths = PresetLoader::search_paths();
+    say!(
+        "Search path: {}",
+        search_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let mut presets = Vec::new();
+    for entry in PresetLoader::available_presets() {
+        say!(
+            "  {:<24} {}  ({})",
+            entry.name,
+            entry.metadata.description.as_deref().unwrap_or("-"),
+            entry.path.display()
+        );
+        presets.push(json!({
+            "name": entry.name,
+            "description": entry.metadata.description,
+            "path": entry.path,
+        }));
+    }
+    output::emit(
+        "presets",
+        json!({ "search_paths": search_paths, "presets": presets }),
+    );
+    Ok(())
+}
+
+fn run_bench_cli(args: &[String]) -> Result<(), String> {
+    let BenchCommand::Nve {
+        steps,
+        timestep_ps,
+        residues,
+        temperature,
+        max_drift,
+    } = BenchCommand::parse(args)?;
+    let defaults = physics_nve::NveConfig::default();
+    let config = physics_nve::NveConfig {
+        residues: residues.unwrap_or(defaults.residues),
+        steps: steps.unwrap_or(defaults.steps),
+        timestep_ps: timestep_ps.unwrap_or(defaults.timestep_ps),
+        temperature: temperature.unwrap_or(defaults.temperature),
+    };
+    let text = !output::is_json();
+    if text {
+        println!(
+            "NVE bench: {}-residue chain at {} K, {} steps of {} ps, no thermostat",
+            config.residues, config.temperature, config.steps, config.timestep_ps
+        );
+        println!("integrator	E0	E_final	max_dev	drift_per_ns");
+    }
+    let reports = physics_nve::run_standard_nve(&config);
+    if text {
+        for report in &reports {
+            println!(
+                "{}	{:.6}	{:.6}	{:.3e}	{:.3e}",
+                report.integrator,
+                report.initial_energy,
+                report.final_energy,
+                report.max_deviation,
+                report.drift_per_ns
+            );
+        }
+    }
+    if let Some(limit) = max_drift {
+        let drifting: Vec<&str> = reports
+            .iter()
+            .filter(|report| report.drift_per_ns.is_nan() || report.drift_per_ns.abs() > limit)
+            .map(|report| report.integrator.as_str())
+            .collect();
+        if !drifting.is_empty() {
+            return Err(format!(
+                "energy drift above {limit} kcal/mol/ns: {}",
+                drifting.join(", ")
+            ));
+        }
+    }
+    output::emit("bench", bench_document(&config, &reports));
+    Ok(())
+}
+
+/// `--output json` document of a bench run that stayed within its drift limit.
+fn bench_document(
+    config: &physics_nve::NveConfig,
+    reports: &[physics_nve::NveReport],
+) -> serde_json::Value {
+    json!({
+        "residues": config.residues,
+        "temperature": config.temperature,
+        "steps": config.steps,
+        "timestep_ps": config.timestep_ps,
+        "integrators": reports
+            .iter()
+            .map(|report| json!({
+                "integrator": report.integrator,
+                "initial_energy": report.initial_energy,
+                "final_energy": report.final_energy,
+                "max_deviation": report.max_deviation,
+                "drift_per_ns": report.drift_per_ns,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn run_compare_cli(args: &[String]) -> Result<(), String> {
+    let command = CompareCommand::parse(args)?;
+    let model = protein::read_ca_coordinates(&command.model)?;
+    let reference = protein::read_ca_coordinates(&command.reference)?;
+    let scores = compare_structures(&model, &reference)
+        .ok_or_else(|| "structures share no residues to compare".to_string())?;
+
+    say!(
+        "Comparing {} against {}",
+        command.model.display(),
+        command.reference.display()
+    );
+    say!(
+        "  Aligned residues: {} / {}",
+        scores.aligned_length,
+        scores.reference_length
+    );
+    say!("  RMSD: {:.3} Å", scores.rmsd);
+    say!("  TM-score: {:.4}", scores.tm_score);
+    say!("  GDT_TS: {:.4}", scores.gdt_ts);
+    say!("  lDDT: {:.4}", scores.lddt);
+
+    if command.lddt_csv.is_some() || command.bfactor_output.is_some() {
+        let per_residue = lddt(&model, &reference);
+        if let Some(csv_path) = command.lddt_csv.as_ref() {
+            fs::write(csv_path, per_residue.to_csv())
+                .map_err(|err| format!("failed to write lDDT CSV {}: {err}", csv_path.display()))?;
+            say!("  Per-residue lDDT written to {}", csv_path.display());
+        }
+        if let Some(pdb_path) = command.bfactor_output.as_ref() {
+            let bfactors: Vec<f64> = per_residue
+                .per_residue
+                .iter()
+                .map(|score| score.unwrap_or(0.0) * 100.0)
+                .collect();
+            protein::write_with_bfactors(&command.model, pdb_path, &bfactors)?;
+            say!("  lDDT B-factors written to {}", pdb_path.display());
+        }
+    }
+    output::emit(
+        "compare",
+        json!({
+            "model": command.model,
+            "reference": command.reference,
+            "aligned_residues": scores.aligned_length,
+            "reference_residues": scores.reference_length,
+            "rmsd": scores.rmsd,
+            "tm_score": scores.tm_score,
+            "gdt_ts": scores.gdt_ts,
+            "lddt": scores.lddt,
+            "files": {
+                "lddt_csv": command.lddt_csv,
+                "bfactors": command.bfactor_output,
+            },
+        }),
+    );
+    Ok(())
+}
+
+fn run_align_cli(args: &[String]) -> Result<(), String> {
+    let command = AlignCommand::parse(args)?;
+    let mobile = protein::read_ca_coordinates(&command.mobile)?;
+    let target = protein::read_ca_coordinates(&command.target)?;
+    let paired = mobile.len().min(target.len());
+    let selection = command.selection(paired);
+    let fit = Superposition::fit_subset(&mobile, &target, &selection)
+        .ok_or_else(|| "alignment needs at least three selected residues".to_string())?;
+
+    let moved = fit.apply_all(&mobile[..paired]);
+    let selected_moved: Vec<[f64; 3]> = selection.iter().map(|&idx| moved[idx]).collect();
+    let selected_target: Vec<[f64; 3]> = selection.iter().map(|&idx| target[idx]).collect();
+
+    if let Some(parent) = command.output.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(|err| {
+            format!(
+                "failed to create output directory {}: {err}",
+                parent.display()
+            )
+        })?;
+    }
+    protein::write_transformed(&command.mobile, &command.output, |point| fit.apply(point))?;
+
+    say!(
+        "Aligned {} onto {}",
+        command.mobile.display(),
+        command.target.display()
+    );
+    let selection_rmsd = folding_sim::similarity::rmsd(&selected_moved, &selected_target);
+    let paired_rmsd = folding_sim::similarity::rmsd(&moved, &target[..paired]);
+    say!("  Fitted residues: {} / {}", selection.len(), paired);
+    say!("  RMSD (selection): {:.3} Å", selection_rmsd);
+    say!("  RMSD (all paired): {:.3} Å", paired_rmsd);
+    say!(
+        "  Aligned coordinates written to {}",
+        command.output.display()
+    );
+    output::emit(
+        "align",
+        json!({
+            "mobile": command.mobile,
+            "target": command.target,
+            "fitted_residues": selection.len(),
+            "paired_residues": paired,
+            "rmsd_selection": selection_rmsd,
+            "rmsd_paired": paired_rmsd,
+            "files": { "aligned": command.output },
+        }),
+    );
+    Ok(())
+}
+
+fn run_derive_contract_cli(args: &[String]) -> Result<(), String> {
+    let command = DeriveContractCommand::parse(args)?;
+    let backbone = protein::read_backbone(&command.target)?;
+    let chain = match command.sequence.as_ref() {
+        Some(path) => protein::load_sequence(path)?.to_chain(),
+        None => PeptideChain::new(
+            backbone
+                .iter()
+                .enumerate()
+                .map(|(idx, (name, atoms))| {
+                    Residue::new(ResidueId(idx), name.as_str(), atoms.ca.unwrap_or_default())
+                })
+                .collect(),
+        ),
+    };
+    if chain.len() != backbone.len() {
+        log::warn!(
+            "chain has {} residues but target has {}; pairing by index",
+            chain.len(),
+            backbone.len()
+        );
+    }
+
+    let atoms: Vec<_> = backbone.into_iter().map(|(_, atoms)| atoms).collect();
+    let targets = backbone_dihedrals(&atoms);
+    let mut options = TargetContractOptions::default();
+    if let Some(max_step) = command.max_step_degrees {
+        options.max_step_degrees = max_step;
+    }
+    let contract = contract_from_target(&chain, &targets, &options);
+    let rotations = contract
+        .instructions
+        .iter()
+        .filter(|instruction| instruction.rotation_span().is_some())
+        .count();
+
+    let rendered = format!(
+        "# Derived from target structure {}\n# rotate spans drive phi and psi toward the target.\n{}",
+        command.target.display(),
+        contract.to_lll()
+    );
+    if let Some(parent) = command.output.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(|err| {
+            format!(
+                "failed to create contract directory {}: {err}",
+                parent.display()
+            )
+        })?;
+    }
+    folding::persist_contract(&rendered, &command.output)?;
+
+    say!(
+        "Derived contract from {} ({} residues, {} rotate spans).",
+        command.target.display(),
+        atoms.len(),
+        rotations
+    );
+    say!("Contract saved to {}", command.output.display());
+    output::emit(
+        "derive-contract",
+        json!({
+            "target": command.target,
+            "residues": atoms.len(),
+            "rotate_spans": rotations,
+            "files": { "contract": command.output },
+        }),
+    );
+    Ok(())
+}
+
+fn run_legacy(opts: CliOptions) -> Result<RunOutcome, String> {
+    if let Some(path) = opts.replay.as_ref() {
+        run_replay(path, opts.show_ghosts, opts.replay_tag.as_deref())?;
+        return Ok(RunOutcome::Completed);
+    }
+
+    let mut chain = None;
+    let mut contract = None;
+    let mut label = opts.preset.clone();
+
+    if let Some(preset) = opts.preset.as_deref() {
+        if let Some(preset_pack) = PresetLoader::load_preset(preset) {
+            chain = Some(preset_pack.chain);
+            contract = Some(preset_pack.contract);
+        } else {
+            log::warn!("preset '{}' not found; falling back to demo", preset);
+            label = Some("demo".into());
+        }
+    }
+
+    if (chain.is_none() || contract.is_none())
+        && let Some(preset_pack) = PresetLoader::load_preset(label.as_deref().unwrap_or("demo"))
+    {
+        chain.get_or_insert(preset_pack.chain.clone());
+        contract.get_or_insert(preset_pack.contract.clone());
+        if label.is_none() {
+            label = Some("demo".into());
+        }
+    }
+
+    if let Some(fasta_path) = opts.fasta.as_ref() {
+        match InputLoader::load_fasta(fasta_path) {
+            Ok(loaded_chain) => chain = Some(loaded_chain),
+            Err(err) => {
                 return Err(format!(
-                    "failed to load contract {}: {err}",
-                    contract_path.display()
+                    "failed to load FASTA {}: {err}",
+                    fasta_path.display()
                 ));
             }
         }
+    }
+
+    if let Some(mmcif_path) = opts.mmcif.as_ref() {
+        if opts.fasta.is_some() {
+            return Err("--fasta and --mmcif both set the chain".into());
+        }
+        let loaded_chain = InputLoader::load_mmcif(mmcif_path)?;
+        say!(
+            "Start: {} ({} residues)",
+            mmcif_path.display(),
+            loaded_chain.len()
+        );
+        chain = Some(loaded_chain);
+    }
+
+    if let Some(pdb_path) = opts.pdb.as_ref() {
+        if opts.fasta.is_some() || opts.mmcif.is_some() {
+            return Err("--pdb and --fasta/--mmcif both set the chain".into());
+        }
+        let structure = protein::load_pdb(pdb_path)?;
+        let chains: Vec<String> = structure
+            .chain_names()
+            .iter()
+            .map(char::to_string)
+            .collect();
+        say!(
+            "Start: {} ({} residues, chains {})",
+            pdb_path.display(),
+            structure.chain.len(),
+            chains.join(", ")
+        );
+        chain = Some(structure.chain);
+    }
+
+    if let Some(contract_path) = opts.contract.as_ref() {
+        contract = Some(InputLoader::load_contract(contract_path)?);
         if label.is_none() {
             label = contract_path
                 .file_stem()
@@ -452,29 +2132,217 @@ fn run_legacy(opts: CliOptions) -> Result<(), String> {
         }
     }
 
-    let chain = chain.ok_or_else(|| "no chain available after parsing inputs".to_string())?;
+    let predicted = match opts.predicted.as_deref() {
+        Some(raw) => {
+            if opts.start_from.is_some() {
+                return Err("--predicted and --start-from both set the start".into());
+            }
+            if opts.mmcif.is_some() || opts.pdb.is_some() {
+                return Err("--predicted and --mmcif/--pdb both set the start".into());
+            }
+            let path = resolve_predicted_model(raw)?;
+            let model = PredictedModel::load(&path)?;
+            if opts.fasta.is_none() {
+                chain = Some(model.chain());
+            }
+            Some((path, model))
+        }
+        None => None,
+    };
+    let mut chain = chain.ok_or_else(|| "no chain available after parsing inputs".to_string())?;
+    if let Some((path, frame)) = opts.start_from.as_ref() {
+        seed_start_positions(&mut chain, path, *frame)?;
+    }
+    if let Some((path, model)) = predicted.as_ref() {
+        model
+            .check_sequence(&chain)
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        place_ca_trace(&mut chain, &model.positions)?;
+        say!(
+            "Start: predicted model {} (mean pLDDT {:.1})",
+            path.display(),
+            model.mean_plddt()
+        );
+    }
     let contract =
         contract.ok_or_else(|| "no contract available after parsing inputs".to_string())?;
+    let (physics_level, level_choice) = if opts.auto_level {
+        let budget = opts
+            .wall_clock_budget
+            .unwrap_or(DEFAULT_WALL_CLOCK_BUDGET_SECS);
+        let choice = choose_physics_level(chain.len(), contract.move_count(), budget);
+        say!(
+            "Physics level: {} (auto; {})",
+            choice.level.as_str(),
+            choice.rationale
+        );
+        (choice.level, Some(choice.rationale))
+    } else {
+        (opts.physics_level.unwrap_or(PhysicsLevel::Toy), None)
+    };
+    contract.check_physics_level(physics_level)?;
+    for plugin in &opts.plugins {
+        let names = load_plugin(plugin)?;
+        say!("Plugin {}: {}", plugin.display(), names.join(", "));
+    }
+    check_physics_components(&opts, &contract, physics_level)?;
+    if opts.model_url.is_some() && !cfg!(feature = "model-score") {
+        return Err("--model-score needs a build with the `model-score` feature".into());
+    }
+    let wants_openmm = opts.physics_engine == PhysicsEngine::OpenMM
+        || contract.instructions.iter().any(|instruction| {
+            matches!(
+                instruction,
+                ContractInstruction::SetPhysicsEngine(PhysicsEngine::OpenMM)
+            )
+        });
+    let has_openmm = PhysicsEngine::Auto.backend_label().contains("openmm");
+    if wants_openmm && !has_openmm {
+        return Err("physics engine `openmm` needs a build with the `openmm` feature".into());
+    }
+    if opts.openmm_cache.is_some() && !has_openmm {
+        log::warn!("no OpenMM bridge in this build; --openmm-cache has no effect");
+    }
+    let ruleset = match opts.rules_path.as_ref() {
+        Some(path) => {
+            let ruleset = rules::load_ruleset(path)?;
+            ruleset
+                .check(chain.len())
+                .map_err(|err| format!("invalid rules {}: {err}", path.display()))?;
+            ruleset
+        }
+        None => Ruleset::default(),
+    };
+    let contract_source = opts
+        .contract
+        .clone()
+        .or_else(|| PresetLoader::preset_path(label.as_deref().unwrap_or("demo")));
+    let rng_seed = opts.rng_seed.unwrap_or_else(entropy_seed);
 
-    let environment = opts
+    let environment_name = opts
         .environment
         .clone()
         .unwrap_or_else(|| "aqueous".to_string());
-    let env_preset =
-        EnvironmentPreset::by_name(&environment).unwrap_or_else(EnvironmentPreset::aqueous);
-    let temperature = opts.temperature.unwrap_or(env_preset.default_temperature);
+    let mut environment = Environment::by_name(&environment_name).unwrap_or_else(|| Environment {
+        name: environment_name.clone(),
+        ..Environment::aqueous()
+    });
+    if let Some(viscosity) = opts.viscosity {
+        environment = environment.with_viscosity(viscosity);
+    }
+    if let Some(dielectric) = opts.dielectric {
+        environment = environment.with_dielectric(dielectric);
+    }
+    if let Some(crowding) = opts.crowding {
+        environment = environment.with_crowding(crowding);
+    }
+    if let Some(model) = opts.crowding_model {
+        environment = environment.with_crowding_model(model);
+    }
+    if let Some(model) = opts.dielectric_model {
+        environment = environment.with_dielectric_model(model);
+    }
+    if environment.crowding_model == CrowdingModel::Explicit
+        && environment.crowding_fraction > JAMMING_FRACTION
+    {
+        return Err(format!(
+            "explicit crowders jam above a volume fraction of {JAMMING_FRACTION}; lower --crowding or use --crowding-model mean-field"
+        ));
+    }
+    let temperature = opts.temperature.unwrap_or(environment.default_temperature);
+
+    let reference_structure = match opts.reference.as_ref() {
+        Some(path) => Some(protein::read_ca_coordinates(path)?),
+        None => None,
+    };
+
+    let symmetry = opts.symmetry.map(|group| match opts.symmetry_radius {
+        Some(radius) => Symmetry::new(group, radius),
+        None => Symmetry::around(group, &chain_positions(&chain), SYMMETRY_CLEARANCE),
+    });
+    if opts.assembly_path.is_some() && symmetry.is_none() {
+        return Err("--assembly needs a point group from --symmetry".into());
+    }
+    if opts.energy_every.is_some() && opts.energy_csv.is_none() && opts.residue_energy_csv.is_none()
+    {
+        return Err("--energy-every needs --energy-csv or --residue-energy-csv".into());
+    }
+    let mut contact_prior = match opts.contacts.as_ref() {
+        Some(path) => Some(load_contact_prior(
+            path,
+            opts.contact_min_probability,
+            chain.len(),
+        )?),
+        None => None,
+    };
+    if let Some((_, model)) = predicted.as_ref() {
+        let held = model.restraints(opts.plddt_min);
+        say!(
+            "pLDDT restraints: {} pairs among residues at pLDDT ≥ {}",
+            held.restraints.len(),
+            opts.plddt_min
+        );
+        match contact_prior.as_mut() {
+            Some(prior) => prior.restraints.extend(held.restraints),
+            None => contact_prior = Some(held),
+        }
+    }
 
     let config = ShellConfig {
         temperature,
+        acceptance_temperature: opts.acceptance_temperature,
         time_step_ms: opts.time_step_ms.unwrap_or(1),
-        rng_seed: opts.rng_seed,
+        real_time: opts.real_time,
+        rng_seed: Some(rng_seed),
+        rng_state: opts.rng_state.clone(),
+        rng_log_interval: opts.rng_log_interval,
+        acceptance_window: opts.acceptance_window,
+        energy_component_stride: opts
+            .energy_csv
+            .as_ref()
+            .or(opts.residue_energy_csv.as_ref())
+            .map(|_| opts.energy_every.unwrap_or(1)),
+        frustration: opts.frustration.as_ref().map(|_| FrustrationOptions {
+            seed: rng_seed,
+            ..FrustrationOptions::default()
+        }),
         log_path: opts.log_path.clone(),
+        overwrite_log: opts.overwrite_log,
+        run_name: opts.run_name.clone(),
         environment,
         diamond_threshold: opts.diamond_threshold,
         diamond_path: opts.diamond_dir.clone(),
         temp_schedule: opts
             .temp_schedule
             .map(|(start, end, steps)| TempScheduleConfig { start, end, steps }),
+        reference_structure,
+        database: opts.database.clone(),
+        remote_root: opts.upload.clone(),
+        symmetry,
+        side_chains: opts.side_chains,
+        contact_prior,
+        physics_level,
+        physics_engine: opts.physics_engine,
+        require_backend: opts.require_backend,
+        thermo_tolerance: opts.thermo_check.then_some(DEFAULT_THERMO_TOLERANCE),
+        level_choice,
+        ruleset,
+        ghost_budget: opts
+            .ghost_limit
+            .map(|limit| GhostBudget::new(limit, opts.ghost_overflow)),
+        force_field: opts.force_field.clone(),
+        integrator: opts.integrator.clone(),
+        md_timestep: opts.md_timestep,
+        scores: opts.scores.clone(),
+        collective_variables: opts.collective_variables.clone(),
+        model_score: opts.model_url.as_ref().map(|url| {
+            ModelScoreConfig::new(url.clone())
+                .with_weight(opts.model_weight)
+                .with_input(opts.model_input)
+        }),
+        openmm_cache: opts.openmm_cache.clone(),
+        native_trajectories: opts.native_trajectories.clone(),
+        memory_cap: opts.mem_cap_mb.map(|mib| (mib * MIB) as usize),
     };
 
     let mut shell = CommandShell::new(
@@ -485,6 +2353,7 @@ fn run_legacy(opts: CliOptions) -> Result<(), String> {
     shell.set_contract_label(label.clone());
 
     let shell_report = shell.run_contract(chain, contract);
+    let outcome = shell_report.outcome();
     let metrics = FoldingMetrics::from_report(&shell_report);
     let trajectory_json = TrajectoryVisualizer::to_json(&shell_report.trajectory);
 
@@ -504,31 +2373,1089 @@ fn run_legacy(opts: CliOptions) -> Result<(), String> {
         (metrics.total_entropy / (metrics.total_entropy + metrics.ghost_entropy)) * 100.0
     };
 
-    println!("Folding run complete.");
-    println!("Total steps: {}", total_steps);
-    println!("Converged at: {}", converged_at);
-    println!("Final G: {:.6}", final_gibbs);
-    println!("Informational Efficiency: {:.1}%", efficiency);
+    say!("Folding run complete.");
+    say!("Total steps: {}", total_steps);
+    say!("Converged at: {}", converged_at);
+    say!("Final G: {:.6}", final_gibbs);
+    say!("Informational Efficiency: {:.1}%", efficiency);
     let stats = &shell_report.metropolis_stats;
-    println!(
+    say!(
         "Accepted / Rejected: {} / {} ({:.1}% acceptance)",
         stats.accepted,
         stats.rejected,
         stats.acceptance_rate() * 100.0
     );
+    if !shell_report.recoveries.is_empty() {
+        let recovered = shell_report
+            .recoveries
+            .iter()
+            .filter(|record| record.recovered)
+            .count();
+        say!(
+            "Recoveries: {} ({recovered} recovered)",
+            shell_report.recoveries.len()
+        );
+    }
+    if let Some(abort) = shell_report
+        .recoveries
+        .iter()
+        .find(|record| record.policy == RecoveryPolicy::Abort)
+    {
+        say!(
+            "Contract aborted at step {} by {} ({})",
+            abort.step,
+            abort.violation.code(),
+            abort.violation.kind()
+        );
+    }
+    if !shell_report.frozen.is_empty() {
+        let regions: Vec<String> = shell_report
+            .frozen
+            .iter()
+            .map(|region| {
+                format!(
+                    "{}..{} (from step {})",
+                    region.start.0, region.end.0, region.step
+                )
+            })
+            .collect();
+        say!("Frozen: {}", regions.join(", "));
+    }
+    if !shell_report.restraints.is_empty() {
+        let restraints: Vec<String> = shell_report
+            .restraints
+            .iter()
+            .map(|restraint| {
+                format!(
+                    "{}..{} {}",
+                    restraint.start.0,
+                    restraint.end.0,
+                    restraint.structure.as_str()
+                )
+            })
+            .collect();
+        say!(
+            "Restraints: {} (final energy {:.3} kcal/mol)",
+            restraints.join(", "),
+            shell_report.restraint_energy
+        );
+    }
+    let contacts = shell.config().contact_prior.as_ref().map(|prior| {
+        let satisfied = prior.satisfied(&chain_positions(&shell_report.final_chain));
+        (satisfied, prior.restraints.len())
+    });
+    if let Some((satisfied, total)) = contacts {
+        say!(
+            "Contact prior: {satisfied}/{total} restraints satisfied (energy {:.3} kcal/mol)",
+            shell_report.contact_prior_energy
+        );
+    }
+    if outcome != RunOutcome::Completed {
+        say!(
+            "Outcome: {} (exit {})",
+            outcome.as_str(),
+            outcome_exit_code(outcome)
+        );
+    }
+    if let Some(dir) = opts.openmm_cache.as_ref() {
+        let cached = shell_report
+            .physics_span_metrics
+            .iter()
+            .filter(|record| record.metrics.cache_path.is_some())
+            .count();
+        say!("OpenMM cache: {cached} spans in {}", dir.display());
+    }
+    if let Some(dir) = opts.native_trajectories.as_ref() {
+        let written = shell_report
+            .physics_span_metrics
+            .iter()
+            .filter(|record| record.metrics.trajectory_path.is_some())
+            .count();
+        say!("Native trajectories: {written} spans in {}", dir.display());
+    }
+    for (name, delta) in &shell_report.score_deltas {
+        say!("Score {name}: ΣΔ {delta:.4} over accepted spans");
+    }
+    if let Some(step) = shell_report.ghost_budget_exceeded_at {
+        say!(
+            "Ghost budget exceeded at step {step} ({}; {} ghost spans pruned)",
+            opts.ghost_overflow.label(),
+            shell_report.ghosts_pruned
+        );
+    }
+    if let Some(first) = shell_report.level_switches.first() {
+        let mut levels = first.from.as_str().to_string();
+        for switch in &shell_report.level_switches {
+            levels.push_str(&format!(" → {} (step {})", switch.to.as_str(), switch.step));
+        }
+        say!("Physics levels: {levels}");
+    }
+    if !shell_report.physics_span_metrics.is_empty() {
+        let mut backends: BTreeMap<&str, usize> = BTreeMap::new();
+        for record in &shell_report.physics_span_metrics {
+            *backends.entry(record.metrics.backend).or_default() += 1;
+        }
+        let counts: Vec<String> = backends
+            .iter()
+            .map(|(backend, spans)| format!("{backend} {spans}"))
+            .collect();
+        say!(
+            "Physics backends: {} (selected {})",
+            counts.join(", "),
+            shell_report.physics_engine.as_str()
+        );
+    }
+    if opts.thermo_check {
+        let discrepancies = &shell_report.thermo_discrepancies;
+        say!(
+            "Thermodynamic checks: {} discrepancies over {} spans",
+            discrepancies.len(),
+            shell_report.applied_rotations.len()
+        );
+        for discrepancy in discrepancies.iter().take(5) {
+            say!(
+                "  step {} {} {}: expected {:.6}, recorded {:.6}",
+                discrepancy.step,
+                discrepancy.span_id,
+                discrepancy.check.as_str(),
+                discrepancy.expected,
+                discrepancy.actual
+            );
+        }
+    }
+    if let Some(first) = shell_report.backend_failures.first() {
+        let stderr: String = first
+            .failure
+            .stderr
+            .lines()
+            .map(|line| format!("\n  {line}"))
+            .collect();
+        log::warn!(
+            "{} backend failures; first at step {} ({}): {} backend: {} (fell back to {}){stderr}",
+            shell_report.backend_failures.len(),
+            first.step,
+            first.span_id,
+            first.failure.backend,
+            first.failure.reason,
+            first.fallback
+        );
+    }
+    if let Some(metadata) = shell.last_metadata() {
+        match metadata.quasi_harmonic_entropy {
+            Some(entropy) => say!(
+                "Entropy: ΣΔS {:.6} (heuristic), {:.6} kcal/mol/K (quasi-harmonic)",
+                metadata.heuristic_entropy,
+                entropy
+            ),
+            None => say!("Entropy: ΣΔS {:.6} (heuristic)", metadata.heuristic_entropy),
+        }
+        if let Some(foldability) = shell_report.energy_samples.foldability() {
+            say!(
+                "Foldability: {:.3} (best {:.4} kcal/mol, {:.4} below the mean of {} decoys, σ {:.4})",
+                foldability.index,
+                foldability.best_energy,
+                foldability.gap,
+                foldability.decoys,
+                foldability.decoy_std
+            );
+        }
+        say!(
+            "Time: {:.0} ms of spans, {:.6} ps simulated, {:.1} ms wall clock",
+            metadata.span_time_ms,
+            metadata.simulated_time_ps,
+            metadata.wall_time_ms
+        );
+    }
+    if opts.mem_report {
+        print_memory_report(&shell_report, opts.mem_cap_mb);
+    }
+    if let Some(reference) = shell.config().reference_structure.as_ref() {
+        let model: Vec<[f64; 3]> = shell_report
+            .final_chain
+            .residues()
+            .map(|residue| residue.position())
+            .collect();
+        if let Some(scores) = compare_structures(&model, reference) {
+            say!(
+                "Reference similarity: TM-score {:.4}, GDT_TS {:.4}, RMSD {:.3} Å",
+                scores.tm_score,
+                scores.gdt_ts,
+                scores.rmsd
+            );
+        }
+    }
 
+    let mut artifacts: Vec<PathBuf> = opts.contract.iter().cloned().collect();
+    let mut files = serde_json::Map::new();
     if let Some(log_path) = shell.last_log_path() {
-        println!("Spans persisted at: {}", log_path.display());
+        artifacts.push(log_path.clone());
+        files.insert("log".into(), json!(log_path));
+        say!("Spans persisted at: {}", log_path.display());
     } else {
-        println!("Spans persisted at: <not written>");
+        say!("Spans persisted at: <not written>");
     }
 
     if let Some(diamond_path) = shell.last_diamond_path() {
-        println!("Diamonds persisted at: {}", diamond_path.display());
+        files.insert("diamonds".into(), json!(diamond_path));
+        say!("Diamonds persisted at: {}", diamond_path.display());
     } else {
-        println!("Diamonds persisted at: logs/diamonds.json (no entry)");
+        say!("Diamonds persisted at: logs/diamonds.json (no entry)");
+    }
+
+    if let Some(database) = shell.last_database_path() {
+        files.insert("database".into(), json!(database));
+        say!("Run stored in database: {}", database.display());
+    }
+
+    if let (Some(log_path), Some(metadata)) = (shell.last_log_path(), shell.last_metadata()) {
+        let mut inputs = Vec::new();
+        for (role, path) in [
+            ("contract", contract_source.as_ref()),
+            ("fasta", opts.fasta.as_ref()),
+            ("mmcif", opts.mmcif.as_ref()),
+            ("pdb", opts.pdb.as_ref()),
+            ("reference", opts.reference.as_ref()),
+            ("contacts", opts.contacts.as_ref()),
+            ("predicted", predicted.as_ref().map(|(path, _)| path)),
+            ("rules", opts.rules_path.as_ref()),
+            ("start", opts.start_from.as_ref().map(|(path, _)| path)),
+        ] {
+            if let Some(path) = path {
+                inputs.push(InputDigest::of(role, path)?);
+            }
+        }
+        for plugin in &opts.plugins {
+            inputs.push(InputDigest::of("plugin", plugin)?);
+        }
+        let config = shell.config();
+        let manifest = RunManifest {
+            run: RunSection {
+                run_id: metadata.run_id.clone(),
+                run_name: metadata.run_name.clone(),
+                log: Some(log_path.clone()),
+                crate_version: metadata.provenance.crate_version.clone(),
+                git_commit: metadata.provenance.git_commit.clone(),
+            },
+            parameters: RunParameters {
+                label: label.clone(),
+                preset: opts.preset.clone(),
+                fasta: opts.fasta.clone(),
+                mmcif: opts.mmcif.clone(),
+                pdb: opts.pdb.clone(),
+                contract: opts.contract.clone(),
+                reference: opts.reference.clone(),
+                environment: config.environment.name.clone(),
+                viscosity: Some(config.environment.viscosity),
+                dielectric: Some(config.environment.dielectric),
+                crowding_fraction: Some(config.environment.crowding_fraction),
+                crowding_model: Some(config.environment.crowding_model.as_str().to_string()),
+                dielectric_model: Some(config.environment.dielectric_model.as_str().to_string()),
+                temperature: config.temperature,
+                acceptance_temperature: config.acceptance_temperature,
+                time_step_ms: config.time_step_ms,
+                rng_seed,
+                rng_state: opts
+                    .rng_state
+                    .as_ref()
+                    .map(|checkpoint| format!("{:016x}", checkpoint.state)),
+                rng_step: opts
+                    .rng_state
+                    .as_ref()
+                    .map(|checkpoint| checkpoint.step)
+                    .filter(|&step| step > 0),
+                rng_velocities: opts
+                    .rng_state
+                    .as_ref()
+                    .filter(|checkpoint| !checkpoint.velocities.is_empty())
+                    .map(|checkpoint| checkpoint.velocities.to_string()),
+                rng_log_every: opts.rng_log_interval,
+                acceptance_window: opts.acceptance_window,
+                info_scale: opts.info_scale,
+                diamond_threshold: opts.diamond_threshold,
+                diamond_dir: opts.diamond_dir.clone(),
+                database: opts.database.clone(),
+                upload: opts.upload.clone(),
+                frames: opts.frames_path.clone(),
+                smooth_frames: opts.smooth_frames,
+                pymol: opts.pymol_script.clone(),
+                chimerax: opts.chimerax_script.clone(),
+                vmd: opts.vmd_script.clone(),
+                color_by: opts.color_by.as_str().to_string(),
+                energy_csv: opts.energy_csv.clone(),
+                energy_every: config.energy_component_stride,
+                residue_energy_csv: opts.residue_energy_csv.clone(),
+                frustration: opts.frustration.clone(),
+                disorder: opts.disorder.clone(),
+                mi_matrix: opts.mi_matrix.clone(),
+                mi_signal: (opts.mi_matrix.is_some()
+                    || opts.te_matrix.is_some()
+                    || opts.pathway.is_some())
+                .then(|| opts.mi_signal.as_str().to_string()),
+                te_matrix: opts.te_matrix.clone(),
+                pathway: opts.pathway.map(|(from, to)| [from, to]),
+                symmetry: config
+                    .symmetry
+                    .as_ref()
+                    .map(|symmetry| symmetry.group.name()),
+                symmetry_radius: config.symmetry.as_ref().map(|symmetry| symmetry.radius),
+                side_chains: config.side_chains.map(|bead| bead.as_str().to_string()),
+                contacts: opts.contacts.clone(),
+                contact_min_probability: opts
+                    .contacts
+                    .as_ref()
+                    .map(|_| opts.contact_min_probability),
+                predicted: predicted.as_ref().map(|(path, _)| path.clone()),
+                plddt_min: predicted.as_ref().map(|_| opts.plddt_min),
+                assembly: opts.assembly_path.clone(),
+                final_pdb: opts.final_pdb.clone(),
+                backmap: opts.backmap_path.clone(),
+                level: Some(config.physics_level.as_str().to_string()),
+                physics_engine: Some(config.physics_engine.as_str().to_string()),
+                require_backend: config.require_backend,
+                thermo_check: config.thermo_tolerance.is_some(),
+                wall_clock_budget: opts.wall_clock_budget,
+                rules: opts.rules_path.clone(),
+                ghost_budget: opts.ghost_limit.map(|limit| limit.label()),
+                ghost_overflow: opts.ghost_limit.map(|_| opts.ghost_overflow.label()),
+                force_field: opts.force_field.clone(),
+                integrator: opts.integrator.clone(),
+                md_timestep: opts.md_timestep,
+                plugins: opts.plugins.clone(),
+                scores: opts
+                    .scores
+                    .iter()
+                    .map(|(name, expression)| format!("{name}={}", expression.source()))
+                    .collect(),
+                collective_variables: opts
+                    .collective_variables
+                    .iter()
+                    .map(CollectiveVariable::name)
+                    .collect(),
+                model_score: opts.model_url.clone(),
+                model_weight: opts.model_url.as_ref().map(|_| opts.model_weight),
+                model_input: opts
+                    .model_url
+                    .as_ref()
+                    .map(|_| opts.model_input.as_str().to_string()),
+                openmm_cache: opts.openmm_cache.clone(),
+                native_trajectories: opts.native_trajectories.clone(),
+                start_from: opts.start_from.as_ref().map(|(path, _)| path.clone()),
+                start_frame: opts.start_from.as_ref().and_then(|(_, frame)| *frame),
+                mem_cap_mb: opts.mem_cap_mb,
+                anneal: opts
+                    .temp_schedule
+                    .map(|(start, end, steps)| AnnealParameters { start, end, steps }),
+            },
+            inputs,
+        };
+        let manifest_path = manifest::manifest_path(log_path);
+        manifest.save(&manifest_path)?;
+        artifacts.push(manifest_path.clone());
+        files.insert("manifest".into(), json!(manifest_path));
+        say!("Run manifest written to {}", manifest_path.display());
+    }
+
+    let mut trajectory_pdb = None;
+    if let Some(frames_path) = opts.frames_path.as_ref() {
+        ensure_parent_dir(frames_path)?;
+        let frames = smooth_frames(&shell_report.frames, opts.smooth_frames);
+        let positions: Vec<Vec<[f64; 3]>> =
+            frames.iter().map(|frame| frame.positions.clone()).collect();
+        let extension = frames_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        let write_frames = |bytes: Vec<u8>| {
+            fs::write(frames_path, bytes)
+                .map_err(|err| format!("failed to write frames {}: {err}", frames_path.display()))
+        };
+        match extension.as_deref() {
+            Some("json") => {
+                let energies = shell_report
+                    .energy_model
+                    .residue_energies(&shell_report.final_chain);
+                write_frames(viewer_json(&shell_report.final_chain, &frames, &energies).into())?
+            }
+            Some("bcif") => write_frames(encode_binary_cif(
+                &shell_report.final_chain,
+                &positions,
+                label.as_deref().unwrap_or("logline"),
+            ))?,
+            _ => {
+                protein::write_multi_model_pdb(&shell_report.final_chain, &positions, frames_path)?;
+                trajectory_pdb = Some((frames_path.clone(), frames.len()));
+            }
+        }
+        artifacts.push(frames_path.clone());
+        files.insert("frames".into(), json!(frames_path));
+        say!(
+            "Trajectory frames ({} total, {} interpolated per span) written to {}",
+            frames.len(),
+            opts.smooth_frames,
+            frames_path.display()
+        );
+    }
+
+    if let Some(final_path) = opts.final_pdb.as_ref() {
+        ensure_parent_dir(final_path)?;
+        let sequence = protein::ProteinSequence {
+            identifier: label.clone(),
+            sequence: String::new(),
+        };
+        protein::write_pdb(&shell_report.final_chain, final_path, &sequence)?;
+        artifacts.push(final_path.clone());
+        files.insert("final_pdb".into(), json!(final_path));
+        let chains: Vec<String> = shell_report
+            .final_system()
+            .ids()
+            .iter()
+            .map(char::to_string)
+            .collect();
+        say!(
+            "Final structure (chains {}) written to {}",
+            chains.join(", "),
+            final_path.display()
+        );
+    }
+
+    if let (Some(assembly_path), Some(symmetry)) = (
+        opts.assembly_path.as_ref(),
+        shell.config().symmetry.as_ref(),
+    ) {
+        ensure_parent_dir(assembly_path)?;
+        let copies = symmetry.assemble(&chain_positions(&shell_report.final_chain));
+        protein::write_assembly_pdb(&shell_report.final_chain, &copies, assembly_path)?;
+        artifacts.push(assembly_path.clone());
+        files.insert("assembly".into(), json!(assembly_path));
+        say!(
+            "{} assembly ({} protomers, radius {:.1} Å) written to {}",
+            symmetry.group.name(),
+            copies.len(),
+            symmetry.radius,
+            assembly_path.display()
+        );
+    }
+
+    if let Some(backmap_path) = opts.backmap_path.as_ref() {
+        ensure_parent_dir(backmap_path)?;
+        let (model, source) = match shell_report.refined_model.clone() {
+            Some(model) => (model, "refined"),
+            None => (backmap(&shell_report.final_chain), "final"),
+        };
+        protein::write_backbone_pdb(&model, &shell_report.final_chain, backmap_path)?;
+        artifacts.push(backmap_path.clone());
+        files.insert("backmap".into(), json!(backmap_path));
+        say!(
+            "Backmapped {source} model ({} residues, N/CA/C/O/CB) written to {}",
+            model.len(),
+            backmap_path.display()
+        );
+    }
+
+    if let Some(energy_path) = opts.energy_csv.as_ref() {
+        let samples = &shell_report.energy_components;
+        ensure_parent_dir(energy_path)?;
+        fs::write(energy_path, energy_components_csv(samples))
+            .map_err(|err| format!("failed to write {}: {err}", energy_path.display()))?;
+        artifacts.push(energy_path.clone());
+        files.insert("energy_csv".into(), json!(energy_path));
+        say!(
+            "Energy components ({} samples) written to {}",
+            samples.len(),
+            energy_path.display()
+        );
+        // Terms are only comparable under one force field.
+        if let Some((first, last)) = samples.first().zip(samples.last())
+            && first.level == last.level
+        {
+            let (from, to) = (first.components, last.components);
+            let changes = [
+                ("bond", to.bond - from.bond),
+                ("angle", to.angle - from.angle),
+                ("dihedral", to.dihedral - from.dihedral),
+                ("nonbonded", to.nonbonded - from.nonbonded),
+                ("solvation", to.solvation - from.solvation),
+            ];
+            if let Some((term, change)) = changes
+                .into_iter()
+                .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+                && change != 0.0
+            {
+                say!("  largest change: {term} {change:+.3} kcal/mol");
+            }
+        }
+    }
+
+    if let Some(residue_path) = opts.residue_energy_csv.as_ref() {
+        let samples = &shell_report.energy_components;
+        let names = shell_report.final_chain.names();
+        ensure_parent_dir(residue_path)?;
+        fs::write(residue_path, residue_energies_csv(samples, names))
+            .map_err(|err| format!("failed to write {}: {err}", residue_path.display()))?;
+        artifacts.push(residue_path.clone());
+        files.insert("residue_energy_csv".into(), json!(residue_path));
+        say!(
+            "Residue energies ({} samples) written to {}",
+            samples.len(),
+            residue_path.display()
+        );
+        if let Some(last) = samples.last().filter(|sample| !sample.residues.is_empty()) {
+            let mut ranked: Vec<(usize, f64)> = last.residues.iter().copied().enumerate().collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let highest: Vec<String> = ranked
+                .iter()
+                .take(3)
+                .map(|&(index, energy)| {
+                    let name = names.get(index).map_or("UNK", String::as_str);
+                    format!("{name}{index} {energy:+.3}")
+                })
+                .collect();
+            say!("  highest final energy: {} kcal/mol", highest.join(", "));
+        }
+    }
+
+    if let Some(frustration_path) = opts.frustration.as_ref() {
+        match shell_report.frustration.as_ref() {
+            Some(report) => {
+                let names = shell_report.final_chain.names();
+                ensure_parent_dir(frustration_path)?;
+                fs::write(frustration_path, frustration_csv(report, names)).map_err(|err| {
+                    format!("failed to write {}: {err}", frustration_path.display())
+                })?;
+                let script_path = frustration_path.with_extension("pml");
+                let structure_path = write_frustration_script(&script_path, &shell_report, report)?;
+                artifacts.extend([
+                    frustration_path.clone(),
+                    script_path.clone(),
+                    structure_path,
+                ]);
+                files.insert("frustration".into(), json!(frustration_path));
+                files.insert("frustration_pymol".into(), json!(script_path));
+                say!(
+                    "Frustration of {} contacts ({} highly, {} minimally frustrated) written to {} (PyMOL: {})",
+                    report.contacts.len(),
+                    report.count(FrustrationClass::High),
+                    report.count(FrustrationClass::Minimal),
+                    frustration_path.display(),
+                    script_path.display()
+                );
+                for contact in report.most_frustrated(3) {
+                    say!(
+                        "  {}{} – {}{}: index {:.2} at {:.1} Å",
+                        names[contact.i],
+                        contact.i,
+                        names[contact.j],
+                        contact.j,
+                        contact.index,
+                        contact.distance
+                    );
+                }
+            }
+            None => log::warn!(
+                "skipping frustration: the final force field has no pairwise nonbonded terms."
+            ),
+        }
+    }
+
+    if let Some(disorder_path) = opts.disorder.as_ref() {
+        let profile = DisorderProfile::new(
+            &shell_report.final_chain,
+            &shell_report.applied_rotations,
+            &shell_report.frames,
+        );
+        ensure_parent_dir(disorder_path)?;
+        fs::write(disorder_path, profile.to_csv())
+            .map_err(|err| format!("failed to write {}: {err}", disorder_path.display()))?;
+        let structure_path = disorder_path.with_extension("pdb");
+        let sequence = protein::ProteinSequence {
+            identifier: label.clone(),
+            sequence: String::new(),
+        };
+        protein::write_pdb(&shell_report.final_chain, &structure_path, &sequence)?;
+        // Scaled to 0-100 like pLDDT, so viewers' B-factor ramps apply as is
+        let bfactors: Vec<f64> = profile
+            .propensities()
+            .iter()
+            .map(|propensity| propensity * 100.0)
+            .collect();
+        protein::write_with_bfactors(&structure_path, &structure_path, &bfactors)?;
+        artifacts.extend([disorder_path.clone(), structure_path.clone()]);
+        files.insert("disorder".into(), json!(disorder_path));
+        files.insert("disorder_pdb".into(), json!(structure_path));
+        let regions = profile.regions();
+        say!(
+            "Disorder propensity written to {} (B-factors: {}), {} disordered region(s)",
+            disorder_path.display(),
+            structure_path.display(),
+            regions.len()
+        );
+        for (first, last) in regions {
+            say!("  residues {}-{}", first + 1, last + 1);
+        }
+    }
+
+    if let Some(mi_path) = opts.mi_matrix.as_ref() {
+        match mutual_information_matrix(&shell_report.frames, opts.mi_signal) {
+            Some(matrix) => {
+                ensure_parent_dir(mi_path)?;
+                fs::write(mi_path, matrix.to_csv())
+                    .map_err(|err| format!("failed to write {}: {err}", mi_path.display()))?;
+                artifacts.push(mi_path.clone());
+                files.insert("mi_matrix".into(), json!(mi_path));
+                say!(
+                    "Residue {} mutual information ({}×{}) written to {}",
+                    opts.mi_signal.as_str(),
+                    matrix.residue_count(),
+                    matrix.residue_count(),
+                    mi_path.display()
+                );
+                for (i, j, bits) in matrix.strongest_pairs(3) {
+                    say!("  residues {i}–{j}: {bits:.4} bits");
+                }
+            }
+            None => {
+                log::warn!("skipping mutual information: the run recorded fewer than two frames.")
+            }
+        }
+    }
+
+    if opts.te_matrix.is_some() || opts.pathway.is_some() {
+        match transfer_entropy_matrix(&shell_report.frames, opts.mi_signal) {
+            Some(matrix) => {
+                if let Some(te_path) = opts.te_matrix.as_ref() {
+                    ensure_parent_dir(te_path)?;
+                    fs::write(te_path, matrix.to_csv())
+                        .map_err(|err| format!("failed to write {}: {err}", te_path.display()))?;
+                    artifacts.push(te_path.clone());
+                    files.insert("te_matrix".into(), json!(te_path));
+                    say!(
+                        "Residue {} transfer entropy (rows → columns) written to {}",
+                        opts.mi_signal.as_str(),
+                        te_path.display()
+                    );
+                    for (source, target, bits) in matrix.strongest_links(3) {
+                        say!("  residue {source} → {target}: {bits:.4} bits");
+                    }
+                }
+                if let Some((from, to)) = opts.pathway {
+                    match matrix.pathway(from, to) {
+                        Some(pathway) => say!(
+                            "Coupling pathway {from} → {to}: {} (bottleneck {:.4} bits)",
+                            pathway
+                                .residues
+                                .iter()
+                                .map(|residue| residue.to_string())
+                                .collect::<Vec<_>>()
+                                .join(" → "),
+                            pathway.bottleneck
+                        ),
+                        None => say!("No coupling pathway from {from} to {to}."),
+                    }
+                }
+            }
+            None => {
+                log::warn!("skipping transfer entropy: the run recorded fewer than three frames.")
+            }
+        }
+    }
+
+    if let Some(script_path) = opts.pymol_script.as_ref() {
+        let structure = write_viewer_script(
+            script_path,
+            &shell_report,
+            trajectory_pdb.as_ref(),
+            opts.color_by,
+            pymol_script,
+        )?;
+        files.insert("pymol".into(), json!(script_path));
+        artifacts.extend([script_path.clone(), structure]);
+        say!("PyMOL script written to {}", script_path.display());
+    }
+
+    if let Some(script_path) = opts.chimerax_script.as_ref() {
+        let movie = script_relative(script_path, &script_path.with_extension("mp4"));
+        let structure = write_viewer_script(
+            script_path,
+            &shell_report,
+            trajectory_pdb.as_ref(),
+            opts.color_by,
+            |scene| chimerax_script(scene, Some(&movie)),
+        )?;
+        files.insert("chimerax".into(), json!(script_path));
+        artifacts.extend([script_path.clone(), structure]);
+        say!("ChimeraX script written to {}", script_path.display());
+    }
+
+    if let Some(script_path) = opts.vmd_script.as_ref() {
+        let frames = smooth_frames(&shell_report.frames, opts.smooth_frames);
+        let bundle = write_vmd_bundle(script_path, &shell_report, &frames, opts.color_by)?;
+        artifacts.push(script_path.clone());
+        files.insert("vmd".into(), json!(script_path));
+        artifacts.extend(bundle);
+        say!(
+            "VMD script written to {} (PSF/DCD alongside, {} frames)",
+            script_path.display(),
+            frames.len()
+        );
     }
 
-    println!("Trajectory snapshot: {}", trajectory_json);
+    if let Some(remote_uri) = shell.last_remote_uri() {
+        upload_run_artifacts(remote_uri, &artifacts)?;
+    }
+
+    say!("Trajectory snapshot: {}", trajectory_json);
+    let metadata = shell.last_metadata();
+    output::emit(
+        "run",
+        json!({
+            "run_id": metadata.map(|metadata| &metadata.run_id),
+            "run_name": metadata.and_then(|metadata| metadata.run_name.as_ref()),
+            "started": metadata.map(|metadata| &metadata.timestamp),
+            "seed": rng_seed,
+            "total_steps": total_steps,
+            "converged_at": converged_at,
+            "accepted": stats.accepted,
+            "rejected": stats.rejected,
+            "acceptance_rate": stats.acceptance_rate(),
+            "final_potential_energy": shell_report.final_energy.total_potential,
+            "final_gibbs_energy": final_gibbs,
+            "informational_efficiency": efficiency / 100.0,
+            "heuristic_entropy": metadata.map(|metadata| metadata.heuristic_entropy),
+            "quasi_harmonic_entropy": metadata.and_then(|metadata| metadata.quasi_harmonic_entropy),
+            "foldability": metadata.and_then(|metadata| metadata.foldability),
+            "energy_gap": metadata.and_then(|metadata| metadata.energy_gap),
+            "span_time_ms": metadata.map(|metadata| metadata.span_time_ms),
+            "simulated_time_ps": metadata.map(|metadata| metadata.simulated_time_ps),
+            "wall_time_ms": metadata.map(|metadata| metadata.wall_time_ms),
+            "tm_score": metadata.and_then(|metadata| metadata.tm_score),
+            "gdt_ts": metadata.and_then(|metadata| metadata.gdt_ts),
+            "violations": shell_report.violations.len(),
+            "backend_failures": shell_report.backend_failures.len(),
+            "frozen": shell_report
+                .frozen
+                .iter()
+                .map(|region| json!({
+                    "start": region.start.0,
+                    "end": region.end.0,
+                    "step": region.step,
+                }))
+                .collect::<Vec<_>>(),
+            "restraints": shell_report
+                .restraints
+                .iter()
+                .map(|restraint| json!({
+                    "start": restraint.start.0,
+                    "end": restraint.end.0,
+                    "structure": restraint.structure.as_str(),
+                    "force_constant": restraint.force_constant,
+                }))
+                .collect::<Vec<_>>(),
+            "restraint_energy": shell_report.restraint_energy,
+            "contact_prior": contacts.map(|(satisfied, total)| json!({
+                "restraints": total,
+                "satisfied": satisfied,
+                "energy": shell_report.contact_prior_energy,
+            })),
+            "outcome": outcome.as_str(),
+            "exit_code": outcome_exit_code(outcome),
+            "files": files,
+        }),
+    );
+    Ok(outcome)
+}
+
+/// `--mem-report`: the process's peak RSS and the run data per subsystem.
+fn print_memory_report(report: &ExecutionReport, cap_mb: Option<f64>) {
+    say!("Memory report:");
+    match peak_rss_bytes() {
+        Some(bytes) => say!("  Peak RSS: {:.2} MiB", bytes as f64 / MIB),
+        None => say!("  Peak RSS: unavailable on this platform"),
+    }
+    let usage = &report.memory;
+    say!("  Run data: {:.2} MiB", usage.total() as f64 / MIB);
+    for (name, bytes) in usage.entries() {
+        say!("    {name:<12} {:>10.3} MiB", bytes as f64 / MIB);
+    }
+    say!("    neighbor lists: none (pair terms are evaluated on the fly)");
+    if let Some(cap) = cap_mb {
+        say!(
+            "  Cap: {cap:.1} MiB; kept every {} frame(s), {} in total",
+            report.frame_stride,
+            report.frames.len()
+        );
+        if let Some(step) = report.memory_cap_exceeded_at {
+            say!("  Cap exceeded from step {step}: the other run data alone do not fit");
+        }
+    }
+}
+
+/// Seed for runs without `--seed`, so the manifest can still replay them.
+fn entropy_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    nanos ^ (u64::from(std::process::id()) << 32)
+}
+
+#[cfg(feature = "upload")]
+fn upload_run_artifacts(remote_uri: &str, artifacts: &[PathBuf]) -> Result<(), String> {
+    for uri in folding_interface::upload_artifacts(remote_uri, artifacts)? {
+        say!("Uploaded {uri}");
+    }
     Ok(())
 }
+
+#[cfg(not(feature = "upload"))]
+fn upload_run_artifacts(_remote_uri: &str, _artifacts: &[PathBuf]) -> Result<(), String> {
+    Err("remote upload requires building with `--features upload`".into())
+}
+
+/// Writes the final structure next to `script_path` and renders a viewer
+/// script that loads it (plus the PDB trajectory, when exported). Returns the
+/// structure path.
+fn write_viewer_script(
+    script_path: &Path,
+    report: &ExecutionReport,
+    trajectory: Option<&(PathBuf, usize)>,
+    metric: ColorMetric,
+    render: impl Fn(&VisualScene) -> String,
+) -> Result<PathBuf, String> {
+    ensure_parent_dir(script_path)?;
+    let stem = script_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "logline".into());
+    let structure_path = script_path.with_file_name(format!("{stem}_final.pdb"));
+    let sequence = protein::ProteinSequence {
+        identifier: None,
+        sequence: String::new(),
+    };
+    protein::write_pdb(&report.final_chain, &structure_path, &sequence)?;
+
+    let values = metric.values(
+        &report.applied_rotations,
+        &report.frames,
+        &report.final_chain,
+    );
+    let structure = script_relative(script_path, &structure_path);
+    let trajectory_path = trajectory.map(|(path, _)| script_relative(script_path, path));
+    let scene = VisualScene {
+        structure: &structure,
+        trajectory: trajectory_path.as_deref(),
+        frame_count: trajectory.map(|(_, count)| *count).unwrap_or(1),
+        metric,
+        values: &values,
+    };
+    fs::write(script_path, render(&scene))
+        .map_err(|err| format!("failed to write script {}: {err}", script_path.display()))?;
+    Ok(structure_path)
+}
+
+/// Writes the final structure as `{stem}_final.pdb` next to `script_path`
+/// and a PyMOL script drawing its frustrated contacts. Returns the PDB path.
+fn write_frustration_script(
+    script_path: &Path,
+    report: &ExecutionReport,
+    frustration: &FrustrationReport,
+) -> Result<PathBuf, String> {
+    let stem = script_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "logline".into());
+    let structure_path = script_path.with_file_name(format!("{stem}_final.pdb"));
+    let sequence = protein::ProteinSequence {
+        identifier: None,
+        sequence: String::new(),
+    };
+    protein::write_pdb(&report.final_chain, &structure_path, &sequence)?;
+    let script =
+        frustration_pymol_script(&script_relative(script_path, &structure_path), frustration);
+    fs::write(script_path, script)
+        .map_err(|err| format!("failed to write script {}: {err}", script_path.display()))?;
+    Ok(structure_path)
+}
+
+/// Writes `{stem}.psf` and `{stem}.dcd` next to `script_path` and a VMD Tcl
+/// script that loads them. Returns the PSF and DCD paths.
+fn write_vmd_bundle(
+    script_path: &Path,
+    report: &ExecutionReport,
+    frames: &[TrajectoryFrame],
+    metric: ColorMetric,
+) -> Result<[PathBuf; 2], String> {
+    ensure_parent_dir(script_path)?;
+    let psf_path = script_path.with_extension("psf");
+    let dcd_path = script_path.with_extension("dcd");
+    fs::write(&psf_path, psf_topology(&report.final_chain))
+        .map_err(|err| format!("failed to write PSF {}: {err}", psf_path.display()))?;
+
+    let mut positions: Vec<Vec<[f64; 3]>> =
+        frames.iter().map(|frame| frame.positions.clone()).collect();
+    if positions.is_empty() {
+        positions.push(
+            report
+                .final_chain
+                .residues()
+                .map(|residue| residue.position())
+                .collect(),
+        );
+    }
+    let timestep_ms = match (frames.first(), frames.last()) {
+        (Some(first), Some(last)) if frames.len() > 1 => {
+            (last.time_ms - first.time_ms) / (frames.len() - 1) as f64
+        }
+        _ => 0.0,
+    };
+    fs::write(
+        &dcd_path,
+        encode_dcd(&positions, timestep_ms, "LogLineFold trajectory"),
+    )
+    .map_err(|err| format!("failed to write DCD {}: {err}", dcd_path.display()))?;
+
+    let values = metric.values(&report.applied_rotations, frames, &report.final_chain);
+    let structure = script_relative(script_path, &psf_path);
+    let trajectory = script_relative(script_path, &dcd_path);
+    let scene = VisualScene {
+        structure: &structure,
+        trajectory: Some(&trajectory),
+        frame_count: positions.len(),
+        metric,
+        values: &values,
+    };
+    fs::write(script_path, vmd_script(&scene))
+        .map_err(|err| format!("failed to write script {}: {err}", script_path.display()))?;
+    Ok([psf_path, dcd_path])
+}
+
+fn chain_positions(chain: &PeptideChain) -> Vec<[f64; 3]> {
+    chain.residues().map(|residue| residue.position()).collect()
+}
+
+fn ensure_parent_dir(path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to create directory {}: {err}", parent.display())),
+        _ => Ok(()),
+    }
+}
+
+/// `path` relative to the directory of `script_path`, so a viewer script
+/// run from its own directory finds the files exported beside it.
+fn script_relative(script_path: &Path, path: &Path) -> String {
+    let absolute = |path: &Path| std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let script = absolute(script_path);
+    let base = script.parent().unwrap_or(Path::new(""));
+    let target = absolute(path);
+    let common = base
+        .components()
+        .zip(target.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut relative: PathBuf = base.components().skip(common).map(|_| "..").collect();
+    relative.extend(target.components().skip(common));
+    relative.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_run_outcome_has_its_own_exit_code() {
+        let codes = [
+            (RunOutcome::Completed, 0),
+            (RunOutcome::CompletedWithViolations, 3),
+            (RunOutcome::BackendFallback, 4),
+            (RunOutcome::Aborted, 5),
+            (RunOutcome::Truncated, 6),
+        ];
+        for (outcome, code) in codes {
+            assert_eq!(outcome_exit_code(outcome), code, "{}", outcome.as_str());
+        }
+        // None of them collides with failed commands or bad arguments
+        assert!(
+            codes
+                .iter()
+                .all(|&(_, code)| code != 1 && code != EXIT_USAGE)
+        );
+    }
+
+    #[test]
+    fn viewer_scripts_refer_to_files_relative_to_themselves() {
+        let script = Path::new("outputs/demo.pml");
+        assert_eq!(
+            script_relative(script, Path::new("outputs/demo_final.pdb")),
+            "demo_final.pdb"
+        );
+        assert_eq!(
+            script_relative(script, Path::new("frames/run 1.pdb")),
+            "../frames/run 1.pdb"
+        );
+        assert_eq!(
+            script_relative(Path::new("demo.cxc"), Path::new("outputs/demo.mp4")),
+            "outputs/demo.mp4"
+        );
+    }
+
+    #[test]
+    fn sweep_runs_pool_their_energy_samples() {
+        let dir = std::env::temp_dir().join(format!("logline_pool_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let log = |name: &str, samples: &str| {
+            let path = dir.join(name);
+            let line = format!(
+                "metadata|run_id={name}|temperature=300|time_step_ms=1|accepted_spans=1|\
+                 rejected_spans=0|acceptance_rate=1|final_potential_energy=0|\
+                 final_gibbs_energy=0|informational_efficiency=1|total_work=0{samples}\n"
+            );
+            fs::write(&path, line).unwrap();
+            path
+        };
+        let mut first = EnergySamples::new();
+        [-1.0, 1.0]
+            .into_iter()
+            .for_each(|energy| first.record(energy));
+        let mut second = EnergySamples::new();
+        [-10.0, 0.0]
+            .into_iter()
+            .for_each(|energy| second.record(energy));
+        let a = log("a.log", &format!("|energy_samples={first}"));
+        let b = log("b.log", &format!("|energy_samples={second}"));
+        let bare = log("bare.log", "");
+
+        let analysis = RunAnalysis::load(&a).unwrap();
+        let pooled = pooled_energy_samples(&analysis, &a, &[b]).unwrap();
+        assert_eq!(pooled.len(), 4);
+        assert_eq!(pooled.best(), Some(-10.0));
+        let err = pooled_energy_samples(&analysis, &a, std::slice::from_ref(&bare)).unwrap_err();
+        assert!(err.contains("bare.log records no energy samples"), "{err}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bench_document_lists_every_integrator() {
+        let config = physics_nve::NveConfig {
+            residues: 4,
+            steps: 20,
+            ..physics_nve::NveConfig::default()
+        };
+        let reports = physics_nve::run_standard_nve(&config);
+        let document = bench_document(&config, &reports);
+        assert_eq!(document["residues"], 4);
+        assert_eq!(document["steps"], 20);
+        assert_eq!(document["timestep_ps"], config.timestep_ps);
+        let integrators = document["integrators"].as_array().unwrap();
+        assert_eq!(integrators.len(), reports.len());
+        for (entry, report) in integrators.iter().zip(&reports) {
+            assert_eq!(entry["integrator"], report.integrator.as_str());
+            for field in [
+                "initial_energy",
+                "final_energy",
+                "max_deviation",
+                "drift_per_ns",
+            ] {
+                assert!(entry[field].is_number(), "{field}: {entry}");
+            }
+        }
+    }
+}
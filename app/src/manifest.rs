@@ -0,0 +1,382 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use hmac_sha256::Hash;
+use serde::{Deserialize, Serialize};
+
+/// Everything needed to repeat a run: the effective parameters after presets
+/// and defaults are applied, the seed actually used, and hashes of the inputs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub run: RunSection,
+    pub parameters: RunParameters,
+    #[serde(default)]
+    pub inputs: Vec<InputDigest>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunSection {
+    pub run_id: String,
+    #[serde(default)]
+    pub run_name: Option<String>,
+    pub log: Option<PathBuf>,
+    pub crate_version: String,
+    pub git_commit: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunParameters {
+    pub label: Option<String>,
+    pub preset: Option<String>,
+    pub fasta: Option<PathBuf>,
+    #[serde(default)]
+    pub mmcif: Option<PathBuf>,
+    #[serde(default)]
+    pub pdb: Option<PathBuf>,
+    pub contract: Option<PathBuf>,
+    pub reference: Option<PathBuf>,
+    pub environment: String,
+    /// Effective solvent conditions; absent in manifests from older builds.
+    #[serde(default)]
+    pub viscosity: Option<f64>,
+    #[serde(default)]
+    pub dielectric: Option<f64>,
+    #[serde(default)]
+    pub crowding_fraction: Option<f64>,
+    #[serde(default)]
+    pub crowding_model: Option<String>,
+    #[serde(default)]
+    pub dielectric_model: Option<String>,
+    pub temperature: f64,
+    /// `--accept-temp`, when Metropolis acceptance ran at its own temperature.
+    #[serde(default)]
+    pub acceptance_temperature: Option<f64>,
+    pub time_step_ms: u64,
+    pub rng_seed: u64,
+    /// `--rng-state` the move stream resumed from, in hexadecimal.
+    #[serde(default)]
+    pub rng_state: Option<String>,
+    /// Step of the run the state was logged at; the resumed run counts on
+    /// from there.
+    #[serde(default)]
+    pub rng_step: Option<usize>,
+    /// Native physics velocities resumed with `rng_state`, as logged.
+    #[serde(default)]
+    pub rng_velocities: Option<String>,
+    /// `--rng-log-every`: accepted spans between logged RNG states.
+    #[serde(default)]
+    pub rng_log_every: Option<usize>,
+    /// `--acceptance-window`: decisions per rolling acceptance point.
+    #[serde(default)]
+    pub acceptance_window: Option<usize>,
+    pub info_scale: f64,
+    pub diamond_threshold: Option<f64>,
+    pub diamond_dir: Option<PathBuf>,
+    pub database: Option<PathBuf>,
+    pub upload: Option<String>,
+    pub frames: Option<PathBuf>,
+    pub smooth_frames: usize,
+    pub pymol: Option<PathBuf>,
+    pub chimerax: Option<PathBuf>,
+    pub vmd: Option<PathBuf>,
+    pub color_by: String,
+    /// `--energy-csv` energy-component series and its `--energy-every` stride.
+    #[serde(default)]
+    pub energy_csv: Option<PathBuf>,
+    #[serde(default)]
+    pub energy_every: Option<usize>,
+    /// `--residue-energy-csv` per-residue attribution of the same samples.
+    #[serde(default)]
+    pub residue_energy_csv: Option<PathBuf>,
+    /// `--frustration` contact CSV; its PyMOL script sits alongside.
+    #[serde(default)]
+    pub frustration: Option<PathBuf>,
+    /// `--disorder` propensity CSV; the B-factor PDB sits alongside.
+    #[serde(default)]
+    pub disorder: Option<PathBuf>,
+    #[serde(default)]
+    pub mi_matrix: Option<PathBuf>,
+    #[serde(default)]
+    pub mi_signal: Option<String>,
+    #[serde(default)]
+    pub te_matrix: Option<PathBuf>,
+    #[serde(default)]
+    pub pathway: Option<[usize; 2]>,
+    /// Point group, protomer placement radius (Å) and assembly PDB of symmetric runs.
+    #[serde(default)]
+    pub symmetry: Option<String>,
+    #[serde(default)]
+    pub symmetry_radius: Option<f64>,
+    /// Side-chain bead of the model (`cb` or `centroid`).
+    #[serde(default)]
+    pub side_chains: Option<String>,
+    #[serde(default)]
+    pub assembly: Option<PathBuf>,
+    /// `--final-pdb` multi-chain structure of the final state.
+    #[serde(default)]
+    pub final_pdb: Option<PathBuf>,
+    /// `--contacts` map and the `--contact-min-prob` its pairs were cut at.
+    #[serde(default)]
+    pub contacts: Option<PathBuf>,
+    #[serde(default)]
+    pub contact_min_probability: Option<f64>,
+    /// `--predicted` model the run started from and its `--plddt-min`.
+    #[serde(default)]
+    pub predicted: Option<PathBuf>,
+    #[serde(default)]
+    pub plddt_min: Option<f64>,
+    /// Full-backbone PDB rebuilt from the Cα model.
+    #[serde(default)]
+    pub backmap: Option<PathBuf>,
+    /// Starting physics level; `--level auto` records the level it picked.
+    #[serde(default)]
+    pub level: Option<String>,
+    /// `--physics-engine` backend selection.
+    #[serde(default)]
+    pub physics_engine: Option<String>,
+    /// `--require-backend`: backend failures abort the run.
+    #[serde(default)]
+    pub require_backend: bool,
+    /// `--thermo-check`: energy bookkeeping verified per span.
+    #[serde(default)]
+    pub thermo_check: bool,
+    #[serde(default)]
+    pub wall_clock_budget: Option<f64>,
+    /// Ruleset file given with `--rules`.
+    #[serde(default)]
+    pub rules: Option<PathBuf>,
+    /// `--ghost-budget` limit (`count:N`, `entropy:F`) and overflow action.
+    #[serde(default)]
+    pub ghost_budget: Option<String>,
+    #[serde(default)]
+    pub ghost_overflow: Option<String>,
+    /// `--force-field` / `--integrator` registry names and `--plugin` libraries.
+    #[serde(default)]
+    pub force_field: Option<String>,
+    #[serde(default)]
+    pub integrator: Option<String>,
+    /// `--md-timestep` in ps.
+    #[serde(default)]
+    pub md_timestep: Option<f64>,
+    #[serde(default)]
+    pub plugins: Vec<PathBuf>,
+    /// `--score name=expression` terms added to the Metropolis criterion.
+    #[serde(default)]
+    pub scores: Vec<String>,
+    /// `--cv` collective variables logged with every accepted span.
+    #[serde(default)]
+    pub collective_variables: Vec<String>,
+    /// `--model-score` server, weight and what it was sent.
+    #[serde(default)]
+    pub model_score: Option<String>,
+    #[serde(default)]
+    pub model_weight: Option<f64>,
+    #[serde(default)]
+    pub model_input: Option<String>,
+    /// `--openmm-cache` directory; rerunning with it reuses cached spans.
+    #[serde(default)]
+    pub openmm_cache: Option<PathBuf>,
+    /// `--native-trajectories` directory the native MD frames went to.
+    #[serde(default)]
+    pub native_trajectories: Option<PathBuf>,
+    /// `--start-from` trajectory and frame the starting Cα positions came from.
+    #[serde(default)]
+    pub start_from: Option<PathBuf>,
+    #[serde(default)]
+    pub start_frame: Option<usize>,
+    /// `--mem-cap` in MiB; the run's frames were thinned to stay under it.
+    #[serde(default)]
+    pub mem_cap_mb: Option<f64>,
+    pub anneal: Option<AnnealParameters>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AnnealParameters {
+    pub start: f64,
+    pub end: f64,
+    pub steps: usize,
+}
+
+/// SHA-256 of an input file, keyed by the role it played in the run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputDigest {
+    pub role: String,
+    pub path: PathBuf,
+    pub sha256: String,
+}
+
+impl InputDigest {
+    pub fn of(role: &str, path: &Path) -> Result<Self, String> {
+        Ok(Self {
+            role: role.to_string(),
+            path: path.to_path_buf(),
+            sha256: sha256_file(path)?,
+        })
+    }
+}
+
+impl RunManifest {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read manifest {}: {err}", path.display()))?;
+        toml::from_str(&contents)
+            .map_err(|err| format!("invalid manifest {}: {err}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|err| format!("failed to serialise manifest: {err}"))?;
+        fs::write(path, contents)
+            .map_err(|err| format!("failed to write manifest {}: {err}", path.display()))
+    }
+
+    /// Fails if any recorded input is missing or no longer matches its hash.
+    pub fn verify_inputs(&self) -> Result<(), String> {
+        for input in &self.inputs {
+            let actual = sha256_file(&input.path)?;
+            if actual != input.sha256 {
+                return Err(format!(
+                    "{} input {} changed since the run (sha256 {} != {})",
+                    input.role,
+                    input.path.display(),
+                    actual,
+                    input.sha256
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `<log stem>.run.manifest.toml` next to the span log.
+pub fn manifest_path(log_path: &Path) -> PathBuf {
+    let stem = log_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "run".into());
+    log_path.with_file_name(format!("{stem}.run.manifest.toml"))
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let bytes =
+        fs::read(path).map_err(|err| format!("failed to read input {}: {err}", path.display()))?;
+    Ok(Hash::hash(&bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_round_trips_and_detects_changed_inputs() {
+        let dir = std::env::temp_dir().join(format!("logline_manifest_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let contract = dir.join("run.lll");
+        fs::write(&contract, "rotate residue=1 angle=5 duration=1\n").unwrap();
+
+        let manifest = RunManifest {
+            run: RunSection {
+                run_id: "abc".into(),
+                run_name: None,
+                log: Some(dir.join("run_abc.log")),
+                crate_version: "0.1.0".into(),
+                git_commit: "unknown".into(),
+            },
+            parameters: RunParameters {
+                label: Some("run".into()),
+                preset: None,
+                fasta: None,
+                mmcif: None,
+                pdb: None,
+                contract: Some(contract.clone()),
+                reference: None,
+                environment: "aqueous".into(),
+                viscosity: Some(0.89),
+                dielectric: Some(78.5),
+                crowding_fraction: Some(0.0),
+                crowding_model: Some("mean-field".into()),
+                dielectric_model: Some("gb".into()),
+                temperature: 298.0,
+                acceptance_temperature: None,
+                time_step_ms: 1,
+                rng_seed: 42,
+                rng_state: None,
+                rng_step: None,
+                rng_velocities: None,
+                rng_log_every: Some(100),
+                acceptance_window: None,
+                info_scale: 0.01,
+                diamond_threshold: None,
+                diamond_dir: None,
+                database: None,
+                upload: None,
+                frames: None,
+                smooth_frames: 0,
+                pymol: None,
+                chimerax: None,
+                vmd: None,
+                color_by: "entropy".into(),
+                energy_csv: None,
+                energy_every: None,
+                residue_energy_csv: None,
+                frustration: None,
+                disorder: None,
+                mi_matrix: None,
+                mi_signal: None,
+                te_matrix: None,
+                pathway: Some([0, 3]),
+                symmetry: Some("C3".into()),
+                symmetry_radius: Some(18.5),
+                side_chains: None,
+                assembly: None,
+                final_pdb: None,
+                contacts: None,
+                contact_min_probability: None,
+                predicted: None,
+                plddt_min: None,
+                backmap: None,
+                level: Some("coarse".into()),
+                physics_engine: Some("native".into()),
+                require_backend: true,
+                thermo_check: true,
+                wall_clock_budget: Some(60.0),
+                rules: None,
+                ghost_budget: Some("count:50".into()),
+                ghost_overflow: Some("prune".into()),
+                force_field: Some("amber99sb".into()),
+                integrator: None,
+                md_timestep: Some(0.002),
+                plugins: Vec::new(),
+                scores: vec!["compact=0.5*rg".into()],
+                collective_variables: vec!["rg".into(), "phi:3".into()],
+                model_score: Some("http://127.0.0.1:8000/score".into()),
+                model_weight: Some(0.5),
+                model_input: Some("features".into()),
+                openmm_cache: None,
+                native_trajectories: None,
+                start_from: None,
+                start_frame: None,
+                mem_cap_mb: None,
+                anneal: Some(AnnealParameters {
+                    start: 350.0,
+                    end: 300.0,
+                    steps: 10,
+                }),
+            },
+            inputs: vec![InputDigest::of("contract", &contract).unwrap()],
+        };
+        let path = manifest_path(manifest.run.log.as_ref().unwrap());
+        assert_eq!(path, dir.join("run_abc.run.manifest.toml"));
+        manifest.save(&path).unwrap();
+        let loaded = RunManifest::load(&path).unwrap();
+        assert_eq!(loaded, manifest);
+        assert!(loaded.verify_inputs().is_ok());
+
+        fs::write(&contract, "commit\n").unwrap();
+        assert!(loaded.verify_inputs().unwrap_err().contains("changed"));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,88 @@
+//! `--output json`: commands keep their text summary off stdout and print a
+//! single JSON document with the same figures and the files they wrote.
+
+use std::io::Write;
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value, json};
+
+use crate::cli::{OutputFormat, Verbosity};
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// Selects the format for the rest of the process; the first call wins.
+pub fn set_format(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+/// Sends `log` records to stderr at the level `verbosity` asks for; a set
+/// `RUST_LOG` takes precedence. Warnings carry a `warning:` prefix; from
+/// `-vv` on every line names its level and the module it came from.
+pub fn init_logging(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+    let detailed = verbosity >= Verbosity::Debug;
+    env_logger::Builder::new()
+        .filter_level(verbosity.level_filter())
+        .parse_default_env()
+        .format(move |buf, record| match record.level() {
+            log::Level::Info if !detailed => writeln!(buf, "{}", record.args()),
+            log::Level::Warn if !detailed => writeln!(buf, "warning: {}", record.args()),
+            log::Level::Error if !detailed => writeln!(buf, "{}", record.args()),
+            level => writeln!(
+                buf,
+                "{} {}: {}",
+                level.as_str().to_ascii_lowercase(),
+                record.target(),
+                record.args()
+            ),
+        })
+        .init();
+}
+
+pub fn is_json() -> bool {
+    FORMAT.get() == Some(&OutputFormat::Json)
+}
+
+/// Whether the text summary goes to stdout: not under `--output json` or `-q`.
+pub fn says() -> bool {
+    !is_json() && VERBOSITY.get() != Some(&Verbosity::Quiet)
+}
+
+/// `println!` for the text summary; silent under `--output json`, where
+/// stdout carries only the JSON document, and under `-q`.
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::output::says() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use say;
+
+/// Prints `document` as the command's result under `--output json`.
+pub fn emit(command: &str, document: Value) {
+    if !is_json() {
+        return;
+    }
+    let mut object = Map::new();
+    object.insert("command".into(), json!(command));
+    object.insert("status".into(), json!("ok"));
+    match document {
+        Value::Object(fields) => object.extend(fields),
+        other => {
+            object.insert("result".into(), other);
+        }
+    }
+    println!("{}", Value::Object(object));
+}
+
+/// Failure counterpart of [`emit`]; the message also goes to stderr.
+pub fn emit_error(command: &str, message: &str) {
+    if is_json() {
+        println!(
+            "{}",
+            json!({ "command": command, "status": "error", "error": message })
+        );
+    }
+}
@@ -2,7 +2,10 @@ use std::fs;
 use std::io::Write;
 use std::path::Path;
 
-use folding_molecule::{PeptideChain, Residue};
+use folding_core::{BackboneAtoms, BackboneCollector, BackmappedResidue, backbone_dihedrals};
+use folding_interface::mmcif::{parse_mmcif_backbone, parse_mmcif_ca_coordinates, transform_mmcif};
+use folding_interface::pdb::{first_model_atoms, pdb_coordinates};
+use folding_molecule::{PeptideChain, Residue, ResidueId, SideChainBead, chain_letter};
 
 /// Representation of an input protein sequence along with optional metadata.
 #[derive(Debug, Clone)]
@@ -112,6 +115,248 @@ fn extract_json_value(contents: &str, key: &str) -> Option<String> {
     None
 }
 
+/// Reads C-alpha coordinates from the first model of a PDB or mmCIF file.
+pub fn read_ca_coordinates(path: &Path) -> Result<Vec<[f64; 3]>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read structure {}: {err}", path.display()))?;
+    let positions = if is_mmcif(path) {
+        parse_mmcif_ca_coordinates(&contents)?
+    } else {
+        parse_ca_coordinates(&contents)?
+    };
+    if positions.is_empty() {
+        return Err(format!(
+            "structure {} contained no CA atoms",
+            path.display()
+        ));
+    }
+    Ok(positions)
+}
+
+/// Copies a PDB or mmCIF file with every atom coordinate passed through `transform`.
+pub fn write_transformed(
+    source: &Path,
+    output: &Path,
+    transform: impl Fn([f64; 3]) -> [f64; 3],
+) -> Result<(), String> {
+    let contents = fs::read_to_string(source)
+        .map_err(|err| format!("failed to read structure {}: {err}", source.display()))?;
+    let rendered = if is_mmcif(source) {
+        transform_mmcif(&contents, transform)?
+    } else {
+        transform_pdb(&contents, transform)?
+    };
+    fs::write(output, rendered)
+        .map_err(|err| format!("failed to write structure {}: {err}", output.display()))
+}
+
+/// Returns true when the path carries an mmCIF extension (`.cif` / `.mmcif`).
+pub fn is_mmcif(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("cif") || ext.eq_ignore_ascii_case("mmcif"))
+        .unwrap_or(false)
+}
+
+fn transform_pdb(
+    contents: &str,
+    transform: impl Fn([f64; 3]) -> [f64; 3],
+) -> Result<String, String> {
+    let mut rendered = String::with_capacity(contents.len());
+    for line in contents.lines() {
+        if (line.starts_with("ATOM") || line.starts_with("HETATM")) && line.len() >= 54 {
+            let position = pdb_coordinates(line)?;
+            let moved = transform(position);
+            rendered.push_str(&format!(
+                "{}{:>8.3}{:>8.3}{:>8.3}{}\n",
+                &line[..30],
+                moved[0],
+                moved[1],
+                moved[2],
+                &line[54..]
+            ));
+        } else {
+            rendered.push_str(line);
+            rendered.push('\n');
+        }
+    }
+    Ok(rendered)
+}
+
+/// Reads N, CA and C positions per residue from the first model of a PDB or
+/// mmCIF file, keeping residue names in file order.
+pub fn read_backbone(path: &Path) -> Result<Vec<(String, BackboneAtoms)>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read structure {}: {err}", path.display()))?;
+    let residues = if is_mmcif(path) {
+        parse_mmcif_backbone(&contents)?
+    } else {
+        parse_pdb_backbone(&contents)?
+    };
+    if residues.is_empty() {
+        return Err(format!(
+            "structure {} contained no backbone atoms",
+            path.display()
+        ));
+    }
+    Ok(residues)
+}
+
+fn parse_pdb_backbone(contents: &str) -> Result<Vec<(String, BackboneAtoms)>, String> {
+    let mut collector = BackboneCollector::default();
+    for atom in first_model_atoms(contents)? {
+        collector.push(
+            atom.residue_key.to_string(),
+            atom.residue_name,
+            atom.name,
+            atom.position,
+        );
+    }
+    Ok(collector.residues)
+}
+
+/// Chain rebuilt from a PDB file, keeping the chain identifier of each residue.
+#[derive(Debug, Clone)]
+pub struct PdbStructure {
+    pub chain: PeptideChain,
+    /// Chain identifier (column 22) of each residue, in chain order.
+    pub chain_ids: Vec<char>,
+}
+
+impl PdbStructure {
+    /// Distinct chain identifiers in file order.
+    pub fn chain_names(&self) -> Vec<char> {
+        let mut names: Vec<char> = Vec::new();
+        for id in &self.chain_ids {
+            if !names.contains(id) {
+                names.push(*id);
+            }
+        }
+        names
+    }
+}
+
+/// Reads the first model of a PDB file as a chain: one bead per residue at
+/// its Cα, with phi/psi measured from N, Cα and C where present. Every chain
+/// is kept, in file order, and starts a chain of the complex; waters and ligands (`HETATM`) and all but the
+/// first alternate location are skipped.
+pub fn load_pdb(path: &Path) -> Result<PdbStructure, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read PDB {}: {err}", path.display()))?;
+    parse_pdb_structure(&contents).map_err(|err| format!("invalid PDB {}: {err}", path.display()))
+}
+
+fn parse_pdb_structure(contents: &str) -> Result<PdbStructure, String> {
+    let mut collector = BackboneCollector::default();
+    let mut chain_ids = Vec::new();
+    for atom in first_model_atoms(contents)? {
+        let residues = collector.residues.len();
+        collector.push(
+            atom.residue_key.to_string(),
+            atom.residue_name,
+            atom.name,
+            atom.position,
+        );
+        if collector.residues.len() > residues {
+            chain_ids.push(atom.chain_id);
+        }
+    }
+
+    let (residues, chain_ids): (Vec<_>, Vec<_>) = collector
+        .residues
+        .into_iter()
+        .zip(chain_ids)
+        .filter(|((_, atoms), _)| atoms.ca.is_some())
+        .unzip();
+    if residues.is_empty() {
+        return Err("no CA atoms".into());
+    }
+    let mut chain = PeptideChain::default();
+    for (index, ((name, atoms), id)) in residues.iter().zip(&chain_ids).enumerate() {
+        if index == 0 || chain_ids[index - 1] != *id {
+            // Chains are split by identifier, so one must not come back later
+            if chain.chain_starts().iter().any(|start| start.id == *id) {
+                return Err(format!("chain {id} is split by another chain"));
+            }
+            chain.begin_chain(*id);
+        }
+        chain.push(Residue::new(
+            ResidueId(index),
+            name.as_str(),
+            atoms.ca.unwrap_or_default(),
+        ));
+    }
+    let atoms: Vec<BackboneAtoms> = residues.into_iter().map(|(_, atoms)| atoms).collect();
+    for (index, dihedrals) in backbone_dihedrals(&atoms).into_iter().enumerate() {
+        // Torsions reaching across a chain break join two chains
+        if let Some(phi) = dihedrals
+            .phi
+            .filter(|_| index > 0 && chain.is_bonded(index - 1))
+        {
+            chain.phi_mut()[index] = phi;
+        }
+        if let Some(psi) = dihedrals.psi.filter(|_| chain.is_bonded(index)) {
+            chain.psi_mut()[index] = psi;
+        }
+    }
+    Ok(PdbStructure { chain, chain_ids })
+}
+
+fn parse_ca_coordinates(contents: &str) -> Result<Vec<[f64; 3]>, String> {
+    let mut positions = Vec::new();
+    for line in contents.lines() {
+        if line.starts_with("ENDMDL") {
+            break;
+        }
+        if !(line.starts_with("ATOM") || line.starts_with("HETATM")) {
+            continue;
+        }
+        if line.get(12..16).map(str::trim) != Some("CA") {
+            continue;
+        }
+        positions.push(pdb_coordinates(line)?);
+    }
+    Ok(positions)
+}
+
+/// Copies a PDB file, storing one value per residue in the B-factor column.
+/// Residues of the first model are counted in file order; atoms beyond
+/// `values` keep their B-factor.
+pub fn write_with_bfactors(source: &Path, output: &Path, values: &[f64]) -> Result<(), String> {
+    let contents = fs::read_to_string(source)
+        .map_err(|err| format!("failed to read PDB {}: {err}", source.display()))?;
+    fs::write(output, replace_bfactors(&contents, values))
+        .map_err(|err| format!("failed to write PDB {}: {err}", output.display()))
+}
+
+fn replace_bfactors(contents: &str, values: &[f64]) -> String {
+    let mut rendered = String::with_capacity(contents.len());
+    let mut residue_key: Option<&str> = None;
+    let mut residue_index = 0usize;
+    for line in contents.lines() {
+        let is_atom = line.starts_with("ATOM") || line.starts_with("HETATM");
+        if let (true, Some(key)) = (is_atom, line.get(17..27)) {
+            if residue_key.is_some_and(|previous| previous != key) {
+                residue_index += 1;
+            }
+            residue_key = Some(key);
+            if let Some(value) = values.get(residue_index) {
+                let prefix = format!("{:<60}", line.get(..60).unwrap_or(line));
+                let suffix = line.get(66..).unwrap_or("");
+                rendered.push_str(&format!("{prefix}{value:>6.2}{suffix}\n"));
+                continue;
+            }
+        }
+        if line.starts_with("ENDMDL") {
+            // Only the first model is scored; later models keep their values.
+            residue_index = values.len();
+        }
+        rendered.push_str(line);
+        rendered.push('\n');
+    }
+    rendered
+}
+
 /// Writes a simple C-alpha-only PDB file.
 pub fn write_pdb(
     chain: &PeptideChain,
@@ -131,38 +376,201 @@ pub fn write_pdb(
     writeln!(file, "TITLE     Mock folding trace generated by LogLine")
         .map_err(|err| format!("failed to write title: {err}"))?;
 
-    for (index, residue) in chain.residues().iter().enumerate() {
-        write_atom_line(&mut file, index + 1, residue)
-            .map_err(|err| format!("failed to write atom line: {err}"))?;
+    write_chain_atoms(
+        &mut file,
+        chain,
+        chain.positions(),
+        &chain.side_chain_positions(),
+    )
+    .map_err(|err| format!("failed to write atom line: {err}"))?;
+    writeln!(file, "END").map_err(|err| format!("failed to write footer: {err}"))?;
+    Ok(())
+}
+
+/// Writes C-alpha frames as a multi-model PDB (one MODEL block per frame),
+/// each chain of a complex under its own identifier.
+pub fn write_multi_model_pdb(
+    chain: &PeptideChain,
+    frames: &[Vec<[f64; 3]>],
+    path: &Path,
+) -> Result<(), String> {
+    let mut file = fs::File::create(path)
+        .map_err(|err| format!("failed to create PDB {}: {err}", path.display()))?;
+    let write_err = |err: std::io::Error| format!("failed to write PDB {}: {err}", path.display());
+
+    writeln!(
+        file,
+        "TITLE     LogLine folding trajectory ({} frames)",
+        frames.len()
+    )
+    .map_err(write_err)?;
+    for (model, frame) in frames.iter().enumerate() {
+        writeln!(file, "MODEL     {:>4}", model + 1).map_err(write_err)?;
+        write_chain_atoms(&mut file, chain, frame, &[]).map_err(write_err)?;
+        writeln!(file, "ENDMDL").map_err(write_err)?;
     }
+    writeln!(file, "END").map_err(write_err)?;
+    Ok(())
+}
 
-    writeln!(file, "TER").map_err(|err| format!("failed to write terminator: {err}"))?;
-    writeln!(file, "END").map_err(|err| format!("failed to write footer: {err}"))?;
+/// Writes a symmetric assembly as one C-alpha chain per protomer copy,
+/// lettered A, B, C, ...
+pub fn write_assembly_pdb(
+    chain: &PeptideChain,
+    copies: &[Vec<[f64; 3]>],
+    path: &Path,
+) -> Result<(), String> {
+    let mut file = fs::File::create(path)
+        .map_err(|err| format!("failed to create PDB {}: {err}", path.display()))?;
+    let write_err = |err: std::io::Error| format!("failed to write PDB {}: {err}", path.display());
+
+    writeln!(
+        file,
+        "TITLE     LogLine symmetric assembly ({} protomers)",
+        copies.len()
+    )
+    .map_err(write_err)?;
+    let mut serial = 0;
+    for (copy, positions) in copies.iter().enumerate() {
+        let chain_id = chain_letter(copy);
+        for (residue, position) in chain.residues().zip(positions) {
+            serial += 1;
+            write_atom_line(
+                &mut file,
+                serial,
+                "CA",
+                residue.name,
+                chain_id,
+                residue.id.0 + 1,
+                *position,
+            )
+            .map_err(write_err)?;
+        }
+        writeln!(file, "TER").map_err(write_err)?;
+    }
+    writeln!(file, "END").map_err(write_err)?;
+    Ok(())
+}
+
+/// Writes a backmapped model of `chain` with N, CA, C, O and (where present)
+/// CB atoms, each chain under its own identifier and closed with `TER`.
+pub fn write_backbone_pdb(
+    model: &[BackmappedResidue],
+    chain: &PeptideChain,
+    path: &Path,
+) -> Result<(), String> {
+    let mut file = fs::File::create(path)
+        .map_err(|err| format!("failed to create PDB {}: {err}", path.display()))?;
+    let write_err = |err: std::io::Error| format!("failed to write PDB {}: {err}", path.display());
+
+    writeln!(file, "TITLE     LogLine backmapped backbone").map_err(write_err)?;
+    let mut serial = 0;
+    for (chain_id, range) in chain.chain_ranges() {
+        for index in range.take_while(|&index| index < model.len()) {
+            let residue = &model[index];
+            let atoms = [
+                ("N", Some(residue.n)),
+                ("CA", Some(residue.ca)),
+                ("C", Some(residue.c)),
+                ("O", Some(residue.o)),
+                ("CB", residue.cb),
+            ];
+            for (atom, position) in atoms {
+                let Some(position) = position else {
+                    continue;
+                };
+                serial += 1;
+                writeln!(
+                    file,
+                    "ATOM  {:>5}  {:<3} {:>3} {}{:>4}    {:>8.3}{:>8.3}{:>8.3}  1.00  0.00           {}",
+                    serial,
+                    atom,
+                    residue.name,
+                    chain_id,
+                    chain.ids()[index].0 + 1,
+                    position[0],
+                    position[1],
+                    position[2],
+                    &atom[..1],
+                )
+                .map_err(write_err)?;
+            }
+        }
+        writeln!(file, "TER").map_err(write_err)?;
+    }
+    writeln!(file, "END").map_err(write_err)?;
+    Ok(())
+}
+
+/// Writes `positions` as the Cα atoms of `chain`, each followed by its
+/// side-chain bead from `beads` where there is one (`CB` for a Cβ bead, `CEN`
+/// for a centroid), closing each chain of a complex with `TER`. Residues keep
+/// their numbers across chains, so scripts that select by residue number work
+/// for complexes too.
+fn write_chain_atoms(
+    mut writer: impl Write,
+    chain: &PeptideChain,
+    positions: &[[f64; 3]],
+    beads: &[Option<[f64; 3]>],
+) -> std::io::Result<()> {
+    let bead_atom = match chain.side_chains() {
+        Some(SideChainBead::Beta) => "CB",
+        _ => "CEN",
+    };
+    let mut serial = 0;
+    for (chain_id, range) in chain.chain_ranges() {
+        for index in range.take_while(|&index| index < positions.len()) {
+            let name = &chain.names()[index];
+            let number = chain.ids()[index].0 + 1;
+            serial += 1;
+            write_atom_line(
+                &mut writer,
+                serial,
+                "CA",
+                name,
+                chain_id,
+                number,
+                positions[index],
+            )?;
+            if let Some(Some(bead)) = beads.get(index) {
+                serial += 1;
+                write_atom_line(
+                    &mut writer,
+                    serial,
+                    bead_atom,
+                    name,
+                    chain_id,
+                    number,
+                    *bead,
+                )?;
+            }
+        }
+        writeln!(writer, "TER")?;
+    }
     Ok(())
 }
 
 fn write_atom_line(
     mut writer: impl Write,
     serial: usize,
-    residue: &Residue,
+    atom: &str,
+    residue_name: &str,
+    chain_id: char,
+    residue_number: usize,
+    position: [f64; 3],
 ) -> std::io::Result<()> {
-    let position = residue.position();
-    // PDB ATOM format with only C-alpha atoms.
+    // PDB ATOM format; every bead is written as a carbon.
     writeln!(
         writer,
-        "ATOM  {:>5}  CA  {:>3} A{:>4}    {:>8.3}{:>8.3}{:>8.3}  1.00  0.00           C",
-        serial,
-        residue.name,
-        residue.id.0 + 1,
-        position[0],
-        position[1],
-        position[2],
+        "ATOM  {:>5}  {:<3} {:>3} {}{:>4}    {:>8.3}{:>8.3}{:>8.3}  1.00  0.00           C",
+        serial, atom, residue_name, chain_id, residue_number, position[0], position[1], position[2],
     )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use folding_molecule::MolecularSystem;
 
     #[test]
     fn parse_simple_fasta() {
@@ -180,6 +588,105 @@ mod tests {
         assert_eq!(seq.identifier.as_deref(), Some("demo"));
     }
 
+    #[test]
+    fn parse_ca_coordinates_reads_first_model_only() {
+        let contents = "\
+ATOM      1  N   ALA A   1      11.104   6.134  -6.504  1.00  0.00           N
+ATOM      2  CA  ALA A   1      11.639   6.071  -5.147  1.00  0.00           C
+ENDMDL
+ATOM      3  CA  ALA A   1       0.000   0.000   0.000  1.00  0.00           C
+";
+        let positions = parse_ca_coordinates(contents).unwrap();
+        assert_eq!(positions, vec![[11.639, 6.071, -5.147]]);
+    }
+
+    #[test]
+    fn load_pdb_keeps_names_chains_and_backbone_torsions() {
+        let contents = "\
+ATOM      1  N   MET A   1      -0.525   1.362   0.000  1.00  0.00           N
+ATOM      2  CA  MET A   1       0.000   0.000   0.000  1.00  0.00           C
+ATOM      3  C   MET A   1       1.520   0.000   0.000  1.00  0.00           C
+ATOM      4  N   GLY A   2       2.100   1.200   0.300  1.00  0.00           N
+ATOM      5  CA AGLY A   2       3.560   1.290   0.200  0.60  0.00           C
+ATOM      6  CA BGLY A   2       9.000   9.000   9.000  0.40  0.00           C
+ATOM      7  C   GLY A   2       4.100   2.700   0.100  1.00  0.00           C
+ATOM      8  N   SER A   3       5.400   2.900  -0.100  1.00  0.00           N
+ATOM      9  CA  SER A   3       6.000   4.200  -0.300  1.00  0.00           C
+HETATM   10  O   HOH A 101      20.000  20.000  20.000  1.00  0.00           O
+ATOM     11  CA  LYS B   1      30.000   0.000   0.000  1.00  0.00           C
+ENDMDL
+ATOM     12  CA  ALA A   1      50.000   0.000   0.000  1.00  0.00           C
+";
+        let structure = parse_pdb_structure(contents).unwrap();
+        assert_eq!(structure.chain.names(), ["MET", "GLY", "SER", "LYS"]);
+        assert_eq!(structure.chain_ids, ['A', 'A', 'A', 'B']);
+        assert_eq!(structure.chain_names(), ['A', 'B']);
+        assert_eq!(structure.chain.chain_breaks(), [3]);
+        assert_eq!(structure.chain.positions()[1], [3.56, 1.29, 0.2]);
+        assert!(structure.chain.phi()[1] != 0.0 && structure.chain.psi()[1] != 0.0);
+        assert!(parse_pdb_structure("HEADER    EMPTY\n").is_err());
+    }
+
+    #[test]
+    fn pdb_chains_interleaved_with_others_are_rejected() {
+        let contents = "\
+ATOM      1  CA  MET A   1       0.000   0.000   0.000  1.00  0.00           C
+ATOM      2  CA  LYS B   1      30.000   0.000   0.000  1.00  0.00           C
+ATOM      3  CA  GLY A   2       3.800   0.000   0.000  1.00  0.00           C
+";
+        let err = parse_pdb_structure(contents).unwrap_err();
+        assert_eq!(err, "chain A is split by another chain");
+    }
+
+    #[test]
+    fn parse_pdb_backbone_groups_atoms_by_residue() {
+        let contents = "\
+ATOM      1  N   ALA A   1      11.104   6.134  -6.504  1.00  0.00           N
+ATOM      2  CA  ALA A   1      11.639   6.071  -5.147  1.00  0.00           C
+ATOM      3  C   ALA A   1      13.140   5.837  -5.198  1.00  0.00           C
+ATOM      4  O   ALA A   1      13.700   5.000  -4.500  1.00  0.00           O
+ATOM      5  N   GLY A   2      13.785   6.580  -6.087  1.00  0.00           N
+";
+        let residues = parse_pdb_backbone(contents).unwrap();
+        assert_eq!(residues.len(), 2);
+        assert_eq!(residues[0].0, "ALA");
+        assert!(residues[0].1.c.is_some());
+        assert_eq!(residues[1].1.n, Some([13.785, 6.58, -6.087]));
+        assert!(residues[1].1.ca.is_none());
+    }
+
+    #[test]
+    fn replace_bfactors_assigns_values_per_residue() {
+        let contents = "\
+ATOM      1  N   ALA A   1      11.104   6.134  -6.504  1.00  0.00           N
+ATOM      2  CA  ALA A   1      11.639   6.071  -5.147  1.00  0.00           C
+ATOM      3  CA  GLY A   2      13.000   6.000  -4.000  1.00  0.00           C
+";
+        let rendered = replace_bfactors(contents, &[87.5, 12.25]);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(&lines[0][60..66], " 87.50");
+        assert_eq!(&lines[1][60..66], " 87.50");
+        assert_eq!(&lines[2][60..66], " 12.25");
+        assert!(lines[2].ends_with("C"));
+    }
+
+    #[test]
+    fn multi_model_pdb_round_trips_first_frame() {
+        let chain = PeptideChain::from_sequence("AC");
+        let frames = vec![
+            vec![[0.0, 0.0, 0.0], [3.8, 0.0, 0.0]],
+            vec![[0.0, 1.0, 0.0], [3.8, 1.0, 0.0]],
+        ];
+        let dir = std::env::temp_dir().join(format!("logline_models_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("trajectory.pdb");
+        write_multi_model_pdb(&chain, &frames, &path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.matches("ENDMDL").count(), 2);
+        assert_eq!(parse_ca_coordinates(&contents).unwrap(), frames[0]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn write_pdb_generates_atom_lines() {
         let seq = ProteinSequence {
@@ -195,4 +702,67 @@ mod tests {
         assert!(contents.contains("END"));
         let _ = fs::remove_file(path);
     }
+
+    #[test]
+    fn side_chain_beads_follow_their_alpha_carbons() {
+        let seq = ProteinSequence {
+            identifier: None,
+            sequence: "AGK".into(),
+        };
+        let chain = seq.to_chain().with_side_chains(SideChainBead::Beta);
+        let mut path = std::env::temp_dir();
+        path.push("test_write_side_chains.pdb");
+        write_pdb(&chain, &path, &seq).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let atoms: Vec<&str> = contents
+            .lines()
+            .filter(|line| line.starts_with("ATOM"))
+            .map(|line| line[12..16].trim())
+            .collect();
+        // Glycine has no bead
+        assert_eq!(atoms, ["CA", "CB", "CA", "CA", "CB"]);
+        let structure = parse_pdb_structure(&contents).unwrap();
+        assert_eq!(structure.chain.len(), 3);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn complexes_are_written_one_chain_per_identifier() {
+        let mut system = MolecularSystem::new();
+        system
+            .push('A', PeptideChain::from_sequence("ACD"))
+            .unwrap();
+        system.push('E', PeptideChain::from_sequence("KR")).unwrap();
+        let seq = ProteinSequence {
+            identifier: None,
+            sequence: String::new(),
+        };
+        let mut path = std::env::temp_dir();
+        path.push("test_write_complex.pdb");
+        write_pdb(&system.to_chain(), &path, &seq).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.matches("TER").count(), 2);
+        let structure = parse_pdb_structure(&contents).unwrap();
+        assert_eq!(structure.chain_ids, ['A', 'A', 'A', 'E', 'E']);
+        assert_eq!(structure.chain.chain_ranges(), [('A', 0..3), ('E', 3..5)]);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn backmapped_complexes_keep_their_chain_identifiers() {
+        let mut system = MolecularSystem::new();
+        system
+            .push('A', PeptideChain::from_sequence("ACD"))
+            .unwrap();
+        system.push('E', PeptideChain::from_sequence("KR")).unwrap();
+        let chain = system.to_chain();
+        let mut path = std::env::temp_dir();
+        path.push(format!("test_write_backbone_{}.pdb", std::process::id()));
+        write_backbone_pdb(&folding_core::backmap(&chain), &chain, &path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.matches("TER").count(), 2);
+        let structure = parse_pdb_structure(&contents).unwrap();
+        assert_eq!(structure.chain_ids, ['A', 'A', 'A', 'E', 'E']);
+        let _ = fs::remove_file(path);
+    }
 }
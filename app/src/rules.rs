@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use folding_core::{RecoveryPolicy, Ruleset};
+use folding_molecule::ResidueId;
+use serde::Deserialize;
+
+/// `--rules` file: every key is optional and overrides `Ruleset::default()`.
+///
+/// ```toml
+/// max_rotation_degrees = 30.0
+/// forbidden_residues = ["10-15", "40"]
+/// max_energy_increase = 5.0
+/// max_ghost_ratio = 0.25
+/// bond_distance = []          # empty disables the check
+/// bond_angle = [1.5, 2.5]
+/// ramachandran_tolerance = 10.0  # degrees outside the allowed regions
+/// max_omega_deviation = 30.0  # degrees from trans; cis X-Pro allowed
+///
+/// [recovery]                  # per rule code or kind; unlisted rules reject
+/// R001 = "halve:3"
+/// MetropolisRejected = "ghost"
+/// StructuralClash = "abort"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesFile {
+    pub max_rotation_degrees: Option<f64>,
+    #[serde(default)]
+    pub forbidden_residues: Vec<String>,
+    pub max_energy_increase: Option<f64>,
+    pub max_ghost_ratio: Option<f64>,
+    pub entropy_budget: Option<f64>,
+    pub information_budget: Option<f64>,
+    /// Clash distance (Å); `0` disables the clash check.
+    pub min_distance: Option<f64>,
+    pub bond_distance: Option<Vec<f64>>,
+    pub bond_angle: Option<Vec<f64>>,
+    pub ramachandran_tolerance: Option<f64>,
+    pub max_omega_deviation: Option<f64>,
+    #[serde(default)]
+    pub recovery: BTreeMap<String, String>,
+}
+
+impl RulesFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read rules {}: {err}", path.display()))?;
+        toml::from_str(&contents).map_err(|err| format!("invalid rules {}: {err}", path.display()))
+    }
+
+    pub fn to_ruleset(&self) -> Result<Ruleset, String> {
+        let mut ruleset = Ruleset::default();
+        if let Some(limit) = self.max_rotation_degrees {
+            ruleset = ruleset.with_rotation_limit(limit);
+        }
+        for raw in &self.forbidden_residues {
+            let (start, end) = parse_range(raw)?;
+            ruleset = ruleset.with_forbidden_range(ResidueId(start), ResidueId(end));
+        }
+        if let Some(limit) = self.max_energy_increase {
+            ruleset = ruleset.with_max_energy_increase(limit);
+        }
+        if let Some(limit) = self.max_ghost_ratio {
+            ruleset = ruleset.with_max_ghost_ratio(limit);
+        }
+        ruleset.entropy_budget = self.entropy_budget.or(ruleset.entropy_budget);
+        ruleset.information_budget = self.information_budget.or(ruleset.information_budget);
+        ruleset.ramachandran_tolerance = self
+            .ramachandran_tolerance
+            .or(ruleset.ramachandran_tolerance);
+        ruleset.max_omega_deviation = self.max_omega_deviation.or(ruleset.max_omega_deviation);
+        if let Some(distance) = self.min_distance {
+            ruleset.min_distance_angstrom = (distance > 0.0).then_some(distance);
+        }
+        if let Some(range) = self.bond_distance.as_deref() {
+            ruleset.bond_distance_range = parse_window("bond_distance", range)?;
+        }
+        if let Some(range) = self.bond_angle.as_deref() {
+            ruleset.bond_angle_range = parse_window("bond_angle", range)?;
+        }
+        for (rule, raw) in &self.recovery {
+            let policy = RecoveryPolicy::parse(raw).ok_or_else(|| {
+                format!("unknown recovery '{raw}' for {rule} (reject, halve[:N], ghost, abort)")
+            })?;
+            ruleset = ruleset.with_recovery(rule.clone(), policy);
+        }
+        Ok(ruleset)
+    }
+}
+
+/// Reads and converts a rules file; call `Ruleset::check` once the chain is known.
+pub fn load_ruleset(path: &Path) -> Result<Ruleset, String> {
+    RulesFile::load(path)?
+        .to_ruleset()
+        .map_err(|err| format!("invalid rules {}: {err}", path.display()))
+}
+
+/// `"12"` or an inclusive `"10-15"`.
+fn parse_range(raw: &str) -> Result<(usize, usize), String> {
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("invalid residue range '{raw}'"))
+    };
+    match raw.split_once('-') {
+        Some((start, end)) => Ok((parse(start)?, parse(end)?)),
+        None => parse(raw).map(|residue| (residue, residue)),
+    }
+}
+
+fn parse_window(key: &str, values: &[f64]) -> Result<Option<(f64, f64)>, String> {
+    match values {
+        [] => Ok(None),
+        [min, max] => Ok(Some((*min, *max))),
+        _ => Err(format!("{key} takes [] or [min, max]")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_file_overrides_defaults() {
+        let file: RulesFile = toml::from_str(
+            "max_rotation_degrees = 30.0\nforbidden_residues = [\"2-4\", \"7\"]\n\
+             max_ghost_ratio = 0.5\nmin_distance = 0\nbond_distance = []\n\
+             ramachandran_tolerance = 15\nmax_omega_deviation = 30\n\
+             [recovery]\nR001 = \"halve:2\"\n",
+        )
+        .unwrap();
+        let ruleset = file.to_ruleset().unwrap();
+        assert_eq!(ruleset.max_rotation_degrees, 30.0);
+        assert_eq!(
+            ruleset.forbidden_ranges,
+            vec![(ResidueId(2), ResidueId(4)), (ResidueId(7), ResidueId(7))]
+        );
+        assert_eq!(ruleset.max_ghost_ratio, Some(0.5));
+        assert_eq!(ruleset.ramachandran_tolerance, Some(15.0));
+        assert_eq!(ruleset.max_omega_deviation, Some(30.0));
+        assert_eq!(ruleset.min_distance_angstrom, None);
+        assert_eq!(ruleset.bond_distance_range, None);
+        assert_eq!(
+            ruleset.bond_angle_range,
+            Ruleset::default().bond_angle_range
+        );
+        assert_eq!(
+            ruleset.recovery,
+            vec![(
+                "R001".to_string(),
+                RecoveryPolicy::HalveAndRetry { attempts: 2 }
+            )]
+        );
+        assert!(ruleset.check(10).is_ok());
+        assert!(ruleset.check(5).is_err());
+
+        let bad: RulesFile = toml::from_str("forbidden_residues = [\"a-b\"]").unwrap();
+        assert!(bad.to_ruleset().is_err());
+        assert!(toml::from_str::<RulesFile>("max_rotation = 3.0").is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! `proptest` strategies for rotation commands, contract instructions and
+//! contracts (feature `proptest`). Residue indices always fall inside the
+//! chain a strategy is built for, and no instruction leaves the toy level, so
+//! generated contracts run end to end without a physics backend.
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use folding_molecule::arbitrary::chain_strategy;
+use folding_molecule::{Dihedral, PeptideChain, ResidueId};
+use proptest::prelude::*;
+use proptest::strategy::Union;
+
+use crate::folding_parser::{
+    ContractInstruction, DomainAxis, DomainMotion, DomainSelector, EnvironmentParameter,
+    FoldingContract,
+};
+use crate::physics_bridge::PhysicsEngine;
+use crate::restraints::SecondaryStructure;
+use crate::rotation_solver::RotationCommand;
+
+/// Chain length assumed by the `Arbitrary` impls in this module.
+pub const DEFAULT_CONTRACT_RESIDUES: usize = 16;
+
+/// Instruction counts drawn by `any::<FoldingContract>()`.
+pub const DEFAULT_CONTRACT_LENGTHS: RangeInclusive<usize> = 0..=24;
+
+fn angle() -> impl Strategy<Value = f64> + Clone {
+    -180.0..180.0f64
+}
+
+fn duration_ms() -> impl Strategy<Value = u64> + Clone {
+    0..20u64
+}
+
+/// Single-token names accepted wherever the grammar takes an identifier.
+fn name() -> impl Strategy<Value = String> + Clone {
+    "[a-z][a-z0-9_]{0,11}"
+}
+
+/// Rotations of any torsion of a residue in `0..residues`.
+pub fn rotation_command_for(residues: usize) -> impl Strategy<Value = RotationCommand> {
+    (
+        0..residues.max(1),
+        any::<Dihedral>(),
+        angle(),
+        duration_ms(),
+        prop::option::of(name()),
+    )
+        .prop_map(
+            |(residue, dihedral, angle_degrees, duration, label)| RotationCommand {
+                residue: ResidueId(residue),
+                dihedral,
+                angle_degrees,
+                duration: Duration::from_millis(duration),
+                label,
+            },
+        )
+}
+
+/// Any toy-level instruction that is valid for a chain of `residues`.
+pub fn instruction_for(residues: usize) -> impl Strategy<Value = ContractInstruction> {
+    let last = residues.max(1) - 1;
+    let range = (0..=last, 0..=last).prop_map(|(a, b)| (ResidueId(a.min(b)), ResidueId(a.max(b))));
+    let mut options: Vec<(u32, BoxedStrategy<ContractInstruction>)> = vec![
+        (
+            6,
+            (0..=last, any::<Dihedral>(), angle(), duration_ms())
+                .prop_map(|(residue, dihedral, angle_degrees, duration_ms)| {
+                    ContractInstruction::Rotate {
+                        residue: ResidueId(residue),
+                        dihedral,
+                        angle_degrees,
+                        duration_ms,
+                    }
+                })
+                .boxed(),
+        ),
+        (
+            1,
+            (range.clone(), -5.0..5.0f64, duration_ms())
+                .prop_map(
+                    |((start, end), shift, duration_ms)| ContractInstruction::MoveDomain {
+                        domain: DomainSelector::Range { start, end },
+                        motion: DomainMotion::Rotate {
+                            angle_degrees: shift,
+                            axis: DomainAxis::Z,
+                        },
+                        duration_ms,
+                    },
+                )
+                .boxed(),
+        ),
+        (
+            1,
+            (range.clone(), prop::option::of(name()))
+                .prop_map(|((start, end), name)| ContractInstruction::DefineDomain {
+                    name,
+                    start,
+                    end,
+                })
+                .boxed(),
+        ),
+        (
+            1,
+            range
+                .clone()
+                .prop_map(|(start, end)| ContractInstruction::Freeze { start, end })
+                .boxed(),
+        ),
+        (
+            1,
+            (range.clone(), any::<bool>(), 0.0..50.0f64)
+                .prop_map(
+                    |((start, end), helix, force_constant)| ContractInstruction::Restrain {
+                        start,
+                        end,
+                        structure: if helix {
+                            SecondaryStructure::Helix
+                        } else {
+                            SecondaryStructure::Sheet
+                        },
+                        force_constant,
+                    },
+                )
+                .boxed(),
+        ),
+        (
+            1,
+            (
+                0..=last,
+                prop::sample::select(vec!["phosphorylation", "acetylation"]),
+            )
+                .prop_map(
+                    |(residue, modification)| ContractInstruction::AddModification {
+                        modification: modification.to_string(),
+                        residue: ResidueId(residue),
+                    },
+                )
+                .boxed(),
+        ),
+        (
+            1,
+            (
+                0..=last,
+                prop::option::of(prop::sample::select(vec!["m", "t", "mt", "m-85"])),
+                duration_ms(),
+            )
+                .prop_map(
+                    |(residue, rotamer, duration_ms)| ContractInstruction::RotamerFlip {
+                        residue: ResidueId(residue),
+                        rotamer: rotamer.map(str::to_string),
+                        duration_ms,
+                    },
+                )
+                .boxed(),
+        ),
+        (
+            1,
+            (name(), prop::option::of(name()))
+                .prop_map(|(chaperone, span)| ContractInstruction::RequireChaperone {
+                    chaperone,
+                    span,
+                })
+                .boxed(),
+        ),
+        (
+            3,
+            prop_oneof![
+                Just(ContractInstruction::ClashCheck),
+                Just(ContractInstruction::Commit),
+                Just(ContractInstruction::Rollback),
+                any::<bool>().prop_map(ContractInstruction::GhostMode),
+                name().prop_map(ContractInstruction::SpanAlias),
+                prop::collection::vec(name(), 1..3).prop_map(ContractInstruction::SpanTags),
+                name().prop_map(ContractInstruction::BeginPhase),
+                Just(ContractInstruction::EndPhase),
+                prop::sample::select(vec!["coarse-grained", "amber99sb"])
+                    .prop_map(|name| ContractInstruction::SetForceField(name.to_string())),
+                prop::sample::select(vec!["verlet", "langevin", "brownian"])
+                    .prop_map(|name| ContractInstruction::SetIntegrator(name.to_string())),
+                prop::sample::select(vec![
+                    PhysicsEngine::OpenMM,
+                    PhysicsEngine::Native,
+                    PhysicsEngine::Auto,
+                ])
+                .prop_map(ContractInstruction::SetPhysicsEngine),
+                prop::option::of(prop::sample::select(vec![0.001, 0.002, 0.005]))
+                    .prop_map(ContractInstruction::SetTimestep),
+                (1.0..2000.0f64).prop_map(ContractInstruction::SetTemperature),
+                prop::option::of(1.0..2000.0f64)
+                    .prop_map(ContractInstruction::SetAcceptanceTemperature),
+                prop::sample::select(vec!["aqueous", "cytosol", "vacuum"])
+                    .prop_map(|name| ContractInstruction::SetEnvironment(name.to_string())),
+                (0.0..0.5f64).prop_map(|value| ContractInstruction::SetEnvironmentParameter {
+                    parameter: EnvironmentParameter::IonicStrength,
+                    value,
+                }),
+                (1.0..80.0f64, 1.0..80.0f64, 0..100usize).prop_map(|(from, to, steps)| {
+                    ContractInstruction::RampEnvironment {
+                        parameter: EnvironmentParameter::Dielectric,
+                        from,
+                        to,
+                        steps,
+                    }
+                }),
+            ]
+            .boxed(),
+        ),
+    ];
+    // A one-residue segment reads back as a plain `rotate`.
+    if residues >= 2 {
+        options.push((
+            2,
+            (0..last, any::<Dihedral>(), angle(), duration_ms())
+                .prop_flat_map(move |(start, dihedral, angle_degrees, duration_ms)| {
+                    (start + 1..=last).prop_map(move |end| ContractInstruction::RotateSegment {
+                        start: ResidueId(start),
+                        end: ResidueId(end),
+                        dihedral,
+                        angle_degrees,
+                        duration_ms,
+                    })
+                })
+                .boxed(),
+        ));
+    }
+    if residues >= 3 {
+        options.push((
+            1,
+            (1..last, prop::option::of(-10.0..10.0f64), duration_ms())
+                .prop_map(
+                    |(residue, angle_degrees, duration_ms)| ContractInstruction::Backrub {
+                        residue: ResidueId(residue),
+                        angle_degrees,
+                        duration_ms,
+                    },
+                )
+                .boxed(),
+        ));
+        options.push((
+            1,
+            (0..last - 1, -30.0..30.0f64, duration_ms())
+                .prop_flat_map(move |(start, perturbation, duration)| {
+                    (start + 2..=last).prop_map(move |end| ContractInstruction::CloseLoop {
+                        start: ResidueId(start),
+                        end: ResidueId(end),
+                        perturbation_degrees: perturbation,
+                        duration_ms: duration,
+                    })
+                })
+                .boxed(),
+        ));
+    }
+    Union::new_weighted(options)
+}
+
+/// Contracts of `lengths` instructions for a chain of `residues`.
+pub fn contract_for(
+    residues: usize,
+    lengths: RangeInclusive<usize>,
+) -> impl Strategy<Value = FoldingContract> {
+    prop::collection::vec(instruction_for(residues), lengths).prop_map(|instructions| {
+        // An `end_phase` with no phase open would not parse back
+        let mut open_phases = 0usize;
+        let instructions = instructions
+            .into_iter()
+            .filter(|instruction| match instruction {
+                ContractInstruction::BeginPhase(_) => {
+                    open_phases += 1;
+                    true
+                }
+                ContractInstruction::EndPhase if open_phases == 0 => false,
+                ContractInstruction::EndPhase => {
+                    open_phases -= 1;
+                    true
+                }
+                _ => true,
+            })
+            .collect();
+        FoldingContract::new(instructions)
+    })
+}
+
+/// A chain with a length in `residues` and a contract written for it.
+pub fn chain_and_contract(
+    residues: RangeInclusive<usize>,
+    lengths: RangeInclusive<usize>,
+) -> impl Strategy<Value = (PeptideChain, FoldingContract)> {
+    chain_strategy(residues).prop_flat_map(move |chain| {
+        let contract = contract_for(chain.len(), lengths.clone());
+        (Just(chain), contract)
+    })
+}
+
+impl Arbitrary for RotationCommand {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        rotation_command_for(DEFAULT_CONTRACT_RESIDUES).boxed()
+    }
+}
+
+impl Arbitrary for ContractInstruction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        instruction_for(DEFAULT_CONTRACT_RESIDUES).boxed()
+    }
+}
+
+impl Arbitrary for FoldingContract {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        contract_for(DEFAULT_CONTRACT_RESIDUES, DEFAULT_CONTRACT_LENGTHS).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::folding_runtime::FoldingEngineBuilder;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(48))]
+
+        #[test]
+        fn contracts_round_trip_through_lll(contract in any::<FoldingContract>()) {
+            let rendered = contract.to_lll();
+            let lines: Vec<&str> = rendered.lines().collect();
+            let reparsed = FoldingContract::from_lines(&lines).unwrap();
+            prop_assert_eq!(reparsed.instructions.len(), contract.instructions.len());
+            prop_assert_eq!(reparsed.to_lll(), rendered);
+        }
+
+        #[test]
+        fn engine_runs_generated_contracts(
+            (chain, contract) in chain_and_contract(1..=12, 0..=10),
+            seed in any::<u64>(),
+        ) {
+            let residues = chain.len();
+            let mut engine = FoldingEngineBuilder::new()
+                .with_chain(chain)
+                .with_rng_seed(seed)
+                .build();
+            let report = engine.execute_contract(&contract);
+            prop_assert_eq!(report.final_chain.len(), residues);
+            prop_assert!(report.applied_rotations.len() <= contract.move_count());
+        }
+    }
+}
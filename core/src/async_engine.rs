@@ -0,0 +1,187 @@
+//! Runs contracts on tokio's blocking pool (feature `async`), so services can
+//! fold many chains concurrently without stalling their async workers. Each
+//! run reports progress over a channel and stops at the next move once its
+//! [`CancellationToken`] is cancelled.
+//!
+//! ```no_run
+//! # async fn fold(builder: folding_core::FoldingEngineBuilder, contract: folding_core::FoldingContract) {
+//! use folding_core::async_engine::{FoldProgress, spawn_fold};
+//! use tokio_util::sync::CancellationToken;
+//!
+//! let mut task = spawn_fold(builder, contract, CancellationToken::new());
+//! while let Some(event) = task.progress.recv().await {
+//!     if let FoldProgress::SpanApplied { step, span_id, .. } = event {
+//!         println!("{step}: {span_id}");
+//!     }
+//! }
+//! let report = task.report().await.unwrap();
+//! # }
+//! ```
+
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::folding_parser::FoldingContract;
+use crate::folding_ruleset::ViolationRecord;
+use crate::folding_runtime::{ExecutionReport, FoldingEngineBuilder};
+use crate::observer::{EngineObserver, ObserverControl};
+use crate::rotation_solver::RotationOutcome;
+
+/// Event sent while a spawned contract runs.
+#[derive(Clone, Debug)]
+pub enum FoldProgress {
+    SpanApplied {
+        step: usize,
+        span_id: String,
+        potential_energy: f64,
+    },
+    Rejected(ViolationRecord),
+    /// The run stopped early because its token was cancelled.
+    Cancelled {
+        step: usize,
+    },
+}
+
+/// A contract running on the blocking pool.
+pub struct FoldTask {
+    /// Closes once the run is over.
+    pub progress: UnboundedReceiver<FoldProgress>,
+    cancel: CancellationToken,
+    handle: JoinHandle<ExecutionReport>,
+}
+
+impl FoldTask {
+    /// Stops the run before its next move; the report covers the moves made.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Waits for the run; cancelled runs still return their partial report.
+    pub async fn report(self) -> Result<ExecutionReport, String> {
+        self.handle
+            .await
+            .map_err(|err| format!("fold task failed: {err}"))
+    }
+}
+
+/// Builds the engine and runs `contract` with `spawn_blocking`. Must be
+/// called inside a tokio runtime. Observers already registered on `builder`
+/// keep working alongside the progress channel.
+pub fn spawn_fold(
+    builder: FoldingEngineBuilder,
+    contract: FoldingContract,
+    cancel: CancellationToken,
+) -> FoldTask {
+    let (sender, progress) = unbounded_channel();
+    let observer = ProgressObserver {
+        sender,
+        cancel: cancel.clone(),
+        pending: None,
+    };
+    let handle = tokio::task::spawn_blocking(move || {
+        let mut engine = builder.with_observer(observer).build();
+        engine.execute_contract(&contract)
+    });
+    FoldTask {
+        progress,
+        cancel,
+        handle,
+    }
+}
+
+struct ProgressObserver {
+    sender: UnboundedSender<FoldProgress>,
+    cancel: CancellationToken,
+    /// Span id held until its energy arrives.
+    pending: Option<String>,
+}
+
+impl EngineObserver for ProgressObserver {
+    fn on_span_applied(&mut self, _step: usize, outcome: &RotationOutcome) {
+        self.pending = Some(outcome.span_record.id.as_str().to_string());
+    }
+
+    fn on_rejection(&mut self, record: &ViolationRecord) {
+        // A dropped receiver only means nobody is listening any more.
+        let _ = self.sender.send(FoldProgress::Rejected(record.clone()));
+    }
+
+    fn on_energy(&mut self, step: usize, potential_energy: f64) -> ObserverControl {
+        if let Some(span_id) = self.pending.take() {
+            let _ = self.sender.send(FoldProgress::SpanApplied {
+                step,
+                span_id,
+                potential_energy,
+            });
+        }
+        ObserverControl::Continue
+    }
+
+    fn before_move(&mut self, step: usize) -> ObserverControl {
+        if self.cancel.is_cancelled() {
+            let _ = self.sender.send(FoldProgress::Cancelled { step });
+            ObserverControl::Stop
+        } else {
+            ObserverControl::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::micro_oscillator::MicroOscillator;
+    use crate::test_support::permissive_ruleset;
+    use folding_molecule::PeptideChain;
+
+    fn builder() -> FoldingEngineBuilder {
+        FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGSVLKTR"))
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_ruleset(permissive_ruleset())
+            .with_temperature(1.0e6)
+            .with_rng_seed(5)
+    }
+
+    #[test]
+    fn concurrent_folds_report_progress_and_honour_cancellation() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let contract =
+                FoldingContract::parse("rotate 2 10\nrotate 3 -5\nrotate 4 8\n").unwrap();
+            let mut running = spawn_fold(builder(), contract.clone(), CancellationToken::new());
+            let cancelled_token = CancellationToken::new();
+            cancelled_token.cancel();
+            let mut cancelled = spawn_fold(builder(), contract, cancelled_token);
+
+            let mut applied = 0;
+            while let Some(event) = running.progress.recv().await {
+                if matches!(event, FoldProgress::SpanApplied { .. }) {
+                    applied += 1;
+                }
+            }
+            let report = running.report().await.unwrap();
+            assert_eq!(applied, report.applied_rotations.len());
+            assert!(applied > 0);
+
+            assert!(matches!(
+                cancelled.progress.recv().await,
+                Some(FoldProgress::Cancelled { step: 0 })
+            ));
+            let report = cancelled.report().await.unwrap();
+            assert!(report.applied_rotations.is_empty());
+            assert_eq!(report.stopped_by_observer, Some(0));
+        });
+    }
+}
@@ -0,0 +1,344 @@
+use folding_molecule::PeptideChain;
+
+use crate::target_contract::{BackboneAtoms, backbone_dihedrals};
+
+/// Ideal trans peptide unit in the frame of one Cα→Cα virtual bond: `x` runs
+/// along the bond, `y` lies in the peptide plane. Carbonyl C and O hang off
+/// the first Cα, the amide N off the second, so bond lengths stay ideal even
+/// when the virtual bond is not exactly 3.80 Å.
+const CARBONYL_C: [f64; 2] = [1.4284, -0.5341];
+const CARBONYL_O: [f64; 2] = [0.2138, -1.2123];
+const AMIDE_N: [f64; 2] = [-1.4084, 0.3774];
+
+/// Virtual bond used to extend the trace past its termini.
+const VIRTUAL_BOND: f64 = 3.8;
+
+/// Heavy backbone atoms (and Cβ, except for glycine) rebuilt around one Cα.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackmappedResidue {
+    pub name: String,
+    pub n: [f64; 3],
+    pub ca: [f64; 3],
+    pub c: [f64; 3],
+    pub o: [f64; 3],
+    pub cb: Option<[f64; 3]>,
+}
+
+impl BackmappedResidue {
+    pub fn backbone(&self) -> BackboneAtoms {
+        BackboneAtoms {
+            n: Some(self.n),
+            ca: Some(self.ca),
+            c: Some(self.c),
+        }
+    }
+
+    /// Signed volume (N−Cα)·((C−Cα)×(Cβ−Cα)) in Å³: about +2.5 for an L
+    /// residue, negative for a D inversion; `None` without a Cβ.
+    pub fn chiral_volume(&self) -> Option<f64> {
+        let cb = self.cb?;
+        let (n, c, cb) = (sub(self.n, self.ca), sub(self.c, self.ca), sub(cb, self.ca));
+        Some(dot(n, cross(c, cb)))
+    }
+
+    /// Puts an inverted Cβ back on the L side; true when it had to move.
+    pub fn restore_chirality(&mut self) -> bool {
+        if self.chiral_volume().is_none_or(|volume| volume > 0.0) {
+            return false;
+        }
+        self.cb = Some(ideal_cb(self.n, self.ca, self.c));
+        true
+    }
+}
+
+/// Rebuilds a full heavy-atom backbone from the Cα trace of `chain`: every
+/// virtual bond gets an ideal trans peptide plane, oriented away from the
+/// local bend of the trace, and non-glycine residues get an ideal Cβ. The
+/// result feeds GB/Full refinement or external tools. Each chain of a
+/// complex is rebuilt on its own, so its termini get their own virtual Cα
+/// instead of a peptide plane bridging the chain break.
+pub fn backmap(chain: &PeptideChain) -> Vec<BackmappedResidue> {
+    chain
+        .chain_ranges()
+        .into_iter()
+        .flat_map(|(_, range)| {
+            backmap_trace(&chain.positions()[range.clone()], &chain.names()[range])
+        })
+        .collect()
+}
+
+/// Moves `chain` onto the Cα `positions` and re-derives phi and psi from the
+/// backbone rebuilt around them, so later torsion moves and rules see the new
+/// conformation. Torsions reaching past a terminus or across a chain break
+/// keep their values.
+pub fn place_ca_trace(chain: &mut PeptideChain, positions: &[[f64; 3]]) -> Result<(), String> {
+    if positions.len() != chain.len() {
+        return Err(format!(
+            "{} Cα positions given for a chain of {} residues",
+            positions.len(),
+            chain.len()
+        ));
+    }
+    chain.positions_mut().copy_from_slice(positions);
+    let backbone: Vec<BackboneAtoms> = backmap(chain)
+        .iter()
+        .map(BackmappedResidue::backbone)
+        .collect();
+    for (index, torsions) in backbone_dihedrals(&backbone).into_iter().enumerate() {
+        if let Some(phi) = torsions
+            .phi
+            .filter(|_| index > 0 && chain.is_bonded(index - 1))
+        {
+            chain.phi_mut()[index] = phi;
+        }
+        if let Some(psi) = torsions.psi.filter(|_| chain.is_bonded(index)) {
+            chain.psi_mut()[index] = psi;
+        }
+    }
+    Ok(())
+}
+
+/// Backmaps one unbroken Cα trace whose residues are named `names`.
+fn backmap_trace(trace: &[[f64; 3]], names: &[String]) -> Vec<BackmappedResidue> {
+    let Some((&first, &last)) = trace.first().zip(trace.last()) else {
+        return Vec::new();
+    };
+    // Straight virtual Cα beyond each terminus so the end residues get an N and a C.
+    let (before, after) = match (trace.get(1), trace.len().checked_sub(2).map(|i| trace[i])) {
+        (Some(&second), Some(penultimate)) => (
+            sub(first, scale(unit_or_x(sub(second, first)), VIRTUAL_BOND)),
+            add(last, scale(unit_or_x(sub(last, penultimate)), VIRTUAL_BOND)),
+        ),
+        _ => (
+            sub(first, [VIRTUAL_BOND, 0.0, 0.0]),
+            add(last, [VIRTUAL_BOND, 0.0, 0.0]),
+        ),
+    };
+    let mut extended = Vec::with_capacity(trace.len() + 2);
+    extended.push(before);
+    extended.extend_from_slice(trace);
+    extended.push(after);
+
+    // Plane k joins extended[k] and extended[k + 1]: it carries the C and O
+    // of residue k - 1 and the N of residue k.
+    let mut carbonyls = Vec::with_capacity(trace.len());
+    let mut amides = Vec::with_capacity(trace.len());
+    for k in 0..extended.len() - 1 {
+        let (x, y) = peptide_frame(&extended, k);
+        let from = extended[k];
+        let to = extended[k + 1];
+        let c = add(from, planar(x, y, CARBONYL_C));
+        let o = add(c, planar(x, y, CARBONYL_O));
+        if k > 0 {
+            carbonyls.push((c, o));
+        }
+        if k + 1 < extended.len() - 1 {
+            amides.push(add(to, planar(x, y, AMIDE_N)));
+        }
+    }
+
+    names
+        .iter()
+        .zip(trace.iter().copied())
+        .zip(amides.into_iter().zip(carbonyls))
+        .map(|((name, ca), (n, (c, o)))| BackmappedResidue {
+            name: name.clone(),
+            n,
+            ca,
+            c,
+            o,
+            cb: (!name.eq_ignore_ascii_case("GLY")).then(|| ideal_cb(n, ca, c)),
+        })
+        .collect()
+}
+
+/// Axes of the peptide plane on virtual bond `k`: `x` along the bond, `y`
+/// pointing away from the neighbouring Cα on either side.
+fn peptide_frame(trace: &[[f64; 3]], k: usize) -> ([f64; 3], [f64; 3]) {
+    let x = unit_or_x(sub(trace[k + 1], trace[k]));
+    let mut bend = [0.0; 3];
+    if let Some(previous) = k.checked_sub(1).map(|i| trace[i]) {
+        bend = add(bend, sub(previous, trace[k]));
+    }
+    if let Some(&next) = trace.get(k + 2) {
+        bend = add(bend, sub(next, trace[k + 1]));
+    }
+    let rejected = sub(bend, scale(x, dot(bend, x)));
+    let y = match unit(rejected) {
+        Some(inward) => scale(inward, -1.0),
+        None => perpendicular(x),
+    };
+    (x, y)
+}
+
+/// Cβ 1.53 Å from Cα, leaning away from N and C and out of their plane on
+/// the L-amino-acid side (about 52° above the N–Cα–C plane).
+fn ideal_cb(n: [f64; 3], ca: [f64; 3], c: [f64; 3]) -> [f64; 3] {
+    let from_n = unit_or_x(sub(ca, n));
+    let from_c = unit_or_x(sub(ca, c));
+    let away = match unit(add(from_n, from_c)) {
+        Some(away) => away,
+        None => perpendicular(from_n),
+    };
+    let normal = match unit(cross(sub(c, ca), from_n)) {
+        Some(normal) => normal,
+        None => perpendicular(away),
+    };
+    add(ca, scale(add(scale(away, 0.612), scale(normal, 0.791)), 1.53))
+}
+
+fn planar(x: [f64; 3], y: [f64; 3], coordinates: [f64; 2]) -> [f64; 3] {
+    add(scale(x, coordinates[0]), scale(y, coordinates[1]))
+}
+
+fn perpendicular(x: [f64; 3]) -> [f64; 3] {
+    let helper = if x[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    unit_or_x(cross(x, helper))
+}
+
+fn unit(v: [f64; 3]) -> Option<[f64; 3]> {
+    let length = dot(v, v).sqrt();
+    (length > 1e-9).then(|| scale(v, 1.0 / length))
+}
+
+fn unit_or_x(v: [f64; 3]) -> [f64; 3] {
+    unit(v).unwrap_or([1.0, 0.0, 0.0])
+}
+
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(v: [f64; 3], factor: f64) -> [f64; 3] {
+    [v[0] * factor, v[1] * factor, v[2] * factor]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target_contract::dihedral_degrees;
+    use folding_molecule::{Dihedral, Residue, ResidueId};
+
+    fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+        dot(sub(a, b), sub(a, b)).sqrt()
+    }
+
+    #[test]
+    fn backmapped_backbone_has_ideal_bonds_and_trans_peptides() {
+        // α-helical Cα trace: 2.3 Å radius, 100° and 1.5 Å rise per residue.
+        let names = ["MET", "GLY", "ALA", "LYS", "GLU", "LEU", "SER"];
+        let chain = PeptideChain::new(
+            names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let angle = (100.0 * i as f64).to_radians();
+                    let position = [2.3 * angle.cos(), 2.3 * angle.sin(), 1.5 * i as f64];
+                    Residue::new(ResidueId(i), *name, position)
+                })
+                .collect(),
+        );
+        let model = backmap(&chain);
+        assert_eq!(model.len(), names.len());
+        for (i, residue) in model.iter().enumerate() {
+            assert!((distance(residue.n, residue.ca) - 1.458).abs() < 1e-3);
+            assert!((distance(residue.ca, residue.c) - 1.525).abs() < 1e-3);
+            assert!((distance(residue.c, residue.o) - 1.231).abs() < 1e-3);
+            assert_eq!(residue.cb.is_none(), names[i] == "GLY");
+            if let Some(cb) = residue.cb {
+                assert!((distance(residue.ca, cb) - 1.53).abs() < 0.02);
+            }
+        }
+        for pair in model.windows(2) {
+            let omega = dihedral_degrees(pair[0].ca, pair[0].c, pair[1].n, pair[1].ca);
+            assert!(omega.abs() > 179.0, "omega {omega}");
+            assert!((distance(pair[0].c, pair[1].n) - 1.329).abs() < 0.05);
+        }
+        let backbone: Vec<BackboneAtoms> = model.iter().map(|r| r.backbone()).collect();
+        let torsions = backbone_dihedrals(&backbone);
+        assert!(
+            torsions[1..6]
+                .iter()
+                .all(|t| t.phi.is_some() && t.psi.is_some())
+        );
+
+        let mut residue = model[2].clone();
+        let volume = residue.chiral_volume().unwrap();
+        assert!((1.5..3.5).contains(&volume), "volume {volume}");
+        // Cβ reflected through Cα: the D form.
+        residue.cb = residue.cb.map(|cb| sub(scale(residue.ca, 2.0), cb));
+        assert!(residue.chiral_volume().unwrap() < 0.0);
+        assert!(residue.restore_chirality());
+        assert_eq!(residue, model[2]);
+        assert!(!model[1].clone().restore_chirality());
+    }
+
+    #[test]
+    fn chains_of_a_complex_are_backmapped_separately() {
+        let mut chain = PeptideChain::from_sequence("AGSVLK");
+        for (index, position) in chain.positions_mut().iter_mut().enumerate() {
+            // Two strands side by side, the second running back along y = 10
+            *position = match index {
+                0..3 => [3.8 * index as f64, 0.0, 0.0],
+                _ => [3.8 * (5 - index) as f64, 10.0, 0.0],
+            };
+        }
+        chain.set_chain_breaks(&[3]);
+        let model = backmap(&chain);
+        assert_eq!(model.len(), 6);
+
+        let mut first = PeptideChain::from_sequence("AGS");
+        first
+            .positions_mut()
+            .copy_from_slice(&chain.positions()[..3]);
+        assert_eq!(model[..3], backmap(&first)[..]);
+        // The chain A terminus extends its own strand, not towards chain B
+        assert!(model[2].c[0] > model[2].ca[0]);
+        assert!(model[3].n[0] > model[3].ca[0]);
+    }
+
+    #[test]
+    fn placed_traces_carry_the_torsions_of_their_backbone() {
+        let mut source = PeptideChain::from_sequence("AGSVLK");
+        source.rotate_torsion(ResidueId(2), Dihedral::Psi, 50.0);
+        let torsions = backbone_dihedrals(
+            &backmap(&source)
+                .iter()
+                .map(BackmappedResidue::backbone)
+                .collect::<Vec<_>>(),
+        );
+
+        let mut chain = PeptideChain::from_sequence("AGSVLK");
+        place_ca_trace(&mut chain, source.positions()).unwrap();
+        assert_eq!(chain.positions(), source.positions());
+        for (index, torsion) in torsions.iter().enumerate().take(5).skip(1) {
+            assert_eq!(Some(chain.phi()[index]), torsion.phi);
+            assert_eq!(Some(chain.psi()[index]), torsion.psi);
+        }
+        // No residue before the first one to measure its phi against
+        assert_eq!(chain.phi()[0], source.phi()[0]);
+
+        let err = place_ca_trace(&mut chain, &source.positions()[..4]).unwrap_err();
+        assert_eq!(err, "4 Cα positions given for a chain of 6 residues");
+    }
+}
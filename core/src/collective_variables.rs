@@ -0,0 +1,156 @@
+//! Collective variables requested per run with
+//! [`FoldingEngineBuilder::with_collective_variable`](crate::FoldingEngineBuilder::with_collective_variable).
+//! The engine evaluates them after every accepted span and the log writer
+//! appends them to span lines as `cv.<name>=<value>`.
+
+use folding_molecule::{Dihedral, PeptideChain, ResidueId};
+
+use crate::scoring::CONTACT_CUTOFF;
+
+/// Names accepted by [`CollectiveVariable::parse`], with their arguments.
+pub const COLLECTIVE_VARIABLES: &[(&str, &str)] = &[
+    ("end_to_end", "first-to-last Cα distance (Å)"),
+    ("rg", "radius of gyration (Å)"),
+    ("contacts[:cutoff]", "Cα pairs at least three apart within the cutoff (Å)"),
+    ("distance:<i>:<j>", "Cα distance between residues i and j (Å)"),
+    ("<dihedral>:<i>", "phi, psi, omega or chi1-4 of residue i (degrees)"),
+];
+
+/// Chain observable tracked along a run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CollectiveVariable {
+    EndToEnd,
+    RadiusOfGyration,
+    /// Cα pairs at least three residues apart closer than the cutoff (Å).
+    Contacts(f64),
+    Distance(ResidueId, ResidueId),
+    Torsion(ResidueId, Dihedral),
+}
+
+impl CollectiveVariable {
+    /// Parses a name from [`COLLECTIVE_VARIABLES`], e.g. `rg`, `contacts:6.5`,
+    /// `distance:0:41` or `phi:12`.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let raw = raw.trim();
+        let mut parts = raw.split(':');
+        let head = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let args: Vec<&str> = parts.collect();
+        let residue = |value: &str| {
+            value
+                .parse::<usize>()
+                .map(ResidueId)
+                .map_err(|_| format!("invalid residue '{value}' in collective variable '{raw}'"))
+        };
+        let variable = match (head.as_str(), args.as_slice()) {
+            ("end_to_end" | "end-to-end", []) => Self::EndToEnd,
+            ("rg" | "radius_of_gyration", []) => Self::RadiusOfGyration,
+            ("contacts", []) => Self::Contacts(CONTACT_CUTOFF),
+            ("contacts", [cutoff]) => match cutoff.parse::<f64>() {
+                Ok(cutoff) if cutoff.is_finite() && cutoff > 0.0 => Self::Contacts(cutoff),
+                _ => return Err(format!("invalid contact cutoff in '{raw}'")),
+            },
+            ("distance", [from, to]) => Self::Distance(residue(from)?, residue(to)?),
+            (name, [index]) => match Dihedral::parse(name) {
+                Some(dihedral) => Self::Torsion(residue(index)?, dihedral),
+                None => return Err(format!("unknown collective variable '{raw}'")),
+            },
+            _ => return Err(format!("unknown collective variable '{raw}'")),
+        };
+        Ok(variable)
+    }
+
+    /// Canonical name, as written in span logs; parses back to `self`.
+    pub fn name(&self) -> String {
+        match self {
+            Self::EndToEnd => "end_to_end".into(),
+            Self::RadiusOfGyration => "rg".into(),
+            Self::Contacts(cutoff) if *cutoff == CONTACT_CUTOFF => "contacts".into(),
+            Self::Contacts(cutoff) => format!("contacts:{cutoff}"),
+            Self::Distance(from, to) => format!("distance:{}:{}", from.0, to.0),
+            Self::Torsion(residue, dihedral) => format!("{}:{}", dihedral.as_str(), residue.0),
+        }
+    }
+
+    /// Value on `chain`; NaN when a residue it names is missing.
+    pub fn evaluate(&self, chain: &PeptideChain) -> f64 {
+        let positions = chain.positions();
+        match self {
+            Self::EndToEnd => end_to_end(positions),
+            Self::RadiusOfGyration => radius_of_gyration(positions),
+            Self::Contacts(cutoff) => contacts(positions, *cutoff) as f64,
+            Self::Distance(from, to) => match (chain.residue(*from), chain.residue(*to)) {
+                (Some(from), Some(to)) => distance(&from.position(), &to.position()),
+                _ => f64::NAN,
+            },
+            Self::Torsion(residue, dihedral) => chain
+                .residue(*residue)
+                .map_or(f64::NAN, |residue| residue.torsion(*dihedral)),
+        }
+    }
+}
+
+pub(crate) fn radius_of_gyration(positions: &[[f64; 3]]) -> f64 {
+    let count = positions.len().max(1) as f64;
+    let mut centroid = [0.0; 3];
+    for position in positions {
+        for axis in 0..3 {
+            centroid[axis] += position[axis] / count;
+        }
+    }
+    (positions
+        .iter()
+        .map(|p| distance(p, &centroid).powi(2))
+        .sum::<f64>()
+        / count)
+        .sqrt()
+}
+
+pub(crate) fn end_to_end(positions: &[[f64; 3]]) -> f64 {
+    match (positions.first(), positions.last()) {
+        (Some(first), Some(last)) => distance(first, last),
+        _ => 0.0,
+    }
+}
+
+pub(crate) fn contacts(positions: &[[f64; 3]], cutoff: f64) -> usize {
+    let mut contacts = 0;
+    for i in 0..positions.len() {
+        for j in i + 3..positions.len() {
+            if distance(&positions[i], &positions[j]) < cutoff {
+                contacts += 1;
+            }
+        }
+    }
+    contacts
+}
+
+fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip_and_evaluate_on_a_chain() {
+        let mut chain = PeptideChain::from_sequence("AGSVLK");
+        chain.phi_mut()[2] = -63.0;
+        for raw in ["end_to_end", "rg", "contacts", "contacts:6.5", "distance:0:5", "phi:2"] {
+            let variable = CollectiveVariable::parse(raw).unwrap();
+            assert_eq!(variable.name(), raw);
+            assert!(variable.evaluate(&chain).is_finite(), "{raw}");
+        }
+        let torsion = CollectiveVariable::parse("PHI:2").unwrap();
+        assert_eq!(torsion.evaluate(&chain), -63.0);
+        let span = CollectiveVariable::parse("distance:0:5").unwrap();
+        assert_eq!(
+            span.evaluate(&chain),
+            CollectiveVariable::EndToEnd.evaluate(&chain)
+        );
+        assert!(CollectiveVariable::parse("psi:9").unwrap().evaluate(&chain).is_nan());
+        assert!(CollectiveVariable::parse("helicity").is_err());
+        assert!(CollectiveVariable::parse("distance:0").is_err());
+        assert!(CollectiveVariable::parse("contacts:-1").is_err());
+    }
+}
@@ -0,0 +1,823 @@
+//! Fluent construction of [`FoldingContract`]s from Rust, checked when the
+//! contract is built instead of when it runs:
+//!
+//! ```
+//! use folding_core::{Dihedral, FoldingContract, PhysicsLevel};
+//!
+//! let contract = FoldingContract::builder()
+//!     .with_chain_length(30)
+//!     .define_domain("helixA", 5, 20)
+//!     .rotate(12, Dihedral::Phi, -30.0)
+//!     .rotate_domain("helixA", 15.0, folding_core::DomainAxis::Z)
+//!     .refine(PhysicsLevel::Gb)
+//!     .commit()
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(contract.move_count(), 3);
+//! ```
+
+use std::ops::RangeInclusive;
+
+use folding_molecule::{Dihedral, ResidueId};
+use folding_physics::{Environment, registry};
+
+use crate::folding_parser::{
+    ContractInstruction, ContractMetadata, DomainAxis, DomainMotion, DomainSelector,
+    EnvironmentParameter, FoldingContract, PhysicsLevel, PhysicsSpanMode, SIMULATE_MAX_PICOSECONDS,
+};
+use crate::physics_bridge::{PhysicsEngine, check_md_timestep};
+use crate::restraints::SecondaryStructure;
+
+impl FoldingContract {
+    pub fn builder() -> ContractBuilder {
+        ContractBuilder::new()
+    }
+}
+
+impl From<&str> for DomainSelector {
+    fn from(name: &str) -> Self {
+        DomainSelector::Named(name.to_string())
+    }
+}
+
+impl From<RangeInclusive<usize>> for DomainSelector {
+    fn from(range: RangeInclusive<usize>) -> Self {
+        DomainSelector::Range {
+            start: ResidueId(*range.start()),
+            end: ResidueId(*range.end()),
+        }
+    }
+}
+
+/// Temperature (K) [`ContractBuilder::minimize`] quenches to; low enough that
+/// only downhill moves pass the Metropolis test.
+pub const QUENCH_TEMPERATURE: f64 = 1.0;
+
+/// Appends instructions in call order. Moves take the duration set by the
+/// last `with_duration_ms` (1 ms by default).
+#[derive(Clone, Debug)]
+pub struct ContractBuilder {
+    metadata: ContractMetadata,
+    instructions: Vec<ContractInstruction>,
+    chain_length: Option<usize>,
+    duration_ms: u64,
+    /// Last thermostat temperature an instruction set, where `minimize`
+    /// starts its quench.
+    temperature: Option<f64>,
+}
+
+impl Default for ContractBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContractBuilder {
+    pub fn new() -> Self {
+        Self {
+            metadata: ContractMetadata::default(),
+            instructions: Vec::new(),
+            chain_length: None,
+            duration_ms: 1,
+            temperature: None,
+        }
+    }
+
+    /// Rejects residues outside a chain of `residues` at build time.
+    pub fn with_chain_length(mut self, residues: usize) -> Self {
+        self.chain_length = Some(residues);
+        self
+    }
+
+    pub fn with_duration_ms(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = duration_ms;
+        self
+    }
+
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.metadata.author = Some(author.to_string());
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.metadata.description = Some(description.to_string());
+        self
+    }
+
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.metadata.tags.push(tag.to_string());
+        self
+    }
+
+    pub fn with_required_physics(mut self, level: PhysicsLevel) -> Self {
+        self.metadata.requires_physics = Some(level);
+        self
+    }
+
+    /// Appends an instruction as is; it is validated like the others.
+    pub fn instruction(mut self, instruction: ContractInstruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    pub fn rotate(self, residue: usize, dihedral: Dihedral, angle_degrees: f64) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::Rotate {
+            residue: ResidueId(residue),
+            dihedral,
+            angle_degrees,
+            duration_ms,
+        })
+    }
+
+    /// Turns the same torsion of every residue in `start..=end` as one span.
+    pub fn rotate_segment(
+        self,
+        start: usize,
+        end: usize,
+        dihedral: Dihedral,
+        angle_degrees: f64,
+    ) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::RotateSegment {
+            start: ResidueId(start),
+            end: ResidueId(end),
+            dihedral,
+            angle_degrees,
+            duration_ms,
+        })
+    }
+
+    /// Offset in Å of a domain name or residue range.
+    pub fn translate_domain(self, domain: impl Into<DomainSelector>, offset: [f64; 3]) -> Self {
+        self.move_domain(domain.into(), DomainMotion::Translate(offset))
+    }
+
+    pub fn rotate_domain(
+        self,
+        domain: impl Into<DomainSelector>,
+        angle_degrees: f64,
+        axis: DomainAxis,
+    ) -> Self {
+        let motion = DomainMotion::Rotate {
+            angle_degrees,
+            axis,
+        };
+        self.move_domain(domain.into(), motion)
+    }
+
+    fn move_domain(self, domain: DomainSelector, motion: DomainMotion) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::MoveDomain {
+            domain,
+            motion,
+            duration_ms,
+        })
+    }
+
+    pub fn close_loop(self, start: usize, end: usize, perturbation_degrees: f64) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::CloseLoop {
+            start: ResidueId(start),
+            end: ResidueId(end),
+            perturbation_degrees,
+            duration_ms,
+        })
+    }
+
+    /// `None` draws the angle at random when the contract runs.
+    pub fn backrub(self, residue: usize, angle_degrees: Option<f64>) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::Backrub {
+            residue: ResidueId(residue),
+            angle_degrees,
+            duration_ms,
+        })
+    }
+
+    /// Flips `residue` to the rotamer named `rotamer`, e.g. `mt`; `None`
+    /// draws one at random when the contract runs.
+    pub fn rotamer(self, residue: usize, rotamer: Option<&str>) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::RotamerFlip {
+            residue: ResidueId(residue),
+            rotamer: rotamer.map(str::to_lowercase),
+            duration_ms,
+        })
+    }
+
+    pub fn refine(self, level: PhysicsLevel) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::Refine { level, duration_ms })
+    }
+
+    pub fn fix_geometry(self) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::FixGeometry { duration_ms })
+    }
+
+    /// Native MD burst of `picoseconds`; `None` runs at the engine's level.
+    pub fn simulate(self, picoseconds: f64, level: Option<PhysicsLevel>) -> Self {
+        let duration_ms = self.duration_ms;
+        self.instruction(ContractInstruction::Simulate {
+            picoseconds,
+            level,
+            duration_ms,
+        })
+    }
+
+    pub fn clash_check(self) -> Self {
+        self.instruction(ContractInstruction::ClashCheck)
+    }
+
+    pub fn commit(self) -> Self {
+        self.instruction(ContractInstruction::Commit)
+    }
+
+    pub fn rollback(self) -> Self {
+        self.instruction(ContractInstruction::Rollback)
+    }
+
+    pub fn ghost(self, enabled: bool) -> Self {
+        self.instruction(ContractInstruction::GhostMode(enabled))
+    }
+
+    pub fn span_alias(self, alias: &str) -> Self {
+        self.instruction(ContractInstruction::SpanAlias(alias.to_string()))
+    }
+
+    /// Opens a phase nested in any open one; spans record the phases they
+    /// ran in.
+    pub fn phase(self, name: &str) -> Self {
+        self.instruction(ContractInstruction::BeginPhase(name.to_string()))
+    }
+
+    pub fn end_phase(self) -> Self {
+        self.instruction(ContractInstruction::EndPhase)
+    }
+
+    /// Tags the next move's span.
+    pub fn tag(self, tags: &[&str]) -> Self {
+        let tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self.instruction(ContractInstruction::SpanTags(tags))
+    }
+
+    pub fn define_domain(self, name: &str, start: usize, end: usize) -> Self {
+        self.instruction(ContractInstruction::DefineDomain {
+            name: Some(name.to_string()),
+            start: ResidueId(start),
+            end: ResidueId(end),
+        })
+    }
+
+    /// Holds residues `start..=end` in place for the rest of the contract.
+    pub fn freeze(self, start: usize, end: usize) -> Self {
+        self.instruction(ContractInstruction::Freeze {
+            start: ResidueId(start),
+            end: ResidueId(end),
+        })
+    }
+
+    /// Restrains phi/psi of `start..=end` toward `structure`, with a force
+    /// constant in kcal/(mol·rad²).
+    pub fn restrain(
+        self,
+        start: usize,
+        end: usize,
+        structure: SecondaryStructure,
+        force_constant: f64,
+    ) -> Self {
+        self.instruction(ContractInstruction::Restrain {
+            start: ResidueId(start),
+            end: ResidueId(end),
+            structure,
+            force_constant,
+        })
+    }
+
+    pub fn require_chaperone(self, chaperone: &str, span: Option<&str>) -> Self {
+        self.instruction(ContractInstruction::RequireChaperone {
+            chaperone: chaperone.to_string(),
+            span: span.map(String::from),
+        })
+    }
+
+    pub fn add_modification(self, modification: &str, residue: usize) -> Self {
+        self.instruction(ContractInstruction::AddModification {
+            modification: modification.to_string(),
+            residue: ResidueId(residue),
+        })
+    }
+
+    pub fn physics_level(self, level: PhysicsLevel) -> Self {
+        self.instruction(ContractInstruction::SetPhysicsLevel(level))
+    }
+
+    pub fn physics_engine(self, engine: PhysicsEngine) -> Self {
+        self.instruction(ContractInstruction::SetPhysicsEngine(engine))
+    }
+
+    pub fn physics_span(self, enabled: bool) -> Self {
+        let mode = if enabled {
+            PhysicsSpanMode::Physics
+        } else {
+            PhysicsSpanMode::Toy
+        };
+        self.instruction(ContractInstruction::SetSpanPhysics(mode))
+    }
+
+    /// Must name a registered force field by the time `build` is called.
+    pub fn force_field(self, name: &str) -> Self {
+        self.instruction(ContractInstruction::SetForceField(name.to_lowercase()))
+    }
+
+    /// Must name a registered integrator by the time `build` is called.
+    pub fn integrator(self, name: &str) -> Self {
+        self.instruction(ContractInstruction::SetIntegrator(name.to_lowercase()))
+    }
+
+    /// MD timestep (ps) of later physics spans; `None` uses the level's default.
+    pub fn timestep(self, picoseconds: Option<f64>) -> Self {
+        self.instruction(ContractInstruction::SetTimestep(picoseconds))
+    }
+
+    /// Thermostat temperature (K) for the rest of the contract.
+    pub fn temperature(mut self, kelvin: f64) -> Self {
+        self.temperature = Some(kelvin);
+        self.instruction(ContractInstruction::SetTemperature(kelvin))
+    }
+
+    /// Metropolis temperature (K); `None` follows the thermostat again.
+    pub fn acceptance_temperature(self, kelvin: Option<f64>) -> Self {
+        self.instruction(ContractInstruction::SetAcceptanceTemperature(kelvin))
+    }
+
+    /// Named solvent environment for the rest of the contract.
+    pub fn environment(self, name: &str) -> Self {
+        self.instruction(ContractInstruction::SetEnvironment(name.to_lowercase()))
+    }
+
+    pub fn set(mut self, parameter: EnvironmentParameter, value: f64) -> Self {
+        if parameter == EnvironmentParameter::Temperature {
+            self.temperature = Some(value);
+        }
+        self.instruction(ContractInstruction::SetEnvironmentParameter { parameter, value })
+    }
+
+    /// Moves `parameter` from `from` to `to` over the next `steps` steps.
+    pub fn ramp(
+        mut self,
+        parameter: EnvironmentParameter,
+        from: f64,
+        to: f64,
+        steps: usize,
+    ) -> Self {
+        if parameter == EnvironmentParameter::Temperature {
+            self.temperature = Some(to);
+        }
+        self.instruction(ContractInstruction::RampEnvironment {
+            parameter,
+            from,
+            to,
+            steps,
+        })
+    }
+
+    /// Cools (or heats) the thermostat linearly from `from_kelvin` to
+    /// `to_kelvin` over the next `steps` steps, then holds it there.
+    pub fn anneal(self, from_kelvin: f64, to_kelvin: f64, steps: usize) -> Self {
+        self.ramp(
+            EnvironmentParameter::Temperature,
+            from_kelvin,
+            to_kelvin,
+            steps,
+        )
+    }
+
+    /// Quenches to [`QUENCH_TEMPERATURE`] over the next `steps` steps, after
+    /// which only moves that lower the energy are accepted. The ramp starts
+    /// at the last temperature set through the builder, or the aqueous
+    /// default when there is none.
+    pub fn minimize(self, steps: usize) -> Self {
+        let from = self
+            .temperature
+            .unwrap_or_else(|| Environment::aqueous().default_temperature);
+        self.anneal(from, QUENCH_TEMPERATURE, steps)
+    }
+
+    /// Checks every instruction and reports the first problem with its
+    /// position and `.lll` form, e.g. `instruction 3 (rotate residue=40 ...):
+    /// residue 40 is outside the 30-residue chain`. MD timesteps are checked
+    /// against the force field in force where they meet it; before any
+    /// `physics_level`, that is the coarse-grained one.
+    pub fn build(self) -> Result<FoldingContract, String> {
+        let mut domains: Vec<&str> = Vec::new();
+        let mut open_phases = 0usize;
+        let mut md = MdSettings {
+            level: PhysicsLevel::Toy,
+            force_field: None,
+            timestep: None,
+        };
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            match instruction {
+                ContractInstruction::BeginPhase(_) => open_phases += 1,
+                ContractInstruction::EndPhase if open_phases == 0 => {
+                    return Err(format!("instruction {}: no phase is open", index + 1));
+                }
+                ContractInstruction::EndPhase => open_phases -= 1,
+                _ => {}
+            }
+            self.check(instruction, &domains)
+                .and_then(|_| md.follow(instruction))
+                .map_err(|err| {
+                    format!(
+                        "instruction {} ({}): {err}",
+                        index + 1,
+                        instruction.to_lll()
+                    )
+                })?;
+            if let ContractInstruction::DefineDomain {
+                name: Some(name), ..
+            } = instruction
+            {
+                domains.push(name);
+            }
+        }
+        Ok(FoldingContract {
+            metadata: self.metadata,
+            instructions: self.instructions,
+        })
+    }
+
+    fn check(&self, instruction: &ContractInstruction, domains: &[&str]) -> Result<(), String> {
+        match instruction {
+            ContractInstruction::Rotate {
+                residue,
+                angle_degrees,
+                ..
+            } => {
+                self.check_residue(*residue)?;
+                check_finite("angle", *angle_degrees)
+            }
+            ContractInstruction::RotateSegment {
+                start,
+                end,
+                angle_degrees,
+                ..
+            } => {
+                self.check_range(*start, *end, 1)?;
+                check_finite("angle", *angle_degrees)
+            }
+            ContractInstruction::MoveDomain { domain, motion, .. } => {
+                match domain {
+                    DomainSelector::Named(name) if !domains.contains(&name.as_str()) => {
+                        return Err(format!("domain '{name}' is not defined before it moves"));
+                    }
+                    DomainSelector::Named(_) => {}
+                    DomainSelector::Range { start, end } => self.check_range(*start, *end, 0)?,
+                }
+                match motion {
+                    DomainMotion::Translate(offset) => {
+                        offset.iter().try_for_each(|v| check_finite("offset", *v))
+                    }
+                    DomainMotion::Rotate { angle_degrees, .. } => {
+                        check_finite("angle", *angle_degrees)
+                    }
+                }
+            }
+            ContractInstruction::CloseLoop {
+                start,
+                end,
+                perturbation_degrees,
+                ..
+            } => {
+                // The anchors must enclose at least two movable residues.
+                self.check_range(*start, *end, 3)?;
+                check_finite("perturbation", *perturbation_degrees)
+            }
+            ContractInstruction::Backrub {
+                residue,
+                angle_degrees,
+                ..
+            } => {
+                self.check_residue(*residue)?;
+                let last = self.chain_length.map(|length| length.saturating_sub(1));
+                if residue.0 == 0 || Some(residue.0) == last {
+                    return Err("terminal residues cannot backrub".into());
+                }
+                angle_degrees.map_or(Ok(()), |angle| check_finite("angle", angle))
+            }
+            ContractInstruction::RotamerFlip {
+                residue, rotamer, ..
+            } => {
+                self.check_residue(*residue)?;
+                rotamer
+                    .as_deref()
+                    .map_or(Ok(()), |rotamer| check_word("rotamer", rotamer))
+            }
+            ContractInstruction::DefineDomain { name, start, end } => {
+                if let Some(name) = name {
+                    check_word("domain name", name)?;
+                }
+                self.check_range(*start, *end, 0)
+            }
+            ContractInstruction::Freeze { start, end } => self.check_range(*start, *end, 0),
+            ContractInstruction::Restrain {
+                start,
+                end,
+                force_constant,
+                ..
+            } => {
+                self.check_range(*start, *end, 0)?;
+                if !force_constant.is_finite() || *force_constant < 0.0 {
+                    return Err(format!(
+                        "force constant {force_constant} must be a non-negative number"
+                    ));
+                }
+                Ok(())
+            }
+            ContractInstruction::AddModification {
+                modification,
+                residue,
+            } => {
+                check_word("modification", modification)?;
+                self.check_residue(*residue)
+            }
+            ContractInstruction::RequireChaperone { chaperone, span } => {
+                check_word("chaperone", chaperone)?;
+                span.as_deref()
+                    .map_or(Ok(()), |span| check_word("span", span))
+            }
+            ContractInstruction::SpanAlias(alias) if alias.trim().is_empty() => {
+                Err("span alias is empty".into())
+            }
+            ContractInstruction::BeginPhase(name) => check_word("phase name", name),
+            ContractInstruction::SpanTags(tags) if tags.is_empty() => Err("no tags given".into()),
+            ContractInstruction::SpanTags(tags) => {
+                tags.iter().try_for_each(|tag| check_word("tag", tag))
+            }
+            ContractInstruction::SetForceField(name) => registry::check_force_field(name),
+            ContractInstruction::SetEnvironment(name) => match Environment::by_name(name) {
+                Some(_) => Ok(()),
+                None => Err(format!("unknown environment '{name}'")),
+            },
+            ContractInstruction::SetEnvironmentParameter { parameter, value } => {
+                parameter.check(*value)
+            }
+            ContractInstruction::RampEnvironment {
+                parameter, from, to, ..
+            } => parameter.check(*from).and_then(|_| parameter.check(*to)),
+            ContractInstruction::SetIntegrator(name) => registry::check_integrator(name),
+            ContractInstruction::SetTemperature(kelvin)
+            | ContractInstruction::SetAcceptanceTemperature(Some(kelvin)) => {
+                check_finite("temperature", *kelvin)?;
+                if *kelvin > 0.0 {
+                    Ok(())
+                } else {
+                    Err(format!("temperature {kelvin} K is not positive"))
+                }
+            }
+            ContractInstruction::Simulate { picoseconds, .. } => {
+                check_finite("simulated time", *picoseconds)?;
+                if *picoseconds <= 0.0 {
+                    Err(format!("simulated time {picoseconds} ps is not positive"))
+                } else if *picoseconds > SIMULATE_MAX_PICOSECONDS {
+                    Err(format!(
+                        "simulated time {picoseconds} ps is above the {SIMULATE_MAX_PICOSECONDS} ps cap"
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            ContractInstruction::SetTimestep(Some(picoseconds)) => {
+                check_finite("timestep", *picoseconds)?;
+                if *picoseconds > 0.0 {
+                    Ok(())
+                } else {
+                    Err(format!("timestep {picoseconds} ps is not positive"))
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_residue(&self, residue: ResidueId) -> Result<(), String> {
+        match self.chain_length {
+            Some(length) if residue.0 >= length => Err(format!(
+                "residue {} is outside the {length}-residue chain",
+                residue.0
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// `end` must lie at least `min_span` residues after `start`.
+    fn check_range(&self, start: ResidueId, end: ResidueId, min_span: usize) -> Result<(), String> {
+        if end.0 < start.0 + min_span {
+            return Err(format!(
+                "range {}-{} must span at least {min_span} residues",
+                start.0, end.0
+            ));
+        }
+        self.check_residue(start)?;
+        self.check_residue(end)
+    }
+}
+
+/// Physics level, force field and MD timestep in force while a contract is
+/// built.
+struct MdSettings<'a> {
+    level: PhysicsLevel,
+    force_field: Option<&'a str>,
+    timestep: Option<f64>,
+}
+
+impl<'a> MdSettings<'a> {
+    /// Follows `instruction` and checks the timestep against the force field
+    /// a span would run with from there.
+    fn follow(&mut self, instruction: &'a ContractInstruction) -> Result<(), String> {
+        let level = match instruction {
+            ContractInstruction::SetPhysicsLevel(level) => {
+                self.level = *level;
+                *level
+            }
+            ContractInstruction::SetForceField(name) => {
+                self.force_field = Some(name);
+                self.level
+            }
+            ContractInstruction::SetTimestep(picoseconds) => {
+                self.timestep = *picoseconds;
+                self.level
+            }
+            ContractInstruction::Refine { level, .. }
+            | ContractInstruction::Simulate {
+                level: Some(level), ..
+            } => *level,
+            ContractInstruction::Simulate { level: None, .. } => self.level,
+            _ => return Ok(()),
+        };
+        match self.timestep {
+            Some(timestep) => check_md_timestep(level, self.force_field, timestep),
+            None => Ok(()),
+        }
+    }
+}
+
+fn check_finite(what: &str, value: f64) -> Result<(), String> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(format!("{what} {value} is not finite"))
+    }
+}
+
+/// Names are written back as single `.lll` tokens, so they cannot hold
+/// separators, quotes or comments.
+fn check_word(what: &str, value: &str) -> Result<(), String> {
+    let invalid = |c: char| c.is_whitespace() || ",;(){}\"'#".contains(c);
+    if value.is_empty() || value.contains(invalid) {
+        Err(format!("{what} '{value}' must be a single word"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_contracts_match_their_lll_form() {
+        let contract = FoldingContract::builder()
+            .with_author("Ada")
+            .with_required_physics(PhysicsLevel::Coarse)
+            .with_chain_length(30)
+            .physics_level(PhysicsLevel::Coarse)
+            .define_domain("helixA", 5, 20)
+            .span_alias("collapse seed")
+            .with_duration_ms(5)
+            .rotate(12, Dihedral::Psi, -30.0)
+            .rotate_segment(10, 25, Dihedral::Phi, 5.0)
+            .translate_domain("helixA", [2.0, 0.0, -1.5])
+            .rotate_domain(22..=28, 15.0, DomainAxis::Z)
+            .close_loop(10, 15, 45.0)
+            .backrub(12, None)
+            .rotamer(13, Some("MT"))
+            .add_modification("phosphorylation", 7)
+            .require_chaperone("Hsp70", Some("helixA"))
+            .commit()
+            .build()
+            .unwrap();
+        assert_eq!(contract.move_count(), 7);
+
+        let parsed = FoldingContract::parse(&contract.to_lll()).unwrap();
+        assert_eq!(parsed.to_lll(), contract.to_lll());
+        assert_eq!(parsed.metadata.author.as_deref(), Some("Ada"));
+        assert!(
+            contract
+                .to_lll()
+                .contains("rotate residue=12 dihedral=psi angle=-30.000 duration=5\n")
+        );
+    }
+
+    #[test]
+    fn anneal_and_minimize_round_trip_as_temperature_ramps() {
+        let contract = FoldingContract::builder()
+            .with_chain_length(30)
+            .rotate(12, Dihedral::Phi, -30.0)
+            .anneal(350.0, 300.0, 500)
+            .minimize(200)
+            .build()
+            .unwrap();
+        let lll = contract.to_lll();
+        assert!(lll.ends_with(
+            "ramp temperature 350 -> 300 over 500 steps\n\
+             ramp temperature 300 -> 1 over 200 steps\n"
+        ));
+
+        let parsed = FoldingContract::parse(&lll).unwrap();
+        assert_eq!(parsed.to_lll(), lll);
+        assert!(matches!(
+            parsed.instructions[2],
+            ContractInstruction::RampEnvironment {
+                parameter: EnvironmentParameter::Temperature,
+                from: 300.0,
+                to: QUENCH_TEMPERATURE,
+                steps: 200,
+            }
+        ));
+
+        // With no temperature set, the quench starts from the aqueous default
+        let quench = FoldingContract::builder().minimize(50).build().unwrap();
+        assert_eq!(quench.to_lll(), "ramp temperature 298 -> 1 over 50 steps\n");
+        assert!(
+            FoldingContract::builder()
+                .anneal(350.0, 0.0, 10)
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn build_rejects_invalid_instructions() {
+        let err = FoldingContract::builder()
+            .with_chain_length(30)
+            .rotate(3, Dihedral::Phi, 10.0)
+            .rotate(40, Dihedral::Phi, -30.0)
+            .build()
+            .unwrap_err();
+        assert!(
+            err.starts_with("instruction 2 (rotate residue=40 angle=-30.000 duration=1)"),
+            "{err}"
+        );
+
+        let undefined = FoldingContract::builder().rotate_domain("helixB", 10.0, DomainAxis::X);
+        assert!(
+            undefined
+                .build()
+                .unwrap_err()
+                .contains("'helixB' is not defined")
+        );
+        assert!(
+            FoldingContract::builder()
+                .close_loop(4, 5, 30.0)
+                .build()
+                .is_err()
+        );
+        assert!(
+            FoldingContract::builder()
+                .rotate(1, Dihedral::Phi, f64::NAN)
+                .build()
+                .is_err()
+        );
+        assert!(
+            FoldingContract::builder()
+                .define_domain("helix A", 1, 4)
+                .build()
+                .is_err()
+        );
+        assert!(
+            FoldingContract::builder()
+                .force_field("no-such-field")
+                .build()
+                .is_err()
+        );
+
+        // A 10 fs step suits the coarse-grained beads but not Amber
+        let timestep = || FoldingContract::builder().timestep(Some(0.01));
+        assert!(timestep().simulate(1.0, None).build().is_ok());
+        let err = timestep()
+            .physics_level(PhysicsLevel::Gb)
+            .build()
+            .unwrap_err();
+        assert!(err.contains("stability limit of 0.002 ps"), "{err}");
+        assert!(timestep().refine(PhysicsLevel::Full).build().is_err());
+        assert!(
+            timestep()
+                .force_field("coarse-grained")
+                .physics_level(PhysicsLevel::Gb)
+                .build()
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Foldability of a run: how far the best conformation it sampled sits below
+//! the others.
+//!
+//! Every conformation the engine scores is a sample: the start and each move
+//! it judges, accepted or not. The lowest is taken as the native candidate and
+//! the rest as its decoys. The index is the gap between the decoy mean and the
+//! best energy over the decoy spread; a sequence whose best state stands many
+//! deviations clear of its decoys folds, one lost among them does not.
+//!
+//! Span logs keep the statistics themselves (`count,mean,squares,best`), so
+//! the runs of a sweep pool into one index afterwards.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Running energy statistics of sampled conformations, kept without storing
+/// the samples so long runs cost nothing extra; runs combine with
+/// [`merge`](Self::merge).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnergySamples {
+    count: usize,
+    mean: f64,
+    /// Sum of squared deviations from `mean` (Welford).
+    squares: f64,
+    best: f64,
+}
+
+/// Energy gap of the best sampled conformation, in kcal/mol.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Foldability {
+    pub best_energy: f64,
+    pub decoys: usize,
+    pub decoy_mean: f64,
+    pub decoy_std: f64,
+    /// `decoy_mean - best_energy`; never negative.
+    pub gap: f64,
+    /// `gap / decoy_std`; 0 when every decoy scores alike.
+    pub index: f64,
+}
+
+impl EnergySamples {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the energy of one conformation; non-finite energies are skipped.
+    pub fn record(&mut self, energy: f64) {
+        if !energy.is_finite() {
+            return;
+        }
+        if self.count == 0 || energy < self.best {
+            self.best = energy;
+        }
+        self.count += 1;
+        let delta = energy - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squares += delta * (energy - self.mean);
+    }
+
+    /// Pools the samples of another run into these.
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.squares +=
+            other.squares + delta * delta * (self.count as f64 * other.count as f64) / count as f64;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.best = self.best.min(other.best);
+        self.count = count;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn best(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.best)
+    }
+
+    /// Gap between the best sample and the rest; `None` until there is at
+    /// least one decoy.
+    pub fn foldability(&self) -> Option<Foldability> {
+        if self.count < 2 {
+            return None;
+        }
+        // Take the best sample back out of the running mean and squares
+        let decoys = self.count - 1;
+        let decoy_mean = (self.mean * self.count as f64 - self.best) / decoys as f64;
+        let squares = (self.squares - (self.best - self.mean) * (self.best - decoy_mean)).max(0.0);
+        let decoy_std = (squares / decoys as f64).sqrt();
+        let gap = (decoy_mean - self.best).max(0.0);
+        let index = if decoy_std > 1e-12 {
+            gap / decoy_std
+        } else {
+            0.0
+        };
+        Some(Foldability {
+            best_energy: self.best,
+            decoys,
+            decoy_mean,
+            decoy_std,
+            gap,
+            index,
+        })
+    }
+}
+
+impl fmt::Display for EnergySamples {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{}",
+            self.count, self.mean, self.squares, self.best
+        )
+    }
+}
+
+impl FromStr for EnergySamples {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, String> {
+        let invalid = || format!("energy samples '{raw}' need count,mean,squares,best");
+        let fields: Vec<&str> = raw.split(',').collect();
+        let [count, mean, squares, best] = fields[..] else {
+            return Err(invalid());
+        };
+        let number = |field: &str| field.parse::<f64>().map_err(|_| invalid());
+        Ok(Self {
+            count: count.parse().map_err(|_| invalid())?,
+            mean: number(mean)?,
+            squares: number(squares)?,
+            best: number(best)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(energies: &[f64]) -> EnergySamples {
+        let mut samples = EnergySamples::new();
+        energies.iter().for_each(|&energy| samples.record(energy));
+        samples
+    }
+
+    #[test]
+    fn gap_is_measured_against_the_decoys_alone() {
+        let foldability = samples(&[-1.0, 1.0, -10.0, 0.0, f64::NAN])
+            .foldability()
+            .unwrap();
+        assert_eq!(foldability.best_energy, -10.0);
+        assert_eq!(foldability.decoys, 3);
+        assert!(foldability.decoy_mean.abs() < 1e-12);
+        assert!((foldability.decoy_std - (2.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert!((foldability.gap - 10.0).abs() < 1e-12);
+        assert!((foldability.index - 10.0 / (2.0f64 / 3.0).sqrt()).abs() < 1e-9);
+
+        // Runs pool into the same statistics as one long run
+        let mut pooled = samples(&[-1.0, 1.0]);
+        pooled.merge(&samples(&[-10.0, 0.0]));
+        let pooled = pooled.foldability().unwrap();
+        assert!((pooled.index - foldability.index).abs() < 1e-9);
+
+        // What a log stores reads back as the same statistics
+        let logged = samples(&[-1.0, 1.0, -10.0]);
+        assert_eq!(logged.to_string().parse::<EnergySamples>(), Ok(logged));
+        assert!("3,1.0,2.0".parse::<EnergySamples>().is_err());
+
+        assert!(samples(&[3.0]).foldability().is_none());
+        assert_eq!(samples(&[2.0, 2.0, 2.0]).foldability().unwrap().index, 0.0);
+    }
+}
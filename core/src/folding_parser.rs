@@ -1,25 +1,120 @@
 use std::collections::HashMap;
+use std::fmt;
 
-use folding_molecule::ResidueId;
+use nom::IResult;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case, take_till, take_till1, take_while, take_while1};
+use nom::character::complete::{alpha0, char, digit1, one_of};
+use nom::combinator::{all_consuming, map_res, opt, rest};
+use nom::number::complete::recognize_float;
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated};
+
+use folding_molecule::{Dihedral, ResidueId};
+
+use crate::physics_bridge::PhysicsEngine;
+use crate::restraints::{DEFAULT_RESTRAINT_CONSTANT, SecondaryStructure};
+use folding_physics::Environment;
 
 /// Core instruction set for `.lll` folding contracts.
 #[derive(Debug, Clone)]
 pub enum ContractInstruction {
+    /// Turns one torsion of `residue`; plain `rotate` lines act on phi.
     Rotate {
         residue: ResidueId,
+        dihedral: Dihedral,
+        angle_degrees: f64,
+        duration_ms: u64,
+    },
+    /// Turns the same torsion of every residue in `start..=end` as one span.
+    RotateSegment {
+        start: ResidueId,
+        end: ResidueId,
+        dihedral: Dihedral,
         angle_degrees: f64,
         duration_ms: u64,
     },
+    /// Translates or rotates a whole domain as a rigid body.
+    MoveDomain {
+        domain: DomainSelector,
+        motion: DomainMotion,
+        duration_ms: u64,
+    },
+    /// Resamples the loop between the `start` and `end` anchors and closes it
+    /// back onto them with cyclic coordinate descent.
+    CloseLoop {
+        start: ResidueId,
+        end: ResidueId,
+        perturbation_degrees: f64,
+        duration_ms: u64,
+    },
+    /// Turns residue `residue` about the axis through its two neighbours'
+    /// Cα; `None` draws the angle at random within ±`BACKRUB_MAX_DEGREES`.
+    Backrub {
+        residue: ResidueId,
+        angle_degrees: Option<f64>,
+        duration_ms: u64,
+    },
+    /// Sets the chi angles of `residue` to the library rotamer named
+    /// `rotamer`; `None` draws one of the others by its frequency.
+    RotamerFlip {
+        residue: ResidueId,
+        rotamer: Option<String>,
+        duration_ms: u64,
+    },
+    /// Backmaps the Cα model to a full backbone and hands it to `level`
+    /// (GB by default) for a refinement span; later spans stay at that level.
+    Refine {
+        level: PhysicsLevel,
+        duration_ms: u64,
+    },
+    /// Turns every unintended cis peptide bond back to trans in one span and
+    /// puts inverted Cβ of the refined model back on the L side; with no
+    /// bond to turn only the Cβ are rebuilt and no span is recorded.
+    FixGeometry {
+        duration_ms: u64,
+    },
+    /// Native MD burst of `picoseconds` simulated time (at most
+    /// [`SIMULATE_MAX_PICOSECONDS`]) between Monte Carlo moves, at `level` (the current level when `None`), recorded as one
+    /// span. The burst's end state is judged like any other move.
+    Simulate {
+        picoseconds: f64,
+        level: Option<PhysicsLevel>,
+        duration_ms: u64,
+    },
     ClashCheck,
     Commit,
     Rollback,
     GhostMode(bool),
     SpanAlias(String),
+    /// Tags for the next move's span; written `tag <tags>` or as trailing
+    /// `#tag` words on the move's own line.
+    SpanTags(Vec<String>),
+    /// Opens a named phase, nested inside any phase already open; spans
+    /// record the phases they ran in.
+    BeginPhase(String),
+    /// Closes the innermost open phase. Phases still open when the contract
+    /// ends close with it.
+    EndPhase,
     DefineDomain {
         name: Option<String>,
         start: ResidueId,
         end: ResidueId,
     },
+    /// Holds `start..=end` in place for the rest of the contract: moves that
+    /// would turn or displace them are refused and MD keeps them at rest.
+    Freeze {
+        start: ResidueId,
+        end: ResidueId,
+    },
+    /// Pulls phi/psi of `start..=end` toward `structure` with a harmonic
+    /// restraint of `force_constant` kcal/(mol·rad²) for the rest of the
+    /// contract.
+    Restrain {
+        start: ResidueId,
+        end: ResidueId,
+        structure: SecondaryStructure,
+        force_constant: f64,
+    },
     RequireChaperone {
         chaperone: String,
         span: Option<String>,
@@ -30,20 +125,111 @@ pub enum ContractInstruction {
     },
     SetPhysicsLevel(PhysicsLevel),
     SetSpanPhysics(PhysicsSpanMode),
+    /// Backend later physics spans are sent to.
+    SetPhysicsEngine(PhysicsEngine),
+    /// Registered force field used by later physics spans.
+    SetForceField(String),
+    /// Registered integrator used by later physics spans.
+    SetIntegrator(String),
+    /// MD timestep (ps) of later physics spans; `None` restores the level's
+    /// default. Checked against the force field's stability limit.
+    SetTimestep(Option<f64>),
+    /// Thermostat temperature (K) physics spans run at; replaces any annealing
+    /// schedule. Metropolis acceptance follows it unless set on its own.
+    SetTemperature(f64),
+    /// Temperature (K) of the Metropolis criterion alone; `None` ties it back
+    /// to the thermostat. Dynamics are unaffected.
+    SetAcceptanceTemperature(Option<f64>),
+    /// Switches to a named solvent environment (`aqueous`, `cytosol`,
+    /// `vacuum`); the thermostat keeps its temperature.
+    SetEnvironment(String),
+    /// Sets one environment parameter and ends any ramp of it.
+    SetEnvironmentParameter {
+        parameter: EnvironmentParameter,
+        value: f64,
+    },
+    /// Moves a parameter linearly from `from` to `to` over the next `steps`
+    /// steps, then holds it at `to`.
+    RampEnvironment {
+        parameter: EnvironmentParameter,
+        from: f64,
+        to: f64,
+        steps: usize,
+    },
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum PhysicsLevel {
-    Toy,
-    Coarse,
-    Gb,
-    Full,
+/// Domain addressed by its `define_domain` name or an explicit residue range.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainSelector {
+    Named(String),
+    Range { start: ResidueId, end: ResidueId },
+}
+
+impl DomainSelector {
+    pub fn label(&self) -> String {
+        match self {
+            DomainSelector::Named(name) => name.clone(),
+            DomainSelector::Range { start, end } => format!("{}-{}", start.0, end.0),
+        }
+    }
 }
 
+/// Axis, through the domain centroid, that a rigid domain rotation turns about.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DomainAxis {
+    X,
+    Y,
+    Z,
+    /// From the domain's first to its last Cα.
+    #[default]
+    EndToEnd,
+}
+
+impl DomainAxis {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DomainAxis::X => "x",
+            DomainAxis::Y => "y",
+            DomainAxis::Z => "z",
+            DomainAxis::EndToEnd => "end-to-end",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "x" => Some(DomainAxis::X),
+            "y" => Some(DomainAxis::Y),
+            "z" => Some(DomainAxis::Z),
+            "end-to-end" | "principal" | "long" => Some(DomainAxis::EndToEnd),
+            _ => None,
+        }
+    }
+}
+
+/// Rigid-body displacement applied by `move_domain`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainMotion {
+    /// Offset in Å.
+    Translate([f64; 3]),
+    Rotate {
+        angle_degrees: f64,
+        axis: DomainAxis,
+    },
+}
+
+/// Largest random backrub angle when a `backrub` line gives none.
+pub const BACKRUB_MAX_DEGREES: f64 = 20.0;
+/// Longest simulated time (ps) of one `simulate` burst; longer runs belong in
+/// several bursts, each judged on its own.
+pub const SIMULATE_MAX_PICOSECONDS: f64 = 10_000.0;
+
+pub use folding_physics::PhysicsLevel;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ForceField {
     Amber99SB,
     CHARMM36,
+    #[allow(non_camel_case_types)]
     OPLS_AA,
 }
 
@@ -59,170 +245,1203 @@ pub enum PhysicsSpanMode {
     Physics,
 }
 
+/// Condition a contract can `set` or `ramp`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EnvironmentParameter {
+    /// Thermostat temperature (K).
+    Temperature,
+    /// Solvent viscosity (mPa·s).
+    Viscosity,
+    /// Solvent relative permittivity.
+    Dielectric,
+    /// Crowder volume fraction.
+    Crowding,
+    /// Salt concentration (mol/L).
+    IonicStrength,
+}
+
+impl EnvironmentParameter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnvironmentParameter::Temperature => "temperature",
+            EnvironmentParameter::Viscosity => "viscosity",
+            EnvironmentParameter::Dielectric => "dielectric",
+            EnvironmentParameter::Crowding => "crowding",
+            EnvironmentParameter::IonicStrength => "ionic_strength",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "temperature" | "thermostat" => Some(EnvironmentParameter::Temperature),
+            "viscosity" => Some(EnvironmentParameter::Viscosity),
+            "dielectric" | "permittivity" => Some(EnvironmentParameter::Dielectric),
+            "crowding" | "crowding_fraction" => Some(EnvironmentParameter::Crowding),
+            "ionic_strength" | "salt" => Some(EnvironmentParameter::IonicStrength),
+            _ => None,
+        }
+    }
+
+    /// Rejects values the parameter cannot take.
+    pub fn check(&self, value: f64) -> Result<(), String> {
+        let valid = match self {
+            EnvironmentParameter::Temperature => value > 0.0,
+            EnvironmentParameter::Viscosity | EnvironmentParameter::IonicStrength => value >= 0.0,
+            EnvironmentParameter::Dielectric => value >= 1.0,
+            EnvironmentParameter::Crowding => (0.0..1.0).contains(&value),
+        };
+        if valid && value.is_finite() {
+            Ok(())
+        } else {
+            Err(format!("{} {value} is out of range", self.as_str()))
+        }
+    }
+}
+
+/// Front-matter header of a `.lll` file, delimited by `---` lines:
+///
+/// ```text
+/// ---
+/// author: Jane Doe
+/// description: GB1 hairpin benchmark
+/// tags: benchmark, gb1
+/// requires_physics: gb
+/// ---
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContractMetadata {
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub requires_physics: Option<PhysicsLevel>,
+}
+
+impl ContractMetadata {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    fn to_front_matter(&self) -> String {
+        let mut rendered = String::from("---\n");
+        if let Some(author) = &self.author {
+            rendered.push_str(&format!("author: {author}\n"));
+        }
+        if let Some(description) = &self.description {
+            rendered.push_str(&format!("description: {description}\n"));
+        }
+        if !self.tags.is_empty() {
+            rendered.push_str(&format!("tags: {}\n", self.tags.join(", ")));
+        }
+        if let Some(level) = self.requires_physics {
+            rendered.push_str(&format!("requires_physics: {}\n", level.as_str()));
+        }
+        rendered.push_str("---\n");
+        rendered
+    }
+}
+
 /// Parsed folding contract ready for execution.
 #[derive(Debug, Clone, Default)]
 pub struct FoldingContract {
+    pub metadata: ContractMetadata,
     pub instructions: Vec<ContractInstruction>,
 }
 
 impl FoldingContract {
     pub fn new(instructions: Vec<ContractInstruction>) -> Self {
-        Self { instructions }
+        Self {
+            metadata: ContractMetadata::default(),
+            instructions,
+        }
+    }
+
+    /// Parses a whole `.lll` source; see [`FoldingContract::from_lines`].
+    pub fn parse(source: &str) -> Result<Self, ContractParseError> {
+        Self::from_lines(&source.lines().collect::<Vec<_>>())
     }
 
-    pub fn from_lines(lines: &[&str]) -> Self {
-        let instructions = lines
+    /// Stops at the first malformed line; blank lines and `#` comments are
+    /// skipped.
+    pub fn from_lines(lines: &[&str]) -> Result<Self, ContractParseError> {
+        let (metadata, body_start) = split_front_matter(lines)?;
+        let mut instructions = Vec::new();
+        let mut open_phases = 0usize;
+        for (index, line) in lines.iter().enumerate().skip(body_start) {
+            let parsed = parse_line(line).map_err(|fault| fault.on_line(index, line))?;
+            for instruction in &parsed {
+                match instruction {
+                    ContractInstruction::BeginPhase(_) => open_phases += 1,
+                    ContractInstruction::EndPhase if open_phases == 0 => {
+                        let word = line.trim_start();
+                        let fault = Fault::at_column(
+                            column_of(line, word),
+                            word.trim_end().chars().count(),
+                            "no phase is open",
+                        );
+                        return Err(fault.on_line(index, line));
+                    }
+                    ContractInstruction::EndPhase => open_phases -= 1,
+                    _ => {}
+                }
+            }
+            instructions.extend(parsed);
+        }
+        Ok(Self {
+            metadata,
+            instructions,
+        })
+    }
+
+    /// Number of move spans (rotations, domain moves, loop closures,
+    /// backrubs, rotamer flips and refinements) the contract will attempt.
+    pub fn move_count(&self) -> usize {
+        self.instructions
             .iter()
-            .filter_map(|line| parse_line(line))
-            .flatten()
-            .collect();
-        Self { instructions }
+            .filter(|instruction| instruction.is_move())
+            .count()
+    }
+
+    /// Physics level in force when the first rotation runs, starting from
+    /// `base` (the level the engine was configured with).
+    pub fn initial_physics_level(&self, base: PhysicsLevel) -> PhysicsLevel {
+        self.instructions
+            .iter()
+            .take_while(|instruction| instruction.rotation_span().is_none())
+            .fold(base, |level, instruction| match instruction {
+                ContractInstruction::SetPhysicsLevel(next) => *next,
+                _ => level,
+            })
+    }
+
+    /// Refuses to run below the level demanded by `requires_physics`.
+    pub fn check_physics_level(&self, base: PhysicsLevel) -> Result<(), String> {
+        let Some(required) = self.metadata.requires_physics else {
+            return Ok(());
+        };
+        let level = self.initial_physics_level(base);
+        if level < required {
+            return Err(format!(
+                "contract requires physics level '{}' but the run is configured for '{}'",
+                required.as_str(),
+                level.as_str()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Renders the contract back to `.lll` text, one instruction per line.
+    pub fn to_lll(&self) -> String {
+        let mut rendered = String::new();
+        if !self.metadata.is_empty() {
+            rendered.push_str(&self.metadata.to_front_matter());
+        }
+        for instruction in &self.instructions {
+            rendered.push_str(&instruction.to_lll());
+            rendered.push('\n');
+        }
+        rendered
     }
 }
 
-fn parse_line(raw_line: &str) -> Option<Vec<ContractInstruction>> {
-    let line_without_comments = raw_line.split('#').next().map(str::trim).unwrap_or("");
+/// Residues, torsion, angle and duration of a `rotate` or segment span.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RotationSpan {
+    pub first: ResidueId,
+    pub last: ResidueId,
+    pub dihedral: Dihedral,
+    pub angle_degrees: f64,
+    pub duration_ms: u64,
+}
 
-    if line_without_comments.is_empty() {
-        return None;
+impl RotationSpan {
+    pub fn residues(&self) -> impl Iterator<Item = ResidueId> {
+        (self.first.0..=self.last.0).map(ResidueId)
     }
+}
 
-    let mut tokens = tokenize(line_without_comments);
-    if tokens.is_empty() {
-        return None;
+impl ContractInstruction {
+    /// Whether the instruction attempts a move and so records a span.
+    pub fn is_move(&self) -> bool {
+        matches!(
+            self,
+            ContractInstruction::Rotate { .. }
+                | ContractInstruction::RotateSegment { .. }
+                | ContractInstruction::MoveDomain { .. }
+                | ContractInstruction::CloseLoop { .. }
+                | ContractInstruction::Backrub { .. }
+                | ContractInstruction::RotamerFlip { .. }
+                | ContractInstruction::Refine { .. }
+                | ContractInstruction::FixGeometry { .. }
+                | ContractInstruction::Simulate { .. }
+        )
     }
 
-    let command = tokens.remove(0).to_lowercase();
-    let mut instructions = Vec::new();
+    /// `Some` for both single-residue and segment rotations.
+    pub fn rotation_span(&self) -> Option<RotationSpan> {
+        match *self {
+            ContractInstruction::Rotate {
+                residue,
+                dihedral,
+                angle_degrees,
+                duration_ms,
+            } => Some(RotationSpan {
+                first: residue,
+                last: residue,
+                dihedral,
+                angle_degrees,
+                duration_ms,
+            }),
+            ContractInstruction::RotateSegment {
+                start,
+                end,
+                dihedral,
+                angle_degrees,
+                duration_ms,
+            } => Some(RotationSpan {
+                first: start,
+                last: end,
+                dihedral,
+                angle_degrees,
+                duration_ms,
+            }),
+            _ => None,
+        }
+    }
 
-    match command.as_str() {
-        "rotate" => {
-            if let Some(instr) = parse_rotate(tokens) {
-                instructions.push(instr);
+    /// The same move with its angle, offset, perturbation or simulated time
+    /// multiplied by `factor`; `None` for instructions without a size (and random backrubs).
+    pub fn scaled(&self, factor: f64) -> Option<Self> {
+        let mut scaled = self.clone();
+        match &mut scaled {
+            ContractInstruction::Rotate { angle_degrees, .. }
+            | ContractInstruction::RotateSegment { angle_degrees, .. }
+            | ContractInstruction::Backrub {
+                angle_degrees: Some(angle_degrees),
+                ..
             }
-        }
-        "clash_check" | "clash" => instructions.push(ContractInstruction::ClashCheck),
-        "commit" => instructions.push(ContractInstruction::Commit),
-        "rollback" | "revert" => instructions.push(ContractInstruction::Rollback),
-        "ghost" => {
-            if let Some(state) = tokens.first() {
-                let flag = matches!(state.as_str().to_lowercase().as_str(), "on" | "true" | "1");
-                instructions.push(ContractInstruction::GhostMode(flag));
+            | ContractInstruction::MoveDomain {
+                motion: DomainMotion::Rotate { angle_degrees, .. },
+                ..
             }
-        }
-        "span_alias" | "alias" | "label" => {
-            let alias = tokens.join(" ");
-            let alias = alias.trim_matches(|c| c == '"' || c == '\'');
-            if !alias.is_empty() {
-                instructions.push(ContractInstruction::SpanAlias(alias.to_owned()));
+            | ContractInstruction::CloseLoop {
+                perturbation_degrees: angle_degrees,
+                ..
+            } => *angle_degrees *= factor,
+            ContractInstruction::MoveDomain {
+                motion: DomainMotion::Translate(offset),
+                ..
+            } => offset.iter_mut().for_each(|component| *component *= factor),
+            ContractInstruction::Simulate { picoseconds, .. } => {
+                *picoseconds = (*picoseconds * factor).min(SIMULATE_MAX_PICOSECONDS)
             }
+            _ => return None,
         }
-        "define_domain" | "domain" => {
-            if let Some(instr) = parse_define_domain(tokens) {
-                instructions.push(instr);
+        Some(scaled)
+    }
+
+    /// Canonical `.lll` line for this instruction.
+    pub fn to_lll(&self) -> String {
+        match self {
+            ContractInstruction::Rotate {
+                residue,
+                dihedral: Dihedral::Phi,
+                angle_degrees,
+                duration_ms,
+            } => format!(
+                "rotate residue={} angle={:.3} duration={}",
+                residue.0, angle_degrees, duration_ms
+            ),
+            ContractInstruction::Rotate {
+                residue,
+                dihedral,
+                angle_degrees,
+                duration_ms,
+            } => format!(
+                "rotate residue={} dihedral={} angle={:.3} duration={}",
+                residue.0,
+                dihedral.as_str(),
+                angle_degrees,
+                duration_ms
+            ),
+            ContractInstruction::RotateSegment {
+                start,
+                end,
+                dihedral,
+                angle_degrees,
+                duration_ms,
+            } => format!(
+                "rotate {}..{} {} by {:.3} {}",
+                start.0,
+                end.0,
+                dihedral.as_str(),
+                angle_degrees,
+                duration_ms
+            ),
+            ContractInstruction::MoveDomain {
+                domain,
+                motion,
+                duration_ms,
+            } => match motion {
+                DomainMotion::Translate([x, y, z]) => format!(
+                    "move_domain {} translate {x:.3} {y:.3} {z:.3} {duration_ms}",
+                    domain.label()
+                ),
+                DomainMotion::Rotate {
+                    angle_degrees,
+                    axis,
+                } => format!(
+                    "move_domain {} rotate {angle_degrees:.3} about {} {duration_ms}",
+                    domain.label(),
+                    axis.as_str()
+                ),
+            },
+            ContractInstruction::CloseLoop {
+                start,
+                end,
+                perturbation_degrees,
+                duration_ms,
+            } => format!(
+                "close_loop {}-{} by {perturbation_degrees:.3} {duration_ms}",
+                start.0, end.0
+            ),
+            ContractInstruction::Backrub {
+                residue,
+                angle_degrees,
+                duration_ms,
+            } => match angle_degrees {
+                Some(angle) => format!("backrub {} by {angle:.3} {duration_ms}", residue.0),
+                None => format!("backrub {} random {duration_ms}", residue.0),
+            },
+            ContractInstruction::RotamerFlip {
+                residue,
+                rotamer,
+                duration_ms,
+            } => format!(
+                "rotamer {} {} {duration_ms}",
+                residue.0,
+                rotamer.as_deref().unwrap_or("random")
+            ),
+            ContractInstruction::Refine { level, duration_ms } => {
+                format!("refine {} {duration_ms}", level.as_str())
             }
-        }
-        "require_chaperone" | "chaperone" => {
-            if let Some(instr) = parse_require_chaperone(tokens) {
-                instructions.push(instr);
+            ContractInstruction::FixGeometry { duration_ms } => {
+                format!("fix_geometry {duration_ms}")
             }
-        }
-        "add_modification" | "modification" | "modify" => {
-            if let Some(instr) = parse_add_modification(tokens) {
-                instructions.push(instr);
+            ContractInstruction::Simulate {
+                picoseconds,
+                level,
+                duration_ms,
+            } => match level {
+                Some(level) => format!(
+                    "simulate {picoseconds}ps at {} {duration_ms}",
+                    level.as_str()
+                ),
+                None => format!("simulate {picoseconds}ps {duration_ms}"),
+            },
+            ContractInstruction::ClashCheck => "clash_check".into(),
+            ContractInstruction::Commit => "commit".into(),
+            ContractInstruction::Rollback => "rollback".into(),
+            ContractInstruction::GhostMode(enabled) => {
+                format!("ghost {}", if *enabled { "on" } else { "off" })
             }
-        }
-        "set_physics_level" | "physics_level" => {
-            if let Some(instr) = parse_set_physics_level(tokens) {
-                instructions.push(instr);
+            ContractInstruction::SpanAlias(alias) => format!("span_alias {alias}"),
+            ContractInstruction::SpanTags(tags) => format!("tag {}", tags.join(" ")),
+            ContractInstruction::BeginPhase(name) => format!("phase {name}"),
+            ContractInstruction::EndPhase => "end_phase".into(),
+            ContractInstruction::DefineDomain { name, start, end } => match name {
+                Some(name) => format!("define_domain {name} {}-{}", start.0, end.0),
+                None => format!("define_domain {}-{}", start.0, end.0),
+            },
+            ContractInstruction::Freeze { start, end } if start == end => {
+                format!("freeze {}", start.0)
+            }
+            ContractInstruction::Freeze { start, end } => format!("freeze {}..{}", start.0, end.0),
+            ContractInstruction::Restrain {
+                start,
+                end,
+                structure,
+                force_constant,
+            } => format!(
+                "restrain {}..{} {} k={force_constant}",
+                start.0,
+                end.0,
+                structure.as_str()
+            ),
+            ContractInstruction::RequireChaperone { chaperone, span } => match span {
+                Some(span) => format!("require_chaperone {chaperone} for {span}"),
+                None => format!("require_chaperone {chaperone}"),
+            },
+            ContractInstruction::AddModification {
+                modification,
+                residue,
+            } => format!("add_modification {modification} at {}", residue.0),
+            ContractInstruction::SetPhysicsLevel(level) => {
+                format!("set_physics_level {}", level.as_str())
             }
+            ContractInstruction::SetSpanPhysics(mode) => {
+                let mode = match mode {
+                    PhysicsSpanMode::Toy => "off",
+                    PhysicsSpanMode::Physics => "on",
+                };
+                format!("physics_span {mode}")
+            }
+            ContractInstruction::SetPhysicsEngine(engine) => {
+                format!("physics_engine {}", engine.as_str())
+            }
+            ContractInstruction::SetForceField(name) => format!("force_field {name}"),
+            ContractInstruction::SetIntegrator(name) => format!("integrator {name}"),
+            ContractInstruction::SetTimestep(timestep) => match timestep {
+                Some(picoseconds) => format!("timestep {picoseconds}ps"),
+                None => "timestep default".into(),
+            },
+            ContractInstruction::SetTemperature(kelvin) => format!("temperature {kelvin}"),
+            ContractInstruction::SetAcceptanceTemperature(kelvin) => match kelvin {
+                Some(kelvin) => format!("acceptance_temperature {kelvin}"),
+                None => "acceptance_temperature off".into(),
+            },
+            ContractInstruction::SetEnvironment(name) => format!("environment {name}"),
+            ContractInstruction::SetEnvironmentParameter { parameter, value } => {
+                format!("set {} {value}", parameter.as_str())
+            }
+            ContractInstruction::RampEnvironment {
+                parameter,
+                from,
+                to,
+                steps,
+            } => format!("ramp {} {from} -> {to} over {steps} steps", parameter.as_str()),
+        }
+    }
+}
+
+/// Splits a leading `---` block (comments and blank lines may precede it) from
+/// the instruction lines and returns the index of the first instruction line.
+/// Unknown keys are ignored so newer headers still load.
+fn split_front_matter(lines: &[&str]) -> Result<(ContractMetadata, usize), ContractParseError> {
+    let mut metadata = ContractMetadata::default();
+    let Some(open) = lines.iter().position(|line| {
+        let trimmed = line.trim();
+        !trimmed.is_empty() && !trimmed.starts_with('#')
+    }) else {
+        return Ok((metadata, 0));
+    };
+    if lines[open].trim() != "---" {
+        return Ok((metadata, 0));
+    }
+    let Some(close) = lines[open + 1..]
+        .iter()
+        .position(|line| line.trim() == "---")
+        .map(|offset| open + 1 + offset)
+    else {
+        let fault = Fault::at_column(
+            column_of(lines[open], lines[open].trim_start()),
+            3,
+            "front matter is never closed with `---`",
+        );
+        return Err(fault.on_line(open, lines[open]));
+    };
+    for (index, line) in lines.iter().enumerate().take(close).skip(open + 1) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
         }
-        "physics_span" | "set_span_physics" => {
-            if let Some(instr) = parse_set_span_physics(tokens) {
-                instructions.push(instr);
+        let Some((key, value)) = line.split_once(':') else {
+            let fault = Fault::at_column(
+                column_of(line, line.trim_start()),
+                trimmed.chars().count(),
+                "expected `key: value` in front matter",
+            );
+            return Err(fault.on_line(index, line));
+        };
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        match key.trim().to_lowercase().as_str() {
+            "author" => metadata.author = Some(value.to_string()),
+            "description" => metadata.description = Some(value.to_string()),
+            "tags" => {
+                metadata.tags = value
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .split(',')
+                    .map(|tag| tag.trim().trim_matches(|c| c == '"' || c == '\''))
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect()
             }
+            "requires_physics" | "physics_level" | "physics" => match PhysicsLevel::parse(value) {
+                Some(level) => metadata.requires_physics = Some(level),
+                None => {
+                    let raw = line[key.len() + 1..].trim();
+                    let fault = Fault::at_column(
+                        column_of(line, raw),
+                        raw.chars().count().max(1),
+                        format!("unknown physics level '{value}'"),
+                    );
+                    return Err(fault.on_line(index, line));
+                }
+            },
+            _ => {}
         }
-        _ => return None,
     }
+    Ok((metadata, close + 1))
+}
+
+/// Syntax error in a `.lll` contract. `line` and `column` are 1-based and
+/// count characters; `Display` renders the message followed by the source
+/// line and a caret under the offending text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractParseError {
+    pub line: usize,
+    pub column: usize,
+    /// Characters underlined by the caret, at least one.
+    pub width: usize,
+    pub message: String,
+    pub source_line: String,
+}
+
+impl ContractParseError {
+    /// The source line with `^^^` under the offending text.
+    pub fn excerpt(&self) -> String {
+        let gutter = self.line.to_string();
+        let padding = " ".repeat(gutter.len());
+        let indent: String = self
+            .source_line
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        format!(
+            "{padding} |\n{gutter} | {}\n{padding} | {indent}{}",
+            self.source_line,
+            "^".repeat(self.width.max(1))
+        )
+    }
+}
 
-    if instructions.is_empty() {
-        None
-    } else {
-        Some(instructions)
+impl fmt::Display for ContractParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}\n{}",
+            self.line,
+            self.column,
+            self.message,
+            self.excerpt()
+        )
+    }
+}
+
+impl std::error::Error for ContractParseError {}
+
+/// Error inside one line, before the line number is known.
+#[derive(Debug)]
+struct Fault {
+    column: usize,
+    width: usize,
+    message: String,
+}
+
+impl Fault {
+    fn at_column(column: usize, width: usize, message: impl Into<String>) -> Self {
+        Self {
+            column,
+            width: width.max(1),
+            message: message.into(),
+        }
+    }
+
+    /// `index` is the 0-based position of `source` in the contract.
+    fn on_line(self, index: usize, source: &str) -> ContractParseError {
+        ContractParseError {
+            line: index + 1,
+            column: self.column,
+            width: self.width,
+            message: self.message,
+            source_line: source.to_string(),
+        }
+    }
+}
+
+/// A word or quoted string and the columns it covers in its line.
+#[derive(Clone, Debug)]
+struct Token {
+    text: String,
+    column: usize,
+    width: usize,
+}
+
+impl Token {
+    fn fault(&self, message: impl Into<String>) -> Fault {
+        Fault::at_column(self.column, self.width, message)
     }
+
+    fn is(&self, word: &str) -> bool {
+        self.text.eq_ignore_ascii_case(word)
+    }
+}
+
+/// Instruction arguments; missing ones are reported just past the line end.
+struct Args {
+    tokens: Vec<Token>,
+    end: usize,
+}
+
+impl Args {
+    fn get(&self, index: usize) -> Option<&Token> {
+        self.tokens.get(index)
+    }
+
+    fn require(&self, index: usize, expected: &str) -> Result<&Token, Fault> {
+        self.tokens
+            .get(index)
+            .ok_or_else(|| Fault::at_column(self.end, 1, format!("expected {expected}")))
+    }
+
+    /// Drops filler words such as `by` or `about`.
+    fn without(mut self, word: &str) -> Self {
+        self.tokens.retain(|token| !token.is(word));
+        self
+    }
+}
+
+fn column_of(line: &str, rest: &str) -> usize {
+    line[..line.len() - rest.len()].chars().count() + 1
+}
+
+fn separators(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_whitespace() || ",;(){}".contains(c))(input)
+}
+
+fn quoted(input: &str) -> IResult<&str, &str> {
+    alt((
+        delimited(char('"'), take_till(|c| c == '"'), char('"')),
+        delimited(char('\''), take_till(|c| c == '\''), char('\'')),
+    ))(input)
+}
+
+fn bare_word(input: &str) -> IResult<&str, &str> {
+    take_till1(|c: char| c.is_whitespace() || ",;(){}\"'#".contains(c))(input)
+}
+
+/// `value` when `parser` accepts the whole of `text`.
+fn whole<'a, T>(text: &'a str, parser: impl FnMut(&'a str) -> IResult<&'a str, T>) -> Option<T> {
+    all_consuming(parser)(text).ok().map(|(_, value)| value)
+}
+
+/// Signed decimal such as `-30`, `2.5` or `1e3`.
+fn decimal(input: &str) -> IResult<&str, f64> {
+    map_res(recognize_float, str::parse::<f64>)(input)
+}
+
+/// A decimal followed by an optional unit word: `-30deg`, `15°`.
+fn quantity(input: &str) -> IResult<&str, f64> {
+    terminated(decimal, take_while(|c: char| c.is_alphabetic() || c == '°'))(input)
+}
+
+/// A temperature in kelvin, optionally suffixed `K`: `310`, `310K`.
+fn kelvin(input: &str) -> IResult<&str, f64> {
+    terminated(decimal, opt(char('K')))(input)
+}
+
+/// Whole milliseconds, optionally suffixed `ms`.
+fn milliseconds(input: &str) -> IResult<&str, u64> {
+    terminated(map_res(digit1, str::parse::<u64>), opt(tag_no_case("ms")))(input)
+}
+
+/// Residue index, optionally behind a word such as `res` or `residue_`.
+fn residue(input: &str) -> IResult<&str, usize> {
+    preceded(
+        take_while(|c: char| c.is_alphabetic() || c == '_'),
+        map_res(digit1, str::parse::<usize>),
+    )(input)
 }
 
-fn parse_rotate(tokens: Vec<String>) -> Option<ContractInstruction> {
+/// Inclusive residue range `5-20`, `5..20` or `[5,20]`.
+fn residue_range(input: &str) -> IResult<&str, (usize, usize)> {
+    delimited(
+        opt(char('[')),
+        separated_pair(residue, alt((tag(".."), tag("-"), tag(","))), residue),
+        opt(char(']')),
+    )(input)
+}
+
+/// `key=value` or `key:value`; the value may be empty.
+fn key_value(input: &str) -> IResult<&str, (&str, &str)> {
+    separated_pair(take_till(|c| c == '=' || c == ':'), one_of("=:"), rest)(input)
+}
+
+/// Words, tags and any text after the tags of one line.
+type Lexed = (Vec<Token>, Vec<Token>, Option<Fault>);
+
+/// Splits a line into words and quoted strings, followed by its tags.
+/// Whitespace and `,;(){}` separate tokens; outside quotes, `#word` after an
+/// instruction is a tag and any other `#` starts a comment. Text after the
+/// tags comes back as a fault, for the caller to raise on moves.
+fn lex(line: &str) -> Result<Lexed, Fault> {
+    let mut tokens = Vec::new();
+    let mut tags = Vec::new();
+    let mut rest = line;
+    loop {
+        if let Ok((next, _)) = separators(rest) {
+            rest = next;
+        }
+        let tag = rest
+            .strip_prefix('#')
+            .filter(|_| !tokens.is_empty())
+            .and_then(|after| bare_word(after).ok());
+        if let Some((next, text)) = tag {
+            tags.push(Token {
+                text: text.to_string(),
+                column: column_of(line, rest),
+                width: rest[..rest.len() - next.len()].chars().count(),
+            });
+            rest = next;
+            continue;
+        }
+        if rest.is_empty() || rest.starts_with('#') {
+            return Ok((tokens, tags, None));
+        }
+        let column = column_of(line, rest);
+        if !tags.is_empty() {
+            let stray = Fault::at_column(column, rest.chars().count(), "tags must end the line");
+            return Ok((tokens, tags, Some(stray)));
+        }
+        let (next, text) = match quoted(rest).or_else(|_| bare_word(rest)) {
+            Ok(parsed) => parsed,
+            Err(_) => {
+                let width = rest.chars().count();
+                return Err(Fault::at_column(
+                    column,
+                    width,
+                    "unterminated quoted string",
+                ));
+            }
+        };
+        tokens.push(Token {
+            text: text.to_string(),
+            column,
+            width: rest[..rest.len() - next.len()].chars().count(),
+        });
+        rest = next;
+    }
+}
+
+/// The line's instruction, preceded by its tags when it has any.
+fn parse_line(raw_line: &str) -> Result<Vec<ContractInstruction>, Fault> {
+    let (mut tokens, tags, stray) = lex(raw_line)?;
     if tokens.is_empty() {
-        return None;
+        return Ok(Vec::new());
     }
+    let command = tokens.remove(0);
+    let args = Args {
+        tokens,
+        end: raw_line.trim_end().chars().count() + 1,
+    };
+
+    let instruction = parse_instruction(&command, args);
+    if tags.is_empty() || instruction.as_ref().is_ok_and(|parsed| !parsed.is_move()) {
+        // Only moves take tags; after anything else `#` starts a comment
+        return instruction.map(|parsed| vec![parsed]);
+    }
+    if let Some(stray) = stray {
+        return Err(stray);
+    }
+    let tags = tags.into_iter().map(|tag| tag.text).collect();
+    Ok(vec![ContractInstruction::SpanTags(tags), instruction?])
+}
+
+fn parse_instruction(command: &Token, args: Args) -> Result<ContractInstruction, Fault> {
+    Ok(match command.text.to_lowercase().as_str() {
+        "rotate" => parse_rotate(args)?,
+        "move_domain" | "domain_move" => parse_move_domain(args)?,
+        "close_loop" | "loop" => parse_close_loop(args)?,
+        "backrub" => parse_backrub(args)?,
+        "rotamer" | "flip_rotamer" => parse_rotamer(args)?,
+        "refine" => parse_refine(args)?,
+        "fix_geometry" | "fix" => ContractInstruction::FixGeometry {
+            duration_ms: optional_duration(args.get(0))?,
+        },
+        "simulate" | "md" => parse_simulate(args)?,
+        "clash_check" | "clash" => ContractInstruction::ClashCheck,
+        "commit" => ContractInstruction::Commit,
+        "rollback" | "revert" => ContractInstruction::Rollback,
+        "ghost" => {
+            let state = args.require(0, "`on` or `off`")?;
+            match state.text.to_lowercase().as_str() {
+                "on" | "true" | "1" => ContractInstruction::GhostMode(true),
+                "off" | "false" | "0" => ContractInstruction::GhostMode(false),
+                _ => {
+                    return Err(
+                        state.fault(format!("expected `on` or `off`, found '{}'", state.text))
+                    );
+                }
+            }
+        }
+        "span_alias" | "alias" | "label" => {
+            args.require(0, "an alias")?;
+            let alias: Vec<&str> = args
+                .tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect();
+            ContractInstruction::SpanAlias(alias.join(" "))
+        }
+        "tag" | "tags" => {
+            args.require(0, "a tag")?;
+            let tags = args
+                .tokens
+                .iter()
+                .map(|token| token.text.trim_start_matches('#').to_string())
+                .collect();
+            ContractInstruction::SpanTags(tags)
+        }
+        "phase" | "begin_phase" => {
+            let name = args.require(0, "a phase name")?;
+            ContractInstruction::BeginPhase(name.text.clone())
+        }
+        "end_phase" => ContractInstruction::EndPhase,
+        "define_domain" | "domain" => parse_define_domain(args)?,
+        "restrain" => parse_restrain(args)?,
+        "freeze" => {
+            let (start, end) = residue_span_at(args.require(0, "a residue or residue range")?)?;
+            ContractInstruction::Freeze {
+                start: ResidueId(start),
+                end: ResidueId(end),
+            }
+        }
+        "require_chaperone" | "chaperone" => parse_require_chaperone(args)?,
+        "add_modification" | "modification" | "modify" => parse_add_modification(args)?,
+        "set_physics_level" | "physics_level" | "level" => parse_set_physics_level(args)?,
+        "physics_span" | "set_span_physics" => parse_set_span_physics(args)?,
+        "physics_engine" | "engine" => {
+            let token = args.require(0, "a physics engine")?;
+            let value = bare_value(token);
+            PhysicsEngine::parse(&value)
+                .map(ContractInstruction::SetPhysicsEngine)
+                .ok_or_else(|| {
+                    token.fault(format!("unknown physics engine '{value}' (openmm, native, auto)"))
+                })?
+        }
+        "force_field" | "forcefield" => {
+            let name = args.require(0, "a force field name")?;
+            ContractInstruction::SetForceField(name.text.to_lowercase())
+        }
+        "integrator" => {
+            let name = args.require(0, "an integrator name")?;
+            ContractInstruction::SetIntegrator(name.text.to_lowercase())
+        }
+        "timestep" | "md_timestep" => parse_timestep(args)?,
+        "temperature" | "set_temperature" => {
+            ContractInstruction::SetTemperature(parse_kelvin(args.require(0, "a temperature")?)?)
+        }
+        "acceptance_temperature" | "metropolis_temperature" => {
+            let token = args.require(0, "a temperature or `off`")?;
+            match bare_value(token).to_lowercase().as_str() {
+                "off" => ContractInstruction::SetAcceptanceTemperature(None),
+                _ => ContractInstruction::SetAcceptanceTemperature(Some(parse_kelvin(token)?)),
+            }
+        }
+        "environment" | "env" => {
+            let token = args.require(0, "an environment name")?;
+            let name = bare_value(token).to_lowercase();
+            if Environment::by_name(&name).is_none() {
+                return Err(token.fault(format!(
+                    "unknown environment '{name}' (aqueous, cytosol, vacuum)"
+                )));
+            }
+            ContractInstruction::SetEnvironment(name)
+        }
+        "set" => parse_set(args)?,
+        "ramp" => parse_ramp(args)?,
+        _ => return Err(command.fault(format!("unknown instruction '{}'", command.text))),
+    })
+}
 
-    let (residue, angle, duration) = if tokens
+fn parse_rotate(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args.without("by");
+    args.require(0, "a residue or residue range")?;
+
+    let ((start, end), dihedral, angle, duration) = if args
+        .tokens
         .iter()
-        .any(|token| token.contains('=') || token.contains(':'))
+        .any(|token| token.text.contains(['=', ':']))
     {
-        parse_rotate_keyed(tokens)?
+        parse_rotate_keyed(args)?
     } else {
-        parse_rotate_positional(tokens)?
+        parse_rotate_positional(args)?
     };
 
-    Some(ContractInstruction::Rotate {
-        residue: ResidueId(residue),
+    if start == end {
+        return Ok(ContractInstruction::Rotate {
+            residue: ResidueId(start),
+            dihedral,
+            angle_degrees: angle,
+            duration_ms: duration,
+        });
+    }
+    Ok(ContractInstruction::RotateSegment {
+        start: ResidueId(start),
+        end: ResidueId(end),
+        dihedral,
         angle_degrees: angle,
         duration_ms: duration,
     })
 }
 
-fn parse_define_domain(tokens: Vec<String>) -> Option<ContractInstruction> {
-    if tokens.is_empty() {
-        return None;
-    }
+fn parse_define_domain(args: Args) -> Result<ContractInstruction, Fault> {
+    let first = args.require(0, "a residue range")?;
+    let (name, range) = match args.get(1) {
+        Some(range) if !is_domain_range(&first.text) => (Some(first.text.clone()), range),
+        _ => (None, first),
+    };
+    let (start, end) = range_at(range)?;
+
+    Ok(ContractInstruction::DefineDomain {
+        name,
+        start: ResidueId(start),
+        end: ResidueId(end),
+    })
+}
+
+/// `restrain <residue|start..end> helix|sheet [k=<kcal/mol/rad²>]`.
+fn parse_restrain(args: Args) -> Result<ContractInstruction, Fault> {
+    let (start, end) = residue_span_at(args.require(0, "a residue or residue range")?)?;
+    let token = args.require(1, "`helix` or `sheet`")?;
+    let structure = SecondaryStructure::parse(&token.text).ok_or_else(|| {
+        token.fault(format!("unknown secondary structure '{}' (helix, sheet)", token.text))
+    })?;
+    let force_constant = match args.get(2) {
+        Some(token) => match split_key_value(&token.text) {
+            Some((key, value)) if key == "k" => match value.parse::<f64>() {
+                Ok(value) if value >= 0.0 && value.is_finite() => value,
+                _ => {
+                    return Err(token.fault(format!(
+                        "expected a non-negative force constant, found '{value}'"
+                    )));
+                }
+            },
+            _ => {
+                return Err(token.fault(format!(
+                    "expected k=<force constant>, found '{}'",
+                    token.text
+                )));
+            }
+        },
+        None => DEFAULT_RESTRAINT_CONSTANT,
+    };
+
+    Ok(ContractInstruction::Restrain {
+        start: ResidueId(start),
+        end: ResidueId(end),
+        structure,
+        force_constant,
+    })
+}
 
-    let mut name: Option<String> = None;
-    let range_value = if tokens.len() == 1 {
-        tokens[0].clone()
+/// `move_domain <name|start-end> translate <dx> <dy> <dz> [duration]` or
+/// `move_domain <name|start-end> rotate <angle> [about x|y|z|end-to-end] [duration]`.
+fn parse_move_domain(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args.without("about");
+    let target = args.require(0, "a domain name or residue range")?;
+    let domain = if is_domain_range(&target.text) {
+        let (start, end) = range_at(target)?;
+        DomainSelector::Range {
+            start: ResidueId(start),
+            end: ResidueId(end),
+        }
     } else {
-        let first = &tokens[0];
-        if is_range_token(first) {
-            first.clone()
-        } else {
-            name = Some(first.clone());
-            tokens.get(1).cloned().unwrap_or_else(|| first.clone())
+        DomainSelector::Named(target.text.clone())
+    };
+    let motion_token = args.require(1, "`translate` or `rotate`")?;
+    let (motion, duration_token) = match motion_token.text.to_lowercase().as_str() {
+        "translate" | "shift" => {
+            let offset = [
+                angle_at(args.require(2, "an x offset")?)?,
+                angle_at(args.require(3, "a y offset")?)?,
+                angle_at(args.require(4, "a z offset")?)?,
+            ];
+            (DomainMotion::Translate(offset), args.get(5))
+        }
+        "rotate" | "turn" => {
+            let angle_degrees = angle_at(args.require(2, "an angle")?)?;
+            match args.get(3).and_then(|token| DomainAxis::parse(&token.text)) {
+                Some(axis) => (
+                    DomainMotion::Rotate {
+                        angle_degrees,
+                        axis,
+                    },
+                    args.get(4),
+                ),
+                None => (
+                    DomainMotion::Rotate {
+                        angle_degrees,
+                        axis: DomainAxis::EndToEnd,
+                    },
+                    args.get(3),
+                ),
+            }
+        }
+        _ => {
+            return Err(motion_token.fault(format!(
+                "expected `translate` or `rotate`, found '{}'",
+                motion_token.text
+            )));
         }
     };
+    Ok(ContractInstruction::MoveDomain {
+        domain,
+        motion,
+        duration_ms: optional_duration(duration_token)?,
+    })
+}
 
-    let (start, end) = parse_range(&range_value)?;
-
-    Some(ContractInstruction::DefineDomain {
-        name,
+/// `close_loop <start>-<end> [by <perturbation_degrees>] [duration_ms]`; the
+/// perturbation defaults to 30°.
+fn parse_close_loop(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args.without("by");
+    let (start, end) = range_at(args.require(0, "a residue range")?)?;
+    let perturbation_degrees = match args.get(1) {
+        Some(token) => angle_at(token)?,
+        None => 30.0,
+    };
+    Ok(ContractInstruction::CloseLoop {
         start: ResidueId(start),
         end: ResidueId(end),
+        perturbation_degrees,
+        duration_ms: optional_duration(args.get(2))?,
     })
 }
 
-fn parse_require_chaperone(tokens: Vec<String>) -> Option<ContractInstruction> {
-    if tokens.is_empty() {
-        return None;
+/// `backrub <residue> [by <angle>|random] [duration_ms]`.
+fn parse_backrub(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args.without("by");
+    let residue = ResidueId(residue_at(args.require(0, "a residue")?)?);
+    let angle_degrees = match args.get(1) {
+        Some(token) if token.is("random") => None,
+        Some(token) => Some(angle_at(token)?),
+        None => None,
+    };
+    Ok(ContractInstruction::Backrub {
+        residue,
+        angle_degrees,
+        duration_ms: optional_duration(args.get(2))?,
+    })
+}
+
+/// `rotamer <residue> [<name>|random] [duration_ms]`, e.g. `rotamer 12 mt`.
+fn parse_rotamer(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args.without("to");
+    let residue = ResidueId(residue_at(args.require(0, "a residue")?)?);
+    let rotamer = args
+        .get(1)
+        .filter(|token| !token.is("random"))
+        .map(|token| token.text.to_lowercase());
+    Ok(ContractInstruction::RotamerFlip {
+        residue,
+        rotamer,
+        duration_ms: optional_duration(args.get(2))?,
+    })
+}
+
+/// `refine [at] [level] [duration_ms]`; the level defaults to `gb`.
+fn parse_refine(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args.without("at");
+    let (level, rest) = match args
+        .get(0)
+        .and_then(|token| PhysicsLevel::parse(&token.text))
+    {
+        Some(level) => (level, 1),
+        None => (PhysicsLevel::Gb, 0),
+    };
+    Ok(ContractInstruction::Refine {
+        level,
+        duration_ms: optional_duration(args.get(rest))?,
+    })
+}
+
+/// `simulate <time>[fs|ps|ns] [at <level>] [duration_ms]`; bare times are ps.
+fn parse_simulate(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args.without("at");
+    let (picoseconds, mut rest) = parse_md_time(&args, "a simulated time")?;
+    if picoseconds > SIMULATE_MAX_PICOSECONDS {
+        return Err(args.require(0, "a simulated time")?.fault(format!(
+            "simulated time {picoseconds} ps is above the {SIMULATE_MAX_PICOSECONDS} ps cap"
+        )));
+    }
+    let level = args
+        .get(rest)
+        .and_then(|token| PhysicsLevel::parse(&token.text));
+    if level.is_some() {
+        rest += 1;
+    }
+    Ok(ContractInstruction::Simulate {
+        picoseconds,
+        level,
+        duration_ms: optional_duration(args.get(rest))?,
+    })
+}
+
+/// `timestep <time>[fs|ps]` or `timestep default`; bare times are ps.
+fn parse_timestep(args: Args) -> Result<ContractInstruction, Fault> {
+    let token = args.require(0, "an MD timestep or `default`")?;
+    if token.is("default") || token.is("auto") {
+        return Ok(ContractInstruction::SetTimestep(None));
     }
+    let (picoseconds, _) = parse_md_time(&args, "an MD timestep")?;
+    Ok(ContractInstruction::SetTimestep(Some(picoseconds)))
+}
+
+/// Positive time in ps from the first argument, written as `2fs`, `2 fs` or a
+/// bare number of ps; also returns how many tokens it took.
+fn parse_md_time(args: &Args, expected: &str) -> Result<(f64, usize), Fault> {
+    let token = args.require(0, expected)?;
+    let not_positive = || token.fault(format!("expected a positive time, found '{}'", token.text));
+    let (value, unit) = whole(&token.text, pair(decimal, alpha0)).ok_or_else(not_positive)?;
+    let mut unit = unit.to_ascii_lowercase();
+    let mut rest = 1;
+    if unit.is_empty()
+        && let Some(next) = args.get(1)
+        && ["fs", "ps", "ns"]
+            .iter()
+            .any(|candidate| next.is(candidate))
+    {
+        unit = next.text.to_ascii_lowercase();
+        rest = 2;
+    }
+    let scale = match unit.as_str() {
+        "" | "ps" => 1.0,
+        "fs" => 1e-3,
+        "ns" => 1e3,
+        _ => return Err(token.fault(format!("unknown time unit '{unit}' (fs, ps, ns)"))),
+    };
+    if value > 0.0 && value.is_finite() {
+        Ok((value * scale, rest))
+    } else {
+        Err(not_positive())
+    }
+}
+
+fn parse_require_chaperone(args: Args) -> Result<ContractInstruction, Fault> {
     let mut chaperone_parts = Vec::new();
     let mut span_parts = Vec::new();
     let mut in_span = false;
 
-    for token in tokens {
-        let lower = token.to_lowercase();
-        if lower == "for" || lower == "span" {
+    for token in &args.tokens {
+        if token.is("for") || token.is("span") {
             in_span = true;
             continue;
         }
         if in_span {
-            span_parts.push(token);
+            span_parts.push(token.text.as_str());
         } else {
-            chaperone_parts.push(token);
+            chaperone_parts.push(token.text.as_str());
         }
     }
 
     if chaperone_parts.is_empty() {
-        return None;
+        let found = args
+            .get(0)
+            .map(|token| token.fault("expected a chaperone name before the span"));
+        return Err(
+            found.unwrap_or_else(|| Fault::at_column(args.end, 1, "expected a chaperone name"))
+        );
     }
     let chaperone = chaperone_parts.join(" ");
     let span = if span_parts.is_empty() {
@@ -231,293 +1450,276 @@ fn parse_require_chaperone(tokens: Vec<String>) -> Option<ContractInstruction> {
         Some(span_parts.join(" "))
     };
 
-    Some(ContractInstruction::RequireChaperone { chaperone, span })
+    Ok(ContractInstruction::RequireChaperone { chaperone, span })
 }
 
-fn parse_add_modification(tokens: Vec<String>) -> Option<ContractInstruction> {
-    if tokens.is_empty() {
-        return None;
-    }
-
-    let mut modification_parts = Vec::new();
-    let mut residue_token: Option<String> = None;
-    let mut iter = tokens.into_iter();
-    while let Some(token) = iter.next() {
-        let lower = token.to_lowercase();
-        if lower == "at" || lower == "on" {
-            residue_token = iter.next();
-            break;
-        } else {
-            modification_parts.push(token);
-        }
-    }
-    let modification = modification_parts.join(" ");
-    if modification.is_empty() {
-        return None;
+/// `add_modification <name> at|on <residue>`.
+fn parse_add_modification(args: Args) -> Result<ContractInstruction, Fault> {
+    let Some(at) = args
+        .tokens
+        .iter()
+        .position(|token| token.is("at") || token.is("on"))
+    else {
+        args.require(0, "a modification name")?;
+        return Err(Fault::at_column(args.end, 1, "expected `at <residue>`"));
+    };
+    if at == 0 {
+        return Err(args.tokens[0].fault("expected a modification name before the residue"));
     }
-    let residue_token = residue_token.unwrap_or_default();
-    let residue_index = parse_residue(&residue_token)?;
+    let modification: Vec<&str> = args.tokens[..at]
+        .iter()
+        .map(|token| token.text.as_str())
+        .collect();
+    let residue = residue_at(args.require(at + 1, "a residue")?)?;
 
-    Some(ContractInstruction::AddModification {
-        modification,
-        residue: ResidueId(residue_index),
+    Ok(ContractInstruction::AddModification {
+        modification: modification.join(" "),
+        residue: ResidueId(residue),
     })
 }
 
-fn parse_set_physics_level(tokens: Vec<String>) -> Option<ContractInstruction> {
-    if tokens.is_empty() {
-        return None;
+/// The value of a `key=value` / `key: value` token, or the token itself.
+fn bare_value(token: &Token) -> String {
+    match split_key_value(&token.text) {
+        Some((_, value)) => value,
+        None => token.text.clone(),
     }
-
-    let raw = tokens[0].clone();
-    let value = if raw.contains(['=', ':']) {
-        split_key_value(&raw)?.1
-    } else {
-        raw
-    };
-
-    let level = match value.to_lowercase().as_str() {
-        "toy" | "none" | "off" => PhysicsLevel::Toy,
-        "coarse" | "cg" => PhysicsLevel::Coarse,
-        "gb" | "implicit" => PhysicsLevel::Gb,
-        "full" | "explicit" => PhysicsLevel::Full,
-        _ => return None,
-    };
-
-    Some(ContractInstruction::SetPhysicsLevel(level))
 }
 
-fn parse_set_span_physics(tokens: Vec<String>) -> Option<ContractInstruction> {
-    if tokens.is_empty() {
-        return None;
-    }
-
-    let raw = tokens[0].clone();
-    let value = if raw.contains(['=', ':']) {
-        split_key_value(&raw)?.1
-    } else {
-        raw
-    };
+fn parse_set_physics_level(args: Args) -> Result<ContractInstruction, Fault> {
+    let token = args.require(0, "a physics level")?;
+    let value = bare_value(token);
+    PhysicsLevel::parse(&value)
+        .map(ContractInstruction::SetPhysicsLevel)
+        .ok_or_else(|| token.fault(format!("unknown physics level '{value}'")))
+}
 
+fn parse_set_span_physics(args: Args) -> Result<ContractInstruction, Fault> {
+    let token = args.require(0, "`on` or `off`")?;
+    let value = bare_value(token);
     let mode = match value.to_lowercase().as_str() {
         "toy" | "off" | "none" => PhysicsSpanMode::Toy,
         "physics" | "on" | "gb" | "full" => PhysicsSpanMode::Physics,
-        _ => return None,
+        _ => return Err(token.fault(format!("expected `on` or `off`, found '{value}'"))),
     };
 
-    Some(ContractInstruction::SetSpanPhysics(mode))
+    Ok(ContractInstruction::SetSpanPhysics(mode))
 }
 
-fn parse_range(token: &str) -> Option<(usize, usize)> {
-    let cleaned = token.trim_matches(|c: char| c == '[' || c == ']' || c.is_whitespace());
-    let mut parts = cleaned.split(|c| c == '-' || c == ',');
-    let start = parts.next()?.trim();
-    let end = parts.next()?.trim();
-    let start_id = parse_residue(start)?;
-    let end_id = parse_residue(end)?;
-    if start_id <= end_id {
-        Some((start_id, end_id))
-    } else {
-        Some((end_id, start_id))
-    }
+/// `set <parameter> <value>`; `set temperature` is the `temperature` line.
+fn parse_set(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args.without("to").without("=");
+    let parameter = parameter_at(args.require(0, "an environment parameter")?)?;
+    let value = parameter_value_at(parameter, args.require(1, "a value")?)?;
+    Ok(match parameter {
+        EnvironmentParameter::Temperature => ContractInstruction::SetTemperature(value),
+        _ => ContractInstruction::SetEnvironmentParameter { parameter, value },
+    })
+}
+
+/// `ramp <parameter> <from> -> <to> over <steps> [steps]`.
+fn parse_ramp(args: Args) -> Result<ContractInstruction, Fault> {
+    let args = args
+        .without("from")
+        .without("->")
+        .without("to")
+        .without("over")
+        .without("steps");
+    let parameter = parameter_at(args.require(0, "an environment parameter")?)?;
+    let from = parameter_value_at(parameter, args.require(1, "a start value")?)?;
+    let to = parameter_value_at(parameter, args.require(2, "an end value")?)?;
+    let steps_token = args.require(3, "a number of steps")?;
+    let steps = steps_token.text.parse().map_err(|_| {
+        steps_token.fault(format!("expected a number of steps, found '{}'", steps_token.text))
+    })?;
+    Ok(ContractInstruction::RampEnvironment {
+        parameter,
+        from,
+        to,
+        steps,
+    })
 }
 
-fn is_range_token(token: &str) -> bool {
-    token.contains('-') && token.chars().any(|c| c.is_ascii_digit())
+fn parameter_at(token: &Token) -> Result<EnvironmentParameter, Fault> {
+    EnvironmentParameter::parse(&token.text).ok_or_else(|| {
+        token.fault(format!(
+            "unknown environment parameter '{}' (temperature, viscosity, dielectric, \
+             crowding, ionic_strength)",
+            token.text
+        ))
+    })
 }
 
-fn parse_rotate_positional(tokens: Vec<String>) -> Option<(usize, f64, u64)> {
-    let residue_token = tokens.get(0)?.as_str();
-    let angle_token = tokens.get(1)?.as_str();
-    let duration_token = tokens.get(2).map(|s| s.as_str());
+fn parameter_value_at(parameter: EnvironmentParameter, token: &Token) -> Result<f64, Fault> {
+    let value = angle_at(token)?;
+    parameter
+        .check(value)
+        .map_err(|err| token.fault(err))?;
+    Ok(value)
+}
 
-    let residue = parse_residue(residue_token)?;
-    let angle = parse_angle(angle_token)?;
-    let duration = duration_token.and_then(parse_duration).unwrap_or(1);
+fn parse_kelvin(token: &Token) -> Result<f64, Fault> {
+    let value = bare_value(token);
+    // Any other unit (`25C`, `77F`) is refused rather than read as kelvin
+    match whole(&value, kelvin) {
+        Some(temperature) if temperature > 0.0 && temperature.is_finite() => Ok(temperature),
+        _ => Err(token.fault(format!("expected a temperature in K, found '{value}'"))),
+    }
+}
 
-    Some((residue, angle, duration))
+fn parse_range(token: &str) -> Option<(usize, usize)> {
+    let (start, end) = whole(token, residue_range)?;
+    Some((start.min(end), start.max(end)))
 }
 
-fn parse_rotate_keyed(tokens: Vec<String>) -> Option<(usize, f64, u64)> {
-    let mut key_values: HashMap<String, String> = HashMap::new();
-    let mut index = 0;
+/// Whether a domain token is a residue range rather than a name; names may
+/// hold `-digit` (`helix-2`), so only tokens that parse as a range, or start
+/// like one, count.
+fn is_domain_range(token: &str) -> bool {
+    parse_range(token).is_some()
+        || token
+            .trim_start_matches('[')
+            .starts_with(|c: char| c.is_ascii_digit())
+}
 
-    while index < tokens.len() {
-        let token = tokens[index].clone();
-        let cleaned = token.trim_matches(|c| matches!(c, ',' | ';'));
-        if cleaned.is_empty() {
-            index += 1;
-            continue;
+/// A single residue, or an inclusive `10..25` / `10-25` segment.
+fn parse_residue_span(token: &str) -> Option<(usize, usize)> {
+    parse_range(token).or_else(|| parse_residue(token).map(|residue| (residue, residue)))
+}
+
+/// `rotate <residue|start..end> [dihedral] [by] <angle> [duration]`, e.g.
+/// `rotate 12 chi1 60` or `rotate 10..25 phi by 5`.
+fn parse_rotate_positional(mut args: Args) -> Result<((usize, usize), Dihedral, f64, u64), Fault> {
+    let dihedral = match args.get(1).and_then(|token| Dihedral::parse(&token.text)) {
+        Some(dihedral) => {
+            args.tokens.remove(1);
+            dihedral
         }
+        None => Dihedral::Phi,
+    };
+    let residues = residue_span_at(args.require(0, "a residue or residue range")?)?;
+    let angle = angle_at(args.require(1, "an angle")?)?;
+    let duration = optional_duration(args.get(2))?;
+
+    Ok((residues, dihedral, angle, duration))
+}
+
+fn parse_rotate_keyed(args: Args) -> Result<((usize, usize), Dihedral, f64, u64), Fault> {
+    let mut key_values: HashMap<String, Token> = HashMap::new();
+    let mut positional: Vec<Token> = Vec::new();
+    let mut tokens = args.tokens.iter().peekable();
 
-        if let Some((key, value)) = split_key_value(cleaned) {
+    while let Some(token) = tokens.next() {
+        if Dihedral::parse(&token.text).is_some() {
+            key_values.insert("dihedral".into(), token.clone());
+        } else if let Some((key, value)) = split_key_value(&token.text) {
             if !value.is_empty() {
-                key_values.insert(key, value);
-            } else if index + 1 < tokens.len() {
-                let next = tokens[index + 1].trim_matches(|c| matches!(c, ',' | ';'));
-                key_values.insert(key, next.to_string());
-                index += 1;
+                let text = value;
+                key_values.insert(
+                    key,
+                    Token {
+                        text,
+                        ..token.clone()
+                    },
+                );
+            } else if let Some(next) = tokens.next() {
+                key_values.insert(key, next.clone());
             }
         } else {
             // Fallback for positional tokens sprinkled into keyed syntax.
-            key_values
-                .entry("positional".into())
-                .and_modify(|existing| {
-                    existing.push(' ');
-                    existing.push_str(cleaned);
-                })
-                .or_insert_with(|| cleaned.to_string());
+            positional.push(token.clone());
         }
-
-        index += 1;
     }
 
     // Support hybrid definitions like "residue=1" or "residue 1" captured above.
-    if let Some(positional) = key_values.remove("positional") {
-        let mut positional_tokens: Vec<String> = positional
-            .split_whitespace()
-            .map(|tok| tok.to_string())
-            .collect();
-        if !positional_tokens.is_empty() {
-            key_values
-                .entry("residue".into())
-                .or_insert_with(|| positional_tokens.remove(0));
-        }
-        if !positional_tokens.is_empty() {
-            key_values
-                .entry("angle".into())
-                .or_insert_with(|| positional_tokens.remove(0));
-        }
-        if !positional_tokens.is_empty() {
-            key_values
-                .entry("duration".into())
-                .or_insert_with(|| positional_tokens.remove(0));
+    let mut positional = positional.into_iter();
+    for key in ["residue", "angle", "duration"] {
+        if key_values.contains_key(key) {
+            continue;
         }
+        match positional.next() {
+            Some(token) => key_values.insert(key.into(), token),
+            None => break,
+        };
     }
 
-    let residue = key_values
-        .get("residue")
-        .or_else(|| key_values.get("res"))
-        .or_else(|| key_values.get("id"))
-        .and_then(|value| parse_residue(value))?;
+    let lookup = |keys: &[&str]| keys.iter().find_map(|key| key_values.get(*key));
+    let missing = |expected: &str| Fault::at_column(args.end, 1, format!("expected {expected}"));
+
+    let residues = lookup(&["residue", "residues", "res", "id", "segment"])
+        .ok_or_else(|| missing("`residue=<index>`"))
+        .and_then(residue_span_at)?;
+    let angle = lookup(&["angle", "theta", "deg"])
+        .ok_or_else(|| missing("`angle=<degrees>`"))
+        .and_then(angle_at)?;
+    let duration = optional_duration(lookup(&["duration", "time", "ms"]))?;
+    let dihedral = match lookup(&["dihedral", "torsion"]) {
+        Some(token) => Dihedral::parse(&token.text)
+            .ok_or_else(|| token.fault(format!("unknown dihedral '{}'", token.text)))?,
+        None => Dihedral::Phi,
+    };
 
-    let angle = key_values
-        .get("angle")
-        .or_else(|| key_values.get("theta"))
-        .or_else(|| key_values.get("deg"))
-        .and_then(|value| parse_angle(value))?;
+    Ok((residues, dihedral, angle, duration))
+}
 
-    let duration = key_values
-        .get("duration")
-        .or_else(|| key_values.get("time"))
-        .or_else(|| key_values.get("ms"))
-        .and_then(|value| parse_duration(value))
-        .unwrap_or(1);
+fn residue_at(token: &Token) -> Result<usize, Fault> {
+    parse_residue(&token.text)
+        .ok_or_else(|| token.fault(format!("expected a residue index, found '{}'", token.text)))
+}
 
-    Some((residue, angle, duration))
+fn residue_span_at(token: &Token) -> Result<(usize, usize), Fault> {
+    parse_residue_span(&token.text).ok_or_else(|| {
+        token.fault(format!(
+            "expected a residue or residue range, found '{}'",
+            token.text
+        ))
+    })
 }
 
-fn tokenize(input: &str) -> Vec<String> {
-    let mut tokens = Vec::new();
-    let mut current = String::new();
-    let mut in_quotes = false;
-    let mut quote_char = '\0';
-
-    for ch in input.chars() {
-        if in_quotes {
-            if ch == quote_char {
-                tokens.push(current.clone());
-                current.clear();
-                in_quotes = false;
-            } else {
-                current.push(ch);
-            }
-            continue;
-        }
+fn range_at(token: &Token) -> Result<(usize, usize), Fault> {
+    parse_range(&token.text).ok_or_else(|| {
+        token.fault(format!(
+            "expected a residue range like 5-20, found '{}'",
+            token.text
+        ))
+    })
+}
 
-        match ch {
-            '"' | '\'' => {
-                if !current.is_empty() {
-                    tokens.push(current.clone());
-                    current.clear();
-                }
-                in_quotes = true;
-                quote_char = ch;
-            }
-            '{' | '}' | '(' | ')' => {
-                if !current.is_empty() {
-                    tokens.push(current.clone());
-                    current.clear();
-                }
-            }
-            ',' | ';' => {
-                if !current.is_empty() {
-                    tokens.push(current.clone());
-                    current.clear();
-                }
-            }
-            c if c.is_whitespace() => {
-                if !current.is_empty() {
-                    tokens.push(current.clone());
-                    current.clear();
-                }
-            }
-            _ => current.push(ch),
-        }
-    }
+fn angle_at(token: &Token) -> Result<f64, Fault> {
+    parse_angle(&token.text)
+        .ok_or_else(|| token.fault(format!("expected a number, found '{}'", token.text)))
+}
 
-    if !current.is_empty() {
-        tokens.push(current);
+/// Trailing duration in ms; one when the line stops before it.
+fn optional_duration(token: Option<&Token>) -> Result<u64, Fault> {
+    match token {
+        Some(token) => parse_duration(&token.text).ok_or_else(|| {
+            token.fault(format!("expected a duration in ms, found '{}'", token.text))
+        }),
+        None => Ok(1),
     }
-
-    tokens
 }
 
 fn split_key_value(token: &str) -> Option<(String, String)> {
-    let delimiter_index = token.find(['=', ':'])?;
-    let key = token[..delimiter_index]
-        .trim()
-        .trim_matches(|c| c == '"' || c == '\'');
-    let value = token[delimiter_index + 1..]
-        .trim()
-        .trim_matches(|c| c == '"' || c == '\'');
-
-    Some((key.to_lowercase(), value.to_string()))
+    let (key, value) = whole(token, key_value)?;
+    let unquote = |text: &str| {
+        text.trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string()
+    };
+    Some((unquote(key).to_lowercase(), unquote(value)))
 }
 
 fn parse_residue(token: &str) -> Option<usize> {
-    let cleaned = token
-        .trim_matches(|c: char| !c.is_ascii_alphanumeric())
-        .trim_start_matches(|c: char| c.is_alphabetic() || c == '_')
-        .replace('_', "");
-    cleaned.parse::<usize>().ok()
+    whole(token, residue)
 }
 
 fn parse_angle(token: &str) -> Option<f64> {
-    let cleaned = extract_numeric(token, |c| matches!(c, '.' | '-' | '+'))?;
-    cleaned.parse::<f64>().ok()
+    whole(token, quantity)
 }
 
 fn parse_duration(token: &str) -> Option<u64> {
-    let cleaned = extract_numeric(token, |_| false)?;
-    cleaned.parse::<u64>().ok()
-}
-
-fn extract_numeric<F>(token: &str, extra: F) -> Option<String>
-where
-    F: Fn(char) -> bool,
-{
-    let mut numeric = String::new();
-    for ch in token.chars() {
-        if ch.is_ascii_digit() || extra(ch) {
-            numeric.push(ch);
-        }
-    }
-    if numeric.is_empty() {
-        None
-    } else {
-        Some(numeric)
-    }
+    whole(token, milliseconds)
 }
 
 #[cfg(test)]
@@ -535,7 +1737,7 @@ mod tests {
             "ghost off",
             "span_alias alpha helix",
         ];
-        let contract = FoldingContract::from_lines(&lines);
+        let contract = FoldingContract::from_lines(&lines).unwrap();
         assert_eq!(contract.instructions.len(), lines.len());
         assert!(matches!(
             contract.instructions[0],
@@ -558,13 +1760,14 @@ mod tests {
     #[test]
     fn parses_key_value_rotation_syntax() {
         let lines = ["rotate residue=4 angle=45deg duration=5ms"];
-        let contract = FoldingContract::from_lines(&lines);
+        let contract = FoldingContract::from_lines(&lines).unwrap();
         assert_eq!(contract.instructions.len(), 1);
         match &contract.instructions[0] {
             ContractInstruction::Rotate {
                 residue,
                 angle_degrees,
                 duration_ms,
+                ..
             } => {
                 assert_eq!(residue.0, 4);
                 assert!((angle_degrees - 45.0).abs() < 1e-6);
@@ -574,16 +1777,280 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_named_dihedrals() {
+        let contract = FoldingContract::from_lines(&[
+            "rotate 12 psi -30",
+            "rotate 12 chi1 60 4",
+            "rotate residue=3 dihedral=omega angle=5",
+            "rotate residue=3 chi2 angle=10",
+            "rotate 2 15",
+        ])
+        .unwrap();
+        let rotations: Vec<(usize, Dihedral, f64, u64)> = contract
+            .instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                ContractInstruction::Rotate {
+                    residue,
+                    dihedral,
+                    angle_degrees,
+                    duration_ms,
+                } => Some((residue.0, *dihedral, *angle_degrees, *duration_ms)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            rotations,
+            [
+                (12, Dihedral::Psi, -30.0, 1),
+                (12, Dihedral::Chi1, 60.0, 4),
+                (3, Dihedral::Omega, 5.0, 1),
+                (3, Dihedral::Chi2, 10.0, 1),
+                (2, Dihedral::Phi, 15.0, 1),
+            ]
+        );
+        let rendered = contract.to_lll();
+        assert!(rendered.contains("dihedral=chi1"));
+        assert_eq!(
+            FoldingContract::from_lines(&rendered.lines().collect::<Vec<_>>())
+                .unwrap()
+                .to_lll(),
+            rendered
+        );
+    }
+
+    #[test]
+    fn parses_segment_rotations() {
+        let contract = FoldingContract::from_lines(&[
+            "rotate 10..25 phi by 5",
+            "rotate residues=4-8 dihedral=psi angle=-3 duration=2",
+            "rotate 7..7 by 1",
+        ])
+        .unwrap();
+        assert_eq!(contract.instructions.len(), 3);
+        let span = contract.instructions[0].rotation_span().unwrap();
+        assert!(matches!(
+            contract.instructions[0],
+            ContractInstruction::RotateSegment { .. }
+        ));
+        assert_eq!((span.first.0, span.last.0), (10, 25));
+        assert_eq!(span.residues().count(), 16);
+        assert_eq!(span.angle_degrees, 5.0);
+        let keyed = contract.instructions[1].rotation_span().unwrap();
+        assert_eq!((keyed.first.0, keyed.last.0), (4, 8));
+        assert_eq!((keyed.dihedral, keyed.duration_ms), (Dihedral::Psi, 2));
+        assert!(matches!(
+            contract.instructions[2],
+            ContractInstruction::Rotate { .. }
+        ));
+
+        let rendered = contract.to_lll();
+        assert!(rendered.starts_with("rotate 10..25 phi by 5.000 1\n"));
+        assert_eq!(
+            FoldingContract::from_lines(&rendered.lines().collect::<Vec<_>>())
+                .unwrap()
+                .to_lll(),
+            rendered
+        );
+    }
+
+    #[test]
+    fn parses_domain_moves() {
+        let contract = FoldingContract::from_lines(&[
+            "move_domain helixA translate 2 0 -1.5 4",
+            "move_domain 5-20 rotate 15 about z",
+            "move_domain helixB rotate -10",
+        ])
+        .unwrap();
+        assert_eq!(contract.instructions.len(), 3);
+        match &contract.instructions[0] {
+            ContractInstruction::MoveDomain {
+                domain,
+                motion,
+                duration_ms,
+            } => {
+                assert_eq!(domain, &DomainSelector::Named("helixA".into()));
+                assert_eq!(*motion, DomainMotion::Translate([2.0, 0.0, -1.5]));
+                assert_eq!(*duration_ms, 4);
+            }
+            other => panic!("unexpected instruction: {other:?}"),
+        }
+        assert!(matches!(
+            &contract.instructions[1],
+            ContractInstruction::MoveDomain {
+                domain: DomainSelector::Range { .. },
+                motion: DomainMotion::Rotate {
+                    axis: DomainAxis::Z,
+                    ..
+                },
+                ..
+            }
+        ));
+        assert!(matches!(
+            &contract.instructions[2],
+            ContractInstruction::MoveDomain {
+                motion: DomainMotion::Rotate {
+                    axis: DomainAxis::EndToEnd,
+                    ..
+                },
+                ..
+            }
+        ));
+
+        // Names may carry a dash and a number without reading as a range
+        let named = FoldingContract::from_lines(&[
+            "define_domain helix-2 5-20",
+            "move_domain helix-2 rotate 10",
+        ])
+        .unwrap();
+        assert!(matches!(
+            &named.instructions[0],
+            ContractInstruction::DefineDomain { name: Some(name), .. } if name == "helix-2"
+        ));
+        assert!(matches!(
+            &named.instructions[1],
+            ContractInstruction::MoveDomain {
+                domain: DomainSelector::Named(name),
+                ..
+            } if name == "helix-2"
+        ));
+        assert!(FoldingContract::from_lines(&["move_domain 5-x rotate 10"]).is_err());
+
+        let rendered = contract.to_lll();
+        assert_eq!(
+            FoldingContract::from_lines(&rendered.lines().collect::<Vec<_>>())
+                .unwrap()
+                .to_lll(),
+            rendered
+        );
+
+        let loops = FoldingContract::from_lines(&["close_loop 10..15 by 45 3", "close_loop 20-26"])
+            .unwrap();
+        assert!(matches!(
+            loops.instructions[..],
+            [
+                ContractInstruction::CloseLoop {
+                    start: ResidueId(10),
+                    end: ResidueId(15),
+                    duration_ms: 3,
+                    ..
+                },
+                ContractInstruction::CloseLoop {
+                    perturbation_degrees: 30.0,
+                    ..
+                }
+            ]
+        ));
+
+        let backrubs = FoldingContract::from_lines(&["backrub 7 by -12 2", "backrub 8"]).unwrap();
+        assert!(matches!(
+            backrubs.instructions[..],
+            [
+                ContractInstruction::Backrub {
+                    residue: ResidueId(7),
+                    angle_degrees: Some(-12.0),
+                    duration_ms: 2,
+                },
+                ContractInstruction::Backrub {
+                    angle_degrees: None,
+                    ..
+                }
+            ]
+        ));
+        assert_eq!(
+            backrubs.to_lll(),
+            "backrub 7 by -12.000 2\nbackrub 8 random 1\n"
+        );
+
+        let flips = FoldingContract::from_lines(&["rotamer 5 to M-85 3", "rotamer 6"]).unwrap();
+        assert_eq!(flips.move_count(), 2);
+        assert!(flips.instructions[0].scaled(0.5).is_none());
+        assert_eq!(flips.to_lll(), "rotamer 5 m-85 3\nrotamer 6 random 1\n");
+
+        let refines = FoldingContract::from_lines(&["refine", "refine at full 5"]).unwrap();
+        assert_eq!(refines.to_lll(), "refine gb 1\nrefine full 5\n");
+
+        let fixes = FoldingContract::from_lines(&["fix_geometry", "fix 4"]).unwrap();
+        assert_eq!(fixes.to_lll(), "fix_geometry 1\nfix_geometry 4\n");
+
+        let bursts = FoldingContract::from_lines(&[
+            "simulate 10ps at coarse",
+            "md 2 ns 3",
+            "simulate 500fs gb",
+        ])
+        .unwrap();
+        assert_eq!(
+            bursts.to_lll(),
+            "simulate 10ps at coarse 1\nsimulate 2000ps 3\nsimulate 0.5ps at gb 1\n"
+        );
+        assert!(FoldingContract::from_lines(&["simulate 10us"]).is_err());
+        assert!(FoldingContract::from_lines(&["simulate -1ps"]).is_err());
+        assert!(FoldingContract::from_lines(&["simulate 10 ns"]).is_ok());
+        let err = FoldingContract::from_lines(&["simulate 11ns"]).unwrap_err();
+        assert!(err.to_string().contains("above the 10000 ps cap"), "{err}");
+        let scaled = bursts.instructions[1].scaled(10.0).unwrap();
+        assert_eq!(scaled.to_lll(), "simulate 10000ps 3");
+    }
+
+    #[test]
+    fn values_follow_the_argument_grammar() {
+        let contract = FoldingContract::from_lines(&[
+            "rotate res4 -30deg 5ms",
+            "rotate [2..6] psi 15° 2",
+            "temperature 310K",
+        ])
+        .unwrap();
+        match &contract.instructions[0] {
+            ContractInstruction::Rotate {
+                residue,
+                angle_degrees,
+                duration_ms,
+                ..
+            } => assert_eq!((residue.0, *angle_degrees, *duration_ms), (4, -30.0, 5)),
+            other => panic!("unexpected instruction: {other:?}"),
+        }
+        assert!(matches!(
+            contract.instructions[1],
+            ContractInstruction::RotateSegment {
+                start: ResidueId(2),
+                end: ResidueId(6),
+                ..
+            }
+        ));
+        assert!(matches!(
+            contract.instructions[2],
+            ContractInstruction::SetTemperature(kelvin) if kelvin == 310.0
+        ));
+        let err = FoldingContract::parse("temperature 25C").unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (1, 13, 3));
+        assert!(err.message.contains("in K, found '25C'"), "{err}");
+
+        // Stray characters inside a value are errors, not silently dropped
+        for line in [
+            "rotate 2 1.5.2",
+            "rotate 2 30 1.5",
+            "rotate 2x 30",
+            "close_loop 1-6-9",
+            "acceptance_temperature 300F",
+            "temperature 300k",
+        ] {
+            let err = FoldingContract::from_lines(&[line]).unwrap_err();
+            assert_eq!(err.line, 1, "{line}");
+        }
+    }
+
     #[test]
     fn parses_case_insensitive_and_inline_comments() {
         let lines = ["ROTATE residue:7 angle: -30 deg duration: 2 ms # comment"];
-        let contract = FoldingContract::from_lines(&lines);
+        let contract = FoldingContract::from_lines(&lines).unwrap();
         assert_eq!(contract.instructions.len(), 1);
         match &contract.instructions[0] {
             ContractInstruction::Rotate {
                 residue,
                 angle_degrees,
                 duration_ms,
+                ..
             } => {
                 assert_eq!(residue.0, 7);
                 assert!((*angle_degrees + 30.0).abs() < 1e-6);
@@ -602,7 +2069,7 @@ mod tests {
             "set_physics_level GB",
             "physics_span on",
         ];
-        let contract = FoldingContract::from_lines(&lines);
+        let contract = FoldingContract::from_lines(&lines).unwrap();
         assert_eq!(contract.instructions.len(), 5);
 
         match &contract.instructions[0] {
@@ -647,4 +2114,190 @@ mod tests {
             other => panic!("unexpected instruction: {other:?}"),
         }
     }
+
+    #[test]
+    fn to_lll_round_trips_through_parser() {
+        let lines = [
+            "rotate residue=4 angle=-12.5 duration=3",
+            "span_alias helix push",
+            "define_domain helixA 5-20",
+            "freeze 1..10",
+            "freeze 12",
+            "restrain 5..15 helix k=10",
+            "restrain 20..24 sheet k=2.5",
+            "require_chaperone Hsp70 for helixA",
+            "add_modification phosphorylation at 50",
+            "set_physics_level gb",
+            "physics_span on",
+            "physics_engine native",
+            "force_field amber99sb",
+            "integrator brownian",
+            "timestep 0.002ps",
+            "timestep default",
+            "temperature 350",
+            "acceptance_temperature 612.5",
+            "acceptance_temperature off",
+            "ghost on",
+            "clash_check",
+            "commit",
+        ];
+        let contract = FoldingContract::from_lines(&lines).unwrap();
+        let rendered = contract.to_lll();
+        let reparsed_lines: Vec<&str> = rendered.lines().collect();
+        let reparsed = FoldingContract::from_lines(&reparsed_lines).unwrap();
+        assert_eq!(reparsed.instructions.len(), lines.len());
+        assert_eq!(reparsed.to_lll(), rendered);
+    }
+
+    #[test]
+    fn parses_front_matter_and_checks_required_level() {
+        let lines = [
+            "# header comment",
+            "---",
+            "author: Ada",
+            "description: hairpin benchmark",
+            "tags: [benchmark, gb1]",
+            "requires_physics: gb",
+            "---",
+            "rotate 0 30.0 2",
+        ];
+        let contract = FoldingContract::from_lines(&lines).unwrap();
+        assert_eq!(contract.metadata.author.as_deref(), Some("Ada"));
+        assert_eq!(contract.metadata.tags, ["benchmark", "gb1"]);
+        assert_eq!(contract.metadata.requires_physics, Some(PhysicsLevel::Gb));
+        assert_eq!(contract.instructions.len(), 1);
+        assert!(contract.check_physics_level(PhysicsLevel::Toy).is_err());
+        assert!(contract.check_physics_level(PhysicsLevel::Full).is_ok());
+
+        let rendered = contract.to_lll();
+        let reparsed = FoldingContract::from_lines(&rendered.lines().collect::<Vec<_>>()).unwrap();
+        assert_eq!(reparsed.metadata, contract.metadata);
+
+        let upgraded = FoldingContract::from_lines(&[
+            "---",
+            "requires_physics: gb",
+            "---",
+            "set_physics_level implicit",
+            "rotate 0 30.0 2",
+        ])
+        .unwrap();
+        assert!(upgraded.check_physics_level(PhysicsLevel::Toy).is_ok());
+    }
+
+    #[test]
+    fn reports_line_and_column_of_malformed_instructions() {
+        let source =
+            "---\nauthor: Ada\n---\n\n# warm up\nrotate 3 psi 20\nrotate 4 psi by fast 2\n";
+        let err = FoldingContract::parse(source).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (7, 17, 4));
+        assert!(err.message.contains("'fast'"));
+        assert!(
+            err.to_string()
+                .ends_with("7 | rotate 4 psi by fast 2\n  |                 ^^^^")
+        );
+
+        let err = FoldingContract::parse("commit\n  twist 4 20").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.message, "unknown instruction 'twist'");
+
+        let err = FoldingContract::parse("backrub").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 8));
+        let err = FoldingContract::parse("span_alias 'open ended").unwrap_err();
+        assert_eq!(err.column, 12);
+        assert!(FoldingContract::parse("---\nauthor: Ada\nrotate 1 5").is_err());
+
+        let restrain = |line: &str| match FoldingContract::parse(line).map(|c| c.instructions) {
+            Ok(instructions) => match instructions[..] {
+                [ContractInstruction::Restrain { force_constant, .. }] => Ok(force_constant),
+                _ => unreachable!(),
+            },
+            Err(err) => Err(err.message),
+        };
+        assert_eq!(restrain("restrain 5..15 helix k=1e3"), Ok(1000.0));
+        assert_eq!(
+            restrain("restrain 5..15 helix"),
+            Ok(DEFAULT_RESTRAINT_CONSTANT)
+        );
+        assert!(restrain("restrain 5..15 helix k=5abc").is_err());
+        assert!(restrain("restrain 5..15 helix k=-1").is_err());
+        assert_eq!(
+            restrain("restrain 5..15 helix weight=5"),
+            Err("expected k=<force constant>, found 'weight=5'".into())
+        );
+        assert!(restrain("restrain 5..15 helix 5").is_err());
+    }
+
+    #[test]
+    fn parses_environment_protocols() {
+        let contract = FoldingContract::from_lines(&[
+            "environment Cytosol",
+            "set temperature 320",
+            "set dielectric 40",
+            "ramp ionic_strength 0 -> 0.15 over 1000 steps",
+        ])
+        .unwrap();
+        let lines: Vec<String> = contract.instructions.iter().map(|i| i.to_lll()).collect();
+        assert_eq!(
+            lines,
+            [
+                "environment cytosol",
+                "temperature 320",
+                "set dielectric 40",
+                "ramp ionic_strength 0 -> 0.15 over 1000 steps",
+            ]
+        );
+        assert!(matches!(
+            contract.instructions[2],
+            ContractInstruction::SetEnvironmentParameter {
+                parameter: EnvironmentParameter::Dielectric,
+                ..
+            }
+        ));
+
+        let err = FoldingContract::parse("environment ocean").unwrap_err();
+        assert!(err.message.contains("unknown environment 'ocean'"));
+        let err = FoldingContract::parse("set crowding 1.5").unwrap_err();
+        assert_eq!(err.column, 14);
+        assert!(FoldingContract::parse("ramp pressure 1 -> 2 over 10").is_err());
+    }
+
+    #[test]
+    fn trailing_hash_words_tag_the_move() {
+        let contract =
+            FoldingContract::parse("rotate 12 phi -30 #docking-attempt #phase1\ncommit # done\n")
+                .unwrap();
+        let lines: Vec<String> = contract.instructions.iter().map(|i| i.to_lll()).collect();
+        assert_eq!(lines[0], "tag docking-attempt phase1");
+        assert_eq!(lines[2], "commit");
+        assert_eq!(contract.move_count(), 1);
+
+        let err = FoldingContract::parse("rotate 12 #dock phi -30").unwrap_err();
+        assert_eq!(err.message, "tags must end the line");
+        // After anything but a move, `#word` still starts a comment
+        let commented = FoldingContract::parse("commit #note to self\nghost on #quiet").unwrap();
+        assert_eq!(commented.instructions.len(), 2);
+        assert_eq!(commented.instructions[0].to_lll(), "commit");
+    }
+
+    #[test]
+    fn phases_open_by_name_and_close_with_end_phase() {
+        let phases = FoldingContract::parse("phase anneal\nphase hot\nend_phase\n").unwrap();
+        assert_eq!(phases.instructions[0].to_lll(), "phase anneal");
+        assert_eq!(phases.instructions[2].to_lll(), "end_phase");
+        let err = FoldingContract::parse("phase anneal\nend_phase\n  end_phase").unwrap_err();
+        assert_eq!((err.line, err.column, err.message.as_str()), (3, 3, "no phase is open"));
+        // A bare `end` is not a phase keyword
+        assert!(FoldingContract::parse("phase anneal\nend").is_err());
+    }
+
+    #[test]
+    fn quoted_strings_keep_separators_and_hashes() {
+        let contract =
+            FoldingContract::parse("span_alias \"loop #2, relaxed\"  # trailing comment\n")
+                .unwrap();
+        match &contract.instructions[0] {
+            ContractInstruction::SpanAlias(alias) => assert_eq!(alias, "loop #2, relaxed"),
+            other => panic!("unexpected instruction: {other:?}"),
+        }
+    }
 }
@@ -1,6 +1,9 @@
-use folding_molecule::{BondConstraintSet, PeptideChain, ResidueId};
+use folding_molecule::{BondConstraintSet, Dihedral, PeptideChain, ResidueId};
 use folding_time::trajectory::SpanRecord;
 
+use crate::geometry_validation::omega_deviation;
+use crate::ramachandran::RamachandranMap;
+
 /// Validates spans against chemical and informational constraints.
 #[derive(Debug, Clone)]
 pub struct Ruleset {
@@ -11,6 +14,21 @@ pub struct Ruleset {
     pub min_distance_angstrom: Option<f64>,
     pub bond_distance_range: Option<(f64, f64)>,
     pub bond_angle_range: Option<(f64, f64)>,
+    /// Inclusive residue ranges no move may touch.
+    pub forbidden_ranges: Vec<(ResidueId, ResidueId)>,
+    /// Largest energy rise a single span may cause, before Metropolis.
+    pub max_energy_increase: Option<f64>,
+    /// Largest share of ghost spans among all spans run so far.
+    pub max_ghost_ratio: Option<f64>,
+    /// Degrees a moved residue's (phi, psi) may lie outside the allowed
+    /// Ramachandran regions of its map; `None` leaves the rule off.
+    pub ramachandran_tolerance: Option<f64>,
+    /// Degrees a moved peptide bond's omega may lie from trans (or from cis,
+    /// for a bond into proline); `None` leaves the rule off.
+    pub max_omega_deviation: Option<f64>,
+    /// Recovery per rule, keyed by code (`R006`) or kind (`MetropolisRejected`);
+    /// rules not listed are rejected.
+    pub recovery: Vec<(String, RecoveryPolicy)>,
 }
 
 impl Default for Ruleset {
@@ -23,6 +41,12 @@ impl Default for Ruleset {
             min_distance_angstrom: Some(1.2),
             bond_distance_range: Some((1.2, 1.9)),
             bond_angle_range: Some((1.6, 2.4)),
+            forbidden_ranges: Vec::new(),
+            max_energy_increase: None,
+            max_ghost_ratio: None,
+            ramachandran_tolerance: None,
+            max_omega_deviation: None,
+            recovery: Vec::new(),
         }
     }
 }
@@ -50,6 +74,7 @@ pub enum RuleViolation {
         residue_b: ResidueId,
         distance: f64,
     },
+    /// `delta_energy` includes the change in any scoring terms.
     MetropolisRejected {
         delta_energy: f64,
     },
@@ -66,6 +91,380 @@ pub enum RuleViolation {
         min: f64,
         max: f64,
     },
+    /// `move_domain` named a domain that was never defined or lies off the chain.
+    UnknownDomain {
+        domain: String,
+    },
+    /// CCD could not bring the loop back onto its anchors, or the anchors do
+    /// not enclose at least two movable residues.
+    LoopNotClosed {
+        start: ResidueId,
+        end: ResidueId,
+        residual: f64,
+    },
+    /// The move needs a neighbour on both sides (e.g. backrub on a terminus).
+    TerminalResidue {
+        residue: ResidueId,
+    },
+    /// The residue type has no such side-chain torsion (e.g. chi1 on glycine).
+    DihedralUnavailable {
+        residue: ResidueId,
+        dihedral: Dihedral,
+    },
+    /// The move touches a residue inside a forbidden range.
+    ForbiddenResidue {
+        residue: ResidueId,
+        start: ResidueId,
+        end: ResidueId,
+    },
+    EnergyIncreaseExceeded {
+        delta_energy: f64,
+        limit: f64,
+    },
+    GhostRatioExceeded {
+        ratio: f64,
+        limit: f64,
+    },
+    /// The physics backend could not run the move (e.g. a `simulate` burst).
+    PhysicsUnavailable {
+        reason: String,
+    },
+    /// The move would turn or displace a residue the contract froze.
+    FrozenResidue {
+        residue: ResidueId,
+        start: ResidueId,
+        end: ResidueId,
+    },
+    /// The move left a residue's (phi, psi) in a disallowed region of its
+    /// Ramachandran map, `deviation` degrees from the nearest allowed one.
+    RamachandranOutlier {
+        residue: ResidueId,
+        map: RamachandranMap,
+        phi: f64,
+        psi: f64,
+        deviation: f64,
+    },
+    /// The move left the peptide bond after `residue` unintentionally cis,
+    /// `deviation` degrees from trans.
+    CisPeptide {
+        residue: ResidueId,
+        omega: f64,
+        deviation: f64,
+    },
+    /// The rotamer library has no side-chain state of that name for the
+    /// residue's type.
+    UnknownRotamer {
+        residue: ResidueId,
+        rotamer: String,
+    },
+    /// A scoring term could not score the move's conformation (e.g. its
+    /// model server failed), so the move cannot be judged.
+    ScoreUnavailable {
+        term: String,
+    },
+}
+
+/// Every violation code with its kind, in code order.
+pub const RULES: [(&str, &str); 21] = [
+    ("R001", "RotationLimitExceeded"),
+    ("R002", "BondDistanceUnsatisfied"),
+    ("R003", "EntropyBudgetExceeded"),
+    ("R004", "InformationBudgetExceeded"),
+    ("R005", "StructuralClash"),
+    ("R006", "MetropolisRejected"),
+    ("R007", "BondLengthOutOfRange"),
+    ("R008", "BondAngleOutOfRange"),
+    ("R009", "UnknownDomain"),
+    ("R010", "LoopNotClosed"),
+    ("R011", "TerminalResidue"),
+    ("R012", "DihedralUnavailable"),
+    ("R013", "ForbiddenResidue"),
+    ("R014", "EnergyIncreaseExceeded"),
+    ("R015", "GhostRatioExceeded"),
+    ("R016", "PhysicsUnavailable"),
+    ("R017", "FrozenResidue"),
+    ("R018", "RamachandranOutlier"),
+    ("R019", "CisPeptide"),
+    ("R020", "UnknownRotamer"),
+    ("R021", "ScoreUnavailable"),
+];
+
+/// Retries `halve` makes when no count is given.
+pub const DEFAULT_HALVING_ATTEMPTS: usize = 3;
+
+/// What the engine does when a move breaks a rule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecoveryPolicy {
+    /// Record the violation and go on with the next instruction.
+    #[default]
+    Reject,
+    /// Retry the move at half, quarter, ... its size, up to `attempts` times.
+    HalveAndRetry { attempts: usize },
+    /// Re-run the move as a ghost span: recorded, never applied.
+    Ghost,
+    /// Record the violation and stop executing the contract.
+    Abort,
+}
+
+impl RecoveryPolicy {
+    /// `reject`, `halve` (or `halve:N`), `ghost` or `abort`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim().to_ascii_lowercase();
+        match raw.as_str() {
+            "reject" => Some(RecoveryPolicy::Reject),
+            "halve" => Some(RecoveryPolicy::HalveAndRetry {
+                attempts: DEFAULT_HALVING_ATTEMPTS,
+            }),
+            "ghost" => Some(RecoveryPolicy::Ghost),
+            "abort" => Some(RecoveryPolicy::Abort),
+            other => {
+                let attempts = other.strip_prefix("halve:")?.parse().ok()?;
+                Some(RecoveryPolicy::HalveAndRetry { attempts })
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecoveryPolicy::Reject => "reject",
+            RecoveryPolicy::HalveAndRetry { .. } => "halve",
+            RecoveryPolicy::Ghost => "ghost",
+            RecoveryPolicy::Abort => "abort",
+        }
+    }
+}
+
+/// How much a violation matters to tooling reading the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Expected during sampling (Metropolis rejections).
+    Warning,
+    /// The span was refused; the run carries on.
+    Error,
+    /// An exhausted budget: no later span can succeed either.
+    Fatal,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Fatal => "fatal",
+        }
+    }
+}
+
+impl RuleViolation {
+    /// Stable identifier for the violation kind; never reused or renumbered.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RuleViolation::RotationLimitExceeded { .. } => "R001",
+            RuleViolation::BondDistanceUnsatisfied { .. } => "R002",
+            RuleViolation::EntropyBudgetExceeded { .. } => "R003",
+            RuleViolation::InformationBudgetExceeded { .. } => "R004",
+            RuleViolation::StructuralClash { .. } => "R005",
+            RuleViolation::MetropolisRejected { .. } => "R006",
+            RuleViolation::BondLengthOutOfRange { .. } => "R007",
+            RuleViolation::BondAngleOutOfRange { .. } => "R008",
+            RuleViolation::UnknownDomain { .. } => "R009",
+            RuleViolation::LoopNotClosed { .. } => "R010",
+            RuleViolation::TerminalResidue { .. } => "R011",
+            RuleViolation::DihedralUnavailable { .. } => "R012",
+            RuleViolation::ForbiddenResidue { .. } => "R013",
+            RuleViolation::EnergyIncreaseExceeded { .. } => "R014",
+            RuleViolation::GhostRatioExceeded { .. } => "R015",
+            RuleViolation::PhysicsUnavailable { .. } => "R016",
+            RuleViolation::FrozenResidue { .. } => "R017",
+            RuleViolation::RamachandranOutlier { .. } => "R018",
+            RuleViolation::CisPeptide { .. } => "R019",
+            RuleViolation::UnknownRotamer { .. } => "R020",
+            RuleViolation::ScoreUnavailable { .. } => "R021",
+        }
+    }
+
+    /// Variant name, as it appears at the start of the `Debug` detail.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RuleViolation::RotationLimitExceeded { .. } => "RotationLimitExceeded",
+            RuleViolation::BondDistanceUnsatisfied { .. } => "BondDistanceUnsatisfied",
+            RuleViolation::EntropyBudgetExceeded { .. } => "EntropyBudgetExceeded",
+            RuleViolation::InformationBudgetExceeded { .. } => "InformationBudgetExceeded",
+            RuleViolation::StructuralClash { .. } => "StructuralClash",
+            RuleViolation::MetropolisRejected { .. } => "MetropolisRejected",
+            RuleViolation::BondLengthOutOfRange { .. } => "BondLengthOutOfRange",
+            RuleViolation::BondAngleOutOfRange { .. } => "BondAngleOutOfRange",
+            RuleViolation::UnknownDomain { .. } => "UnknownDomain",
+            RuleViolation::LoopNotClosed { .. } => "LoopNotClosed",
+            RuleViolation::TerminalResidue { .. } => "TerminalResidue",
+            RuleViolation::DihedralUnavailable { .. } => "DihedralUnavailable",
+            RuleViolation::ForbiddenResidue { .. } => "ForbiddenResidue",
+            RuleViolation::EnergyIncreaseExceeded { .. } => "EnergyIncreaseExceeded",
+            RuleViolation::GhostRatioExceeded { .. } => "GhostRatioExceeded",
+            RuleViolation::PhysicsUnavailable { .. } => "PhysicsUnavailable",
+            RuleViolation::FrozenResidue { .. } => "FrozenResidue",
+            RuleViolation::RamachandranOutlier { .. } => "RamachandranOutlier",
+            RuleViolation::CisPeptide { .. } => "CisPeptide",
+            RuleViolation::UnknownRotamer { .. } => "UnknownRotamer",
+            RuleViolation::ScoreUnavailable { .. } => "ScoreUnavailable",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            RuleViolation::MetropolisRejected { .. } => Severity::Warning,
+            RuleViolation::EntropyBudgetExceeded { .. }
+            | RuleViolation::InformationBudgetExceeded { .. } => Severity::Fatal,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Variant fields as `(name, value)` pairs; residues are bare indices.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let id = |residue: &ResidueId| residue.0.to_string();
+        match self {
+            RuleViolation::RotationLimitExceeded {
+                residue,
+                requested,
+                limit,
+            } => vec![
+                ("residue", id(residue)),
+                ("requested", requested.to_string()),
+                ("limit", limit.to_string()),
+            ],
+            RuleViolation::BondDistanceUnsatisfied { residue }
+            | RuleViolation::TerminalResidue { residue } => vec![("residue", id(residue))],
+            RuleViolation::EntropyBudgetExceeded { consumed, budget }
+            | RuleViolation::InformationBudgetExceeded { consumed, budget } => vec![
+                ("consumed", consumed.to_string()),
+                ("budget", budget.to_string()),
+            ],
+            RuleViolation::StructuralClash {
+                residue_a,
+                residue_b,
+                distance,
+            } => vec![
+                ("residue_a", id(residue_a)),
+                ("residue_b", id(residue_b)),
+                ("distance", distance.to_string()),
+            ],
+            RuleViolation::MetropolisRejected { delta_energy } => {
+                vec![("delta_energy", delta_energy.to_string())]
+            }
+            RuleViolation::BondLengthOutOfRange {
+                residue_left,
+                residue_right,
+                distance,
+                min,
+                max,
+            } => vec![
+                ("residue_left", id(residue_left)),
+                ("residue_right", id(residue_right)),
+                ("distance", distance.to_string()),
+                ("min", min.to_string()),
+                ("max", max.to_string()),
+            ],
+            RuleViolation::BondAngleOutOfRange {
+                residue_center,
+                angle,
+                min,
+                max,
+            } => vec![
+                ("residue_center", id(residue_center)),
+                ("angle", angle.to_string()),
+                ("min", min.to_string()),
+                ("max", max.to_string()),
+            ],
+            RuleViolation::UnknownDomain { domain } => vec![("domain", domain.clone())],
+            RuleViolation::LoopNotClosed {
+                start,
+                end,
+                residual,
+            } => vec![
+                ("start", id(start)),
+                ("end", id(end)),
+                ("residual", residual.to_string()),
+            ],
+            RuleViolation::DihedralUnavailable { residue, dihedral } => vec![
+                ("residue", id(residue)),
+                ("dihedral", dihedral.as_str().to_string()),
+            ],
+            RuleViolation::ForbiddenResidue {
+                residue,
+                start,
+                end,
+            }
+            | RuleViolation::FrozenResidue {
+                residue,
+                start,
+                end,
+            } => vec![
+                ("residue", id(residue)),
+                ("start", id(start)),
+                ("end", id(end)),
+            ],
+            RuleViolation::EnergyIncreaseExceeded {
+                delta_energy,
+                limit,
+            } => vec![
+                ("delta_energy", delta_energy.to_string()),
+                ("limit", limit.to_string()),
+            ],
+            RuleViolation::GhostRatioExceeded { ratio, limit } => {
+                vec![("ratio", ratio.to_string()), ("limit", limit.to_string())]
+            }
+            RuleViolation::PhysicsUnavailable { reason } => vec![("reason", reason.clone())],
+            RuleViolation::RamachandranOutlier {
+                residue,
+                map,
+                phi,
+                psi,
+                deviation,
+            } => vec![
+                ("residue", id(residue)),
+                ("map", map.as_str().to_string()),
+                ("phi", phi.to_string()),
+                ("psi", psi.to_string()),
+                ("deviation", deviation.to_string()),
+            ],
+            RuleViolation::CisPeptide {
+                residue,
+                omega,
+                deviation,
+            } => vec![
+                ("residue", id(residue)),
+                ("omega", omega.to_string()),
+                ("deviation", deviation.to_string()),
+            ],
+            RuleViolation::UnknownRotamer { residue, rotamer } => {
+                vec![("residue", id(residue)), ("rotamer", rotamer.clone())]
+            }
+            RuleViolation::ScoreUnavailable { term } => vec![("term", term.clone())],
+        }
+    }
+}
+
+/// A violation together with the engine step at which it was raised.
+#[derive(Debug, Clone)]
+pub struct ViolationRecord {
+    pub step: usize,
+    pub violation: RuleViolation,
+}
+
+impl ViolationRecord {
+    /// `violation|` log line: step, code, severity and kind, then the variant
+    /// fields, with the `Debug` detail last for older readers.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("step", self.step.to_string()),
+            ("code", self.violation.code().to_string()),
+            ("severity", self.violation.severity().as_str().to_string()),
+            ("kind", self.violation.kind().to_string()),
+        ];
+        fields.extend(self.violation.fields());
+        fields.push(("detail", format!("{:?}", self.violation)));
+        fields
+    }
 }
 
 impl Ruleset {
@@ -104,11 +503,112 @@ impl Ruleset {
         self
     }
 
+    pub fn with_forbidden_range(mut self, start: ResidueId, end: ResidueId) -> Self {
+        self.forbidden_ranges.push((start, end));
+        self
+    }
+
+    pub fn with_max_energy_increase(mut self, limit: f64) -> Self {
+        self.max_energy_increase = Some(limit);
+        self
+    }
+
+    pub fn with_max_ghost_ratio(mut self, limit: f64) -> Self {
+        self.max_ghost_ratio = Some(limit);
+        self
+    }
+
+    pub fn with_ramachandran_tolerance(mut self, degrees: f64) -> Self {
+        self.ramachandran_tolerance = Some(degrees);
+        self
+    }
+
+    pub fn with_max_omega_deviation(mut self, degrees: f64) -> Self {
+        self.max_omega_deviation = Some(degrees);
+        self
+    }
+
+    /// Sets the recovery for `rule`, a violation code or kind; the last
+    /// setting for a rule wins.
+    pub fn with_recovery(mut self, rule: impl Into<String>, policy: RecoveryPolicy) -> Self {
+        self.recovery.push((rule.into(), policy));
+        self
+    }
+
+    pub fn recovery_for(&self, violation: &RuleViolation) -> RecoveryPolicy {
+        self.recovery
+            .iter()
+            .rev()
+            .find(|(rule, _)| rule == violation.code() || rule == violation.kind())
+            .map(|(_, policy)| *policy)
+            .unwrap_or_default()
+    }
+
+    /// Checks that the limits are usable on a chain of `chain_len` residues.
+    pub fn check(&self, chain_len: usize) -> Result<(), String> {
+        if self.max_rotation_degrees <= 0.0 {
+            return Err(format!(
+                "max_rotation_degrees must be positive, got {}",
+                self.max_rotation_degrees
+            ));
+        }
+        for (name, value) in [
+            ("entropy_budget", self.entropy_budget),
+            ("information_budget", self.information_budget),
+            ("min_distance", self.min_distance_angstrom),
+            ("max_energy_increase", self.max_energy_increase),
+            ("ramachandran_tolerance", self.ramachandran_tolerance),
+            ("max_omega_deviation", self.max_omega_deviation),
+        ] {
+            if let Some(value) = value
+                && value < 0.0
+            {
+                return Err(format!("{name} must not be negative, got {value}"));
+            }
+        }
+        if let Some(ratio) = self.max_ghost_ratio
+            && !(0.0..=1.0).contains(&ratio)
+        {
+            return Err(format!("max_ghost_ratio must lie in [0, 1], got {ratio}"));
+        }
+        for (name, range) in [
+            ("bond_distance", self.bond_distance_range),
+            ("bond_angle", self.bond_angle_range),
+        ] {
+            if let Some((min, max)) = range
+                && min > max
+            {
+                return Err(format!("{name} range is inverted: {min} > {max}"));
+            }
+        }
+        for (start, end) in &self.forbidden_ranges {
+            if start.0 > end.0 {
+                return Err(format!("forbidden range {}-{} is inverted", start.0, end.0));
+            }
+            if start.0 >= chain_len {
+                return Err(format!(
+                    "forbidden range {}-{} lies beyond the {chain_len}-residue chain",
+                    start.0, end.0
+                ));
+            }
+        }
+        for (rule, _) in &self.recovery {
+            if !RULES
+                .iter()
+                .any(|(code, kind)| rule == code || rule == kind)
+            {
+                return Err(format!("recovery names unknown rule '{rule}'"));
+            }
+        }
+        Ok(())
+    }
+
     pub fn validate_rotation(
         &self,
         residue: ResidueId,
+        dihedral: Dihedral,
         angle: f64,
-        _chain: &PeptideChain,
+        chain: &PeptideChain,
     ) -> Result<(), RuleViolation> {
         if angle.abs() > self.max_rotation_degrees {
             return Err(RuleViolation::RotationLimitExceeded {
@@ -117,11 +617,54 @@ impl Ruleset {
                 limit: self.max_rotation_degrees,
             });
         }
+        self.check_residue_allowed(residue)?;
+        if let (Some(index), Some(entry)) = (dihedral.chi_index(), chain.residue(residue))
+            && index >= entry.chi_count()
+        {
+            return Err(RuleViolation::DihedralUnavailable { residue, dihedral });
+        }
         // Bond constraint evaluation is stubbed for now.
         let _ = &self.bond_constraints;
         Ok(())
     }
 
+    pub fn check_residue_allowed(&self, residue: ResidueId) -> Result<(), RuleViolation> {
+        match self
+            .forbidden_ranges
+            .iter()
+            .find(|(start, end)| (start.0..=end.0).contains(&residue.0))
+        {
+            Some(&(start, end)) => Err(RuleViolation::ForbiddenResidue {
+                residue,
+                start,
+                end,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn check_energy_increase(&self, delta_energy: f64) -> Result<(), RuleViolation> {
+        match self.max_energy_increase {
+            Some(limit) if delta_energy > limit => Err(RuleViolation::EnergyIncreaseExceeded {
+                delta_energy,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// `ghost` and `applied` count the spans so far, including the new one.
+    pub fn check_ghost_ratio(&self, ghost: usize, applied: usize) -> Result<(), RuleViolation> {
+        let Some(limit) = self.max_ghost_ratio else {
+            return Ok(());
+        };
+        let ratio = ghost as f64 / (ghost + applied).max(1) as f64;
+        if ratio > limit {
+            return Err(RuleViolation::GhostRatioExceeded { ratio, limit });
+        }
+        Ok(())
+    }
+
     pub fn check_budgets(
         &self,
         span: &SpanRecord,
@@ -149,16 +692,77 @@ impl Ruleset {
         Ok(())
     }
 
+    /// Checks the residues whose phi or psi changed since `previous`; the
+    /// termini, whose phi or psi is undefined, are skipped.
+    pub fn check_ramachandran(
+        &self,
+        previous: &PeptideChain,
+        chain: &PeptideChain,
+    ) -> Result<(), RuleViolation> {
+        let Some(tolerance) = self.ramachandran_tolerance else {
+            return Ok(());
+        };
+        let (phi, psi) = (chain.phi(), chain.psi());
+        let inner = |&index: &usize| chain.is_bonded(index - 1) && chain.is_bonded(index);
+        for index in (1..chain.len().saturating_sub(1)).filter(inner) {
+            if previous.phi().get(index) == Some(&phi[index])
+                && previous.psi().get(index) == Some(&psi[index])
+            {
+                continue;
+            }
+            let map = RamachandranMap::for_residue(&chain.names()[index]);
+            let deviation = map.deviation(phi[index], psi[index]);
+            if deviation > tolerance {
+                return Err(RuleViolation::RamachandranOutlier {
+                    residue: chain.ids()[index],
+                    map,
+                    phi: phi[index],
+                    psi: psi[index],
+                    deviation,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the peptide bonds whose omega changed since `previous`; the
+    /// last residue has no bond after it.
+    pub fn check_omega(
+        &self,
+        previous: &PeptideChain,
+        chain: &PeptideChain,
+    ) -> Result<(), RuleViolation> {
+        let Some(limit) = self.max_omega_deviation else {
+            return Ok(());
+        };
+        let bonds = chain.omega().iter().zip(chain.names().iter().skip(1));
+        for (index, (&omega, next)) in bonds.enumerate() {
+            if previous.omega().get(index) == Some(&omega) {
+                continue;
+            }
+            let deviation = omega_deviation(omega, next);
+            if deviation > limit {
+                return Err(RuleViolation::CisPeptide {
+                    residue: chain.ids()[index],
+                    omega,
+                    deviation,
+                });
+            }
+        }
+        Ok(())
+    }
+
     pub fn check_structure(&self, chain: &PeptideChain) -> Result<(), RuleViolation> {
+        let positions = chain.positions();
+        let ids = chain.ids();
         if let Some(min_distance) = self.min_distance_angstrom {
-            let residues = chain.residues();
-            for (i, left) in residues.iter().enumerate() {
-                for right in residues.iter().skip(i + 1) {
-                    let distance = distance(left.position(), right.position());
+            for i in 0..positions.len() {
+                for j in i + 1..positions.len() {
+                    let distance = distance(positions[i], positions[j]);
                     if distance < min_distance {
                         return Err(RuleViolation::StructuralClash {
-                            residue_a: left.id,
-                            residue_b: right.id,
+                            residue_a: ids[i],
+                            residue_b: ids[j],
                             distance,
                         });
                     }
@@ -166,35 +770,30 @@ impl Ruleset {
             }
         }
         if let Some((min, max)) = self.bond_distance_range {
-            let residues = chain.residues();
-            for window in residues.windows(2) {
-                if let [left, right] = window {
-                    let distance = distance(left.position(), right.position());
-                    if distance < min || distance > max {
-                        return Err(RuleViolation::BondLengthOutOfRange {
-                            residue_left: left.id,
-                            residue_right: right.id,
-                            distance,
-                            min,
-                            max,
-                        });
-                    }
+            for i in (1..positions.len()).filter(|&i| chain.is_bonded(i - 1)) {
+                let distance = distance(positions[i - 1], positions[i]);
+                if distance < min || distance > max {
+                    return Err(RuleViolation::BondLengthOutOfRange {
+                        residue_left: ids[i - 1],
+                        residue_right: ids[i],
+                        distance,
+                        min,
+                        max,
+                    });
                 }
             }
         }
         if let Some((min, max)) = self.bond_angle_range {
-            let residues = chain.residues();
-            for window in residues.windows(3) {
-                if let [left, center, right] = window {
-                    let angle = bond_angle(left.position(), center.position(), right.position());
-                    if angle < min || angle > max {
-                        return Err(RuleViolation::BondAngleOutOfRange {
-                            residue_center: center.id,
-                            angle,
-                            min,
-                            max,
-                        });
-                    }
+            let within_chain = |&i: &usize| chain.is_bonded(i - 2) && chain.is_bonded(i - 1);
+            for i in (2..positions.len()).filter(within_chain) {
+                let angle = bond_angle(positions[i - 2], positions[i - 1], positions[i]);
+                if angle < min || angle > max {
+                    return Err(RuleViolation::BondAngleOutOfRange {
+                        residue_center: ids[i - 1],
+                        angle,
+                        min,
+                        max,
+                    });
                 }
             }
         }
@@ -202,6 +801,31 @@ impl Ruleset {
     }
 }
 
+pub(crate) fn distance(left: [f64; 3], right: [f64; 3]) -> f64 {
+    ((left[0] - right[0]).powi(2) + (left[1] - right[1]).powi(2) + (left[2] - right[2]).powi(2))
+        .sqrt()
+}
+
+pub(crate) fn bond_angle(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> f64 {
+    let v1 = subtract(a, b);
+    let v2 = subtract(c, b);
+    let dot = dot(v1, v2);
+    let norm = (norm(v1) * norm(v2)).max(1e-9);
+    (dot / norm).clamp(-1.0, 1.0).acos()
+}
+
+fn subtract(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn norm(v: [f64; 3]) -> f64 {
+    (v[0].powi(2) + v[1].powi(2) + v[2].powi(2)).sqrt()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,6 +845,177 @@ mod tests {
         assert!(matches!(result, Err(RuleViolation::StructuralClash { .. })));
     }
 
+    #[test]
+    fn recovery_is_chosen_per_rule() {
+        let ruleset = Ruleset::default()
+            .with_recovery("R001", RecoveryPolicy::parse("halve:2").unwrap())
+            .with_recovery("MetropolisRejected", RecoveryPolicy::Ghost)
+            .with_recovery("R006", RecoveryPolicy::Abort);
+        let too_far = RuleViolation::RotationLimitExceeded {
+            residue: ResidueId(0),
+            requested: 90.0,
+            limit: 30.0,
+        };
+        let rejected = RuleViolation::MetropolisRejected { delta_energy: 1.0 };
+        let clash = RuleViolation::StructuralClash {
+            residue_a: ResidueId(0),
+            residue_b: ResidueId(2),
+            distance: 0.5,
+        };
+        assert_eq!(
+            ruleset.recovery_for(&too_far),
+            RecoveryPolicy::HalveAndRetry { attempts: 2 }
+        );
+        assert_eq!(ruleset.recovery_for(&rejected), RecoveryPolicy::Abort);
+        assert_eq!(ruleset.recovery_for(&clash), RecoveryPolicy::Reject);
+        assert!(ruleset.check(4).is_ok());
+        let typo = Ruleset::default().with_recovery("R999", RecoveryPolicy::Ghost);
+        assert!(typo.check(4).unwrap_err().contains("R999"));
+        assert_eq!(RecoveryPolicy::parse("retry"), None);
+    }
+
+    #[test]
+    fn violations_carry_codes_severities_and_fields() {
+        let rejected = RuleViolation::MetropolisRejected { delta_energy: 1.5 };
+        assert_eq!(rejected.code(), "R006");
+        assert_eq!(rejected.severity(), Severity::Warning);
+        let unscored = RuleViolation::ScoreUnavailable {
+            term: "model".into(),
+        };
+        for violation in [&rejected, &unscored] {
+            assert!(RULES.contains(&(violation.code(), violation.kind())));
+        }
+        assert_eq!(unscored.kind(), "ScoreUnavailable");
+        let exhausted = RuleViolation::EntropyBudgetExceeded {
+            consumed: 2.0,
+            budget: 1.0,
+        };
+        assert_eq!(exhausted.severity(), Severity::Fatal);
+        let record = ViolationRecord {
+            step: 3,
+            violation: RuleViolation::ForbiddenResidue {
+                residue: ResidueId(4),
+                start: ResidueId(2),
+                end: ResidueId(6),
+            },
+        };
+        let fields = record.fields();
+        assert_eq!(fields[0], ("step", "3".to_string()));
+        assert_eq!(fields[1], ("code", "R013".to_string()));
+        assert_eq!(fields[2], ("severity", "error".to_string()));
+        assert_eq!(fields[3], ("kind", "ForbiddenResidue".to_string()));
+        assert_eq!(fields[4], ("residue", "4".to_string()));
+        assert!(fields.last().unwrap().1.starts_with("ForbiddenResidue {"));
+    }
+
+    #[test]
+    fn rejects_side_chain_torsions_the_residue_lacks() {
+        let chain = PeptideChain::from_sequence("GS");
+        let ruleset = Ruleset::default();
+        assert!(matches!(
+            ruleset.validate_rotation(ResidueId(0), Dihedral::Chi1, 10.0, &chain),
+            Err(RuleViolation::DihedralUnavailable { .. })
+        ));
+        assert!(
+            ruleset
+                .validate_rotation(ResidueId(1), Dihedral::Chi1, 10.0, &chain)
+                .is_ok()
+        );
+        assert!(
+            ruleset
+                .validate_rotation(ResidueId(0), Dihedral::Psi, 10.0, &chain)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn file_limits_reject_forbidden_residues_and_bad_values() {
+        let chain = PeptideChain::from_sequence("ACDEFG");
+        let ruleset = Ruleset::default()
+            .with_forbidden_range(ResidueId(2), ResidueId(3))
+            .with_max_energy_increase(1.0)
+            .with_max_ghost_ratio(0.5);
+        assert!(ruleset.check(6).is_ok());
+        assert!(matches!(
+            ruleset.validate_rotation(ResidueId(3), Dihedral::Phi, 5.0, &chain),
+            Err(RuleViolation::ForbiddenResidue { .. })
+        ));
+        assert!(matches!(
+            ruleset.check_energy_increase(1.5),
+            Err(RuleViolation::EnergyIncreaseExceeded { .. })
+        ));
+        assert!(ruleset.check_ghost_ratio(1, 1).is_ok());
+        assert!(ruleset.check_ghost_ratio(2, 1).is_err());
+
+        assert!(ruleset.check(2).unwrap_err().contains("beyond"));
+        let bad = Ruleset::default().with_max_ghost_ratio(1.5);
+        assert!(bad.check(6).unwrap_err().contains("max_ghost_ratio"));
+    }
+
+    #[test]
+    fn ramachandran_rule_checks_the_residues_a_move_turned() {
+        let previous = PeptideChain::from_sequence("AAGPA");
+        let ruleset = Ruleset::default().with_ramachandran_tolerance(10.0);
+        let turned = |index: usize, phi: f64, psi: f64| {
+            let mut chain = previous.clone();
+            chain.phi_mut()[index] = phi;
+            chain.psi_mut()[index] = psi;
+            chain
+        };
+        // Unchanged residues sit at (0, 0) but are not judged
+        assert!(
+            ruleset
+                .check_ramachandran(&previous, &turned(1, -60.0, -45.0))
+                .is_ok()
+        );
+        assert!(matches!(
+            ruleset.check_ramachandran(&previous, &turned(1, 60.0, -60.0)),
+            Err(RuleViolation::RamachandranOutlier {
+                map: RamachandranMap::General,
+                ..
+            })
+        ));
+        assert!(
+            ruleset
+                .check_ramachandran(&previous, &turned(2, 60.0, -40.0))
+                .is_ok()
+        );
+        let outlier = ruleset
+            .check_ramachandran(&previous, &turned(3, -120.0, 150.0))
+            .unwrap_err();
+        assert_eq!(outlier.code(), "R018");
+        assert_eq!(outlier.fields()[1], ("map", "proline".to_string()));
+        assert!(
+            Ruleset::default()
+                .check_ramachandran(&previous, &turned(3, -120.0, 150.0))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn omega_rule_allows_cis_only_before_proline() {
+        let previous = PeptideChain::from_sequence("AAGPA");
+        let ruleset = Ruleset::default().with_max_omega_deviation(30.0);
+        let turned = |index: usize, delta: f64| {
+            let mut chain = previous.clone();
+            chain.rotate_torsion(ResidueId(index), Dihedral::Omega, delta);
+            chain
+        };
+        assert!(ruleset.check_omega(&previous, &turned(0, 20.0)).is_ok());
+        let cis = ruleset
+            .check_omega(&previous, &turned(1, 180.0))
+            .unwrap_err();
+        assert_eq!(cis.code(), "R019");
+        assert_eq!(cis.fields()[0], ("residue", "1".to_string()));
+        // Gly-Pro may go cis
+        assert!(ruleset.check_omega(&previous, &turned(2, 180.0)).is_ok());
+        assert!(
+            Ruleset::default()
+                .check_omega(&previous, &turned(1, 180.0))
+                .is_ok()
+        );
+    }
+
     #[test]
     fn detects_bond_length_out_of_range() {
         let residues = vec![
@@ -238,28 +1033,3 @@ mod tests {
         ));
     }
 }
-
-fn distance(left: [f64; 3], right: [f64; 3]) -> f64 {
-    ((left[0] - right[0]).powi(2) + (left[1] - right[1]).powi(2) + (left[2] - right[2]).powi(2))
-        .sqrt()
-}
-
-fn bond_angle(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> f64 {
-    let v1 = subtract(a, b);
-    let v2 = subtract(c, b);
-    let dot = dot(v1, v2);
-    let norm = (norm(v1) * norm(v2)).max(1e-9);
-    (dot / norm).clamp(-1.0, 1.0).acos()
-}
-
-fn subtract(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
-    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
-}
-
-fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
-    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
-}
-
-fn norm(v: [f64; 3]) -> f64 {
-    (v[0].powi(2) + v[1].powi(2) + v[2].powi(2)).sqrt()
-}
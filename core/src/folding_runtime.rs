@@ -1,16 +1,47 @@
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use crate::folding_parser::{ContractInstruction, FoldingContract, PhysicsLevel, PhysicsSpanMode};
-use crate::folding_ruleset::{RuleViolation, Ruleset};
+use crate::backmapping::{BackmappedResidue, backmap, place_ca_trace};
+use crate::collective_variables::CollectiveVariable;
+use crate::foldability::EnergySamples;
+use crate::folding_parser::{
+    BACKRUB_MAX_DEGREES, ContractInstruction, DomainAxis, DomainMotion, DomainSelector, FoldingContract, PhysicsLevel,
+    EnvironmentParameter, PhysicsSpanMode, RotationSpan,
+};
+use crate::folding_ruleset::{RecoveryPolicy, RuleViolation, Ruleset, Severity, ViolationRecord};
+use crate::geometry_validation::{
+    GeometryLimits, GeometryReport, GeometryValidator, omega_deviation,
+};
+use crate::ghost_budget::{GhostBudget, GhostOverflow};
+use crate::loop_closure::resample_loop;
+use crate::memory::{FORCE_BYTES_PER_RESIDUE, MemoryUsage};
 use crate::micro_oscillator::MicroOscillator;
-use crate::physics_bridge::{self, PhysicsRequest, PhysicsSpanMetrics, PhysicsEngine};
+use crate::observer::{EngineObserver, ObserverControl};
+use crate::physics_bridge::{
+    self, BackendFailure, ComponentBridge, MdBurst, NativeBridges, NativeVelocities,
+    PhysicsAttempt, PhysicsCache, PhysicsEngine, PhysicsRequest, PhysicsSpanMetrics,
+    TrajectoryOutput,
+};
 use crate::protein_state::{EnergyState, ProteinSnapshot, ProteinState};
-use crate::rotation_solver::{RotationCommand, RotationOutcome, RotationSolver};
-use crate::simple_rng::SimpleRng;
+use crate::restraints::{ContactPrior, DihedralRestraint};
+use crate::scoring::ScoringTerm;
+use crate::rotation_solver::{
+    RotamerCommand, RotationCommand, RotationOutcome, RotationSolver, TorsionLimits,
+};
+use crate::simple_rng::{RngCheckpoint, SimpleRng};
+use crate::symmetry::Symmetry;
+use crate::thermo_checks::{self, SpanBookkeeping, ThermoCheck, ThermoDiscrepancy};
 use crate::validation::{ValidationEvent, Validator};
-use folding_molecule::{EnergyModel, PeptideChain, ResidueId};
-use folding_time::RotationClock;
-use folding_time::trajectory::Trajectory;
+use folding_molecule::{
+    Dihedral, EnergyModel, MolecularSystem, PeptideChain, ResidueId, Rotamer, SideChainBead,
+    find_rotamer, nearest_rotamer, rotamers,
+};
+use folding_physics::{
+    CrowderField, CrowdingModel, EnergyComponents, Environment, FrustrationOptions,
+    FrustrationReport, JAMMING_FRACTION,
+};
+use folding_time::{RotationClock, TimeScale};
+use folding_time::trajectory::{SpanRecord, Trajectory};
 
 #[derive(Clone, Debug)]
 pub enum TemperatureSchedule {
@@ -34,12 +65,84 @@ impl TemperatureSchedule {
     }
 }
 
+/// A contract `ramp` in progress.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct EnvironmentRamp {
+    parameter: EnvironmentParameter,
+    from: f64,
+    to: f64,
+    start_step: usize,
+    steps: usize,
+}
+
+impl EnvironmentRamp {
+    fn value_at(&self, step: usize) -> f64 {
+        let elapsed = step.saturating_sub(self.start_step).min(self.steps);
+        if self.steps == 0 {
+            self.to
+        } else {
+            self.from + (self.to - self.from) * elapsed as f64 / self.steps as f64
+        }
+    }
+
+    fn finished_at(&self, step: usize) -> bool {
+        step >= self.start_step + self.steps
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct MetropolisStats {
     pub accepted: usize,
     pub rejected: usize,
 }
 
+/// Default number of Metropolis decisions in the rolling acceptance window.
+pub const DEFAULT_ACCEPTANCE_WINDOW: usize = 20;
+
+/// Simulated time (ps) a `refine` span relaxes the chain for.
+pub const REFINE_PICOSECONDS: f64 = 1.0;
+
+/// Acceptance over the last `total` Metropolis decisions (at most the
+/// window), taken at the decision that completed move `step`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AcceptanceSample {
+    pub step: usize,
+    pub accepted: usize,
+    pub total: usize,
+}
+
+impl AcceptanceSample {
+    pub fn rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.accepted as f64 / self.total as f64
+        }
+    }
+}
+
+/// One sample per decision in `decisions` (`(step, accepted)`, in order),
+/// over the `window` decisions ending there; early samples cover fewer.
+fn rolling_acceptance(decisions: &[(usize, bool)], window: usize) -> Vec<AcceptanceSample> {
+    let window = window.max(1);
+    let mut accepted = 0;
+    decisions
+        .iter()
+        .enumerate()
+        .map(|(i, &(step, accept))| {
+            accepted += usize::from(accept);
+            if let Some(&(_, dropped)) = i.checked_sub(window).map(|j| &decisions[j]) {
+                accepted -= usize::from(dropped);
+            }
+            AcceptanceSample {
+                step,
+                accepted,
+                total: (i + 1).min(window),
+            }
+        })
+        .collect()
+}
+
 impl MetropolisStats {
     pub fn record_accept(&mut self) {
         self.accepted += 1;
@@ -70,28 +173,120 @@ pub struct FoldingEngine {
     validator: Validator,
     ghost_mode: bool,
     pending_alias: Option<String>,
-    checkpoints: Vec<ProteinSnapshot>,
+    /// Tags the next move's span is recorded with.
+    pending_tags: Vec<String>,
+    /// Open contract phases, outermost first, each with the count of
+    /// phases opened before it this run.
+    phases: Vec<(String, usize)>,
+    phases_opened: usize,
+    /// State at each `commit`, with the physics bridges' velocities.
+    checkpoints: Vec<(ProteinSnapshot, NativeVelocities)>,
     ghost_trajectory: Trajectory,
+    /// Thermostat temperature (K): physics spans, crowding and Gibbs energy.
     temperature: f64,
+    /// Metropolis temperature (K) when set apart from the thermostat.
+    acceptance_temperature: Option<f64>,
     boltzmann_constant: f64,
     rng: SimpleRng,
     temperature_schedule: Option<TemperatureSchedule>,
     initial_temperature: f64,
     step_index: usize,
+    /// Step the next run starts counting from; a resumed run continues the
+    /// numbering of the run it picks up.
+    resume_step: usize,
     metropolis_stats: MetropolisStats,
+    /// `(step, accepted)` of every Metropolis decision, in order.
+    metropolis_decisions: Vec<(usize, bool)>,
+    acceptance_window: usize,
     domains: Vec<DomainDefinition>,
+    frozen: Vec<FrozenRegion>,
+    /// Every residue in `frozen`, sorted, for physics requests.
+    frozen_residues: Vec<usize>,
+    restraints: Vec<DihedralRestraint>,
     chaperone_requirements: Vec<ChaperoneRequirement>,
     modifications: Vec<PostTranslationalModification>,
     physics_level: PhysicsLevel,
     span_physics_mode: PhysicsSpanMode,
     physics_spans: Vec<String>,
     physics_span_metrics: Vec<PhysicsSpanRecord>,
+    level_switches: Vec<PhysicsLevelSwitch>,
     physics_engine: PhysicsEngine,
+    /// Abort the contract when a backend fails instead of falling back.
+    require_backend: bool,
+    backend_failures: Vec<BackendFailureRecord>,
+    /// Registry names of the force field and integrator physics spans use.
+    force_field: Option<String>,
+    integrator: Option<String>,
+    /// MD timestep (ps) physics spans integrate with; `None` uses the level's.
+    md_timestep: Option<f64>,
+    /// Timestep the engine was built with, restored at the start of every run.
+    configured_md_timestep: Option<f64>,
+    openmm_cache: Option<PathBuf>,
+    /// Directory native physics spans write their MD frames to.
+    native_trajectories: Option<PathBuf>,
+    /// Native bridges kept per physics level across the spans of a run.
+    native_bridges: NativeBridges,
+    /// Velocities the first run's bridges start from, when resuming.
+    resume_velocities: NativeVelocities,
+    geometry_validator: GeometryValidator,
+    environment: Environment,
+    /// Environment and crowders the engine was built with; every run starts
+    /// from them, whatever earlier contracts switched to.
+    configured_environment: Environment,
+    configured_crowders: Option<CrowderField>,
+    /// Temperature, schedule and acceptance temperature the engine was built
+    /// with, restored at the start of every run like the environment.
+    configured_temperature: f64,
+    configured_temperature_schedule: Option<TemperatureSchedule>,
+    configured_acceptance_temperature: Option<f64>,
+    /// Contract ramps still moving an environment parameter.
+    environment_ramps: Vec<EnvironmentRamp>,
+    crowders: Option<CrowderField>,
+    symmetry: Option<Symmetry>,
+    contact_prior: Option<ContactPrior>,
+    time_scale: TimeScale,
+    refined_model: Option<Vec<BackmappedResidue>>,
+    ghost_budget: Option<GhostBudget>,
+    ghost_budget_exceeded_at: Option<usize>,
+    ghosts_pruned: usize,
+    /// Heap estimate (bytes) the run's data is kept under by thinning frames.
+    memory_cap: Option<usize>,
+    memory_cap_exceeded_at: Option<usize>,
+    /// Product of ghost-budget escalations, applied on top of the schedule.
+    temperature_scale: f64,
+    /// Record the RNG state every this many accepted spans.
+    rng_log_interval: Option<usize>,
+    rng_checkpoints: Vec<RngCheckpoint>,
+    /// Split the force-field energy into its terms every this many accepted
+    /// spans.
+    energy_component_stride: Option<usize>,
+    energy_components: Vec<EnergyComponentSample>,
+    /// Bridge the components are evaluated with, rebuilt only when the
+    /// level, environment or force field changes.
+    component_bridge: ComponentBridge,
+    /// Analyse the final chain's frustration with these options.
+    frustration: Option<FrustrationOptions>,
+    /// Energy of every conformation judged this run, for the foldability index.
+    energy_samples: EnergySamples,
+    observers: Vec<Box<dyn EngineObserver>>,
+    stopped_by_observer: Option<usize>,
+    stopped_by_ghost_budget: Option<usize>,
+    scoring_terms: Vec<ScoringTerm>,
+    /// Summed score change of accepted spans, per scoring term.
+    score_totals: Vec<f64>,
+    collective_variables: Vec<CollectiveVariable>,
+    /// Relative tolerance of the energy bookkeeping checks; `None` skips them.
+    thermo_tolerance: Option<f64>,
+    thermo_discrepancies: Vec<ThermoDiscrepancy>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geometry_validation::GeometryCheckKind;
+    use crate::ghost_budget::GhostLimit;
+    use crate::restraints::DistanceRestraint;
+    use crate::test_support::permissive_ruleset;
 
     #[test]
     fn constant_schedule_returns_initial() {
@@ -111,215 +306,2166 @@ mod tests {
         assert!((schedule.temperature_for_step(10, 400.0) - 300.0).abs() < 1e-6);
         assert!((schedule.temperature_for_step(20, 400.0) - 300.0).abs() < 1e-6);
     }
-}
 
-pub struct FoldingEngineBuilder {
-    chain: Option<PeptideChain>,
-    energy_model: Option<EnergyModel>,
-    oscillator: Option<MicroOscillator>,
-    clock: Option<RotationClock>,
-    ruleset: Option<Ruleset>,
-    temperature: Option<f64>,
-    rng_seed: Option<u64>,
-    temperature_schedule: Option<TemperatureSchedule>,
-    physics_level: Option<PhysicsLevel>,
-    physics_engine: Option<PhysicsEngine>,
-}
+    fn permissive_engine(sequence: &str) -> FoldingEngine {
+        FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence(sequence))
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_ruleset(permissive_ruleset())
+            .with_temperature(1.0e6)
+            .with_rng_seed(3)
+            .build()
+    }
 
-pub struct ExecutionReport {
-    pub applied_rotations: Vec<RotationOutcome>,
-    pub ghost_rotations: Vec<RotationOutcome>,
-    pub rejections: Vec<RuleViolation>,
-    pub final_energy: EnergyState,
-    pub trajectory: Trajectory,
-    pub metropolis_stats: MetropolisStats,
-    pub domains: Vec<DomainDefinition>,
-    pub chaperone_requirements: Vec<ChaperoneRequirement>,
-    pub modifications: Vec<PostTranslationalModification>,
-    pub physics_level: PhysicsLevel,
-    pub physics_spans: Vec<String>,
-    pub physics_span_metrics: Vec<PhysicsSpanRecord>,
-}
+    #[test]
+    fn segment_rotation_is_one_span() {
+        let mut engine = permissive_engine("AGSVLK");
+        let contract = FoldingContract::from_lines(&["rotate 1..3 psi by 20"]).unwrap();
+        let report = engine.execute_contract(&contract);
 
-#[derive(Clone, Debug)]
-pub struct DomainDefinition {
-    pub name: Option<String>,
-    pub start: ResidueId,
-    pub end: ResidueId,
-}
+        assert!(report.rejections.is_empty());
+        assert_eq!(report.applied_rotations.len(), 1);
+        assert_eq!(report.trajectory.len(), 1);
+        assert_eq!(report.applied_rotations[0].span_record.id.as_str(), "segment-1-3-psi");
+        assert_eq!(report.final_chain.psi(), [0.0, 20.0, 20.0, 20.0, 0.0, 0.0]);
+    }
 
-#[derive(Clone, Debug)]
-pub struct ChaperoneRequirement {
-    pub chaperone: String,
-    pub span: Option<String>,
-}
+    #[test]
+    fn segment_records_the_turn_its_members_made() {
+        // Phi starts at 0: alanine may reach +90°, proline only turns toward -65°
+        let mut engine = permissive_engine("AAPAA");
+        let contract = FoldingContract::from_lines(&["rotate 1..3 phi by 120"]).unwrap();
+        let report = engine.execute_contract(&contract);
 
-#[derive(Clone, Debug)]
-pub struct PostTranslationalModification {
-    pub modification: String,
-    pub residue: ResidueId,
-}
+        assert_eq!(report.final_chain.phi(), [0.0, 90.0, 0.0, 90.0, 0.0]);
+        let outcome = &report.applied_rotations[0];
+        assert_eq!(outcome.applied_angle, 60.0);
+        assert_eq!(outcome.span_record.delta_theta, 60.0);
+    }
 
-#[derive(Clone, Debug)]
-pub struct PhysicsSpanRecord {
-    pub span_id: String,
-    pub metrics: PhysicsSpanMetrics,
-}
+    #[test]
+    fn domain_moves_and_loop_closure_keep_the_rest_fixed() {
+        let mut engine = permissive_engine("AGSVLKTR");
+        let start = chain_positions(&PeptideChain::from_sequence("AGSVLKTR"));
+        let contract = FoldingContract::from_lines(&[
+            "define_domain tail 5-7",
+            "move_domain tail translate 1 0 0",
+            "move_domain tail rotate 30 about z",
+            "move_domain head rotate 5",
+        ])
+        .unwrap();
+        let report = engine.execute_contract(&contract);
 
-impl FoldingEngineBuilder {
-    pub fn new() -> Self {
-        Self {
-            chain: None,
-            energy_model: None,
-            oscillator: None,
-            clock: None,
-            ruleset: None,
-            temperature: None,
-            rng_seed: None,
-            temperature_schedule: None,
-            physics_level: None,
-            physics_engine: None,
+        assert_eq!(report.applied_rotations.len(), 2);
+        assert!(matches!(
+            report.rejections.as_slice(),
+            [RuleViolation::UnknownDomain { .. }]
+        ));
+        let end = chain_positions(&report.final_chain);
+        assert_eq!(&end[..5], &start[..5]);
+        let gap = |positions: &[[f64; 3]], a: usize, b: usize| {
+            (0..3)
+                .map(|axis| (positions[a][axis] - positions[b][axis]).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        };
+        assert!((gap(&end, 5, 7) - gap(&start, 5, 7)).abs() < 1e-9);
+        assert!((end[6][2] - start[6][2]).abs() < 1e-9);
+        assert!((gap(&end, 4, 5) - gap(&start, 4, 5)).abs() > 1e-3);
+
+        let mut engine = permissive_engine("AGSVLKTR");
+        let contract =
+            FoldingContract::from_lines(&["close_loop 1-6 by 40", "close_loop 2-4"]).unwrap();
+        let report = engine.execute_contract(&contract);
+        assert_eq!(report.applied_rotations.len(), 1);
+        assert!(matches!(
+            report.rejections.as_slice(),
+            [RuleViolation::LoopNotClosed { .. }]
+        ));
+        let closed = chain_positions(&report.final_chain);
+        assert_eq!(&closed[..3], &start[..3]);
+        assert_eq!(&closed[6..], &start[6..]);
+        assert!((gap(&closed, 5, 6) - gap(&start, 5, 6)).abs() < 0.1);
+        assert!((3..6).any(|index| gap(&[closed[index], start[index]], 0, 1) > 1e-3));
+    }
+
+    #[test]
+    fn frozen_residues_refuse_direct_and_indirect_moves() {
+        let mut engine = permissive_engine("AGSVLKTR");
+        let start = chain_positions(&PeptideChain::from_sequence("AGSVLKTR"));
+        let contract = FoldingContract::from_lines(&[
+            "rotate 6 psi by 15",
+            "freeze 4..7",
+            "rotate 5 by 10",
+            // Turns residues 3.. about the 1-2 bond, carrying the frozen core
+            "rotate 1 psi by 10",
+            "rotate 1 by 10",
+            "move_domain 3-6 translate 1 0 0",
+        ])
+        .unwrap();
+        let report = engine.execute_contract(&contract);
+
+        assert_eq!(report.applied_rotations.len(), 2);
+        assert_eq!(
+            report.frozen,
+            [FrozenRegion {
+                start: ResidueId(4),
+                end: ResidueId(7),
+                step: 1,
+            }]
+        );
+        assert_eq!(report.rejections.len(), 3);
+        assert!(report.rejections.iter().all(|violation| violation.code() == "R017"));
+        let end = chain_positions(&report.final_chain);
+        assert_eq!(&end[4..], &start[4..]);
+        assert_ne!(end[1], start[1]);
+    }
+
+    #[test]
+    fn backrub_keeps_both_virtual_bonds() {
+        let mut engine = permissive_engine("AGSVLK");
+        let start = chain_positions(&PeptideChain::from_sequence("AGSVLK"));
+        let contract =
+            FoldingContract::from_lines(&["backrub 2 by 25", "backrub 3", "backrub 0"]).unwrap();
+        let report = engine.execute_contract(&contract);
+
+        assert_eq!(report.applied_rotations.len(), 2);
+        assert!(report.applied_rotations[1].applied_angle.abs() <= BACKRUB_MAX_DEGREES);
+        assert!(matches!(
+            report.rejections.as_slice(),
+            [RuleViolation::TerminalResidue { .. }]
+        ));
+        let end = chain_positions(&report.final_chain);
+        let bond = |positions: &[[f64; 3]], a: usize| {
+            (0..3)
+                .map(|axis| (positions[a][axis] - positions[a + 1][axis]).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        };
+        for a in 0..5 {
+            assert!((bond(&end, a) - bond(&start, a)).abs() < 1e-9);
         }
+        assert_ne!(end[2], start[2]);
+        assert_eq!(end[4], start[4]);
     }
 
-    pub fn with_chain(mut self, chain: PeptideChain) -> Self {
-        self.chain = Some(chain);
-        self
+    #[test]
+    fn rotamer_flips_set_every_chi_of_the_residue() {
+        let mut engine = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGLKF"))
+            .with_side_chains(SideChainBead::Centroid)
+            .with_ruleset(permissive_ruleset())
+            .with_temperature(1.0e6)
+            .with_rng_seed(3)
+            .build();
+        let contract = FoldingContract::from_lines(&[
+            "rotamer 2 mt",
+            "rotamer 3",
+            "rotamer 1 t",
+            "rotamer 4 tt",
+        ])
+        .unwrap();
+        let report = engine.execute_contract(&contract);
+
+        assert_eq!(report.applied_rotations.len(), 2);
+        assert_eq!(
+            report.applied_rotations[0].span_record.id.as_str(),
+            "residue-2-rotamer-mt"
+        );
+        let codes: Vec<&str> = report.rejections.iter().map(|v| v.code()).collect();
+        assert_eq!(codes, ["R012", "R020"]);
+        let chain = &report.final_chain;
+        assert_eq!(chain.side_chains(), Some(SideChainBead::Centroid));
+        assert_eq!(chain.chi()[2][..2], [-65.0, 175.0]);
+        // A random flip lands on another library state, all four chi set
+        let lysine = nearest_rotamer("LYS", chain.chi()[3]).unwrap();
+        assert_eq!(chain.chi()[3], lysine.chi);
+        assert_ne!(lysine.name, nearest_rotamer("LYS", [0.0; 4]).unwrap().name);
     }
 
-    pub fn with_energy_model(mut self, energy_model: EnergyModel) -> Self {
-        self.energy_model = Some(energy_model);
-        self
+    #[test]
+    fn rotamer_flips_validate_every_chi() {
+        let mut chain = PeptideChain::from_sequence("AGLKF");
+        chain.chi_mut()[2] = [-65.0, 175.0, 0.0, 0.0];
+        let mut engine = FoldingEngineBuilder::new()
+            .with_chain(chain)
+            .with_side_chains(SideChainBead::Centroid)
+            .with_ruleset(Ruleset {
+                max_rotation_degrees: 60.0,
+                ..permissive_ruleset()
+            })
+            .with_temperature(1.0e6)
+            .build();
+        // mt -> mp turns chi1 by 20 degrees but chi2 by 110
+        let contract = FoldingContract::from_lines(&["rotamer 2 mp"]).unwrap();
+        let report = engine.execute_contract(&contract);
+        assert!(report.applied_rotations.is_empty());
+        assert_eq!(report.rejections[0].code(), "R001");
+        assert_eq!(report.final_chain.chi()[2][..2], [-65.0, 175.0]);
     }
 
-    pub fn with_oscillator(mut self, oscillator: MicroOscillator) -> Self {
-        self.oscillator = Some(oscillator);
-        self
+    #[test]
+    fn recovery_policies_halve_or_abort() {
+        let ruleset = permissive_ruleset()
+            .with_rotation_limit(30.0)
+            .with_recovery("R001", RecoveryPolicy::HalveAndRetry { attempts: 3 });
+        let build = |ruleset: Ruleset| {
+            FoldingEngineBuilder::new()
+                .with_chain(PeptideChain::from_sequence("AGSVLK"))
+                .with_oscillator(MicroOscillator::new(0.0, 0.0))
+                .with_ruleset(ruleset)
+                .with_temperature(1.0e6)
+                .with_rng_seed(3)
+                .build()
+        };
+        let contract = FoldingContract::from_lines(&["rotate 2 100", "rotate 3 10"]).unwrap();
+
+        let report = build(ruleset.clone()).execute_contract(&contract);
+        assert!(report.rejections.is_empty());
+        assert_eq!(report.applied_rotations.len(), 2);
+        assert!((report.applied_rotations[0].span_record.delta_theta - 25.0).abs() < 1e-9);
+        assert_eq!(report.recoveries.len(), 1);
+        assert!(report.recoveries[0].recovered);
+
+        let report = build(ruleset.with_recovery("R001", RecoveryPolicy::Abort))
+            .execute_contract(&contract);
+        assert_eq!(report.rejections.len(), 1);
+        assert!(report.applied_rotations.is_empty());
+        assert_eq!(report.recoveries[0].policy, RecoveryPolicy::Abort);
     }
 
-    pub fn with_clock(mut self, clock: RotationClock) -> Self {
-        self.clock = Some(clock);
-        self
+    #[test]
+    fn outcome_tells_refused_spans_from_an_aborted_run() {
+        let ruleset = permissive_ruleset().with_rotation_limit(30.0);
+        let build = |ruleset: Ruleset| {
+            FoldingEngineBuilder::new()
+                .with_chain(PeptideChain::from_sequence("AGSVLK"))
+                .with_oscillator(MicroOscillator::new(0.0, 0.0))
+                .with_ruleset(ruleset)
+                .with_temperature(1.0e6)
+                .with_rng_seed(3)
+                .build()
+        };
+        let contract = FoldingContract::from_lines(&["rotate 2 100", "rotate 3 10"]).unwrap();
+
+        // The over-limit span is refused, the run goes on to the end
+        let report = build(ruleset.clone()).execute_contract(&contract);
+        assert_eq!(report.applied_rotations.len(), 1);
+        assert_eq!(report.outcome(), RunOutcome::CompletedWithViolations);
+        assert_eq!(report.outcome().as_str(), "completed_with_violations");
+
+        let report =
+            build(ruleset.with_recovery("R001", RecoveryPolicy::Abort)).execute_contract(&contract);
+        assert!(report.applied_rotations.is_empty());
+        assert_eq!(report.outcome(), RunOutcome::Aborted);
+
+        let clean = FoldingContract::from_lines(&["rotate 3 10"]).unwrap();
+        let report = permissive_engine("AGSVLK").execute_contract(&clean);
+        assert_eq!(report.outcome(), RunOutcome::Completed);
     }
 
-    pub fn with_ruleset(mut self, ruleset: Ruleset) -> Self {
-        self.ruleset = Some(ruleset);
-        self
+    #[test]
+    fn ghost_budget_prunes_or_stops() {
+        let contract = FoldingContract::from_lines(&[
+            "ghost on",
+            "rotate 1 5",
+            "rotate 2 5",
+            "rotate 3 5",
+            "ghost off",
+            "rotate 2 5",
+        ]).unwrap();
+        let run = |overflow: GhostOverflow| {
+            let mut engine = permissive_engine("AGSVLK");
+            engine.ghost_budget = Some(GhostBudget::new(GhostLimit::Count(1), overflow));
+            engine.execute_contract(&contract)
+        };
+
+        let pruned = run(GhostOverflow::Prune);
+        assert_eq!(pruned.ghost_rotations.len(), 1);
+        assert_eq!(pruned.ghosts_pruned, 2);
+        assert_eq!(pruned.ghost_rotations[0].span_record.id, "residue-3");
+        assert_eq!(pruned.applied_rotations.len(), 1);
+
+        let stopped = run(GhostOverflow::Stop);
+        assert_eq!(stopped.ghost_rotations.len(), 2);
+        assert!(stopped.ghost_budget_exceeded_at.is_some());
+        assert!(stopped.applied_rotations.is_empty());
+        assert_eq!(stopped.outcome(), RunOutcome::Truncated);
+        assert_eq!(pruned.outcome(), RunOutcome::Completed);
     }
 
-    pub fn with_temperature(mut self, temperature: f64) -> Self {
-        self.temperature = Some(temperature);
-        self
+    #[test]
+    fn scoring_terms_join_the_metropolis_criterion() {
+        let start = chain_positions(&PeptideChain::from_sequence("AGSVLK"));
+        let deviation = move |chain: &PeptideChain| -> f64 {
+            chain_positions(chain)
+                .iter()
+                .zip(&start)
+                .map(|(a, b)| (0..3).map(|axis| (a[axis] - b[axis]).abs()).sum::<f64>())
+                .sum()
+        };
+        let run = |weight: f64| {
+            let deviation = deviation.clone();
+            let mut engine = FoldingEngineBuilder::new()
+                .with_chain(PeptideChain::from_sequence("AGSVLK"))
+                .with_oscillator(MicroOscillator::new(0.0, 0.0))
+                .with_ruleset(permissive_ruleset())
+                .with_temperature(1.0e6)
+                .with_score("restraint", move |chain| weight * deviation(chain))
+                .build();
+            let contract = FoldingContract::from_lines(&["rotate 2 20", "rotate 3 -15"]).unwrap();
+            engine.execute_contract(&contract)
+        };
+
+        let penalised = run(1.0e9);
+        assert!(penalised.applied_rotations.is_empty());
+        assert_eq!(penalised.rejections.len(), 2);
+        assert!(penalised.rejections.iter().all(|violation| violation.code() == "R006"));
+        assert_eq!(penalised.score_deltas, vec![("restraint".to_string(), 0.0)]);
+
+        let rewarded = run(-1.0e9);
+        assert_eq!(rewarded.applied_rotations.len(), 2);
+        assert!(rewarded.score_deltas[0].1 < 0.0);
     }
 
-    pub fn with_rng_seed(mut self, seed: u64) -> Self {
-        self.rng_seed = Some(seed);
-        self
+    #[test]
+    fn energy_components_are_sampled_at_the_stride() {
+        let builder = || {
+            FoldingEngineBuilder::new()
+                .with_chain(PeptideChain::from_sequence("AGSVLKTR"))
+                .with_ruleset(permissive_ruleset())
+        };
+        let lines: Vec<String> = (0..7)
+            .map(|step| format!("rotate {} psi by -40", 1 + step))
+            .collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let contract = FoldingContract::from_lines(&lines).unwrap();
+
+        let report = builder()
+            .with_energy_component_stride(3)
+            .build()
+            .execute_contract(&contract);
+        assert_eq!(report.applied_rotations.len(), 7);
+        let steps: Vec<usize> = report.energy_components.iter().map(|s| s.step).collect();
+        assert_eq!(steps, [0, 3, 6, 7]);
+        let first = &report.energy_components[0];
+        let last = &report.energy_components[3];
+        assert_eq!(first.time_ms, 0.0);
+        assert!(last.time_ms > first.time_ms);
+        assert_eq!(last.level, report.physics_level);
+        assert_ne!(first.components, last.components);
+        assert!(last.components.total().is_finite());
+        assert_eq!(last.residues.len(), 8);
+        let attributed: f64 = last.residues.iter().sum();
+        assert!((attributed - last.components.total()).abs() < 1e-6);
+
+        let unsampled = builder().build().execute_contract(&contract);
+        assert!(unsampled.energy_components.is_empty());
+        assert!(unsampled.frustration.is_none());
     }
 
-    pub fn with_temperature_schedule(mut self, schedule: TemperatureSchedule) -> Self {
-        self.temperature_schedule = Some(schedule);
-        self
+    #[test]
+    fn frustration_is_analysed_on_the_final_chain() {
+        let contract = FoldingContract::from_lines(&["rotate 3 psi by -60"]).unwrap();
+        let report = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("KAGSVLKTRE"))
+            .with_frustration_analysis(FrustrationOptions {
+                decoys: 50,
+                ..FrustrationOptions::default()
+            })
+            .build()
+            .execute_contract(&contract);
+        let frustration = report.frustration.unwrap();
+        assert_eq!(frustration.residues.len(), report.final_chain.len());
+        assert!(frustration.contacts.iter().all(|contact| {
+            contact.j >= contact.i + 3 && contact.distance <= 6.5 && contact.index.is_finite()
+        }));
     }
 
-    pub fn with_physics_level(mut self, level: PhysicsLevel) -> Self {
-        self.physics_level = Some(level);
-        self
+    #[test]
+    fn dimer_chains_fold_side_by_side_without_a_bond_between_them() {
+        let mut partner = PeptideChain::from_sequence("GSVLK");
+        for position in partner.positions_mut() {
+            position[0] += 12.0;
+        }
+        let mut system = MolecularSystem::new();
+        system
+            .push('A', PeptideChain::from_sequence("KAGSV"))
+            .unwrap();
+        system.push('H', partner.clone()).unwrap();
+        let contract =
+            FoldingContract::from_lines(&["rotate 2 psi by 40", "rotate 3 psi by -30"]).unwrap();
+        let report = FoldingEngineBuilder::new()
+            .with_system(&system)
+            .with_ruleset(Ruleset {
+                // The 12 Å gap between the chains is not a bond to check
+                bond_distance_range: Some((2.0, 3.0)),
+                ..permissive_ruleset()
+            })
+            .build()
+            .execute_contract(&contract);
+
+        assert_eq!(report.applied_rotations.len(), 2);
+        assert_eq!(report.final_chain.chain_breaks(), [5]);
+        let dimer = report.final_system();
+        assert_eq!(dimer.ids(), ['A', 'H']);
+        // Turning chain A's torsions leaves its partner where it was
+        assert_eq!(dimer.chain('H').unwrap().positions(), partner.positions());
+        assert_ne!(
+            dimer.chain('A').unwrap().positions(),
+            system.chain('A').unwrap().positions()
+        );
     }
 
-    pub fn with_physics_engine(mut self, engine: PhysicsEngine) -> Self {
-        self.physics_engine = Some(engine);
-        self
+    #[test]
+    fn every_judged_conformation_is_a_foldability_sample() {
+        let mut engine = permissive_engine("AGSVLKTR");
+        let lines = ["rotate 2 psi by 40", "rotate 4 psi by -60", "rotate 5 phi by 30"];
+        let report = engine.execute_contract(&FoldingContract::from_lines(&lines).unwrap());
+
+        // The start plus one sample per move
+        assert_eq!(report.energy_samples.len(), 4);
+        let foldability = report.energy_samples.foldability().unwrap();
+        assert_eq!(foldability.decoys, 3);
+        assert!(foldability.best_energy <= report.final_energy.total_potential + 1e-9);
+        assert!(foldability.gap >= 0.0 && foldability.index.is_finite());
+
+        // A new physics level starts the samples over from where the chain is
+        let lines = [
+            "rotate 2 psi by 40",
+            "rotate 4 psi by -60",
+            "set_physics_level coarse",
+            "rotate 5 phi by 30",
+        ];
+        let report = engine.execute_contract(&FoldingContract::from_lines(&lines).unwrap());
+        assert_eq!(report.energy_samples.len(), 2);
     }
 
-    pub fn build(self) -> FoldingEngine {
-        let chain = self.chain.expect("chain not provided");
-        let energy_model = self.energy_model.unwrap_or_default();
-        let oscillator = self
-            .oscillator
-            .unwrap_or_else(|| MicroOscillator::new(10.0, 1.0));
-        let clock = self.clock.unwrap_or_else(|| RotationClock::new(1));
-        let ruleset = self.ruleset.unwrap_or_default();
-        let mut temperature = self.temperature.unwrap_or(310.0);
-        let temperature_schedule = self.temperature_schedule.clone();
-        if let Some(TemperatureSchedule::Linear { start, .. }) = &temperature_schedule {
-            temperature = *start;
+    #[test]
+    fn resuming_from_a_recorded_rng_state_reproduces_the_stream() {
+        let builder = |chain: PeptideChain| {
+            FoldingEngineBuilder::new()
+                .with_chain(chain)
+                .with_ruleset(permissive_ruleset())
+                .with_temperature(300.0)
+                .with_rng_seed(3)
+                .with_rng_log_interval(4)
+        };
+        let moves: Vec<String> = (0..24)
+            .map(|step| format!("rotate {} {}", 1 + step % 6, 25 - 10 * (step % 5)))
+            .collect();
+        let contract = |lines: &[String]| {
+            let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+            FoldingContract::from_lines(&lines).unwrap()
+        };
+        let chain = PeptideChain::from_sequence("AGSVLKTR");
+        let full = builder(chain.clone())
+            .build()
+            .execute_contract(&contract(&moves));
+        let first = builder(chain).build().execute_contract(&contract(&moves[..12]));
+        let resume = first.rng_checkpoints.last().unwrap();
+        assert_eq!(resume.step, 12);
+        let second = builder(first.final_chain.clone())
+            .with_rng_state(resume.state)
+            .with_resume_step(resume.step)
+            .build()
+            .execute_contract(&contract(&moves[12..]));
+
+        assert_eq!(full.rng_checkpoints.len(), full.applied_rotations.len() / 4 + 1);
+        let last_state = |report: &ExecutionReport| report.rng_checkpoints.last().unwrap().state;
+        assert_eq!(last_state(&full), last_state(&second));
+        // The resumed run carries on the step numbering of the first
+        assert_eq!(full.rng_checkpoints.last(), second.rng_checkpoints.last());
+        assert_eq!(
+            second.applied_rotations[0].span_record.step,
+            full.applied_rotations[first.applied_rotations.len()]
+                .span_record
+                .step
+        );
+        assert!(!full.rejections.is_empty());
+        assert_eq!(full.final_chain.positions(), second.final_chain.positions());
+        let angles = |report: &ExecutionReport| -> Vec<f64> {
+            report.applied_rotations.iter().map(|outcome| outcome.applied_angle).collect()
+        };
+        assert_eq!(angles(&full), [angles(&first), angles(&second)].concat());
+    }
+
+    #[test]
+    fn collective_variables_are_evaluated_after_each_accepted_span() {
+        let variables: Vec<CollectiveVariable> = ["end_to_end", "phi:2", "rg", "end_to_end"]
+            .iter()
+            .map(|raw| CollectiveVariable::parse(raw).unwrap())
+            .collect();
+        let mut builder = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGSVLK"))
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_ruleset(permissive_ruleset())
+            .with_temperature(1.0e6);
+        for variable in variables.iter().cloned() {
+            builder = builder.with_collective_variable(variable);
         }
-        let rng = match self.rng_seed {
-            Some(seed) => SimpleRng::seed_from_u64(seed),
-            None => SimpleRng::from_entropy(),
+        let contract = FoldingContract::from_lines(&["rotate 2 20", "rotate 3 -15"]).unwrap();
+        let report = builder.build().execute_contract(&contract);
+
+        assert_eq!(report.collective_variables, ["end_to_end", "phi:2", "rg"]);
+        assert_eq!(report.applied_rotations.len(), 2);
+        let first = &report.applied_rotations[0].collective_variables;
+        let last = &report.applied_rotations[1].collective_variables;
+        assert_eq!(first[1], last[1]);
+        let expected: Vec<f64> = variables[..3]
+            .iter()
+            .map(|variable| variable.evaluate(&report.final_chain))
+            .collect();
+        assert_eq!(*last, expected);
+    }
+
+    #[test]
+    fn simulate_records_one_span_for_an_md_burst() {
+        let mut engine = permissive_engine("AGSVLK");
+        let before = engine.state.chain.positions().to_vec();
+        let contract = FoldingContract::from_lines(&["simulate 0.1ps at coarse"]).unwrap();
+        let report = engine.execute_contract(&contract);
+
+        assert!(report.rejections.is_empty(), "{:?}", report.rejections);
+        assert_eq!(report.applied_rotations.len(), 1);
+        let span = &report.applied_rotations[0];
+        assert_eq!(span.span_record.id.as_str(), "md-0.1ps-coarse");
+        assert!(span.physics_metrics.is_some());
+        assert!((span.span_record.simulated_ps - 0.1).abs() < 1e-9);
+        assert_ne!(report.final_chain.positions(), before.as_slice());
+    }
+
+    #[test]
+    fn timestep_directive_is_checked_against_the_force_field() {
+        let mut engine = permissive_engine("AGSVLK");
+        let contract =
+            FoldingContract::from_lines(&["timestep 1fs", "simulate 10fs at coarse"]).unwrap();
+        let report = engine.execute_contract(&contract);
+        assert!(report.backend_failures.is_empty());
+        assert_eq!(report.applied_rotations.len(), 1);
+
+        let contract =
+            FoldingContract::from_lines(&["timestep 50fs", "simulate 0.1ps at coarse"]).unwrap();
+        let report = engine.execute_contract(&contract);
+        assert!(report.applied_rotations.is_empty());
+        assert_eq!(report.backend_failures.len(), 1);
+        let reason = &report.backend_failures[0].failure.reason;
+        assert!(reason.contains("stability limit of 0.02 ps"), "{reason}");
+
+        // The next run integrates with the level's default timestep again
+        let contract = FoldingContract::from_lines(&["simulate 0.1ps at coarse"]).unwrap();
+        let report = engine.execute_contract(&contract);
+        assert!(report.backend_failures.is_empty());
+        assert_eq!(engine.md_timestep, None);
+
+        use crate::physics_bridge::check_md_timestep;
+        assert!(check_md_timestep(PhysicsLevel::Coarse, None, 0.01).is_ok());
+        assert!(check_md_timestep(PhysicsLevel::Gb, None, 0.01).is_err());
+        assert!(check_md_timestep(PhysicsLevel::Gb, Some("coarse-grained"), 0.01).is_ok());
+        assert!(check_md_timestep(PhysicsLevel::Coarse, None, 0.0).is_err());
+        assert!(check_md_timestep(PhysicsLevel::Coarse, None, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn physics_engine_directive_selects_the_backend_recorded_per_span() {
+        let mut engine = permissive_engine("AGSVLK");
+        let contract = FoldingContract::from_lines(&[
+            "physics_engine native",
+            "set_physics_level coarse",
+            "physics_span on",
+            "rotate 2 15",
+        ])
+        .unwrap();
+        let report = engine.execute_contract(&contract);
+
+        assert_eq!(report.physics_engine, PhysicsEngine::Native);
+        assert_eq!(report.physics_span_metrics.len(), 1);
+        assert_eq!(report.physics_span_metrics[0].metrics.backend, "native");
+        let metrics = report.applied_rotations[0].physics_metrics.as_ref().unwrap();
+        assert_eq!(metrics.backend, "native");
+    }
+
+    #[test]
+    fn native_velocities_follow_accepted_spans_and_reset_per_run() {
+        let mut engine = permissive_engine("AGSVLK");
+        let contract = FoldingContract::from_lines(&[
+            "physics_engine native",
+            "set_physics_level coarse",
+            "physics_span on",
+            "rotate 2 15",
+            "commit",
+            "ghost on",
+            "rotate 3 -10",
+            "ghost off",
+        ])
+        .unwrap();
+        let report = engine.execute_contract(&contract);
+        assert_eq!(report.ghost_rotations.len(), 1);
+        let committed = &report.rng_checkpoints[0].velocities;
+        assert!(committed.level(PhysicsLevel::Coarse).is_some());
+        // The ghost span ran MD but leaves the velocities where it found them
+        assert_eq!(&engine.native_bridges.velocities(), committed);
+
+        let contract = FoldingContract::from_lines(&["physics_span off", "rotate 2 15"]).unwrap();
+        let report = engine.execute_contract(&contract);
+        assert_eq!(report.applied_rotations.len(), 1);
+        assert!(engine.native_bridges.is_empty());
+    }
+
+    #[test]
+    fn thermo_checks_report_inconsistent_bookkeeping() {
+        let checked = |lines: &[&str], acceptance_temperature: Option<f64>| {
+            let mut engine = permissive_engine("AGSVLKEQ");
+            engine.thermo_tolerance = Some(thermo_checks::DEFAULT_THERMO_TOLERANCE);
+            engine.configured_acceptance_temperature = acceptance_temperature;
+            let contract = FoldingContract::from_lines(lines).unwrap();
+            let report = engine.execute_contract(&contract);
+            report
+                .thermo_discrepancies
+                .iter()
+                .map(|discrepancy| discrepancy.check)
+                .collect::<Vec<_>>()
         };
-        let state = ProteinState::new(chain, energy_model);
-        let solver = RotationSolver::new(oscillator, clock);
-        let validator = Validator::new(ruleset);
-        let physics_level = self.physics_level.unwrap_or(PhysicsLevel::Toy);
-        let physics_engine = self.physics_engine.unwrap_or(PhysicsEngine::Auto);
-        FoldingEngine {
-            state,
-            solver,
-            validator,
-            ghost_mode: false,
-            pending_alias: None,
-            checkpoints: Vec::new(),
-            ghost_trajectory: Trajectory::new(),
-            temperature,
-            boltzmann_constant: 0.0019872041, // kcal·mol⁻¹·K⁻¹
-            rng,
-            temperature_schedule,
-            initial_temperature: temperature,
-            step_index: 0,
-            metropolis_stats: MetropolisStats::default(),
-            domains: Vec::new(),
-            chaperone_requirements: Vec::new(),
-            modifications: Vec::new(),
-            physics_level,
-            span_physics_mode: PhysicsSpanMode::Toy,
-            physics_spans: Vec::new(),
-            physics_span_metrics: Vec::new(),
-            physics_engine,
+        let moves = ["rotate 2..4 psi by 30", "rotate 3..5 psi by -25"];
+        assert!(checked(&moves, None).is_empty());
+        assert_eq!(
+            checked(&moves, Some(600.0)),
+            [ThermoCheck::Temperature, ThermoCheck::Temperature]
+        );
+        // Without a commit, rollback drops the span but keeps the moved chain.
+        assert_eq!(
+            checked(&["move_domain 4-7 translate 0.5 0 0", "rollback"], None),
+            [ThermoCheck::EnergyBalance]
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "openmm"))]
+    fn backend_failures_are_reported_and_can_abort_the_run() {
+        let contract = FoldingContract::from_lines(&[
+            "physics_engine openmm",
+            "physics_span on",
+            "rotate 2 15",
+            "rotate 3 -10",
+        ])
+        .unwrap();
+        let mut engine = permissive_engine("AGSVLK");
+        let degraded = engine.execute_contract(&contract);
+        assert_eq!(degraded.applied_rotations.len(), 2);
+        assert_eq!(degraded.backend_failures.len(), 2);
+        assert_eq!(degraded.backend_failures[0].failure.backend, "openmm");
+        assert_eq!(degraded.backend_failures[0].fallback, "toy");
+        assert_eq!(degraded.outcome(), RunOutcome::BackendFallback);
+
+        engine.require_backend = true;
+        let strict = engine.execute_contract(&contract);
+        assert!(strict.applied_rotations.is_empty());
+        assert_eq!(strict.backend_failures.len(), 1);
+        assert_eq!(strict.backend_failures[0].fallback, "none");
+        assert_eq!(strict.recoveries[0].policy, RecoveryPolicy::Abort);
+        assert_eq!(strict.recoveries[0].violation.code(), "R016");
+        // Aborting outranks the fallback that came before it
+        assert_eq!(strict.outcome(), RunOutcome::Aborted);
+    }
+
+    #[test]
+    fn observers_see_spans_and_can_stop_the_run() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Seen {
+            spans: Vec<usize>,
+            rejections: Vec<&'static str>,
+            temperatures: usize,
+        }
+        struct StopAfterTwo(Arc<Mutex<Seen>>);
+        impl EngineObserver for StopAfterTwo {
+            fn on_span_applied(&mut self, step: usize, _outcome: &RotationOutcome) {
+                self.0.lock().unwrap().spans.push(step);
+            }
+            fn on_rejection(&mut self, record: &ViolationRecord) {
+                self.0.lock().unwrap().rejections.push(record.violation.code());
+            }
+            fn on_schedule_step(&mut self, _step: usize, temperature: f64) {
+                assert_eq!(temperature, 1.0e6);
+                self.0.lock().unwrap().temperatures += 1;
+            }
+            fn on_energy(&mut self, _step: usize, energy: f64) -> ObserverControl {
+                assert!(energy.is_finite());
+                if self.0.lock().unwrap().spans.len() >= 2 {
+                    ObserverControl::Stop
+                } else {
+                    ObserverControl::Continue
+                }
+            }
         }
+
+        let seen = Arc::new(Mutex::new(Seen::default()));
+        let mut engine = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGSVLK"))
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_ruleset(permissive_ruleset().with_rotation_limit(30.0))
+            .with_temperature(1.0e6)
+            .with_observer(StopAfterTwo(seen.clone()))
+            .build();
+        let contract = FoldingContract::from_lines(&[
+            "rotate 1 90",
+            "rotate 1 5",
+            "rotate 2 5",
+            "rotate 3 5",
+        ]).unwrap();
+        let report = engine.execute_contract(&contract);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.rejections, ["R001"]);
+        assert_eq!(seen.spans.len(), 2);
+        assert_eq!(seen.temperatures, 3);
+        assert_eq!(report.applied_rotations.len(), 2);
+        assert_eq!(report.stopped_by_observer, seen.spans.last().copied());
     }
-}
 
-impl FoldingEngine {
-    pub fn execute_contract(&mut self, contract: &FoldingContract) -> ExecutionReport {
-        let mut applied_rotations = Vec::new();
+    #[test]
+    fn level_switches_are_recorded_in_order() {
+        let mut engine = permissive_engine("AGSVLK");
+        let contract = FoldingContract::from_lines(&[
+            "level coarse",
+            "rotate 2 10",
+            "rotate 3 -5",
+            "level coarse",
+            "level gb",
+            "rotate 2 psi 5",
+        ]).unwrap();
+        let report = engine.execute_contract(&contract);
+
+        assert_eq!(report.physics_level, PhysicsLevel::Gb);
+        assert_eq!(
+            report.level_switches,
+            vec![
+                PhysicsLevelSwitch {
+                    step: 0,
+                    from: PhysicsLevel::Toy,
+                    to: PhysicsLevel::Coarse,
+                },
+                PhysicsLevelSwitch {
+                    step: 2,
+                    from: PhysicsLevel::Coarse,
+                    to: PhysicsLevel::Gb,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn refine_backmaps_the_chain_and_raises_the_level() {
+        let mut engine = permissive_engine("AGSVLK");
+        let contract =
+            FoldingContract::from_lines(&["rotate 2 psi 10", "refine", "rotate 3 5"]).unwrap();
+        let report = engine.execute_contract(&contract);
+
+        assert_eq!(report.physics_level, PhysicsLevel::Gb);
+        assert_eq!(report.applied_rotations[1].span_record.id.as_str(), "refine-gb");
+        assert_eq!(report.applied_rotations[1].applied_angle, 0.0);
+        let model = report.refined_model.expect("refine span accepted");
+        assert_eq!(model.len(), 6);
+        // Frames follow the start and each accepted span
+        assert_eq!(model[2].ca, report.frames[2].positions[2]);
+        assert!(model[1].cb.is_none() && model[0].cb.is_some());
+
+        // The backend relaxed the chain: the model is not the plain backmap
+        let mut unrefined = report.final_chain.clone();
+        unrefined.positions_mut().copy_from_slice(&report.frames[1].positions);
+        let plain = backmap(&unrefined);
+        assert_ne!(model, plain);
+        assert!(report.applied_rotations[1].physics_metrics.is_some());
+    }
+
+    #[test]
+    fn fix_geometry_turns_unintended_cis_peptides_back_to_trans() {
+        // Residue 1's bond runs into the proline and may stay cis.
+        let turns = ["rotate 1 omega 175", "rotate 2 omega 170"];
+        let contract = FoldingContract::from_lines(&[turns[0], turns[1], "fix_geometry"]).unwrap();
+        let report = permissive_engine("AAPAA").execute_contract(&contract);
+
+        assert_eq!(report.applied_rotations.len(), 3);
+        assert_eq!(report.applied_rotations[2].residue, ResidueId(2));
+        assert_eq!(report.applied_rotations[2].applied_angle, -170.0);
+        let omega = report.final_chain.omega();
+        assert_eq!((omega[1], omega[2]), (355.0, 180.0));
+        let cis = report
+            .geometry
+            .checks
+            .iter()
+            .find(|check| check.kind == GeometryCheckKind::CisPeptide)
+            .unwrap();
+        assert_eq!((cis.checked, cis.failures), (4, 0));
+
+        // With every bond already trans there is nothing to record
+        let clean = FoldingContract::from_lines(&["fix_geometry"]).unwrap();
+        let report = permissive_engine("AAPAA").execute_contract(&clean);
+        assert!(report.applied_rotations.is_empty() && report.rejections.is_empty());
+        assert_eq!(report.trajectory.iter().count(), 0);
+
+        // An inverted Cβ in the refined model is rebuilt even so
+        let mut engine = permissive_engine("AAPAA");
+        let mut model = backmap(&engine.state.chain);
+        let ideal = model[3].clone();
+        model[3].cb = ideal.cb.map(|cb| {
+            let ca = ideal.ca;
+            [
+                2.0 * ca[0] - cb[0],
+                2.0 * ca[1] - cb[1],
+                2.0 * ca[2] - cb[2],
+            ]
+        });
+        engine.refined_model = Some(model);
+        assert!(engine.execute_fix_geometry(1).unwrap().is_none());
+        assert_eq!(engine.refined_model.as_ref().unwrap()[3], ideal);
+
+        let mut guarded = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AAPAA"))
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_ruleset(permissive_ruleset().with_max_omega_deviation(30.0))
+            .with_temperature(1.0e6)
+            .build();
+        let report = guarded.execute_contract(&FoldingContract::from_lines(&turns).unwrap());
+        assert_eq!(report.applied_rotations.len(), 1);
+        assert_eq!(report.rejections[0].code(), "R019");
+    }
+
+    #[test]
+    fn fix_geometry_leaves_chain_breaks_alone() {
+        let mut chain = PeptideChain::from_sequence("AGSVLK");
+        chain.set_chain_breaks(&[3]);
+        let mut engine = FoldingEngineBuilder::new()
+            .with_chain(chain)
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_ruleset(permissive_ruleset())
+            .with_temperature(1.0e6)
+            .build();
+        // Residue 2's omega spans the break between chains A and B
+        let turns = ["rotate 1 omega 170", "rotate 2 omega 180", "fix_geometry"];
+        let report = engine.execute_contract(&FoldingContract::from_lines(&turns).unwrap());
+        assert_eq!(report.applied_rotations.len(), 3);
+        let omega = report.final_chain.omega();
+        assert_eq!((omega[1], omega[2]), (180.0, 360.0));
+    }
+
+    #[test]
+    fn warm_start_resumes_from_previous_coordinates() {
+        let contract = FoldingContract::from_lines(&["rotate 2 psi 30", "commit"]).unwrap();
+        let first = permissive_engine("AGSVLK").execute_contract(&contract);
+        let previous = chain_positions(&first.final_chain);
+
+        let mut resumed = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGSVLK"))
+            .with_initial_coordinates(previous.clone())
+            .with_rng_seed(3)
+            .build();
+        let report = resumed.execute_contract(&FoldingContract::default());
+        assert_eq!(report.frames[0].positions, previous);
+
+        // The torsions follow the coordinates, not the fresh chain's defaults
+        let mut placed = PeptideChain::from_sequence("AGSVLK");
+        place_ca_trace(&mut placed, &previous).unwrap();
+        assert_eq!(report.final_chain.psi(), placed.psi());
+        assert_ne!(placed.psi(), PeptideChain::from_sequence("AGSVLK").psi());
+
+        let anonymous = FoldingEngineBuilder::new()
+            .with_initial_coordinates(previous.clone())
+            .build()
+            .execute_contract(&FoldingContract::default());
+        assert_eq!(anonymous.final_chain.names()[3], "ALA");
+    }
+
+    #[test]
+    fn mismatched_initial_coordinates_are_a_build_error() {
+        let err = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGSVLK"))
+            .with_initial_coordinates(vec![[0.0; 3]; 4])
+            .try_build()
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            "initial coordinates: 4 Cα positions given for a chain of 6 residues"
+        );
+        assert!(FoldingEngineBuilder::new().try_build().is_err());
+    }
+
+    #[test]
+    fn spans_keep_contract_and_simulated_time_apart() {
+        let mut engine = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGSVLK"))
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_ruleset(permissive_ruleset())
+            .with_temperature(1.0e6)
+            .with_time_scale(TimeScale::new(0.5))
+            .with_rng_seed(3)
+            .build();
+        let contract = FoldingContract::from_lines(&[
+            "rotate residue=2 angle=30 duration=40",
+            "rotate residue=3 angle=-10 duration=20",
+        ])
+        .unwrap();
+        let report = engine.execute_contract(&contract);
+        assert_eq!(report.applied_rotations.len(), 2);
+        assert_eq!(report.applied_rotations[0].span_record.simulated_ps, 20.0);
+        assert_eq!(report.trajectory.total_duration(), Duration::from_millis(60));
+        assert_eq!(report.trajectory.total_simulated_ps(), 30.0);
+    }
+
+    #[test]
+    fn rolling_acceptance_covers_the_last_window_of_decisions() {
+        let decisions: Vec<(usize, bool)> = [true, true, false, false, false, true]
+            .into_iter()
+            .enumerate()
+            .map(|(i, accepted)| (i + 1, accepted))
+            .collect();
+        let series = rolling_acceptance(&decisions, 3);
+        let rates: Vec<(usize, usize)> = series.iter().map(|s| (s.accepted, s.total)).collect();
+        assert_eq!(rates, [(1, 1), (2, 2), (2, 3), (1, 3), (0, 3), (1, 3)]);
+        assert_eq!(series[4].step, 5);
+        assert_eq!(series[4].rate(), 0.0);
+        assert!(rolling_acceptance(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn acceptance_temperature_leaves_the_thermostat_alone() {
+        let cold = || {
+            FoldingEngineBuilder::new()
+                .with_chain(PeptideChain::from_sequence("AGSVLKTR"))
+                .with_oscillator(MicroOscillator::new(0.0, 0.0))
+                .with_ruleset(permissive_ruleset())
+                .with_temperature(1.0)
+                .with_rng_seed(3)
+                .with_score("spread", |chain| 100.0 * chain.positions()[7][1])
+        };
+        let moves = ["rotate 2 psi 40", "rotate 3 psi -35", "rotate 4 psi 50", "rotate 2 psi -45"];
+        let contract = FoldingContract::from_lines(&moves).unwrap();
+        let strict = cold().build().execute_contract(&contract);
+        assert!(strict.metropolis_stats.rejected > 0);
+        assert_eq!(strict.acceptance_series.len(), strict.metropolis_stats.total());
+        let last = strict.acceptance_series.last().unwrap();
+        assert_eq!(last.accepted, strict.metropolis_stats.accepted);
+        assert_eq!(last.total, moves.len());
+
+        let mut engine = cold().with_acceptance_temperature(1.0e9).build();
+        let relaxed = engine.execute_contract(&contract);
+        assert_eq!(relaxed.metropolis_stats.rejected, 0);
+        assert_eq!(engine.temperature, 1.0);
+
+        let mut lines = vec!["acceptance_temperature 1000000000"];
+        lines.extend(moves);
+        let mut engine = cold().build();
+        let from_contract = engine.execute_contract(&FoldingContract::from_lines(&lines).unwrap());
+        assert_eq!(from_contract.metropolis_stats.rejected, 0);
+        assert_eq!(engine.temperature, 1.0);
+
+        // The next run starts from the built temperatures again
+        let heated = FoldingContract::from_lines(&[
+            "temperature 350",
+            "ramp temperature 350 -> 400 over 5 steps",
+        ]);
+        engine.execute_contract(&heated.unwrap());
+        assert_eq!(engine.temperature, 350.0);
+        let rerun = engine.execute_contract(&contract);
+        assert_eq!(
+            rerun.metropolis_stats.rejected,
+            strict.metropolis_stats.rejected
+        );
+        assert_eq!(engine.temperature, 1.0);
+        assert_eq!(engine.acceptance_temperature, None);
+    }
+
+    #[test]
+    fn unavailable_score_rejects_the_move() {
+        let mut engine = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGSVLKTR"))
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_ruleset(permissive_ruleset())
+            .with_score("model", |_| f64::NAN)
+            .build();
+        let before = engine.state.chain.psi()[2];
+        let contract = FoldingContract::from_lines(&["rotate 2 psi 40"]).unwrap();
+        let report = engine.execute_contract(&contract);
+        assert_eq!(report.rejections.len(), 1);
+        assert_eq!(report.rejections[0].code(), "R021");
+        assert_eq!(
+            report.rejections[0].fields(),
+            [("term", "model".to_string())]
+        );
+        assert_eq!(engine.state.chain.psi()[2], before);
+    }
+
+    #[test]
+    fn helix_restraint_steers_metropolis_toward_helical_psi() {
+        let cold = || {
+            FoldingEngineBuilder::new()
+                .with_chain(PeptideChain::from_sequence("AGSVLKTR"))
+                .with_oscillator(MicroOscillator::new(0.0, 0.0))
+                .with_ruleset(permissive_ruleset())
+                .with_temperature(1.0)
+                .with_rng_seed(3)
+                .build()
+        };
+        let start_psi = PeptideChain::from_sequence("AGSVLKTR").psi()[3];
+        let toward = if start_psi > -47.0 { -30.0 } else { 30.0 };
+        let toward_line = format!("rotate 3 psi by {toward}");
+        let away_line = format!("rotate 3 psi by {}", -toward);
+        let contract = FoldingContract::from_lines(&[
+            "restrain 3..3 helix k=1000",
+            away_line.as_str(),
+            toward_line.as_str(),
+        ])
+        .unwrap();
+        let report = cold().execute_contract(&contract);
+
+        assert_eq!(report.restraints.len(), 1);
+        assert_eq!(report.applied_rotations.len(), 1);
+        assert!(matches!(
+            report.rejections.as_slice(),
+            [RuleViolation::MetropolisRejected { .. }]
+        ));
+        let expected = report.restraints[0].energy(&report.final_chain);
+        assert!((report.restraint_energy - expected).abs() < 1e-9);
+        let unfolded = PeptideChain::from_sequence("AGSVLKTR");
+        assert!(report.restraint_energy < report.restraints[0].energy(&unfolded));
+    }
+
+    #[test]
+    fn contact_prior_steers_metropolis_toward_the_predicted_distance() {
+        let cold = || {
+            FoldingEngineBuilder::new()
+                .with_chain(PeptideChain::from_sequence("AGSVLKTR"))
+                .with_oscillator(MicroOscillator::new(0.0, 0.0))
+                .with_ruleset(permissive_ruleset())
+                .with_temperature(1.0)
+                .with_rng_seed(3)
+        };
+        // Predict the end-to-end distance the chain reaches after one turn
+        let turned = cold()
+            .build()
+            .execute_contract(&FoldingContract::from_lines(&["rotate 3 psi by 30"]).unwrap());
+        let target = DistanceRestraint {
+            first: ResidueId(0),
+            second: ResidueId(7),
+            lower: 0.0,
+            upper: 0.0,
+            weight: 1.0,
+        };
+        let distance = target.distance(turned.final_chain.positions()).unwrap();
+        let prior = ContactPrior::new(vec![DistanceRestraint {
+            lower: distance,
+            upper: distance,
+            ..target
+        }])
+        .with_force_constant(1000.0);
+
+        let contract =
+            FoldingContract::from_lines(&["rotate 3 psi by -30", "rotate 3 psi by 30"]).unwrap();
+        let report = cold()
+            .with_contact_prior(prior.clone())
+            .build()
+            .execute_contract(&contract);
+
+        assert_eq!(report.applied_rotations.len(), 1);
+        assert!(matches!(
+            report.rejections.as_slice(),
+            [RuleViolation::MetropolisRejected { .. }]
+        ));
+        let expected = prior.energy(report.final_chain.positions());
+        assert!((report.contact_prior_energy - expected).abs() < 1e-9);
+        assert!(
+            report.contact_prior_energy < 1e-6,
+            "{}",
+            report.contact_prior_energy
+        );
+        let unfolded = PeptideChain::from_sequence("AGSVLKTR");
+        assert!(report.contact_prior_energy < prior.energy(unfolded.positions()));
+    }
+
+    #[test]
+    fn spans_carry_their_instruction_tags() {
+        let contract =
+            FoldingContract::from_lines(&["rotate 2 psi 5 #dock", "rotate 3 psi -5"]).unwrap();
+        let report = permissive_engine("AGSVLKTR").execute_contract(&contract);
+        let tags: Vec<&[String]> = report
+            .applied_rotations
+            .iter()
+            .map(|outcome| outcome.span_record.tags.as_slice())
+            .collect();
+        assert_eq!(tags, [&["dock".to_string()][..], &[]]);
+        assert_eq!(report.trajectory.iter().next().unwrap().tags, ["dock"]);
+    }
+
+    #[test]
+    fn phases_with_the_same_name_keep_their_own_totals() {
+        let contract = FoldingContract::from_lines(&[
+            "phase approach",
+            "rotate 2 psi 5",
+            "rotate 3 psi 5",
+            "end_phase",
+            "rotate 4 psi -5",
+            "phase approach",
+            "rotate 5 psi -5",
+        ])
+        .unwrap();
+        let report = permissive_engine("AGSVLKTR").execute_contract(&contract);
+        let phases: Vec<(String, usize)> = report
+            .trajectory
+            .phases()
+            .into_iter()
+            .map(|phase| (phase.path, phase.spans))
+            .collect();
+        assert_eq!(
+            phases,
+            [("approach".to_string(), 2), ("approach".to_string(), 1)]
+        );
+        let last = report.trajectory.iter().last().unwrap();
+        assert_eq!(
+            (last.phase_path(), last.phase_blocks.as_slice()),
+            ("approach".into(), &[1][..])
+        );
+    }
+
+    #[test]
+    fn contracts_set_and_ramp_the_environment() {
+        let mut lines = vec![
+            "environment cytosol",
+            "set temperature 320",
+            "ramp ionic_strength 0 -> 0.2 over 4 steps",
+            "rotate 2 psi 5",
+            "rotate 3 psi -5",
+        ];
+        let mut engine = permissive_engine("AGSVLKTR");
+        engine.execute_contract(&FoldingContract::from_lines(&lines).unwrap());
+        assert_eq!(engine.environment.name, "cytosol");
+        assert_eq!(engine.temperature, 320.0);
+        assert!((engine.environment.ionic_strength - 0.05).abs() < 1e-12);
+
+        lines.extend(["rotate 4 psi 5", "rotate 5 psi -5", "rotate 6 psi 5"]);
+        let mut engine = permissive_engine("AGSVLKTR");
+        engine.execute_contract(&FoldingContract::from_lines(&lines).unwrap());
+        assert_eq!(engine.environment.ionic_strength, 0.2);
+        assert!(engine.environment_ramps.is_empty());
+    }
+
+    #[test]
+    fn switched_environments_keep_the_crowding_model_and_reset_per_run() {
+        let explicit = Environment::aqueous().with_crowding_model(CrowdingModel::Explicit);
+        let mut engine = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("AGSVLKTR"))
+            .with_oscillator(MicroOscillator::new(0.0, 0.0))
+            .with_environment(explicit)
+            .with_rng_seed(3)
+            .build();
+        assert!(engine.crowders().is_none());
+
+        let switch = FoldingContract::from_lines(&["environment cytosol"]).unwrap();
+        engine.execute_contract(&switch);
+        assert_eq!(engine.environment.crowding_model, CrowdingModel::Explicit);
+        let cytosol = engine.crowders().unwrap().centers.len();
+        assert!(cytosol > 0);
+
+        let denser = FoldingContract::from_lines(&["environment cytosol", "set crowding 0.35"]);
+        engine.execute_contract(&denser.unwrap());
+        assert!(engine.crowders().unwrap().centers.len() > cytosol);
+
+        let back = FoldingContract::from_lines(&["environment cytosol", "environment vacuum"]);
+        engine.execute_contract(&back.unwrap());
+        assert!(engine.crowders().is_none());
+
+        engine.execute_contract(&FoldingContract::from_lines(&["rotate 2 psi 5"]).unwrap());
+        assert_eq!(engine.environment.name, "aqueous");
+        assert!(engine.crowders().is_none());
+    }
+
+    #[test]
+    fn memory_cap_thins_frames_but_keeps_the_ends() {
+        let sequence = "A".repeat(200);
+        let lines: Vec<String> = (0..64)
+            .map(|step| format!("rotate {} psi {}", 1 + step % 150, 5 - 10 * (step % 2)))
+            .collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let contract = FoldingContract::from_lines(&lines).unwrap();
+        let full = permissive_engine(&sequence).execute_contract(&contract);
+        assert_eq!(full.frame_stride, 1);
+        assert_eq!(full.frames.len(), full.applied_rotations.len() + 1);
+
+        let cap = full.memory.total() - full.memory.frames / 2;
+        let mut engine = permissive_engine(&sequence);
+        engine.memory_cap = Some(cap);
+        let capped = engine.execute_contract(&contract);
+        assert!(capped.memory.total() <= cap);
+        assert!(capped.frame_stride > 1);
+        assert_eq!(capped.frames[0], full.frames[0]);
+        assert_eq!(capped.frames.last(), full.frames.last());
+        assert_eq!(capped.final_chain.positions(), full.final_chain.positions());
+        assert_eq!(capped.memory_cap_exceeded_at, None);
+    }
+
+    #[test]
+    fn memory_cap_below_the_run_data_is_reported() {
+        let sequence = "A".repeat(5_000);
+        let lines: Vec<String> = (0..3)
+            .map(|step| format!("rotate {} psi {}", 1 + step * 1600, 5 - 10 * (step % 2)))
+            .collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let contract = FoldingContract::from_lines(&lines).unwrap();
+
+        // The chain alone outgrows the cap: frames thin to the ends and the
+        // first step over it is reported instead of passing silently
+        let mut engine = permissive_engine(&sequence);
+        engine.memory_cap = Some(64 * 1024);
+        let report = engine.execute_contract(&contract);
+        assert_eq!(report.applied_rotations.len(), 3);
+        assert_eq!(report.memory_cap_exceeded_at, Some(1));
+        assert_eq!(report.frames.len(), 3);
+        assert!(report.memory.total() > 64 * 1024);
+
+        let mut engine = permissive_engine(&sequence);
+        engine.memory_cap = Some(4 << 20);
+        let report = engine.execute_contract(&contract);
+        assert_eq!(report.memory_cap_exceeded_at, None);
+        assert!(report.memory.total() <= 4 << 20);
+    }
+}
+
+pub struct FoldingEngineBuilder {
+    chain: Option<PeptideChain>,
+    initial_coordinates: Option<Vec<[f64; 3]>>,
+    side_chains: Option<SideChainBead>,
+    energy_model: Option<EnergyModel>,
+    oscillator: Option<MicroOscillator>,
+    clock: Option<RotationClock>,
+    ruleset: Option<Ruleset>,
+    temperature: Option<f64>,
+    acceptance_temperature: Option<f64>,
+    rng_seed: Option<u64>,
+    rng_state: Option<u64>,
+    resume_step: usize,
+    resume_velocities: NativeVelocities,
+    rng_log_interval: Option<usize>,
+    energy_component_stride: Option<usize>,
+    frustration: Option<FrustrationOptions>,
+    acceptance_window: Option<usize>,
+    temperature_schedule: Option<TemperatureSchedule>,
+    physics_level: Option<PhysicsLevel>,
+    physics_engine: Option<PhysicsEngine>,
+    require_backend: bool,
+    force_field: Option<String>,
+    integrator: Option<String>,
+    md_timestep: Option<f64>,
+    openmm_cache: Option<PathBuf>,
+    native_trajectories: Option<PathBuf>,
+    geometry_limits: Option<GeometryLimits>,
+    environment: Option<Environment>,
+    symmetry: Option<Symmetry>,
+    contact_prior: Option<ContactPrior>,
+    time_scale: Option<TimeScale>,
+    ghost_budget: Option<GhostBudget>,
+    memory_cap: Option<usize>,
+    observers: Vec<Box<dyn EngineObserver>>,
+    scoring_terms: Vec<ScoringTerm>,
+    collective_variables: Vec<CollectiveVariable>,
+    thermo_tolerance: Option<f64>,
+}
+
+pub struct ExecutionReport {
+    pub applied_rotations: Vec<RotationOutcome>,
+    pub ghost_rotations: Vec<RotationOutcome>,
+    pub rejections: Vec<RuleViolation>,
+    /// `rejections` with the step each was raised at, for structured output.
+    pub violations: Vec<ViolationRecord>,
+    /// Violations handled by a recovery policy other than plain rejection.
+    pub recoveries: Vec<RecoveryRecord>,
+    pub final_energy: EnergyState,
+    /// Energy model the run scored the chain with.
+    pub energy_model: EnergyModel,
+    pub trajectory: Trajectory,
+    pub metropolis_stats: MetropolisStats,
+    /// Rolling acceptance after each Metropolis decision, over the last
+    /// `acceptance_window` decisions.
+    pub acceptance_series: Vec<AcceptanceSample>,
+    pub acceptance_window: usize,
+    pub domains: Vec<DomainDefinition>,
+    /// Regions the contract froze, in the order it froze them.
+    pub frozen: Vec<FrozenRegion>,
+    /// Secondary-structure restraints the contract added.
+    pub restraints: Vec<DihedralRestraint>,
+    /// Their summed energy on the final chain, kcal/mol (included in
+    /// `final_energy`).
+    pub restraint_energy: f64,
+    /// Contact-prior restraint energy on the final chain, kcal/mol (included
+    /// in `final_energy`).
+    pub contact_prior_energy: f64,
+    pub chaperone_requirements: Vec<ChaperoneRequirement>,
+    pub modifications: Vec<PostTranslationalModification>,
+    pub physics_level: PhysicsLevel,
+    /// Engine selection in force at the end of the run; each span's
+    /// `metrics.backend` names the one that actually serviced it.
+    pub physics_engine: PhysicsEngine,
+    pub physics_spans: Vec<String>,
+    pub physics_span_metrics: Vec<PhysicsSpanRecord>,
+    /// Backends that failed to service a span, in order.
+    pub backend_failures: Vec<BackendFailureRecord>,
+    /// Mid-run level changes, in order; `physics_level` is the last level.
+    pub level_switches: Vec<PhysicsLevelSwitch>,
+    pub final_chain: PeptideChain,
+    pub geometry: GeometryReport,
+    /// Initial chain plus a frame after every accepted span or rollback;
+    /// under a memory cap only every `frame_stride`-th, plus the final chain.
+    pub frames: Vec<TrajectoryFrame>,
+    /// Frames offered per frame kept; above 1 once a memory cap thinned them.
+    pub frame_stride: usize,
+    /// Step at which the run's data first stayed over the memory cap with
+    /// the frames thinned as far as they go.
+    pub memory_cap_exceeded_at: Option<usize>,
+    /// Full backbone rebuilt around the trace the last accepted `refine` span
+    /// relaxed.
+    pub refined_model: Option<Vec<BackmappedResidue>>,
+    /// Step at which a ghost span first pushed the run past its ghost budget.
+    pub ghost_budget_exceeded_at: Option<usize>,
+    /// Oldest ghost spans dropped to stay within the budget.
+    pub ghosts_pruned: usize,
+    /// Step after which an [`EngineObserver`] asked the engine to stop.
+    pub stopped_by_observer: Option<usize>,
+    /// Step at which a ghost budget with the `stop` overflow ended the run.
+    pub stopped_by_ghost_budget: Option<usize>,
+    /// Summed score change of accepted spans for each scoring term, by name.
+    pub score_deltas: Vec<(String, f64)>,
+    /// Engine RNG state at every `commit`, every `rng_log_interval` accepted
+    /// spans and when the run ended (always the last entry). The RNG also
+    /// seeds the integrator of each physics span, so resuming from a state
+    /// reproduces the stochastic stream from that point on.
+    pub rng_checkpoints: Vec<RngCheckpoint>,
+    /// Force-field energy by term at the start, every
+    /// `energy_component_stride` accepted spans and at the end; empty unless
+    /// a stride was set.
+    pub energy_components: Vec<EnergyComponentSample>,
+    /// Mutational frustration of the final chain under the force field of
+    /// the final physics level; `None` unless requested, or when that force
+    /// field has no pairwise nonbonded terms.
+    pub frustration: Option<FrustrationReport>,
+    /// Potential energy of the start and of every move judged by energy,
+    /// accepted or not, since the last physics-level change;
+    /// [`EnergySamples::foldability`] scores the best of them against the
+    /// rest.
+    pub energy_samples: EnergySamples,
+    /// Names of the tracked collective variables, matching the order of
+    /// [`RotationOutcome::collective_variables`].
+    pub collective_variables: Vec<String>,
+    /// Energy bookkeeping mismatches; empty unless thermo checks were on.
+    pub thermo_discrepancies: Vec<ThermoDiscrepancy>,
+    /// Wall-clock time `execute_contract` took. Contract time is
+    /// `trajectory.total_duration()`; simulated time is
+    /// `trajectory.total_simulated_ps()`.
+    pub wall_time: Duration,
+    /// Estimated heap held by the run's data when it ended, before the
+    /// report copied the chain and trajectory.
+    pub memory: MemoryUsage,
+}
+
+/// How a contract run ended, most severe first; [`ExecutionReport::outcome`]
+/// picks the first that applies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// A recovery policy of `abort` (or `--require-backend`) ended the contract.
+    Aborted,
+    /// A budget cut the contract short: a ghost budget set to `stop`, an
+    /// exhausted entropy or information budget, or an observer stop.
+    Truncated,
+    /// A physics backend failed and its spans fell back to another one.
+    BackendFallback,
+    /// Ran to the end, but spans were refused with error or fatal violations.
+    CompletedWithViolations,
+    Completed,
+}
+
+impl RunOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunOutcome::Aborted => "aborted",
+            RunOutcome::Truncated => "truncated",
+            RunOutcome::BackendFallback => "backend_fallback",
+            RunOutcome::CompletedWithViolations => "completed_with_violations",
+            RunOutcome::Completed => "completed",
+        }
+    }
+}
+
+impl ExecutionReport {
+    pub fn outcome(&self) -> RunOutcome {
+        let worst = self
+            .violations
+            .iter()
+            .map(|record| record.violation.severity())
+            .max();
+        if self
+            .recoveries
+            .iter()
+            .any(|record| record.policy == RecoveryPolicy::Abort)
+        {
+            RunOutcome::Aborted
+        } else if self.stopped_by_ghost_budget.is_some()
+            || self.stopped_by_observer.is_some()
+            || worst == Some(Severity::Fatal)
+        {
+            RunOutcome::Truncated
+        } else if !self.backend_failures.is_empty() {
+            RunOutcome::BackendFallback
+        } else if worst == Some(Severity::Error) {
+            RunOutcome::CompletedWithViolations
+        } else {
+            RunOutcome::Completed
+        }
+    }
+
+    /// The final structure split back into the chains of a complex.
+    pub fn final_system(&self) -> MolecularSystem {
+        MolecularSystem::from_chain(&self.final_chain)
+    }
+}
+
+/// Cα snapshot stamped with the accumulated span time at which it was taken.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrajectoryFrame {
+    pub time_ms: f64,
+    pub positions: Vec<[f64; 3]>,
+}
+
+impl TrajectoryFrame {
+    fn heap_bytes(&self) -> usize {
+        size_of::<Self>() + self.positions.capacity() * size_of::<[f64; 3]>()
+    }
+}
+
+/// Frames collected for the report. Every `stride`-th offered frame is kept;
+/// thinning drops every other kept frame and doubles the stride.
+struct FrameRecorder {
+    frames: Vec<TrajectoryFrame>,
+    stride: usize,
+    /// Frames offered since the last one kept.
+    skipped: usize,
+}
+
+impl FrameRecorder {
+    fn new(first: TrajectoryFrame) -> Self {
+        Self {
+            frames: vec![first],
+            stride: 1,
+            skipped: 0,
+        }
+    }
+
+    /// Offers the next frame; `frame` only runs when it is kept.
+    fn offer(&mut self, frame: impl FnOnce() -> TrajectoryFrame) -> bool {
+        self.skipped += 1;
+        if self.skipped < self.stride {
+            return false;
+        }
+        self.skipped = 0;
+        self.frames.push(frame());
+        true
+    }
+
+    fn bytes(&self) -> usize {
+        self.frames.iter().map(TrajectoryFrame::heap_bytes).sum()
+    }
+
+    /// Halves the kept frames; false once only the first and latest are left.
+    fn thin(&mut self) -> bool {
+        if self.frames.len() <= 2 {
+            return false;
+        }
+        // With an even count the latest frame sits at an odd index and goes.
+        if self.frames.len().is_multiple_of(2) {
+            self.skipped += self.stride;
+        }
+        let mut index = 0;
+        self.frames.retain(|_| {
+            index += 1;
+            index % 2 == 1
+        });
+        self.stride *= 2;
+        true
+    }
+
+    /// The kept frames, ending on the current chain.
+    fn finish(mut self, last: impl FnOnce() -> TrajectoryFrame) -> Vec<TrajectoryFrame> {
+        if self.skipped > 0 {
+            self.frames.push(last());
+        }
+        self.frames
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DomainDefinition {
+    pub name: Option<String>,
+    pub start: ResidueId,
+    pub end: ResidueId,
+}
+
+/// Residues a `freeze` instruction holds in place from `step` on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrozenRegion {
+    pub start: ResidueId,
+    pub end: ResidueId,
+    /// Engine step at which the freeze took effect.
+    pub step: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChaperoneRequirement {
+    pub chaperone: String,
+    pub span: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PostTranslationalModification {
+    pub modification: String,
+    pub residue: ResidueId,
+}
+
+#[derive(Clone, Debug)]
+pub struct PhysicsSpanRecord {
+    pub span_id: String,
+    pub metrics: PhysicsSpanMetrics,
+}
+
+/// A backend failure while servicing the span at `step`.
+#[derive(Clone, Debug)]
+pub struct BackendFailureRecord {
+    pub step: usize,
+    pub span_id: String,
+    pub failure: BackendFailure,
+    /// What serviced the span instead: `native`, `toy`, or `none` when the
+    /// span was rejected.
+    pub fallback: &'static str,
+}
+
+/// Physics level change made by the contract after `step` moves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhysicsLevelSwitch {
+    pub step: usize,
+    pub from: PhysicsLevel,
+    pub to: PhysicsLevel,
+}
+
+/// Force-field energy terms of the chain after `step` moves, `time_ms` into
+/// the contract, under the force field of the physics level then active.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnergyComponentSample {
+    pub step: usize,
+    pub time_ms: f64,
+    pub level: PhysicsLevel,
+    pub components: EnergyComponents,
+    /// The same energy attributed to each residue, pair terms split evenly;
+    /// empty when the force field cannot attribute it.
+    pub residues: Vec<f64>,
+}
+
+/// A violation the ruleset's [`RecoveryPolicy`] acted on.
+#[derive(Clone, Debug)]
+pub struct RecoveryRecord {
+    pub step: usize,
+    pub violation: RuleViolation,
+    pub policy: RecoveryPolicy,
+    /// Whether the recovery produced a span (a smaller move or a ghost);
+    /// always false for `Abort`.
+    pub recovered: bool,
+}
+
+impl Default for FoldingEngineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FoldingEngineBuilder {
+    pub fn new() -> Self {
+        Self {
+            chain: None,
+            initial_coordinates: None,
+            side_chains: None,
+            energy_model: None,
+            oscillator: None,
+            clock: None,
+            ruleset: None,
+            temperature: None,
+            acceptance_temperature: None,
+            rng_seed: None,
+            rng_state: None,
+            resume_step: 0,
+            resume_velocities: NativeVelocities::default(),
+            rng_log_interval: None,
+            energy_component_stride: None,
+            frustration: None,
+            acceptance_window: None,
+            temperature_schedule: None,
+            physics_level: None,
+            physics_engine: None,
+            require_backend: false,
+            force_field: None,
+            integrator: None,
+            md_timestep: None,
+            openmm_cache: None,
+            native_trajectories: None,
+            geometry_limits: None,
+            environment: None,
+            symmetry: None,
+            contact_prior: None,
+            time_scale: None,
+            ghost_budget: None,
+            memory_cap: None,
+            observers: Vec::new(),
+            scoring_terms: Vec::new(),
+            collective_variables: Vec::new(),
+            thermo_tolerance: None,
+        }
+    }
+
+    pub fn with_chain(mut self, chain: PeptideChain) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    /// Folds the chains of `system` together, as one chain with a break
+    /// between each; [`ExecutionReport::final_system`] splits the result.
+    pub fn with_system(self, system: &MolecularSystem) -> Self {
+        self.with_chain(system.to_chain())
+    }
+
+    /// Starting Cα positions, e.g. the final frame of an earlier run or a
+    /// predicted model, in place of the chain's own layout. Without
+    /// `with_chain` the chain is poly-alanine of the same length.
+    pub fn with_initial_coordinates(mut self, positions: Vec<[f64; 3]>) -> Self {
+        self.initial_coordinates = Some(positions);
+        self
+    }
+
+    /// Gives the chain side-chain beads of kind `bead`, so their clashes
+    /// enter the energy and `rotamer` flips change packing; see
+    /// [`PeptideChain::with_side_chains`].
+    pub fn with_side_chains(mut self, bead: SideChainBead) -> Self {
+        self.side_chains = Some(bead);
+        self
+    }
+
+    pub fn with_energy_model(mut self, energy_model: EnergyModel) -> Self {
+        self.energy_model = Some(energy_model);
+        self
+    }
+
+    pub fn with_oscillator(mut self, oscillator: MicroOscillator) -> Self {
+        self.oscillator = Some(oscillator);
+        self
+    }
+
+    /// Simulated picoseconds per millisecond of span duration, for spans no
+    /// physics backend reports a simulated time for.
+    pub fn with_time_scale(mut self, time_scale: TimeScale) -> Self {
+        self.time_scale = Some(time_scale);
+        self
+    }
+
+    /// [`RotationClock::real_time`] holds each accepted span back until its
+    /// wall-clock slot, for demos and live dashboards.
+    pub fn with_clock(mut self, clock: RotationClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    pub fn with_ruleset(mut self, ruleset: Ruleset) -> Self {
+        self.ruleset = Some(ruleset);
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Temperature (K) of the Metropolis criterion only; physics spans keep
+    /// the thermostat temperature. Unset, acceptance follows the thermostat.
+    pub fn with_acceptance_temperature(mut self, temperature: f64) -> Self {
+        self.acceptance_temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    /// Resumes the move stream from a state in
+    /// [`ExecutionReport::rng_checkpoints`]; the seed still places crowders.
+    pub fn with_rng_state(mut self, state: u64) -> Self {
+        self.rng_state = Some(state);
+        self
+    }
+
+    /// Numbers the first run's steps from the `step` of the same
+    /// [`RngCheckpoint`], so spans, decisions and logged states line up with
+    /// the run being resumed; later runs count from 0 again.
+    pub fn with_resume_step(mut self, step: usize) -> Self {
+        self.resume_step = step;
+        self
+    }
+
+    /// Starts the native physics bridges from the velocities of the same
+    /// [`RngCheckpoint`], so the resumed run continues their dynamics.
+    pub fn with_native_velocities(mut self, velocities: NativeVelocities) -> Self {
+        self.resume_velocities = velocities;
+        self
+    }
+
+    /// Also records the RNG state every `spans` accepted spans.
+    pub fn with_rng_log_interval(mut self, spans: usize) -> Self {
+        self.rng_log_interval = (spans > 0).then_some(spans);
+        self
+    }
+
+    /// Records the force-field energy by term every `spans` accepted spans
+    /// in [`ExecutionReport::energy_components`].
+    pub fn with_energy_component_stride(mut self, spans: usize) -> Self {
+        self.energy_component_stride = (spans > 0).then_some(spans);
+        self
+    }
+
+    /// Analyses the frustration of the final chain's contacts in
+    /// [`ExecutionReport::frustration`].
+    pub fn with_frustration_analysis(mut self, options: FrustrationOptions) -> Self {
+        self.frustration = Some(options);
+        self
+    }
+
+    /// Metropolis decisions each point of
+    /// [`ExecutionReport::acceptance_series`] averages over; defaults to
+    /// [`DEFAULT_ACCEPTANCE_WINDOW`].
+    pub fn with_acceptance_window(mut self, decisions: usize) -> Self {
+        self.acceptance_window = Some(decisions.max(1));
+        self
+    }
+
+    pub fn with_temperature_schedule(mut self, schedule: TemperatureSchedule) -> Self {
+        self.temperature_schedule = Some(schedule);
+        self
+    }
+
+    pub fn with_physics_level(mut self, level: PhysicsLevel) -> Self {
+        self.physics_level = Some(level);
+        self
+    }
+
+    pub fn with_physics_engine(mut self, engine: PhysicsEngine) -> Self {
+        self.physics_engine = Some(engine);
+        self
+    }
+
+    /// Stops the contract with `PhysicsUnavailable` when a backend fails,
+    /// rather than falling back to the next backend or the toy solver.
+    pub fn with_require_backend(mut self, required: bool) -> Self {
+        self.require_backend = required;
+        self
+    }
+
+    /// Force field from `folding_physics::registry`, by name, for physics spans.
+    pub fn with_force_field(mut self, name: impl Into<String>) -> Self {
+        self.force_field = Some(name.into());
+        self
+    }
+
+    /// Integrator from `folding_physics::registry`, by name, for physics spans.
+    pub fn with_integrator(mut self, name: impl Into<String>) -> Self {
+        self.integrator = Some(name.into());
+        self
+    }
+
+    /// MD timestep (ps) for physics spans in place of the level's default;
+    /// backends reject steps beyond the force field's stability limit.
+    pub fn with_md_timestep(mut self, picoseconds: f64) -> Self {
+        self.md_timestep = Some(picoseconds);
+        self
+    }
+
+    /// Directory where OpenMM bridge responses are cached per physics span
+    /// and reused on a rerun; see [`PhysicsCache`].
+    pub fn with_openmm_cache(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.openmm_cache = Some(dir.into());
+        self
+    }
+
+    /// Directory where the native backend writes the MD frames of each
+    /// physics span as JSON; the path is kept in the span's physics metrics.
+    pub fn with_native_trajectories(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.native_trajectories = Some(dir.into());
+        self
+    }
+
+    pub fn with_geometry_limits(mut self, limits: GeometryLimits) -> Self {
+        self.geometry_limits = Some(limits);
+        self
+    }
+
+    /// Solvent conditions: viscosity and dielectric reach the physics backend,
+    /// crowding adds an excluded-volume term to every energy evaluation.
+    pub fn with_environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Treats the chain as one protomer of a symmetric assembly: moves act on
+    /// the protomer and every copy follows, while contacts with the copies add
+    /// an interface term to every energy evaluation.
+    pub fn with_symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = Some(symmetry);
+        self
+    }
+
+    /// Adds the prior's distance restraints to every energy evaluation, so
+    /// Metropolis moves are steered toward the predicted contacts.
+    pub fn with_contact_prior(mut self, prior: ContactPrior) -> Self {
+        self.contact_prior = Some(prior);
+        self
+    }
+
+    pub fn with_ghost_budget(mut self, budget: GhostBudget) -> Self {
+        self.ghost_budget = Some(budget);
+        self
+    }
+
+    /// Keeps the run's estimated heap under `bytes` by thinning the frames
+    /// collected for the report; the first and final frames are always kept.
+    pub fn with_memory_cap(mut self, bytes: usize) -> Self {
+        self.memory_cap = Some(bytes);
+        self
+    }
+
+    /// Registers an observer; observers are called in registration order.
+    pub fn with_observer(mut self, observer: impl EngineObserver + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    /// Adds `score(after) - score(before)` of every move to ΔE in the
+    /// Metropolis criterion, e.g. a restraint or learned score in kcal/mol.
+    pub fn with_score(
+        mut self,
+        name: impl Into<String>,
+        score: impl Fn(&PeptideChain) -> f64 + Send + 'static,
+    ) -> Self {
+        self.scoring_terms.push(ScoringTerm::new(name, score));
+        self
+    }
+
+    /// Evaluates `variable` after every accepted span; values land in
+    /// [`RotationOutcome::collective_variables`] in registration order.
+    /// Registering the same variable twice tracks it once.
+    pub fn with_collective_variable(mut self, variable: CollectiveVariable) -> Self {
+        if !self.collective_variables.contains(&variable) {
+            self.collective_variables.push(variable);
+        }
+        self
+    }
+
+    /// Checks every accepted span's ΔE, G and temperature against the values
+    /// they derive from, within a relative `tolerance`, and reports
+    /// mismatches in [`ExecutionReport::thermo_discrepancies`].
+    pub fn with_thermo_checks(mut self, tolerance: f64) -> Self {
+        self.thermo_tolerance = Some(tolerance);
+        self
+    }
+
+    /// Builds the engine, panicking where [`try_build`](Self::try_build)
+    /// would return an error.
+    pub fn build(self) -> FoldingEngine {
+        self.try_build().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Builds the engine, or says why it cannot: neither a chain nor initial
+    /// coordinates were given, or the coordinates do not match the chain.
+    pub fn try_build(self) -> Result<FoldingEngine, String> {
+        let mut chain = match (self.chain, &self.initial_coordinates) {
+            (Some(chain), _) => chain,
+            (None, Some(positions)) => PeptideChain::from_sequence(&"A".repeat(positions.len())),
+            (None, None) => return Err("chain not provided".into()),
+        };
+        if let Some(positions) = &self.initial_coordinates {
+            place_ca_trace(&mut chain, positions)
+                .map_err(|err| format!("initial coordinates: {err}"))?;
+        }
+        if let Some(bead) = self.side_chains {
+            chain.set_side_chains(Some(bead));
+        }
+        let energy_model = self.energy_model.unwrap_or_default();
+        let oscillator = self
+            .oscillator
+            .unwrap_or_else(|| MicroOscillator::new(10.0, 1.0));
+        let clock = self.clock.unwrap_or_else(|| RotationClock::new(1));
+        let ruleset = self.ruleset.unwrap_or_default();
+        let mut temperature = self.temperature.unwrap_or(310.0);
+        let temperature_schedule = self.temperature_schedule.clone();
+        if let Some(TemperatureSchedule::Linear { start, .. }) = &temperature_schedule {
+            temperature = *start;
+        }
+        let rng = match self.rng_seed {
+            Some(seed) => SimpleRng::seed_from_u64(seed),
+            None => SimpleRng::from_entropy(),
+        };
+        let environment = self.environment.unwrap_or_default();
+        // Seeded runs get the same crowder layout without disturbing the move stream.
+        let crowders = environment.crowders(&chain_positions(&chain), rng.clone().next_u64());
+        warn_if_jammed(crowders.as_ref());
+        // A resumed stream keeps the seed's crowders.
+        let rng = self.rng_state.map_or(rng, SimpleRng::from_state);
+        let state = ProteinState::new(chain, energy_model);
+        let solver = RotationSolver::new(oscillator, clock);
+        let validator = Validator::new(ruleset);
+        let physics_level = self.physics_level.unwrap_or(PhysicsLevel::Toy);
+        let physics_engine = self.physics_engine.unwrap_or(PhysicsEngine::Auto);
+        let geometry_validator = GeometryValidator::new(self.geometry_limits.unwrap_or_default());
+        Ok(FoldingEngine {
+            state,
+            solver,
+            validator,
+            ghost_mode: false,
+            pending_alias: None,
+            pending_tags: Vec::new(),
+            phases: Vec::new(),
+            phases_opened: 0,
+            checkpoints: Vec::new(),
+            ghost_trajectory: Trajectory::new(),
+            temperature,
+            acceptance_temperature: self.acceptance_temperature,
+            boltzmann_constant: 0.0019872041, // kcal·mol⁻¹·K⁻¹
+            rng,
+            temperature_schedule,
+            initial_temperature: temperature,
+            step_index: 0,
+            resume_step: self.resume_step,
+            metropolis_stats: MetropolisStats::default(),
+            metropolis_decisions: Vec::new(),
+            acceptance_window: self.acceptance_window.unwrap_or(DEFAULT_ACCEPTANCE_WINDOW),
+            domains: Vec::new(),
+            frozen: Vec::new(),
+            frozen_residues: Vec::new(),
+            restraints: Vec::new(),
+            chaperone_requirements: Vec::new(),
+            modifications: Vec::new(),
+            physics_level,
+            span_physics_mode: PhysicsSpanMode::Toy,
+            physics_spans: Vec::new(),
+            physics_span_metrics: Vec::new(),
+            level_switches: Vec::new(),
+            physics_engine,
+            require_backend: self.require_backend,
+            backend_failures: Vec::new(),
+            force_field: self.force_field,
+            integrator: self.integrator,
+            md_timestep: self.md_timestep,
+            configured_md_timestep: self.md_timestep,
+            openmm_cache: self.openmm_cache,
+            native_trajectories: self.native_trajectories,
+            native_bridges: NativeBridges::new(),
+            resume_velocities: self.resume_velocities,
+            geometry_validator,
+            configured_environment: environment.clone(),
+            configured_crowders: crowders.clone(),
+            configured_temperature: temperature,
+            configured_temperature_schedule: self.temperature_schedule.clone(),
+            configured_acceptance_temperature: self.acceptance_temperature,
+            environment,
+            environment_ramps: Vec::new(),
+            crowders,
+            symmetry: self.symmetry,
+            contact_prior: self.contact_prior,
+            time_scale: self.time_scale.unwrap_or_default(),
+            refined_model: None,
+            ghost_budget: self.ghost_budget,
+            ghost_budget_exceeded_at: None,
+            ghosts_pruned: 0,
+            memory_cap: self.memory_cap,
+            memory_cap_exceeded_at: None,
+            temperature_scale: 1.0,
+            rng_log_interval: self.rng_log_interval,
+            rng_checkpoints: Vec::new(),
+            energy_component_stride: self.energy_component_stride,
+            energy_components: Vec::new(),
+            component_bridge: ComponentBridge::default(),
+            frustration: self.frustration,
+            energy_samples: EnergySamples::new(),
+            observers: self.observers,
+            stopped_by_observer: None,
+            stopped_by_ghost_budget: None,
+            score_totals: vec![0.0; self.scoring_terms.len()],
+            scoring_terms: self.scoring_terms,
+            collective_variables: self.collective_variables,
+            thermo_tolerance: self.thermo_tolerance,
+            thermo_discrepancies: Vec::new(),
+        })
+    }
+}
+
+impl FoldingEngine {
+    pub fn execute_contract(&mut self, contract: &FoldingContract) -> ExecutionReport {
+        let mut applied_rotations = Vec::new();
         let mut ghost_rotations = Vec::new();
         let mut rejections = Vec::new();
-        self.step_index = 0;
+        let mut violations = Vec::new();
+        let mut recoveries = Vec::new();
+        self.step_index = std::mem::take(&mut self.resume_step);
+        self.pending_tags.clear();
+        self.phases.clear();
+        self.phases_opened = 0;
+        self.environment = self.configured_environment.clone();
+        self.crowders = self.configured_crowders.clone();
+        self.environment_ramps.clear();
+        self.temperature = self.configured_temperature;
+        self.initial_temperature = self.configured_temperature;
+        self.temperature_schedule = self.configured_temperature_schedule.clone();
+        self.acceptance_temperature = self.configured_acceptance_temperature;
+        self.temperature_scale = 1.0;
+        self.md_timestep = self.configured_md_timestep;
         self.metropolis_stats = MetropolisStats::default();
+        self.metropolis_decisions.clear();
         self.domains.clear();
+        self.frozen.clear();
+        self.frozen_residues.clear();
+        self.restraints.clear();
         self.chaperone_requirements.clear();
         self.modifications.clear();
         self.physics_spans.clear();
         self.physics_span_metrics.clear();
+        self.backend_failures.clear();
+        self.level_switches.clear();
+        self.refined_model = None;
+        self.ghost_budget_exceeded_at = None;
+        self.ghosts_pruned = 0;
+        self.memory_cap_exceeded_at = None;
+        self.stopped_by_observer = None;
+        self.stopped_by_ghost_budget = None;
+        self.score_totals = vec![0.0; self.scoring_terms.len()];
+        self.rng_checkpoints.clear();
+        self.native_bridges = NativeBridges::new();
+        self.native_bridges
+            .restore(&std::mem::take(&mut self.resume_velocities));
+        self.energy_components.clear();
+        self.thermo_discrepancies.clear();
+        let start_potential = self.potential_energy();
+        self.energy_samples = EnergySamples::new();
+        self.energy_samples.record(start_potential);
+        let start_trajectory_energy = self.trajectory_energy();
+        let started = Instant::now();
+        let mut elapsed_ms = 0.0;
+        let mut frames = FrameRecorder::new(self.frame(elapsed_ms));
+        if self.energy_component_stride.is_some() {
+            self.record_energy_components(elapsed_ms);
+        }
+        self.solver.clock_mut().start();
         for instruction in &contract.instructions {
             match instruction {
-                ContractInstruction::Rotate {
-                    residue,
-                    angle_degrees,
-                    duration_ms,
-                } => match self.execute_rotation(*residue, *angle_degrees, *duration_ms) {
-                    Ok(outcome) => {
-                        if outcome.ghost {
-                            ghost_rotations.push(outcome);
-                        } else {
-                            applied_rotations.push(outcome);
+                instruction if instruction.is_move() => {
+                    if self.observers_stop_before_move() {
+                        break;
+                    }
+                    let mut abort = false;
+                    let result = match self.execute_move(instruction) {
+                        Some(Err(err @ RuleViolation::PhysicsUnavailable { .. }))
+                            if self.require_backend =>
+                        {
+                            recoveries.push(RecoveryRecord {
+                                step: self.step_index,
+                                violation: err.clone(),
+                                policy: RecoveryPolicy::Abort,
+                                recovered: false,
+                            });
+                            abort = true;
+                            Some(Err(err))
+                        }
+                        Some(Err(err)) => {
+                            let (result, aborted) = self.recover(instruction, err, &mut recoveries);
+                            abort = aborted;
+                            Some(result)
+                        }
+                        other => other,
+                    };
+                    self.pending_tags.clear();
+                    match result {
+                        Some(Ok(outcome)) => {
+                            if outcome.ghost {
+                                ghost_rotations.push(outcome);
+                                abort |= self
+                                    .enforce_ghost_budget(&mut ghost_rotations, &applied_rotations);
+                            } else {
+                                elapsed_ms += outcome.span_record.duration.as_secs_f64() * 1000.0;
+                                // Real-time clocks hold the span back until its slot.
+                                self.solver.clock_mut().release();
+                                abort |= self.notify_span_applied(&outcome);
+                                applied_rotations.push(outcome);
+                                if self.rng_log_interval.is_some_and(|interval| {
+                                    applied_rotations.len().is_multiple_of(interval)
+                                }) {
+                                    self.record_rng_state();
+                                }
+                                if self.energy_component_stride.is_some_and(|stride| {
+                                    applied_rotations.len().is_multiple_of(stride)
+                                }) {
+                                    self.record_energy_components(elapsed_ms);
+                                }
+                                self.record_frame(
+                                    &mut frames,
+                                    elapsed_ms,
+                                    [&applied_rotations, &ghost_rotations],
+                                );
+                            }
+                        }
+                        Some(Err(err)) => {
+                            self.record_violation(err, &mut violations, &mut rejections)
                         }
+                        None => {}
                     }
-                    Err(err) => rejections.push(err),
-                },
+                    if abort {
+                        break;
+                    }
+                }
                 ContractInstruction::ClashCheck => {
                     if let Err(err) = self.validator.validate_structure(&self.state.chain) {
-                        rejections.push(err);
+                        let policy = self.validator.ruleset().recovery_for(&err);
+                        self.record_violation(err.clone(), &mut violations, &mut rejections);
+                        if policy == RecoveryPolicy::Abort {
+                            recoveries.push(RecoveryRecord {
+                                step: self.step_index,
+                                violation: err,
+                                policy,
+                                recovered: false,
+                            });
+                            break;
+                        }
                     }
                 }
                 ContractInstruction::Commit => self.commit(),
-                ContractInstruction::Rollback => self.rollback(),
+                ContractInstruction::Rollback => {
+                    self.rollback();
+                    self.record_frame(
+                        &mut frames,
+                        elapsed_ms,
+                        [&applied_rotations, &ghost_rotations],
+                    );
+                }
                 ContractInstruction::GhostMode(enabled) => self.set_ghost_mode(*enabled),
                 ContractInstruction::SpanAlias(alias) => {
                     self.pending_alias = Some(alias.clone());
                 }
+                ContractInstruction::SpanTags(tags) => self.pending_tags.extend(tags.clone()),
+                ContractInstruction::BeginPhase(name) => {
+                    self.phases.push((name.clone(), self.phases_opened));
+                    self.phases_opened += 1;
+                }
+                ContractInstruction::EndPhase => {
+                    self.phases.pop();
+                }
                 ContractInstruction::DefineDomain { name, start, end } => {
                     self.domains.push(DomainDefinition {
                         name: name.clone(),
@@ -327,6 +2473,18 @@ impl FoldingEngine {
                         end: *end,
                     });
                 }
+                ContractInstruction::Freeze { start, end } => self.freeze(*start, *end),
+                ContractInstruction::Restrain {
+                    start,
+                    end,
+                    structure,
+                    force_constant,
+                } => self.restraints.push(DihedralRestraint {
+                    start: *start,
+                    end: *end,
+                    structure: *structure,
+                    force_constant: *force_constant,
+                }),
                 ContractInstruction::RequireChaperone { chaperone, span } => {
                     self.chaperone_requirements.push(ChaperoneRequirement {
                         chaperone: chaperone.clone(),
@@ -342,72 +2500,1157 @@ impl FoldingEngine {
                         residue: *residue,
                     });
                 }
-                ContractInstruction::SetPhysicsLevel(level) => {
-                    self.physics_level = *level;
+                ContractInstruction::SetPhysicsLevel(level) => self.switch_physics_level(*level),
+                ContractInstruction::SetSpanPhysics(mode) => {
+                    self.span_physics_mode = *mode;
+                }
+                ContractInstruction::SetPhysicsEngine(engine) => self.physics_engine = *engine,
+                ContractInstruction::SetForceField(name) => self.force_field = Some(name.clone()),
+                ContractInstruction::SetIntegrator(name) => self.integrator = Some(name.clone()),
+                ContractInstruction::SetTimestep(picoseconds) => self.md_timestep = *picoseconds,
+                ContractInstruction::SetTemperature(kelvin) => {
+                    self.set_environment_parameter(EnvironmentParameter::Temperature, *kelvin);
+                }
+                ContractInstruction::SetAcceptanceTemperature(kelvin) => {
+                    self.acceptance_temperature = *kelvin;
+                }
+                ContractInstruction::SetEnvironment(name) => self.switch_environment(name),
+                ContractInstruction::SetEnvironmentParameter { parameter, value } => {
+                    self.set_environment_parameter(*parameter, *value);
+                }
+                ContractInstruction::RampEnvironment {
+                    parameter,
+                    from,
+                    to,
+                    steps,
+                } => {
+                    self.set_environment_parameter(*parameter, *from);
+                    self.environment_ramps.push(EnvironmentRamp {
+                        parameter: *parameter,
+                        from: *from,
+                        to: *to,
+                        start_step: self.step_index,
+                        steps: *steps,
+                    });
+                }
+                _ => unreachable!("moves are handled by the first arm"),
+            }
+        }
+        self.record_rng_state();
+        if self.energy_component_stride.is_some() {
+            self.record_energy_components(elapsed_ms);
+        }
+        if let Some(tolerance) = self.thermo_tolerance {
+            let expected = start_potential + self.trajectory_energy() - start_trajectory_energy;
+            let actual = self.potential_energy();
+            if !thermo_checks::agrees(expected, actual, tolerance) {
+                self.thermo_discrepancies.push(ThermoDiscrepancy {
+                    step: self.step_index,
+                    span_id: String::new(),
+                    check: ThermoCheck::EnergyBalance,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        let mut final_energy = self.state.energy_state();
+        final_energy.total_potential += self.crowding_energy()
+            + self.symmetry_energy()
+            + self.restraint_energy()
+            + self.contact_prior_energy();
+        let trajectory = self.state.trajectory().clone();
+        let geometry = self
+            .geometry_validator
+            .validate_model(&self.state.chain, self.refined_model.as_deref());
+        let frame_stride = frames.stride;
+        let frames = frames.finish(|| self.frame(elapsed_ms));
+        let memory = self.memory_usage([&applied_rotations, &ghost_rotations], &frames);
+        ExecutionReport {
+            applied_rotations,
+            ghost_rotations,
+            rejections,
+            violations,
+            recoveries,
+            final_energy,
+            energy_model: self.state.energy_model.clone(),
+            trajectory,
+            metropolis_stats: self.metropolis_stats.clone(),
+            acceptance_series: rolling_acceptance(
+                &self.metropolis_decisions,
+                self.acceptance_window,
+            ),
+            acceptance_window: self.acceptance_window,
+            domains: self.domains.clone(),
+            frozen: self.frozen.clone(),
+            restraints: self.restraints.clone(),
+            restraint_energy: self.restraint_energy(),
+            contact_prior_energy: self.contact_prior_energy(),
+            chaperone_requirements: self.chaperone_requirements.clone(),
+            modifications: self.modifications.clone(),
+            physics_level: self.physics_level,
+            physics_engine: self.physics_engine,
+            physics_spans: self.physics_spans.clone(),
+            physics_span_metrics: self.physics_span_metrics.clone(),
+            backend_failures: self.backend_failures.clone(),
+            level_switches: self.level_switches.clone(),
+            final_chain: self.state.chain.clone(),
+            geometry,
+            frames,
+            frame_stride,
+            memory_cap_exceeded_at: self.memory_cap_exceeded_at,
+            refined_model: self.refined_model.clone(),
+            ghost_budget_exceeded_at: self.ghost_budget_exceeded_at,
+            ghosts_pruned: self.ghosts_pruned,
+            stopped_by_observer: self.stopped_by_observer,
+            stopped_by_ghost_budget: self.stopped_by_ghost_budget,
+            rng_checkpoints: self.rng_checkpoints.clone(),
+            energy_components: self.energy_components.clone(),
+            frustration: self.analyze_frustration(),
+            energy_samples: self.energy_samples,
+            score_deltas: self
+                .scoring_terms
+                .iter()
+                .map(|term| term.name.clone())
+                .zip(self.score_totals.iter().copied())
+                .collect(),
+            collective_variables: self
+                .collective_variables
+                .iter()
+                .map(CollectiveVariable::name)
+                .collect(),
+            thermo_discrepancies: self.thermo_discrepancies.clone(),
+            wall_time: started.elapsed(),
+            memory,
+        }
+    }
+
+    /// Offers a frame of the current chain and, under a memory cap, thins the
+    /// kept frames until the run's estimate fits again. When the first and
+    /// latest frames alone do not fit, the step is noted and the run goes on.
+    fn record_frame(
+        &mut self,
+        frames: &mut FrameRecorder,
+        time_ms: f64,
+        outcomes: [&[RotationOutcome]; 2],
+    ) {
+        if !frames.offer(|| self.frame(time_ms)) {
+            return;
+        }
+        let Some(cap) = self.memory_cap else {
+            return;
+        };
+        let others = self.memory_usage(outcomes, &[]).total();
+        let budget = cap.saturating_sub(others);
+        while frames.bytes() > budget && frames.thin() {}
+        if frames.bytes() > budget && self.memory_cap_exceeded_at.is_none() {
+            self.memory_cap_exceeded_at = Some(self.step_index);
+            log::warn!(
+                "step {}: run data needs {} bytes with frames thinned out, over the {cap}-byte memory cap",
+                self.step_index,
+                others + frames.bytes()
+            );
+        }
+    }
+
+    /// Heap estimate of the engine's run data plus `outcomes` and `frames`.
+    fn memory_usage(
+        &self,
+        outcomes: [&[RotationOutcome]; 2],
+        frames: &[TrajectoryFrame],
+    ) -> MemoryUsage {
+        let outcome_bytes: usize = outcomes
+            .iter()
+            .flat_map(|outcomes| outcomes.iter())
+            .map(|outcome| {
+                size_of::<RotationOutcome>()
+                    + outcome.span_record.id.capacity()
+                    + outcome.collective_variables.capacity() * size_of::<f64>()
+            })
+            .sum();
+        MemoryUsage {
+            chain: self.state.chain.heap_bytes(),
+            trajectory: self.state.trajectory().heap_bytes()
+                + self.ghost_trajectory.heap_bytes()
+                + outcome_bytes,
+            frames: frames.iter().map(TrajectoryFrame::heap_bytes).sum(),
+            checkpoints: self
+                .checkpoints
+                .iter()
+                .map(|(snapshot, velocities)| {
+                    snapshot.chain.heap_bytes()
+                        + snapshot.trajectory.heap_bytes()
+                        + velocities.heap_bytes()
+                })
+                .sum(),
+            forces: if self.physics_span_metrics.is_empty() {
+                0
+            } else {
+                self.state.chain.len() * FORCE_BYTES_PER_RESIDUE
+            },
+            crowders: self
+                .crowders
+                .as_ref()
+                .map_or(0, |field| field.centers.capacity() * size_of::<[f64; 3]>()),
+        }
+    }
+
+    /// Tells observers about an applied span; true when one asks to stop.
+    fn notify_span_applied(&mut self, outcome: &RotationOutcome) -> bool {
+        if self.observers.is_empty() {
+            return false;
+        }
+        let step = self.step_index;
+        let energy = self.potential_energy();
+        let mut stop = false;
+        for observer in &mut self.observers {
+            observer.on_span_applied(step, outcome);
+            stop |= observer.on_energy(step, energy) == ObserverControl::Stop;
+        }
+        if stop {
+            self.stopped_by_observer = Some(step);
+        }
+        stop
+    }
+
+    /// Asks observers before a move; true when one wants to stop first.
+    fn observers_stop_before_move(&mut self) -> bool {
+        let step = self.step_index;
+        let mut stop = false;
+        for observer in &mut self.observers {
+            stop |= observer.before_move(step) == ObserverControl::Stop;
+        }
+        if stop {
+            self.stopped_by_observer = Some(step);
+        }
+        stop
+    }
+
+    fn record_violation(
+        &mut self,
+        violation: RuleViolation,
+        violations: &mut Vec<ViolationRecord>,
+        rejections: &mut Vec<RuleViolation>,
+    ) {
+        let record = self.violation_record(&violation);
+        log::debug!(
+            "step {}: rejected by {} ({})",
+            record.step,
+            violation.code(),
+            violation.kind()
+        );
+        for observer in &mut self.observers {
+            observer.on_rejection(&record);
+        }
+        violations.push(record);
+        rejections.push(violation);
+    }
+
+    /// Checks the ghost budget after a ghost span; true when the run must stop.
+    /// Escalation happens once per run, when the budget is first exceeded.
+    fn enforce_ghost_budget(
+        &mut self,
+        ghosts: &mut Vec<RotationOutcome>,
+        applied: &[RotationOutcome],
+    ) -> bool {
+        let Some(budget) = self.ghost_budget else {
+            return false;
+        };
+        let entropy = |spans: &[RotationOutcome]| -> f64 {
+            spans.iter().map(|outcome| outcome.span_record.delta_entropy).sum()
+        };
+        let applied_entropy = entropy(applied);
+        let exceeded = |ghosts: &[RotationOutcome]| {
+            budget.exceeded(ghosts.len(), entropy(ghosts), applied_entropy)
+        };
+        if !exceeded(ghosts) {
+            return false;
+        }
+        let first = self.ghost_budget_exceeded_at.is_none();
+        if first {
+            self.ghost_budget_exceeded_at = Some(self.step_index);
+            log::info!(
+                "step {}: ghost budget exceeded with {} ghosts",
+                self.step_index,
+                ghosts.len()
+            );
+        }
+        match budget.overflow {
+            GhostOverflow::Stop => {
+                self.stopped_by_ghost_budget = Some(self.step_index);
+                true
+            }
+            GhostOverflow::Escalate { factor } => {
+                if first {
+                    self.temperature_scale *= factor;
+                    self.temperature *= factor;
                 }
-                ContractInstruction::SetSpanPhysics(mode) => {
-                    self.span_physics_mode = *mode;
+                false
+            }
+            GhostOverflow::Prune => {
+                while !ghosts.is_empty() && exceeded(ghosts) {
+                    ghosts.remove(0);
+                    self.ghost_trajectory.remove_oldest();
+                    self.ghosts_pruned += 1;
                 }
+                false
             }
         }
-        let final_energy = self.state.energy_state();
-        let trajectory = self.state.trajectory().clone();
-        ExecutionReport {
-            applied_rotations,
-            ghost_rotations,
-            rejections,
-            final_energy,
-            trajectory,
-            metropolis_stats: self.metropolis_stats.clone(),
-            domains: self.domains.clone(),
-            chaperone_requirements: self.chaperone_requirements.clone(),
-            modifications: self.modifications.clone(),
-            physics_level: self.physics_level,
-            physics_spans: self.physics_spans.clone(),
-            physics_span_metrics: self.physics_span_metrics.clone(),
+    }
+
+    fn frame(&self, time_ms: f64) -> TrajectoryFrame {
+        TrajectoryFrame {
+            time_ms,
+            positions: self.state.chain.positions().to_vec(),
+        }
+    }
+
+    /// Σ ΔE of the spans on the trajectory.
+    fn trajectory_energy(&self) -> f64 {
+        self.state.trajectory().iter().map(|span| span.delta_energy).sum()
+    }
+
+    /// Chain potential plus the environment's excluded-volume term and, for
+    /// symmetric assemblies, the interface with the other protomers.
+    fn potential_energy(&self) -> f64 {
+        self.state.energy_model.total_energy(&self.state.chain)
+            + self.crowding_energy()
+            + self.symmetry_energy()
+            + self.restraint_energy()
+            + self.contact_prior_energy()
+    }
+
+    fn contact_prior_energy(&self) -> f64 {
+        self.contact_prior
+            .as_ref()
+            .map_or(0.0, |prior| prior.energy(self.state.chain.positions()))
+    }
+
+    fn restraint_energy(&self) -> f64 {
+        self.restraints
+            .iter()
+            .map(|restraint| restraint.energy(&self.state.chain))
+            .sum()
+    }
+
+    fn symmetry_energy(&self) -> f64 {
+        let Some(symmetry) = &self.symmetry else {
+            return 0.0;
+        };
+        let energy_model = &self.state.energy_model;
+        symmetry.interface_energy(&chain_positions(&self.state.chain), |dist| {
+            energy_model.contact_energy(dist)
+        })
+    }
+
+    pub fn symmetry(&self) -> Option<&Symmetry> {
+        self.symmetry.as_ref()
+    }
+
+    fn crowding_energy(&self) -> f64 {
+        let positions = chain_positions(&self.state.chain);
+        match (self.environment.crowding_model, &self.crowders) {
+            (CrowdingModel::Explicit, Some(crowders)) => crowders.energy(&positions),
+            (CrowdingModel::Explicit, None) => 0.0,
+            (CrowdingModel::MeanField, _) => self
+                .environment
+                .excluded_volume_energy(&positions, self.temperature),
+        }
+    }
+
+    /// Explicit crowders placed around the starting chain, if any.
+    pub fn crowders(&self) -> Option<&CrowderField> {
+        self.crowders.as_ref()
+    }
+
+    /// Runs a move instruction; `None` for instructions that are not moves.
+    /// A move that is rejected or ghosted leaves the physics bridges'
+    /// velocities as they were before it.
+    fn execute_move(
+        &mut self,
+        instruction: &ContractInstruction,
+    ) -> Option<Result<RotationOutcome, RuleViolation>> {
+        let velocities = self.native_bridges.velocities();
+        let result = self.run_move(instruction);
+        if !matches!(&result, Some(Ok(outcome)) if !outcome.ghost) {
+            self.native_bridges.restore(&velocities);
         }
+        result
     }
 
-    fn execute_rotation(
+    fn run_move(
         &mut self,
-        residue: ResidueId,
-        angle_degrees: f64,
-        duration_ms: u64,
-    ) -> Result<RotationOutcome, RuleViolation> {
+        instruction: &ContractInstruction,
+    ) -> Option<Result<RotationOutcome, RuleViolation>> {
+        match instruction {
+            ContractInstruction::Rotate { .. } | ContractInstruction::RotateSegment { .. } => {
+                Some(self.execute_rotation(instruction.rotation_span()?))
+            }
+            ContractInstruction::MoveDomain {
+                domain,
+                motion,
+                duration_ms,
+            } => Some(self.execute_domain_move(domain, *motion, *duration_ms)),
+            ContractInstruction::CloseLoop {
+                start,
+                end,
+                perturbation_degrees,
+                duration_ms,
+            } => Some(self.execute_loop_closure(
+                *start,
+                *end,
+                *perturbation_degrees,
+                *duration_ms,
+            )),
+            ContractInstruction::Backrub {
+                residue,
+                angle_degrees,
+                duration_ms,
+            } => Some(self.execute_backrub(*residue, *angle_degrees, *duration_ms)),
+            ContractInstruction::RotamerFlip {
+                residue,
+                rotamer,
+                duration_ms,
+            } => Some(self.execute_rotamer_flip(*residue, rotamer.as_deref(), *duration_ms)),
+            ContractInstruction::Refine { level, duration_ms } => {
+                Some(self.execute_refine(*level, *duration_ms))
+            }
+            ContractInstruction::FixGeometry { duration_ms } => {
+                self.execute_fix_geometry(*duration_ms).transpose()
+            }
+            ContractInstruction::Simulate {
+                picoseconds,
+                level,
+                duration_ms,
+            } => Some(self.execute_simulate(*picoseconds, *level, *duration_ms)),
+            _ => None,
+        }
+    }
+
+    /// Runs one span; a segment turns every residue in it by the same (noisy)
+    /// angle and is validated, accepted or rejected as a whole. Members whose
+    /// torsion limits cut the turn back turn less, and the span records their
+    /// mean turn.
+    fn execute_rotation(&mut self, span: RotationSpan) -> Result<RotationOutcome, RuleViolation> {
+        let RotationSpan {
+            first: residue,
+            dihedral,
+            angle_degrees,
+            duration_ms,
+            ..
+        } = span;
         self.apply_temperature_schedule();
-        self.validator
-            .validate_rotation(residue, angle_degrees, &self.state.chain)?;
+        for member in span.residues() {
+            self.validator
+                .validate_rotation(member, dihedral, angle_degrees, &self.state.chain)?;
+        }
+        self.check_frozen(span.residues().map(|member| member.0))?;
         let alias = self.pending_alias.take();
-        let baseline_energy = self.state.energy_model.total_energy(&self.state.chain);
+        let baseline_energy = self.potential_energy();
+        let segment_length = span.residues().count();
+        let label = match (&alias, segment_length > 1) {
+            (None, true) => Some(match dihedral {
+                Dihedral::Phi => format!("segment-{}-{}", span.first.0, span.last.0),
+                other => format!("segment-{}-{}-{}", span.first.0, span.last.0, other.as_str()),
+            }),
+            _ => alias.clone(),
+        };
         let command = RotationCommand {
             residue,
+            dihedral,
             angle_degrees,
             duration: Duration::from_millis(duration_ms.max(1)),
-            label: alias.clone(),
+            label,
         };
+        let limits = self.torsion_limits(residue, dihedral);
         let mut physics_applied = false;
         let mut outcome = if self.span_physics_mode == PhysicsSpanMode::Physics {
-            if let Some(physics_outcome) = physics_bridge::run_physics_step_with_engine(PhysicsRequest {
+            let attempt = physics_bridge::attempt_physics_step(PhysicsRequest {
                 chain: &self.state.chain,
                 command: command.clone(),
                 level: self.physics_level,
                 temperature: self.temperature,
-            }, self.physics_engine.clone()) {
-                physics_applied = true;
-                physics_outcome
-            } else {
-                self.solver.solve(command.clone())
+                environment: &self.environment,
+                crowders: self.crowders.as_ref(),
+                force_field: self.force_field.as_deref(),
+                integrator: self.integrator.as_deref(),
+                timestep_ps: self.md_timestep,
+                cache: self.openmm_cache.as_deref().map(|dir| PhysicsCache {
+                    dir,
+                    step: self.step_index,
+                }),
+                trajectories: self.native_trajectories.as_deref().map(|dir| TrajectoryOutput {
+                    dir,
+                    step: self.step_index,
+                }),
+                seed: self.rng.next_u64(),
+                frozen: &self.frozen_residues,
+            }, self.physics_engine, &mut self.native_bridges);
+            let span_id = command
+                .label
+                .clone()
+                .unwrap_or_else(|| format!("residue-{}", residue.0));
+            match self.settle_backends(attempt, &span_id) {
+                Ok(Some(mut physics_outcome)) => {
+                    physics_applied = true;
+                    // Backends turn by the angle asked; the residue's limits still hold.
+                    let (torsion_limits, current) = limits;
+                    physics_outcome.applied_angle =
+                        torsion_limits.limit(dihedral, current, physics_outcome.applied_angle);
+                    physics_outcome.span_record.delta_theta = physics_outcome.applied_angle;
+                    physics_outcome
+                }
+                Ok(None) => self.solver.solve_within(command.clone(), Some(limits)),
+                Err(reason) => {
+                    self.pending_alias = alias;
+                    self.increment_step();
+                    return Err(RuleViolation::PhysicsUnavailable { reason });
+                }
             }
         } else {
-            self.solver.solve(command.clone())
+            self.solver.solve_within(command.clone(), Some(limits))
         };
+        // A segment costs as much entropy and information as its single moves.
+        outcome.span_record.delta_entropy *= segment_length as f64;
+        outcome.span_record.delta_information *= segment_length as f64;
         let pending_metrics = if physics_applied {
             outcome.physics_metrics.clone()
         } else {
             None
         };
+        let applied_angle = outcome.applied_angle;
+        // Every member turns by the span's angle, cut back to its own limits.
+        let member_angles: Vec<(ResidueId, f64)> = span
+            .residues()
+            .map(|member| {
+                let (limits, current) = self.torsion_limits(member, dihedral);
+                (member, limits.limit(dihedral, current, applied_angle))
+            })
+            .collect();
+        // The span records the turn its members made, not the one asked for.
+        outcome.applied_angle =
+            member_angles.iter().map(|(_, angle)| angle).sum::<f64>() / segment_length as f64;
+        outcome.span_record.delta_theta = outcome.applied_angle;
+        self.settle_move(outcome, alias, baseline_energy, pending_metrics, |state| {
+            for (member, angle) in member_angles {
+                state.apply_rotation(member, dihedral, angle);
+            }
+        })
+    }
+
+    /// Limits of `residue`'s type with the current value of `dihedral`.
+    fn torsion_limits(&self, residue: ResidueId, dihedral: Dihedral) -> (TorsionLimits, f64) {
+        let name = &self.state.chain.names()[residue.0];
+        let current = self
+            .state
+            .chain
+            .residue(residue)
+            .map_or(0.0, |entry| entry.torsion(dihedral));
+        (TorsionLimits::for_residue(name), current)
+    }
+
+    /// Moves a domain rigidly: translations shift every member by the offset,
+    /// rotations turn them about the chosen axis through the domain centroid.
+    fn execute_domain_move(
+        &mut self,
+        domain: &DomainSelector,
+        motion: DomainMotion,
+        duration_ms: u64,
+    ) -> Result<RotationOutcome, RuleViolation> {
+        self.apply_temperature_schedule();
+        let (start, end) = self.resolve_domain(domain)?;
+        self.validator.validate_members(start..=end)?;
+        self.check_frozen(start..=end)?;
+        // A 1 Å shift is costed like a 10° turn.
+        let (angle, magnitude) = match motion {
+            DomainMotion::Translate(offset) => {
+                let distance = offset.iter().map(|value| value * value).sum::<f64>().sqrt();
+                (0.0, 10.0 * distance)
+            }
+            DomainMotion::Rotate { angle_degrees, .. } => {
+                self.validator.validate_rotation(
+                    ResidueId(start),
+                    Dihedral::Phi,
+                    angle_degrees,
+                    &self.state.chain,
+                )?;
+                (angle_degrees, angle_degrees.abs())
+            }
+        };
+        let alias = self.pending_alias.take();
+        let baseline_energy = self.potential_energy();
+        let label = alias
+            .clone()
+            .unwrap_or_else(|| format!("domain-{}", domain.label()));
+        let mut span_record = SpanRecord::new(
+            label,
+            magnitude * 0.01,
+            magnitude * 0.005,
+            Duration::from_millis(duration_ms.max(1)),
+        );
+        span_record.delta_theta = angle;
+        let outcome = RotationOutcome {
+            residue: ResidueId(start),
+            dihedral: Dihedral::Phi,
+            applied_angle: angle,
+            span_record,
+            ghost: false,
+            physics_metrics: None,
+            collective_variables: Vec::new(),
+        };
+        let members = start..end + 1;
+        self.settle_move(outcome, alias, baseline_energy, None, |state| match motion {
+            DomainMotion::Translate(offset) => state.translate_residues(members, offset),
+            DomainMotion::Rotate { angle_degrees, axis } => {
+                let center = state.centroid(members.clone());
+                let direction = match axis {
+                    DomainAxis::X => [1.0, 0.0, 0.0],
+                    DomainAxis::Y => [0.0, 1.0, 0.0],
+                    DomainAxis::Z => [0.0, 0.0, 1.0],
+                    DomainAxis::EndToEnd => {
+                        let positions = state.chain.positions();
+                        let (first, last) = (positions[start], positions[end]);
+                        [last[0] - first[0], last[1] - first[1], last[2] - first[2]]
+                    }
+                };
+                state.rotate_residues(members, center, direction, angle_degrees.to_radians());
+            }
+        })
+    }
+
+    /// Resamples the loop between two anchors and closes it with CCD. The
+    /// anchors, the residue right after the first one (it sits on the first
+    /// rotatable virtual bond) and everything outside stay where they are; a
+    /// loop that cannot be closed is rejected.
+    fn execute_loop_closure(
+        &mut self,
+        start: ResidueId,
+        end: ResidueId,
+        perturbation_degrees: f64,
+        duration_ms: u64,
+    ) -> Result<RotationOutcome, RuleViolation> {
+        self.apply_temperature_schedule();
+        let (first, last) = (start.0.min(end.0), start.0.max(end.0));
+        // Both anchors must lie in one chain of a complex
+        if last >= self.state.chain.chain_end(first) || last < first + 3 {
+            return Err(RuleViolation::LoopNotClosed {
+                start,
+                end,
+                residual: f64::INFINITY,
+            });
+        }
+        self.validator.validate_rotation(
+            start,
+            Dihedral::Psi,
+            perturbation_degrees,
+            &self.state.chain,
+        )?;
+        self.validator.validate_members(first + 1..last)?;
+        self.check_frozen(first + 1..last)?;
+        let mut loop_positions = chain_positions(&self.state.chain)[first..=last].to_vec();
+        let closure = resample_loop(&mut loop_positions, perturbation_degrees, &mut self.rng);
+        if !closure.closed() {
+            self.increment_step();
+            return Err(RuleViolation::LoopNotClosed {
+                start,
+                end,
+                residual: closure.residual,
+            });
+        }
+        let alias = self.pending_alias.take();
+        let baseline_energy = self.potential_energy();
+        let turn = closure.total_turn();
+        let label = alias
+            .clone()
+            .unwrap_or_else(|| format!("loop-{first}-{last}"));
+        let mut span_record = SpanRecord::new(
+            label,
+            turn * 0.01,
+            turn * 0.005,
+            Duration::from_millis(duration_ms.max(1)),
+        );
+        span_record.delta_theta = turn;
+        let outcome = RotationOutcome {
+            residue: ResidueId(first),
+            dihedral: Dihedral::Psi,
+            applied_angle: turn,
+            span_record,
+            ghost: false,
+            physics_metrics: None,
+            collective_variables: Vec::new(),
+        };
+        self.settle_move(outcome, alias, baseline_energy, None, |state| {
+            let positions = state.chain.positions_mut();
+            for (offset, position) in loop_positions.iter().enumerate().take(last - first).skip(1) {
+                positions[first + offset] = *position;
+            }
+            // Turning the virtual bond i→i+1 is the psi move of residue i.
+            let psi = state.chain.psi_mut();
+            for (offset, change) in closure.torsion_changes.iter().enumerate() {
+                psi[first + offset] += change;
+            }
+        })
+    }
+
+    /// Backrub: swings Cα `residue` about the axis through its neighbours'
+    /// Cα, leaving both virtual bonds and the rest of the chain untouched.
+    fn execute_backrub(
+        &mut self,
+        residue: ResidueId,
+        angle_degrees: Option<f64>,
+        duration_ms: u64,
+    ) -> Result<RotationOutcome, RuleViolation> {
+        self.apply_temperature_schedule();
+        let index = residue.0;
+        let chain = &self.state.chain;
+        if index == 0 || !chain.is_bonded(index - 1) || !chain.is_bonded(index) {
+            return Err(RuleViolation::TerminalResidue { residue });
+        }
+        let angle = angle_degrees.unwrap_or_else(|| {
+            self.rng
+                .gen_range(-BACKRUB_MAX_DEGREES..BACKRUB_MAX_DEGREES)
+        });
+        self.validator
+            .validate_rotation(residue, Dihedral::Phi, angle, &self.state.chain)?;
+        self.check_frozen([residue.0])?;
+        let alias = self.pending_alias.take();
+        let baseline_energy = self.potential_energy();
+        let label = alias.clone().unwrap_or_else(|| format!("backrub-{index}"));
+        let mut span_record = SpanRecord::new(
+            label,
+            angle.abs() * 0.01,
+            angle.abs() * 0.005,
+            Duration::from_millis(duration_ms.max(1)),
+        );
+        span_record.delta_theta = angle;
+        let outcome = RotationOutcome {
+            residue,
+            dihedral: Dihedral::Phi,
+            applied_angle: angle,
+            span_record,
+            ghost: false,
+            physics_metrics: None,
+            collective_variables: Vec::new(),
+        };
+        self.settle_move(outcome, alias, baseline_energy, None, |state| {
+            let positions = state.chain.positions();
+            let before = positions[index - 1];
+            let after = positions[index + 1];
+            let axis = [after[0] - before[0], after[1] - before[1], after[2] - before[2]];
+            state.rotate_residues(index..index + 1, before, axis, angle.to_radians());
+        })
+    }
+
+    /// Rotamer flip: sets every chi angle of `residue` to a library rotamer,
+    /// the one named or, for `None`, one of the others drawn by frequency.
+    fn execute_rotamer_flip(
+        &mut self,
+        residue: ResidueId,
+        rotamer: Option<&str>,
+        duration_ms: u64,
+    ) -> Result<RotationOutcome, RuleViolation> {
+        self.apply_temperature_schedule();
+        let unavailable = RuleViolation::DihedralUnavailable {
+            residue,
+            dihedral: Dihedral::Chi1,
+        };
+        let Some(entry) = self.state.chain.residue(residue) else {
+            return Err(unavailable);
+        };
+        let (name, chi, chi_count) = (entry.name, entry.chi(), entry.chi_count());
+        let library = rotamers(name);
+        if library.is_empty() {
+            return Err(unavailable);
+        }
+        let target = match rotamer {
+            Some(wanted) => {
+                *find_rotamer(name, wanted).ok_or_else(|| RuleViolation::UnknownRotamer {
+                    residue,
+                    rotamer: wanted.to_string(),
+                })?
+            }
+            None => {
+                let current = nearest_rotamer(name, chi).map(|rotamer| rotamer.name);
+                let others: Vec<&Rotamer> = library
+                    .iter()
+                    .filter(|candidate| Some(candidate.name) != current)
+                    .collect();
+                let total: f64 = others.iter().map(|candidate| candidate.probability).sum();
+                let mut draw = self.rng.gen_range(0.0..total);
+                **others
+                    .iter()
+                    .find(|candidate| {
+                        draw -= candidate.probability;
+                        draw < 0.0
+                    })
+                    .or(others.last())
+                    .ok_or(unavailable)?
+            }
+        };
+        let mut command = RotamerCommand {
+            residue,
+            chi,
+            chi_count,
+            rotamer: target,
+            duration: Duration::from_millis(duration_ms.max(1)),
+            label: None,
+        };
+        let chis = [
+            Dihedral::Chi1,
+            Dihedral::Chi2,
+            Dihedral::Chi3,
+            Dihedral::Chi4,
+        ];
+        for (dihedral, change) in chis.into_iter().zip(command.chi_changes()) {
+            self.validator
+                .validate_rotation(residue, dihedral, change, &self.state.chain)?;
+        }
+        self.check_frozen([residue.0])?;
+        let alias = self.pending_alias.take();
+        let baseline_energy = self.potential_energy();
+        command.label = alias.clone();
+        let outcome = self.solver.solve_rotamer(command);
+        self.settle_move(outcome, alias, baseline_energy, None, |state| {
+            let chi = &mut state.chain.chi_mut()[residue.0];
+            chi[..chi_count].copy_from_slice(&target.chi[..chi_count]);
+        })
+    }
+
+    /// Relaxes the chain with an MD burst of [`REFINE_PICOSECONDS`] at
+    /// `level`, which stays in force for the rest of the contract, and
+    /// rebuilds the full backbone around the positions the backend returns.
+    /// Once the span is accepted the chain takes the relaxed trace and the
+    /// rebuilt backbone is reported as `refined_model`.
+    fn execute_refine(
+        &mut self,
+        level: PhysicsLevel,
+        duration_ms: u64,
+    ) -> Result<RotationOutcome, RuleViolation> {
+        self.apply_temperature_schedule();
+        self.switch_physics_level(level);
+        let alias = self.pending_alias.take();
+        let baseline_energy = self.potential_energy();
+        let label = alias
+            .clone()
+            .unwrap_or_else(|| format!("refine-{}", level.as_str()));
+        let duration = Duration::from_millis(duration_ms.max(1));
+        let burst = match self.md_burst(&label, level, REFINE_PICOSECONDS, duration) {
+            Ok(burst) => burst,
+            Err(err) => {
+                self.pending_alias = alias;
+                return Err(err);
+            }
+        };
+        let mut relaxed = self.state.chain.clone();
+        relaxed.positions_mut().copy_from_slice(&burst.positions);
+        let model = backmap(&relaxed);
+        let outcome = RotationOutcome {
+            residue: ResidueId(0),
+            dihedral: Dihedral::Phi,
+            applied_angle: 0.0,
+            span_record: SpanRecord::new(label, 0.0, 0.0, duration),
+            ghost: false,
+            physics_metrics: Some(burst.metrics.clone()),
+            collective_variables: Vec::new(),
+        };
+        let positions = burst.positions;
+        let result = self.settle_move(outcome, alias, baseline_energy, Some(burst.metrics), |state| {
+            state.chain.positions_mut().copy_from_slice(&positions);
+        });
+        if matches!(&result, Ok(outcome) if !outcome.ghost) {
+            self.refined_model = Some(model);
+        }
+        result
+    }
+
+    /// Turns each peptide bond whose omega is outside the geometry limit
+    /// back to trans, as one span, and rebuilds inverted Cβ of the refined
+    /// model on the L side once that span is accepted. With no bond to turn
+    /// the Cβ are rebuilt straight away and no span is recorded.
+    fn execute_fix_geometry(
+        &mut self,
+        duration_ms: u64,
+    ) -> Result<Option<RotationOutcome>, RuleViolation> {
+        self.apply_temperature_schedule();
+        let limit = self.geometry_validator.limits().omega_deviation_degrees.1;
+        let chain = &self.state.chain;
+        let fixes: Vec<(ResidueId, f64)> = (0..chain.len().saturating_sub(1))
+            .filter(|&index| {
+                chain.is_bonded(index)
+                    && omega_deviation(chain.omega()[index], &chain.names()[index + 1]) > limit
+            })
+            .map(|index| {
+                let to_trans = (360.0 - chain.omega()[index]).rem_euclid(360.0) - 180.0;
+                (chain.ids()[index], to_trans)
+            })
+            .collect();
+        if fixes.is_empty() {
+            // Nothing to turn: no span, and an alias meant for one lapses
+            self.pending_alias = None;
+            let restored = self.restore_refined_chirality();
+            log::info!(
+                "step {}: no peptide bond to turn trans, {restored} Cβ restored",
+                self.step_index
+            );
+            return Ok(None);
+        }
+        for (residue, delta) in &fixes {
+            self.validator.validate_rotation(
+                *residue,
+                Dihedral::Omega,
+                *delta,
+                &self.state.chain,
+            )?;
+        }
+        self.check_frozen(fixes.iter().map(|(residue, _)| residue.0))?;
+        let alias = self.pending_alias.take();
+        let baseline_energy = self.potential_energy();
+        let label = alias.clone().unwrap_or_else(|| "fix_geometry".to_string());
+        let turned: f64 = fixes.iter().map(|(_, delta)| delta.abs()).sum();
+        let mut span_record = SpanRecord::new(
+            label,
+            turned * 0.01,
+            turned * 0.005,
+            Duration::from_millis(duration_ms.max(1)),
+        );
+        span_record.delta_theta = turned;
+        let outcome = RotationOutcome {
+            residue: fixes.first().map_or(ResidueId(0), |(residue, _)| *residue),
+            dihedral: Dihedral::Omega,
+            applied_angle: fixes.first().map_or(0.0, |(_, delta)| *delta),
+            span_record,
+            ghost: false,
+            physics_metrics: None,
+            collective_variables: Vec::new(),
+        };
+        let result = self.settle_move(outcome, alias, baseline_energy, None, |state| {
+            for (residue, delta) in &fixes {
+                state.apply_rotation(*residue, Dihedral::Omega, *delta);
+            }
+        });
+        if matches!(&result, Ok(outcome) if !outcome.ghost) {
+            let restored = self.restore_refined_chirality();
+            log::info!(
+                "step {}: {} peptide bonds turned trans, {restored} Cβ restored",
+                self.step_index,
+                fixes.len()
+            );
+        }
+        result.map(Some)
+    }
+
+    /// Puts every inverted Cβ of the refined model back on the L side and
+    /// returns how many moved.
+    fn restore_refined_chirality(&mut self) -> usize {
+        self.refined_model.as_mut().map_or(0, |model| {
+            model
+                .iter_mut()
+                .map(BackmappedResidue::restore_chirality)
+                .filter(|&restored| restored)
+                .count()
+        })
+    }
+
+    /// Records the backends that failed during `attempt` on `span_id` and
+    /// returns the outcome that serviced the span, if any. With
+    /// `require_backend` set, a failure is returned as the span's error.
+    fn settle_backends(
+        &mut self,
+        attempt: PhysicsAttempt,
+        span_id: &str,
+    ) -> Result<Option<RotationOutcome>, String> {
+        let PhysicsAttempt { outcome, failures } = attempt;
+        let required = self.require_backend && !failures.is_empty();
+        let fallback = match &outcome {
+            _ if required => "none",
+            Some(outcome) => outcome
+                .physics_metrics
+                .as_ref()
+                .map_or("toy", |metrics| metrics.backend),
+            None => "toy",
+        };
+        let reason = failures
+            .first()
+            .map(|failure| format!("{} backend failed: {}", failure.backend, failure.reason));
+        for failure in failures {
+            log::debug!(
+                "step {}: {} backend failed ({}), falling back to {fallback}",
+                self.step_index,
+                failure.backend,
+                failure.reason
+            );
+            self.backend_failures.push(BackendFailureRecord {
+                step: self.step_index,
+                span_id: span_id.to_string(),
+                failure,
+                fallback,
+            });
+        }
+        match reason {
+            Some(reason) if required => Err(reason),
+            _ => Ok(outcome),
+        }
+    }
+
+    /// Runs a native MD burst from the current chain and proposes its end
+    /// state as one span; the engine's physics level is left unchanged.
+    fn execute_simulate(
+        &mut self,
+        picoseconds: f64,
+        level: Option<PhysicsLevel>,
+        duration_ms: u64,
+    ) -> Result<RotationOutcome, RuleViolation> {
+        self.apply_temperature_schedule();
+        let level = level.unwrap_or(self.physics_level);
+        let alias = self.pending_alias.take();
+        let baseline_energy = self.potential_energy();
+        let label = alias
+            .clone()
+            .unwrap_or_else(|| format!("md-{picoseconds}ps-{}", level.as_str()));
+        let duration = Duration::from_millis(duration_ms.max(1));
+        let burst = match self.md_burst(&label, level, picoseconds, duration) {
+            Ok(burst) => burst,
+            Err(err) => {
+                self.pending_alias = alias;
+                return Err(err);
+            }
+        };
+        let outcome = RotationOutcome {
+            residue: ResidueId(0),
+            dihedral: Dihedral::Phi,
+            applied_angle: 0.0,
+            span_record: SpanRecord::new(label, 0.0, 0.0, duration),
+            ghost: false,
+            physics_metrics: Some(burst.metrics.clone()),
+            collective_variables: Vec::new(),
+        };
+        let positions = burst.positions;
+        self.settle_move(outcome, alias, baseline_energy, Some(burst.metrics), |state| {
+            state.chain.positions_mut().copy_from_slice(&positions);
+        })
+    }
+
+    /// Integrates the chain for `picoseconds` at `level` on the native
+    /// backend; a failed burst is recorded as a backend failure and uses up
+    /// the step.
+    fn md_burst(
+        &mut self,
+        label: &str,
+        level: PhysicsLevel,
+        picoseconds: f64,
+        duration: Duration,
+    ) -> Result<MdBurst, RuleViolation> {
+        let request = PhysicsRequest {
+            chain: &self.state.chain,
+            command: RotationCommand {
+                residue: ResidueId(0),
+                dihedral: Dihedral::Phi,
+                angle_degrees: 0.0,
+                duration,
+                label: Some(label.to_string()),
+            },
+            level,
+            temperature: self.temperature,
+            environment: &self.environment,
+            crowders: self.crowders.as_ref(),
+            force_field: self.force_field.as_deref(),
+            integrator: self.integrator.as_deref(),
+            timestep_ps: self.md_timestep,
+            cache: None,
+            trajectories: self.native_trajectories.as_deref().map(|dir| TrajectoryOutput {
+                dir,
+                step: self.step_index,
+            }),
+            seed: self.rng.next_u64(),
+            frozen: &self.frozen_residues,
+        };
+        let burst = match physics_bridge::simulate_native(
+            request,
+            picoseconds,
+            &mut self.native_bridges,
+        ) {
+            Ok(burst) if burst.positions.len() == self.state.chain.len() => Ok(burst),
+            Ok(burst) => Err(format!(
+                "MD burst returned {} positions for {} residues",
+                burst.positions.len(),
+                self.state.chain.len()
+            )),
+            Err(reason) => Err(reason),
+        };
+        burst.map_err(|reason| {
+            self.backend_failures.push(BackendFailureRecord {
+                step: self.step_index,
+                span_id: label.to_string(),
+                failure: BackendFailure::new("native", reason.clone()),
+                fallback: "none",
+            });
+            self.increment_step();
+            RuleViolation::PhysicsUnavailable { reason }
+        })
+    }
+
+    /// Inclusive residue indices of `domain`, clamped to the chain. Names
+    /// refer to the most recent `define_domain` with that name.
+    fn resolve_domain(&self, domain: &DomainSelector) -> Result<(usize, usize), RuleViolation> {
+        let (start, end) = match domain {
+            DomainSelector::Named(name) => self
+                .domains
+                .iter()
+                .rev()
+                .find(|definition| definition.name.as_deref() == Some(name.as_str()))
+                .map(|definition| (definition.start.0, definition.end.0)),
+            DomainSelector::Range { start, end } => Some((start.0, end.0)),
+        }
+        .ok_or_else(|| RuleViolation::UnknownDomain {
+            domain: domain.label(),
+        })?;
+        let last = self.state.chain.len().checked_sub(1);
+        match last {
+            Some(last) if start <= last => Ok((start, end.min(last))),
+            _ => Err(RuleViolation::UnknownDomain {
+                domain: domain.label(),
+            }),
+        }
+    }
+
+    /// Ghost, budget, structure and Metropolis handling shared by every move
+    /// type; `apply` performs the move once the span has been admitted.
+    fn settle_move(
+        &mut self,
+        mut outcome: RotationOutcome,
+        alias: Option<String>,
+        baseline_energy: f64,
+        pending_metrics: Option<PhysicsSpanMetrics>,
+        apply: impl FnOnce(&mut ProteinState),
+    ) -> Result<RotationOutcome, RuleViolation> {
+        let physics_applied = pending_metrics.is_some();
+        outcome.span_record.tags = self.pending_tags.clone();
+        outcome.span_record.phase = self.phases.iter().map(|(name, _)| name.clone()).collect();
+        outcome.span_record.phase_blocks = self.phases.iter().map(|&(_, block)| block).collect();
+        let backend_delta_energy = pending_metrics
+            .as_ref()
+            .and_then(|metrics| metrics.delta_energy);
+        outcome.span_record.temperature = self.metropolis_temperature();
+        outcome.span_record.step = self.step_index;
+        outcome.span_record.simulated_ps = match &pending_metrics {
+            Some(metrics) if metrics.simulation_time_ps > 0.0 => metrics.simulation_time_ps,
+            _ => self.time_scale.to_ps(outcome.span_record.duration),
+        };
         if self.ghost_mode {
+            if let Err(err) = self.validator.validate_ghost_ratio(
+                self.ghost_trajectory.len() + 1,
+                self.state.trajectory().len(),
+            ) {
+                self.pending_alias = alias;
+                self.increment_step();
+                return Err(err);
+            }
             outcome.ghost = true;
+            log::debug!(
+                "step {}: ghost span {} delta_S={:.4}",
+                self.step_index,
+                outcome.span_record.id,
+                outcome.span_record.delta_entropy
+            );
             self.ghost_trajectory.push(outcome.span_record.clone());
             self.increment_step();
             return Ok(outcome);
@@ -423,38 +3666,89 @@ impl FoldingEngine {
             return Err(err);
         }
 
-        let snapshot = self.state.snapshot();
-        self.state.apply_rotation(residue, outcome.applied_angle);
-        if let Err(err) = self.validator.validate_structure(&self.state.chain) {
-            self.state.restore(snapshot);
+        // Moves only touch the chain, so the trajectory is not copied.
+        let previous = self.state.chain.clone();
+        apply(&mut self.state);
+        if let Err(err) = self
+            .validator
+            .validate_structure(&self.state.chain)
+            .and_then(|()| {
+                self.validator
+                    .validate_torsions(&previous, &self.state.chain)
+            })
+            .and_then(|()| self.check_frozen_in_place(&previous))
+        {
+            self.state.chain = previous;
             self.pending_alias = alias;
             self.increment_step();
             return Err(err);
         }
 
-        let new_energy = self.state.energy_model.total_energy(&self.state.chain);
+        let new_energy = self.potential_energy();
+        self.energy_samples.record(new_energy);
         let delta_energy = new_energy - baseline_energy;
         let projected_entropy =
             self.state.trajectory().total_entropy() + outcome.span_record.delta_entropy;
         let projected_gibbs = new_energy - self.temperature * projected_entropy;
         outcome.span_record.delta_energy = delta_energy;
         outcome.span_record.gibbs_energy = projected_gibbs;
-        if delta_energy > 0.0 {
-            let beta = 1.0 / (self.boltzmann_constant * self.temperature.max(1.0));
-            let exponent = (-delta_energy * beta).clamp(-700.0, 50.0);
+        if let Err(err) = self.validator.validate_energy_change(delta_energy) {
+            self.state.chain = previous;
+            self.pending_alias = alias;
+            self.increment_step();
+            return Err(err);
+        }
+        let score_deltas: Vec<f64> = self
+            .scoring_terms
+            .iter()
+            .map(|term| term.score(&self.state.chain) - term.score(&previous))
+            .collect();
+        if let Some(term) = self
+            .scoring_terms
+            .iter()
+            .zip(&score_deltas)
+            .find(|(_, delta)| !delta.is_finite())
+            .map(|(term, _)| term.name.clone())
+        {
+            self.state.chain = previous;
+            self.pending_alias = alias;
+            self.increment_step();
+            return Err(RuleViolation::ScoreUnavailable { term });
+        }
+        let criterion = delta_energy + score_deltas.iter().sum::<f64>();
+        if criterion > 0.0 {
+            let beta = 1.0 / (self.boltzmann_constant * self.metropolis_temperature().max(1.0));
+            let exponent = (-criterion * beta).clamp(-700.0, 50.0);
             let acceptance = exponent.exp().min(1.0);
             let roll: f64 = self.rng.gen_range(0.0..1.0);
             if roll >= acceptance {
-                self.state.restore(snapshot);
+                self.state.chain = previous;
                 self.pending_alias = alias;
-                self.metropolis_stats.record_reject();
+                self.record_decision(false);
                 self.increment_step();
-                return Err(RuleViolation::MetropolisRejected { delta_energy });
+                return Err(RuleViolation::MetropolisRejected {
+                    delta_energy: criterion,
+                });
             }
         }
 
-        self.metropolis_stats.record_accept();
+        self.record_decision(true);
+        for (total, delta) in self.score_totals.iter_mut().zip(&score_deltas) {
+            *total += delta;
+        }
+        outcome.collective_variables = self
+            .collective_variables
+            .iter()
+            .map(|variable| variable.evaluate(&self.state.chain))
+            .collect();
 
+        log::debug!(
+            "step {}: span {} accepted delta_E={:.4} delta_S={:.4}",
+            self.step_index,
+            outcome.span_record.id,
+            outcome.span_record.delta_energy,
+            outcome.span_record.delta_entropy
+        );
         let trajectory = self.state.trajectory_mut();
         trajectory.push(outcome.span_record.clone());
         if physics_applied {
@@ -465,17 +3759,92 @@ impl FoldingEngine {
                     .push(PhysicsSpanRecord { span_id, metrics });
             }
         }
+        if let Some(tolerance) = self.thermo_tolerance {
+            let bookkeeping = SpanBookkeeping {
+                potential_before: baseline_energy,
+                potential_after: self.potential_energy(),
+                delta_energy: outcome.span_record.delta_energy,
+                backend_delta_energy,
+                gibbs_energy: outcome.span_record.gibbs_energy,
+                thermostat_temperature: self.temperature,
+                span_temperature: outcome.span_record.temperature,
+                total_entropy: self.state.trajectory().total_entropy(),
+            };
+            for (check, expected, actual) in bookkeeping.check(tolerance) {
+                self.thermo_discrepancies.push(ThermoDiscrepancy {
+                    step: self.step_index,
+                    span_id: outcome.span_record.id.clone(),
+                    check,
+                    expected,
+                    actual,
+                });
+            }
+        }
         self.increment_step();
         Ok(outcome)
     }
 
     fn commit(&mut self) {
-        self.checkpoints.push(self.state.snapshot());
+        self.checkpoints
+            .push((self.state.snapshot(), self.native_bridges.velocities()));
+        self.record_rng_state();
+    }
+
+    fn record_rng_state(&mut self) {
+        let checkpoint = RngCheckpoint {
+            step: self.step_index,
+            state: self.rng.state(),
+            velocities: self.native_bridges.velocities(),
+        };
+        if self.rng_checkpoints.last() != Some(&checkpoint) {
+            self.rng_checkpoints.push(checkpoint);
+        }
+    }
+
+    fn record_energy_components(&mut self, time_ms: f64) {
+        if self
+            .energy_components
+            .last()
+            .is_some_and(|sample| sample.step == self.step_index)
+        {
+            return;
+        }
+        match self.component_bridge.energy_components(
+            &self.state.chain,
+            self.physics_level,
+            &self.environment,
+            self.force_field.as_deref(),
+        ) {
+            Ok((components, residues)) => self.energy_components.push(EnergyComponentSample {
+                step: self.step_index,
+                time_ms,
+                level: self.physics_level,
+                components,
+                residues,
+            }),
+            Err(err) => log::warn!("energy components at step {}: {err}", self.step_index),
+        }
+    }
+
+    fn analyze_frustration(&self) -> Option<FrustrationReport> {
+        let options = self.frustration.as_ref()?;
+        physics_bridge::frustration(
+            &self.state.chain,
+            self.physics_level,
+            &self.environment,
+            self.force_field.as_deref(),
+            options,
+        )
+        .unwrap_or_else(|err| {
+            log::warn!("frustration analysis: {err}");
+            None
+        })
     }
 
     fn rollback(&mut self) {
-        if let Some(snapshot) = self.checkpoints.pop() {
+        if let Some((snapshot, velocities)) = self.checkpoints.pop() {
             self.state.restore(snapshot);
+            self.native_bridges.restore(&velocities);
         } else {
             let _ = self.state.trajectory_mut().pop_last();
         }
@@ -492,14 +3861,234 @@ impl FoldingEngine {
         self.state.trajectory()
     }
 
+    /// Temperature the Metropolis criterion uses; ghost-budget escalation
+    /// scales it like the thermostat.
+    fn metropolis_temperature(&self) -> f64 {
+        self.acceptance_temperature
+            .map_or(self.temperature, |kelvin| kelvin * self.temperature_scale)
+    }
+
+    /// Replaces the solvent conditions with a named environment. The run's
+    /// crowding and dielectric models carry over, and explicit crowders are
+    /// placed afresh for the new crowding fraction.
+    fn switch_environment(&mut self, name: &str) {
+        let Some(environment) = Environment::by_name(name) else {
+            return;
+        };
+        self.environment = environment
+            .with_crowding_model(self.environment.crowding_model)
+            .with_dielectric_model(self.environment.dielectric_model);
+        self.environment_ramps
+            .retain(|ramp| ramp.parameter == EnvironmentParameter::Temperature);
+        self.place_crowders();
+    }
+
+    /// Places explicit crowders around the current chain for the current
+    /// environment, replacing any earlier ones.
+    fn place_crowders(&mut self) {
+        let seed = self.rng.clone().next_u64();
+        self.crowders = self
+            .environment
+            .crowders(&chain_positions(&self.state.chain), seed);
+        warn_if_jammed(self.crowders.as_ref());
+    }
+
+    /// Sets a parameter from a `set` line and ends its ramp.
+    fn set_environment_parameter(&mut self, parameter: EnvironmentParameter, value: f64) {
+        self.environment_ramps
+            .retain(|ramp| ramp.parameter != parameter);
+        self.apply_environment_parameter(parameter, value);
+    }
+
+    fn apply_environment_parameter(&mut self, parameter: EnvironmentParameter, value: f64) {
+        let environment = std::mem::take(&mut self.environment);
+        self.environment = match parameter {
+            EnvironmentParameter::Temperature => {
+                self.temperature_schedule = None;
+                self.initial_temperature = value;
+                self.temperature = value * self.temperature_scale;
+                environment
+            }
+            EnvironmentParameter::Viscosity => environment.with_viscosity(value),
+            EnvironmentParameter::Dielectric => environment.with_dielectric(value),
+            EnvironmentParameter::Crowding => environment.with_crowding(value),
+            EnvironmentParameter::IonicStrength => environment.with_ionic_strength(value),
+        };
+        if parameter == EnvironmentParameter::Crowding {
+            self.place_crowders();
+        }
+    }
+
+    /// Moves ramped parameters to their value for this step and drops the
+    /// ramps that have reached their end.
+    fn apply_environment_ramps(&mut self) {
+        let step = self.step_index;
+        for ramp in self.environment_ramps.clone() {
+            self.apply_environment_parameter(ramp.parameter, ramp.value_at(step));
+        }
+        self.environment_ramps
+            .retain(|ramp| !ramp.finished_at(step));
+    }
+
     fn apply_temperature_schedule(&mut self) {
+        self.apply_environment_ramps();
         if let Some(schedule) = &self.temperature_schedule {
             let new_temp = schedule.temperature_for_step(self.step_index, self.initial_temperature);
-            self.temperature = new_temp;
+            self.temperature = new_temp * self.temperature_scale;
         }
+        for observer in &mut self.observers {
+            observer.on_schedule_step(self.step_index, self.temperature);
+        }
+    }
+
+    /// Applies the ruleset's recovery for `violation`, raised by `instruction`.
+    /// Returns the final result of the move and whether the contract must stop.
+    fn recover(
+        &mut self,
+        instruction: &ContractInstruction,
+        violation: RuleViolation,
+        recoveries: &mut Vec<RecoveryRecord>,
+    ) -> (Result<RotationOutcome, RuleViolation>, bool) {
+        let policy = self.validator.ruleset().recovery_for(&violation);
+        let step = self.step_index;
+        let result = match policy {
+            RecoveryPolicy::Reject => return (Err(violation), false),
+            RecoveryPolicy::Abort => Err(violation.clone()),
+            RecoveryPolicy::HalveAndRetry { attempts } => {
+                let mut result = Err(violation.clone());
+                let mut factor = 1.0;
+                for _ in 0..attempts {
+                    factor *= 0.5;
+                    let Some(smaller) = instruction.scaled(factor) else {
+                        break;
+                    };
+                    if let Some(retried) = self.execute_move(&smaller) {
+                        result = retried;
+                    }
+                    if result.is_ok() {
+                        break;
+                    }
+                }
+                result
+            }
+            RecoveryPolicy::Ghost => {
+                let ghost_mode = self.ghost_mode;
+                self.ghost_mode = true;
+                let result = self
+                    .execute_move(instruction)
+                    .unwrap_or_else(|| Err(violation.clone()));
+                self.ghost_mode = ghost_mode;
+                result
+            }
+        };
+        recoveries.push(RecoveryRecord {
+            step,
+            violation,
+            policy,
+            recovered: result.is_ok(),
+        });
+        (result, policy == RecoveryPolicy::Abort)
+    }
+
+    fn violation_record(&self, violation: &RuleViolation) -> ViolationRecord {
+        ViolationRecord {
+            step: self.step_index,
+            violation: violation.clone(),
+        }
+    }
+
+    fn freeze(&mut self, start: ResidueId, end: ResidueId) {
+        log::info!("step {}: freezing residues {}..{}", self.step_index, start.0, end.0);
+        self.frozen.push(FrozenRegion {
+            start,
+            end,
+            step: self.step_index,
+        });
+        let len = self.state.chain.len();
+        self.frozen_residues
+            .extend((start.0..=end.0).filter(|&index| index < len));
+        self.frozen_residues.sort_unstable();
+        self.frozen_residues.dedup();
+    }
+
+    /// Refuses moves that turn or carry a frozen residue.
+    fn check_frozen(&self, members: impl IntoIterator<Item = usize>) -> Result<(), RuleViolation> {
+        members.into_iter().try_for_each(|member| {
+            match self
+                .frozen
+                .iter()
+                .find(|region| (region.start.0..=region.end.0).contains(&member))
+            {
+                Some(region) => Err(RuleViolation::FrozenResidue {
+                    residue: ResidueId(member),
+                    start: region.start,
+                    end: region.end,
+                }),
+                None => Ok(()),
+            }
+        })
+    }
+
+    /// Catches moves that displace frozen residues indirectly, e.g. a psi
+    /// turn upstream swinging a frozen segment downstream of it.
+    fn check_frozen_in_place(&self, previous: &PeptideChain) -> Result<(), RuleViolation> {
+        let (before, after) = (previous.positions(), self.state.chain.positions());
+        let moved = self.frozen_residues.iter().copied().filter(|&index| {
+            (0..3).any(|axis| (after[index][axis] - before[index][axis]).abs() > 1e-6)
+        });
+        self.check_frozen(moved)
+    }
+
+    /// Backends keep no state between spans, so the chain carries over as is:
+    /// the first span at the new level starts from where the old one left it.
+    /// Energies sampled at the old level are not decoys of the new one, so
+    /// the foldability samples start over from the current conformation.
+    fn switch_physics_level(&mut self, level: PhysicsLevel) {
+        if level != self.physics_level {
+            log::info!(
+                "step {}: physics level {} -> {}",
+                self.step_index,
+                self.physics_level.as_str(),
+                level.as_str()
+            );
+            self.level_switches.push(PhysicsLevelSwitch {
+                step: self.step_index,
+                from: self.physics_level,
+                to: level,
+            });
+            self.physics_level = level;
+            self.energy_samples = EnergySamples::new();
+            self.energy_samples.record(self.potential_energy());
+        }
+    }
+
+    fn record_decision(&mut self, accepted: bool) {
+        if accepted {
+            self.metropolis_stats.record_accept();
+        } else {
+            self.metropolis_stats.record_reject();
+        }
+        self.metropolis_decisions
+            .push((self.step_index + 1, accepted));
     }
 
     fn increment_step(&mut self) {
         self.step_index = self.step_index.saturating_add(1);
     }
 }
+
+/// Warns when explicit crowders fell short of the requested volume fraction.
+fn warn_if_jammed(crowders: Option<&CrowderField>) {
+    if let Some(field) = crowders.filter(|field| field.centers.len() < field.requested) {
+        log::warn!(
+            "placed {} of {} explicit crowders (volume fraction {:.3}); random packing jams near {JAMMING_FRACTION}",
+            field.centers.len(),
+            field.requested,
+            field.volume_fraction()
+        );
+    }
+}
+
+fn chain_positions(chain: &PeptideChain) -> Vec<[f64; 3]> {
+    chain.positions().to_vec()
+}
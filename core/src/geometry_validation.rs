@@ -0,0 +1,398 @@
+use folding_molecule::{PeptideChain, ResidueId};
+
+use crate::backmapping::BackmappedResidue;
+use crate::folding_ruleset::{bond_angle, distance};
+
+/// Allowed ranges applied when validating an exported structure.
+#[derive(Clone, Debug)]
+pub struct GeometryLimits {
+    /// Consecutive Cα–Cα distance in Å (covers both trans and cis peptides).
+    pub bond_length: (f64, f64),
+    /// Cα(i-1)–Cα(i)–Cα(i+1) virtual bond angle in degrees.
+    pub bond_angle_degrees: (f64, f64),
+    /// Departure of each peptide bond's omega from trans, in degrees; bonds
+    /// into a proline are measured from cis as well.
+    pub omega_deviation_degrees: (f64, f64),
+    /// Signed Cα chiral volume in Å³ (L residues sit near +2.5).
+    pub chiral_volume: (f64, f64),
+}
+
+impl Default for GeometryLimits {
+    fn default() -> Self {
+        Self {
+            bond_length: (2.8, 4.0),
+            bond_angle_degrees: (75.0, 155.0),
+            omega_deviation_degrees: (0.0, 30.0),
+            chiral_volume: (0.5, 5.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeometryCheckKind {
+    BondLength,
+    BondAngle,
+    Chirality,
+    CisPeptide,
+}
+
+impl GeometryCheckKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GeometryCheckKind::BondLength => "bond_length",
+            GeometryCheckKind::BondAngle => "bond_angle",
+            GeometryCheckKind::Chirality => "chirality",
+            GeometryCheckKind::CisPeptide => "cis_peptide",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeometryStatus {
+    Pass,
+    Fail,
+    /// The check needs atoms the structure does not carry (e.g. Cα-only traces).
+    Skipped,
+}
+
+impl GeometryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GeometryStatus::Pass => "pass",
+            GeometryStatus::Fail => "fail",
+            GeometryStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// Outcome of a single family of geometry checks.
+#[derive(Clone, Debug)]
+pub struct GeometryCheck {
+    pub kind: GeometryCheckKind,
+    pub status: GeometryStatus,
+    pub checked: usize,
+    pub failures: usize,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Individual out-of-range measurement.
+#[derive(Clone, Debug)]
+pub struct GeometryIssue {
+    pub kind: GeometryCheckKind,
+    pub residues: Vec<ResidueId>,
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Machine-readable validation section attached to execution reports.
+#[derive(Clone, Debug, Default)]
+pub struct GeometryReport {
+    pub checks: Vec<GeometryCheck>,
+    pub issues: Vec<GeometryIssue>,
+}
+
+impl GeometryReport {
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != GeometryStatus::Fail)
+    }
+
+    pub fn failure_count(&self) -> usize {
+        self.issues.len()
+    }
+}
+
+/// Degrees `omega` lies from a trans peptide bond. A bond into proline
+/// (`next` is `PRO`) may also be cis, so there the nearer of the two counts.
+pub fn omega_deviation(omega: f64, next: &str) -> f64 {
+    let from_cis = (omega + 180.0).rem_euclid(360.0) - 180.0;
+    let from_trans = 180.0 - from_cis.abs();
+    if next.eq_ignore_ascii_case("PRO") {
+        from_trans.min(from_cis.abs())
+    } else {
+        from_trans
+    }
+}
+
+/// Post-export validation of bond lengths, bond angles, peptide bonds and
+/// chirality.
+#[derive(Clone, Debug, Default)]
+pub struct GeometryValidator {
+    limits: GeometryLimits,
+}
+
+impl GeometryValidator {
+    pub fn new(limits: GeometryLimits) -> Self {
+        Self { limits }
+    }
+
+    pub fn limits(&self) -> &GeometryLimits {
+        &self.limits
+    }
+
+    pub fn validate(&self, chain: &PeptideChain) -> GeometryReport {
+        self.validate_model(chain, None)
+    }
+
+    /// Same checks, with chirality judged on `model` when a full backbone
+    /// (e.g. a refined one) is available.
+    pub fn validate_model(
+        &self,
+        chain: &PeptideChain,
+        model: Option<&[BackmappedResidue]>,
+    ) -> GeometryReport {
+        let mut report = GeometryReport::default();
+        self.check_bond_lengths(chain, &mut report);
+        self.check_bond_angles(chain, &mut report);
+        self.check_peptide_bonds(chain, &mut report);
+        match model {
+            Some(model) => self.check_chirality(chain, model, &mut report),
+            // Residues carry a single Cα bead; chirality needs N, C and Cβ positions.
+            None => report.checks.push(GeometryCheck {
+                kind: GeometryCheckKind::Chirality,
+                status: GeometryStatus::Skipped,
+                checked: 0,
+                failures: 0,
+                min: 0.0,
+                max: 0.0,
+            }),
+        }
+        report
+    }
+
+    fn check_bond_lengths(&self, chain: &PeptideChain, report: &mut GeometryReport) {
+        let (min, max) = self.limits.bond_length;
+        let mut checked = 0;
+        let mut failures = 0;
+        let (positions, ids) = (chain.positions(), chain.ids());
+        for i in (1..positions.len()).filter(|&i| chain.is_bonded(i - 1)) {
+            checked += 1;
+            let value = distance(positions[i - 1], positions[i]);
+            if value < min || value > max {
+                failures += 1;
+                report.issues.push(GeometryIssue {
+                    kind: GeometryCheckKind::BondLength,
+                    residues: vec![ids[i - 1], ids[i]],
+                    value,
+                    min,
+                    max,
+                });
+            }
+        }
+        report.checks.push(summarise(
+            GeometryCheckKind::BondLength,
+            checked,
+            failures,
+            (min, max),
+        ));
+    }
+
+    fn check_bond_angles(&self, chain: &PeptideChain, report: &mut GeometryReport) {
+        let (min, max) = self.limits.bond_angle_degrees;
+        let mut checked = 0;
+        let mut failures = 0;
+        let windows = chain.positions().windows(3).zip(chain.ids().windows(3));
+        let within_chain = |&(i, _): &(usize, _)| chain.is_bonded(i) && chain.is_bonded(i + 1);
+        for (_, (window, ids)) in windows.enumerate().filter(within_chain) {
+            checked += 1;
+            let value = bond_angle(window[0], window[1], window[2]).to_degrees();
+            if value < min || value > max {
+                failures += 1;
+                report.issues.push(GeometryIssue {
+                    kind: GeometryCheckKind::BondAngle,
+                    residues: ids.to_vec(),
+                    value,
+                    min,
+                    max,
+                });
+            }
+        }
+        report.checks.push(summarise(
+            GeometryCheckKind::BondAngle,
+            checked,
+            failures,
+            (min, max),
+        ));
+    }
+
+    fn check_peptide_bonds(&self, chain: &PeptideChain, report: &mut GeometryReport) {
+        let (min, max) = self.limits.omega_deviation_degrees;
+        let mut checked = 0;
+        let mut failures = 0;
+        let (omega, ids, names) = (chain.omega(), chain.ids(), chain.names());
+        for i in (1..chain.len()).filter(|&i| chain.is_bonded(i - 1)) {
+            checked += 1;
+            let value = omega_deviation(omega[i - 1], &names[i]);
+            if value < min || value > max {
+                failures += 1;
+                report.issues.push(GeometryIssue {
+                    kind: GeometryCheckKind::CisPeptide,
+                    residues: vec![ids[i - 1], ids[i]],
+                    value,
+                    min,
+                    max,
+                });
+            }
+        }
+        report.checks.push(summarise(
+            GeometryCheckKind::CisPeptide,
+            checked,
+            failures,
+            (min, max),
+        ));
+    }
+
+    fn check_chirality(
+        &self,
+        chain: &PeptideChain,
+        model: &[BackmappedResidue],
+        report: &mut GeometryReport,
+    ) {
+        let (min, max) = self.limits.chiral_volume;
+        let mut checked = 0;
+        let mut failures = 0;
+        for (id, residue) in chain.ids().iter().zip(model) {
+            let Some(value) = residue.chiral_volume() else {
+                continue;
+            };
+            checked += 1;
+            if value < min || value > max {
+                failures += 1;
+                report.issues.push(GeometryIssue {
+                    kind: GeometryCheckKind::Chirality,
+                    residues: vec![*id],
+                    value,
+                    min,
+                    max,
+                });
+            }
+        }
+        report.checks.push(summarise(
+            GeometryCheckKind::Chirality,
+            checked,
+            failures,
+            (min, max),
+        ));
+    }
+}
+
+fn summarise(
+    kind: GeometryCheckKind,
+    checked: usize,
+    failures: usize,
+    (min, max): (f64, f64),
+) -> GeometryCheck {
+    let status = if checked == 0 {
+        GeometryStatus::Skipped
+    } else if failures == 0 {
+        GeometryStatus::Pass
+    } else {
+        GeometryStatus::Fail
+    };
+    GeometryCheck {
+        kind,
+        status,
+        checked,
+        failures,
+        min,
+        max,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backmapping::backmap;
+    use folding_molecule::{Dihedral, Residue};
+
+    fn chain_from_positions(positions: &[[f64; 3]]) -> PeptideChain {
+        let residues = positions
+            .iter()
+            .enumerate()
+            .map(|(idx, position)| Residue::new(ResidueId(idx), "ALA", *position))
+            .collect();
+        PeptideChain::new(residues)
+    }
+
+    #[test]
+    fn ideal_trace_passes_and_skips_chirality() {
+        let chain = chain_from_positions(&[[0.0, 0.0, 0.0], [3.8, 0.0, 0.0], [5.7, 3.29, 0.0]]);
+        let report = GeometryValidator::default().validate(&chain);
+        assert!(report.passed());
+        assert!(report.issues.is_empty());
+        let chirality = report
+            .checks
+            .iter()
+            .find(|check| check.kind == GeometryCheckKind::Chirality)
+            .unwrap();
+        assert_eq!(chirality.status, GeometryStatus::Skipped);
+    }
+
+    #[test]
+    fn stretched_bond_and_straight_angle_are_reported() {
+        let chain = chain_from_positions(&[[0.0, 0.0, 0.0], [3.8, 0.0, 0.0], [9.0, 0.0, 0.0]]);
+        let report = GeometryValidator::default().validate(&chain);
+        assert!(!report.passed());
+        assert!(report.issues.iter().any(|issue| {
+            issue.kind == GeometryCheckKind::BondLength && issue.residues[0] == ResidueId(1)
+        }));
+        assert!(
+            report
+                .issues
+                .iter()
+                .any(|issue| issue.kind == GeometryCheckKind::BondAngle)
+        );
+    }
+
+    #[test]
+    fn cis_peptides_and_inverted_residues_are_reported_per_residue() {
+        let mut chain = PeptideChain::from_sequence("AAPA");
+        let ideal = chain_from_positions(&[
+            [0.0, 0.0, 0.0],
+            [3.8, 0.0, 0.0],
+            [5.7, 3.29, 0.0],
+            [9.5, 3.29, 0.0],
+        ]);
+        // Cis before the proline is allowed; cis between residues 0 and 1 is not.
+        chain.rotate_torsion(ResidueId(0), Dihedral::Omega, 175.0);
+        chain.rotate_torsion(ResidueId(1), Dihedral::Omega, -180.0);
+        chain.positions_mut().copy_from_slice(ideal.positions());
+        assert!((omega_deviation(-5.0, "PRO") - 5.0).abs() < 1e-9);
+
+        let mut model = backmap(&chain);
+        model[3].cb = model[3].cb.map(|cb| {
+            let ca = model[3].ca;
+            [
+                2.0 * ca[0] - cb[0],
+                2.0 * ca[1] - cb[1],
+                2.0 * ca[2] - cb[2],
+            ]
+        });
+        let report = GeometryValidator::default().validate_model(&chain, Some(&model));
+        assert!(!report.passed());
+        let flagged: Vec<(GeometryCheckKind, Vec<ResidueId>)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.kind, issue.residues.clone()))
+            .collect();
+        assert_eq!(
+            flagged,
+            [
+                (
+                    GeometryCheckKind::CisPeptide,
+                    vec![ResidueId(0), ResidueId(1)]
+                ),
+                (GeometryCheckKind::Chirality, vec![ResidueId(3)]),
+            ]
+        );
+        let chirality = report
+            .checks
+            .iter()
+            .find(|check| check.kind == GeometryCheckKind::Chirality)
+            .unwrap();
+        assert_eq!((chirality.checked, chirality.failures), (4, 1));
+    }
+}
@@ -0,0 +1,123 @@
+/// Temperature multiplier of `escalate` when no factor is given.
+pub const DEFAULT_ESCALATION_FACTOR: f64 = 1.5;
+
+/// Cap on the ghost spans a run keeps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GhostLimit {
+    /// At most this many ghost spans.
+    Count(usize),
+    /// Ghost |ΔS| at most this fraction of all span |ΔS|, ghost and applied.
+    EntropyFraction(f64),
+}
+
+impl GhostLimit {
+    /// `count:50` or `entropy:0.25`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (kind, value) = raw.trim().split_once(':')?;
+        match kind.to_ascii_lowercase().as_str() {
+            "count" => value.parse().ok().map(GhostLimit::Count),
+            "entropy" => value
+                .parse()
+                .ok()
+                .filter(|fraction: &f64| (0.0..=1.0).contains(fraction))
+                .map(GhostLimit::EntropyFraction),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            GhostLimit::Count(count) => format!("count:{count}"),
+            GhostLimit::EntropyFraction(fraction) => format!("entropy:{fraction}"),
+        }
+    }
+}
+
+/// What the engine does once the cap is exceeded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GhostOverflow {
+    /// Stop executing the contract.
+    Stop,
+    /// Multiply the temperature by `factor`, once, and carry on.
+    Escalate { factor: f64 },
+    /// Drop the oldest ghost spans until the run is back within the cap.
+    Prune,
+}
+
+impl GhostOverflow {
+    /// `stop`, `prune`, `escalate` or `escalate:<factor>`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim().to_ascii_lowercase();
+        match raw.as_str() {
+            "stop" => Some(GhostOverflow::Stop),
+            "prune" => Some(GhostOverflow::Prune),
+            "escalate" => Some(GhostOverflow::Escalate {
+                factor: DEFAULT_ESCALATION_FACTOR,
+            }),
+            other => {
+                let factor: f64 = other.strip_prefix("escalate:")?.parse().ok()?;
+                (factor > 0.0).then_some(GhostOverflow::Escalate { factor })
+            }
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            GhostOverflow::Stop => "stop".into(),
+            GhostOverflow::Prune => "prune".into(),
+            GhostOverflow::Escalate { factor } => format!("escalate:{factor}"),
+        }
+    }
+}
+
+/// Ghost-span cap and the action taken when a ghost span pushes past it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GhostBudget {
+    pub limit: GhostLimit,
+    pub overflow: GhostOverflow,
+}
+
+impl GhostBudget {
+    pub fn new(limit: GhostLimit, overflow: GhostOverflow) -> Self {
+        Self { limit, overflow }
+    }
+
+    pub fn exceeded(&self, ghost_spans: usize, ghost_entropy: f64, applied_entropy: f64) -> bool {
+        match self.limit {
+            GhostLimit::Count(count) => ghost_spans > count,
+            GhostLimit::EntropyFraction(fraction) => {
+                let total = ghost_entropy.abs() + applied_entropy.abs();
+                total > f64::EPSILON && ghost_entropy.abs() / total > fraction
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_and_overflow_actions_parse() {
+        assert_eq!(GhostLimit::parse("count:3"), Some(GhostLimit::Count(3)));
+        assert_eq!(
+            GhostLimit::parse("entropy:0.25"),
+            Some(GhostLimit::EntropyFraction(0.25))
+        );
+        assert_eq!(GhostLimit::parse("entropy:2"), None);
+        assert_eq!(
+            GhostOverflow::parse("escalate"),
+            Some(GhostOverflow::Escalate { factor: 1.5 })
+        );
+        assert_eq!(GhostOverflow::parse("escalate:0"), None);
+        assert_eq!(GhostOverflow::parse("Prune").unwrap().label(), "prune");
+
+        let by_count = GhostBudget::new(GhostLimit::Count(2), GhostOverflow::Stop);
+        assert!(!by_count.exceeded(2, 10.0, 0.0));
+        assert!(by_count.exceeded(3, 0.0, 0.0));
+        let by_entropy = GhostBudget::new(GhostLimit::EntropyFraction(0.5), GhostOverflow::Prune);
+        assert!(!by_entropy.exceeded(10, 1.0, 1.0));
+        assert!(by_entropy.exceeded(1, 1.5, 1.0));
+        assert!(!by_entropy.exceeded(0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::folding_parser::PhysicsLevel;
+
+/// Wall-clock budget assumed when `--level auto` is given without one.
+pub const DEFAULT_WALL_CLOCK_BUDGET_SECS: f64 = 60.0;
+
+/// Rough cost (s) of one Cα pair interaction evaluated by the native backend.
+const PAIR_SECONDS: f64 = 2e-8;
+
+/// Level picked by [`choose_physics_level`] and why.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelChoice {
+    pub level: PhysicsLevel,
+    /// Predicted wall-clock time (s) of the run at `level`.
+    pub estimated_secs: f64,
+    pub rationale: String,
+}
+
+/// Predicted wall-clock time (s) for `steps` spans on a chain of `residues`
+/// at `level`: each span integrates the native backend's step count over all
+/// residue pairs, GB paying three times more per pair for the Born radii.
+pub fn estimated_run_secs(level: PhysicsLevel, residues: usize, steps: usize) -> f64 {
+    let (integration_steps, pair_factor) = match level {
+        PhysicsLevel::Toy => (1.0, 1.0),
+        PhysicsLevel::Coarse => (200.0, 1.0),
+        PhysicsLevel::Gb => (500.0, 3.0),
+        PhysicsLevel::Full => (1000.0, 30.0),
+    };
+    let pairs = (residues * residues.saturating_sub(1)) as f64 / 2.0;
+    steps.max(1) as f64 * integration_steps * pair_factor * pairs * PAIR_SECONDS
+}
+
+/// `--level auto`: the most detailed of GB, Coarse and Toy whose predicted
+/// run time fits `budget_secs`. Full is never chosen automatically.
+pub fn choose_physics_level(residues: usize, steps: usize, budget_secs: f64) -> LevelChoice {
+    let candidates = [PhysicsLevel::Gb, PhysicsLevel::Coarse];
+    let summary = format!("{residues} residues × {steps} steps, budget {budget_secs:.0} s");
+    let mut skipped = Vec::new();
+    for level in candidates {
+        let estimated_secs = estimated_run_secs(level, residues, steps);
+        if estimated_secs <= budget_secs {
+            let rejected = if skipped.is_empty() {
+                String::new()
+            } else {
+                format!("; {}", skipped.join(", "))
+            };
+            return LevelChoice {
+                level,
+                estimated_secs,
+                rationale: format!(
+                    "{summary}: {} fits (≈{estimated_secs:.1} s){rejected}",
+                    level.as_str()
+                ),
+            };
+        }
+        skipped.push(format!(
+            "{} would take ≈{estimated_secs:.1} s",
+            level.as_str()
+        ));
+    }
+    let estimated_secs = estimated_run_secs(PhysicsLevel::Toy, residues, steps);
+    LevelChoice {
+        level: PhysicsLevel::Toy,
+        estimated_secs,
+        rationale: format!("{summary}: {}", skipped.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_level_trades_detail_for_budget() {
+        let small = choose_physics_level(20, 10, 60.0);
+        assert_eq!(small.level, PhysicsLevel::Gb);
+        assert!(small.estimated_secs <= 60.0);
+        assert!(small.rationale.starts_with("20 residues × 10 steps"));
+
+        let medium = choose_physics_level(300, 200, 60.0);
+        assert_eq!(medium.level, PhysicsLevel::Coarse);
+        assert!(medium.rationale.contains("gb would take"));
+
+        let large = choose_physics_level(2000, 500, 60.0);
+        assert_eq!(large.level, PhysicsLevel::Toy);
+        assert!(large.rationale.contains("coarse would take"));
+        assert_eq!(choose_physics_level(2000, 500, 1e6).level, PhysicsLevel::Gb);
+    }
+}
@@ -1,21 +1,105 @@
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+#[cfg(feature = "async")]
+pub mod async_engine;
+pub mod backmapping;
+pub mod collective_variables;
+pub mod contract_builder;
+pub mod foldability;
 pub mod folding_parser;
 pub mod folding_ruleset;
 pub mod folding_runtime;
+pub mod geometry_validation;
+pub mod ghost_budget;
+pub mod level_selection;
+pub mod loop_closure;
+pub mod memory;
 pub mod micro_oscillator;
+pub mod observer;
 pub mod physics_bridge;
 pub mod protein_state;
+pub mod ramachandran;
+pub mod restraints;
 pub mod rotation_solver;
+pub mod scoring;
 pub mod simple_rng;
+pub mod symmetry;
+pub mod target_contract;
+pub mod test_support;
+pub mod thermo_checks;
 pub mod validation;
 
-pub use folding_parser::{ContractInstruction, FoldingContract, PhysicsLevel, PhysicsSpanMode};
-pub use folding_ruleset::{RuleViolation, Ruleset};
+pub use backmapping::{BackmappedResidue, backmap, place_ca_trace};
+pub use collective_variables::{COLLECTIVE_VARIABLES, CollectiveVariable};
+pub use contract_builder::ContractBuilder;
+pub use foldability::{EnergySamples, Foldability};
+pub use folding_parser::{
+    BACKRUB_MAX_DEGREES, ContractInstruction, ContractMetadata, ContractParseError, DomainAxis,
+    DomainMotion, DomainSelector, EnvironmentParameter, FoldingContract, PhysicsLevel,
+    PhysicsSpanMode, RotationSpan, SIMULATE_MAX_PICOSECONDS,
+};
+pub use folding_ruleset::{RecoveryPolicy, RuleViolation, Ruleset, Severity, ViolationRecord};
 pub use folding_runtime::{
-    ChaperoneRequirement, DomainDefinition, ExecutionReport, FoldingEngine, FoldingEngineBuilder,
-    MetropolisStats, PhysicsSpanRecord, PostTranslationalModification, TemperatureSchedule,
+    AcceptanceSample, BackendFailureRecord, ChaperoneRequirement, DEFAULT_ACCEPTANCE_WINDOW,
+    DomainDefinition, EnergyComponentSample, ExecutionReport, FoldingEngine, FoldingEngineBuilder,
+    FrozenRegion, MetropolisStats, PhysicsLevelSwitch, PhysicsSpanRecord,
+    PostTranslationalModification, RecoveryRecord, RunOutcome, TemperatureSchedule,
+    TrajectoryFrame,
+};
+pub use geometry_validation::{
+    GeometryCheck, GeometryCheckKind, GeometryIssue, GeometryLimits, GeometryReport,
+    GeometryStatus, GeometryValidator,
 };
+pub use ghost_budget::{GhostBudget, GhostLimit, GhostOverflow};
+pub use level_selection::{DEFAULT_WALL_CLOCK_BUDGET_SECS, LevelChoice, choose_physics_level};
+pub use loop_closure::LoopClosure;
+pub use memory::{MemoryUsage, peak_rss_bytes};
 pub use micro_oscillator::MicroOscillator;
-pub use physics_bridge::{PhysicsRequest, PhysicsSpanMetrics};
+pub use observer::{EngineObserver, ObserverControl};
+pub use physics_bridge::{
+    BackendFailure, MdBurst, NativeBridges, NativeVelocities, PhysicsAttempt, PhysicsCache,
+    PhysicsRequest, PhysicsSpanMetrics, TrajectoryOutput,
+};
 pub use protein_state::{EnergyState, ProteinState};
-pub use rotation_solver::{RotationCommand, RotationOutcome, RotationSolver};
+pub use ramachandran::RamachandranMap;
+pub use restraints::{
+    ContactPrior, DEFAULT_CONTACT_CONSTANT, DEFAULT_RESTRAINT_CONSTANT, DihedralRestraint,
+    DistanceRestraint, SecondaryStructure,
+};
+pub use rotation_solver::{
+    RotamerCommand, RotationCommand, RotationOutcome, RotationSolver, TorsionLimits,
+};
+pub use simple_rng::{RngCheckpoint, SimpleRng};
+pub use scoring::{ScoreExpression, ScoringTerm};
+pub use symmetry::{PointGroup, Symmetry};
+pub use target_contract::{
+    BackboneAtoms, BackboneCollector, TargetContractOptions, TargetDihedrals, backbone_dihedrals,
+    contract_from_target,
+};
+pub use thermo_checks::{DEFAULT_THERMO_TOLERANCE, ThermoCheck, ThermoDiscrepancy};
 pub use validation::{ValidationEvent, Validator};
+
+pub use folding_molecule::{Dihedral, SideChainBead};
+pub use folding_physics::{
+    ContactFrustration, CrowderField, CrowdingModel, DielectricModel, EnergyComponents,
+    Environment, FrustrationClass, FrustrationOptions, FrustrationReport, JAMMING_FRACTION,
+};
+pub use folding_physics::nve as physics_nve;
+pub use folding_physics::registry as physics_registry;
+
+/// Cargo features folding-core and the crates below it were compiled with.
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = folding_physics::enabled_features();
+    let own = [
+        ("openmm", cfg!(feature = "openmm")),
+        ("plugins", cfg!(feature = "plugins")),
+        ("proptest", cfg!(feature = "proptest")),
+        ("async", cfg!(feature = "async")),
+    ];
+    for (feature, _) in own.into_iter().filter(|(_, enabled)| *enabled) {
+        if !features.contains(&feature) {
+            features.push(feature);
+        }
+    }
+    features
+}
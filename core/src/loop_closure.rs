@@ -0,0 +1,193 @@
+use crate::simple_rng::SimpleRng;
+
+/// Largest gap (Å) between the moving loop end and its anchor that counts as closed.
+pub const CLOSURE_TOLERANCE: f64 = 0.05;
+
+/// Full passes over the loop's virtual bonds before giving up.
+const MAX_SWEEPS: usize = 500;
+
+/// Result of closing a loop with cyclic coordinate descent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoopClosure {
+    pub sweeps: usize,
+    /// Distance (Å) left between the loop end and its anchor.
+    pub residual: f64,
+    /// Net turn (degrees) about each virtual bond `i`→`i + 1` of the loop.
+    pub torsion_changes: Vec<f64>,
+}
+
+impl LoopClosure {
+    pub fn closed(&self) -> bool {
+        self.residual <= CLOSURE_TOLERANCE
+    }
+
+    /// Sum of the absolute turns, the size of the move in degrees.
+    pub fn total_turn(&self) -> f64 {
+        self.torsion_changes.iter().map(|angle| angle.abs()).sum()
+    }
+}
+
+/// Resamples the Cα loop `points` (anchors included at both ends): every
+/// virtual bond is turned by a random angle within `±perturbation` degrees,
+/// then [`ccd_close`] brings the last point back onto its original position.
+pub fn resample_loop(
+    points: &mut [[f64; 3]],
+    perturbation: f64,
+    rng: &mut SimpleRng,
+) -> LoopClosure {
+    let Some(&target) = points.last() else {
+        return LoopClosure {
+            sweeps: 0,
+            residual: 0.0,
+            torsion_changes: Vec::new(),
+        };
+    };
+    let pivots = points.len().saturating_sub(2);
+    let mut kicks = vec![0.0; pivots];
+    let limit = perturbation.abs();
+    if limit > 0.0 {
+        for (pivot, kick) in kicks.iter_mut().enumerate() {
+            *kick = rng.gen_range(-limit..limit);
+            rotate_about_bond(points, pivot, kick.to_radians());
+        }
+    }
+    let mut closure = ccd_close(points, target, CLOSURE_TOLERANCE, MAX_SWEEPS);
+    for (change, kick) in closure.torsion_changes.iter_mut().zip(kicks) {
+        *change += kick;
+    }
+    closure
+}
+
+/// Cyclic coordinate descent: sweeps the virtual bonds of `points` in order,
+/// each time applying the turn that brings the last point closest to `target`,
+/// until it is within `tolerance` or `max_sweeps` passes have run. The first
+/// two points never move.
+pub fn ccd_close(
+    points: &mut [[f64; 3]],
+    target: [f64; 3],
+    tolerance: f64,
+    max_sweeps: usize,
+) -> LoopClosure {
+    let pivots = points.len().saturating_sub(2);
+    let mut torsion_changes = vec![0.0; pivots];
+    let mut sweeps = 0;
+    let mut residual = gap(points, target);
+    while residual > tolerance && sweeps < max_sweeps && pivots > 0 {
+        sweeps += 1;
+        for (pivot, change) in torsion_changes.iter_mut().enumerate() {
+            let angle = optimal_turn(points, pivot, target);
+            rotate_about_bond(points, pivot, angle);
+            *change += angle.to_degrees();
+        }
+        residual = gap(points, target);
+    }
+    LoopClosure {
+        sweeps,
+        residual,
+        torsion_changes,
+    }
+}
+
+/// Turns every point after `pivot + 1` by `angle` radians about the
+/// `pivot`→`pivot + 1` virtual bond.
+pub fn rotate_about_bond(points: &mut [[f64; 3]], pivot: usize, angle: f64) {
+    if pivot + 2 >= points.len() {
+        return;
+    }
+    let origin = points[pivot];
+    let Some(k) = unit(sub(points[pivot + 1], origin)) else {
+        return;
+    };
+    let (sin, cos) = angle.sin_cos();
+    for point in &mut points[pivot + 2..] {
+        let v = sub(*point, origin);
+        let k_cross_v = cross(k, v);
+        let k_dot_v = dot(k, v);
+        for axis in 0..3 {
+            point[axis] = origin[axis]
+                + v[axis] * cos
+                + k_cross_v[axis] * sin
+                + k[axis] * k_dot_v * (1.0 - cos);
+        }
+    }
+}
+
+/// Turn about bond `pivot` that minimises the distance from the last point to `target`.
+fn optimal_turn(points: &[[f64; 3]], pivot: usize, target: [f64; 3]) -> f64 {
+    let origin = points[pivot];
+    let Some(k) = unit(sub(points[pivot + 1], origin)) else {
+        return 0.0;
+    };
+    let reject = |v: [f64; 3]| {
+        let along = dot(k, v);
+        [
+            v[0] - k[0] * along,
+            v[1] - k[1] * along,
+            v[2] - k[2] * along,
+        ]
+    };
+    let moving = reject(sub(points[points.len() - 1], origin));
+    let goal = reject(sub(target, origin));
+    dot(k, cross(moving, goal)).atan2(dot(moving, goal))
+}
+
+fn gap(points: &[[f64; 3]], target: [f64; 3]) -> f64 {
+    points
+        .last()
+        .map(|last| dot(sub(*last, target), sub(*last, target)).sqrt())
+        .unwrap_or(0.0)
+}
+
+fn unit(v: [f64; 3]) -> Option<[f64; 3]> {
+    let length = dot(v, v).sqrt();
+    (length > f64::EPSILON).then(|| [v[0] / length, v[1] / length, v[2] / length])
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resampled_loop_closes_on_its_anchors_and_keeps_bonds() {
+        let original: Vec<[f64; 3]> = (0..8)
+            .map(|i| {
+                let angle = i as f64 * 0.9;
+                [2.3 * angle.cos(), 2.3 * angle.sin(), i as f64 * 1.5]
+            })
+            .collect();
+        let bond = |points: &[[f64; 3]], i: usize| gap(&points[..=i], points[i + 1]);
+        let mut points = original.clone();
+        let closure = resample_loop(&mut points, 40.0, &mut SimpleRng::seed_from_u64(11));
+
+        assert!(closure.closed(), "residual {}", closure.residual);
+        assert!(closure.total_turn() > 0.0);
+        assert_eq!(points[0], original[0]);
+        assert_eq!(points[1], original[1]);
+        assert!(gap(&points, original[7]) <= CLOSURE_TOLERANCE);
+        for i in 0..7 {
+            assert!((bond(&points, i) - bond(&original, i)).abs() < 1e-9);
+        }
+        assert!(
+            points[3..7]
+                .iter()
+                .zip(&original[3..7])
+                .any(|(moved, start)| gap(&[*moved], *start) > 1e-3)
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Memory accounting behind `--mem-report` and the engine's memory cap
+//! ([`FoldingEngineBuilder::with_memory_cap`](crate::FoldingEngineBuilder::with_memory_cap)).
+//! Subsystem sizes are estimates from allocated capacity; peak RSS comes from
+//! the operating system.
+
+use std::mem::size_of;
+
+/// Heap a physics span allocates per residue: forces, velocities and
+/// previous positions, plus a mass.
+pub const FORCE_BYTES_PER_RESIDUE: usize = 3 * size_of::<[f64; 3]>() + size_of::<f64>();
+
+/// Estimated heap held by a run's data, in bytes, per subsystem.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The chain being folded.
+    pub chain: usize,
+    /// Span records: the trajectory, ghost spans and reported outcomes.
+    pub trajectory: usize,
+    /// Cα frames kept for the report.
+    pub frames: usize,
+    /// `commit` snapshots still on the stack.
+    pub checkpoints: usize,
+    /// Force, velocity and mass buffers of a physics span; zero when every
+    /// span stayed at the toy level.
+    pub forces: usize,
+    /// Crowder centres the excluded-volume term scans. Pair terms are
+    /// evaluated on the fly, so no neighbour lists are kept.
+    pub crowders: usize,
+}
+
+impl MemoryUsage {
+    pub fn total(&self) -> usize {
+        self.chain + self.trajectory + self.frames + self.checkpoints + self.forces + self.crowders
+    }
+
+    /// Subsystem names and sizes, in report order.
+    pub fn entries(&self) -> [(&'static str, usize); 6] {
+        [
+            ("chain", self.chain),
+            ("trajectory", self.trajectory),
+            ("frames", self.frames),
+            ("checkpoints", self.checkpoints),
+            ("forces", self.forces),
+            ("crowders", self.crowders),
+        ]
+    }
+}
+
+/// Peak resident set size of this process (`VmHWM`), where the OS reports it.
+pub fn peak_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_hwm(&status)
+}
+
+fn parse_vm_hwm(status: &str) -> Option<u64> {
+    let line = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+    let kib: u64 = line.trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_peak_rss_from_proc_status() {
+        let status = "Name:\tfolding-app\nVmPeak:\t  20480 kB\nVmHWM:\t    1536 kB\n";
+        assert_eq!(parse_vm_hwm(status), Some(1536 * 1024));
+        assert_eq!(parse_vm_hwm("Name:\tfolding-app\n"), None);
+    }
+}
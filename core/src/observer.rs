@@ -0,0 +1,36 @@
+use crate::folding_ruleset::ViolationRecord;
+use crate::rotation_solver::RotationOutcome;
+
+/// Whether the engine should keep executing the contract.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ObserverControl {
+    #[default]
+    Continue,
+    Stop,
+}
+
+/// Callbacks fired while a contract executes, registered with
+/// `FoldingEngineBuilder::with_observer`. Every method defaults to a no-op;
+/// share state with the caller through `Arc<Mutex<_>>` or channels.
+pub trait EngineObserver: Send {
+    /// A span was accepted and applied to the chain (ghost spans are not).
+    fn on_span_applied(&mut self, _step: usize, _outcome: &RotationOutcome) {}
+
+    /// A violation was recorded, after any recovery policy ran.
+    fn on_rejection(&mut self, _record: &ViolationRecord) {}
+
+    /// Before each move, with the temperature (K) it runs at.
+    fn on_schedule_step(&mut self, _step: usize, _temperature: f64) {}
+
+    /// Total potential energy after each applied span; `Stop` ends the
+    /// contract after this span.
+    fn on_energy(&mut self, _step: usize, _potential_energy: f64) -> ObserverControl {
+        ObserverControl::Continue
+    }
+
+    /// Before each move is attempted; `Stop` ends the contract without it,
+    /// e.g. when the caller cancelled the run.
+    fn before_move(&mut self, _step: usize) -> ObserverControl {
+        ObserverControl::Continue
+    }
+}
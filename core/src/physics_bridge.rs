@@ -1,7 +1,13 @@
 use crate::folding_parser::PhysicsLevel;
 use crate::rotation_solver::{RotationCommand, RotationOutcome};
 use folding_molecule::PeptideChain;
-use folding_physics::native_bridge::{NativePhysicsBridge, PhysicsRequest as PhysicsPhysicsRequest, RotationOutcome as PhysicsRotationOutcome};
+use folding_physics::native_bridge::NativePhysicsBridge;
+use folding_physics::{
+    CrowderField, EnergyComponents, Environment, FrustrationOptions, FrustrationReport,
+};
+use std::path::{Path, PathBuf};
+
+pub use native_bridge::{NativeBridges, NativeVelocities};
 
 /// Request passed to the physics backend bridge.
 pub struct PhysicsRequest<'a> {
@@ -9,6 +15,65 @@ pub struct PhysicsRequest<'a> {
     pub command: RotationCommand,
     pub level: PhysicsLevel,
     pub temperature: f64,
+    pub environment: &'a Environment,
+    /// Explicit crowders placed for the run, if the environment uses them.
+    pub crowders: Option<&'a CrowderField>,
+    /// Registered force field and integrator replacing the level's defaults.
+    pub force_field: Option<&'a str>,
+    pub integrator: Option<&'a str>,
+    /// MD timestep in ps; `None` leaves the backend's per-level default.
+    pub timestep_ps: Option<f64>,
+    /// Where OpenMM bridge responses are cached, one file per span.
+    pub cache: Option<PhysicsCache<'a>>,
+    /// Where the native backend writes each span's MD frames.
+    pub trajectories: Option<TrajectoryOutput<'a>>,
+    /// Seed for the integrator's random stream, drawn from the engine RNG so
+    /// its state determines the physics noise too.
+    pub seed: u64,
+    /// Residues the contract froze; the native backend holds them in place,
+    /// the OpenMM bridge refuses spans that have any.
+    pub frozen: &'a [usize],
+}
+
+/// Output directory and the engine step of the span being run. In a cache
+/// directory each span keeps the OpenMM bridge response as `<key>.json`,
+/// reused when the same span is run again with the same input.
+#[derive(Clone, Copy, Debug)]
+pub struct PhysicsCache<'a> {
+    pub dir: &'a Path,
+    pub step: usize,
+}
+
+impl PhysicsCache<'_> {
+    /// `00012-residue-3`: unique per span even when span labels repeat.
+    pub fn key(&self, label: &str) -> String {
+        span_key(self.step, label)
+    }
+}
+
+/// Output directory of the native backend's MD frames and the engine step
+/// of the span being run; each span writes `<key>.json`, keyed like
+/// [`PhysicsCache::key`].
+#[derive(Clone, Copy, Debug)]
+pub struct TrajectoryOutput<'a> {
+    pub dir: &'a Path,
+    pub step: usize,
+}
+
+impl TrajectoryOutput<'_> {
+    /// File the span labelled `label` writes its frames to.
+    pub fn path(&self, label: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", span_key(self.step, label)))
+    }
+}
+
+fn span_key(step: usize, label: &str) -> String {
+    let label: String = label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{step:05}-{label}")
 }
 
 /// Diagnostics captured when a physics backend services a span.
@@ -20,17 +85,182 @@ pub struct PhysicsSpanMetrics {
     pub kinetic_energy: f64,
     pub temperature: f64,
     pub simulation_time_ps: f64,
+    /// ΔE (kcal/mol) the backend reported for the span, if it reports one.
+    pub delta_energy: Option<f64>,
+    /// Backend that serviced the span: `openmm` or `native`.
+    pub backend: &'static str,
+    /// MD frames written for the span, when trajectory output is on.
     pub trajectory_path: Option<String>,
+    /// Cached OpenMM bridge response of the span, when caching is on.
+    pub cache_path: Option<String>,
 }
 
 /// Physics engine backend selection
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PhysicsEngine {
     OpenMM,
     Native,
     Auto, // Try OpenMM first, fallback to Native
 }
 
+impl PhysicsEngine {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PhysicsEngine::OpenMM => "openmm",
+            PhysicsEngine::Native => "native",
+            PhysicsEngine::Auto => "auto",
+        }
+    }
+
+    /// Accepts the same spellings as the `physics_engine` directive.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "openmm" => Some(PhysicsEngine::OpenMM),
+            "native" => Some(PhysicsEngine::Native),
+            "auto" => Some(PhysicsEngine::Auto),
+            _ => None,
+        }
+    }
+
+    /// Backends this selection can reach in the current build.
+    pub fn backend_label(&self) -> &'static str {
+        match self {
+            PhysicsEngine::OpenMM => "openmm",
+            PhysicsEngine::Native => "native",
+            PhysicsEngine::Auto if cfg!(feature = "openmm") => "auto(openmm,native)",
+            PhysicsEngine::Auto => "auto(native)",
+        }
+    }
+}
+
+/// Cα positions and diagnostics after a `simulate` burst.
+#[derive(Clone, Debug)]
+pub struct MdBurst {
+    pub positions: Vec<[f64; 3]>,
+    pub metrics: PhysicsSpanMetrics,
+}
+
+/// Integrates the chain for `picoseconds` of simulated time at
+/// `request.level` on the native backend. Unlike a physics span, whose
+/// diagnostics annotate a rotation, the burst's final positions are returned
+/// for the engine to adopt.
+pub fn simulate_native(
+    request: PhysicsRequest<'_>,
+    picoseconds: f64,
+    bridges: &mut NativeBridges,
+) -> Result<MdBurst, String> {
+    native_bridge::simulate(request, picoseconds, bridges)
+}
+
+/// Native bridge splitting a chain's energy by term, kept across samples
+/// while the level, environment and force field stay the same.
+#[derive(Default)]
+pub struct ComponentBridge {
+    cached: Option<KeyedBridge>,
+}
+
+struct KeyedBridge {
+    level: PhysicsLevel,
+    environment: Environment,
+    force_field: Option<String>,
+    bridge: NativePhysicsBridge,
+}
+
+impl ComponentBridge {
+    /// Energy of `chain` split by term under the force field a native span
+    /// at `level` would use, or the registered `force_field` replacing it,
+    /// with the same energy attributed to each residue (empty when the force
+    /// field cannot attribute it).
+    pub fn energy_components(
+        &mut self,
+        chain: &PeptideChain,
+        level: PhysicsLevel,
+        environment: &Environment,
+        force_field: Option<&str>,
+    ) -> Result<(EnergyComponents, Vec<f64>), String> {
+        let keyed = match self.cached.take() {
+            Some(keyed)
+                if keyed.level == level
+                    && keyed.environment == *environment
+                    && keyed.force_field.as_deref() == force_field =>
+            {
+                keyed
+            }
+            _ => KeyedBridge {
+                level,
+                environment: environment.clone(),
+                force_field: force_field.map(str::to_string),
+                bridge: NativePhysicsBridge::with_environment(level, environment)
+                    .with_components(force_field, None)?,
+            },
+        };
+        let bridge = &self.cached.insert(keyed).bridge;
+        let residues = bridge.residue_energies(chain).unwrap_or_default();
+        Ok((bridge.energy_components(chain), residues))
+    }
+}
+
+/// Frustration of `chain`'s contacts under the force field a native span at
+/// `level` would use, or the registered `force_field` replacing it; `None`
+/// when that force field has no pairwise nonbonded terms.
+pub fn frustration(
+    chain: &PeptideChain,
+    level: PhysicsLevel,
+    environment: &Environment,
+    force_field: Option<&str>,
+    options: &FrustrationOptions,
+) -> Result<Option<FrustrationReport>, String> {
+    let bridge = NativePhysicsBridge::with_environment(level, environment)
+        .with_components(force_field, None)?;
+    Ok(bridge.frustration(chain, options))
+}
+
+/// Fails when `timestep` (ps) is not positive or exceeds the stability limit
+/// of the force field a native span at `level` would use, or of the
+/// registered `force_field` replacing it.
+pub fn check_md_timestep(
+    level: PhysicsLevel,
+    force_field: Option<&str>,
+    timestep: f64,
+) -> Result<(), String> {
+    NativePhysicsBridge::new(level)
+        .with_components(force_field, None)?
+        .check_timestep(timestep)
+}
+
+/// Lines of a bridge's standard error kept with a failure; tracebacks end
+/// with the error itself.
+pub const STDERR_TAIL_LINES: usize = 20;
+
+/// Why a backend could not service a span.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackendFailure {
+    /// `openmm` or `native`.
+    pub backend: &'static str,
+    pub reason: String,
+    /// Last [`STDERR_TAIL_LINES`] lines the bridge process wrote to stderr;
+    /// empty for the in-process native backend.
+    pub stderr: String,
+}
+
+impl BackendFailure {
+    pub fn new(backend: &'static str, reason: impl Into<String>) -> Self {
+        Self {
+            backend,
+            reason: reason.into(),
+            stderr: String::new(),
+        }
+    }
+}
+
+/// Outcome of offering a span to the selected backends, with every backend
+/// that failed on the way.
+#[derive(Clone, Debug)]
+pub struct PhysicsAttempt {
+    pub outcome: Option<RotationOutcome>,
+    pub failures: Vec<BackendFailure>,
+}
+
 /// Attempt to execute a physics-backed step. Returns `None` when no physics
 /// engine is available or the request cannot be satisfied.
 pub fn run_physics_step(request: PhysicsRequest<'_>) -> Option<RotationOutcome> {
@@ -39,42 +269,57 @@ pub fn run_physics_step(request: PhysicsRequest<'_>) -> Option<RotationOutcome>
 
 /// Execute a physics step with a specific engine
 pub fn run_physics_step_with_engine(request: PhysicsRequest<'_>, engine: PhysicsEngine) -> Option<RotationOutcome> {
-    match engine {
-        PhysicsEngine::OpenMM => {
-            #[cfg(feature = "openmm")]
-            {
-                openmm_bridge::run(request)
-            }
-            #[cfg(not(feature = "openmm"))]
-            {
-                let _ = request;
-                None
-            }
-        }
-        PhysicsEngine::Native => {
-            native_bridge::run(request)
-        }
-        PhysicsEngine::Auto => {
-            // Try OpenMM first, fallback to native
-            #[cfg(feature = "openmm")]
-            {
-                openmm_bridge::run(request).or_else(|| native_bridge::run(request))
-            }
-            #[cfg(not(feature = "openmm"))]
-            {
-                native_bridge::run(request)
-            }
+    attempt_physics_step(request, engine, &mut NativeBridges::new()).outcome
+}
+
+/// Like [`run_physics_step_with_engine`], but reports why each backend it
+/// tried failed; `Auto` falls back from OpenMM to native. The native backend
+/// reuses the bridge `bridges` keeps for the request's level.
+pub fn attempt_physics_step(
+    request: PhysicsRequest<'_>,
+    engine: PhysicsEngine,
+    bridges: &mut NativeBridges,
+) -> PhysicsAttempt {
+    let mut failures = Vec::new();
+    let mut attempt = |result: Result<RotationOutcome, BackendFailure>| match result {
+        Ok(outcome) => Some(outcome),
+        Err(failure) => {
+            failures.push(failure);
+            None
         }
+    };
+    let outcome = match engine {
+        PhysicsEngine::OpenMM => attempt(run_openmm(&request)),
+        PhysicsEngine::Native => attempt(native_bridge::run(&request, bridges)),
+        PhysicsEngine::Auto if cfg!(feature = "openmm") => attempt(run_openmm(&request))
+            .or_else(|| attempt(native_bridge::run(&request, bridges))),
+        PhysicsEngine::Auto => attempt(native_bridge::run(&request, bridges)),
+    };
+    PhysicsAttempt { outcome, failures }
+}
+
+fn run_openmm(request: &PhysicsRequest<'_>) -> Result<RotationOutcome, BackendFailure> {
+    #[cfg(feature = "openmm")]
+    {
+        openmm_bridge::run(request)
+    }
+    #[cfg(not(feature = "openmm"))]
+    {
+        let _ = request;
+        Err(BackendFailure::new("openmm", "this build has no OpenMM bridge"))
     }
 }
 
 #[cfg(feature = "openmm")]
 mod openmm_bridge {
-    use super::PhysicsRequest;
+    use super::{BackendFailure, PhysicsCache, PhysicsRequest, STDERR_TAIL_LINES};
     use crate::rotation_solver::RotationOutcome;
     use folding_time::trajectory::SpanRecord;
     use serde::{Deserialize, Serialize};
-    use std::path::PathBuf;
+    use std::collections::hash_map::DefaultHasher;
+    use std::fs;
+    use std::hash::{Hash, Hasher};
+    use std::path::{Path, PathBuf};
     use std::process::{Command, Stdio};
     use std::time::Duration;
 
@@ -87,6 +332,7 @@ mod openmm_bridge {
     #[derive(Serialize)]
     struct SerializedCommand {
         residue: usize,
+        dihedral: &'static str,
         angle_degrees: f64,
         duration_ms: u64,
         label: Option<String>,
@@ -98,9 +344,15 @@ mod openmm_bridge {
         temperature: f64,
         residues: Vec<SerializedResidue>,
         command: SerializedCommand,
+        /// For `LangevinIntegrator.setRandomNumberSeed`; OpenMM treats 0 as
+        /// "pick one", so the engine's seed is mapped into 1..=i32::MAX.
+        seed: i32,
+        /// Integration timestep override; the bridge keeps its own otherwise.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timestep_ps: Option<f64>,
     }
 
-    #[derive(Deserialize)]
+    #[derive(Serialize, Deserialize)]
     struct BridgeResponse {
         applied_angle: f64,
         delta_entropy: f64,
@@ -117,7 +369,44 @@ mod openmm_bridge {
         trajectory_path: Option<String>,
     }
 
-    pub fn run(request: PhysicsRequest<'_>) -> Option<RotationOutcome> {
+    /// Response stored for a span; reused when the same span is requested
+    /// again with the same input.
+    #[derive(Serialize, Deserialize)]
+    struct CachedSpan {
+        input_hash: u64,
+        response: BridgeResponse,
+    }
+
+    /// Cache file of one span under the cache directory.
+    struct CacheFile(PathBuf);
+
+    impl CacheFile {
+        fn new(cache: PhysicsCache<'_>, label: &str) -> Self {
+            Self(cache.dir.join(format!("{}.json", cache.key(label))))
+        }
+
+        fn cached(&self, input_hash: u64) -> Option<BridgeResponse> {
+            let cached: CachedSpan = serde_json::from_slice(&fs::read(&self.0).ok()?).ok()?;
+            (cached.input_hash == input_hash).then_some(cached.response)
+        }
+
+        /// Stores `response`; `false` when the file could not be written.
+        fn store(&self, input_hash: u64, response: BridgeResponse) -> (BridgeResponse, bool) {
+            let cached = CachedSpan { input_hash, response };
+            let stored = serde_json::to_vec_pretty(&cached)
+                .is_ok_and(|json| fs::write(&self.0, json).is_ok());
+            (cached.response, stored)
+        }
+    }
+
+    pub fn run(request: &PhysicsRequest<'_>) -> Result<RotationOutcome, BackendFailure> {
+        // The bridge script has no way to pin particles; leave frozen spans
+        // to a backend that holds them
+        if !request.frozen.is_empty() {
+            return Err(failure(
+                "the OpenMM bridge cannot hold frozen residues".to_string(),
+            ));
+        }
         let python = std::env::var("PYTHON_OPENMM_BIN").unwrap_or_else(|_| "python3".to_string());
         let script_path = openmm_script_path();
         let label = request
@@ -129,7 +418,6 @@ mod openmm_bridge {
         let residues: Vec<SerializedResidue> = request
             .chain
             .residues()
-            .iter()
             .map(|res| SerializedResidue {
                 index: res.id.0,
                 position: res.position(),
@@ -137,37 +425,102 @@ mod openmm_bridge {
             .collect();
 
         let payload = BridgeRequest {
-            level: format_level(request.level),
+            level: request.level.as_str().to_string(),
             temperature: request.temperature,
             residues,
             command: SerializedCommand {
                 residue: request.command.residue.0,
+                dihedral: request.command.dihedral.as_str(),
                 angle_degrees: request.command.angle_degrees,
                 duration_ms: request.command.duration.as_millis() as u64,
                 label: Some(label.clone()),
             },
+            seed: (request.seed % i32::MAX as u64) as i32 + 1,
+            timestep_ps: request.timestep_ps,
         };
 
-        let mut child = Command::new(python)
-            .arg(script_path)
+        let file = request.cache.and_then(|cache| {
+            fs::create_dir_all(cache.dir).ok()?;
+            Some(CacheFile::new(cache, &label))
+        });
+        let input_hash = {
+            let mut hasher = DefaultHasher::new();
+            serde_json::to_string(&payload)
+                .map_err(|err| failure(format!("failed to encode request: {err}")))?
+                .hash(&mut hasher);
+            hasher.finish()
+        };
+        if let Some(file) = &file
+            && let Some(response) = file.cached(input_hash)
+        {
+            return Ok(outcome(request, label, response, Some(&file.0)));
+        }
+
+        let mut child = Command::new(&python)
+            .arg(&script_path)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::null())
+            .stderr(Stdio::piped())
             .spawn()
-            .ok()?;
+            .map_err(|err| failure(format!("failed to start {python}: {err}")))?;
+
+        // Dropping stdin closes it, so the bridge sees the end of its input.
+        let written = child
+            .stdin
+            .take()
+            .map(|stdin| serde_json::to_writer(stdin, &payload));
+        let output = child
+            .wait_with_output()
+            .map_err(|err| failure(format!("bridge did not finish: {err}")))?;
+        let stderr = stderr_tail(&output.stderr);
+        if !output.status.success() {
+            return Err(BackendFailure {
+                stderr,
+                ..failure(format!("{} exited with {}", script_path.display(), output.status))
+            });
+        }
+        if let Some(Err(err)) = written {
+            return Err(BackendFailure {
+                stderr,
+                ..failure(format!("failed to send request: {err}"))
+            });
+        }
 
-        if let Some(stdin) = child.stdin.as_mut() {
-            if serde_json::to_writer(stdin, &payload).is_err() {
-                return None;
+        let response: BridgeResponse = serde_json::from_slice(&output.stdout).map_err(|err| {
+            BackendFailure {
+                stderr,
+                ..failure(format!("invalid bridge response: {err}"))
             }
+        })?;
+        match &file {
+            Some(file) => {
+                let (response, stored) = file.store(input_hash, response);
+                Ok(outcome(request, label, response, stored.then_some(file.0.as_path())))
+            }
+            None => Ok(outcome(request, label, response, None)),
         }
+    }
 
-        let output = child.wait_with_output().ok()?;
-        if !output.status.success() {
-            return None;
+    fn failure(reason: String) -> BackendFailure {
+        BackendFailure {
+            backend: "openmm",
+            reason,
+            stderr: String::new(),
         }
+    }
 
-        let response: BridgeResponse = serde_json::from_slice(&output.stdout).ok()?;
+    fn stderr_tail(stderr: &[u8]) -> String {
+        let stderr = String::from_utf8_lossy(stderr);
+        let lines: Vec<&str> = stderr.trim_end().lines().collect();
+        lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n")
+    }
+
+    fn outcome(
+        request: &PhysicsRequest<'_>,
+        label: String,
+        response: BridgeResponse,
+        cache: Option<&Path>,
+    ) -> RotationOutcome {
         let duration_ms = response
             .duration_ms
             .unwrap_or_else(|| request.command.duration.as_millis() as u64);
@@ -181,7 +534,9 @@ mod openmm_bridge {
         span.delta_energy = response.delta_energy.unwrap_or(0.0);
         span.gibbs_energy = response.gibbs_energy.unwrap_or(0.0);
 
-        Some(RotationOutcome {
+        RotationOutcome {
+            residue: request.command.residue,
+            dihedral: request.command.dihedral,
             applied_angle: response.applied_angle,
             span_record: span,
             ghost: false,
@@ -192,19 +547,13 @@ mod openmm_bridge {
                 kinetic_energy: response.kinetic_energy.unwrap_or_default(),
                 temperature: response.temperature.unwrap_or(request.temperature),
                 simulation_time_ps: response.simulation_time_ps.unwrap_or(0.0),
+                delta_energy: response.delta_energy,
+                backend: "openmm",
                 trajectory_path: response.trajectory_path,
+                cache_path: cache.map(|path| path.display().to_string()),
             }),
-        })
-    }
-
-    fn format_level(level: super::PhysicsLevel) -> String {
-        match level {
-            super::PhysicsLevel::Toy => "toy",
-            super::PhysicsLevel::Coarse => "coarse",
-            super::PhysicsLevel::Gb => "gb",
-            super::PhysicsLevel::Full => "full",
+            collective_variables: Vec::new(),
         }
-        .to_string()
     }
 
     fn openmm_script_path() -> PathBuf {
@@ -220,9 +569,17 @@ mod openmm_bridge {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use folding_molecule::{PeptideChain, Residue, ResidueId};
+    use folding_molecule::{Dihedral, PeptideChain, Residue, ResidueId};
     use crate::rotation_solver::RotationCommand;
 
+    #[test]
+    fn cache_keys_are_unique_per_step() {
+        let dir = std::path::Path::new("/tmp/openmm-cache");
+        let key = |step| PhysicsCache { dir, step }.key("helix push/1");
+        assert_eq!(key(12), "00012-helix_push_1");
+        assert_ne!(key(12), key(13));
+    }
+
     #[test]
     fn test_native_physics_integration() {
         // Create a simple test chain
@@ -235,9 +592,10 @@ mod tests {
         // Create a simple rotation command
         let command = RotationCommand {
             residue: ResidueId(0),
+            dihedral: Dihedral::Phi,
             angle_degrees: 10.0,
             label: Some("test_rotation".to_string()),
-            duration: std::time::Duration::from_millis(1),
+            duration: std::time::Duration::from_millis(3),
         };
 
         // Create physics request
@@ -246,6 +604,15 @@ mod tests {
             command,
             level: PhysicsLevel::Toy,
             temperature: 300.0,
+            environment: &folding_physics::Environment::default(),
+            crowders: None,
+            force_field: None,
+            integrator: Some("brownian"),
+            timestep_ps: None,
+            cache: None,
+            trajectories: None,
+            seed: 1,
+            frozen: &[],
         };
 
         // Test native physics engine
@@ -255,97 +622,428 @@ mod tests {
         let outcome = result.unwrap();
         assert!(!outcome.ghost);
         assert!(outcome.physics_metrics.is_some());
+        // The span reports the commanded move, not a placeholder
+        assert_eq!(outcome.applied_angle, 10.0);
+        let span = &outcome.span_record;
+        assert_eq!(span.id, "test_rotation");
+        assert_eq!(span.duration, std::time::Duration::from_millis(3));
+        assert_eq!(span.delta_theta, 10.0);
+        assert!(span.delta_entropy.is_finite());
+        let bits_per_entropy = span.delta_information * 0.001987 * std::f64::consts::LN_2;
+        assert!((bits_per_entropy - span.delta_entropy).abs() < 1e-9);
         
         let metrics = outcome.physics_metrics.unwrap();
         assert!(metrics.rmsd >= 0.0);
         assert!(metrics.radius_of_gyration >= 0.0);
         assert!(metrics.simulation_time_ps > 0.0);
     }
+
+    #[test]
+    fn native_bridges_carry_state_across_spans() {
+        let residues = (0..4)
+            .map(|i| Residue::new(ResidueId(i), "ALA", [3.8 * i as f64, 0.0, 0.0]))
+            .collect();
+        let chain = PeptideChain::new(residues);
+        let environment = folding_physics::Environment::default();
+        let request = |level| PhysicsRequest {
+            chain: &chain,
+            command: RotationCommand {
+                residue: ResidueId(1),
+                dihedral: Dihedral::Psi,
+                angle_degrees: 30.0,
+                label: None,
+                duration: std::time::Duration::from_millis(1),
+            },
+            level,
+            temperature: 300.0,
+            environment: &environment,
+            crowders: None,
+            force_field: None,
+            integrator: None,
+            timestep_ps: None,
+            cache: None,
+            trajectories: None,
+            seed: 3,
+            frozen: &[],
+        };
+        let kinetic = |bridges: &mut NativeBridges| {
+            let engine = PhysicsEngine::Native;
+            let attempt = attempt_physics_step(request(PhysicsLevel::Toy), engine, bridges);
+            attempt.outcome.unwrap().physics_metrics.unwrap().kinetic_energy
+        };
+
+        let mut bridges = NativeBridges::new();
+        let first = kinetic(&mut bridges);
+        let second = kinetic(&mut bridges);
+        assert_eq!(bridges.len(), 1);
+        // A fresh bridge repeats the first span; the kept one starts moving
+        assert_eq!(kinetic(&mut NativeBridges::new()), first);
+        assert_ne!(second, first);
+
+        attempt_physics_step(request(PhysicsLevel::Coarse), PhysicsEngine::Native, &mut bridges);
+        assert_eq!(bridges.len(), 2);
+    }
+
+    #[test]
+    fn restored_velocities_replay_the_next_span() {
+        let residues = (0..4)
+            .map(|i| Residue::new(ResidueId(i), "ALA", [3.8 * i as f64, 0.0, 0.0]))
+            .collect();
+        let chain = PeptideChain::new(residues);
+        let water = folding_physics::Environment::default();
+        let salty = water.clone().with_ionic_strength(0.5);
+        let kinetic = |bridges: &mut NativeBridges, level, environment| {
+            let request = PhysicsRequest {
+                chain: &chain,
+                command: RotationCommand {
+                    residue: ResidueId(1),
+                    dihedral: Dihedral::Psi,
+                    angle_degrees: 30.0,
+                    label: None,
+                    duration: std::time::Duration::from_millis(1),
+                },
+                level,
+                temperature: 300.0,
+                environment,
+                crowders: None,
+                force_field: None,
+                integrator: None,
+                timestep_ps: None,
+                cache: None,
+                trajectories: None,
+                seed: 3,
+                frozen: &[],
+            };
+            let outcome = attempt_physics_step(request, PhysicsEngine::Native, bridges).outcome;
+            outcome.unwrap().physics_metrics.unwrap().kinetic_energy
+        };
+
+        for level in [PhysicsLevel::Toy, PhysicsLevel::Gb] {
+            let mut bridges = NativeBridges::new();
+            kinetic(&mut bridges, level, &water);
+            let velocities = bridges.velocities();
+            assert!(velocities.level(level).is_some_and(|v| v.len() == 4));
+            let next = kinetic(&mut bridges, level, &water);
+            bridges.restore(&velocities);
+            assert_eq!(kinetic(&mut bridges, level, &water), next);
+
+            // A new environment keeps the bridge and the velocities it carries
+            bridges.restore(&velocities);
+            kinetic(&mut bridges, level, &salty);
+            assert_eq!(bridges.len(), 1);
+            assert_ne!(bridges.velocities(), NativeVelocities::default());
+
+            // Velocities restored before the bridge exists wait for it
+            let parsed: NativeVelocities = velocities.to_string().parse().unwrap();
+            assert_eq!(parsed, velocities);
+            let mut resumed = NativeBridges::new();
+            resumed.restore(&parsed);
+            assert_eq!(kinetic(&mut resumed, level, &water), next);
+        }
+        assert!("gb:1,2".parse::<NativeVelocities>().is_err());
+    }
 }
 
 /// Native Rust physics engine implementation using the physics crate
 mod native_bridge {
-    use super::{PhysicsRequest, PhysicsSpanMetrics};
+    use super::{BackendFailure, MdBurst, PhysicsRequest, PhysicsSpanMetrics};
+    use crate::folding_parser::PhysicsLevel;
     use crate::rotation_solver::RotationOutcome;
     use folding_physics::native_bridge::NativePhysicsBridge;
-    use folding_physics::native_bridge::{PhysicsRequest as PhysicsPhysicsRequest};
-    use folding_physics::{PhysicsLevel as PhysicsPhysicsLevel};
-
-    pub fn run(request: PhysicsRequest<'_>) -> Option<RotationOutcome> {
-        // Convert core PhysicsLevel to physics crate PhysicsLevel
-        let physics_level = match request.level {
-            super::PhysicsLevel::Toy => PhysicsPhysicsLevel::Toy,
-            super::PhysicsLevel::Coarse => PhysicsPhysicsLevel::Coarse,
-            super::PhysicsLevel::Gb => PhysicsPhysicsLevel::GB,
-            super::PhysicsLevel::Full => PhysicsPhysicsLevel::Full,
-        };
+    use folding_molecule::Dihedral;
+    use std::collections::{BTreeMap, HashMap};
+    use std::collections::hash_map::Entry;
+    use std::fmt;
+    use std::str::FromStr;
+    use folding_physics::native_bridge::{
+        PhysicsRequest as PhysicsPhysicsRequest, RotationOutcome as NativeOutcome,
+        TorsionRotation,
+    };
+    use folding_time::trajectory::SpanRecord;
 
-        // Create native physics bridge
-        let mut bridge = NativePhysicsBridge::new(physics_level);
+    /// Boltzmann constant in kcal/(mol·K), the unit of span entropy.
+    const BOLTZMANN: f64 = 0.001987;
 
-        // Convert chain to physics request format
-        let initial_positions: Vec<[f64; 3]> = request.chain.residues()
-            .iter()
-            .map(|r| r.position())
-            .collect();
+    /// Native bridges an engine keeps between spans, one per physics level,
+    /// so integrator velocities and force-field state carry over instead of
+    /// being rebuilt for every span. A level's bridge is rebuilt when the
+    /// force field or integrator it was built for changes; a new environment
+    /// only rebuilds its force field.
+    #[derive(Default)]
+    pub struct NativeBridges {
+        bridges: HashMap<PhysicsLevel, CachedBridge>,
+        /// Velocities restored for levels whose bridge is not built yet.
+        pending: BTreeMap<PhysicsLevel, Vec<[f64; 3]>>,
+    }
 
-        let residue_types: Vec<String> = request.chain.residues()
-            .iter()
-            .map(|r| "ALA".to_string()) // Simplified - all residues as ALA
-            .collect();
+    struct CachedBridge {
+        force_field: Option<String>,
+        integrator: Option<String>,
+        bridge: NativePhysicsBridge,
+    }
 
-        // Convert rotation command to rotation commands (simplified)
-        let rotation_commands = vec![(0, 0.1)]; // Default rotation
+    impl CachedBridge {
+        fn matches(&self, request: &PhysicsRequest<'_>) -> bool {
+            self.force_field.as_deref() == request.force_field
+                && self.integrator.as_deref() == request.integrator
+        }
+    }
 
-        let physics_request = PhysicsPhysicsRequest {
-            initial_positions,
-            residue_types,
-            rotation_commands,
-            physics_level,
-            temperature: request.temperature,
-            simulation_time: 1.0, // 1 ps simulation
-        };
+    /// Integrator velocities (Å/ps) of the kept bridges by level: taken before
+    /// a span so a rejected one can be undone, and with every RNG checkpoint
+    /// so a resumed run continues the same dynamics. Written as
+    /// `gb:x,y,z;x,y,z/coarse:…`.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct NativeVelocities(BTreeMap<PhysicsLevel, Vec<[f64; 3]>>);
 
-        // Run physics simulation
-        match bridge.run_physics_simulation(&physics_request) {
-            Ok(outcome) => {
-                let metrics = PhysicsSpanMetrics {
-                    rmsd: outcome.rmsd,
-                    radius_of_gyration: outcome.radius_of_gyration,
-                    potential_energy: outcome.potential_energy,
-                    kinetic_energy: outcome.kinetic_energy,
-                    temperature: outcome.temperature,
-                    simulation_time_ps: outcome.simulation_time * 1000.0, // Convert to ps
-                    trajectory_path: None,
-                };
-
-                // Convert final angles back to (phi, psi) format
-                let final_angles: Vec<(f64, f64)> = outcome.final_angles;
-
-                // Create a proper RotationOutcome compatible with the existing structure
-                use folding_time::trajectory::SpanRecord;
-                use std::time::Duration;
-                let span_record = SpanRecord {
-                    id: "native_physics".to_string(),
-                    delta_entropy: 0.1,
-                    delta_information: 0.05,
-                    duration: Duration::from_millis(1),
-                    delta_theta: 0.1,
-                    delta_energy: outcome.energy,
-                    gibbs_energy: outcome.energy - request.temperature * 0.1 * 0.001,
-                };
-                
-                Some(RotationOutcome {
-                    applied_angle: 0.1, // Default rotation angle
-                    span_record,
-                    ghost: false,
-                    physics_metrics: Some(metrics),
-                })
+    impl NativeVelocities {
+        pub fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        pub fn level(&self, level: PhysicsLevel) -> Option<&[[f64; 3]]> {
+            self.0.get(&level).map(Vec::as_slice)
+        }
+
+        pub fn heap_bytes(&self) -> usize {
+            self.0
+                .values()
+                .map(|velocities| velocities.capacity() * std::mem::size_of::<[f64; 3]>())
+                .sum()
+        }
+    }
+
+    impl fmt::Display for NativeVelocities {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for (index, (level, velocities)) in self.0.iter().enumerate() {
+                if index > 0 {
+                    f.write_str("/")?;
+                }
+                write!(f, "{}:", level.as_str())?;
+                for (bead, [x, y, z]) in velocities.iter().enumerate() {
+                    if bead > 0 {
+                        f.write_str(";")?;
+                    }
+                    write!(f, "{x},{y},{z}")?;
+                }
             }
-            Err(e) => {
-                eprintln!("Native physics simulation failed: {}", e);
-                None
+            Ok(())
+        }
+    }
+
+    impl FromStr for NativeVelocities {
+        type Err = String;
+
+        fn from_str(raw: &str) -> Result<Self, String> {
+            let mut levels = BTreeMap::new();
+            for entry in raw.split('/').filter(|entry| !entry.is_empty()) {
+                let (level, beads) = entry
+                    .split_once(':')
+                    .ok_or_else(|| format!("velocities '{entry}' need a level prefix"))?;
+                let level = PhysicsLevel::parse(level)
+                    .ok_or_else(|| format!("unknown physics level '{level}' in velocities"))?;
+                let velocities = beads
+                    .split(';')
+                    .filter(|bead| !bead.is_empty())
+                    .map(|bead| {
+                        let components = bead
+                            .split(',')
+                            .map(|value| {
+                                value
+                                    .parse::<f64>()
+                                    .map_err(|err| format!("velocity '{bead}': {err}"))
+                            })
+                            .collect::<Result<Vec<f64>, String>>()?;
+                        <[f64; 3]>::try_from(components)
+                            .map_err(|_| format!("velocity '{bead}' needs three components"))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                levels.insert(level, velocities);
             }
+            Ok(Self(levels))
         }
     }
+
+    impl NativeBridges {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Levels with a bridge kept.
+        pub fn len(&self) -> usize {
+            self.bridges.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.bridges.is_empty()
+        }
+
+        /// Velocities every kept bridge would start its next span from.
+        pub fn velocities(&self) -> NativeVelocities {
+            let mut velocities = self.pending.clone();
+            for (level, cached) in &self.bridges {
+                let kept = cached.bridge.velocities();
+                if !kept.is_empty() {
+                    velocities.insert(*level, kept);
+                }
+            }
+            NativeVelocities(velocities)
+        }
+
+        /// Puts back velocities taken with [`Self::velocities`]; bridges
+        /// missing from them start their next span at rest, and levels
+        /// without a bridge yet get theirs once it is built.
+        pub fn restore(&mut self, velocities: &NativeVelocities) {
+            self.pending = velocities.0.clone();
+            for (level, cached) in &mut self.bridges {
+                let restored = self.pending.remove(level).unwrap_or_default();
+                cached.bridge.restore_velocities(&restored);
+            }
+        }
+
+        /// The level's bridge, built on first use or when the request's
+        /// components differ, and set up for this span.
+        fn bridge(
+            &mut self,
+            request: &PhysicsRequest<'_>,
+        ) -> Result<&mut NativePhysicsBridge, String> {
+            let build = || -> Result<CachedBridge, String> {
+                let bridge =
+                    NativePhysicsBridge::with_environment(request.level, request.environment)
+                        .with_components(request.force_field, request.integrator)?;
+                Ok(CachedBridge {
+                    force_field: request.force_field.map(str::to_string),
+                    integrator: request.integrator.map(str::to_string),
+                    bridge,
+                })
+            };
+            let cached = match self.bridges.entry(request.level) {
+                Entry::Occupied(entry) if entry.get().matches(request) => entry.into_mut(),
+                Entry::Occupied(mut entry) => {
+                    entry.insert(build()?);
+                    entry.into_mut()
+                }
+                Entry::Vacant(entry) => {
+                    let cached = entry.insert(build()?);
+                    if let Some(velocities) = self.pending.remove(&request.level) {
+                        cached.bridge.restore_velocities(&velocities);
+                    }
+                    cached
+                }
+            };
+            cached.bridge.set_environment(request.environment)?;
+            cached.bridge.set_crowders(request.crowders.cloned());
+            cached.bridge.set_seed(request.seed);
+            Ok(&mut cached.bridge)
+        }
+    }
+
+    /// The request translated for the physics crate; `simulation_time` is in
+    /// ps and `label` names the span's trajectory file.
+    fn translate(
+        request: &PhysicsRequest<'_>,
+        rotation_commands: Vec<TorsionRotation>,
+        simulation_time: f64,
+        label: &str,
+    ) -> PhysicsPhysicsRequest {
+        PhysicsPhysicsRequest {
+            initial_positions: request.chain.positions().to_vec(),
+            residue_types: request.chain.names().to_vec(),
+            rotation_commands,
+            physics_level: request.level,
+            temperature: request.temperature,
+            simulation_time,
+            timestep: request.timestep_ps,
+            trajectory_path: request.trajectories.map(|output| output.path(label)),
+            frozen: request.frozen.to_vec(),
+            chain_breaks: request.chain.chain_breaks(),
+        }
+    }
+
+    fn metrics(outcome: &NativeOutcome) -> PhysicsSpanMetrics {
+        PhysicsSpanMetrics {
+            rmsd: outcome.rmsd,
+            radius_of_gyration: outcome.radius_of_gyration,
+            potential_energy: outcome.potential_energy,
+            kinetic_energy: outcome.kinetic_energy,
+            temperature: outcome.temperature,
+            simulation_time_ps: outcome.simulated_time_ps,
+            delta_energy: None,
+            backend: "native",
+            trajectory_path: outcome.trajectory_path.clone(),
+            cache_path: None,
+        }
+    }
+
+    pub fn simulate(
+        request: PhysicsRequest<'_>,
+        picoseconds: f64,
+        bridges: &mut NativeBridges,
+    ) -> Result<MdBurst, String> {
+        let label = request.command.label.as_deref().unwrap_or("md");
+        let physics_request = translate(&request, Vec::new(), picoseconds, label);
+        let outcome = bridges.bridge(&request)?.run_physics_simulation(&physics_request)?;
+        Ok(MdBurst {
+            metrics: metrics(&outcome),
+            positions: outcome.final_positions,
+        })
+    }
+
+    /// Turns the commanded torsion and relaxes the chain for 1 ps. ΔS is the
+    /// heat the span exchanges with the thermostat over T — the rotated
+    /// structure's potential energy less the final potential and kinetic
+    /// energy, negative when the span draws heat from the bath — and ΔI is
+    /// the same entropy in bits.
+    pub fn run(
+        request: &PhysicsRequest<'_>,
+        bridges: &mut NativeBridges,
+    ) -> Result<RotationOutcome, BackendFailure> {
+        let command = &request.command;
+        let rotation = TorsionRotation {
+            residue: command.residue.0,
+            dihedral: command.dihedral,
+            angle: command.angle_degrees,
+        };
+        let label = command.label.clone().unwrap_or_else(|| match command.dihedral {
+            Dihedral::Phi => format!("residue-{}", command.residue.0),
+            other => format!("residue-{}-{}", command.residue.0, other.as_str()),
+        });
+        let physics_request = translate(request, vec![rotation], 1.0, &label);
+        let bridge = bridges
+            .bridge(request)
+            .map_err(|e| BackendFailure::new("native", format!("setup failed: {e}")))?;
+        let outcome = bridge
+            .run_physics_simulation(&physics_request)
+            .map_err(|e| BackendFailure::new("native", format!("simulation failed: {e}")))?;
+
+        let delta_energy = outcome.potential_energy - outcome.initial_energy;
+        let metrics = PhysicsSpanMetrics {
+            delta_energy: Some(delta_energy),
+            ..metrics(&outcome)
+        };
+        let heat = outcome.rotated_energy - outcome.energy;
+        let temperature = request.temperature.max(f64::EPSILON);
+        let delta_entropy = heat / temperature;
+        let mut span = SpanRecord::new(
+            label,
+            delta_entropy,
+            delta_entropy / (BOLTZMANN * std::f64::consts::LN_2),
+            command.duration,
+        );
+        span.simulated_ps = metrics.simulation_time_ps;
+        span.delta_theta = command.angle_degrees;
+        span.delta_energy = delta_energy;
+        span.gibbs_energy = outcome.potential_energy - request.temperature * delta_entropy;
+        span.temperature = request.temperature;
+
+        Ok(RotationOutcome {
+            residue: command.residue,
+            dihedral: command.dihedral,
+            applied_angle: command.angle_degrees,
+            span_record: span,
+            ghost: false,
+            physics_metrics: Some(metrics),
+            collective_variables: Vec::new(),
+        })
+    }
 }
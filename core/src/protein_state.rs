@@ -1,4 +1,6 @@
-use folding_molecule::{EnergyModel, PeptideChain, ResidueId};
+use std::ops::Range;
+
+use folding_molecule::{Dihedral, EnergyModel, PeptideChain, ResidueId};
 use folding_time::trajectory::Trajectory;
 
 /// Runtime representation of the protein being folded.
@@ -42,32 +44,53 @@ impl ProteinState {
 
     pub fn energy_state(&self) -> EnergyState {
         let summary = self.energy_model.energy_summary(&self.chain);
-        let kinetic = self
-            .chain
-            .residues()
-            .iter()
-            .map(|residue| residue.phi.abs() + residue.psi.abs())
-            .sum();
+        let phi: f64 = self.chain.phi().iter().map(|angle| angle.abs()).sum();
+        let psi: f64 = self.chain.psi().iter().map(|angle| angle.abs()).sum();
+        let kinetic = phi + psi;
         EnergyState {
             total_potential: summary.total(),
             total_kinetic: kinetic,
         }
     }
 
-    pub fn apply_rotation(&mut self, residue: ResidueId, delta_angle: f64) {
-        if let Some(residue_entry) = self.chain.residue_mut(residue) {
-            residue_entry.phi += delta_angle;
-            let current_position = residue_entry.position();
-            let radius = (current_position[0].powi(2) + current_position[1].powi(2))
-                .sqrt()
-                .max(1.0);
-            let angle_rad = residue_entry.phi.to_radians();
-            let new_position = [
-                radius * angle_rad.cos(),
-                radius * angle_rad.sin(),
-                current_position[2],
-            ];
-            residue_entry.set_position(new_position);
+    /// Turns `dihedral` of `residue` by `delta_angle` degrees; see
+    /// [`PeptideChain::rotate_torsion`].
+    pub fn apply_rotation(&mut self, residue: ResidueId, dihedral: Dihedral, delta_angle: f64) {
+        self.chain.rotate_torsion(residue, dihedral, delta_angle);
+    }
+
+    /// Rotates `range` by `angle` radians about the line through `origin`
+    /// along `axis`. A zero axis leaves the residues untouched.
+    pub fn rotate_residues(
+        &mut self,
+        range: Range<usize>,
+        origin: [f64; 3],
+        axis: [f64; 3],
+        angle: f64,
+    ) {
+        self.chain.rotate_residues(range, origin, axis, angle);
+    }
+
+    /// Mean Cα position of `range`, the origin for rigid domain rotations.
+    pub fn centroid(&self, range: Range<usize>) -> [f64; 3] {
+        let positions = self.chain.positions();
+        let end = range.end.min(positions.len());
+        let members = &positions[range.start.min(end)..end];
+        let mut center = [0.0; 3];
+        for p in members {
+            for axis in 0..3 {
+                center[axis] += p[axis] / members.len() as f64;
+            }
+        }
+        center
+    }
+
+    /// Shifts `range` by `offset` Å.
+    pub fn translate_residues(&mut self, range: Range<usize>, offset: [f64; 3]) {
+        let positions = self.chain.positions_mut();
+        let end = range.end.min(positions.len());
+        for p in &mut positions[range.start.min(end)..end] {
+            *p = [p[0] + offset[0], p[1] + offset[1], p[2] + offset[2]];
         }
     }
 
@@ -83,3 +106,43 @@ impl ProteinState {
         self.trajectory = snapshot.trajectory;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distances(chain: &PeptideChain) -> Vec<f64> {
+        let positions = chain.positions();
+        positions
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| {
+                positions[i + 1..].iter().map(move |b| {
+                    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn psi_swings_downstream_residues_rigidly() {
+        let mut state =
+            ProteinState::new(PeptideChain::from_sequence("AGSVL"), EnergyModel::default());
+        let before = state.chain.clone();
+        state.apply_rotation(ResidueId(1), Dihedral::Psi, 60.0);
+
+        assert_eq!(state.chain.psi()[1], 60.0);
+        assert_eq!(state.chain.positions()[..3], before.positions()[..3]);
+        assert_ne!(state.chain.positions()[3], before.positions()[3]);
+        // Bonds along the rotation axis and across it keep their lengths.
+        let (old, new) = (distances(&before), distances(&state.chain));
+        assert!((old[0] - new[0]).abs() < 1e-9 && (old[4] - new[4]).abs() < 1e-9);
+        assert!(old.iter().zip(&new).any(|(a, b)| (a - b).abs() > 1e-3));
+
+        let snapshot = state.chain.clone();
+        state.apply_rotation(ResidueId(4), Dihedral::Chi2, 30.0);
+        let residue = state.chain.residue(ResidueId(4)).unwrap();
+        assert_eq!(residue.torsion(Dihedral::Chi2), 30.0);
+        assert_eq!(distances(&snapshot), distances(&state.chain));
+    }
+}
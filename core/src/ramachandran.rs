@@ -0,0 +1,106 @@
+/// Which Ramachandran map a residue is judged against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RamachandranMap {
+    General,
+    /// No side chain: both halves of the plot, mirrored through the origin.
+    Glycine,
+    /// The pyrrolidine ring pins phi near -65°.
+    Proline,
+}
+
+/// Allowed (phi, psi) boxes in degrees, inclusive.
+type Region = ((f64, f64), (f64, f64));
+
+const GENERAL_REGIONS: [Region; 5] = [
+    // β sheet and polyproline II, plus the strip across psi = ±180
+    ((-180.0, -45.0), (90.0, 180.0)),
+    ((-180.0, -45.0), (-180.0, -150.0)),
+    // Right-handed α helix and the bridge above it
+    ((-160.0, -30.0), (-80.0, 50.0)),
+    // Left-handed α helix
+    ((40.0, 90.0), (0.0, 90.0)),
+    // Rare but populated ε corner
+    ((50.0, 90.0), (-180.0, -150.0)),
+];
+
+const PROLINE_REGIONS: [Region; 3] = [
+    ((-95.0, -40.0), (-60.0, 20.0)),
+    ((-95.0, -40.0), (100.0, 180.0)),
+    ((-95.0, -40.0), (-180.0, -170.0)),
+];
+
+impl RamachandranMap {
+    /// Map for a three-letter residue name.
+    pub fn for_residue(name: &str) -> Self {
+        match name {
+            "GLY" => RamachandranMap::Glycine,
+            "PRO" => RamachandranMap::Proline,
+            _ => RamachandranMap::General,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RamachandranMap::General => "general",
+            RamachandranMap::Glycine => "glycine",
+            RamachandranMap::Proline => "proline",
+        }
+    }
+
+    /// Angular distance in degrees from (phi, psi) to the nearest allowed
+    /// region; 0 inside one.
+    pub fn deviation(&self, phi: f64, psi: f64) -> f64 {
+        let nearest = |regions: &[Region], sign: f64| {
+            regions
+                .iter()
+                .map(|(phi_range, psi_range)| {
+                    let d_phi = outside(sign * phi, *phi_range);
+                    let d_psi = outside(sign * psi, *psi_range);
+                    d_phi.hypot(d_psi)
+                })
+                .fold(f64::INFINITY, f64::min)
+        };
+        match self {
+            RamachandranMap::General => nearest(&GENERAL_REGIONS, 1.0),
+            RamachandranMap::Glycine => {
+                nearest(&GENERAL_REGIONS, 1.0).min(nearest(&GENERAL_REGIONS, -1.0))
+            }
+            RamachandranMap::Proline => nearest(&PROLINE_REGIONS, 1.0),
+        }
+    }
+}
+
+/// Degrees from `angle` to the nearest end of `range`, going either way
+/// around the circle; 0 inside.
+fn outside(angle: f64, (low, high): (f64, f64)) -> f64 {
+    let wrap = |delta: f64| (delta + 180.0).rem_euclid(360.0) - 180.0;
+    let angle = wrap(angle);
+    if (low..=high).contains(&angle) {
+        return 0.0;
+    }
+    wrap(angle - low).abs().min(wrap(angle - high).abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_allow_their_regions_and_measure_outliers() {
+        let general = RamachandranMap::General;
+        assert_eq!(general.deviation(-57.0, -47.0), 0.0);
+        assert_eq!(general.deviation(-119.0, 113.0), 0.0);
+        // Wrapped across ±180
+        assert_eq!(general.deviation(-120.0, 535.0), 0.0);
+        assert!((general.deviation(60.0, -60.0) - 60.0).abs() < 1e-9);
+        assert!(general.deviation(0.0, 0.0) > 20.0);
+
+        let glycine = RamachandranMap::for_residue("GLY");
+        assert_eq!(glycine.deviation(80.0, -170.0), 0.0);
+        assert_eq!(glycine.deviation(60.0, -40.0), 0.0);
+
+        let proline = RamachandranMap::for_residue("PRO");
+        assert_eq!(proline.deviation(-65.0, 145.0), 0.0);
+        assert!((proline.deviation(-120.0, 145.0) - 25.0).abs() < 1e-9);
+    }
+}
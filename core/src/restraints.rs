@@ -0,0 +1,466 @@
+use folding_molecule::{PeptideChain, ResidueId};
+use serde_json::Value;
+
+/// Force constant of `restrain` lines that give none, in kcal/(mol·rad²).
+pub const DEFAULT_RESTRAINT_CONSTANT: f64 = 10.0;
+
+/// Force constant of contact-prior distance restraints, in kcal/(mol·Å²),
+/// before each is scaled by its probability.
+pub const DEFAULT_CONTACT_CONSTANT: f64 = 1.0;
+
+/// Cα–Cα distance (Å) under which a predicted contact counts as formed.
+pub const CONTACT_DISTANCE: f64 = 8.0;
+
+/// Half-width (Å) of the flat bottom around a predicted distance.
+pub const DISTANCE_TOLERANCE: f64 = 1.0;
+
+/// Pairs closer in sequence than this are in contact by construction and
+/// are dropped from contact priors.
+pub const MIN_CONTACT_SEPARATION: usize = 3;
+
+/// Backbone conformation a `restrain` line pulls a segment toward.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecondaryStructure {
+    /// Right-handed α-helix.
+    Helix,
+    /// Extended β-strand.
+    Sheet,
+}
+
+impl SecondaryStructure {
+    /// `helix` (or `alpha`), `sheet` (or `strand`, `beta`).
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "helix" | "alpha" => Some(SecondaryStructure::Helix),
+            "sheet" | "strand" | "beta" => Some(SecondaryStructure::Sheet),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecondaryStructure::Helix => "helix",
+            SecondaryStructure::Sheet => "sheet",
+        }
+    }
+
+    /// Ideal (phi, psi) in degrees.
+    pub fn target(&self) -> (f64, f64) {
+        match self {
+            SecondaryStructure::Helix => (-57.0, -47.0),
+            SecondaryStructure::Sheet => (-119.0, 113.0),
+        }
+    }
+}
+
+/// Harmonic restraint of phi and psi over `start..=end`:
+/// E = ½ k Σ (Δphi² + Δpsi²), with the deviations wrapped to ±180° and
+/// taken in radians.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DihedralRestraint {
+    pub start: ResidueId,
+    pub end: ResidueId,
+    pub structure: SecondaryStructure,
+    pub force_constant: f64,
+}
+
+impl DihedralRestraint {
+    /// Restraint energy in kcal/mol; residues past the chain end add nothing.
+    pub fn energy(&self, chain: &PeptideChain) -> f64 {
+        let (phi_target, psi_target) = self.structure.target();
+        let (phi, psi) = (chain.phi(), chain.psi());
+        let deviation = |angle: f64, target: f64| {
+            let wrapped = (angle - target + 180.0).rem_euclid(360.0) - 180.0;
+            wrapped.to_radians().powi(2)
+        };
+        let squared: f64 = (self.start.0..=self.end.0)
+            .filter(|&index| index < phi.len())
+            .map(|index| deviation(phi[index], phi_target) + deviation(psi[index], psi_target))
+            .sum();
+        0.5 * self.force_constant * squared
+    }
+}
+
+/// Flat-bottomed Cα–Cα restraint: free between `lower` and `upper`,
+/// E = w k (d − bound)² outside.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DistanceRestraint {
+    pub first: ResidueId,
+    pub second: ResidueId,
+    pub lower: f64,
+    pub upper: f64,
+    /// Probability the prediction gave the pair, scaling the force constant.
+    pub weight: f64,
+}
+
+impl DistanceRestraint {
+    pub fn distance(&self, positions: &[[f64; 3]]) -> Option<f64> {
+        let a = positions.get(self.first.0)?;
+        let b = positions.get(self.second.0)?;
+        Some(
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| (x - y).powi(2))
+                .sum::<f64>()
+                .sqrt(),
+        )
+    }
+
+    /// Energy in kcal/mol at force constant `k`; pairs off the chain add nothing.
+    pub fn energy(&self, positions: &[[f64; 3]], k: f64) -> f64 {
+        let Some(distance) = self.distance(positions) else {
+            return 0.0;
+        };
+        let violation = if distance > self.upper {
+            distance - self.upper
+        } else if distance < self.lower {
+            self.lower - distance
+        } else {
+            0.0
+        };
+        self.weight * k * violation * violation
+    }
+
+    pub fn is_satisfied(&self, positions: &[[f64; 3]]) -> bool {
+        self.distance(positions)
+            .is_some_and(|distance| (self.lower..=self.upper).contains(&distance))
+    }
+}
+
+/// Distance restraints from a predicted contact or distance map (PSICOV,
+/// CASP RR, or the dense matrices AlphaFold-style tools write). Files number
+/// residues from 1; the restraints number them from 0 like the rest of the
+/// engine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContactPrior {
+    pub restraints: Vec<DistanceRestraint>,
+    pub force_constant: f64,
+}
+
+impl ContactPrior {
+    pub fn new(restraints: Vec<DistanceRestraint>) -> Self {
+        Self {
+            restraints,
+            force_constant: DEFAULT_CONTACT_CONSTANT,
+        }
+    }
+
+    /// Comma- or whitespace-separated rows, one pair each:
+    /// `i j p` (contact under [`CONTACT_DISTANCE`]), `i j d p` (distance
+    /// `d` ± [`DISTANCE_TOLERANCE`]) or `i j lower upper p` (CASP RR).
+    /// Comments (`#`), headers and other lines that do not start with a
+    /// residue number are skipped.
+    pub fn parse_csv(text: &str) -> Result<Self, String> {
+        let mut restraints = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let fields: Vec<&str> = line
+                .split([',', ' ', '\t'])
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .collect();
+            if fields
+                .first()
+                .is_none_or(|field| field.parse::<usize>().is_err())
+            {
+                continue;
+            }
+            let values = fields
+                .iter()
+                .map(|field| field.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| format!("line {}: expected numbers", number + 1))?;
+            let (first, second, lower, upper, probability) = match values[..] {
+                [first, second, probability] => (first, second, 0.0, CONTACT_DISTANCE, probability),
+                [first, second, distance, probability] => (
+                    first,
+                    second,
+                    (distance - DISTANCE_TOLERANCE).max(0.0),
+                    distance + DISTANCE_TOLERANCE,
+                    probability,
+                ),
+                [first, second, lower, upper, probability] => {
+                    (first, second, lower, upper, probability)
+                }
+                _ => {
+                    return Err(format!(
+                        "line {}: expected `i j p`, `i j d p` or `i j lower upper p`",
+                        number + 1
+                    ));
+                }
+            };
+            let restraint = pair(first, second, lower, upper, probability)
+                .map_err(|err| format!("line {}: {err}", number + 1))?;
+            restraints.push(restraint);
+        }
+        Ok(Self::new(restraints))
+    }
+
+    /// A list of `{"i", "j", "probability"}` objects (optionally with
+    /// `distance` or `lower`/`upper`), bare or under `contacts`; or a dense
+    /// contact-probability matrix under `contact_probabilities`,
+    /// `probabilities` or `contact_map`. Either may sit in a one-element
+    /// array, as AlphaFold's PAE files do.
+    pub fn parse_json(text: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(text).map_err(|err| format!("invalid JSON: {err}"))?;
+        let value = match value {
+            Value::Array(mut items) if items.len() == 1 && items[0].is_object() => items.remove(0),
+            other => other,
+        };
+        if let Some(matrix) = ["contact_probabilities", "probabilities", "contact_map"]
+            .iter()
+            .find_map(|key| value.get(key))
+        {
+            return Self::from_matrix(matrix);
+        }
+        let contacts = value
+            .get("contacts")
+            .unwrap_or(&value)
+            .as_array()
+            .ok_or("expected a contact list or a contact-probability matrix")?;
+        let mut restraints = Vec::with_capacity(contacts.len());
+        for (index, contact) in contacts.iter().enumerate() {
+            let field = |key: &str| contact.get(key).and_then(Value::as_f64);
+            let (Some(first), Some(second)) = (field("i"), field("j")) else {
+                return Err(format!("contact {index}: missing `i` or `j`"));
+            };
+            let probability = field("probability").unwrap_or(1.0);
+            let (lower, upper) = match (field("lower"), field("upper"), field("distance")) {
+                (lower, Some(upper), _) => (lower.unwrap_or(0.0), upper),
+                (_, _, Some(distance)) => (
+                    (distance - DISTANCE_TOLERANCE).max(0.0),
+                    distance + DISTANCE_TOLERANCE,
+                ),
+                _ => (0.0, CONTACT_DISTANCE),
+            };
+            restraints.push(
+                pair(first, second, lower, upper, probability)
+                    .map_err(|err| format!("contact {index}: {err}"))?,
+            );
+        }
+        Ok(Self::new(restraints))
+    }
+
+    fn from_matrix(matrix: &Value) -> Result<Self, String> {
+        let rows = matrix
+            .as_array()
+            .ok_or("contact matrix must be an array of rows")?;
+        let mut restraints = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let row = row
+                .as_array()
+                .filter(|row| row.len() == rows.len())
+                .ok_or_else(|| {
+                    format!("contact matrix row {} is not {} wide", i + 1, rows.len())
+                })?;
+            for (j, cell) in row.iter().enumerate().skip(i + 1) {
+                let probability = cell.as_f64().ok_or_else(|| {
+                    format!("contact matrix cell {},{} is not a number", i + 1, j + 1)
+                })?;
+                if probability > 0.0 {
+                    restraints.push(pair(
+                        (i + 1) as f64,
+                        (j + 1) as f64,
+                        0.0,
+                        CONTACT_DISTANCE,
+                        probability,
+                    )?);
+                }
+            }
+        }
+        Ok(Self::new(restraints))
+    }
+
+    /// Holds a model's own geometry: every pair within [`CONTACT_DISTANCE`]
+    /// is kept at its distance ± [`DISTANCE_TOLERANCE`], weighted by the
+    /// lower of the two residues' `confidence` (0..1, e.g. pLDDT / 100).
+    pub fn from_model(positions: &[[f64; 3]], confidence: &[f64]) -> Self {
+        let mut restraints = Vec::new();
+        for first in 0..positions.len() {
+            for second in first + MIN_CONTACT_SEPARATION..positions.len() {
+                let mut restraint = DistanceRestraint {
+                    first: ResidueId(first),
+                    second: ResidueId(second),
+                    lower: 0.0,
+                    upper: 0.0,
+                    weight: confidence
+                        .get(first)
+                        .zip(confidence.get(second))
+                        .map_or(0.0, |(a, b)| a.min(*b).clamp(0.0, 1.0)),
+                };
+                let Some(distance) = restraint.distance(positions) else {
+                    continue;
+                };
+                if distance <= CONTACT_DISTANCE && restraint.weight > 0.0 {
+                    restraint.lower = (distance - DISTANCE_TOLERANCE).max(0.0);
+                    restraint.upper = distance + DISTANCE_TOLERANCE;
+                    restraints.push(restraint);
+                }
+            }
+        }
+        Self::new(restraints)
+    }
+
+    /// Drops pairs below `min_probability` or closer in sequence than
+    /// [`MIN_CONTACT_SEPARATION`].
+    pub fn filtered(mut self, min_probability: f64) -> Self {
+        self.restraints.retain(|restraint| {
+            restraint.weight >= min_probability
+                && restraint.second.0 - restraint.first.0 >= MIN_CONTACT_SEPARATION
+        });
+        self
+    }
+
+    pub fn with_force_constant(mut self, k: f64) -> Self {
+        self.force_constant = k;
+        self
+    }
+
+    /// Errors when a pair names a residue past the end of the chain.
+    pub fn check(&self, residues: usize) -> Result<(), String> {
+        match self
+            .restraints
+            .iter()
+            .find(|restraint| restraint.second.0 >= residues)
+        {
+            Some(restraint) => Err(format!(
+                "contact {}-{} is past the end of the {residues}-residue chain",
+                restraint.first.0 + 1,
+                restraint.second.0 + 1
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn energy(&self, positions: &[[f64; 3]]) -> f64 {
+        self.restraints
+            .iter()
+            .map(|restraint| restraint.energy(positions, self.force_constant))
+            .sum()
+    }
+
+    /// Restraints whose pair sits within its bounds.
+    pub fn satisfied(&self, positions: &[[f64; 3]]) -> usize {
+        self.restraints
+            .iter()
+            .filter(|restraint| restraint.is_satisfied(positions))
+            .count()
+    }
+}
+
+/// Validated restraint between 1-based residues `first` and `second`, in
+/// either order.
+fn pair(
+    first: f64,
+    second: f64,
+    lower: f64,
+    upper: f64,
+    probability: f64,
+) -> Result<DistanceRestraint, String> {
+    let residue = |raw: f64| {
+        (raw.fract() == 0.0 && raw >= 1.0)
+            .then(|| ResidueId(raw as usize - 1))
+            .ok_or_else(|| format!("residue {raw} is not a 1-based residue number"))
+    };
+    let (first, second) = (residue(first)?, residue(second)?);
+    if first == second {
+        return Err(format!("residue {} paired with itself", first.0 + 1));
+    }
+    if !(0.0..=1.0).contains(&probability) {
+        return Err(format!("probability {probability} is outside 0..1"));
+    }
+    if !(lower.is_finite() && upper.is_finite() && 0.0 <= lower && lower <= upper) {
+        return Err(format!("invalid distance bounds {lower}..{upper}"));
+    }
+    Ok(DistanceRestraint {
+        first: ResidueId(first.0.min(second.0)),
+        second: ResidueId(first.0.max(second.0)),
+        lower,
+        upper,
+        weight: probability,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restraint_energy_vanishes_on_target_and_wraps() {
+        let mut chain = PeptideChain::from_sequence("AAAA");
+        let restraint = DihedralRestraint {
+            start: ResidueId(1),
+            end: ResidueId(2),
+            structure: SecondaryStructure::Helix,
+            force_constant: 2.0,
+        };
+        for index in 1..=2 {
+            chain.phi_mut()[index] = -57.0;
+            chain.psi_mut()[index] = -47.0;
+        }
+        assert!(restraint.energy(&chain).abs() < 1e-12);
+
+        // 10° off in phi on one residue; 360° turns cost nothing
+        chain.phi_mut()[1] = -47.0;
+        chain.psi_mut()[2] = 313.0;
+        let expected = 0.5 * 2.0 * 10f64.to_radians().powi(2);
+        assert!((restraint.energy(&chain) - expected).abs() < 1e-12);
+        assert_eq!(
+            SecondaryStructure::parse("Strand"),
+            Some(SecondaryStructure::Sheet)
+        );
+    }
+
+    #[test]
+    fn contact_priors_parse_csv_rr_and_json_maps() {
+        let csv = "PFRMAT RR\ni,j,probability\n1,9,0.9\n2 8 6.5 0.7 # distance\n3 10 0 8 0.3\n4,5,0.95\nEND\n";
+        let prior = ContactPrior::parse_csv(csv).unwrap();
+        assert_eq!(prior.restraints.len(), 4);
+        assert_eq!(
+            prior.restraints[1],
+            DistanceRestraint {
+                first: ResidueId(1),
+                second: ResidueId(7),
+                lower: 5.5,
+                upper: 7.5,
+                weight: 0.7,
+            }
+        );
+        // Below the threshold or adjacent in sequence
+        let kept = prior.filtered(0.5);
+        assert_eq!(kept.restraints.len(), 2);
+        assert!(kept.check(9).is_ok());
+        assert!(kept.check(8).unwrap_err().contains("1-9"));
+        assert!(ContactPrior::parse_csv("1,1,0.5").is_err());
+        assert!(ContactPrior::parse_csv("1,4,1.5").is_err());
+
+        let listed = ContactPrior::parse_json(
+            r#"{"contacts": [{"i": 1, "j": 9, "probability": 0.9}, {"i": 2, "j": 8, "distance": 6.5}]}"#,
+        )
+        .unwrap();
+        assert_eq!(listed.restraints[0], kept.restraints[0]);
+        assert_eq!(listed.restraints[1].weight, 1.0);
+        let dense = ContactPrior::parse_json(
+            r#"[{"contact_probabilities": [[0, 0.2, 0.8], [0.2, 0, 0], [0.8, 0, 0]]}]"#,
+        )
+        .unwrap();
+        assert_eq!(dense.restraints.len(), 2);
+        assert_eq!(dense.restraints[1].second, ResidueId(2));
+
+        let mut positions = vec![[0.0; 3]; 9];
+        positions[8] = [10.0, 0.0, 0.0];
+        positions[1] = [0.0, 0.0, 0.0];
+        positions[7] = [6.0, 0.0, 0.0];
+        // Only the 1-9 pair sits 2 Å past its 8 Å bound
+        assert!((kept.energy(&positions) - 0.9 * 4.0).abs() < 1e-12);
+        assert_eq!(kept.satisfied(&positions), 1);
+
+        // A model holds its confident pairs where they are
+        let model: Vec<[f64; 3]> = (0..6).map(|i| [2.5 * i as f64, 0.0, 0.0]).collect();
+        let held = ContactPrior::from_model(&model, &[0.9, 0.9, 0.9, 0.9, 0.3, 0.9]);
+        assert_eq!(held.restraints.len(), 3);
+        assert_eq!(held.satisfied(&model), 3);
+        assert_eq!(held.filtered(0.7).restraints.len(), 2);
+    }
+}
@@ -1,28 +1,90 @@
 use std::time::Duration;
 
-use folding_molecule::ResidueId;
+use folding_molecule::{Dihedral, ResidueId, Rotamer};
 use folding_time::RotationClock;
 use folding_time::trajectory::SpanRecord;
 
 use crate::micro_oscillator::MicroOscillator;
 use crate::physics_bridge::PhysicsSpanMetrics;
+use crate::ramachandran::RamachandranMap;
 
 /// Command describing the desired rotation.
 #[derive(Debug, Clone)]
 pub struct RotationCommand {
     pub residue: ResidueId,
+    pub dihedral: Dihedral,
     pub angle_degrees: f64,
     pub duration: Duration,
     pub label: Option<String>,
 }
 
+/// Command switching a residue's side chain to a library rotamer.
+#[derive(Debug, Clone)]
+pub struct RotamerCommand {
+    pub residue: ResidueId,
+    /// The residue's chi angles before the flip.
+    pub chi: [f64; 4],
+    /// Chi angles the residue type has; the rest are left alone.
+    pub chi_count: usize,
+    pub rotamer: Rotamer,
+    pub duration: Duration,
+    pub label: Option<String>,
+}
+
+impl RotamerCommand {
+    /// Change of each chi angle the flip makes, the short way round.
+    pub fn chi_changes(&self) -> Vec<f64> {
+        self.chi
+            .iter()
+            .zip(self.rotamer.chi)
+            .take(self.chi_count)
+            .map(|(current, target)| (target - current + 180.0).rem_euclid(360.0) - 180.0)
+            .collect()
+    }
+}
+
 /// Result from executing a rotation step.
 #[derive(Debug, Clone)]
 pub struct RotationOutcome {
+    pub residue: ResidueId,
+    pub dihedral: Dihedral,
     pub applied_angle: f64,
     pub span_record: SpanRecord,
     pub ghost: bool,
     pub physics_metrics: Option<PhysicsSpanMetrics>,
+    /// Values of the run's collective variables after the span, in the order
+    /// they were requested; empty for ghost and rejected spans.
+    pub collective_variables: Vec<f64>,
+}
+
+/// Phi window (degrees) a residue may be turned within: proline's ring pins
+/// it near -65°, glycine may take either sign, and the other residues stay
+/// off the empty phi > 90° strip.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TorsionLimits {
+    pub phi: Option<(f64, f64)>,
+}
+
+impl TorsionLimits {
+    pub fn for_residue(name: &str) -> Self {
+        let phi = match RamachandranMap::for_residue(name) {
+            RamachandranMap::General => Some((-180.0, 90.0)),
+            RamachandranMap::Glycine => None,
+            RamachandranMap::Proline => Some((-95.0, -40.0)),
+        };
+        Self { phi }
+    }
+
+    /// `delta` cut back so a torsion at `current` stays inside the window;
+    /// one already outside may turn toward it but no further away.
+    pub fn limit(&self, dihedral: Dihedral, current: f64, delta: f64) -> f64 {
+        let (Dihedral::Phi, Some((low, high))) = (dihedral, self.phi) else {
+            return delta;
+        };
+        let current = (current + 180.0).rem_euclid(360.0) - 180.0;
+        let target = current + delta;
+        target.clamp(low.min(current), high.max(current)) - current
+    }
 }
 
 /// Calculates the final rotation after applying oscillations and clock pacing.
@@ -36,12 +98,31 @@ impl RotationSolver {
         Self { oscillator, clock }
     }
 
+    /// The clock pacing span releases; see [`RotationClock::real_time`].
+    pub fn clock_mut(&mut self) -> &mut RotationClock {
+        &mut self.clock
+    }
+
     pub fn solve(&self, command: RotationCommand) -> RotationOutcome {
+        self.solve_within(command, None)
+    }
+
+    /// Solves `command` for a residue with `limits`, whose torsion currently
+    /// sits at the second value; the noisy angle is cut back to the window.
+    pub fn solve_within(
+        &self,
+        command: RotationCommand,
+        limits: Option<(TorsionLimits, f64)>,
+    ) -> RotationOutcome {
         let oscillation = self.oscillator.sample(command.duration);
-        let applied_angle = command.angle_degrees + oscillation;
-        let id = command
-            .label
-            .unwrap_or_else(|| format!("residue-{}", command.residue.0));
+        let mut applied_angle = command.angle_degrees + oscillation;
+        if let Some((limits, current)) = limits {
+            applied_angle = limits.limit(command.dihedral, current, applied_angle);
+        }
+        let id = command.label.unwrap_or_else(|| match command.dihedral {
+            Dihedral::Phi => format!("residue-{}", command.residue.0),
+            other => format!("residue-{}-{}", command.residue.0, other.as_str()),
+        });
         let span_duration = if command.duration.is_zero() {
             self.clock.tick_duration()
         } else {
@@ -55,10 +136,44 @@ impl RotationSolver {
         );
         span.delta_theta = applied_angle;
         RotationOutcome {
+            residue: command.residue,
+            dihedral: command.dihedral,
+            applied_angle,
+            span_record: span,
+            ghost: false,
+            physics_metrics: None,
+            collective_variables: Vec::new(),
+        }
+    }
+
+    /// Solves a rotamer flip. Rotamers are discrete states, so the oscillator
+    /// adds nothing; the span is costed on every chi angle that turns and
+    /// reports the chi1 change as its angle.
+    pub fn solve_rotamer(&self, command: RotamerCommand) -> RotationOutcome {
+        let changes = command.chi_changes();
+        let applied_angle = changes.first().copied().unwrap_or(0.0);
+        let turned: f64 = changes.iter().map(|change| change.abs()).sum();
+        let id = command.label.unwrap_or_else(|| {
+            format!(
+                "residue-{}-rotamer-{}",
+                command.residue.0, command.rotamer.name
+            )
+        });
+        let span_duration = if command.duration.is_zero() {
+            self.clock.tick_duration()
+        } else {
+            command.duration
+        };
+        let mut span = SpanRecord::new(id, turned * 0.01, turned * 0.005, span_duration);
+        span.delta_theta = applied_angle;
+        RotationOutcome {
+            residue: command.residue,
+            dihedral: Dihedral::Chi1,
             applied_angle,
             span_record: span,
             ghost: false,
             physics_metrics: None,
+            collective_variables: Vec::new(),
         }
     }
 }
@@ -72,6 +187,7 @@ mod tests {
         let solver = RotationSolver::new(MicroOscillator::new(0.0, 0.0), RotationClock::new(1));
         let command = RotationCommand {
             residue: ResidueId(1),
+            dihedral: Dihedral::Psi,
             angle_degrees: 30.0,
             duration: Duration::from_millis(4),
             label: Some("alias-span".into()),
@@ -82,6 +198,56 @@ mod tests {
         assert!((outcome.applied_angle - 30.0).abs() < f64::EPSILON);
         assert_eq!(outcome.span_record.id.as_str(), "alias-span");
         assert_eq!(outcome.span_record.duration, Duration::from_millis(4));
+        assert_eq!(outcome.dihedral, Dihedral::Psi);
         assert!(!outcome.ghost);
     }
+
+    #[test]
+    fn proline_phi_stays_in_its_ring_window() {
+        let solver = RotationSolver::new(MicroOscillator::new(0.0, 0.0), RotationClock::new(1));
+        let command = |dihedral, angle_degrees| RotationCommand {
+            residue: ResidueId(3),
+            dihedral,
+            angle_degrees,
+            duration: Duration::from_millis(1),
+            label: None,
+        };
+        let proline = Some((TorsionLimits::for_residue("PRO"), -60.0));
+        let turned = solver.solve_within(command(Dihedral::Phi, -60.0), proline);
+        assert_eq!(turned.applied_angle, -35.0);
+        assert_eq!(turned.span_record.delta_theta, -35.0);
+        let psi = solver.solve_within(command(Dihedral::Psi, -60.0), proline);
+        assert_eq!(psi.applied_angle, -60.0);
+
+        // From outside the window: toward it, never further away
+        let outside = TorsionLimits::for_residue("PRO");
+        assert_eq!(outside.limit(Dihedral::Phi, 0.0, 20.0), 0.0);
+        assert_eq!(outside.limit(Dihedral::Phi, 0.0, -20.0), -20.0);
+        let (glycine, alanine) = (
+            TorsionLimits::for_residue("GLY"),
+            TorsionLimits::for_residue("ALA"),
+        );
+        assert_eq!(glycine.limit(Dihedral::Phi, 80.0, 60.0), 60.0);
+        assert_eq!(alanine.limit(Dihedral::Phi, 80.0, 60.0), 10.0);
+    }
+
+    #[test]
+    fn rotamer_flips_turn_each_chi_the_short_way() {
+        let solver = RotationSolver::new(MicroOscillator::new(5.0, 1.0), RotationClock::new(1));
+        let command = RotamerCommand {
+            residue: ResidueId(4),
+            chi: [170.0, 60.0, 33.0, 0.0],
+            chi_count: 2,
+            rotamer: *folding_molecule::find_rotamer("LEU", "mt").unwrap(),
+            duration: Duration::from_millis(2),
+            label: None,
+        };
+        assert_eq!(command.chi_changes(), [125.0, 115.0]);
+
+        let outcome = solver.solve_rotamer(command);
+        assert_eq!(outcome.dihedral, Dihedral::Chi1);
+        assert_eq!(outcome.applied_angle, 125.0);
+        assert_eq!(outcome.span_record.id.as_str(), "residue-4-rotamer-mt");
+        assert!((outcome.span_record.delta_entropy - 2.4).abs() < 1e-12);
+    }
 }
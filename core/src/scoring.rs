@@ -0,0 +1,326 @@
+use folding_molecule::PeptideChain;
+
+use crate::collective_variables;
+
+/// Cα distance (Å) under which residues at least three apart are in contact.
+pub const CONTACT_CUTOFF: f64 = 8.0;
+
+/// Chain score added to the Metropolis criterion: a move is judged on
+/// ΔE + Σ (score(after) − score(before)), in kcal/mol. A term that cannot
+/// score a conformation returns NaN, and the move is rejected as
+/// `ScoreUnavailable`.
+pub type ScoreFn = Box<dyn Fn(&PeptideChain) -> f64 + Send>;
+
+/// Named scoring term registered with `FoldingEngineBuilder::with_score`.
+pub struct ScoringTerm {
+    pub name: String,
+    score: ScoreFn,
+}
+
+impl ScoringTerm {
+    pub fn new(
+        name: impl Into<String>,
+        score: impl Fn(&PeptideChain) -> f64 + Send + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            score: Box::new(score),
+        }
+    }
+
+    pub fn score(&self, chain: &PeptideChain) -> f64 {
+        (self.score)(chain)
+    }
+}
+
+/// Score written as an arithmetic expression over chain descriptors: `rg`
+/// (radius of gyration, Å), `end_to_end` (Å), `contacts` (Cα pairs within
+/// [`CONTACT_CUTOFF`]) and `residues`. Supports `+ - * / ^`, parentheses and
+/// `abs`, `sqrt`, `exp`, `ln`, `min`, `max`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreExpression {
+    source: String,
+    root: Node,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Number(f64),
+    Variable(Variable),
+    Negate(Box<Node>),
+    Binary(char, Box<Node>, Box<Node>),
+    Call(String, Vec<Node>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Variable {
+    RadiusOfGyration,
+    EndToEnd,
+    Contacts,
+    Residues,
+}
+
+impl ScoreExpression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let root = parser.expression()?;
+        if let Some(token) = parser.tokens.get(parser.position) {
+            return Err(format!("unexpected '{}' in score '{source}'", token.text()));
+        }
+        Ok(Self {
+            source: source.trim().to_string(),
+            root,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn evaluate(&self, chain: &PeptideChain) -> f64 {
+        evaluate(&self.root, &Descriptors::of(chain))
+    }
+}
+
+struct Descriptors {
+    radius_of_gyration: f64,
+    end_to_end: f64,
+    contacts: f64,
+    residues: f64,
+}
+
+impl Descriptors {
+    fn of(chain: &PeptideChain) -> Self {
+        let positions = chain.positions();
+        Self {
+            radius_of_gyration: collective_variables::radius_of_gyration(positions),
+            end_to_end: collective_variables::end_to_end(positions),
+            contacts: collective_variables::contacts(positions, CONTACT_CUTOFF) as f64,
+            residues: positions.len() as f64,
+        }
+    }
+}
+
+fn evaluate(node: &Node, descriptors: &Descriptors) -> f64 {
+    match node {
+        Node::Number(value) => *value,
+        Node::Variable(variable) => match variable {
+            Variable::RadiusOfGyration => descriptors.radius_of_gyration,
+            Variable::EndToEnd => descriptors.end_to_end,
+            Variable::Contacts => descriptors.contacts,
+            Variable::Residues => descriptors.residues,
+        },
+        Node::Negate(inner) => -evaluate(inner, descriptors),
+        Node::Binary(op, left, right) => {
+            let (left, right) = (evaluate(left, descriptors), evaluate(right, descriptors));
+            match op {
+                '+' => left + right,
+                '-' => left - right,
+                '*' => left * right,
+                '/' => left / right,
+                _ => left.powf(right),
+            }
+        }
+        Node::Call(function, args) => {
+            let args: Vec<f64> = args.iter().map(|arg| evaluate(arg, descriptors)).collect();
+            match function.as_str() {
+                "abs" => args[0].abs(),
+                "sqrt" => args[0].sqrt(),
+                "exp" => args[0].exp(),
+                "ln" => args[0].ln(),
+                "min" => args[0].min(args[1]),
+                _ => args[0].max(args[1]),
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Symbol(char),
+}
+
+impl Token {
+    fn text(&self) -> String {
+        match self {
+            Token::Number(value) => value.to_string(),
+            Token::Ident(name) => name.clone(),
+            Token::Symbol(symbol) => symbol.to_string(),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text
+                .parse()
+                .map_err(|_| format!("invalid number '{text}' in score '{source}'"))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(
+                chars[start..i].iter().collect::<String>().to_lowercase(),
+            ));
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        } else {
+            return Err(format!("unexpected '{c}' in score '{source}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek_symbol(&self, symbols: &str) -> Option<char> {
+        match self.tokens.get(self.position) {
+            Some(Token::Symbol(symbol)) if symbols.contains(*symbol) => Some(*symbol),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        if self.peek_symbol(&symbol.to_string()).is_some() {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(format!("expected '{symbol}' in score"))
+        }
+    }
+
+    fn expression(&mut self) -> Result<Node, String> {
+        let mut node = self.term()?;
+        while let Some(op) = self.peek_symbol("+-") {
+            self.position += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.term()?));
+        }
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Node, String> {
+        let mut node = self.unary()?;
+        while let Some(op) = self.peek_symbol("*/") {
+            self.position += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.peek_symbol("-").is_some() {
+            self.position += 1;
+            return Ok(Node::Negate(Box::new(self.unary()?)));
+        }
+        let base = self.primary()?;
+        if self.peek_symbol("^").is_some() {
+            self.position += 1;
+            return Ok(Node::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| "score ends unexpectedly".to_string())?;
+        self.position += 1;
+        match token {
+            Token::Number(value) => Ok(Node::Number(value)),
+            Token::Symbol('(') => {
+                let node = self.expression()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Token::Ident(name) if self.peek_symbol("(").is_some() => {
+                let arity = match name.as_str() {
+                    "abs" | "sqrt" | "exp" | "ln" => 1,
+                    "min" | "max" => 2,
+                    _ => return Err(format!("unknown function '{name}' in score")),
+                };
+                self.position += 1;
+                let mut args = vec![self.expression()?];
+                while self.peek_symbol(",").is_some() {
+                    self.position += 1;
+                    args.push(self.expression()?);
+                }
+                self.expect(')')?;
+                if args.len() != arity {
+                    return Err(format!("{name} takes {arity} argument(s)"));
+                }
+                Ok(Node::Call(name, args))
+            }
+            Token::Ident(name) => match name.as_str() {
+                "rg" => Ok(Node::Variable(Variable::RadiusOfGyration)),
+                "end_to_end" => Ok(Node::Variable(Variable::EndToEnd)),
+                "contacts" => Ok(Node::Variable(Variable::Contacts)),
+                "residues" => Ok(Node::Variable(Variable::Residues)),
+                _ => Err(format!(
+                    "unknown variable '{name}' in score (rg, end_to_end, contacts, residues)"
+                )),
+            },
+            Token::Symbol(symbol) => Err(format!("unexpected '{symbol}' in score")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use folding_molecule::{Residue, ResidueId};
+
+    fn straight_chain(length: usize) -> PeptideChain {
+        PeptideChain::new(
+            (0..length)
+                .map(|i| Residue::new(ResidueId(i), "ALA", [3.8 * i as f64, 0.0, 0.0]))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn expressions_score_chain_descriptors() {
+        let chain = straight_chain(4);
+        let expression =
+            ScoreExpression::parse("2 * end_to_end - contacts + max(1, -residues)^2").unwrap();
+        assert!((expression.evaluate(&chain) - (2.0 * 11.4 - 0.0 + 1.0)).abs() < 1e-9);
+        let rg = ScoreExpression::parse("sqrt(rg ^ 2)").unwrap();
+        assert!((rg.evaluate(&chain) - (3.8f64 * 3.8 * 1.25).sqrt()).abs() < 1e-9);
+        assert_eq!(ScoreExpression::parse(" -rg ").unwrap().source(), "-rg");
+
+        assert!(ScoreExpression::parse("rg +").is_err());
+        assert!(
+            ScoreExpression::parse("energy")
+                .unwrap_err()
+                .contains("unknown variable")
+        );
+        assert!(ScoreExpression::parse("min(rg)").is_err());
+        assert!(ScoreExpression::parse("(rg").is_err());
+    }
+}
@@ -1,5 +1,19 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::physics_bridge::NativeVelocities;
+
+/// Generator state taken during a run; [`SimpleRng::from_state`] resumes the
+/// stream exactly where it was.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RngCheckpoint {
+    /// Engine step at which the state was taken.
+    pub step: usize,
+    pub state: u64,
+    /// Velocities the native physics bridges carried at that step, so a
+    /// resumed run continues their dynamics too.
+    pub velocities: NativeVelocities,
+}
+
 /// Lightweight pseudo-random number generator sufficient for deterministic tests.
 #[derive(Clone, Debug)]
 pub struct SimpleRng {
@@ -16,6 +30,15 @@ impl SimpleRng {
         Self { state: initial }
     }
 
+    /// Resumes a stream from [`Self::state`]; unlike a seed, 0 is kept as is.
+    pub fn from_state(state: u64) -> Self {
+        Self { state }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
     pub fn from_entropy() -> Self {
         let nanos = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -29,7 +52,7 @@ impl SimpleRng {
         range.start + span * self.next_f64()
     }
 
-    fn next_u64(&mut self) -> u64 {
+    pub fn next_u64(&mut self) -> u64 {
         const MULTIPLIER: u64 = 6364136223846793005;
         const INCREMENT: u64 = 1442695040888963407;
         self.state = self.state.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
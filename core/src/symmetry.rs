@@ -0,0 +1,244 @@
+use std::f64::consts::PI;
+
+/// Point group of a homo-oligomer: `Cn` rings or `Dn` double rings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointGroup {
+    Cyclic(usize),
+    Dihedral(usize),
+}
+
+impl PointGroup {
+    /// `C3`, `d2`, ...; the order must be at least 1 (`C1` is a monomer).
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let order: usize = raw.get(1..)?.parse().ok().filter(|order| *order >= 1)?;
+        match raw.chars().next()?.to_ascii_uppercase() {
+            'C' => Some(PointGroup::Cyclic(order)),
+            'D' => Some(PointGroup::Dihedral(order)),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            PointGroup::Cyclic(order) => format!("C{order}"),
+            PointGroup::Dihedral(order) => format!("D{order}"),
+        }
+    }
+
+    /// Number of protomers in the assembly.
+    pub fn copies(&self) -> usize {
+        match self {
+            PointGroup::Cyclic(order) => *order,
+            PointGroup::Dihedral(order) => 2 * order,
+        }
+    }
+
+    /// Rotation matrices of the group, identity first. The n-fold axis is z;
+    /// the `Dn` two-fold axes lie in the xy plane, starting with x.
+    pub fn operators(&self) -> Vec<[[f64; 3]; 3]> {
+        let (order, flips) = match self {
+            PointGroup::Cyclic(order) => (*order, false),
+            PointGroup::Dihedral(order) => (*order, true),
+        };
+        let mut operators: Vec<[[f64; 3]; 3]> = (0..order)
+            .map(|step| rotation_z(2.0 * PI * step as f64 / order as f64))
+            .collect();
+        if flips {
+            let flip = [[1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, -1.0]];
+            let ring = operators.clone();
+            operators.extend(ring.iter().map(|rotation| multiply(rotation, &flip)));
+        }
+        operators
+    }
+}
+
+/// Symmetric assembly built from one protomer. The protomer is centred,
+/// pushed `radius` Å out along x (and, for `Dn`, half a radius up along z so
+/// the two rings do not overlap), then copied by every group operator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Symmetry {
+    pub group: PointGroup,
+    pub radius: f64,
+}
+
+impl Symmetry {
+    pub fn new(group: PointGroup, radius: f64) -> Self {
+        Self {
+            group,
+            radius: radius.max(0.0),
+        }
+    }
+
+    /// Smallest radius that keeps neighbouring copies of `protomer` (treated as
+    /// spheres around its centroid) `clearance` Å apart.
+    pub fn around(group: PointGroup, protomer: &[[f64; 3]], clearance: f64) -> Self {
+        let center = centroid(protomer);
+        let extent = protomer
+            .iter()
+            .map(|p| distance(p, &center))
+            .fold(0.0, f64::max);
+        let order = match group {
+            PointGroup::Cyclic(order) | PointGroup::Dihedral(order) => order,
+        };
+        let ring = if order < 2 {
+            0.0
+        } else {
+            (extent + 0.5 * clearance) / (PI / order as f64).sin()
+        };
+        let radius = match group {
+            PointGroup::Cyclic(_) => ring,
+            // The rise puts the two rings a whole radius apart along z, so
+            // the radius must also clear each copy from its two-fold partner.
+            PointGroup::Dihedral(_) => ring.max(2.0 * extent + clearance),
+        };
+        Self::new(group, radius)
+    }
+
+    /// Every protomer copy, the first one being the placed protomer itself.
+    pub fn assemble(&self, protomer: &[[f64; 3]]) -> Vec<Vec<[f64; 3]>> {
+        let center = centroid(protomer);
+        let rise = match self.group {
+            PointGroup::Cyclic(_) => 0.0,
+            PointGroup::Dihedral(_) => 0.5 * self.radius,
+        };
+        let placed: Vec<[f64; 3]> = protomer
+            .iter()
+            .map(|p| {
+                [
+                    p[0] - center[0] + self.radius,
+                    p[1] - center[1],
+                    p[2] - center[2] + rise,
+                ]
+            })
+            .collect();
+        self.group
+            .operators()
+            .iter()
+            .map(|rotation| placed.iter().map(|p| apply(rotation, p)).collect())
+            .collect()
+    }
+
+    /// Interaction energy per protomer with its symmetry mates: half the sum
+    /// of `pair_energy` over every bead pair between copy 0 and each other copy.
+    pub fn interface_energy(&self, protomer: &[[f64; 3]], pair_energy: impl Fn(f64) -> f64) -> f64 {
+        let copies = self.assemble(protomer);
+        let Some((reference, mates)) = copies.split_first() else {
+            return 0.0;
+        };
+        let total: f64 = mates
+            .iter()
+            .flat_map(|mate| {
+                reference
+                    .iter()
+                    .flat_map(move |a| mate.iter().map(move |b| distance(a, b)))
+            })
+            .map(&pair_energy)
+            .sum();
+        0.5 * total
+    }
+}
+
+fn rotation_z(angle: f64) -> [[f64; 3]; 3] {
+    let (sin, cos) = angle.sin_cos();
+    [[cos, -sin, 0.0], [sin, cos, 0.0], [0.0, 0.0, 1.0]]
+}
+
+fn multiply(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut product = [[0.0; 3]; 3];
+    for row in 0..3 {
+        for column in 0..3 {
+            product[row][column] = (0..3).map(|k| a[row][k] * b[k][column]).sum();
+        }
+    }
+    product
+}
+
+fn apply(rotation: &[[f64; 3]; 3], p: &[f64; 3]) -> [f64; 3] {
+    let mut rotated = [0.0; 3];
+    for (row, value) in rotated.iter_mut().enumerate() {
+        *value = (0..3).map(|k| rotation[row][k] * p[k]).sum();
+    }
+    rotated
+}
+
+fn centroid(positions: &[[f64; 3]]) -> [f64; 3] {
+    let mut center = [0.0; 3];
+    if positions.is_empty() {
+        return center;
+    }
+    for p in positions {
+        for axis in 0..3 {
+            center[axis] += p[axis] / positions.len() as f64;
+        }
+    }
+    center
+}
+
+fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assemblies_follow_the_point_group() {
+        assert_eq!(PointGroup::parse("c3"), Some(PointGroup::Cyclic(3)));
+        assert_eq!(PointGroup::parse("D2").map(|g| g.copies()), Some(4));
+        assert_eq!(PointGroup::parse("C0"), None);
+
+        let protomer: Vec<[f64; 3]> = (0..5).map(|i| [i as f64 * 3.8, 1.0, 0.5]).collect();
+        let ring = Symmetry::new(PointGroup::Cyclic(4), 15.0).assemble(&protomer);
+        assert_eq!(ring.len(), 4);
+        // Copies are rigid images: internal distances survive, and a C4 ring
+        // closes after four quarter turns with every copy at the same radius.
+        for copy in &ring {
+            assert!((distance(&copy[0], &copy[4]) - 4.0 * 3.8).abs() < 1e-9);
+            let center = centroid(copy);
+            assert!((center[0].hypot(center[1]) - 15.0).abs() < 1e-9);
+        }
+        let quarter = apply(&rotation_z(PI / 2.0), &ring[0][2]);
+        assert!(distance(&quarter, &ring[1][2]) < 1e-9);
+
+        let double = Symmetry::new(PointGroup::Dihedral(2), 15.0).assemble(&protomer);
+        assert_eq!(double.len(), 4);
+        let snug = Symmetry::around(PointGroup::Cyclic(4), &protomer, 4.0);
+        let neighbours = snug.assemble(&protomer);
+        let gap = distance(&centroid(&neighbours[0]), &centroid(&neighbours[1]));
+        assert!((gap - 2.0 * (7.6 + 2.0)).abs() < 1e-9);
+        assert!((centroid(&double[0])[2] + centroid(&double[2])[2]).abs() < 1e-9);
+        assert!(centroid(&double[0])[2] > 0.0);
+
+        let near = Symmetry::new(PointGroup::Cyclic(2), 5.0);
+        let far = Symmetry::new(PointGroup::Cyclic(2), 50.0);
+        let repulsion = |r: f64| 0.1 / r.powi(12);
+        assert!(
+            near.interface_energy(&protomer, repulsion)
+                > far.interface_energy(&protomer, repulsion)
+        );
+    }
+
+    #[test]
+    fn dihedral_rings_clear_their_two_fold_partners() {
+        let protomer: Vec<[f64; 3]> = (0..5).map(|i| [i as f64 * 3.8, 1.0, 0.5]).collect();
+        let extent = 7.6;
+        for order in [1, 2, 3, 6] {
+            let snug = Symmetry::around(PointGroup::Dihedral(order), &protomer, 4.0);
+            let centers: Vec<[f64; 3]> = snug
+                .assemble(&protomer)
+                .iter()
+                .map(|copy| centroid(copy))
+                .collect();
+            let closest = (0..centers.len())
+                .flat_map(|i| ((i + 1)..centers.len()).map(move |j| (i, j)))
+                .map(|(i, j)| distance(&centers[i], &centers[j]))
+                .fold(f64::INFINITY, f64::min);
+            assert!(
+                (closest - (2.0 * extent + 4.0)).abs() < 1e-9,
+                "D{order}: {closest}"
+            );
+        }
+    }
+}
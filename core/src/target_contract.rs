@@ -0,0 +1,242 @@
+use folding_molecule::{Dihedral, PeptideChain};
+
+use crate::folding_parser::{ContractInstruction, FoldingContract};
+
+/// Backbone atoms of one residue read from a target structure.
+#[derive(Clone, Debug, Default)]
+pub struct BackboneAtoms {
+    pub n: Option<[f64; 3]>,
+    pub ca: Option<[f64; 3]>,
+    pub c: Option<[f64; 3]>,
+}
+
+/// Accumulates backbone atoms read in file order, starting a new residue
+/// whenever the residue key (chain, number, insertion code) changes.
+#[derive(Debug, Default)]
+pub struct BackboneCollector {
+    pub residues: Vec<(String, BackboneAtoms)>,
+    current_key: Option<String>,
+}
+
+impl BackboneCollector {
+    /// Adds one atom; names other than N, CA and C only open the residue.
+    pub fn push(&mut self, key: String, residue_name: &str, atom_name: &str, position: [f64; 3]) {
+        if self.current_key.as_deref() != Some(key.as_str()) {
+            self.residues
+                .push((residue_name.to_string(), BackboneAtoms::default()));
+            self.current_key = Some(key);
+        }
+        if let Some((_, atoms)) = self.residues.last_mut() {
+            match atom_name {
+                "N" => atoms.n = Some(position),
+                "CA" => atoms.ca = Some(position),
+                "C" => atoms.c = Some(position),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Target backbone torsions in degrees; terminal or incomplete residues are `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TargetDihedrals {
+    pub phi: Option<f64>,
+    pub psi: Option<f64>,
+}
+
+/// Computes phi (C[i-1]–N–CA–C) and psi (N–CA–C–N[i+1]) for every residue.
+pub fn backbone_dihedrals(residues: &[BackboneAtoms]) -> Vec<TargetDihedrals> {
+    (0..residues.len())
+        .map(|idx| {
+            let current = &residues[idx];
+            let previous = idx.checked_sub(1).and_then(|prev| residues.get(prev));
+            let next = residues.get(idx + 1);
+            let phi = match (previous.and_then(|p| p.c), current.n, current.ca, current.c) {
+                (Some(c_prev), Some(n), Some(ca), Some(c)) => {
+                    Some(dihedral_degrees(c_prev, n, ca, c))
+                }
+                _ => None,
+            };
+            let psi = match (current.n, current.ca, current.c, next.and_then(|n| n.n)) {
+                (Some(n), Some(ca), Some(c), Some(n_next)) => {
+                    Some(dihedral_degrees(n, ca, c, n_next))
+                }
+                _ => None,
+            };
+            TargetDihedrals { phi, psi }
+        })
+        .collect()
+}
+
+/// Signed dihedral angle a–b–c–d in degrees within (-180, 180].
+pub fn dihedral_degrees(a: [f64; 3], b: [f64; 3], c: [f64; 3], d: [f64; 3]) -> f64 {
+    let b0 = sub(a, b);
+    let b1 = sub(c, b);
+    let b2 = sub(d, c);
+    let b1_norm = dot(b1, b1).sqrt().max(f64::EPSILON);
+    let b1_unit = [b1[0] / b1_norm, b1[1] / b1_norm, b1[2] / b1_norm];
+    let v = sub(b0, scale(b1_unit, dot(b0, b1_unit)));
+    let w = sub(b2, scale(b1_unit, dot(b2, b1_unit)));
+    let x = dot(v, w);
+    let y = dot(cross(b1_unit, v), w);
+    y.atan2(x).to_degrees()
+}
+
+/// Options for turning target torsions into rotation spans.
+#[derive(Clone, Debug)]
+pub struct TargetContractOptions {
+    /// Largest rotation emitted in a single span; bigger deltas are split.
+    pub max_step_degrees: f64,
+    /// Deltas below this magnitude are considered already satisfied.
+    pub tolerance_degrees: f64,
+    pub duration_ms: u64,
+    pub alias: Option<String>,
+}
+
+impl Default for TargetContractOptions {
+    fn default() -> Self {
+        Self {
+            max_step_degrees: 30.0,
+            tolerance_degrees: 0.5,
+            duration_ms: 1,
+            alias: Some("target_replay".into()),
+        }
+    }
+}
+
+/// Builds a contract whose `rotate` spans drive each residue's phi and psi from
+/// the current chain toward the target. Residues are paired by index.
+pub fn contract_from_target(
+    chain: &PeptideChain,
+    targets: &[TargetDihedrals],
+    options: &TargetContractOptions,
+) -> FoldingContract {
+    let mut instructions = Vec::new();
+    if let Some(alias) = options.alias.as_ref() {
+        instructions.push(ContractInstruction::SpanAlias(alias.clone()));
+    }
+    let max_step = options.max_step_degrees.abs().max(f64::EPSILON);
+    for (residue, target) in chain.residues().zip(targets) {
+        for (dihedral, goal) in [(Dihedral::Phi, target.phi), (Dihedral::Psi, target.psi)] {
+            let Some(goal) = goal else {
+                continue;
+            };
+            let mut remaining = wrap_degrees(goal - residue.torsion(dihedral));
+            if remaining.abs() < options.tolerance_degrees {
+                continue;
+            }
+            while remaining.abs() > f64::EPSILON {
+                let step = remaining.clamp(-max_step, max_step);
+                instructions.push(ContractInstruction::Rotate {
+                    residue: residue.id,
+                    dihedral,
+                    angle_degrees: step,
+                    duration_ms: options.duration_ms,
+                });
+                remaining -= step;
+            }
+        }
+    }
+    instructions.push(ContractInstruction::ClashCheck);
+    instructions.push(ContractInstruction::Commit);
+    FoldingContract::new(instructions)
+}
+
+fn wrap_degrees(angle: f64) -> f64 {
+    let wrapped = (angle + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped == -180.0 { 180.0 } else { wrapped }
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f64; 3], factor: f64) -> [f64; 3] {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dihedral_matches_known_geometry() {
+        let angle = dihedral_degrees(
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 1.0],
+        );
+        assert!((angle.abs() - 90.0).abs() < 1e-9);
+        let trans = dihedral_degrees(
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [-1.0, 1.0, 0.0],
+        );
+        assert!((trans.abs() - 180.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn contract_splits_large_deltas_and_skips_missing_targets() {
+        let chain = PeptideChain::from_sequence("AGS");
+        let targets = [
+            TargetDihedrals {
+                phi: None,
+                psi: Some(120.0),
+            },
+            TargetDihedrals {
+                phi: Some(-75.0),
+                psi: Some(145.0),
+            },
+            TargetDihedrals {
+                phi: Some(0.2),
+                psi: None,
+            },
+        ];
+        let contract = contract_from_target(&chain, &targets, &TargetContractOptions::default());
+        let rotations: Vec<(usize, Dihedral, f64)> = contract
+            .instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                ContractInstruction::Rotate {
+                    residue,
+                    dihedral,
+                    angle_degrees,
+                    ..
+                } => Some((residue.0, *dihedral, *angle_degrees)),
+                _ => None,
+            })
+            .collect();
+        let phi: Vec<_> = rotations
+            .iter()
+            .filter(|(_, dihedral, _)| *dihedral == Dihedral::Phi)
+            .collect();
+        assert_eq!(phi.len(), 3);
+        assert!(phi.iter().all(|(residue, _, _)| *residue == 1));
+        let total: f64 = phi.iter().map(|(_, _, angle)| angle).sum();
+        assert!((total + 75.0).abs() < 1e-9);
+        let psi_total = |index: usize| -> f64 {
+            rotations
+                .iter()
+                .filter(|(residue, dihedral, _)| *residue == index && *dihedral == Dihedral::Psi)
+                .map(|(_, _, angle)| angle)
+                .sum()
+        };
+        assert!((psi_total(0) - 120.0).abs() < 1e-9);
+        assert!((psi_total(1) - 145.0).abs() < 1e-9);
+        assert_eq!(psi_total(2), 0.0);
+    }
+}
@@ -0,0 +1,428 @@
+//! Builders for synthetic chains, trajectories and execution reports with
+//! controllable statistics, for testing analysis code without running a
+//! simulation. Output is deterministic for a given seed.
+
+use std::time::Duration;
+
+use folding_molecule::{Dihedral, EnergyModel, PeptideChain, ResidueId};
+use folding_time::trajectory::{SpanRecord, Trajectory};
+
+use crate::foldability::EnergySamples;
+use crate::folding_parser::PhysicsLevel;
+use crate::folding_ruleset::{RuleViolation, Ruleset, ViolationRecord};
+use crate::folding_runtime::{
+    DEFAULT_ACCEPTANCE_WINDOW, ExecutionReport, MetropolisStats, TrajectoryFrame,
+};
+use crate::physics_bridge::PhysicsEngine;
+use crate::geometry_validation::GeometryReport;
+use crate::memory::MemoryUsage;
+use crate::protein_state::EnergyState;
+use crate::rotation_solver::RotationOutcome;
+use crate::simple_rng::SimpleRng;
+
+const DEFAULT_SEED: u64 = 7;
+
+/// Default rules without the minimum distance, bond length and bond angle
+/// checks, so engine tests on short idealised chains are judged by the
+/// remaining rules alone.
+pub fn permissive_ruleset() -> Ruleset {
+    Ruleset {
+        min_distance_angstrom: None,
+        bond_distance_range: None,
+        bond_angle_range: None,
+        ..Ruleset::default()
+    }
+}
+
+/// Cα geometry produced by [`ChainBuilder`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChainShape {
+    /// Straight line along x.
+    #[default]
+    Extended,
+    /// α-helix: 2.3 Å radius, 1.5 Å rise and 100° per residue.
+    Helix,
+    /// Random walk with fixed bond length.
+    RandomCoil,
+}
+
+/// Synthetic [`PeptideChain`] of a given length and shape.
+#[derive(Clone, Debug)]
+pub struct ChainBuilder {
+    sequence: String,
+    shape: ChainShape,
+    bond_length: f64,
+    seed: u64,
+}
+
+impl ChainBuilder {
+    /// Poly-alanine chain of `length` residues.
+    pub fn new(length: usize) -> Self {
+        Self {
+            sequence: "A".repeat(length),
+            shape: ChainShape::default(),
+            bond_length: 3.8,
+            seed: DEFAULT_SEED,
+        }
+    }
+
+    /// One-letter sequence; also sets the length.
+    pub fn with_sequence(mut self, sequence: &str) -> Self {
+        self.sequence = sequence.to_string();
+        self
+    }
+
+    pub fn with_shape(mut self, shape: ChainShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Cα–Cα distance (Å) for extended chains and random coils.
+    pub fn with_bond_length(mut self, bond_length: f64) -> Self {
+        self.bond_length = bond_length;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn build(&self) -> PeptideChain {
+        let mut chain = PeptideChain::from_sequence(&self.sequence);
+        let mut rng = SimpleRng::seed_from_u64(self.seed);
+        let mut previous = [0.0; 3];
+        for (index, slot) in chain.positions_mut().iter_mut().enumerate() {
+            let position = match self.shape {
+                ChainShape::Extended => [index as f64 * self.bond_length, 0.0, 0.0],
+                ChainShape::Helix => {
+                    let angle = (index as f64 * 100.0).to_radians();
+                    [2.3 * angle.cos(), 2.3 * angle.sin(), 1.5 * index as f64]
+                }
+                ChainShape::RandomCoil if index == 0 => [0.0; 3],
+                ChainShape::RandomCoil => {
+                    let z: f64 = rng.gen_range(-1.0..1.0);
+                    let azimuth = rng.gen_range(0.0..std::f64::consts::TAU);
+                    let planar = (1.0 - z * z).sqrt();
+                    [
+                        previous[0] + self.bond_length * planar * azimuth.cos(),
+                        previous[1] + self.bond_length * planar * azimuth.sin(),
+                        previous[2] + self.bond_length * z,
+                    ]
+                }
+            };
+            *slot = position;
+            previous = position;
+        }
+        chain
+    }
+}
+
+/// Span records whose ΔS, ΔI and ΔE are drawn uniformly from `mean ± spread`.
+#[derive(Clone, Debug)]
+pub struct TrajectoryBuilder {
+    spans: usize,
+    entropy: (f64, f64),
+    information: (f64, f64),
+    energy: (f64, f64),
+    duration_ms: u64,
+    temperature: f64,
+    seed: u64,
+}
+
+impl TrajectoryBuilder {
+    pub fn new(spans: usize) -> Self {
+        Self {
+            spans,
+            entropy: (0.1, 0.0),
+            information: (0.05, 0.0),
+            energy: (-0.1, 0.0),
+            duration_ms: 1,
+            temperature: 300.0,
+            seed: DEFAULT_SEED,
+        }
+    }
+
+    pub fn with_entropy(mut self, mean: f64, spread: f64) -> Self {
+        self.entropy = (mean, spread);
+        self
+    }
+
+    pub fn with_information(mut self, mean: f64, spread: f64) -> Self {
+        self.information = (mean, spread);
+        self
+    }
+
+    pub fn with_energy(mut self, mean: f64, spread: f64) -> Self {
+        self.energy = (mean, spread);
+        self
+    }
+
+    pub fn with_duration_ms(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = duration_ms;
+        self
+    }
+
+    /// Temperature (K) used for the running Gibbs energy `ΣΔE − T ΣΔS`.
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Spans named `span-<n>`; Δθ follows the solver's ΔS = |Δθ| × 0.01 and
+    /// alternates in sign.
+    pub fn records(&self) -> Vec<SpanRecord> {
+        let mut rng = SimpleRng::seed_from_u64(self.seed);
+        let mut sample = |(mean, spread): (f64, f64)| {
+            if spread > 0.0 {
+                rng.gen_range(mean - spread..mean + spread)
+            } else {
+                mean
+            }
+        };
+        let (mut energy, mut entropy) = (0.0, 0.0);
+        (0..self.spans)
+            .map(|index| {
+                let mut record = SpanRecord::new(
+                    format!("span-{index}"),
+                    sample(self.entropy),
+                    sample(self.information),
+                    Duration::from_millis(self.duration_ms),
+                );
+                record.delta_energy = sample(self.energy);
+                let sign = if index % 2 == 0 { 1.0 } else { -1.0 };
+                record.delta_theta = sign * record.delta_entropy.abs() * 100.0;
+                energy += record.delta_energy;
+                entropy += record.delta_entropy;
+                record.gibbs_energy = energy - self.temperature * entropy;
+                record
+            })
+            .collect()
+    }
+
+    pub fn build(&self) -> Trajectory {
+        let mut trajectory = Trajectory::new();
+        for record in self.records() {
+            trajectory.push(record);
+        }
+        trajectory
+    }
+}
+
+/// [`ExecutionReport`] for a chain with synthetic applied and ghost spans,
+/// Metropolis rejections and fluctuating trajectory frames.
+#[derive(Clone, Debug)]
+pub struct ReportBuilder {
+    chain: PeptideChain,
+    applied: TrajectoryBuilder,
+    ghosts: TrajectoryBuilder,
+    rejections: usize,
+    frames: usize,
+    amplitude: f64,
+    seed: u64,
+}
+
+impl ReportBuilder {
+    pub fn new(chain: PeptideChain) -> Self {
+        Self {
+            chain,
+            applied: TrajectoryBuilder::new(0),
+            ghosts: TrajectoryBuilder::new(0),
+            rejections: 0,
+            frames: 0,
+            amplitude: 0.0,
+            seed: DEFAULT_SEED,
+        }
+    }
+
+    /// Accepted spans; they also fill `trajectory`.
+    pub fn with_applied(mut self, spans: TrajectoryBuilder) -> Self {
+        self.applied = spans;
+        self
+    }
+
+    pub fn with_ghosts(mut self, spans: TrajectoryBuilder) -> Self {
+        self.ghosts = spans;
+        self
+    }
+
+    /// Metropolis rejections, counted in the acceptance statistics.
+    pub fn with_rejections(mut self, rejections: usize) -> Self {
+        self.rejections = rejections;
+        self
+    }
+
+    /// Frames of the chain with each coordinate displaced uniformly by up to
+    /// `amplitude` Å.
+    pub fn with_frames(mut self, frames: usize, amplitude: f64) -> Self {
+        self.frames = frames;
+        self.amplitude = amplitude;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn build(&self) -> ExecutionReport {
+        let applied = self.applied.records();
+        let applied_rotations = self.outcomes(&applied, false);
+        let ghost_rotations = self.outcomes(&self.ghosts.records(), true);
+        let mut trajectory = Trajectory::new();
+        for record in &applied {
+            trajectory.push(record.clone());
+        }
+
+        let violations: Vec<ViolationRecord> = (0..self.rejections)
+            .map(|step| ViolationRecord {
+                step,
+                violation: RuleViolation::MetropolisRejected { delta_energy: 1.0 },
+            })
+            .collect();
+        let mut rng = SimpleRng::seed_from_u64(self.seed);
+        let step_ms = self.applied.duration_ms as f64;
+        let frames = (0..self.frames)
+            .map(|index| TrajectoryFrame {
+                time_ms: index as f64 * step_ms,
+                positions: self
+                    .chain
+                    .positions()
+                    .iter()
+                    .map(|position| {
+                        position.map(|value| {
+                            if self.amplitude > 0.0 {
+                                value + rng.gen_range(-self.amplitude..self.amplitude)
+                            } else {
+                                value
+                            }
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        ExecutionReport {
+            applied_rotations,
+            ghost_rotations,
+            rejections: violations
+                .iter()
+                .map(|record| record.violation.clone())
+                .collect(),
+            violations,
+            recoveries: Vec::new(),
+            final_energy: EnergyState {
+                total_potential: applied.iter().map(|record| record.delta_energy).sum(),
+                total_kinetic: 0.0,
+            },
+            energy_model: EnergyModel::default(),
+            trajectory,
+            metropolis_stats: MetropolisStats {
+                accepted: applied.len(),
+                rejected: self.rejections,
+            },
+            acceptance_series: Vec::new(),
+            acceptance_window: DEFAULT_ACCEPTANCE_WINDOW,
+            domains: Vec::new(),
+            frozen: Vec::new(),
+            restraints: Vec::new(),
+            restraint_energy: 0.0,
+            contact_prior_energy: 0.0,
+            chaperone_requirements: Vec::new(),
+            modifications: Vec::new(),
+            physics_level: PhysicsLevel::Toy,
+            physics_engine: PhysicsEngine::Auto,
+            physics_spans: Vec::new(),
+            physics_span_metrics: Vec::new(),
+            backend_failures: Vec::new(),
+            level_switches: Vec::new(),
+            final_chain: self.chain.clone(),
+            geometry: GeometryReport::default(),
+            frames,
+            frame_stride: 1,
+            memory_cap_exceeded_at: None,
+            refined_model: None,
+            ghost_budget_exceeded_at: None,
+            ghosts_pruned: 0,
+            stopped_by_observer: None,
+            stopped_by_ghost_budget: None,
+            rng_checkpoints: Vec::new(),
+            energy_components: Vec::new(),
+            frustration: None,
+            energy_samples: EnergySamples::new(),
+            score_deltas: Vec::new(),
+            collective_variables: Vec::new(),
+            thermo_discrepancies: Vec::new(),
+            wall_time: Duration::ZERO,
+            memory: MemoryUsage::default(),
+        }
+    }
+
+    /// Phi rotations cycling over the residues that have a predecessor.
+    fn outcomes(&self, records: &[SpanRecord], ghost: bool) -> Vec<RotationOutcome> {
+        let movable = self.chain.len().saturating_sub(1).max(1);
+        records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| RotationOutcome {
+                residue: ResidueId(1 + index % movable),
+                dihedral: Dihedral::Phi,
+                applied_angle: record.delta_theta,
+                span_record: record.clone(),
+                ghost,
+                physics_metrics: None,
+                collective_variables: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builders_produce_requested_statistics() {
+        let helix = ChainBuilder::new(8).with_shape(ChainShape::Helix).build();
+        let [a, b] = [helix.positions()[0], helix.positions()[1]];
+        let step = ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt();
+        assert!((step - 3.8).abs() < 0.1);
+        let coil = ChainBuilder::new(5)
+            .with_shape(ChainShape::RandomCoil)
+            .build();
+        assert_eq!(coil.len(), 5);
+
+        let spans = TrajectoryBuilder::new(50)
+            .with_entropy(0.2, 0.1)
+            .with_seed(3);
+        let trajectory = spans.build();
+        assert_eq!(trajectory.len(), 50);
+        assert!(
+            trajectory
+                .iter()
+                .all(|span| (0.1..0.3).contains(&span.delta_entropy))
+        );
+        assert!((trajectory.total_entropy() / 50.0 - 0.2).abs() < 0.05);
+        let reseeded = spans.clone().with_seed(4).records();
+        assert_ne!(spans.records()[7].delta_entropy, reseeded[7].delta_entropy);
+
+        let report = ReportBuilder::new(ChainBuilder::new(6).with_sequence("ACDEFG").build())
+            .with_applied(TrajectoryBuilder::new(4))
+            .with_ghosts(TrajectoryBuilder::new(2))
+            .with_rejections(3)
+            .with_frames(5, 0.5)
+            .build();
+        assert_eq!(report.metropolis_stats.accepted, 4);
+        assert_eq!(report.metropolis_stats.rejected, 3);
+        assert_eq!(report.rejections.len(), 3);
+        assert!(report.ghost_rotations.iter().all(|outcome| outcome.ghost));
+        assert_eq!(report.frames.len(), 5);
+        assert_ne!(report.frames[1].positions, report.frames[2].positions);
+        assert_eq!(report.final_chain.names()[1], "CYS");
+    }
+}
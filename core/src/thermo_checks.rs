@@ -0,0 +1,137 @@
+//! Energy bookkeeping checks enabled with
+//! [`FoldingEngineBuilder::with_thermo_checks`](crate::FoldingEngineBuilder::with_thermo_checks).
+//! Every accepted span's ΔE, G and temperature are compared with the values
+//! they should follow from, and mismatches are reported rather than fixed.
+
+/// Relative tolerance used by `--thermo-check`.
+pub const DEFAULT_THERMO_TOLERANCE: f64 = 1e-6;
+
+/// Quantity a discrepancy was found in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThermoCheck {
+    /// Span ΔE against the change in total potential across the span.
+    EnergyDelta,
+    /// ΔE a physics backend reported against the engine's ΔE.
+    BackendEnergy,
+    /// Span G against E − T·S with the thermostat temperature and the
+    /// trajectory's entropy (kcal/mol, K, kcal·mol⁻¹·K⁻¹).
+    GibbsEnergy,
+    /// Metropolis temperature of the span against the thermostat G uses.
+    Temperature,
+    /// Final potential against the initial potential plus Σ ΔE of the
+    /// trajectory, checked once at the end of a contract.
+    EnergyBalance,
+}
+
+impl ThermoCheck {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThermoCheck::EnergyDelta => "energy_delta",
+            ThermoCheck::BackendEnergy => "backend_energy",
+            ThermoCheck::GibbsEnergy => "gibbs_energy",
+            ThermoCheck::Temperature => "temperature",
+            ThermoCheck::EnergyBalance => "energy_balance",
+        }
+    }
+}
+
+/// A value that disagreed with the one it should equal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThermoDiscrepancy {
+    pub step: usize,
+    /// Span checked; empty for [`ThermoCheck::EnergyBalance`].
+    pub span_id: String,
+    pub check: ThermoCheck,
+    pub expected: f64,
+    pub actual: f64,
+}
+
+impl ThermoDiscrepancy {
+    pub fn error(&self) -> f64 {
+        (self.actual - self.expected).abs()
+    }
+}
+
+/// Numbers recorded for one accepted span, next to the ones they derive from.
+#[derive(Clone, Copy, Debug)]
+pub struct SpanBookkeeping {
+    pub potential_before: f64,
+    pub potential_after: f64,
+    pub delta_energy: f64,
+    pub backend_delta_energy: Option<f64>,
+    pub gibbs_energy: f64,
+    pub thermostat_temperature: f64,
+    pub span_temperature: f64,
+    /// Trajectory entropy including the span.
+    pub total_entropy: f64,
+}
+
+impl SpanBookkeeping {
+    /// Checks that failed, with the expected and recorded values.
+    pub fn check(&self, tolerance: f64) -> Vec<(ThermoCheck, f64, f64)> {
+        let delta = self.potential_after - self.potential_before;
+        let gibbs = self.potential_after - self.thermostat_temperature * self.total_entropy;
+        let mut pairs = vec![
+            (ThermoCheck::EnergyDelta, delta, self.delta_energy),
+            (ThermoCheck::GibbsEnergy, gibbs, self.gibbs_energy),
+            (
+                ThermoCheck::Temperature,
+                self.thermostat_temperature,
+                self.span_temperature,
+            ),
+        ];
+        if let Some(backend) = self.backend_delta_energy {
+            pairs.insert(1, (ThermoCheck::BackendEnergy, self.delta_energy, backend));
+        }
+        pairs.retain(|&(_, expected, actual)| !agrees(expected, actual, tolerance));
+        pairs
+    }
+}
+
+/// Equal within `tolerance`, relative to the larger magnitude (absolute
+/// below 1).
+pub fn agrees(expected: f64, actual: f64, tolerance: f64) -> bool {
+    (actual - expected).abs() <= tolerance * expected.abs().max(actual.abs()).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_each_inconsistent_quantity() {
+        let consistent = SpanBookkeeping {
+            potential_before: 10.0,
+            potential_after: 7.5,
+            delta_energy: -2.5,
+            backend_delta_energy: None,
+            gibbs_energy: 7.5 - 300.0 * 0.2,
+            thermostat_temperature: 300.0,
+            span_temperature: 300.0,
+            total_entropy: 0.2,
+        };
+        assert!(consistent.check(DEFAULT_THERMO_TOLERANCE).is_empty());
+
+        let inconsistent = SpanBookkeeping {
+            backend_delta_energy: Some(41.0),
+            gibbs_energy: 7.5 - 310.0 * 0.2,
+            span_temperature: 600.0,
+            ..consistent
+        };
+        let failed: Vec<ThermoCheck> = inconsistent
+            .check(DEFAULT_THERMO_TOLERANCE)
+            .into_iter()
+            .map(|(check, _, _)| check)
+            .collect();
+        assert_eq!(
+            failed,
+            [
+                ThermoCheck::BackendEnergy,
+                ThermoCheck::GibbsEnergy,
+                ThermoCheck::Temperature
+            ]
+        );
+        assert!(agrees(1e-9, 2e-9, 1e-6));
+        assert!(!agrees(1000.0, 1000.1, 1e-6));
+    }
+}
@@ -1,4 +1,4 @@
-use folding_molecule::{PeptideChain, ResidueId};
+use folding_molecule::{Dihedral, PeptideChain, ResidueId};
 use folding_time::trajectory::{SpanRecord, Trajectory};
 
 use crate::folding_ruleset::{RuleViolation, Ruleset};
@@ -20,13 +20,19 @@ impl Validator {
         Self { ruleset }
     }
 
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
+
     pub fn validate_rotation(
         &self,
         residue: ResidueId,
+        dihedral: Dihedral,
         angle: f64,
         chain: &PeptideChain,
     ) -> Result<(), RuleViolation> {
-        self.ruleset.validate_rotation(residue, angle, chain)
+        self.ruleset
+            .validate_rotation(residue, dihedral, angle, chain)
     }
 
     pub fn validate_span(&self, span: &SpanRecord, trajectory: &Trajectory) -> ValidationEvent {
@@ -41,4 +47,33 @@ impl Validator {
     pub fn validate_structure(&self, chain: &PeptideChain) -> Result<(), RuleViolation> {
         self.ruleset.check_structure(chain)
     }
+
+    /// Rejects moves that leave a residue they turned outside its
+    /// Ramachandran map, or a peptide bond they turned cis.
+    pub fn validate_torsions(
+        &self,
+        previous: &PeptideChain,
+        chain: &PeptideChain,
+    ) -> Result<(), RuleViolation> {
+        self.ruleset.check_ramachandran(previous, chain)?;
+        self.ruleset.check_omega(previous, chain)
+    }
+
+    /// Rejects moves whose members include a forbidden residue.
+    pub fn validate_members(
+        &self,
+        members: impl IntoIterator<Item = usize>,
+    ) -> Result<(), RuleViolation> {
+        members
+            .into_iter()
+            .try_for_each(|index| self.ruleset.check_residue_allowed(ResidueId(index)))
+    }
+
+    pub fn validate_energy_change(&self, delta_energy: f64) -> Result<(), RuleViolation> {
+        self.ruleset.check_energy_increase(delta_energy)
+    }
+
+    pub fn validate_ghost_ratio(&self, ghost: usize, applied: usize) -> Result<(), RuleViolation> {
+        self.ruleset.check_ghost_ratio(ghost, applied)
+    }
 }
@@ -1,25 +1,128 @@
+use folding_core::physics_bridge::PhysicsEngine;
 use folding_core::{
-    ContractInstruction, ExecutionReport, FoldingContract, FoldingEngineBuilder, MetropolisStats,
-    TemperatureSchedule,
+    CollectiveVariable, ContractMetadata, EnergyComponentSample, ExecutionReport, FoldingContract, FoldingEngineBuilder,
+    EnergySamples, GeometryReport,
+    MetropolisStats, PhysicsLevel, RngCheckpoint, Ruleset, ScoreExpression, TemperatureSchedule,
 };
 use folding_molecule::{EnergyModel, PeptideChain};
-use folding_sim::FoldingMetrics;
-use std::fs::{self, File};
-use std::io::Write;
+use folding_sim::{FoldingMetrics, compare_structures, quasi_harmonic_entropy};
+use folding_time::RotationClock;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::SystemTime;
+
+pub mod mmcif;
+pub mod model_score;
+pub mod pdb;
+pub mod predicted_model;
+#[cfg(feature = "upload")]
+pub mod remote_upload;
+pub mod sqlite_sink;
+
+pub use folding_core::{
+    ContactPrior, CrowdingModel, DielectricModel, Environment, FrustrationClass,
+    FrustrationOptions, FrustrationReport, GhostBudget, GhostLimit, GhostOverflow, PointGroup,
+    SideChainBead, Symmetry,
+};
+#[cfg(feature = "model-score")]
+pub use model_score::ModelScorer;
+pub use model_score::{ModelInput, ModelScoreConfig};
+#[cfg(feature = "alphafold")]
+pub use predicted_model::fetch_alphafold_model;
+pub use predicted_model::{
+    DEFAULT_PLDDT_CUTOFF, PredictedModel, alphafold_file_name, is_uniprot_accession,
+};
+#[cfg(feature = "upload")]
+pub use remote_upload::{RemoteStore, upload_artifacts};
+pub use sqlite_sink::{QueryResult, RUNS_QUERY, SqliteSink, VIOLATIONS_QUERY};
 
 /// Configuration for a folding shell invocation.
 #[derive(Clone, Debug)]
 pub struct ShellConfig {
+    /// Thermostat temperature (K) of physics spans.
     pub temperature: f64,
+    /// Metropolis temperature (K), when acceptance should not follow the
+    /// thermostat.
+    pub acceptance_temperature: Option<f64>,
     pub time_step_ms: u64,
+    /// Release accepted spans `time_step_ms` apart in wall-clock time.
+    pub real_time: bool,
     pub rng_seed: Option<u64>,
+    /// Span log path; when the file already exists the run writes
+    /// `<stem>_<run_id>.<ext>` beside it unless `overwrite_log` is set.
     pub log_path: Option<PathBuf>,
-    pub environment: String,
+    pub overwrite_log: bool,
+    /// User-chosen run name, logged next to the generated run id and used
+    /// in place of the contract label in default log file names.
+    pub run_name: Option<String>,
+    /// Solvent conditions handed to the engine; the name is logged.
+    pub environment: Environment,
     pub diamond_threshold: Option<f64>,
     pub diamond_path: Option<PathBuf>,
     pub temp_schedule: Option<TempScheduleConfig>,
+    /// Reference Cα trace scored against the final chain (TM-score, GDT_TS).
+    pub reference_structure: Option<Vec<[f64; 3]>>,
+    /// SQLite database that also receives each run (see [`SqliteSink`]).
+    pub database: Option<PathBuf>,
+    /// Object-storage root (`s3://` or `gs://`); each run is recorded under
+    /// `<root>/<run_id>` in its metadata.
+    pub remote_root: Option<String>,
+    /// Point-group symmetry; the chain is then one protomer of the assembly.
+    pub symmetry: Option<Symmetry>,
+    /// Side-chain bead grown from every residue's Cα (`--side-chains`);
+    /// `None` keeps the Cα-only model.
+    pub side_chains: Option<SideChainBead>,
+    /// Predicted contacts whose distance restraints join every energy
+    /// evaluation.
+    pub contact_prior: Option<ContactPrior>,
+    /// Level the engine starts at; contracts may still switch it.
+    pub physics_level: PhysicsLevel,
+    /// Backend physics spans are sent to; contracts may still switch it.
+    pub physics_engine: PhysicsEngine,
+    /// Abort the contract when a physics backend fails instead of degrading.
+    pub require_backend: bool,
+    /// Relative tolerance of the per-span energy bookkeeping checks
+    /// (`--thermo-check`); `None` leaves them off.
+    pub thermo_tolerance: Option<f64>,
+    /// Why `--level auto` picked `physics_level`, logged with the run.
+    pub level_choice: Option<String>,
+    /// Limits every span is validated against (`--rules`).
+    pub ruleset: Ruleset,
+    /// Cap on ghost spans and what happens once it is exceeded.
+    pub ghost_budget: Option<GhostBudget>,
+    /// Registered force field and integrator for physics spans; contracts may
+    /// still switch them.
+    pub force_field: Option<String>,
+    pub integrator: Option<String>,
+    /// MD timestep (ps) of physics spans in place of each level's default.
+    pub md_timestep: Option<f64>,
+    /// Named scores whose change is added to ΔE in the Metropolis criterion.
+    pub scores: Vec<(String, ScoreExpression)>,
+    /// Model server whose score joins the criterion as the `model` term.
+    pub model_score: Option<ModelScoreConfig>,
+    /// Directory caching OpenMM bridge responses per span (OpenMM builds only).
+    pub openmm_cache: Option<PathBuf>,
+    /// Directory native physics spans write their MD frames to.
+    pub native_trajectories: Option<PathBuf>,
+    /// Heap estimate, in bytes, the engine keeps the run's data under.
+    pub memory_cap: Option<usize>,
+    /// Collective variables evaluated after every accepted span and appended
+    /// to its log line.
+    pub collective_variables: Vec<CollectiveVariable>,
+    /// Engine RNG state, and the physics velocities with it, to resume from
+    /// instead of starting at the seed.
+    pub rng_state: Option<RngCheckpoint>,
+    /// Log the RNG state every this many accepted spans, besides commits and
+    /// the end of the run.
+    pub rng_log_interval: Option<usize>,
+    /// Record the force-field energy by term every this many accepted spans.
+    pub energy_component_stride: Option<usize>,
+    /// Analyse the final structure's frustration with these options.
+    pub frustration: Option<FrustrationOptions>,
+    /// Metropolis decisions in the rolling acceptance window; `None` keeps
+    /// the engine default.
+    pub acceptance_window: Option<usize>,
 }
 
 /// Linear annealing configuration for temperature.
@@ -59,7 +162,12 @@ impl InformationToRotation {
 /// Metadata persisted in span logs.
 #[derive(Debug, Clone)]
 pub struct LogMetadata {
+    /// ULID: 48-bit millisecond timestamp plus 80 random bits, so ids sort
+    /// by start time and do not collide across hosts.
     pub run_id: String,
+    pub run_name: Option<String>,
+    /// RFC 3339 UTC time the run started; durations come from `wall_time_ms`,
+    /// which is measured on the monotonic clock.
     pub timestamp: String,
     pub contract_name: Option<String>,
     pub environment: String,
@@ -72,6 +180,82 @@ pub struct LogMetadata {
     pub final_gibbs_energy: f64,
     pub informational_efficiency: f64,
     pub total_work: f64,
+    pub tm_score: Option<f64>,
+    pub gdt_ts: Option<f64>,
+    /// Sum of the per-span ΔS heuristics.
+    pub heuristic_entropy: f64,
+    /// Schlitter estimate (kcal·mol⁻¹·K⁻¹) over the recorded frames.
+    pub quasi_harmonic_entropy: Option<f64>,
+    /// Foldability index of the sampled energies and the gap (kcal/mol) it
+    /// divides by the decoy spread; `None` with fewer than two samples.
+    pub foldability: Option<f64>,
+    pub energy_gap: Option<f64>,
+    /// The statistics behind them, for pooling runs; empty on runs with no
+    /// scored conformations.
+    pub energy_samples: EnergySamples,
+    /// Summed span duration of accepted spans (contract time, ms).
+    pub span_time_ms: f64,
+    /// Physical time the accepted spans stand for, in ps.
+    pub simulated_time_ps: f64,
+    /// How long the engine took to run the contract.
+    pub wall_time_ms: f64,
+    /// Where the run's artifacts are uploaded, when a remote root is configured.
+    pub remote_uri: Option<String>,
+    /// Level picked by `--level auto` and its rationale.
+    pub level_choice: Option<String>,
+    pub provenance: Provenance,
+}
+
+/// Build and host details recorded with every run so results can be traced
+/// back to the binary that produced them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Provenance {
+    pub crate_version: String,
+    /// Short commit hash baked in at build time (`LOGLINE_GIT_COMMIT` overrides).
+    pub git_commit: String,
+    pub hostname: String,
+    pub os: String,
+    /// Physics level and engine selection, e.g. `toy/auto(native)`.
+    pub physics_backend: String,
+    /// Cargo features of this crate and the crates below it.
+    pub features: Vec<String>,
+}
+
+impl Provenance {
+    pub fn current(physics_level: PhysicsLevel, engine: PhysicsEngine) -> Self {
+        let mut features = folding_core::enabled_features();
+        let own = [
+            ("bundled-sqlite", cfg!(feature = "bundled-sqlite")),
+            ("upload", cfg!(feature = "upload")),
+            ("plugins", cfg!(feature = "plugins")),
+            ("model-score", cfg!(feature = "model-score")),
+            ("alphafold", cfg!(feature = "alphafold")),
+        ];
+        for (feature, _) in own.into_iter().filter(|(_, enabled)| *enabled) {
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: env!("LOGLINE_GIT_COMMIT").to_string(),
+            hostname: hostname(),
+            os: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+            physics_backend: format!("{}/{}", physics_level.as_str(), engine.backend_label()),
+            features: features.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+fn hostname() -> String {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok())
+        .or_else(|| fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".into())
 }
 
 /// Span representation compatible with the CLI replay command.
@@ -84,12 +268,23 @@ pub struct FoldSpan {
     pub delta_I: f64,
     pub delta_E: f64,
     pub duration_ms: u64,
+    pub simulated_ps: f64,
     pub ghost_flag: bool,
     pub G: f64,
+    /// Metropolis temperature (K) the span was judged at.
+    pub temperature: f64,
+    /// Physics backend that serviced the span; `None` for toy spans.
+    pub backend: Option<String>,
+    /// MD frames the backend wrote for the span, if any.
+    pub trajectory: Option<String>,
+    /// Contract tags of the span's instruction.
+    pub tags: Vec<String>,
+    /// Phase path (`anneal/cooling`); empty outside any phase.
+    pub phase: String,
 }
 
 impl FoldSpan {
-    fn from_outcome(outcome: &folding_core::RotationOutcome) -> Self {
+    pub(crate) fn from_outcome(outcome: &folding_core::RotationOutcome) -> Self {
         Self {
             id: outcome.span_record.id.clone(),
             delta_theta: outcome.span_record.delta_theta,
@@ -97,23 +292,51 @@ impl FoldSpan {
             delta_I: outcome.span_record.delta_information,
             delta_E: outcome.span_record.delta_energy,
             duration_ms: outcome.span_record.duration.as_millis() as u64,
+            simulated_ps: outcome.span_record.simulated_ps,
             ghost_flag: outcome.ghost,
             G: outcome.span_record.gibbs_energy,
+            temperature: outcome.span_record.temperature,
+            backend: outcome
+                .physics_metrics
+                .as_ref()
+                .map(|metrics| metrics.backend.to_string()),
+            trajectory: outcome
+                .physics_metrics
+                .as_ref()
+                .and_then(|metrics| metrics.trajectory_path.clone()),
+            tags: outcome.span_record.tags.clone(),
+            phase: outcome.span_record.phase_path(),
         }
     }
 
     fn to_line(&self) -> String {
-        format!(
-            "span|id={}|delta_theta={:.6}|delta_S={:.6}|delta_I={:.6}|delta_E={:.6}|duration_ms={}|ghost_flag={}|G={:.6}",
+        let mut line = format!(
+            "span|id={}|delta_theta={:.6}|delta_S={:.6}|delta_I={:.6}|delta_E={:.6}|duration_ms={}|simulated_ps={:.6}|ghost_flag={}|G={:.6}|temperature={:.3}",
             escape_field(&self.id),
             self.delta_theta,
             self.delta_S,
             self.delta_I,
             self.delta_E,
             self.duration_ms,
+            self.simulated_ps,
             if self.ghost_flag { 1 } else { 0 },
-            self.G
-        )
+            self.G,
+            self.temperature
+        );
+        if let Some(backend) = &self.backend {
+            line.push_str(&format!("|backend={}", escape_field(backend)));
+        }
+        if let Some(trajectory) = &self.trajectory {
+            line.push_str(&format!("|trajectory={}", escape_field(trajectory)));
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| escape_field(tag)).collect();
+            line.push_str(&format!("|tags={}", tags.join(",")));
+        }
+        if !self.phase.is_empty() {
+            line.push_str(&format!("|phase={}", escape_field(&self.phase)));
+        }
+        line
     }
 }
 
@@ -128,74 +351,97 @@ impl LogLineWriter {
 
     pub fn write_report(
         &mut self,
-        path: &Path,
+        file: &mut impl Write,
         metadata: &LogMetadata,
         report: &ExecutionReport,
     ) -> Result<(), std::io::Error> {
-        if let Some(parent) = path.parent() {
-            if !parent.as_os_str().is_empty() {
-                fs::create_dir_all(parent)?;
-            }
-        }
-        let mut file = File::create(path)?;
         writeln!(file, "{}", metadata_line(metadata))?;
 
         for outcome in &report.applied_rotations {
             let span = FoldSpan::from_outcome(outcome);
-            writeln!(file, "{}", span.to_line())?;
+            let variables: String = report
+                .collective_variables
+                .iter()
+                .zip(&outcome.collective_variables)
+                .map(|(name, value)| format!("|cv.{name}={value:.6}"))
+                .collect();
+            writeln!(file, "{}{variables}", span.to_line())?;
         }
         for outcome in &report.ghost_rotations {
             let mut span = FoldSpan::from_outcome(outcome);
             span.ghost_flag = true;
             writeln!(file, "{}", span.to_line())?;
         }
-        for violation in &report.rejections {
+        for phase in report.trajectory.phases() {
             writeln!(
                 file,
-                "violation|detail={}",
-                escape_field(&format!("{violation:?}"))
+                "phase|path={}|depth={}|spans={}|delta_S={:.6}|delta_E={:.6}|duration_ms={}|simulated_ps={:.6}",
+                escape_field(&phase.path),
+                phase.depth,
+                phase.spans,
+                phase.delta_entropy,
+                phase.delta_energy,
+                phase.duration.as_millis(),
+                phase.simulated_ps
             )?;
         }
-        Ok(())
-    }
-}
-
-/// Description of environmental presets used by the CLI.
-#[derive(Clone, Debug)]
-pub struct EnvironmentPreset {
-    pub name: String,
-    pub default_temperature: f64,
-}
-
-impl EnvironmentPreset {
-    pub fn aqueous() -> Self {
-        Self {
-            name: "aqueous".into(),
-            default_temperature: 298.0,
+        for record in &report.violations {
+            let fields: String = record
+                .fields()
+                .iter()
+                .map(|(key, value)| format!("|{key}={}", escape_field(value)))
+                .collect();
+            writeln!(file, "violation{fields}")?;
         }
-    }
-
-    pub fn cytosol() -> Self {
-        Self {
-            name: "cytosol".into(),
-            default_temperature: 310.0,
+        for record in &report.backend_failures {
+            writeln!(
+                file,
+                "backend_failure|step={}|span={}|backend={}|fallback={}|reason={}|stderr={}",
+                record.step,
+                escape_field(&record.span_id),
+                record.failure.backend,
+                record.fallback,
+                escape_field(&record.failure.reason.replace('\n', " ")),
+                escape_field(&record.failure.stderr.replace('\n', "\\n"))
+            )?;
         }
-    }
-
-    pub fn vacuum() -> Self {
-        Self {
-            name: "vacuum".into(),
-            default_temperature: 295.0,
+        for discrepancy in &report.thermo_discrepancies {
+            writeln!(
+                file,
+                "thermo|step={}|span={}|check={}|expected={:.6}|actual={:.6}",
+                discrepancy.step,
+                escape_field(&discrepancy.span_id),
+                discrepancy.check.as_str(),
+                discrepancy.expected,
+                discrepancy.actual
+            )?;
         }
-    }
-
-    pub fn by_name(name: &str) -> Option<Self> {
-        match name.to_ascii_lowercase().as_str() {
-            "aqueous" => Some(Self::aqueous()),
-            "cytosol" => Some(Self::cytosol()),
-            "vacuum" => Some(Self::vacuum()),
-            _ => None,
+        for line in geometry_lines(&report.geometry) {
+            writeln!(file, "{line}")?;
+        }
+        for sample in &report.acceptance_series {
+            writeln!(
+                file,
+                "acceptance|step={}|accepted={}|total={}|rate={:.6}|window={}",
+                sample.step,
+                sample.accepted,
+                sample.total,
+                sample.rate(),
+                report.acceptance_window
+            )?;
         }
+        for checkpoint in &report.rng_checkpoints {
+            write!(
+                file,
+                "rng|step={}|state={:016x}",
+                checkpoint.step, checkpoint.state
+            )?;
+            if !checkpoint.velocities.is_empty() {
+                write!(file, "|velocities={}", checkpoint.velocities)?;
+            }
+            writeln!(file)?;
+        }
+        Ok(())
     }
 }
 
@@ -208,6 +454,14 @@ pub struct PresetPack {
 
 pub struct PresetLoader;
 
+/// A `.lll` file found on disk together with its front-matter.
+#[derive(Debug, Clone)]
+pub struct ContractEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub metadata: ContractMetadata,
+}
+
 impl PresetLoader {
     pub fn load_preset(name: &str) -> Option<PresetPack> {
         if name.eq_ignore_ascii_case("demo") {
@@ -216,19 +470,84 @@ impl PresetLoader {
         Self::load_from_contract(name)
     }
 
+    /// Directories searched for presets, highest priority first: each entry of
+    /// `$LOGLINE_CONTRACT_PATH`, `./contracts`, then
+    /// `$XDG_CONFIG_HOME/logline/contracts` (or `~/.config/logline/contracts`).
+    pub fn search_paths() -> Vec<PathBuf> {
+        let var = |key: &str| std::env::var_os(key).filter(|value| !value.is_empty());
+        preset_search_paths(
+            var("LOGLINE_CONTRACT_PATH"),
+            var("XDG_CONFIG_HOME").map(PathBuf::from),
+            var("HOME").map(PathBuf::from),
+        )
+    }
+
+    /// Contract file backing a preset: the first match along [`Self::search_paths`].
+    pub fn preset_path(name: &str) -> Option<PathBuf> {
+        let file = if name.eq_ignore_ascii_case("demo") {
+            "demo_chain.lll".to_string()
+        } else {
+            format!("{name}.lll")
+        };
+        Self::search_paths()
+            .into_iter()
+            .map(|dir| dir.join(&file))
+            .find(|path| path.is_file())
+    }
+
+    /// Every preset visible along the search paths; earlier directories shadow
+    /// later ones with the same name.
+    pub fn available_presets() -> Vec<ContractEntry> {
+        let mut presets: Vec<ContractEntry> = Vec::new();
+        for dir in Self::search_paths() {
+            let Ok(entries) = Self::contracts_in(&dir) else {
+                continue;
+            };
+            for entry in entries {
+                if !presets.iter().any(|known| known.name == entry.name) {
+                    presets.push(entry);
+                }
+            }
+        }
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        presets
+    }
+
+    /// Contracts in `dir`, sorted by name.
+    pub fn contracts_in(dir: &Path) -> Result<Vec<ContractEntry>, String> {
+        let entries = fs::read_dir(dir)
+            .map_err(|err| format!("failed to read contract directory {}: {err}", dir.display()))?;
+        let mut contracts = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("lll") {
+                continue;
+            }
+            let contract = InputLoader::load_contract(&path)?;
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            contracts.push(ContractEntry {
+                name,
+                path,
+                metadata: contract.metadata,
+            });
+        }
+        contracts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(contracts)
+    }
+
     fn load_demo() -> Option<PresetPack> {
-        let contract_path = Path::new("contracts/demo_chain.lll");
-        let contract = InputLoader::load_contract(contract_path).ok()?;
+        let contract_path = Self::preset_path("demo")?;
+        let contract = InputLoader::load_contract(&contract_path).ok()?;
         let chain =
             chain_for_contract(&contract).unwrap_or_else(|| PeptideChain::from_sequence("ACDEFGH"));
         Some(PresetPack { chain, contract })
     }
 
     fn load_from_contract(name: &str) -> Option<PresetPack> {
-        let contract_path = Path::new("contracts").join(format!("{name}.lll"));
-        if !contract_path.exists() {
-            return None;
-        }
+        let contract_path = Self::preset_path(name)?;
         let contract = InputLoader::load_contract(&contract_path).ok()?;
         let chain = chain_for_contract(&contract)
             .unwrap_or_else(|| PeptideChain::from_sequence("ACDEFGHIK"));
@@ -236,11 +555,27 @@ impl PresetLoader {
     }
 }
 
+fn preset_search_paths(
+    contract_path: Option<std::ffi::OsString>,
+    xdg_config_home: Option<PathBuf>,
+    home: Option<PathBuf>,
+) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = contract_path
+        .map(|raw| std::env::split_paths(&raw).collect())
+        .unwrap_or_default();
+    paths.push(PathBuf::from("contracts"));
+    if let Some(config) = xdg_config_home.or_else(|| home.map(|home| home.join(".config"))) {
+        paths.push(config.join("logline").join("contracts"));
+    }
+    paths.dedup();
+    paths
+}
+
 fn chain_for_contract(contract: &FoldingContract) -> Option<PeptideChain> {
     let mut max_index: Option<usize> = None;
     for instruction in &contract.instructions {
-        if let ContractInstruction::Rotate { residue, .. } = instruction {
-            let idx = residue.0;
+        if let Some(span) = instruction.rotation_span() {
+            let idx = span.last.0.max(span.first.0);
             max_index = Some(max_index.map(|current| current.max(idx)).unwrap_or(idx));
         }
     }
@@ -275,11 +610,17 @@ impl InputLoader {
         Ok(PeptideChain::from_sequence(&sequence))
     }
 
+    /// Starts from an experimental structure: the first chain of an
+    /// mmCIF/PDBx file at its Cα coordinates, with measured phi/psi.
+    pub fn load_mmcif(path: &Path) -> Result<PeptideChain, String> {
+        mmcif::load_mmcif(path)
+    }
+
     pub fn load_contract(path: &Path) -> Result<FoldingContract, String> {
         let contents = fs::read_to_string(path)
             .map_err(|err| format!("failed to read contract {}: {err}", path.display()))?;
-        let lines: Vec<&str> = contents.lines().collect();
-        Ok(FoldingContract::from_lines(&lines))
+        FoldingContract::parse(&contents)
+            .map_err(|err| format!("invalid contract {}: {err}", path.display()))
     }
 }
 
@@ -290,6 +631,9 @@ pub struct CommandShell {
     config: ShellConfig,
     last_log_path: Option<PathBuf>,
     last_diamond_path: Option<PathBuf>,
+    last_database_path: Option<PathBuf>,
+    last_remote_uri: Option<String>,
+    last_metadata: Option<LogMetadata>,
     contract_label: Option<String>,
 }
 
@@ -305,6 +649,9 @@ impl CommandShell {
             config,
             last_log_path: None,
             last_diamond_path: None,
+            last_database_path: None,
+            last_remote_uri: None,
+            last_metadata: None,
             contract_label: None,
         }
     }
@@ -325,47 +672,193 @@ impl CommandShell {
         self.last_diamond_path.as_ref()
     }
 
+    pub fn last_database_path(&self) -> Option<&PathBuf> {
+        self.last_database_path.as_ref()
+    }
+
+    /// Remote URI recorded in the last run's metadata.
+    pub fn last_remote_uri(&self) -> Option<&str> {
+        self.last_remote_uri.as_deref()
+    }
+
+    /// Metadata written with the last run, including its run id and provenance.
+    pub fn last_metadata(&self) -> Option<&LogMetadata> {
+        self.last_metadata.as_ref()
+    }
+
     pub fn run_contract(
         &mut self,
         chain: PeptideChain,
         contract: FoldingContract,
     ) -> ExecutionReport {
+        let started = SystemTime::now();
+        let chain_len = chain.len();
         let mut builder = FoldingEngineBuilder::new()
             .with_chain(chain)
             .with_energy_model(EnergyModel::default())
             .with_temperature(self.config.temperature)
-            .with_ruleset(folding_core::Ruleset::default());
+            .with_clock(if self.config.real_time {
+                RotationClock::real_time(self.config.time_step_ms)
+            } else {
+                RotationClock::new(self.config.time_step_ms)
+            })
+            .with_ruleset(self.config.ruleset.clone())
+            .with_environment(self.config.environment.clone())
+            .with_physics_level(self.config.physics_level)
+            .with_physics_engine(self.config.physics_engine)
+            .with_require_backend(self.config.require_backend);
 
         if let Some(seed) = self.config.rng_seed {
             builder = builder.with_rng_seed(seed);
         }
+        if let Some(checkpoint) = self.config.rng_state.clone() {
+            builder = builder
+                .with_rng_state(checkpoint.state)
+                .with_resume_step(checkpoint.step)
+                .with_native_velocities(checkpoint.velocities);
+        }
+        if let Some(tolerance) = self.config.thermo_tolerance {
+            builder = builder.with_thermo_checks(tolerance);
+        }
+        if let Some(spans) = self.config.rng_log_interval {
+            builder = builder.with_rng_log_interval(spans);
+        }
+        if let Some(spans) = self.config.energy_component_stride {
+            builder = builder.with_energy_component_stride(spans);
+        }
+        if let Some(options) = self.config.frustration {
+            builder = builder.with_frustration_analysis(options);
+        }
+        if let Some(decisions) = self.config.acceptance_window {
+            builder = builder.with_acceptance_window(decisions);
+        }
+        if let Some(temperature) = self.config.acceptance_temperature {
+            builder = builder.with_acceptance_temperature(temperature);
+        }
+        if let Some(symmetry) = self.config.symmetry.clone() {
+            builder = builder.with_symmetry(symmetry);
+        }
+        if let Some(bead) = self.config.side_chains {
+            builder = builder.with_side_chains(bead);
+        }
+        if let Some(prior) = self.config.contact_prior.clone() {
+            builder = builder.with_contact_prior(prior);
+        }
+        if let Some(budget) = self.config.ghost_budget {
+            builder = builder.with_ghost_budget(budget);
+        }
+        if let Some(bytes) = self.config.memory_cap {
+            builder = builder.with_memory_cap(bytes);
+        }
+        if let Some(name) = self.config.force_field.clone() {
+            builder = builder.with_force_field(name);
+        }
+        if let Some(name) = self.config.integrator.clone() {
+            builder = builder.with_integrator(name);
+        }
+        if let Some(picoseconds) = self.config.md_timestep {
+            builder = builder.with_md_timestep(picoseconds);
+        }
+        if let Some(dir) = self.config.openmm_cache.clone() {
+            builder = builder.with_openmm_cache(dir);
+        }
+        if let Some(dir) = self.config.native_trajectories.clone() {
+            builder = builder.with_native_trajectories(dir);
+        }
+        for (name, expression) in self.config.scores.clone() {
+            builder = builder.with_score(name, move |chain| expression.evaluate(chain));
+        }
+        for variable in self.config.collective_variables.iter().cloned() {
+            builder = builder.with_collective_variable(variable);
+        }
         if let Some(schedule) = self.config.temp_schedule.clone() {
             builder = builder.with_temperature_schedule(schedule.into());
         }
+        #[cfg(feature = "model-score")]
+        let model_scorer = self
+            .config
+            .model_score
+            .clone()
+            .map(|config| std::sync::Arc::new(ModelScorer::new(config)));
+        #[cfg(feature = "model-score")]
+        if let Some(scorer) = model_scorer.clone() {
+            // A failed request scores NaN, which rejects the move
+            builder = builder.with_score("model", move |chain| {
+                scorer.score(chain).unwrap_or(f64::NAN)
+            });
+        }
+        #[cfg(not(feature = "model-score"))]
+        if self.config.model_score.is_some() {
+            log::warn!("model scoring needs the `model-score` feature; ignoring the model server");
+        }
         let mut engine = builder.build();
+        log::info!(
+            "executing contract with {} instructions on {} residues",
+            contract.instructions.len(),
+            chain_len
+        );
         let report = engine.execute_contract(&contract);
+        #[cfg(feature = "model-score")]
+        if let Some((requests, failures)) = model_scorer.map(|scorer| scorer.stats())
+            && failures > 0
+        {
+            log::warn!("model server: {failures} of {requests} requests failed");
+        }
 
         let metrics = FoldingMetrics::from_report(&report);
         let run_id = generate_run_id();
-        let metadata = self.build_metadata(&report, &metrics, &run_id);
-        let log_path = self.resolve_log_path(&run_id);
-        if let Err(err) = self.writer.write_report(&log_path, &metadata, &report) {
-            eprintln!("failed to write span log {}: {err}", log_path.display());
+        let metadata = self.build_metadata(&report, &metrics, &run_id, started);
+        self.last_remote_uri = metadata.remote_uri.clone();
+        let (log_path, file) = self.create_log(&run_id);
+        if let Some(requested) = self.config.log_path.as_ref()
+            && requested != &log_path
+        {
+            log::warn!(
+                "{} already exists; writing this run to {} (--overwrite-log replaces it)",
+                requested.display(),
+                log_path.display()
+            );
+        }
+        let written =
+            file.and_then(|mut file| self.writer.write_report(&mut file, &metadata, &report));
+        if let Err(err) = written {
+            log::error!("failed to write span log {}: {err}", log_path.display());
             self.last_log_path = None;
         } else {
+            log::info!("run {run_id}: span log written to {}", log_path.display());
             self.last_log_path = Some(log_path);
         }
 
+        self.last_database_path = None;
+        if let Some(database) = self.config.database.clone() {
+            let stored = SqliteSink::open(&database).and_then(|mut sink| {
+                sink.record_run(&metadata, &report, self.last_log_path.as_deref())
+            });
+            match stored {
+                Ok(()) => self.last_database_path = Some(database),
+                Err(err) => log::error!("failed to store run in {}: {err}", database.display()),
+            }
+        }
+
         self.last_diamond_path = None;
+        self.last_metadata = Some(metadata);
         report
     }
 
-    fn resolve_log_path(&self, run_id: &str) -> PathBuf {
+    /// Creates the run's span log and returns it with the path it took.
+    fn create_log(&self, run_id: &str) -> (PathBuf, io::Result<File>) {
         if let Some(custom) = self.config.log_path.as_ref() {
-            return custom.clone();
+            return create_log_file(custom, self.config.overwrite_log, run_id);
         }
-        let label = self.contract_label.as_deref().unwrap_or("fold");
-        Path::new("logs").join(format!("{}_{}.log", label, run_id))
+        let label = self
+            .config
+            .run_name
+            .as_deref()
+            .or(self.contract_label.as_deref())
+            .unwrap_or("fold");
+        let path = Path::new("logs").join(format!("{}_{}.log", label, run_id));
+        let file = open_log_file(&path, false);
+        (path, file)
     }
 
     fn build_metadata(
@@ -373,9 +866,10 @@ impl CommandShell {
         report: &ExecutionReport,
         metrics: &FoldingMetrics,
         run_id: &str,
+        started: SystemTime,
     ) -> LogMetadata {
         let stats: &MetropolisStats = &report.metropolis_stats;
-        let timestamp = current_timestamp();
+        let timestamp = folding_time::rfc3339(started);
         let accepted = report.applied_rotations.len();
         let rejected = report.rejections.len();
         let total_entropy = report.trajectory.total_entropy();
@@ -386,11 +880,25 @@ impl CommandShell {
         } else {
             metrics.total_entropy / (metrics.total_entropy + metrics.ghost_entropy)
         };
+        let similarity = self
+            .config
+            .reference_structure
+            .as_ref()
+            .and_then(|reference| {
+                let model: Vec<[f64; 3]> = report
+                    .final_chain
+                    .residues()
+                    .map(|residue| residue.position())
+                    .collect();
+                compare_structures(&model, reference)
+            });
+        let foldability = report.energy_samples.foldability();
         LogMetadata {
             run_id: run_id.to_string(),
+            run_name: self.config.run_name.clone(),
             timestamp,
             contract_name: self.contract_label.clone(),
-            environment: self.config.environment.clone(),
+            environment: self.config.environment.name.clone(),
             temperature: self.config.temperature,
             time_step_ms: self.config.time_step_ms,
             accepted_spans: accepted,
@@ -400,6 +908,23 @@ impl CommandShell {
             final_gibbs_energy: final_gibbs,
             informational_efficiency: efficiency,
             total_work: compute_total_work(report),
+            tm_score: similarity.as_ref().map(|scores| scores.tm_score),
+            gdt_ts: similarity.as_ref().map(|scores| scores.gdt_ts),
+            heuristic_entropy: total_entropy,
+            quasi_harmonic_entropy: quasi_harmonic_entropy(&report.frames, self.config.temperature),
+            foldability: foldability.map(|foldability| foldability.index),
+            energy_gap: foldability.map(|foldability| foldability.gap),
+            energy_samples: report.energy_samples,
+            span_time_ms: report.trajectory.total_duration().as_secs_f64() * 1000.0,
+            simulated_time_ps: report.trajectory.total_simulated_ps(),
+            wall_time_ms: report.wall_time.as_secs_f64() * 1000.0,
+            remote_uri: self
+                .config
+                .remote_root
+                .as_ref()
+                .map(|root| format!("{}/{}", root.trim_end_matches('/'), run_id)),
+            level_choice: self.config.level_choice.clone(),
+            provenance: Provenance::current(report.physics_level, report.physics_engine),
         }
     }
 }
@@ -416,7 +941,7 @@ fn compute_total_work(report: &ExecutionReport) -> f64 {
 }
 
 fn metadata_line(metadata: &LogMetadata) -> String {
-    format!(
+    let mut line = format!(
         "metadata|run_id={}|timestamp={}|contract_name={}|environment={}|temperature={:.6}|time_step_ms={}|accepted_spans={}|rejected_spans={}|acceptance_rate={:.6}|final_potential_energy={:.6}|final_gibbs_energy={:.6}|informational_efficiency={:.6}|total_work={:.6}",
         escape_field(&metadata.run_id),
         escape_field(&metadata.timestamp),
@@ -431,26 +956,201 @@ fn metadata_line(metadata: &LogMetadata) -> String {
         metadata.final_gibbs_energy,
         metadata.informational_efficiency,
         metadata.total_work
-    )
+    );
+    if let Some(run_name) = metadata.run_name.as_deref() {
+        line.push_str(&format!("|run_name={}", escape_field(run_name)));
+    }
+    if let Some(tm_score) = metadata.tm_score {
+        line.push_str(&format!("|tm_score={tm_score:.6}"));
+    }
+    if let Some(gdt_ts) = metadata.gdt_ts {
+        line.push_str(&format!("|gdt_ts={gdt_ts:.6}"));
+    }
+    line.push_str(&format!(
+        "|heuristic_entropy={:.6}",
+        metadata.heuristic_entropy
+    ));
+    if let Some(entropy) = metadata.quasi_harmonic_entropy {
+        line.push_str(&format!("|quasi_harmonic_entropy={entropy:.6}"));
+    }
+    if let (Some(index), Some(gap)) = (metadata.foldability, metadata.energy_gap) {
+        line.push_str(&format!("|foldability={index:.6}|energy_gap={gap:.6}"));
+    }
+    if !metadata.energy_samples.is_empty() {
+        line.push_str(&format!("|energy_samples={}", metadata.energy_samples));
+    }
+    line.push_str(&format!(
+        "|span_time_ms={:.3}|simulated_time_ps={:.6}|wall_time_ms={:.3}",
+        metadata.span_time_ms, metadata.simulated_time_ps, metadata.wall_time_ms
+    ));
+    if let Some(remote_uri) = metadata.remote_uri.as_deref() {
+        line.push_str(&format!("|remote_uri={}", escape_field(remote_uri)));
+    }
+    if let Some(choice) = metadata.level_choice.as_deref() {
+        line.push_str(&format!("|level_choice={}", escape_field(choice)));
+    }
+    let provenance = &metadata.provenance;
+    line.push_str(&format!(
+        "|crate_version={}|git_commit={}|hostname={}|os={}|physics_backend={}|features={}",
+        escape_field(&provenance.crate_version),
+        escape_field(&provenance.git_commit),
+        escape_field(&provenance.hostname),
+        escape_field(&provenance.os),
+        escape_field(&provenance.physics_backend),
+        escape_field(&provenance.features.join(","))
+    ));
+    line
+}
+
+/// Renders the geometry validation section appended to span logs.
+pub fn geometry_lines(report: &GeometryReport) -> Vec<String> {
+    let mut lines = Vec::with_capacity(report.checks.len() + report.issues.len());
+    for check in &report.checks {
+        lines.push(format!(
+            "geometry|check={}|status={}|checked={}|failures={}|min={:.6}|max={:.6}",
+            check.kind.as_str(),
+            check.status.as_str(),
+            check.checked,
+            check.failures,
+            check.min,
+            check.max
+        ));
+    }
+    for issue in &report.issues {
+        let residues: Vec<String> = issue.residues.iter().map(|id| id.0.to_string()).collect();
+        lines.push(format!(
+            "geometry_issue|check={}|residues={}|value={:.6}|min={:.6}|max={:.6}",
+            issue.kind.as_str(),
+            residues.join(","),
+            issue.value,
+            issue.min,
+            issue.max
+        ));
+    }
+    lines
+}
+
+/// Renders [`ExecutionReport::energy_components`] as CSV, one row per
+/// sample, energies in kcal/mol.
+pub fn energy_components_csv(samples: &[EnergyComponentSample]) -> String {
+    let mut csv =
+        String::from("step,time_ms,level,bond,angle,dihedral,nonbonded,solvation,total\n");
+    for sample in samples {
+        let terms = &sample.components;
+        csv.push_str(&format!(
+            "{},{:.3},{},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6}\n",
+            sample.step,
+            sample.time_ms,
+            sample.level.as_str(),
+            terms.bond,
+            terms.angle,
+            terms.dihedral,
+            terms.nonbonded,
+            terms.solvation,
+            terms.total()
+        ));
+    }
+    csv
+}
+
+/// Renders the per-residue attribution of [`ExecutionReport::energy_components`]
+/// as long-format CSV, one row per sample and residue; `names` are the chain's
+/// residue names. Samples whose force field could not attribute its energy are
+/// left out.
+pub fn residue_energies_csv(samples: &[EnergyComponentSample], names: &[String]) -> String {
+    let mut csv = String::from("step,time_ms,level,residue,name,energy\n");
+    for sample in samples {
+        for (index, energy) in sample.residues.iter().enumerate() {
+            csv.push_str(&format!(
+                "{},{:.3},{},{},{},{:.6}\n",
+                sample.step,
+                sample.time_ms,
+                sample.level.as_str(),
+                index,
+                names.get(index).map_or("UNK", String::as_str),
+                energy
+            ));
+        }
+    }
+    csv
+}
+
+/// Renders [`ExecutionReport::frustration`] as CSV, one row per contact;
+/// `names` are the chain's residue names.
+pub fn frustration_csv(report: &FrustrationReport, names: &[String]) -> String {
+    let name = |index: usize| names.get(index).map_or("UNK", String::as_str);
+    let mut csv = String::from(
+        "i,j,name_i,name_j,distance,native,decoy_mean,decoy_std,frustration_index,class\n",
+    );
+    for contact in &report.contacts {
+        csv.push_str(&format!(
+            "{},{},{},{},{:.3},{:.6},{:.6},{:.6},{:.4},{}\n",
+            contact.i,
+            contact.j,
+            name(contact.i),
+            name(contact.j),
+            contact.distance,
+            contact.native,
+            contact.decoy_mean,
+            contact.decoy_std,
+            contact.index,
+            contact.class.as_str()
+        ));
+    }
+    csv
 }
 
 fn escape_field(value: &str) -> String {
-    value.replace('|', "_").replace('=', "_")
+    value.replace(['|', '='], "_")
 }
 
 fn generate_run_id() -> String {
-    let nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
-    format!("{:x}", nanos)
+    ulid::Ulid::new().to_string()
+}
+
+/// Creates `requested`, or `<stem>_<run_id>.<ext>` beside it when it is
+/// already taken and `overwrite` is off. The file is claimed with
+/// `create_new`, so a log appearing between the check and the write is
+/// never clobbered.
+fn create_log_file(requested: &Path, overwrite: bool, run_id: &str) -> (PathBuf, io::Result<File>) {
+    match open_log_file(requested, overwrite) {
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            let path = with_run_id(requested, run_id);
+            let file = open_log_file(&path, false);
+            (path, file)
+        }
+        file => (requested.to_path_buf(), file),
+    }
+}
+
+/// Opens `path` for writing, creating its directory; an existing file is
+/// truncated when `overwrite` is set and an `AlreadyExists` error otherwise.
+fn open_log_file(path: &Path, overwrite: bool) -> io::Result<File> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    if overwrite {
+        options.write(true).create(true).truncate(true);
+    } else {
+        options.write(true).create_new(true);
+    }
+    options.open(path)
 }
 
-fn current_timestamp() -> String {
-    let duration = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
+/// `logs/run.log` → `logs/run_<run_id>.log`, for a log path already taken.
+fn with_run_id(path: &Path, run_id: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
         .unwrap_or_default();
-    format!("{}.{:09}", duration.as_secs(), duration.subsec_nanos())
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}_{run_id}.{}", ext.to_string_lossy()),
+        None => format!("{stem}_{run_id}"),
+    };
+    path.with_file_name(name)
 }
 
 /// Convenience alias re-exported for CLI consumers.
@@ -462,3 +1162,213 @@ pub fn compute_sequence_length(contract: &FoldingContract) -> usize {
         .map(|chain| chain.residues().len())
         .unwrap_or(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_search_paths_follow_env_then_cwd_then_xdg() {
+        let joined = std::env::join_paths(["/opt/a", "/opt/b"]).unwrap();
+        let paths = preset_search_paths(Some(joined), None, Some(PathBuf::from("/home/ada")));
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("/opt/a"),
+                PathBuf::from("/opt/b"),
+                PathBuf::from("contracts"),
+                PathBuf::from("/home/ada/.config/logline/contracts"),
+            ]
+        );
+        let xdg = preset_search_paths(None, Some(PathBuf::from("/xdg")), None);
+        assert_eq!(
+            xdg.last().unwrap(),
+            &PathBuf::from("/xdg/logline/contracts")
+        );
+    }
+
+    #[test]
+    fn run_ids_are_sortable_ulids_and_taken_logs_get_one_appended() {
+        let (first, second) = (generate_run_id(), generate_run_id());
+        assert_eq!(first.len(), 26);
+        assert_ne!(first, second);
+        assert!(first[..10] <= second[..10]);
+        assert_eq!(
+            with_run_id(Path::new("logs/run.jsonl"), &first),
+            PathBuf::from(format!("logs/run_{first}.jsonl"))
+        );
+        assert_eq!(with_run_id(Path::new("run"), "01J"), PathBuf::from("run_01J"));
+    }
+
+    #[test]
+    fn taken_log_paths_are_never_clobbered() {
+        let dir = std::env::temp_dir().join(format!("logline_logs_{}", std::process::id()));
+        let requested = dir.join("run.log");
+        let _ = fs::remove_dir_all(&dir);
+
+        let (path, file) = create_log_file(&requested, false, "01J");
+        assert_eq!(path, requested);
+        writeln!(file.unwrap(), "first").unwrap();
+
+        // The path exists now, so the next run goes beside it
+        let (path, file) = create_log_file(&requested, false, "01K");
+        assert_eq!(path, dir.join("run_01K.log"));
+        writeln!(file.unwrap(), "second").unwrap();
+        assert_eq!(fs::read_to_string(&requested).unwrap(), "first\n");
+        let (_, file) = create_log_file(&requested, false, "01K");
+        assert_eq!(file.unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+
+        let (path, file) = create_log_file(&requested, true, "01L");
+        assert_eq!(path, requested);
+        writeln!(file.unwrap(), "third").unwrap();
+        assert_eq!(fs::read_to_string(&requested).unwrap(), "third\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn energy_component_csvs_hold_one_row_per_sample_and_residue() {
+        let components = folding_core::EnergyComponents {
+            bond: 1.0,
+            angle: 2.0,
+            dihedral: 0.5,
+            nonbonded: -4.0,
+            solvation: -0.25,
+        };
+        let samples = [
+            EnergyComponentSample {
+                step: 0,
+                time_ms: 0.0,
+                level: PhysicsLevel::Gb,
+                components,
+                residues: vec![-0.5, -0.25],
+            },
+            EnergyComponentSample {
+                step: 3,
+                time_ms: 12.5,
+                level: PhysicsLevel::Coarse,
+                components: folding_core::EnergyComponents::default(),
+                residues: Vec::new(),
+            },
+        ];
+        let csv = energy_components_csv(&samples);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            rows[0],
+            "step,time_ms,level,bond,angle,dihedral,nonbonded,solvation,total"
+        );
+        assert_eq!(
+            rows[1],
+            "0,0.000,gb,1.000000,2.000000,0.500000,-4.000000,-0.250000,-0.750000"
+        );
+        assert!(rows[2].starts_with("3,12.500,coarse,"));
+        assert_eq!(rows.len(), 3);
+
+        let names = ["ALA".to_string(), "GLY".to_string()];
+        let residues = residue_energies_csv(&samples, &names);
+        assert_eq!(
+            residues,
+            "step,time_ms,level,residue,name,energy\n\
+             0,0.000,gb,0,ALA,-0.500000\n\
+             0,0.000,gb,1,GLY,-0.250000\n"
+        );
+    }
+
+    #[test]
+    fn provenance_is_logged_with_every_run() {
+        let provenance = Provenance::current(PhysicsLevel::Toy, PhysicsEngine::Native);
+        assert_eq!(provenance.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(provenance.git_commit, env!("LOGLINE_GIT_COMMIT"));
+        assert!(!provenance.git_commit.is_empty() && !provenance.hostname.is_empty());
+        assert!(provenance.os.starts_with(std::env::consts::OS));
+        assert_eq!(provenance.physics_backend, "toy/native");
+        let enabled = [
+            ("bundled-sqlite", cfg!(feature = "bundled-sqlite")),
+            ("upload", cfg!(feature = "upload")),
+            ("model-score", cfg!(feature = "model-score")),
+            ("alphafold", cfg!(feature = "alphafold")),
+        ];
+        for (feature, enabled) in enabled {
+            assert_eq!(
+                provenance.features.contains(&feature.to_string()),
+                enabled,
+                "{feature}"
+            );
+        }
+        // Features of the crates below come along, each listed once
+        for feature in folding_core::enabled_features() {
+            assert!(
+                provenance.features.contains(&feature.to_string()),
+                "{feature}"
+            );
+        }
+        let mut unique = provenance.features.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), provenance.features.len());
+
+        let features = [
+            "proptest",
+            "amber99sb",
+            "charmm36",
+            "opls_aa",
+            "plugins",
+            "openmm",
+            "async",
+            "bundled-sqlite",
+            "upload",
+            "model-score",
+            "alphafold",
+        ];
+        let metadata = LogMetadata {
+            run_id: "01J".into(),
+            run_name: None,
+            timestamp: "0".into(),
+            contract_name: None,
+            environment: "aqueous".into(),
+            temperature: 300.0,
+            time_step_ms: 1,
+            accepted_spans: 0,
+            rejected_spans: 0,
+            acceptance_rate: 0.0,
+            final_potential_energy: 0.0,
+            final_gibbs_energy: 0.0,
+            informational_efficiency: 0.0,
+            total_work: 0.0,
+            tm_score: None,
+            gdt_ts: None,
+            heuristic_entropy: 0.0,
+            quasi_harmonic_entropy: None,
+            foldability: None,
+            energy_gap: None,
+            energy_samples: EnergySamples::default(),
+            span_time_ms: 0.0,
+            simulated_time_ps: 0.0,
+            wall_time_ms: 0.0,
+            remote_uri: None,
+            level_choice: None,
+            provenance: Provenance {
+                hostname: "bench|01".into(),
+                physics_backend: PhysicsEngine::Auto.backend_label().into(),
+                features: features.iter().map(|feature| feature.to_string()).collect(),
+                ..provenance
+            },
+        };
+        let analysis = folding_sim::RunAnalysis::parse(&metadata_line(&metadata)).unwrap();
+        let parsed = analysis.metadata;
+        assert_eq!(
+            parsed.crate_version.as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(
+            parsed.git_commit.as_deref(),
+            Some(env!("LOGLINE_GIT_COMMIT"))
+        );
+        // A field separator in the hostname cannot split the line
+        assert_eq!(parsed.hostname.as_deref(), Some("bench_01"));
+        assert_eq!(
+            parsed.physics_backend.as_deref(),
+            Some(PhysicsEngine::Auto.backend_label())
+        );
+        assert_eq!(parsed.features, features);
+    }
+}
@@ -0,0 +1,393 @@
+//! mmCIF/PDBx `_atom_site` loops: starting chains, backbone atoms, Cα
+//! coordinates and coordinate transforms.
+//!
+//! Only the first model is read. A starting chain keeps the `ATOM` records
+//! of the first polymer chain and its first alternate conformer: every
+//! residue with a Cα becomes a bead at that Cα, and phi/psi are measured
+//! from the N, Cα and C atoms where the file has them.
+
+use std::fs;
+use std::path::Path;
+
+use folding_core::{BackboneAtoms, BackboneCollector, backbone_dihedrals};
+use folding_molecule::{PeptideChain, Residue, ResidueId};
+
+/// Reads the first polymer chain of an mmCIF/PDBx file.
+pub fn load_mmcif(path: &Path) -> Result<PeptideChain, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read mmCIF {}: {err}", path.display()))?;
+    parse_mmcif(&contents).map_err(|err| format!("invalid mmCIF {}: {err}", path.display()))
+}
+
+/// Builds a chain from the `_atom_site` loop of mmCIF/PDBx text. Residues
+/// without a Cα are skipped, so gaps in the model join their neighbours.
+pub fn parse_mmcif(contents: &str) -> Result<PeptideChain, String> {
+    let (mut first_chain, mut first_alt) = (None, None);
+    let mut residues = collect_backbone(contents, |columns, tokens| {
+        let field = |index: Option<usize>| index.map(|index| tokens[index].as_str());
+        field(columns.group).is_none_or(|group| group == "ATOM")
+            && first_only(&mut first_chain, field(columns.chain))
+            // Alternate conformers: keep the first one named
+            && first_only(
+                &mut first_alt,
+                field(columns.alt).filter(|alt| !matches!(*alt, "." | "?")),
+            )
+    })?;
+
+    residues.retain(|(_, atoms)| atoms.ca.is_some());
+    if residues.is_empty() {
+        return Err("no CA atoms".into());
+    }
+    let mut chain = PeptideChain::new(
+        residues
+            .iter()
+            .enumerate()
+            .map(|(index, (name, atoms))| {
+                Residue::new(
+                    ResidueId(index),
+                    name.as_str(),
+                    atoms.ca.unwrap_or_default(),
+                )
+            })
+            .collect(),
+    );
+    let atoms: Vec<BackboneAtoms> = residues.into_iter().map(|(_, atoms)| atoms).collect();
+    for (index, dihedrals) in backbone_dihedrals(&atoms).into_iter().enumerate() {
+        if let Some(phi) = dihedrals.phi {
+            chain.phi_mut()[index] = phi;
+        }
+        if let Some(psi) = dihedrals.psi {
+            chain.psi_mut()[index] = psi;
+        }
+    }
+    Ok(chain)
+}
+
+/// N, CA and C positions per residue of the first model, every chain
+/// included, with residue names in file order.
+pub fn parse_mmcif_backbone(contents: &str) -> Result<Vec<(String, BackboneAtoms)>, String> {
+    collect_backbone(contents, |_, _| true)
+}
+
+/// Cα coordinates of the first model in file order.
+pub fn parse_mmcif_ca_coordinates(contents: &str) -> Result<Vec<[f64; 3]>, String> {
+    let mut positions = Vec::new();
+    let mut first_model = None;
+    visit_atom_site(
+        contents,
+        |columns, tokens| {
+            let model = columns.model.map(|index| tokens[index].as_str());
+            if first_only(&mut first_model, model) && tokens[columns.atom_name] == "CA" {
+                positions.push(columns.coordinates(tokens)?);
+            }
+            Ok(())
+        },
+        |_| {},
+    )?;
+    Ok(positions)
+}
+
+/// Renders mmCIF text again with every atom coordinate passed through
+/// `transform`; lines outside the `_atom_site` loop are kept as they are.
+pub fn transform_mmcif(
+    contents: &str,
+    transform: impl Fn([f64; 3]) -> [f64; 3],
+) -> Result<String, String> {
+    let rendered = std::cell::RefCell::new(String::with_capacity(contents.len()));
+    visit_atom_site(
+        contents,
+        |columns, tokens| {
+            let moved = transform(columns.coordinates(tokens)?);
+            let mut tokens = tokens.to_vec();
+            tokens[columns.x] = format!("{:.3}", moved[0]);
+            tokens[columns.y] = format!("{:.3}", moved[1]);
+            tokens[columns.z] = format!("{:.3}", moved[2]);
+            let quoted: Vec<String> = tokens.iter().map(|token| quote_cif(token)).collect();
+            let mut out = rendered.borrow_mut();
+            out.push_str(&quoted.join(" "));
+            out.push('\n');
+            Ok(())
+        },
+        |line| {
+            let mut out = rendered.borrow_mut();
+            out.push_str(line);
+            out.push('\n');
+        },
+    )?;
+    Ok(rendered.into_inner())
+}
+
+/// Backbone atoms of the first model's rows that `keep` accepts.
+fn collect_backbone(
+    contents: &str,
+    mut keep: impl FnMut(&AtomSiteColumns, &[String]) -> bool,
+) -> Result<Vec<(String, BackboneAtoms)>, String> {
+    let mut collector = BackboneCollector::default();
+    let mut first_model = None;
+    visit_atom_site(
+        contents,
+        |columns, tokens| {
+            let model = columns.model.map(|index| tokens[index].as_str());
+            if !first_only(&mut first_model, model) || !keep(columns, tokens) {
+                return Ok(());
+            }
+            let residue_name = columns
+                .residue_name
+                .map(|idx| tokens[idx].as_str())
+                .unwrap_or("UNK");
+            collector.push(
+                columns.residue_key(tokens),
+                residue_name,
+                &tokens[columns.atom_name],
+                columns.coordinates(tokens)?,
+            );
+            Ok(())
+        },
+        |_| {},
+    )?;
+    Ok(collector.residues)
+}
+
+/// True when `value` is missing or matches the first value seen.
+fn first_only(first: &mut Option<String>, value: Option<&str>) -> bool {
+    value.is_none_or(|value| first.get_or_insert_with(|| value.to_string()) == value)
+}
+
+/// Column layout of an mmCIF `_atom_site` loop.
+struct AtomSiteColumns {
+    count: usize,
+    group: Option<usize>,
+    atom_name: usize,
+    alt: Option<usize>,
+    x: usize,
+    y: usize,
+    z: usize,
+    model: Option<usize>,
+    residue_name: Option<usize>,
+    chain: Option<usize>,
+    /// Chain, sequence number and insertion code columns identifying a residue.
+    residue_key: Vec<usize>,
+}
+
+impl AtomSiteColumns {
+    fn from_headers(headers: &[&str]) -> Result<Self, String> {
+        let find = |name: &str| headers.iter().position(|header| *header == name);
+        let require =
+            |name: &str| find(name).ok_or_else(|| format!("mmCIF atom_site loop missing {name}"));
+        Ok(Self {
+            count: headers.len(),
+            group: find("_atom_site.group_PDB"),
+            atom_name: find("_atom_site.label_atom_id")
+                .or_else(|| find("_atom_site.auth_atom_id"))
+                .ok_or_else(|| "mmCIF atom_site loop missing atom names".to_string())?,
+            alt: find("_atom_site.label_alt_id"),
+            x: require("_atom_site.Cartn_x")?,
+            y: require("_atom_site.Cartn_y")?,
+            z: require("_atom_site.Cartn_z")?,
+            model: find("_atom_site.pdbx_PDB_model_num"),
+            residue_name: find("_atom_site.label_comp_id")
+                .or_else(|| find("_atom_site.auth_comp_id")),
+            chain: find("_atom_site.auth_asym_id").or_else(|| find("_atom_site.label_asym_id")),
+            residue_key: [
+                find("_atom_site.auth_asym_id").or_else(|| find("_atom_site.label_asym_id")),
+                find("_atom_site.auth_seq_id").or_else(|| find("_atom_site.label_seq_id")),
+                find("_atom_site.pdbx_PDB_ins_code"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+        })
+    }
+
+    fn residue_key(&self, tokens: &[String]) -> String {
+        let parts: Vec<&str> = self
+            .residue_key
+            .iter()
+            .map(|&idx| tokens[idx].as_str())
+            .collect();
+        parts.join(":")
+    }
+
+    fn coordinates(&self, tokens: &[String]) -> Result<[f64; 3], String> {
+        let parse = |idx: usize| -> Result<f64, String> {
+            tokens[idx]
+                .parse()
+                .map_err(|_| format!("invalid mmCIF coordinate '{}'", tokens[idx]))
+        };
+        Ok([parse(self.x)?, parse(self.y)?, parse(self.z)?])
+    }
+}
+
+/// Visits every `_atom_site` row, passing the column layout and row tokens.
+/// Lines outside the loop are handed to `other` untouched.
+fn visit_atom_site(
+    contents: &str,
+    mut row: impl FnMut(&AtomSiteColumns, &[String]) -> Result<(), String>,
+    mut other: impl FnMut(&str),
+) -> Result<(), String> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let is_atom_loop = line.trim() == "loop_"
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.trim_start().starts_with("_atom_site."));
+        other(line);
+        index += 1;
+        if !is_atom_loop {
+            continue;
+        }
+        let mut headers = Vec::new();
+        while let Some(header) = lines.get(index).map(|line| line.trim())
+            && header.starts_with("_atom_site.")
+        {
+            headers.push(header);
+            other(lines[index]);
+            index += 1;
+        }
+        let columns = AtomSiteColumns::from_headers(&headers)?;
+        while let Some(data) = lines.get(index) {
+            let trimmed = data.trim();
+            if trimmed.is_empty()
+                || trimmed.starts_with('#')
+                || trimmed.starts_with('_')
+                || trimmed.starts_with("loop_")
+                || trimmed.starts_with("data_")
+            {
+                break;
+            }
+            let tokens = tokenize_cif(trimmed);
+            if tokens.len() != columns.count {
+                return Err(format!(
+                    "mmCIF atom_site row has wrong column count: {trimmed}"
+                ));
+            }
+            row(&columns, &tokens)?;
+            index += 1;
+        }
+    }
+    Ok(())
+}
+
+fn tokenize_cif(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut token = String::new();
+        if ch == '\'' || ch == '"' {
+            chars.next();
+            while let Some(next) = chars.next() {
+                let closes = next == ch && chars.peek().is_none_or(|after| after.is_whitespace());
+                if closes {
+                    break;
+                }
+                token.push(next);
+            }
+        } else {
+            while let Some(&next) = chars.peek() {
+                if next.is_whitespace() {
+                    break;
+                }
+                token.push(next);
+                chars.next();
+            }
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn quote_cif(token: &str) -> String {
+    let needs_quotes = token.is_empty()
+        || token.contains(char::is_whitespace)
+        || token.starts_with(['\'', '"', '_', '#', '$', ';']);
+    if !needs_quotes {
+        token.to_string()
+    } else if token.contains('\'') {
+        format!("\"{token}\"")
+    } else {
+        format!("'{token}'")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIF: &str = "\
+data_test
+#
+loop_
+_atom_site.group_PDB
+_atom_site.id
+_atom_site.label_atom_id
+_atom_site.label_alt_id
+_atom_site.label_comp_id
+_atom_site.auth_asym_id
+_atom_site.auth_seq_id
+_atom_site.pdbx_PDB_ins_code
+_atom_site.Cartn_x
+_atom_site.Cartn_y
+_atom_site.Cartn_z
+_atom_site.pdbx_PDB_model_num
+ATOM 1 N . ALA A 1 ? -0.525 1.362 0.000 1
+ATOM 2 CA . ALA A 1 ? 0.000 0.000 0.000 1
+ATOM 3 C . ALA A 1 ? 1.520 0.000 0.000 1
+ATOM 4 N . GLY A 2 ? 2.100 1.200 0.300 1
+ATOM 5 CA A GLY A 2 ? 3.560 1.290 0.200 1
+ATOM 6 CA B GLY A 2 ? 9.000 9.000 9.000 1
+ATOM 7 C . GLY A 2 ? 4.100 2.700 0.100 1
+ATOM 8 N . SER A 3 ? 5.400 2.900 -0.100 1
+ATOM 9 CA . SER A 3 ? 6.000 4.200 -0.300 1
+ATOM 10 C . SER A 3 ? 7.500 4.100 -0.200 1
+HETATM 11 O . HOH A 101 ? 20.000 20.000 20.000 1
+ATOM 12 CA . LYS B 1 ? 30.000 0.000 0.000 1
+ATOM 13 CA . ALA A 1 ? 50.000 0.000 0.000 2
+#
+";
+
+    #[test]
+    fn reads_the_first_chain_of_the_first_model() {
+        let chain = parse_mmcif(CIF).unwrap();
+        assert_eq!(chain.names(), ["ALA", "GLY", "SER"]);
+        assert_eq!(chain.positions()[1], [3.56, 1.29, 0.2]);
+        // Glycine has all three neighbours' backbone atoms, so both torsions
+        assert!(chain.phi()[1] != 0.0 && chain.psi()[1] != 0.0);
+        assert_eq!(chain.phi()[0], 0.0);
+
+        assert!(parse_mmcif("data_empty\n").is_err());
+        let bad_row = CIF.replace("ATOM 9 CA . SER A 3 ? 6.000", "ATOM 9 CA . SER A 3 ? x");
+        assert!(parse_mmcif(&bad_row).is_err());
+    }
+
+    #[test]
+    fn atom_site_round_trips_through_transform() {
+        let contents = "\
+data_demo
+loop_
+_atom_site.group_PDB
+_atom_site.label_atom_id
+_atom_site.Cartn_x
+_atom_site.Cartn_y
+_atom_site.Cartn_z
+_atom_site.pdbx_PDB_model_num
+ATOM N 1.000 2.000 3.000 1
+ATOM CA 4.000 5.000 6.000 1
+ATOM \"C1'\" 7.000 8.000 9.000 1
+ATOM CA 0.000 0.000 0.000 2
+#
+";
+        let positions = parse_mmcif_ca_coordinates(contents).unwrap();
+        assert_eq!(positions, vec![[4.0, 5.0, 6.0]]);
+
+        let shifted = transform_mmcif(contents, |p| [p[0] + 1.0, p[1], p[2]]).unwrap();
+        assert!(shifted.contains("ATOM CA 5.000 5.000 6.000 1"));
+        assert!(shifted.contains("ATOM C1' 8.000 8.000 9.000 1"));
+        assert!(shifted.ends_with("#\n"));
+    }
+}
@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use folding_molecule::PeptideChain;
+use serde_json::{Value, json};
+
+/// Timeout for one request to the model server when none is given.
+pub const DEFAULT_MODEL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What the model server receives for each candidate conformation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ModelInput {
+    /// Cα coordinates (Å), one `[x, y, z]` per residue.
+    #[default]
+    Coordinates,
+    /// Backbone torsions (degrees), one `[phi, psi, omega]` per residue.
+    Features,
+}
+
+impl ModelInput {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "coordinates" | "coords" | "xyz" => Some(ModelInput::Coordinates),
+            "features" | "torsions" => Some(ModelInput::Features),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelInput::Coordinates => "coordinates",
+            ModelInput::Features => "features",
+        }
+    }
+}
+
+/// Learned potential served over HTTP. Each candidate conformation is POSTed
+/// as JSON to `url`, which answers `{"score": <kcal/mol>}`; the change in
+/// `weight × score` across a move is added to ΔE in the Metropolis criterion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelScoreConfig {
+    pub url: String,
+    pub weight: f64,
+    pub input: ModelInput,
+    pub timeout: Duration,
+}
+
+impl ModelScoreConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            weight: 1.0,
+            input: ModelInput::default(),
+            timeout: DEFAULT_MODEL_TIMEOUT,
+        }
+    }
+
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn with_input(mut self, input: ModelInput) -> Self {
+        self.input = input;
+        self
+    }
+}
+
+/// JSON body sent for `chain`: residue names plus coordinates or features.
+pub fn request_body(chain: &PeptideChain, input: ModelInput) -> Value {
+    let residues: Vec<&str> = chain.names().iter().map(String::as_str).collect();
+    match input {
+        ModelInput::Coordinates => {
+            json!({ "residues": residues, "coordinates": chain.positions() })
+        }
+        ModelInput::Features => {
+            let features: Vec<[f64; 3]> = chain
+                .residues()
+                .map(|r| [r.phi(), r.psi(), r.omega()])
+                .collect();
+            json!({ "residues": residues, "features": features })
+        }
+    }
+}
+
+/// Reads `{"score": <number>}` from a model server response.
+pub fn parse_response(body: &str) -> Result<f64, String> {
+    let value: Value =
+        serde_json::from_str(body).map_err(|err| format!("invalid model response: {err}"))?;
+    value
+        .get("score")
+        .and_then(Value::as_f64)
+        .filter(|score| score.is_finite())
+        .ok_or_else(|| format!("model response has no finite score: {body}"))
+}
+
+#[cfg(feature = "model-score")]
+pub use client::ModelScorer;
+
+#[cfg(feature = "model-score")]
+mod client {
+    use super::{ModelScoreConfig, parse_response, request_body};
+    use folding_molecule::PeptideChain;
+    use std::collections::VecDeque;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::sync::Mutex;
+
+    /// Conformations remembered so a move's "before" chain is not re-scored.
+    const CACHE_SIZE: usize = 4;
+
+    /// Client for a [`ModelScoreConfig`] server, shared with the engine.
+    pub struct ModelScorer {
+        config: ModelScoreConfig,
+        agent: ureq::Agent,
+        state: Mutex<ScorerState>,
+    }
+
+    #[derive(Default)]
+    struct ScorerState {
+        cache: VecDeque<(u64, f64)>,
+        requests: usize,
+        failures: usize,
+    }
+
+    impl ModelScorer {
+        pub fn new(config: ModelScoreConfig) -> Self {
+            let agent = ureq::AgentBuilder::new().timeout(config.timeout).build();
+            Self {
+                config,
+                agent,
+                state: Mutex::new(ScorerState::default()),
+            }
+        }
+
+        /// Raw model score of `chain`, unweighted.
+        pub fn request(&self, chain: &PeptideChain) -> Result<f64, String> {
+            let body = request_body(chain, self.config.input).to_string();
+            let response = self
+                .agent
+                .post(&self.config.url)
+                .set("content-type", "application/json")
+                .send_string(&body)
+                .map_err(|err| format!("model server {} failed: {err}", self.config.url))?;
+            let text = response
+                .into_string()
+                .map_err(|err| format!("model server {} failed: {err}", self.config.url))?;
+            parse_response(&text)
+        }
+
+        /// Weighted score used by the engine. A failed request is counted and
+        /// returned as an error; the engine rejects the move it was scoring.
+        pub fn score(&self, chain: &PeptideChain) -> Result<f64, String> {
+            let key = conformation_key(chain);
+            let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+            if let Some((_, score)) = state.cache.iter().find(|(cached, _)| *cached == key) {
+                return Ok(*score);
+            }
+            state.requests += 1;
+            match self.request(chain) {
+                Ok(raw) => {
+                    let score = self.config.weight * raw;
+                    if state.cache.len() == CACHE_SIZE {
+                        state.cache.pop_front();
+                    }
+                    state.cache.push_back((key, score));
+                    Ok(score)
+                }
+                Err(err) => {
+                    if state.failures == 0 {
+                        log::warn!("{err}; rejecting moves the model cannot score");
+                    }
+                    state.failures += 1;
+                    Err(err)
+                }
+            }
+        }
+
+        /// Requests sent and how many of them failed.
+        pub fn stats(&self) -> (usize, usize) {
+            let state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+            (state.requests, state.failures)
+        }
+    }
+
+    fn conformation_key(chain: &PeptideChain) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for residue in chain.residues() {
+            for value in
+                residue
+                    .position()
+                    .iter()
+                    .chain(&[residue.phi(), residue.psi(), residue.omega()])
+            {
+                value.to_bits().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bodies_and_responses_follow_the_model_protocol() {
+        let chain = PeptideChain::from_sequence("AG");
+        let body = request_body(&chain, ModelInput::Coordinates);
+        assert_eq!(body["coordinates"].as_array().unwrap().len(), 2);
+        assert!(body.get("features").is_none());
+        assert_eq!(ModelInput::parse("torsions"), Some(ModelInput::Features));
+
+        assert_eq!(parse_response("{\"score\": -1.5}"), Ok(-1.5));
+        assert!(parse_response("{\"energy\": 1}").is_err());
+        assert!(parse_response("not json").is_err());
+    }
+
+    #[cfg(feature = "model-score")]
+    #[test]
+    fn scorer_posts_conformations_and_caches_scores() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/score", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for stream in listener.incoming().take(1) {
+                let mut stream = stream.unwrap();
+                let mut request = vec![0u8; 65536];
+                let mut text = String::new();
+                while !text.ends_with('}') {
+                    let read = stream.read(&mut request).unwrap();
+                    text.push_str(&String::from_utf8_lossy(&request[..read]));
+                }
+                bodies.push(text);
+                let body = "{\"score\": 2.5}";
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            bodies
+        });
+
+        let config = ModelScoreConfig::new(url)
+            .with_weight(2.0)
+            .with_input(ModelInput::Features);
+        let scorer = ModelScorer::new(config);
+        let chain = PeptideChain::from_sequence("AGS");
+        assert_eq!(scorer.score(&chain), Ok(5.0));
+        assert_eq!(scorer.score(&chain), Ok(5.0));
+        assert_eq!(scorer.stats(), (1, 0));
+        let bodies = server.join().unwrap();
+        assert!(bodies[0].contains("\"features\""));
+
+        let mut moved = chain.clone();
+        moved.phi_mut()[1] += 10.0;
+        assert!(scorer.score(&moved).is_err());
+        assert_eq!(scorer.stats(), (2, 1));
+    }
+}
@@ -0,0 +1,101 @@
+//! Fixed-column PDB `ATOM` records, shared by every reader of PDB files.
+//!
+//! Only the first model is read, and of residues modelled in several
+//! alternate conformers only the first conformer named.
+
+use std::ops::Range;
+
+/// One `ATOM` record of a PDB file's first model.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PdbAtom<'a> {
+    line: &'a str,
+    pub name: &'a str,
+    pub residue_name: &'a str,
+    /// Chain, residue number and insertion code (columns 22–27); unique per
+    /// residue.
+    pub residue_key: &'a str,
+    pub chain_id: char,
+    pub position: [f64; 3],
+}
+
+impl PdbAtom<'_> {
+    /// Temperature factor; AlphaFold and ESMFold write pLDDT there.
+    pub fn b_factor(&self) -> Result<f64, String> {
+        column(self.line, 60..66, "B-factor")
+    }
+}
+
+/// `ATOM` records of the first model, in file order; waters and ligands
+/// (`HETATM`) are left out.
+pub fn first_model_atoms(contents: &str) -> Result<Vec<PdbAtom<'_>>, String> {
+    let mut atoms = Vec::new();
+    let mut first_alt: Option<char> = None;
+    for line in contents.lines() {
+        if line.starts_with("ENDMDL") {
+            break;
+        }
+        if !line.starts_with("ATOM") {
+            continue;
+        }
+        let alt = line.get(16..17).and_then(|field| field.chars().next());
+        if let Some(alt) = alt.filter(|alt| *alt != ' ')
+            && *first_alt.get_or_insert(alt) != alt
+        {
+            continue;
+        }
+        atoms.push(PdbAtom {
+            line,
+            name: line.get(12..16).map(str::trim).unwrap_or(""),
+            residue_name: line.get(17..20).map(str::trim).unwrap_or("UNK"),
+            residue_key: line.get(21..27).unwrap_or(""),
+            chain_id: line
+                .get(21..22)
+                .and_then(|field| field.chars().next())
+                .unwrap_or(' '),
+            position: pdb_coordinates(line)?,
+        });
+    }
+    Ok(atoms)
+}
+
+/// x, y and z of an `ATOM` or `HETATM` line.
+pub fn pdb_coordinates(line: &str) -> Result<[f64; 3], String> {
+    Ok([
+        column(line, 30..38, "coordinate")?,
+        column(line, 38..46, "coordinate")?,
+        column(line, 46..54, "coordinate")?,
+    ])
+}
+
+fn column(line: &str, range: Range<usize>, what: &str) -> Result<f64, String> {
+    line.get(range)
+        .map(str::trim)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| format!("invalid {what} in line: {line}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_model_atoms_keep_the_first_conformer() {
+        let contents = "\
+HETATM    1  O   HOH A 100       9.000   9.000   9.000  1.00  0.00           O
+ATOM      2  CA AGLY A   1       1.000   2.000   3.000  0.60 12.50           C
+ATOM      3  CA BGLY A   1       1.500   2.000   3.000  0.40 12.50           C
+ATOM      4  CA  ALA B   2       4.000   5.000   6.000  1.00 88.00           C
+ENDMDL
+ATOM      5  CA  SER B   3       7.000   8.000   9.000  1.00 88.00           C
+";
+        let atoms = first_model_atoms(contents).unwrap();
+        assert_eq!(atoms.len(), 2);
+        assert_eq!((atoms[0].name, atoms[0].residue_name), ("CA", "GLY"));
+        assert_eq!(atoms[0].position, [1.0, 2.0, 3.0]);
+        assert_eq!((atoms[1].chain_id, atoms[1].residue_key), ('B', "B   2 "));
+        assert_eq!(atoms[1].b_factor(), Ok(88.0));
+
+        let err = first_model_atoms("ATOM      1  CA  GLY A   1       x").unwrap_err();
+        assert!(err.starts_with("invalid coordinate"));
+    }
+}
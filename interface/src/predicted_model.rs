@@ -0,0 +1,208 @@
+use std::fs;
+use std::path::Path;
+
+use folding_core::ContactPrior;
+use folding_molecule::{PeptideChain, Residue, ResidueId};
+
+use crate::pdb::first_model_atoms;
+
+/// Where AlphaFold DB serves its predicted models.
+pub const ALPHAFOLD_DB_URL: &str = "https://alphafold.ebi.ac.uk/files";
+
+/// Model version requested from AlphaFold DB.
+pub const ALPHAFOLD_DB_VERSION: u32 = 4;
+
+/// How long a model download may take before it is given up.
+#[cfg(feature = "alphafold")]
+pub const ALPHAFOLD_DB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// pLDDT under which residues are left out of the model's restraints; 70 is
+/// where AlphaFold's own "confident" band starts.
+pub const DEFAULT_PLDDT_CUTOFF: f64 = 70.0;
+
+/// True for UniProt accessions (`P69905`, `A0A023GPI8`, ...).
+pub fn is_uniprot_accession(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    let alphanumeric = |b: &u8| b.is_ascii_uppercase() || b.is_ascii_digit();
+    matches!(bytes.len(), 6 | 10)
+        && bytes.iter().all(alphanumeric)
+        && bytes[0].is_ascii_uppercase()
+        && bytes[1].is_ascii_digit()
+        && bytes[5].is_ascii_digit()
+}
+
+/// File name AlphaFold DB gives the model of `accession`.
+pub fn alphafold_file_name(accession: &str) -> String {
+    format!("AF-{accession}-F1-model_v{ALPHAFOLD_DB_VERSION}.pdb")
+}
+
+pub fn alphafold_model_url(accession: &str) -> String {
+    format!("{ALPHAFOLD_DB_URL}/{}", alphafold_file_name(accession))
+}
+
+/// Cα trace of an AlphaFold or ESMFold model with the per-residue pLDDT both
+/// tools write into the B-factor column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PredictedModel {
+    pub names: Vec<String>,
+    pub positions: Vec<[f64; 3]>,
+    /// pLDDT per residue on the 0–100 scale.
+    pub plddt: Vec<f64>,
+}
+
+impl PredictedModel {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read model {}: {err}", path.display()))?;
+        Self::parse_pdb(&contents).map_err(|err| format!("invalid model {}: {err}", path.display()))
+    }
+
+    /// Reads the first model's Cα atoms. ESMFold versions that store pLDDT
+    /// as a 0–1 fraction are rescaled to 0–100.
+    pub fn parse_pdb(contents: &str) -> Result<Self, String> {
+        let mut model = Self {
+            names: Vec::new(),
+            positions: Vec::new(),
+            plddt: Vec::new(),
+        };
+        for atom in first_model_atoms(contents)?
+            .into_iter()
+            .filter(|atom| atom.name == "CA")
+        {
+            model.names.push(atom.residue_name.to_string());
+            model.positions.push(atom.position);
+            model.plddt.push(atom.b_factor()?);
+        }
+        if model.positions.is_empty() {
+            return Err("no CA atoms".into());
+        }
+        if model.plddt.iter().all(|plddt| *plddt <= 1.0) {
+            model.plddt.iter_mut().for_each(|plddt| *plddt *= 100.0);
+        }
+        Ok(model)
+    }
+
+    /// Checks that `chain` is the model's sequence, residue for residue.
+    pub fn check_sequence(&self, chain: &PeptideChain) -> Result<(), String> {
+        if self.names.len() != chain.len() {
+            return Err(format!(
+                "the model has {} residues but the chain has {}",
+                self.names.len(),
+                chain.len()
+            ));
+        }
+        match self
+            .names
+            .iter()
+            .zip(chain.names())
+            .position(|(model, expected)| !model.eq_ignore_ascii_case(expected))
+        {
+            Some(index) => Err(format!(
+                "residue {} of the model is {} but the chain has {}",
+                index + 1,
+                self.names[index],
+                chain.names()[index]
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The model as a chain: its residue names at its Cα positions.
+    pub fn chain(&self) -> PeptideChain {
+        PeptideChain::new(
+            self.names
+                .iter()
+                .zip(&self.positions)
+                .enumerate()
+                .map(|(index, (name, position))| Residue::new(ResidueId(index), name, *position))
+                .collect(),
+        )
+    }
+
+    pub fn mean_plddt(&self) -> f64 {
+        self.plddt.iter().sum::<f64>() / self.plddt.len().max(1) as f64
+    }
+
+    /// Distance restraints holding the model's geometry, weighted by pLDDT /
+    /// 100; pairs with a residue under `min_plddt` are dropped.
+    pub fn restraints(&self, min_plddt: f64) -> ContactPrior {
+        let confidence: Vec<f64> = self.plddt.iter().map(|plddt| plddt / 100.0).collect();
+        ContactPrior::from_model(&self.positions, &confidence).filtered(min_plddt / 100.0)
+    }
+}
+
+/// Downloads the AlphaFold DB model of `accession` as PDB text.
+#[cfg(feature = "alphafold")]
+pub fn fetch_alphafold_model(accession: &str) -> Result<String, String> {
+    let url = alphafold_model_url(accession);
+    ureq::AgentBuilder::new()
+        .timeout(ALPHAFOLD_DB_TIMEOUT)
+        .build()
+        .get(&url)
+        .call()
+        .map_err(|err| format!("failed to fetch {url}: {err}"))?
+        .into_string()
+        .map_err(|err| format!("failed to read {url}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(serial: usize, name: &str, residue: &str, x: f64, plddt: f64) -> String {
+        format!(
+            "ATOM  {serial:>5} {name:<4} {residue} A{serial:>4}    {x:>8.3}{:>8.3}{:>8.3}  1.00{plddt:>6.2}           C",
+            0.0, 0.0
+        )
+    }
+
+    #[test]
+    fn predicted_models_carry_plddt_into_restraint_weights() {
+        let pdb = [
+            atom(1, "N", "MET", 0.0, 91.0),
+            atom(1, "CA", "MET", 0.0, 91.0),
+            atom(2, "CA", "GLY", 2.5, 88.0),
+            atom(3, "CA", "LYS", 5.0, 40.0),
+            atom(4, "CA", "ALA", 7.5, 95.0),
+            "ENDMDL".into(),
+            atom(5, "CA", "SER", 10.0, 95.0),
+        ]
+        .join("\n");
+        let model = PredictedModel::parse_pdb(&pdb).unwrap();
+        assert_eq!(model.names, ["MET", "GLY", "LYS", "ALA"]);
+        assert_eq!(model.plddt, [91.0, 88.0, 40.0, 95.0]);
+        assert_eq!(model.chain().positions()[3], [7.5, 0.0, 0.0]);
+
+        assert!(
+            model
+                .check_sequence(&PeptideChain::from_sequence("MGKA"))
+                .is_ok()
+        );
+        let err = model
+            .check_sequence(&PeptideChain::from_sequence("MGRA"))
+            .unwrap_err();
+        assert_eq!(err, "residue 3 of the model is LYS but the chain has ARG");
+        assert!(
+            model
+                .check_sequence(&PeptideChain::from_sequence("MGK"))
+                .is_err()
+        );
+
+        let prior = model.restraints(DEFAULT_PLDDT_CUTOFF);
+        assert_eq!(prior.restraints.len(), 1);
+        assert!((prior.restraints[0].weight - 0.91).abs() < 1e-12);
+
+        // ESMFold's 0-1 pLDDT
+        let esm = atom(1, "CA", "MET", 0.0, 0.42);
+        let rescaled = PredictedModel::parse_pdb(&esm).unwrap().plddt[0];
+        assert!((rescaled - 42.0).abs() < 1e-9);
+
+        assert!(is_uniprot_accession("P69905"));
+        assert!(is_uniprot_accession("A0A023GPI8"));
+        assert!(!is_uniprot_accession("model.pdb"));
+        assert_eq!(
+            alphafold_model_url("P69905"),
+            "https://alphafold.ebi.ac.uk/files/AF-P69905-F1-model_v4.pdb"
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac_sha256::{HMAC, Hash};
+
+/// S3-compatible bucket location parsed from `s3://bucket/prefix` or
+/// `gs://bucket/prefix`. GCS is reached through its XML API with HMAC keys.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteStore {
+    pub endpoint: String,
+    pub bucket: String,
+    pub prefix: String,
+    pub region: String,
+    scheme: &'static str,
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+}
+
+impl RemoteStore {
+    /// Reads credentials from `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY`
+    /// (plus optional `AWS_SESSION_TOKEN` and `AWS_REGION`); `LOGLINE_S3_ENDPOINT`
+    /// overrides the endpoint for MinIO and other S3-compatible stores.
+    pub fn from_uri(uri: &str) -> Result<Self, String> {
+        let env = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+        let (scheme, rest) = uri
+            .split_once("://")
+            .ok_or_else(|| format!("remote URI '{uri}' must start with s3:// or gs://"))?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return Err(format!("remote URI '{uri}' has no bucket"));
+        }
+        let (scheme, region, default_endpoint) = match scheme {
+            "s3" => {
+                let region = env("AWS_REGION").unwrap_or_else(|| "us-east-1".into());
+                let endpoint = format!("https://s3.{region}.amazonaws.com");
+                ("s3", region, endpoint)
+            }
+            "gs" => (
+                "gs",
+                "auto".to_string(),
+                "https://storage.googleapis.com".to_string(),
+            ),
+            other => return Err(format!("unsupported remote scheme '{other}'")),
+        };
+        Ok(Self {
+            endpoint: env("LOGLINE_S3_ENDPOINT")
+                .unwrap_or(default_endpoint)
+                .trim_end_matches('/')
+                .to_string(),
+            bucket: bucket.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+            region,
+            scheme,
+            access_key: env("AWS_ACCESS_KEY_ID")
+                .ok_or_else(|| "AWS_ACCESS_KEY_ID is not set".to_string())?,
+            secret_key: env("AWS_SECRET_ACCESS_KEY")
+                .ok_or_else(|| "AWS_SECRET_ACCESS_KEY is not set".to_string())?,
+            session_token: env("AWS_SESSION_TOKEN"),
+        })
+    }
+
+    /// `scheme://bucket/prefix` for the store root.
+    pub fn uri(&self) -> String {
+        if self.prefix.is_empty() {
+            format!("{}://{}", self.scheme, self.bucket)
+        } else {
+            format!("{}://{}/{}", self.scheme, self.bucket, self.prefix)
+        }
+    }
+
+    /// Uploads `file` under `prefix/key` and returns its remote URI.
+    pub fn upload_file(&self, file: &Path, key: &str) -> Result<String, String> {
+        let body = fs::read(file)
+            .map_err(|err| format!("failed to read artifact {}: {err}", file.display()))?;
+        let object = if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.prefix, key)
+        };
+        let path = format!("/{}/{}", self.bucket, uri_encode_path(&object));
+        let host = self
+            .endpoint
+            .split_once("://")
+            .map(|(_, host)| host)
+            .unwrap_or(&self.endpoint)
+            .to_string();
+        let (date, time) = utc_stamp(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        );
+        let request = SignedPut {
+            host: &host,
+            path: &path,
+            payload_hash: hex(&Hash::hash(&body)),
+            amz_date: format!("{date}T{time}Z"),
+            date,
+            region: &self.region,
+            session_token: self.session_token.as_deref(),
+        };
+        let authorization = request.authorization(&self.access_key, &self.secret_key);
+
+        let mut put = ureq::put(&format!("{}{}", self.endpoint, path))
+            .set("x-amz-date", &request.amz_date)
+            .set("x-amz-content-sha256", &request.payload_hash)
+            .set("authorization", &authorization);
+        if let Some(token) = request.session_token {
+            put = put.set("x-amz-security-token", token);
+        }
+        put.send_bytes(&body)
+            .map_err(|err| format!("upload of {} failed: {err}", file.display()))?;
+        Ok(format!("{}://{}/{}", self.scheme, self.bucket, object))
+    }
+}
+
+/// Pushes each artifact to `remote_uri/<file name>` and returns the object URIs.
+pub fn upload_artifacts(remote_uri: &str, files: &[PathBuf]) -> Result<Vec<String>, String> {
+    let store = RemoteStore::from_uri(remote_uri)?;
+    files
+        .iter()
+        .map(|file| {
+            let name = file
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .ok_or_else(|| format!("artifact {} has no file name", file.display()))?;
+            store.upload_file(file, &name)
+        })
+        .collect()
+}
+
+/// Inputs to an AWS Signature Version 4 `PUT` (path-style, unsigned query).
+struct SignedPut<'a> {
+    host: &'a str,
+    path: &'a str,
+    payload_hash: String,
+    amz_date: String,
+    date: String,
+    region: &'a str,
+    session_token: Option<&'a str>,
+}
+
+impl SignedPut<'_> {
+    fn authorization(&self, access_key: &str, secret_key: &str) -> String {
+        let mut headers = vec![
+            ("host", self.host.to_string()),
+            ("x-amz-content-sha256", self.payload_hash.clone()),
+            ("x-amz-date", self.amz_date.clone()),
+        ];
+        if let Some(token) = self.session_token {
+            headers.push(("x-amz-security-token", token.to_string()));
+        }
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+            .collect();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "PUT\n{}\n\n{}\n{}\n{}",
+            self.path, canonical_headers, signed_headers, self.payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", self.date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            self.amz_date,
+            scope,
+            hex(&Hash::hash(canonical_request.as_bytes()))
+        );
+        let key = signing_key(secret_key, &self.date, self.region, "s3");
+        let signature = hex(&HMAC::mac(string_to_sign.as_bytes(), key));
+        format!(
+            "AWS4-HMAC-SHA256 Credential={access_key}/{scope}, SignedHeaders={signed_headers}, Signature={signature}"
+        )
+    }
+}
+
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+    let k_date = HMAC::mac(date.as_bytes(), format!("AWS4{secret_key}").as_bytes());
+    let k_region = HMAC::mac(region.as_bytes(), k_date);
+    let k_service = HMAC::mac(service.as_bytes(), k_region);
+    HMAC::mac(b"aws4_request", k_service)
+}
+
+fn uri_encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            other => encoded.push_str(&format!("%{other:02X}")),
+        }
+    }
+    encoded
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// `(YYYYMMDD, HHMMSS)` in UTC for a Unix timestamp.
+fn utc_stamp(seconds: u64) -> (String, String) {
+    let (year, month, day) = folding_time::civil_from_days((seconds / 86_400) as i64);
+    let secs = seconds % 86_400;
+    (
+        format!("{year:04}{month:02}{day:02}"),
+        format!("{:02}{:02}{:02}", secs / 3_600, secs / 60 % 60, secs % 60),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_key_matches_aws_reference() {
+        // Example from the AWS SigV4 documentation.
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+        assert_eq!(
+            utc_stamp(1_329_264_000 + 3_723),
+            ("20120215".into(), "010203".into())
+        );
+        assert_eq!(uri_encode_path("runs/a b+c.log"), "runs/a%20b%2Bc.log");
+    }
+}
@@ -0,0 +1,392 @@
+use std::path::Path;
+
+use folding_core::ExecutionReport;
+use folding_sim::FoldingMetrics;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, params};
+
+use crate::{FoldSpan, LogMetadata};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run_id TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    contract_name TEXT,
+    environment TEXT NOT NULL,
+    temperature REAL NOT NULL,
+    time_step_ms INTEGER NOT NULL,
+    accepted_spans INTEGER NOT NULL,
+    rejected_spans INTEGER NOT NULL,
+    acceptance_rate REAL NOT NULL,
+    final_potential_energy REAL NOT NULL,
+    final_gibbs_energy REAL NOT NULL,
+    informational_efficiency REAL NOT NULL,
+    total_work REAL NOT NULL,
+    tm_score REAL,
+    gdt_ts REAL,
+    log_path TEXT
+);
+CREATE TABLE IF NOT EXISTS spans (
+    run_id TEXT NOT NULL REFERENCES runs(run_id) ON DELETE CASCADE,
+    span_index INTEGER NOT NULL,
+    span_id TEXT NOT NULL,
+    ghost INTEGER NOT NULL,
+    delta_theta REAL NOT NULL,
+    delta_entropy REAL NOT NULL,
+    delta_information REAL NOT NULL,
+    delta_energy REAL NOT NULL,
+    gibbs_energy REAL NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    PRIMARY KEY (run_id, span_index)
+);
+CREATE TABLE IF NOT EXISTS violations (
+    run_id TEXT NOT NULL REFERENCES runs(run_id) ON DELETE CASCADE,
+    violation_index INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    step INTEGER,
+    code TEXT,
+    severity TEXT,
+    PRIMARY KEY (run_id, violation_index)
+);
+CREATE TABLE IF NOT EXISTS metrics (
+    run_id TEXT NOT NULL REFERENCES runs(run_id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    value REAL NOT NULL,
+    PRIMARY KEY (run_id, name)
+);
+CREATE INDEX IF NOT EXISTS spans_by_run ON spans(run_id);
+CREATE INDEX IF NOT EXISTS violations_by_kind ON violations(kind);
+";
+
+/// Canned query behind `logline db runs`.
+pub const RUNS_QUERY: &str = "SELECT run_id, timestamp, contract_name, environment, accepted_spans, rejected_spans, round(acceptance_rate, 4) AS acceptance_rate, round(final_gibbs_energy, 4) AS final_gibbs_energy FROM runs ORDER BY timestamp";
+
+/// Columns added to `violations` after the first schema; databases created
+/// before them gain the columns (NULL for old rows) when opened.
+const VIOLATION_COLUMNS: [(&str, &str); 3] =
+    [("step", "INTEGER"), ("code", "TEXT"), ("severity", "TEXT")];
+
+/// Canned query behind `logline db violations`.
+pub const VIOLATIONS_QUERY: &str = "SELECT code, kind, severity, count(*) AS occurrences, count(DISTINCT run_id) AS runs FROM violations GROUP BY code, kind, severity ORDER BY occurrences DESC, kind";
+
+/// Rows returned by [`SqliteSink::query`], rendered as text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl QueryResult {
+    /// Tab-separated table with a header line.
+    pub fn to_tsv(&self) -> String {
+        let mut output = self.columns.join("\t");
+        output.push('\n');
+        for row in &self.rows {
+            output.push_str(&row.join("\t"));
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// SQLite run store (tables `runs`, `spans`, `violations`, `metrics`) that
+/// sits alongside the flat span logs so multi-run projects can use SQL.
+pub struct SqliteSink {
+    connection: Connection,
+}
+
+impl SqliteSink {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+        }
+        let connection = Connection::open(path)
+            .map_err(|err| format!("failed to open database {}: {err}", path.display()))?;
+        connection
+            .execute_batch("PRAGMA foreign_keys = ON;")
+            .and_then(|_| connection.execute_batch(SCHEMA))
+            .and_then(|_| add_missing_columns(&connection))
+            .map_err(|err| format!("failed to initialise database schema: {err}"))?;
+        Ok(Self { connection })
+    }
+
+    /// Stores one run; re-recording a run id replaces its previous rows.
+    pub fn record_run(
+        &mut self,
+        metadata: &LogMetadata,
+        report: &ExecutionReport,
+        log_path: Option<&Path>,
+    ) -> Result<(), String> {
+        let tx = self
+            .connection
+            .transaction()
+            .map_err(|err| err.to_string())?;
+        tx.execute("DELETE FROM runs WHERE run_id = ?1", [&metadata.run_id])
+            .map_err(|err| err.to_string())?;
+        tx.execute(
+            "INSERT INTO runs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            params![
+                metadata.run_id,
+                metadata.timestamp,
+                metadata.contract_name,
+                metadata.environment,
+                metadata.temperature,
+                metadata.time_step_ms as i64,
+                metadata.accepted_spans as i64,
+                metadata.rejected_spans as i64,
+                metadata.acceptance_rate,
+                metadata.final_potential_energy,
+                metadata.final_gibbs_energy,
+                metadata.informational_efficiency,
+                metadata.total_work,
+                metadata.tm_score,
+                metadata.gdt_ts,
+                log_path.map(|path| path.display().to_string()),
+            ],
+        )
+        .map_err(|err| format!("failed to insert run: {err}"))?;
+
+        {
+            let mut insert_span = tx
+                .prepare("INSERT INTO spans VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)")
+                .map_err(|err| err.to_string())?;
+            // Accepted and ghost spans interleaved again in execution order
+            let mut outcomes: Vec<_> = report
+                .applied_rotations
+                .iter()
+                .chain(&report.ghost_rotations)
+                .collect();
+            outcomes.sort_by_key(|outcome| outcome.span_record.step);
+            for (index, outcome) in outcomes.into_iter().enumerate() {
+                let span = FoldSpan::from_outcome(outcome);
+                insert_span
+                    .execute(params![
+                        metadata.run_id,
+                        index as i64,
+                        span.id,
+                        span.ghost_flag,
+                        span.delta_theta,
+                        span.delta_S,
+                        span.delta_I,
+                        span.delta_E,
+                        span.G,
+                        span.duration_ms as i64,
+                    ])
+                    .map_err(|err| format!("failed to insert span: {err}"))?;
+            }
+
+            let mut insert_violation = tx
+                .prepare(
+                    "INSERT INTO violations (run_id, violation_index, kind, detail, step, code, severity) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .map_err(|err| err.to_string())?;
+            for (index, record) in report.violations.iter().enumerate() {
+                let violation = &record.violation;
+                insert_violation
+                    .execute(params![
+                        metadata.run_id,
+                        index as i64,
+                        violation.kind(),
+                        format!("{violation:?}"),
+                        record.step as i64,
+                        violation.code(),
+                        violation.severity().as_str(),
+                    ])
+                    .map_err(|err| format!("failed to insert violation: {err}"))?;
+            }
+
+            let metrics = FoldingMetrics::from_report(report);
+            let stats = &report.metropolis_stats;
+            let mut insert_metric = tx
+                .prepare("INSERT INTO metrics VALUES (?1, ?2, ?3)")
+                .map_err(|err| err.to_string())?;
+            for (name, value) in [
+                ("total_entropy", metrics.total_entropy),
+                ("ghost_entropy", metrics.ghost_entropy),
+                ("total_information", metrics.total_information),
+                ("ghost_information", metrics.ghost_information),
+                ("applied_spans", metrics.applied_spans as f64),
+                ("ghost_spans", metrics.ghost_spans as f64),
+                ("metropolis_acceptance_rate", stats.acceptance_rate()),
+                (
+                    "final_potential_energy",
+                    report.final_energy.total_potential,
+                ),
+            ]
+            .into_iter()
+            .chain(
+                metadata
+                    .quasi_harmonic_entropy
+                    .map(|entropy| ("quasi_harmonic_entropy", entropy)),
+            )
+            .chain(metadata.foldability.map(|index| ("foldability", index)))
+            .chain(metadata.energy_gap.map(|gap| ("energy_gap", gap)))
+            {
+                insert_metric
+                    .execute(params![metadata.run_id, name, value])
+                    .map_err(|err| format!("failed to insert metric: {err}"))?;
+            }
+        }
+        tx.commit()
+            .map_err(|err| format!("failed to commit run: {err}"))
+    }
+
+    /// Runs arbitrary SQL and renders every cell as text.
+    pub fn query(&self, sql: &str) -> Result<QueryResult, String> {
+        let mut statement = self
+            .connection
+            .prepare(sql)
+            .map_err(|err| format!("invalid query: {err}"))?;
+        let columns: Vec<String> = statement
+            .column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let width = columns.len();
+        let rows = statement
+            .query_map([], |row| {
+                (0..width)
+                    .map(|idx| row.get_ref(idx).map(render_value))
+                    .collect::<Result<Vec<String>, _>>()
+            })
+            .map_err(|err| format!("query failed: {err}"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("query failed: {err}"))?;
+        Ok(QueryResult { columns, rows })
+    }
+}
+
+fn render_value(value: ValueRef<'_>) -> String {
+    match value {
+        ValueRef::Null => "NULL".into(),
+        ValueRef::Integer(value) => value.to_string(),
+        ValueRef::Real(value) => value.to_string(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned(),
+        ValueRef::Blob(bytes) => format!("<blob {} bytes>", bytes.len()),
+    }
+}
+
+fn add_missing_columns(connection: &Connection) -> rusqlite::Result<()> {
+    for (column, sql_type) in VIOLATION_COLUMNS {
+        let present: bool = connection.query_row(
+            "SELECT count(*) > 0 FROM pragma_table_info('violations') WHERE name = ?1",
+            [column],
+            |row| row.get(0),
+        )?;
+        if !present {
+            connection.execute_batch(&format!(
+                "ALTER TABLE violations ADD COLUMN {column} {sql_type};"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Provenance;
+    use folding_core::physics_bridge::PhysicsEngine;
+    use folding_core::test_support::permissive_ruleset;
+    use folding_core::{FoldingContract, FoldingEngineBuilder};
+    use folding_molecule::PeptideChain;
+
+    #[test]
+    fn records_runs_and_answers_queries() {
+        let contract = FoldingContract::from_lines(&[
+            "rotate residue=1 angle=5 duration=1",
+            "ghost on",
+            "rotate residue=2 angle=5 duration=1",
+            "ghost off",
+            "rotate residue=1 angle=-3 duration=1",
+            "commit",
+        ])
+        .unwrap();
+        let mut engine = FoldingEngineBuilder::new()
+            .with_chain(PeptideChain::from_sequence("ACDE"))
+            .with_ruleset(permissive_ruleset())
+            // Hot enough that Metropolis accepts every move
+            .with_acceptance_temperature(1e6)
+            .with_rng_seed(7)
+            .build();
+        let report = engine.execute_contract(&contract);
+        let metadata = LogMetadata {
+            run_id: "run-1".into(),
+            run_name: None,
+            timestamp: "0".into(),
+            contract_name: Some("demo".into()),
+            environment: "aqueous".into(),
+            temperature: 298.0,
+            time_step_ms: 1,
+            accepted_spans: report.applied_rotations.len(),
+            rejected_spans: report.rejections.len(),
+            acceptance_rate: 1.0,
+            final_potential_energy: 0.0,
+            final_gibbs_energy: 0.0,
+            informational_efficiency: 1.0,
+            total_work: 0.0,
+            tm_score: None,
+            gdt_ts: None,
+            heuristic_entropy: 0.0,
+            quasi_harmonic_entropy: None,
+            foldability: None,
+            energy_gap: None,
+            energy_samples: report.energy_samples,
+            span_time_ms: 0.0,
+            simulated_time_ps: 0.0,
+            wall_time_ms: 0.0,
+            remote_uri: None,
+            level_choice: None,
+            provenance: Provenance::current(report.physics_level, PhysicsEngine::Native),
+        };
+
+        let mut sink = SqliteSink::open(Path::new(":memory:")).unwrap();
+        sink.record_run(&metadata, &report, None).unwrap();
+        sink.record_run(&metadata, &report, None).unwrap();
+
+        let runs = sink.query(RUNS_QUERY).unwrap();
+        assert_eq!(runs.rows.len(), 1);
+        assert_eq!(runs.rows[0][2], "demo");
+        let spans = sink
+            .query("SELECT count(*) FROM spans WHERE run_id = 'run-1'")
+            .unwrap();
+        let expected = report.applied_rotations.len() + report.ghost_rotations.len();
+        assert_eq!(spans.rows[0][0], expected.to_string());
+        // The ghost span sits between the two it ran between
+        let ghosts = sink
+            .query("SELECT ghost FROM spans WHERE run_id = 'run-1' ORDER BY span_index")
+            .unwrap();
+        assert_eq!(ghosts.rows, [["0"], ["1"], ["0"]]);
+        let metric = sink
+            .query("SELECT value FROM metrics WHERE name = 'applied_spans'")
+            .unwrap();
+        assert_eq!(
+            metric.rows[0][0],
+            (report.applied_rotations.len() as f64).to_string()
+        );
+        assert!(sink.query("SELECT * FROM missing").is_err());
+    }
+
+    #[test]
+    fn older_databases_gain_violation_codes() {
+        let sink = SqliteSink::open(Path::new(":memory:")).unwrap();
+        sink.connection
+            .execute_batch(
+                "DROP TABLE violations; CREATE TABLE violations (run_id TEXT NOT NULL, \
+                 violation_index INTEGER NOT NULL, kind TEXT NOT NULL, detail TEXT NOT NULL);",
+            )
+            .unwrap();
+        add_missing_columns(&sink.connection).unwrap();
+        add_missing_columns(&sink.connection).unwrap();
+        let columns = sink
+            .query("SELECT name FROM pragma_table_info('violations')")
+            .unwrap();
+        let names: Vec<&str> = columns.rows.iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(names[4..], ["step", "code", "severity"]);
+        assert!(sink.query(VIOLATIONS_QUERY).is_ok());
+    }
+}
@@ -0,0 +1,77 @@
+//! `proptest` strategies for chains (feature `proptest`).
+
+use std::ops::RangeInclusive;
+
+use proptest::prelude::*;
+
+use crate::{Dihedral, PeptideChain, ResidueId};
+
+/// One-letter codes of the twenty standard amino acids.
+pub const AMINO_ACIDS: &str = "ACDEFGHIKLMNPQRSTVWY";
+
+/// Residue counts drawn by `any::<PeptideChain>()`.
+pub const DEFAULT_CHAIN_LENGTHS: RangeInclusive<usize> = 1..=40;
+
+/// Standard one-letter sequences with a length in `lengths`.
+pub fn sequence_strategy(lengths: RangeInclusive<usize>) -> impl Strategy<Value = String> {
+    let codes: Vec<char> = AMINO_ACIDS.chars().collect();
+    prop::collection::vec(prop::sample::select(codes), lengths)
+        .prop_map(|symbols| symbols.into_iter().collect())
+}
+
+/// Chains laid out by [`PeptideChain::from_sequence`] with phi/psi drawn from
+/// ±180°.
+pub fn chain_strategy(lengths: RangeInclusive<usize>) -> impl Strategy<Value = PeptideChain> {
+    sequence_strategy(lengths)
+        .prop_flat_map(|sequence| {
+            let torsions =
+                prop::collection::vec((-180.0..180.0f64, -180.0..180.0f64), sequence.len());
+            (Just(sequence), torsions)
+        })
+        .prop_map(|(sequence, torsions)| {
+            let mut chain = PeptideChain::from_sequence(&sequence);
+            for (index, (phi, psi)) in torsions.into_iter().enumerate() {
+                chain.phi_mut()[index] = phi;
+                chain.psi_mut()[index] = psi;
+            }
+            chain
+        })
+}
+
+impl Arbitrary for PeptideChain {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        chain_strategy(DEFAULT_CHAIN_LENGTHS).boxed()
+    }
+}
+
+impl Arbitrary for Dihedral {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        prop::sample::select(vec![
+            Dihedral::Phi,
+            Dihedral::Psi,
+            Dihedral::Omega,
+            Dihedral::Chi1,
+            Dihedral::Chi2,
+            Dihedral::Chi3,
+            Dihedral::Chi4,
+        ])
+        .boxed()
+    }
+}
+
+impl Arbitrary for ResidueId {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (0..*DEFAULT_CHAIN_LENGTHS.end())
+            .prop_map(ResidueId)
+            .boxed()
+    }
+}
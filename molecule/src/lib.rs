@@ -1,5 +1,20 @@
 use std::f64::consts::PI;
+use std::ops::Range;
 
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+pub mod side_chain;
+
+pub use side_chain::{Rotamer, SideChainBead, find_rotamer, nearest_rotamer, rotamers};
+
+/// Cargo features folding-molecule was compiled with.
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "proptest") {
+        features.push("proptest");
+    }
+    features
+}
 
 /// Identifier for a residue within a peptide chain.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -31,6 +46,58 @@ impl From<AminoAcid> for String {
     }
 }
 
+/// Torsion a rotation acts on: the backbone phi/psi/omega or a side-chain chi.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Dihedral {
+    #[default]
+    Phi,
+    Psi,
+    Omega,
+    Chi1,
+    Chi2,
+    Chi3,
+    Chi4,
+}
+
+impl Dihedral {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dihedral::Phi => "phi",
+            Dihedral::Psi => "psi",
+            Dihedral::Omega => "omega",
+            Dihedral::Chi1 => "chi1",
+            Dihedral::Chi2 => "chi2",
+            Dihedral::Chi3 => "chi3",
+            Dihedral::Chi4 => "chi4",
+        }
+    }
+
+    /// Accepts the names and the Greek letters, e.g. `psi`, `ψ`, `chi2`, `χ2`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "phi" | "φ" => Some(Dihedral::Phi),
+            "psi" | "ψ" => Some(Dihedral::Psi),
+            "omega" | "ω" => Some(Dihedral::Omega),
+            "chi1" | "chi" | "χ1" | "χ" => Some(Dihedral::Chi1),
+            "chi2" | "χ2" => Some(Dihedral::Chi2),
+            "chi3" | "χ3" => Some(Dihedral::Chi3),
+            "chi4" | "χ4" => Some(Dihedral::Chi4),
+            _ => None,
+        }
+    }
+
+    /// Zero-based side-chain index, `None` for backbone torsions.
+    pub fn chi_index(&self) -> Option<usize> {
+        match self {
+            Dihedral::Chi1 => Some(0),
+            Dihedral::Chi2 => Some(1),
+            Dihedral::Chi3 => Some(2),
+            Dihedral::Chi4 => Some(3),
+            _ => None,
+        }
+    }
+}
+
 /// Representation of a single amino-acid residue with coarse coordinates.
 #[derive(Clone, Debug)]
 pub struct Residue {
@@ -38,6 +105,10 @@ pub struct Residue {
     pub name: String,
     pub phi: f64,
     pub psi: f64,
+    /// Peptide bond torsion to the next residue; 180 is trans.
+    pub omega: f64,
+    /// Side-chain torsions chi1..chi4; only the first `chi_count()` are meaningful.
+    pub chi: [f64; 4],
     position: [f64; 3],
 }
 
@@ -48,10 +119,36 @@ impl Residue {
             name: name.into(),
             phi: 0.0,
             psi: 0.0,
+            omega: 180.0,
+            chi: [0.0; 4],
             position,
         }
     }
 
+    /// Current value of `dihedral` in degrees.
+    pub fn torsion(&self, dihedral: Dihedral) -> f64 {
+        match dihedral {
+            Dihedral::Phi => self.phi,
+            Dihedral::Psi => self.psi,
+            Dihedral::Omega => self.omega,
+            chi => self.chi[chi.chi_index().unwrap_or(0)],
+        }
+    }
+
+    pub fn torsion_mut(&mut self, dihedral: Dihedral) -> &mut f64 {
+        match dihedral {
+            Dihedral::Phi => &mut self.phi,
+            Dihedral::Psi => &mut self.psi,
+            Dihedral::Omega => &mut self.omega,
+            chi => &mut self.chi[chi.chi_index().unwrap_or(0)],
+        }
+    }
+
+    /// Number of side-chain chi angles this residue type has.
+    pub fn chi_count(&self) -> usize {
+        side_chain_dihedral_count(&self.name)
+    }
+
     pub fn with_position(mut self, position: [f64; 3]) -> Self {
         self.position = position;
         self
@@ -80,15 +177,49 @@ impl Default for BondConstraintSet {
     }
 }
 
+/// Where one chain of a complex begins within a merged [`PeptideChain`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainStart {
+    /// Index of the chain's first residue.
+    pub residue: usize,
+    /// One-character chain identifier, as in PDB files.
+    pub id: char,
+}
+
 /// Simplified peptide chain with evenly spaced residues.
+///
+/// Residues are stored as parallel arrays (structure of arrays), so force
+/// loops stream through contiguous positions and physics backends can borrow
+/// [`positions`](Self::positions) without copying. [`Residue`] is the owned
+/// form used to build a chain; [`ResidueRef`] and [`ResidueMut`] view one
+/// residue in place.
+///
+/// A chain may also hold several chains of a complex back to back (see
+/// [`MolecularSystem::to_chain`]): the backbone is not bonded across a
+/// [`ChainStart`], so bonded terms and torsion moves stop at chain ends while
+/// nonbonded terms span the whole complex.
 #[derive(Clone, Debug, Default)]
 pub struct PeptideChain {
-    residues: Vec<Residue>,
+    ids: Vec<ResidueId>,
+    names: Vec<String>,
+    positions: Vec<[f64; 3]>,
+    phi: Vec<f64>,
+    psi: Vec<f64>,
+    omega: Vec<f64>,
+    chi: Vec<[f64; 4]>,
+    /// Empty for a single unnamed chain.
+    chains: Vec<ChainStart>,
+    /// `None` for a bare Cα trace.
+    side_chain: Option<SideChainBead>,
 }
 
 impl PeptideChain {
     pub fn new(residues: Vec<Residue>) -> Self {
-        Self { residues }
+        let mut chain = Self::default();
+        for residue in residues {
+            chain.push(residue);
+        }
+        chain
     }
 
     pub fn from_sequence(sequence: &str) -> Self {
@@ -103,31 +234,557 @@ impl PeptideChain {
                 Residue::new(ResidueId(idx), name, position)
             })
             .collect();
-        Self { residues }
+        Self::new(residues)
+    }
+
+    pub fn push(&mut self, residue: Residue) {
+        self.ids.push(residue.id);
+        self.names.push(residue.name);
+        self.positions.push(residue.position);
+        self.phi.push(residue.phi);
+        self.psi.push(residue.psi);
+        self.omega.push(residue.omega);
+        self.chi.push(residue.chi);
+    }
+
+    /// Starts chain `id`: the next residue pushed is its first and is not
+    /// bonded to the residue before it.
+    pub fn begin_chain(&mut self, id: char) {
+        let residue = self.len();
+        self.chains.retain(|start| start.residue != residue);
+        self.chains.push(ChainStart { residue, id });
+    }
+
+    /// Chains of a complex in residue order; empty for a single unnamed chain.
+    pub fn chain_starts(&self) -> &[ChainStart] {
+        &self.chains
+    }
+
+    /// First residues of every chain after the first.
+    pub fn chain_breaks(&self) -> Vec<usize> {
+        self.chains
+            .iter()
+            .map(|start| start.residue)
+            .filter(|&residue| residue > 0)
+            .collect()
+    }
+
+    /// Splits the chain at `breaks`, naming the chains A, B, C, … as
+    /// [`chain_letter`] does; for backends that only carry break indices.
+    pub fn set_chain_breaks(&mut self, breaks: &[usize]) {
+        self.chains.clear();
+        if breaks.is_empty() {
+            return;
+        }
+        let starts = std::iter::once(0).chain(breaks.iter().copied().filter(|&b| b > 0));
+        self.chains = starts
+            .enumerate()
+            .map(|(index, residue)| ChainStart {
+                residue,
+                id: chain_letter(index),
+            })
+            .collect();
+    }
+
+    /// Number of chains; 1 for a plain chain.
+    pub fn chain_count(&self) -> usize {
+        self.chain_ranges().len()
+    }
+
+    /// Identifier and residue range of each chain; a plain chain is `A`.
+    pub fn chain_ranges(&self) -> Vec<(char, Range<usize>)> {
+        if self.chains.is_empty() {
+            return vec![('A', 0..self.len())];
+        }
+        let mut ranges: Vec<(char, Range<usize>)> = Vec::new();
+        for (index, start) in self.chains.iter().enumerate() {
+            let end = self
+                .chains
+                .get(index + 1)
+                .map_or(self.len(), |next| next.residue);
+            ranges.push((start.id, start.residue..end));
+        }
+        // Residues before the first named chain belong to it
+        if let Some(first) = ranges.first_mut() {
+            first.1.start = 0;
+        }
+        ranges
+    }
+
+    /// Whether residue `index` is bonded to residue `index + 1`.
+    pub fn is_bonded(&self, index: usize) -> bool {
+        index + 1 < self.len() && !self.chains.iter().any(|start| start.residue == index + 1)
+    }
+
+    /// End (exclusive) of the chain holding residue `index`.
+    pub fn chain_end(&self, index: usize) -> usize {
+        self.chains
+            .iter()
+            .map(|start| start.residue)
+            .find(|&residue| residue > index)
+            .unwrap_or(self.len())
+    }
+
+    /// Gives every residue but glycine a side-chain bead of kind `bead`.
+    pub fn with_side_chains(mut self, bead: SideChainBead) -> Self {
+        self.side_chain = Some(bead);
+        self
+    }
+
+    pub fn set_side_chains(&mut self, bead: Option<SideChainBead>) {
+        self.side_chain = bead;
     }
 
-    pub fn residues(&self) -> &[Residue] {
-        &self.residues
+    /// Side-chain model of the chain; `None` for a bare Cα trace.
+    pub fn side_chains(&self) -> Option<SideChainBead> {
+        self.side_chain
     }
 
-    pub fn residues_mut(&mut self) -> &mut [Residue] {
-        &mut self.residues
+    /// Side-chain bead of residue `index`, placed from the Cα trace and its
+    /// chi1 (see [`side_chain`]); `None` without a side-chain model, for
+    /// glycine, or for a residue bonded to nothing.
+    pub fn side_chain_position(&self, index: usize) -> Option<[f64; 3]> {
+        let bead = self.side_chain?;
+        let previous = (index > 0 && self.is_bonded(index - 1)).then(|| self.positions[index - 1]);
+        let next = self.is_bonded(index).then(|| self.positions[index + 1]);
+        side_chain::place_bead(
+            bead,
+            self.names.get(index)?,
+            previous,
+            self.positions[index],
+            next,
+            self.chi[index][0],
+        )
     }
 
-    pub fn residue(&self, id: ResidueId) -> Option<&Residue> {
-        self.residues.get(id.0)
+    /// [`side_chain_position`](Self::side_chain_position) of every residue.
+    pub fn side_chain_positions(&self) -> Vec<Option<[f64; 3]>> {
+        (0..self.len())
+            .map(|index| self.side_chain_position(index))
+            .collect()
     }
 
-    pub fn residue_mut(&mut self, id: ResidueId) -> Option<&mut Residue> {
-        self.residues.get_mut(id.0)
+    pub fn residues(
+        &self,
+    ) -> impl ExactSizeIterator<Item = ResidueRef<'_>> + DoubleEndedIterator {
+        (0..self.len()).map(move |index| self.view(index))
+    }
+
+    pub fn residues_mut(
+        &mut self,
+    ) -> impl ExactSizeIterator<Item = ResidueMut<'_>> + DoubleEndedIterator {
+        self.ids
+            .iter()
+            .zip(&self.names)
+            .zip(self.positions.iter_mut())
+            .zip(self.phi.iter_mut())
+            .zip(self.psi.iter_mut())
+            .zip(self.omega.iter_mut())
+            .zip(self.chi.iter_mut())
+            .map(|((((((id, name), position), phi), psi), omega), chi)| ResidueMut {
+                id: *id,
+                name,
+                position,
+                phi,
+                psi,
+                omega,
+                chi,
+            })
+    }
+
+    pub fn residue(&self, id: ResidueId) -> Option<ResidueRef<'_>> {
+        (id.0 < self.len()).then(|| self.view(id.0))
+    }
+
+    fn view(&self, index: usize) -> ResidueRef<'_> {
+        ResidueRef {
+            id: self.ids[index],
+            name: &self.names[index],
+            chain: self,
+            index,
+        }
+    }
+
+    pub fn residue_mut(&mut self, id: ResidueId) -> Option<ResidueMut<'_>> {
+        let index = id.0;
+        if index >= self.len() {
+            return None;
+        }
+        Some(ResidueMut {
+            id: self.ids[index],
+            name: &self.names[index],
+            position: &mut self.positions[index],
+            phi: &mut self.phi[index],
+            psi: &mut self.psi[index],
+            omega: &mut self.omega[index],
+            chi: &mut self.chi[index],
+        })
+    }
+
+    /// Cα positions in residue order.
+    pub fn positions(&self) -> &[[f64; 3]] {
+        &self.positions
+    }
+
+    pub fn positions_mut(&mut self) -> &mut [[f64; 3]] {
+        &mut self.positions
+    }
+
+    /// Backbone phi angles in degrees, in residue order.
+    pub fn phi(&self) -> &[f64] {
+        &self.phi
+    }
+
+    pub fn phi_mut(&mut self) -> &mut [f64] {
+        &mut self.phi
+    }
+
+    /// Backbone psi angles in degrees, in residue order.
+    pub fn psi(&self) -> &[f64] {
+        &self.psi
+    }
+
+    pub fn psi_mut(&mut self) -> &mut [f64] {
+        &mut self.psi
+    }
+
+    /// Peptide-bond omega angles in degrees; residue `i` holds the bond to
+    /// residue `i + 1`.
+    pub fn omega(&self) -> &[f64] {
+        &self.omega
+    }
+
+    /// Side-chain chi1..chi4 angles in degrees, in residue order.
+    pub fn chi(&self) -> &[[f64; 4]] {
+        &self.chi
+    }
+
+    pub fn chi_mut(&mut self) -> &mut [[f64; 4]] {
+        &mut self.chi
+    }
+
+    pub fn ids(&self) -> &[ResidueId] {
+        &self.ids
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
     }
 
     pub fn len(&self) -> usize {
-        self.residues.len()
+        self.ids.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.residues.is_empty()
+        self.ids.is_empty()
+    }
+
+    /// Turns `dihedral` of `residue` by `delta_angle` degrees. Phi keeps the
+    /// coarse in-plane placement of the residue itself. Psi and omega swing every
+    /// residue after `residue + 1` in its chain about the Cα(i)→Cα(i+1) virtual
+    /// bond, so bond lengths are preserved. Chi only moves the side chain, whose
+    /// bead is placed from the recorded angle, so just the angle changes.
+    pub fn rotate_torsion(&mut self, residue: ResidueId, dihedral: Dihedral, delta_angle: f64) {
+        let Some(mut residue_entry) = self.residue_mut(residue) else {
+            return;
+        };
+        *residue_entry.torsion_mut(dihedral) += delta_angle;
+        match dihedral {
+            Dihedral::Phi => {
+                let current_position = residue_entry.position();
+                let radius = (current_position[0].powi(2) + current_position[1].powi(2))
+                    .sqrt()
+                    .max(1.0);
+                let angle_rad = residue_entry.torsion(Dihedral::Phi).to_radians();
+                let new_position = [
+                    radius * angle_rad.cos(),
+                    radius * angle_rad.sin(),
+                    current_position[2],
+                ];
+                residue_entry.set_position(new_position);
+            }
+            Dihedral::Psi | Dihedral::Omega => {
+                self.rotate_downstream(residue.0, delta_angle.to_radians())
+            }
+            _ => {}
+        }
+    }
+
+    /// Rigidly rotates residues `pivot + 2..` of the pivot's chain about the
+    /// `pivot`→`pivot + 1` bond.
+    fn rotate_downstream(&mut self, pivot: usize, angle: f64) {
+        let chain_end = self.chain_end(pivot);
+        if pivot + 2 >= chain_end {
+            return;
+        }
+        let origin = self.positions[pivot];
+        let end = self.positions[pivot + 1];
+        let axis = [end[0] - origin[0], end[1] - origin[1], end[2] - origin[2]];
+        self.rotate_residues(pivot + 2..chain_end, origin, axis, angle);
+    }
+
+    /// Rotates `range` by `angle` radians about the line through `origin`
+    /// along `axis`. A zero axis leaves the residues untouched.
+    pub fn rotate_residues(
+        &mut self,
+        range: Range<usize>,
+        origin: [f64; 3],
+        axis: [f64; 3],
+        angle: f64,
+    ) {
+        let length = (axis[0].powi(2) + axis[1].powi(2) + axis[2].powi(2)).sqrt();
+        if length < f64::EPSILON {
+            return;
+        }
+        let k = [axis[0] / length, axis[1] / length, axis[2] / length];
+        let (sin, cos) = angle.sin_cos();
+        let end = range.end.min(self.positions.len());
+        for position in &mut self.positions[range.start.min(end)..end] {
+            let p = *position;
+            let v = [p[0] - origin[0], p[1] - origin[1], p[2] - origin[2]];
+            let k_dot_v = k[0] * v[0] + k[1] * v[1] + k[2] * v[2];
+            let k_cross_v = [
+                k[1] * v[2] - k[2] * v[1],
+                k[2] * v[0] - k[0] * v[2],
+                k[0] * v[1] - k[1] * v[0],
+            ];
+            let mut rotated = [0.0; 3];
+            for axis in 0..3 {
+                rotated[axis] = origin[axis]
+                    + v[axis] * cos
+                    + k_cross_v[axis] * sin
+                    + k[axis] * k_dot_v * (1.0 - cos);
+            }
+            *position = rotated;
+        }
+    }
+
+    /// Bytes the chain holds on the heap, counting allocated capacity.
+    pub fn heap_bytes(&self) -> usize {
+        use std::mem::size_of;
+        self.ids.capacity() * size_of::<ResidueId>()
+            + self.names.capacity() * size_of::<String>()
+            + self.names.iter().map(String::capacity).sum::<usize>()
+            + self.positions.capacity() * size_of::<[f64; 3]>()
+            + (self.phi.capacity() + self.psi.capacity() + self.omega.capacity())
+                * size_of::<f64>()
+            + self.chi.capacity() * size_of::<[f64; 4]>()
+            + self.chains.capacity() * size_of::<ChainStart>()
+    }
+}
+
+/// Several peptide chains simulated together, e.g. a dimer, each under a
+/// one-character identifier. The engine folds the merged
+/// [`to_chain`](Self::to_chain) form; [`from_chain`](Self::from_chain) splits
+/// a merged chain back.
+#[derive(Clone, Debug, Default)]
+pub struct MolecularSystem {
+    chains: Vec<(char, PeptideChain)>,
+}
+
+impl MolecularSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `chain` under `id`; identifiers must be unique.
+    pub fn push(&mut self, id: char, chain: PeptideChain) -> Result<(), String> {
+        if self.chain(id).is_some() {
+            return Err(format!("duplicate chain identifier '{id}'"));
+        }
+        self.chains.push((id, chain));
+        Ok(())
+    }
+
+    /// Adds `chain` under the next free letter (A, B, C, …); fails once all
+    /// 62 PDB chain identifiers are taken.
+    pub fn with_chain(mut self, chain: PeptideChain) -> Result<Self, String> {
+        let id = (0..CHAIN_IDS.len())
+            .map(chain_letter)
+            .find(|id| self.chain(*id).is_none())
+            .ok_or_else(|| format!("no free chain identifier among {}", CHAIN_IDS.len()))?;
+        self.chains.push((id, chain));
+        Ok(self)
+    }
+
+    pub fn chains(&self) -> &[(char, PeptideChain)] {
+        &self.chains
+    }
+
+    pub fn chain(&self, id: char) -> Option<&PeptideChain> {
+        self.chains
+            .iter()
+            .find(|(chain_id, _)| *chain_id == id)
+            .map(|(_, chain)| chain)
+    }
+
+    pub fn ids(&self) -> Vec<char> {
+        self.chains.iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Number of chains.
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+
+    pub fn residue_count(&self) -> usize {
+        self.chains.iter().map(|(_, chain)| chain.len()).sum()
+    }
+
+    /// Every chain back to back with residues renumbered from 0, each
+    /// starting a [`ChainStart`].
+    pub fn to_chain(&self) -> PeptideChain {
+        let mut merged = PeptideChain {
+            side_chain: self.chains.iter().find_map(|(_, chain)| chain.side_chain),
+            ..PeptideChain::default()
+        };
+        for (id, chain) in &self.chains {
+            merged.begin_chain(*id);
+            for residue in chain.residues() {
+                let mut residue = residue.to_residue();
+                residue.id = ResidueId(merged.len());
+                merged.push(residue);
+            }
+        }
+        merged
+    }
+
+    /// Splits `chain` at its chain starts, renumbering each chain from 0.
+    pub fn from_chain(chain: &PeptideChain) -> Self {
+        let chains = chain
+            .chain_ranges()
+            .into_iter()
+            .map(|(id, range)| {
+                let residues = range
+                    .enumerate()
+                    .map(|(offset, index)| {
+                        let mut residue = chain
+                            .residue(ResidueId(index))
+                            .expect("index in range")
+                            .to_residue();
+                        residue.id = ResidueId(offset);
+                        residue
+                    })
+                    .collect();
+                let mut part = PeptideChain::new(residues);
+                part.side_chain = chain.side_chain;
+                (id, part)
+            })
+            .collect();
+        Self { chains }
+    }
+}
+
+const CHAIN_IDS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// PDB chain identifier for the `index`-th chain: A–Z, then a–z, then 0–9.
+pub fn chain_letter(index: usize) -> char {
+    CHAIN_IDS[index % CHAIN_IDS.len()] as char
+}
+
+/// Read-only view of one residue of a [`PeptideChain`].
+#[derive(Clone, Copy, Debug)]
+pub struct ResidueRef<'a> {
+    pub id: ResidueId,
+    pub name: &'a str,
+    chain: &'a PeptideChain,
+    index: usize,
+}
+
+impl ResidueRef<'_> {
+    pub fn phi(&self) -> f64 {
+        self.chain.phi[self.index]
+    }
+
+    pub fn psi(&self) -> f64 {
+        self.chain.psi[self.index]
+    }
+
+    pub fn omega(&self) -> f64 {
+        self.chain.omega[self.index]
+    }
+
+    pub fn chi(&self) -> [f64; 4] {
+        self.chain.chi[self.index]
+    }
+
+    pub fn torsion(&self, dihedral: Dihedral) -> f64 {
+        match dihedral {
+            Dihedral::Phi => self.phi(),
+            Dihedral::Psi => self.psi(),
+            Dihedral::Omega => self.omega(),
+            chi => self.chi()[chi.chi_index().unwrap_or(0)],
+        }
+    }
+
+    pub fn chi_count(&self) -> usize {
+        side_chain_dihedral_count(self.name)
+    }
+
+    pub fn position(&self) -> [f64; 3] {
+        self.chain.positions[self.index]
+    }
+
+    /// Owned copy of the residue.
+    pub fn to_residue(&self) -> Residue {
+        Residue {
+            id: self.id,
+            name: self.name.to_string(),
+            phi: self.phi(),
+            psi: self.psi(),
+            omega: self.omega(),
+            chi: self.chi(),
+            position: self.position(),
+        }
+    }
+}
+
+/// Mutable view of one residue of a [`PeptideChain`]; the name and id are
+/// copies, fixed once the residue is in a chain.
+#[derive(Debug)]
+pub struct ResidueMut<'a> {
+    pub id: ResidueId,
+    pub name: &'a str,
+    position: &'a mut [f64; 3],
+    phi: &'a mut f64,
+    psi: &'a mut f64,
+    omega: &'a mut f64,
+    chi: &'a mut [f64; 4],
+}
+
+impl ResidueMut<'_> {
+    pub fn torsion(&self, dihedral: Dihedral) -> f64 {
+        match dihedral {
+            Dihedral::Phi => *self.phi,
+            Dihedral::Psi => *self.psi,
+            Dihedral::Omega => *self.omega,
+            chi => self.chi[chi.chi_index().unwrap_or(0)],
+        }
+    }
+
+    pub fn torsion_mut(&mut self, dihedral: Dihedral) -> &mut f64 {
+        match dihedral {
+            Dihedral::Phi => self.phi,
+            Dihedral::Psi => self.psi,
+            Dihedral::Omega => self.omega,
+            chi => &mut self.chi[chi.chi_index().unwrap_or(0)],
+        }
+    }
+
+    pub fn chi_count(&self) -> usize {
+        side_chain_dihedral_count(self.name)
+    }
+
+    pub fn position(&self) -> [f64; 3] {
+        *self.position
+    }
+
+    pub fn set_position(&mut self, position: [f64; 3]) {
+        *self.position = position;
     }
 }
 
@@ -135,6 +792,8 @@ impl PeptideChain {
 #[derive(Clone, Debug, Default)]
 pub struct EnergySummary {
     pub potential: f64,
+    /// Part of `potential` between residues of different chains.
+    pub interchain: f64,
 }
 
 impl EnergySummary {
@@ -165,23 +824,136 @@ impl EnergyModel {
     }
 
     pub fn energy_summary(&self, chain: &PeptideChain) -> EnergySummary {
+        let positions = chain.positions();
         let mut potential = 0.0;
-        for window in chain.residues().windows(2) {
-            if let [left, right] = window {
-                let dist = distance(left.position(), right.position());
-                let stretch = dist - 3.8;
-                potential += 0.5 * self.bond_strength * stretch * stretch;
+        for i in (1..positions.len()).filter(|&i| chain.is_bonded(i - 1)) {
+            let dist = distance(positions[i - 1], positions[i]);
+            let stretch = dist - 3.8;
+            potential += 0.5 * self.bond_strength * stretch * stretch;
+        }
+        for (i, &position) in positions.iter().enumerate() {
+            for &other in &positions[i + 1..] {
+                potential += self.contact_energy(distance(position, other));
             }
         }
-        for (i, residue) in chain.residues().iter().enumerate() {
-            for other in chain.residues().iter().skip(i + 1) {
-                let dist = distance(residue.position(), other.position());
+        let (side_chains, side_chains_interchain) = self.side_chain_energy(chain);
+        EnergySummary {
+            potential: potential + side_chains,
+            interchain: self.backbone_interchain_energy(chain) + side_chains_interchain,
+        }
+    }
+
+    /// Steric repulsion between residues of different chains of a complex.
+    pub fn interchain_energy(&self, chain: &PeptideChain) -> f64 {
+        self.backbone_interchain_energy(chain) + self.side_chain_energy(chain).1
+    }
+
+    /// Cα–Cα part of [`Self::interchain_energy`].
+    fn backbone_interchain_energy(&self, chain: &PeptideChain) -> f64 {
+        let positions = chain.positions();
+        let ranges = chain.chain_ranges();
+        let mut energy = 0.0;
+        for (index, (_, first)) in ranges.iter().enumerate() {
+            for (_, second) in &ranges[index + 1..] {
+                for i in first.clone() {
+                    for j in second.clone() {
+                        energy += self.contact_energy(distance(positions[i], positions[j]));
+                    }
+                }
+            }
+        }
+        energy
+    }
+
+    /// Steric repulsion the side-chain beads add, in total and between
+    /// residues of different chains, from one pass over their contacts.
+    fn side_chain_energy(&self, chain: &PeptideChain) -> (f64, f64) {
+        let (mut total, mut interchain) = (0.0, 0.0);
+        for_each_side_chain_contact(chain, |i, j, dist| {
+            let term = self.contact_energy(dist);
+            total += term;
+            if chain.chain_end(i) != chain.chain_end(j) {
+                interchain += term;
+            }
+        });
+        (total, interchain)
+    }
+
+    /// Steric repulsion between two non-bonded beads `dist` Å apart.
+    pub fn contact_energy(&self, dist: f64) -> f64 {
+        if dist > 0.0 {
+            self.steric_repulsion / dist.powi(12)
+        } else {
+            0.0
+        }
+    }
+
+    /// Splits the potential across residues: each bond and steric pair term is
+    /// shared equally between its two residues, so the values sum to the total.
+    pub fn residue_energies(&self, chain: &PeptideChain) -> Vec<f64> {
+        let positions = chain.positions();
+        let mut energies = vec![0.0; positions.len()];
+        for i in (1..positions.len()).filter(|&i| chain.is_bonded(i - 1)) {
+            let dist = distance(positions[i - 1], positions[i]);
+            let stretch = dist - 3.8;
+            let term = 0.5 * self.bond_strength * stretch * stretch;
+            energies[i - 1] += 0.5 * term;
+            energies[i] += 0.5 * term;
+        }
+        for i in 0..positions.len() {
+            for j in i + 1..positions.len() {
+                let dist = distance(positions[i], positions[j]);
                 if dist > 0.0 {
-                    potential += self.steric_repulsion / dist.powi(12);
+                    let term = self.steric_repulsion / dist.powi(12);
+                    energies[i] += 0.5 * term;
+                    energies[j] += 0.5 * term;
                 }
             }
         }
-        EnergySummary { potential }
+        for_each_side_chain_contact(chain, |i, j, dist| {
+            let term = self.contact_energy(dist);
+            energies[i] += 0.5 * term;
+            energies[j] += 0.5 * term;
+        });
+        energies
+    }
+}
+
+/// Beyond this distance (Å) a side-chain contact is below 1e-12 kcal/mol
+/// and is skipped.
+const SIDE_CHAIN_CUTOFF: f64 = 8.0;
+
+/// Calls `visit(residue, residue, distance)` for every steric pair a chain's
+/// side-chain beads add within [`SIDE_CHAIN_CUTOFF`]: every two beads, and
+/// each bead with every Cα but its own residue's and those bonded to it.
+/// Nothing for a bare Cα trace.
+fn for_each_side_chain_contact(chain: &PeptideChain, mut visit: impl FnMut(usize, usize, f64)) {
+    if chain.side_chains().is_none() {
+        return;
+    }
+    let beads = chain.side_chain_positions();
+    let positions = chain.positions();
+    let mut near = |i: usize, j: usize, a: [f64; 3], b: [f64; 3]| {
+        let dist = distance(a, b);
+        if dist <= SIDE_CHAIN_CUTOFF {
+            visit(i, j, dist);
+        }
+    };
+    for (i, bead) in beads.iter().enumerate() {
+        let Some(bead) = *bead else {
+            continue;
+        };
+        for (j, other) in beads.iter().enumerate().skip(i + 1) {
+            if let Some(other) = *other {
+                near(i, j, bead, other);
+            }
+        }
+        for (k, &position) in positions.iter().enumerate() {
+            let bonded = (k + 1 == i && chain.is_bonded(k)) || (k == i + 1 && chain.is_bonded(i));
+            if k != i && !bonded {
+                near(i, k, bead, position);
+            }
+        }
     }
 }
 
@@ -212,6 +984,17 @@ fn amino_acid_three_letter(symbol: char) -> String {
     .to_string()
 }
 
+/// Rotatable side-chain torsions per residue type; unknown names allow all four.
+fn side_chain_dihedral_count(name: &str) -> usize {
+    match name.to_ascii_uppercase().as_str() {
+        "GLY" | "ALA" => 0,
+        "SER" | "CYS" | "THR" | "VAL" => 1,
+        "ASP" | "ASN" | "HIS" | "PHE" | "TYR" | "TRP" | "ILE" | "LEU" | "PRO" => 2,
+        "GLU" | "GLN" | "MET" => 3,
+        _ => 4,
+    }
+}
+
 fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
     ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
 }
@@ -226,6 +1009,26 @@ mod tests {
         assert_eq!(chain.len(), 4);
         assert_eq!(chain.residue(ResidueId(0)).unwrap().name, "ALA");
         assert_eq!(chain.residue(ResidueId(2)).unwrap().name, "ASP");
+        assert_eq!(chain.residue(ResidueId(2)).unwrap().chi_count(), 2);
+        assert_eq!(chain.residue(ResidueId(0)).unwrap().torsion(Dihedral::Omega), 180.0);
+        assert_eq!(Dihedral::parse("χ2"), Some(Dihedral::Chi2));
+    }
+
+    #[test]
+    fn residue_views_share_the_chain_arrays() {
+        let mut chain = PeptideChain::from_sequence("AGS");
+        let mut residue = chain.residue_mut(ResidueId(1)).unwrap();
+        residue.set_position([1.0, 2.0, 3.0]);
+        *residue.torsion_mut(Dihedral::Psi) = -45.0;
+        assert_eq!(chain.positions()[1], [1.0, 2.0, 3.0]);
+        assert_eq!(chain.psi(), [0.0, -45.0, 0.0]);
+
+        for mut residue in chain.residues_mut() {
+            *residue.torsion_mut(Dihedral::Chi1) = 10.0;
+        }
+        let copy = chain.residue(ResidueId(1)).unwrap().to_residue();
+        assert_eq!((copy.name.as_str(), copy.psi, copy.chi[0]), ("GLY", -45.0, 10.0));
+        assert_eq!(PeptideChain::new(vec![copy]).positions(), [[1.0, 2.0, 3.0]]);
     }
 
     #[test]
@@ -235,5 +1038,65 @@ mod tests {
         let energy = model.total_energy(&chain);
         assert!(energy.is_finite());
         assert!(energy >= 0.0);
+        let per_residue: f64 = model.residue_energies(&chain).iter().sum();
+        assert!((per_residue - energy).abs() < 1e-9);
+    }
+
+    #[test]
+    fn system_chains_merge_without_a_bond_between_them() {
+        let mut second = PeptideChain::from_sequence("GGG");
+        for position in second.positions_mut() {
+            position[1] += 8.0;
+        }
+        let mut system = MolecularSystem::new();
+        system
+            .push('A', PeptideChain::from_sequence("AAAA"))
+            .unwrap();
+        system.push('B', second).unwrap();
+        assert!(system.push('B', PeptideChain::from_sequence("K")).is_err());
+        assert_eq!(system.residue_count(), 7);
+
+        let mut merged = system.to_chain();
+        assert_eq!(merged.chain_breaks(), [4]);
+        assert_eq!(merged.chain_ranges(), [('A', 0..4), ('B', 4..7)]);
+        assert!(merged.is_bonded(2) && !merged.is_bonded(3));
+        assert_eq!(merged.residue(ResidueId(4)).unwrap().name, "GLY");
+
+        // Swinging the end of chain A leaves chain B where it was
+        let before = merged.positions()[4..].to_vec();
+        merged.rotate_torsion(ResidueId(1), Dihedral::Psi, 60.0);
+        assert_eq!(merged.positions()[4..], before);
+
+        // The gap between the chains is not stretched like a bond
+        let model = EnergyModel::default();
+        let summary = model.energy_summary(&merged);
+        let split = MolecularSystem::from_chain(&merged);
+        let separate: f64 = split
+            .chains()
+            .iter()
+            .map(|(_, chain)| model.total_energy(chain))
+            .sum();
+        assert!(summary.interchain > 0.0);
+        assert!((summary.total() - summary.interchain - separate).abs() < 1e-9);
+        assert_eq!(split.ids(), ['A', 'B']);
+        assert_eq!(split.chain('B').unwrap().names(), ["GLY", "GLY", "GLY"]);
+        assert_eq!(split.chain('B').unwrap().positions(), before);
+        assert_eq!(
+            MolecularSystem::from_chain(&PeptideChain::from_sequence("AA")).ids(),
+            ['A']
+        );
+    }
+
+    #[test]
+    fn with_chain_takes_the_next_free_letter_until_none_is_left() {
+        let mut system = MolecularSystem::new();
+        system.push('A', PeptideChain::from_sequence("G")).unwrap();
+        let mut system = system.with_chain(PeptideChain::from_sequence("A")).unwrap();
+        assert_eq!(system.ids(), ['A', 'B']);
+        for _ in 2..62 {
+            system = system.with_chain(PeptideChain::from_sequence("A")).unwrap();
+        }
+        assert_eq!(system.ids().last(), Some(&'9'));
+        assert!(system.with_chain(PeptideChain::from_sequence("A")).is_err());
     }
 }
@@ -0,0 +1,461 @@
+//! Side-chain beads and the rotamer library.
+//!
+//! The chain is a Cα trace, so a side chain is one bead per residue placed
+//! from the trace itself: a Cβ on the L side of the local Cα plane, or a
+//! centroid swung about the Cα→Cβ axis by chi1. The preceding Cα stands in
+//! for the backbone N when measuring chi1; in real structures the two agree
+//! to a few degrees. Glycine has no bead.
+//!
+//! Rotamers are the common chi states of each residue type with their
+//! backbone-independent frequencies, named per chi `p` (+60°), `t` (180°) or
+//! `m` (-60°), or by the angle where a chi is not rotameric (e.g. `m-85`).
+
+/// Bead a chain's side chains are modelled with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SideChainBead {
+    /// The Cβ alone: fixed by the backbone, so chi moves leave it in place.
+    Beta,
+    /// The side-chain centroid, which chi1 swings about the Cα→Cβ axis.
+    Centroid,
+}
+
+/// One side-chain conformation of a residue type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rotamer {
+    pub name: &'static str,
+    /// chi1..chi4 in degrees; only the residue's own chi angles are meaningful.
+    pub chi: [f64; 4],
+    /// Frequency among the residue type's side chains in high-resolution
+    /// structures.
+    pub probability: f64,
+}
+
+/// Cα–Cβ distance in Å.
+pub const CA_CB_DISTANCE: f64 = 1.53;
+
+/// Angle (degrees) between the Cβ and the bisector of the Cα(i-1)–Cα–Cα(i+1)
+/// angle, toward the L side.
+const BETA_TILT_DEGREES: f64 = 44.0;
+
+/// Angle (degrees) the centroid leans off the Cα→Cβ axis: a tetrahedral Cγ.
+const CENTROID_TILT_DEGREES: f64 = 70.5;
+
+impl SideChainBead {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SideChainBead::Beta => "cb",
+            SideChainBead::Centroid => "centroid",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "cb" | "beta" => Some(SideChainBead::Beta),
+            "centroid" | "sc" => Some(SideChainBead::Centroid),
+            _ => None,
+        }
+    }
+}
+
+/// Å from the Cβ to the side-chain centroid; `None` for glycine.
+pub fn side_chain_reach(name: &str) -> Option<f64> {
+    let reach = match name.to_ascii_uppercase().as_str() {
+        "GLY" => return None,
+        "ALA" => 0.0,
+        "SER" | "THR" | "VAL" | "PRO" => 1.0,
+        "CYS" => 1.2,
+        "ILE" => 1.4,
+        "ASP" | "ASN" => 1.6,
+        "LEU" => 1.8,
+        "MET" | "HIS" => 2.2,
+        "GLU" | "GLN" => 2.3,
+        "LYS" => 2.6,
+        "PHE" => 2.7,
+        "TRP" => 3.0,
+        "TYR" => 3.1,
+        "ARG" => 3.2,
+        _ => 1.5,
+    };
+    Some(reach)
+}
+
+/// Bead of a residue named `name` at Cα `ca`, between the Cα it is bonded to
+/// on each side; `None` for glycine or a residue bonded to nothing.
+pub(crate) fn place_bead(
+    bead: SideChainBead,
+    name: &str,
+    previous: Option<[f64; 3]>,
+    ca: [f64; 3],
+    next: Option<[f64; 3]>,
+    chi1: f64,
+) -> Option<[f64; 3]> {
+    let reach = side_chain_reach(name)?;
+    let away = |other: [f64; 3]| normalize(sub(ca, other));
+    let (bisector, normal) = match (previous, next) {
+        (Some(previous), Some(next)) => {
+            let bisector = normalize(add(away(previous), away(next)));
+            let normal = normalize(cross(sub(ca, previous), sub(next, ca)));
+            if dot(bisector, bisector) < 0.5 || dot(normal, normal) < 0.5 {
+                // A straight trace: any side will do
+                let axis = away(previous);
+                let bisector = perpendicular(axis);
+                (bisector, cross(axis, bisector))
+            } else {
+                (bisector, normal)
+            }
+        }
+        (Some(end), None) | (None, Some(end)) => {
+            let bisector = away(end);
+            (bisector, perpendicular(bisector))
+        }
+        (None, None) => return None,
+    };
+    let tilt = BETA_TILT_DEGREES.to_radians();
+    let axis = sub(scale(bisector, tilt.cos()), scale(normal, tilt.sin()));
+    let beta = add(ca, scale(axis, CA_CB_DISTANCE));
+    if bead == SideChainBead::Beta || reach == 0.0 {
+        return Some(beta);
+    }
+
+    // Swing the centroid about Cα→Cβ, chi1 measured from the N-side Cα
+    let reference = previous.or(next)?;
+    let toward = sub(reference, ca);
+    let zero = normalize(sub(toward, scale(axis, dot(toward, axis))));
+    if dot(zero, zero) < 0.5 {
+        return Some(beta);
+    }
+    let side = cross(axis, zero);
+    let (sin, cos) = chi1.to_radians().sin_cos();
+    let lean = CENTROID_TILT_DEGREES.to_radians();
+    let direction = add(
+        scale(axis, lean.cos()),
+        scale(add(scale(zero, cos), scale(side, sin)), lean.sin()),
+    );
+    Some(add(beta, scale(direction, reach)))
+}
+
+/// Rotamers of residue type `name`, most common first; empty for glycine,
+/// alanine and unknown types.
+pub fn rotamers(name: &str) -> &'static [Rotamer] {
+    match name.to_ascii_uppercase().as_str() {
+        "SER" => &SER,
+        "THR" => &THR,
+        "CYS" => &CYS,
+        "VAL" => &VAL,
+        "PRO" => &PRO,
+        "ASP" => &ASP,
+        "ASN" => &ASN,
+        "HIS" => &HIS,
+        "PHE" => &PHE,
+        "TYR" => &TYR,
+        "TRP" => &TRP,
+        "ILE" => &ILE,
+        "LEU" => &LEU,
+        "MET" => &MET,
+        "GLU" => &GLU,
+        "GLN" => &GLN,
+        "LYS" => &LYS,
+        "ARG" => &ARG,
+        _ => &[],
+    }
+}
+
+/// The rotamer of residue type `name` called `rotamer`, e.g. `mt` for leucine.
+pub fn find_rotamer(name: &str, rotamer: &str) -> Option<&'static Rotamer> {
+    rotamers(name)
+        .iter()
+        .find(|candidate| candidate.name.eq_ignore_ascii_case(rotamer))
+}
+
+/// The library rotamer closest to `chi` over the chi angles `name` has.
+pub fn nearest_rotamer(name: &str, chi: [f64; 4]) -> Option<&'static Rotamer> {
+    let distance = |rotamer: &Rotamer| -> f64 {
+        rotamer
+            .chi
+            .iter()
+            .zip(chi)
+            .take(super::side_chain_dihedral_count(name))
+            .map(|(target, current)| {
+                let delta = (target - current + 180.0).rem_euclid(360.0) - 180.0;
+                delta * delta
+            })
+            .sum()
+    };
+    rotamers(name)
+        .iter()
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+const fn state(name: &'static str, chi: [f64; 4], probability: f64) -> Rotamer {
+    Rotamer {
+        name,
+        chi,
+        probability,
+    }
+}
+
+const SER: [Rotamer; 3] = [
+    state("p", [64.0, 0.0, 0.0, 0.0], 0.48),
+    state("m", [-65.0, 0.0, 0.0, 0.0], 0.29),
+    state("t", [178.0, 0.0, 0.0, 0.0], 0.22),
+];
+
+const THR: [Rotamer; 3] = [
+    state("p", [62.0, 0.0, 0.0, 0.0], 0.49),
+    state("m", [-60.0, 0.0, 0.0, 0.0], 0.43),
+    state("t", [-175.0, 0.0, 0.0, 0.0], 0.07),
+];
+
+const CYS: [Rotamer; 3] = [
+    state("m", [-65.0, 0.0, 0.0, 0.0], 0.55),
+    state("t", [-177.0, 0.0, 0.0, 0.0], 0.27),
+    state("p", [62.0, 0.0, 0.0, 0.0], 0.17),
+];
+
+const VAL: [Rotamer; 3] = [
+    state("t", [175.0, 0.0, 0.0, 0.0], 0.73),
+    state("m", [-60.0, 0.0, 0.0, 0.0], 0.20),
+    state("p", [63.0, 0.0, 0.0, 0.0], 0.06),
+];
+
+const PRO: [Rotamer; 2] = [
+    state("endo", [30.0, -35.0, 0.0, 0.0], 0.55),
+    state("exo", [-30.0, 40.0, 0.0, 0.0], 0.45),
+];
+
+const ASP: [Rotamer; 5] = [
+    state("m-20", [-70.0, -15.0, 0.0, 0.0], 0.51),
+    state("t0", [-177.0, 0.0, 0.0, 0.0], 0.21),
+    state("p-10", [62.0, -10.0, 0.0, 0.0], 0.10),
+    state("p30", [62.0, 30.0, 0.0, 0.0], 0.09),
+    state("t70", [-177.0, 65.0, 0.0, 0.0], 0.06),
+];
+
+const ASN: [Rotamer; 7] = [
+    state("m-20", [-65.0, -20.0, 0.0, 0.0], 0.31),
+    state("t30", [-174.0, 31.0, 0.0, 0.0], 0.15),
+    state("t-20", [-177.0, -15.0, 0.0, 0.0], 0.12),
+    state("p30", [62.0, 30.0, 0.0, 0.0], 0.09),
+    state("m-80", [-65.0, -75.0, 0.0, 0.0], 0.08),
+    state("m120", [-65.0, 120.0, 0.0, 0.0], 0.08),
+    state("p-10", [62.0, -10.0, 0.0, 0.0], 0.07),
+];
+
+const HIS: [Rotamer; 8] = [
+    state("m-70", [-65.0, -70.0, 0.0, 0.0], 0.29),
+    state("t60", [-177.0, 60.0, 0.0, 0.0], 0.16),
+    state("m80", [-65.0, 80.0, 0.0, 0.0], 0.13),
+    state("t-80", [-177.0, -80.0, 0.0, 0.0], 0.11),
+    state("p-80", [62.0, -75.0, 0.0, 0.0], 0.09),
+    state("m170", [-65.0, 165.0, 0.0, 0.0], 0.07),
+    state("t-160", [-177.0, -165.0, 0.0, 0.0], 0.05),
+    state("p80", [62.0, 80.0, 0.0, 0.0], 0.04),
+];
+
+const PHE: [Rotamer; 4] = [
+    state("m-85", [-65.0, -85.0, 0.0, 0.0], 0.44),
+    state("t80", [-177.0, 80.0, 0.0, 0.0], 0.33),
+    state("p90", [62.0, 90.0, 0.0, 0.0], 0.13),
+    state("m-30", [-65.0, -30.0, 0.0, 0.0], 0.09),
+];
+
+const TYR: [Rotamer; 4] = [
+    state("m-85", [-65.0, -85.0, 0.0, 0.0], 0.43),
+    state("t80", [-177.0, 80.0, 0.0, 0.0], 0.34),
+    state("p90", [62.0, 90.0, 0.0, 0.0], 0.13),
+    state("m-30", [-65.0, -30.0, 0.0, 0.0], 0.09),
+];
+
+const TRP: [Rotamer; 7] = [
+    state("m95", [-65.0, 95.0, 0.0, 0.0], 0.31),
+    state("t90", [-177.0, 90.0, 0.0, 0.0], 0.18),
+    state("t-105", [-177.0, -105.0, 0.0, 0.0], 0.13),
+    state("p90", [62.0, 90.0, 0.0, 0.0], 0.11),
+    state("m0", [-65.0, -5.0, 0.0, 0.0], 0.11),
+    state("p-90", [62.0, -90.0, 0.0, 0.0], 0.09),
+    state("m-90", [-65.0, -90.0, 0.0, 0.0], 0.06),
+];
+
+const ILE: [Rotamer; 7] = [
+    state("mt", [-65.0, 170.0, 0.0, 0.0], 0.60),
+    state("mm", [-57.0, -60.0, 0.0, 0.0], 0.15),
+    state("pt", [62.0, 170.0, 0.0, 0.0], 0.13),
+    state("tt", [-177.0, 165.0, 0.0, 0.0], 0.08),
+    state("tp", [-177.0, 66.0, 0.0, 0.0], 0.02),
+    state("pp", [62.0, 100.0, 0.0, 0.0], 0.01),
+    state("mp", [-65.0, 100.0, 0.0, 0.0], 0.01),
+];
+
+const LEU: [Rotamer; 5] = [
+    state("mt", [-65.0, 175.0, 0.0, 0.0], 0.59),
+    state("tp", [-177.0, 65.0, 0.0, 0.0], 0.29),
+    state("tt", [-172.0, 145.0, 0.0, 0.0], 0.02),
+    state("mp", [-85.0, 65.0, 0.0, 0.0], 0.02),
+    state("pp", [62.0, 80.0, 0.0, 0.0], 0.01),
+];
+
+const MET: [Rotamer; 8] = [
+    state("mmm", [-65.0, -65.0, -70.0, 0.0], 0.19),
+    state("mtp", [-68.0, 180.0, 75.0, 0.0], 0.17),
+    state("mtm", [-68.0, 180.0, -75.0, 0.0], 0.11),
+    state("mtt", [-68.0, 180.0, 180.0, 0.0], 0.08),
+    state("ttp", [-177.0, 180.0, 75.0, 0.0], 0.07),
+    state("tpp", [-177.0, 65.0, 75.0, 0.0], 0.05),
+    state("ptm", [62.0, 180.0, -75.0, 0.0], 0.05),
+    state("mmp", [-65.0, -65.0, 103.0, 0.0], 0.05),
+];
+
+const GLU: [Rotamer; 6] = [
+    state("mt-10", [-65.0, 180.0, -10.0, 0.0], 0.33),
+    state("tp10", [-177.0, 65.0, 10.0, 0.0], 0.24),
+    state("mm-40", [-65.0, -65.0, -40.0, 0.0], 0.11),
+    state("tt0", [-177.0, 180.0, 0.0, 0.0], 0.07),
+    state("mp0", [-65.0, 85.0, 0.0, 0.0], 0.06),
+    state("pt-20", [62.0, 180.0, -20.0, 0.0], 0.05),
+];
+
+const GLN: [Rotamer; 7] = [
+    state("mt-30", [-65.0, 180.0, -25.0, 0.0], 0.38),
+    state("tt0", [-177.0, 180.0, 0.0, 0.0], 0.17),
+    state("mm-40", [-65.0, -65.0, -40.0, 0.0], 0.16),
+    state("tp60", [-177.0, 65.0, 60.0, 0.0], 0.10),
+    state("pt20", [62.0, 180.0, 20.0, 0.0], 0.04),
+    state("mm100", [-65.0, -65.0, 100.0, 0.0], 0.03),
+    state("tp-100", [-177.0, 65.0, -100.0, 0.0], 0.02),
+];
+
+const LYS: [Rotamer; 8] = [
+    state("mttt", [-65.0, 180.0, 180.0, 180.0], 0.36),
+    state("tttt", [-177.0, 180.0, 180.0, 180.0], 0.13),
+    state("tptt", [-177.0, 68.0, 180.0, 180.0], 0.09),
+    state("mmtt", [-62.0, -68.0, 180.0, 180.0], 0.06),
+    state("mttp", [-65.0, 180.0, 180.0, 65.0], 0.06),
+    state("mttm", [-65.0, 180.0, 180.0, -65.0], 0.05),
+    state("mtmt", [-68.0, 180.0, -65.0, 180.0], 0.03),
+    state("ptpt", [62.0, 180.0, 68.0, 180.0], 0.02),
+];
+
+const ARG: [Rotamer; 8] = [
+    state("mtp85", [-65.0, 180.0, 65.0, 85.0], 0.06),
+    state("mtt85", [-67.0, 180.0, 180.0, 85.0], 0.06),
+    state("mtt180", [-65.0, 180.0, 180.0, 180.0], 0.05),
+    state("mtp180", [-65.0, 180.0, 65.0, -175.0], 0.05),
+    state("mtm-85", [-67.0, 180.0, -65.0, -85.0], 0.05),
+    state("ttt180", [-177.0, 180.0, 180.0, 180.0], 0.04),
+    state("mmt-85", [-62.0, -68.0, 180.0, -85.0], 0.04),
+    state("tpp80", [-177.0, 65.0, 65.0, 85.0], 0.03),
+];
+
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f64; 3], factor: f64) -> [f64; 3] {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Unit vector along `a`; zero for a zero vector.
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    let length = dot(a, a).sqrt();
+    if length < 1e-9 {
+        [0.0; 3]
+    } else {
+        scale(a, 1.0 / length)
+    }
+}
+
+/// A unit vector at right angles to the unit vector `a`.
+fn perpendicular(a: [f64; 3]) -> [f64; 3] {
+    let other = if a[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    normalize(cross(a, other))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dihedral, EnergyModel, PeptideChain, ResidueId};
+
+    fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+        dot(sub(a, b), sub(a, b)).sqrt()
+    }
+
+    #[test]
+    fn beads_follow_the_trace_and_chi1() {
+        let bare = PeptideChain::from_sequence("AGLKV");
+        assert_eq!(bare.side_chain_position(2), None);
+
+        let beta = bare.clone().with_side_chains(SideChainBead::Beta);
+        let cb = beta.side_chain_position(0).unwrap();
+        assert!((distance(cb, beta.positions()[0]) - CA_CB_DISTANCE).abs() < 1e-9);
+        assert_eq!(beta.side_chain_position(1), None);
+        let mut turned = beta.clone();
+        turned.rotate_torsion(ResidueId(2), Dihedral::Chi1, 120.0);
+        assert_eq!(turned.side_chain_position(2), beta.side_chain_position(2));
+
+        // A centroid swings on a cone about Cα→Cβ as chi1 turns
+        let mut centroid = bare.with_side_chains(SideChainBead::Centroid);
+        let ca = centroid.positions()[2];
+        let before = centroid.side_chain_position(2).unwrap();
+        centroid.rotate_torsion(ResidueId(2), Dihedral::Chi1, 120.0);
+        let after = centroid.side_chain_position(2).unwrap();
+        assert!(distance(before, after) > 1.0);
+        assert!((distance(before, ca) - distance(after, ca)).abs() < 1e-9);
+        assert_eq!(
+            centroid.side_chain_position(0),
+            beta.side_chain_position(0),
+            "alanine's centroid is its Cβ"
+        );
+
+        // Bead clashes enter the energy, still split exactly across residues
+        let model = EnergyModel::default();
+        let total = model.total_energy(&centroid);
+        assert!(total > model.total_energy(&PeptideChain::from_sequence("AGLKV")));
+        let per_residue: f64 = model.residue_energies(&centroid).iter().sum();
+        assert!((per_residue - total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn library_names_the_common_rotamers() {
+        assert_eq!(find_rotamer("LEU", "MT").unwrap().chi[..2], [-65.0, 175.0]);
+        assert!(find_rotamer("LEU", "m-85").is_none());
+        assert!(rotamers("GLY").is_empty() && rotamers("ALA").is_empty());
+        assert_eq!(
+            nearest_rotamer("PHE", [-170.0, 70.0, 99.0, 99.0])
+                .unwrap()
+                .name,
+            "t80"
+        );
+        for name in "SER THR CYS VAL PRO ASP ASN HIS PHE TYR TRP ILE LEU MET GLU GLN LYS ARG"
+            .split_whitespace()
+        {
+            let library = rotamers(name);
+            let total: f64 = library.iter().map(|rotamer| rotamer.probability).sum();
+            assert!(total > 0.3 && total <= 1.0 + 1e-9, "{name}: {total}");
+            assert!(
+                library
+                    .windows(2)
+                    .all(|pair| pair[0].probability >= pair[1].probability)
+            );
+        }
+    }
+}
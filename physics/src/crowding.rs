@@ -0,0 +1,229 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::Vec3;
+
+/// Contact radius (Å) of a Cα bead against a crowder surface.
+const BEAD_RADIUS: f64 = 1.9;
+/// Strength (kcal/mol) of the bead–crowder repulsion.
+const EPSILON: f64 = 1.0;
+/// Placement attempts per requested crowder before giving up.
+const MAX_ATTEMPTS_PER_CROWDER: usize = 1000;
+/// Volume fraction at which random sequential addition of spheres jams;
+/// explicit crowders are never placed beyond it.
+pub const JAMMING_FRACTION: f64 = 0.38;
+
+/// How crowding enters the energy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CrowdingModel {
+    /// Scaled-particle excluded-volume term on the chain's equivalent sphere.
+    #[default]
+    MeanField,
+    /// Inert spheres placed around the chain that repel its beads.
+    Explicit,
+}
+
+impl CrowdingModel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrowdingModel::MeanField => "mean-field",
+            CrowdingModel::Explicit => "explicit",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "mean-field" | "meanfield" | "spt" => Some(CrowdingModel::MeanField),
+            "explicit" | "spheres" => Some(CrowdingModel::Explicit),
+            _ => None,
+        }
+    }
+}
+
+/// Inert crowder spheres filling a cube around the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrowderField {
+    pub radius: f64,
+    pub centers: Vec<[f64; 3]>,
+    /// Crowders the requested volume fraction called for; more than
+    /// `centers` holds when placement stopped short.
+    #[serde(default)]
+    pub requested: usize,
+    pub box_center: [f64; 3],
+    pub half_width: f64,
+}
+
+impl CrowderField {
+    /// Random sequential addition towards `fraction` of the box volume.
+    /// Crowders never overlap each other or the chain, so placement aims no
+    /// higher than [`JAMMING_FRACTION`] and can stop short of that too;
+    /// `requested` keeps the count `fraction` called for.
+    pub fn place(positions: &[[f64; 3]], fraction: f64, radius: f64, seed: u64) -> Self {
+        let radius = radius.max(BEAD_RADIUS);
+        let box_center = centroid(positions);
+        let extent = positions
+            .iter()
+            .map(|position| distance(position, &box_center))
+            .fold(0.0, f64::max);
+        let half_width = extent + 2.0 * radius;
+        let box_volume = (2.0 * half_width).powi(3);
+        let count = |fraction: f64| (fraction * box_volume / sphere_volume(radius)) as usize;
+        let requested = count(fraction.clamp(0.0, 0.95));
+        let target = count(fraction.clamp(0.0, JAMMING_FRACTION));
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut centers: Vec<[f64; 3]> = Vec::with_capacity(target);
+        let mut attempts = 0;
+        while centers.len() < target && attempts < target * MAX_ATTEMPTS_PER_CROWDER {
+            attempts += 1;
+            let candidate = [
+                box_center[0] + rng.gen_range(-half_width..half_width),
+                box_center[1] + rng.gen_range(-half_width..half_width),
+                box_center[2] + rng.gen_range(-half_width..half_width),
+            ];
+            let clear_of_chain = positions
+                .iter()
+                .all(|position| distance(position, &candidate) >= radius + BEAD_RADIUS);
+            let clear_of_crowders = centers
+                .iter()
+                .all(|center| distance(center, &candidate) >= 2.0 * radius);
+            if clear_of_chain && clear_of_crowders {
+                centers.push(candidate);
+            }
+        }
+
+        Self {
+            radius,
+            centers,
+            requested,
+            box_center,
+            half_width,
+        }
+    }
+
+    /// Fraction of the box actually occupied by the placed crowders.
+    pub fn volume_fraction(&self) -> f64 {
+        let box_volume = (2.0 * self.half_width).powi(3);
+        if box_volume <= 0.0 {
+            return 0.0;
+        }
+        self.centers.len() as f64 * sphere_volume(self.radius) / box_volume
+    }
+
+    /// WCA repulsion between every bead and every crowder it touches.
+    pub fn energy(&self, positions: &[[f64; 3]]) -> f64 {
+        let contact = self.radius + BEAD_RADIUS;
+        positions
+            .iter()
+            .flat_map(|position| {
+                self.centers
+                    .iter()
+                    .map(move |center| wca_energy(distance(position, center), contact))
+            })
+            .sum()
+    }
+
+    /// Forces on each bead from [`CrowderField::energy`].
+    pub fn forces(&self, positions: &[[f64; 3]]) -> Vec<Vec3> {
+        let contact = self.radius + BEAD_RADIUS;
+        positions
+            .iter()
+            .map(|position| {
+                let mut force = Vec3::zeros();
+                for center in &self.centers {
+                    let offset = Vec3::new(
+                        position[0] - center[0],
+                        position[1] - center[1],
+                        position[2] - center[2],
+                    );
+                    let r = offset.norm();
+                    if r > 0.0 && r < contact {
+                        force += offset * (wca_force(r, contact) / r);
+                    }
+                }
+                force
+            })
+            .collect()
+    }
+}
+
+/// Purely repulsive Lennard-Jones shifted so it vanishes at `contact`.
+fn wca_energy(r: f64, contact: f64) -> f64 {
+    if r >= contact {
+        return 0.0;
+    }
+    let sigma = contact / 2f64.powf(1.0 / 6.0);
+    let s6 = (sigma / r.max(1e-6)).powi(6);
+    4.0 * EPSILON * (s6 * s6 - s6) + EPSILON
+}
+
+/// Magnitude of `-dE/dr`, positive when pushing the bead away.
+fn wca_force(r: f64, contact: f64) -> f64 {
+    if r >= contact {
+        return 0.0;
+    }
+    let r = r.max(1e-6);
+    let sigma = contact / 2f64.powf(1.0 / 6.0);
+    let s6 = (sigma / r).powi(6);
+    24.0 * EPSILON * (2.0 * s6 * s6 - s6) / r
+}
+
+fn sphere_volume(radius: f64) -> f64 {
+    4.0 / 3.0 * std::f64::consts::PI * radius.powi(3)
+}
+
+fn centroid(positions: &[[f64; 3]]) -> [f64; 3] {
+    let mut center = [0.0; 3];
+    if positions.is_empty() {
+        return center;
+    }
+    let n = positions.len() as f64;
+    for position in positions {
+        for axis in 0..3 {
+            center[axis] += position[axis] / n;
+        }
+    }
+    center
+}
+
+fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crowders_fill_the_box_without_touching_the_chain() {
+        let chain: Vec<[f64; 3]> = (0..10).map(|i| [i as f64 * 3.8, 0.0, 0.0]).collect();
+        let field = CrowderField::place(&chain, 0.2, 10.0, 7);
+        assert!(!field.centers.is_empty());
+        assert!(field.volume_fraction() > 0.1 && field.volume_fraction() <= 0.2 + 1e-9);
+        assert_eq!(field, CrowderField::place(&chain, 0.2, 10.0, 7));
+        assert_eq!(field.energy(&chain), 0.0);
+        assert!(field.requested >= field.centers.len());
+
+        let single = CrowderField {
+            centers: vec![[0.0; 3]],
+            ..field
+        };
+        let touching = [[10.0, 0.0, 0.0]];
+        assert!(single.energy(&touching) > 0.0);
+        assert!(single.forces(&touching)[0].x > 0.0);
+        assert_eq!(
+            CrowdingModel::parse("explicit"),
+            Some(CrowdingModel::Explicit)
+        );
+    }
+
+    #[test]
+    fn dense_requests_stop_at_the_jamming_fraction() {
+        let chain: Vec<[f64; 3]> = (0..10).map(|i| [i as f64 * 3.8, 0.0, 0.0]).collect();
+        let field = CrowderField::place(&chain, 0.9, 10.0, 7);
+        assert!(field.volume_fraction() <= JAMMING_FRACTION + 1e-9);
+        assert!(field.centers.len() < field.requested);
+    }
+}
@@ -0,0 +1,277 @@
+use serde::{Deserialize, Serialize};
+
+use crate::crowding::{CrowderField, CrowdingModel};
+
+/// Viscosity of water at 298 K in mPa·s; friction is expressed relative to it.
+pub const WATER_VISCOSITY: f64 = 0.89;
+
+/// Boltzmann constant in kcal·mol⁻¹·K⁻¹.
+const KB: f64 = 0.0019872041;
+
+/// Andrade activation temperature (K) of water, `ln η ∝ B / T`.
+const ANDRADE_B: f64 = 1960.0;
+
+/// One mPa·s·Å of Stokes drag expressed in amu/ps.
+const DRAG_TO_AMU_PER_PS: f64 = 60.22;
+
+/// Stokes radius (Å) of a Cα bead.
+pub const BEAD_HYDRODYNAMIC_RADIUS: f64 = 1.9;
+
+/// Slope (Å⁻¹) of the distance-dependent dielectric, ε(r) = 4r.
+pub const DISTANCE_DIELECTRIC_SLOPE: f64 = 4.0;
+
+/// How the Coulomb term is screened, from cheapest to most detailed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DielectricModel {
+    /// Coulomb over the solvent permittivity, no solvation term.
+    Constant,
+    /// Coulomb over ε(r) = 4r, no solvation term.
+    DistanceDependent,
+    /// Vacuum Coulomb plus the generalized-Born solvation term.
+    #[default]
+    Gb,
+}
+
+impl DielectricModel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DielectricModel::Constant => "constant",
+            DielectricModel::DistanceDependent => "distance",
+            DielectricModel::Gb => "gb",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "constant" | "uniform" => Some(DielectricModel::Constant),
+            "distance" | "distance-dependent" | "rdie" => Some(DielectricModel::DistanceDependent),
+            "gb" | "implicit" => Some(DielectricModel::Gb),
+            _ => None,
+        }
+    }
+
+    /// Relative permittivity dividing a Coulomb pair `r` Å apart.
+    pub fn permittivity(&self, r: f64, solvent_dielectric: f64) -> f64 {
+        match self {
+            DielectricModel::Constant => solvent_dielectric.max(1.0),
+            DielectricModel::DistanceDependent => (DISTANCE_DIELECTRIC_SLOPE * r).max(1.0),
+            DielectricModel::Gb => 1.0,
+        }
+    }
+}
+
+/// Solvent conditions a run is simulated under.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    /// Temperature (K) used when the run does not specify one.
+    pub default_temperature: f64,
+    /// Solvent viscosity in mPa·s at `default_temperature`.
+    pub viscosity: f64,
+    /// Relative permittivity of the solvent, used by the GB electrostatics.
+    pub dielectric: f64,
+    /// Volume fraction occupied by inert crowders, in `[0, 1)`.
+    pub crowding_fraction: f64,
+    /// Crowder radius in Å for the excluded-volume term.
+    pub crowder_radius: f64,
+    /// Whether crowders are a mean-field term or explicit spheres.
+    #[serde(default)]
+    pub crowding_model: CrowdingModel,
+    /// Salt concentration in mol/L, screening the GB electrostatics.
+    #[serde(default)]
+    pub ionic_strength: f64,
+    /// Screening of the Coulomb term.
+    #[serde(default)]
+    pub dielectric_model: DielectricModel,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::aqueous()
+    }
+}
+
+impl Environment {
+    pub fn aqueous() -> Self {
+        Self {
+            name: "aqueous".into(),
+            default_temperature: 298.0,
+            viscosity: WATER_VISCOSITY,
+            dielectric: 78.5,
+            crowding_fraction: 0.0,
+            crowder_radius: 20.0,
+            crowding_model: CrowdingModel::MeanField,
+            ionic_strength: 0.0,
+            dielectric_model: DielectricModel::Gb,
+        }
+    }
+
+    /// Cytoplasm-like conditions: roughly twice the viscosity of water,
+    /// ~30% of the volume taken up by macromolecules and 150 mM salt.
+    pub fn cytosol() -> Self {
+        Self {
+            name: "cytosol".into(),
+            default_temperature: 310.0,
+            viscosity: 2.0,
+            dielectric: 70.0,
+            crowding_fraction: 0.3,
+            crowder_radius: 20.0,
+            crowding_model: CrowdingModel::MeanField,
+            ionic_strength: 0.15,
+            dielectric_model: DielectricModel::Gb,
+        }
+    }
+
+    pub fn vacuum() -> Self {
+        Self {
+            name: "vacuum".into(),
+            default_temperature: 295.0,
+            viscosity: 0.0,
+            dielectric: 1.0,
+            crowding_fraction: 0.0,
+            crowder_radius: 20.0,
+            crowding_model: CrowdingModel::MeanField,
+            ionic_strength: 0.0,
+            dielectric_model: DielectricModel::Gb,
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "aqueous" | "water" => Some(Self::aqueous()),
+            "cytosol" => Some(Self::cytosol()),
+            "vacuum" => Some(Self::vacuum()),
+            _ => None,
+        }
+    }
+
+    pub fn with_viscosity(mut self, viscosity: f64) -> Self {
+        self.viscosity = viscosity.max(0.0);
+        self
+    }
+
+    pub fn with_dielectric(mut self, dielectric: f64) -> Self {
+        self.dielectric = dielectric.max(1.0);
+        self
+    }
+
+    pub fn with_crowding(mut self, fraction: f64) -> Self {
+        self.crowding_fraction = fraction.clamp(0.0, 0.95);
+        self
+    }
+
+    pub fn with_ionic_strength(mut self, molar: f64) -> Self {
+        self.ionic_strength = molar.max(0.0);
+        self
+    }
+
+    pub fn with_dielectric_model(mut self, model: DielectricModel) -> Self {
+        self.dielectric_model = model;
+        self
+    }
+
+    pub fn with_crowding_model(mut self, model: CrowdingModel) -> Self {
+        self.crowding_model = model;
+        self
+    }
+
+    /// Explicit crowders around `positions`, if this environment uses them.
+    pub fn crowders(&self, positions: &[[f64; 3]], seed: u64) -> Option<CrowderField> {
+        if self.crowding_model != CrowdingModel::Explicit || self.crowding_fraction <= 0.0 {
+            return None;
+        }
+        Some(CrowderField::place(
+            positions,
+            self.crowding_fraction,
+            self.crowder_radius,
+            seed,
+        ))
+    }
+
+    /// Solvent viscosity at `temperature`, extrapolated from the reference
+    /// value with the Andrade relation.
+    pub fn viscosity_at(&self, temperature: f64) -> f64 {
+        if self.viscosity <= 0.0 || temperature <= 0.0 || self.default_temperature <= 0.0 {
+            return self.viscosity.max(0.0);
+        }
+        let exponent = ANDRADE_B * (1.0 / temperature - 1.0 / self.default_temperature);
+        self.viscosity * exponent.exp()
+    }
+
+    /// Stokes–Einstein drag `6πη(T)a` (amu/ps) on a bead of radius `radius` Å,
+    /// used as the Langevin friction coefficient.
+    pub fn stokes_friction(&self, temperature: f64, radius: f64) -> f64 {
+        6.0 * std::f64::consts::PI * self.viscosity_at(temperature) * radius * DRAG_TO_AMU_PER_PS
+    }
+
+    /// Excluded-volume free energy (kcal/mol) of the chain's equivalent sphere
+    /// in a bath of hard-sphere crowders, from scaled-particle theory. Compact
+    /// conformations pay less, so crowding favours the folded state.
+    pub fn excluded_volume_energy(&self, positions: &[[f64; 3]], temperature: f64) -> f64 {
+        let phi = self.crowding_fraction;
+        if phi <= 0.0 || positions.is_empty() {
+            return 0.0;
+        }
+        let radius = (5.0f64 / 3.0).sqrt() * radius_of_gyration(positions);
+        let x = radius / self.crowder_radius.max(f64::EPSILON);
+        let y = phi / (1.0 - phi);
+        let ln_gamma = -(1.0 - phi).ln()
+            + 3.0 * y * x
+            + (3.0 * y + 4.5 * y * y) * x * x
+            + (y + 3.0 * y * y + 3.0 * y * y * y) * x * x * x;
+        KB * temperature * ln_gamma
+    }
+}
+
+fn radius_of_gyration(positions: &[[f64; 3]]) -> f64 {
+    let n = positions.len() as f64;
+    let mut center = [0.0; 3];
+    for position in positions {
+        for axis in 0..3 {
+            center[axis] += position[axis] / n;
+        }
+    }
+    let squared: f64 = positions
+        .iter()
+        .map(|position| {
+            (0..3)
+                .map(|axis| (position[axis] - center[axis]).powi(2))
+                .sum::<f64>()
+        })
+        .sum();
+    (squared / n).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crowding_penalises_expanded_chains() {
+        let compact: Vec<[f64; 3]> = (0..8)
+            .map(|i| [(i % 2) as f64 * 3.8, (i / 2) as f64 * 3.8, 0.0])
+            .collect();
+        let extended: Vec<[f64; 3]> = (0..8).map(|i| [i as f64 * 3.8, 0.0, 0.0]).collect();
+        let cytosol = Environment::cytosol();
+        let compact_energy = cytosol.excluded_volume_energy(&compact, 310.0);
+        let extended_energy = cytosol.excluded_volume_energy(&extended, 310.0);
+        assert!(compact_energy > 0.0);
+        assert!(extended_energy > compact_energy);
+        assert_eq!(
+            Environment::aqueous().excluded_volume_energy(&extended, 310.0),
+            0.0
+        );
+    }
+
+    #[test]
+    fn friction_follows_viscosity_and_temperature() {
+        let water = Environment::aqueous();
+        assert!((water.viscosity_at(298.0) - WATER_VISCOSITY).abs() < 1e-12);
+        assert!(water.viscosity_at(350.0) < water.viscosity_at(298.0));
+        let drag = water.stokes_friction(298.0, BEAD_HYDRODYNAMIC_RADIUS);
+        assert!((drag - 1919.0).abs() < 5.0);
+        assert!(Environment::cytosol().stokes_friction(298.0, 1.9) > drag);
+        assert_eq!(Environment::vacuum().stokes_friction(298.0, 1.9), 0.0);
+    }
+}
@@ -1,11 +1,29 @@
+use crate::environment::DielectricModel;
+use crate::topology::{NonbondedPair, TopologySlot};
 use folding_molecule::PeptideChain;
 use nalgebra::{Vector3, Point3};
 use std::collections::HashMap;
 use std::f64::consts::PI;
+use std::sync::{Arc, OnceLock};
 
 pub type Vec3 = Vector3<f64>;
 pub type Point3D = Point3<f64>;
 
+/// Debye screening length (Å) of water at 1 mol/L ionic strength and 298 K;
+/// it scales as 1/√I.
+const DEBYE_LENGTH_AT_1M: f64 = 3.04;
+
+/// Distance (Å) beyond which the nonbonded sums drop a pair.
+pub const NONBONDED_CUTOFF: f64 = 12.0;
+
+/// Lennard-Jones scale on 1-4 pairs of the coarse-grained model, as Amber's.
+const COARSE_ONE_FOUR_LJ: f64 = 1.0 / 2.0;
+
+/// Amber's 1-4 scaling: Lennard-Jones by 1/SCNB (SCNB = 2.0) and
+/// electrostatics by 1/SCEE (SCEE = 1.2).
+const AMBER_ONE_FOUR_LJ: f64 = 1.0 / 2.0;
+const AMBER_ONE_FOUR_COULOMB: f64 = 1.0 / 1.2;
+
 /// Trait for force field implementations
 pub trait ForceField {
     fn compute_energy(&self, chain: &PeptideChain) -> f64;
@@ -14,6 +32,164 @@ pub trait ForceField {
     fn angle_energy(&self, chain: &PeptideChain) -> f64;
     fn dihedral_energy(&self, chain: &PeptideChain) -> f64;
     fn nonbonded_energy(&self, chain: &PeptideChain) -> f64;
+    /// Implicit-solvent term; 0 for force fields without one.
+    fn solvation_energy(&self, _chain: &PeptideChain) -> f64 {
+        0.0
+    }
+    fn energy_components(&self, chain: &PeptideChain) -> EnergyComponents {
+        EnergyComponents {
+            bond: self.bond_energy(chain),
+            angle: self.angle_energy(chain),
+            dihedral: self.dihedral_energy(chain),
+            nonbonded: self.nonbonded_energy(chain),
+            solvation: self.solvation_energy(chain),
+        }
+    }
+    /// Potential energy attributed to each residue, summing to
+    /// `compute_energy`: pair terms split half to each residue, angles go to
+    /// their vertex and torsions to the residue they rotate. `None` when the
+    /// force field cannot attribute its terms.
+    fn residue_energies(&self, _chain: &PeptideChain) -> Option<Vec<f64>> {
+        None
+    }
+    /// Nonbonded energy of two residues `distance` apart, as the nonbonded
+    /// sum counts a pair of kind `pair`: 0 when excluded or beyond
+    /// [`NONBONDED_CUTOFF`]. `None` for force fields whose nonbonded terms
+    /// are not pairwise.
+    fn pair_energy(
+        &self,
+        _first: &ResidueParameters,
+        _second: &ResidueParameters,
+        _distance: f64,
+        _pair: NonbondedPair,
+    ) -> Option<f64> {
+        None
+    }
+    /// Largest timestep (ps) the force field integrates stably; `None` when
+    /// unknown, which skips the check.
+    fn max_stable_timestep(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// Adds half of a pair term to each of its residues.
+fn split_pair(energies: &mut [f64], i: usize, j: usize, term: f64) {
+    energies[i] += 0.5 * term;
+    energies[j] += 0.5 * term;
+}
+
+/// One evaluation of a force field split by term, in kcal/mol.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EnergyComponents {
+    pub bond: f64,
+    pub angle: f64,
+    pub dihedral: f64,
+    pub nonbonded: f64,
+    pub solvation: f64,
+}
+
+impl EnergyComponents {
+    pub fn total(&self) -> f64 {
+        self.bond + self.angle + self.dihedral + self.nonbonded + self.solvation
+    }
+}
+
+/// Per-residue bead parameters, relative to alanine for size and
+/// hydrophobicity; `charge` is the side chain's net charge at pH 7 (e).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResidueParameters {
+    /// Bead radius scale applied to σ.
+    pub size: f64,
+    /// Contact well-depth scale applied to ε.
+    pub hydrophobicity: f64,
+    pub charge: f64,
+}
+
+impl ResidueParameters {
+    /// Parameters for a three-letter residue name; unknown names get alanine's.
+    pub fn for_residue(name: &str) -> Self {
+        let (size, hydrophobicity, charge) = match name.trim().to_ascii_uppercase().as_str() {
+            "GLY" => (0.85, 0.6, 0.0),
+            "SER" => (1.0, 0.7, 0.0),
+            "CYS" => (1.05, 1.3, 0.0),
+            "THR" => (1.08, 0.9, 0.0),
+            "PRO" => (1.08, 0.9, 0.0),
+            "ASP" => (1.08, 0.5, -1.0),
+            "ASN" => (1.08, 0.6, 0.0),
+            "VAL" => (1.12, 1.6, 0.0),
+            "GLU" => (1.15, 0.5, -1.0),
+            "GLN" => (1.15, 0.6, 0.0),
+            "HIS" => (1.18, 0.9, 0.0),
+            "ILE" | "LEU" => (1.2, 1.9, 0.0),
+            "MET" => (1.2, 1.7, 0.0),
+            "LYS" => (1.2, 0.5, 1.0),
+            "PHE" => (1.25, 2.0, 0.0),
+            "ARG" => (1.25, 0.6, 1.0),
+            "TYR" => (1.28, 1.5, 0.0),
+            "TRP" => (1.35, 2.1, 0.0),
+            _ => (1.0, 1.0, 0.0),
+        };
+        Self {
+            size,
+            hydrophobicity,
+            charge,
+        }
+    }
+
+    /// Parameters of every residue of `chain`, in order.
+    pub fn for_chain(chain: &PeptideChain) -> Vec<Self> {
+        chain.names().iter().map(|name| Self::for_residue(name)).collect()
+    }
+
+    /// Lorentz–Berthelot mixing: σ scales with the mean size, ε with the
+    /// geometric mean hydrophobicity.
+    pub fn mix(&self, other: &Self, sigma: f64, epsilon: f64) -> (f64, f64) {
+        (
+            sigma * 0.5 * (self.size + other.size),
+            epsilon * (self.hydrophobicity * other.hydrophobicity).sqrt(),
+        )
+    }
+}
+
+/// Well depth (kcal/mol) of the proline ring's pull on phi.
+const PROLINE_PHI_WELL: f64 = 4.0;
+
+/// Phi (degrees) the proline ring holds.
+const PROLINE_PHI: f64 = -65.0;
+
+/// Backbone torsion terms per residue type: glycine, with no Cβ, gets a
+/// flatter profile, and proline's ring adds a stiff well on phi.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TorsionParameters {
+    /// Scale on the generic phi/psi terms.
+    pub scale: f64,
+    /// (depth in kcal/mol, phi0 in degrees) of an extra 1 - cos(φ - φ0) well.
+    pub phi_well: Option<(f64, f64)>,
+}
+
+impl TorsionParameters {
+    pub fn for_residue(name: &str) -> Self {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "GLY" => Self {
+                scale: 0.5,
+                phi_well: None,
+            },
+            "PRO" => Self {
+                scale: 1.0,
+                phi_well: Some((PROLINE_PHI_WELL, PROLINE_PHI)),
+            },
+            _ => Self {
+                scale: 1.0,
+                phi_well: None,
+            },
+        }
+    }
+
+    /// Energy of the extra phi well at `phi` degrees; 0 without one.
+    pub fn well_energy(&self, phi: f64) -> f64 {
+        self.phi_well
+            .map_or(0.0, |(depth, phi0)| depth * (1.0 - (phi - phi0).to_radians().cos()))
+    }
 }
 
 /// Coarse-grained force field for fast simulations
@@ -24,6 +200,7 @@ pub struct CoarseGrainedForceField {
     dihedral_strength: f64,
     lj_epsilon: f64,
     lj_sigma: f64,
+    topology: TopologySlot,
 }
 
 impl CoarseGrainedForceField {
@@ -34,8 +211,116 @@ impl CoarseGrainedForceField {
             dihedral_strength: 2.0, // kcal/mol
             lj_epsilon: 0.2,        // kcal/mol
             lj_sigma: 3.5,          // Å
+            topology: TopologySlot::default(),
+        }
+    }
+
+    /// Visits each bond as `(i, j, energy)`.
+    fn bond_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, usize, f64)) {
+        let positions = chain.positions();
+        
+        for &(i, j) in &self.topology.get(chain).bonds {
+            let pos1 = positions[i];
+            let pos2 = positions[j];
+            let r = distance(pos1, pos2);
+            let r0 = 3.8; // Target bond length
+            let dr = r - r0;
+            visit(i, j, 0.5 * self.bond_strength * dr * dr);
+        }
+    }
+
+    /// Visits each angle as `(vertex, energy)`.
+    fn angle_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, f64)) {
+        let positions = chain.positions();
+        
+        for &(i, vertex, k) in &self.topology.get(chain).angles {
+            let pos1 = positions[i];
+            let pos2 = positions[vertex];
+            let pos3 = positions[k];
+            
+            let v1 = [pos1[0] - pos2[0], pos1[1] - pos2[1], pos1[2] - pos2[2]];
+            let v2 = [pos3[0] - pos2[0], pos3[1] - pos2[1], pos3[2] - pos2[2]];
+            
+            let dot = v1[0] * v2[0] + v1[1] * v2[1] + v1[2] * v2[2];
+            let norm1 = (v1[0] * v1[0] + v1[1] * v1[1] + v1[2] * v1[2]).sqrt();
+            let norm2 = (v2[0] * v2[0] + v2[1] * v2[1] + v2[2] * v2[2]).sqrt();
+            
+            if norm1 > 1e-10 && norm2 > 1e-10 {
+                let cos_theta = (dot / (norm1 * norm2)).clamp(-1.0, 1.0);
+                let theta = cos_theta.acos();
+                let theta0 = 120.0 * PI / 180.0; // Target angle
+                let dtheta = theta - theta0;
+                visit(vertex, 0.5 * self.angle_strength * dtheta * dtheta);
+            }
+        }
+    }
+
+    /// Visits the phi/psi terms of each residue as `(residue, energy)`.
+    fn dihedral_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, f64)) {
+        let topology = self.topology.get(chain);
+        // One term per dihedral, i.e. per 1-4 pair within a chain
+        for &(i, _) in &topology.one_four {
+            // Simple dihedral potential based on phi/psi angles
+            let phi = chain.phi()[i + 1];
+            let psi = chain.psi()[i + 1];
+            let torsion = topology.torsions[i + 1];
+            
+            // Ramachandran-like potential
+            let strength = self.dihedral_strength * torsion.scale;
+            visit(
+                i + 1,
+                strength * (1.0 + (3.0 * phi.to_radians()).cos())
+                    + strength * (1.0 + psi.to_radians().cos())
+                    + torsion.well_energy(phi),
+            );
+        }
+    }
+
+    /// Visits each nonbonded pair inside the cutoff as `(i, j, energy)`.
+    fn nonbonded_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, usize, f64)) {
+        let positions = chain.positions();
+        let topology = self.topology.get(chain);
+        let parameters = &topology.residues;
+        
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                // 1-2 and 1-3 pairs are left to the bond and angle terms
+                let pair = topology.pair(i, j);
+                if pair == NonbondedPair::Excluded {
+                    continue;
+                }
+                let pos1 = positions[i];
+                let pos2 = positions[j];
+                let r = distance(pos1, pos2);
+                
+                if r > 1e-10 && r < NONBONDED_CUTOFF {
+                    visit(i, j, self.pair_term(&parameters[i], &parameters[j], r, pair));
+                }
+            }
         }
     }
+
+    /// Lennard-Jones energy of two beads `r` apart.
+    fn pair_term(
+        &self,
+        first: &ResidueParameters,
+        second: &ResidueParameters,
+        r: f64,
+        pair: NonbondedPair,
+    ) -> f64 {
+        let scale = match pair {
+            NonbondedPair::Excluded => return 0.0,
+            NonbondedPair::OneFour => COARSE_ONE_FOUR_LJ,
+            NonbondedPair::Full => 1.0,
+        };
+        let (sigma, epsilon) = first.mix(second, self.lj_sigma, self.lj_epsilon);
+        let epsilon = epsilon * scale;
+        let sigma_r = sigma / r;
+        let sigma_r6 = sigma_r.powi(6);
+        let sigma_r12 = sigma_r6 * sigma_r6;
+        
+        4.0 * epsilon * (sigma_r12 - sigma_r6)
+    }
 }
 
 impl Default for CoarseGrainedForceField {
@@ -45,6 +330,10 @@ impl Default for CoarseGrainedForceField {
 }
 
 impl ForceField for CoarseGrainedForceField {
+    fn max_stable_timestep(&self) -> Option<f64> {
+        Some(0.02) // Cα beads without explicit hydrogens
+    }
+
     fn compute_energy(&self, chain: &PeptideChain) -> f64 {
         self.bond_energy(chain) + 
         self.angle_energy(chain) + 
@@ -53,13 +342,15 @@ impl ForceField for CoarseGrainedForceField {
     }
 
     fn compute_forces(&self, chain: &PeptideChain) -> Vec<Vec3> {
-        let residues = chain.residues();
-        let mut forces = vec![Vec3::zeros(); residues.len()];
+        let positions = chain.positions();
+        let topology = self.topology.get(chain);
+        let parameters = &topology.residues;
+        let mut forces = vec![Vec3::zeros(); positions.len()];
         
         // Bond forces
-        for i in 0..residues.len().saturating_sub(1) {
-            let pos1 = residues[i].position();
-            let pos2 = residues[i + 1].position();
+        for &(i, j) in &topology.bonds {
+            let pos1 = positions[i];
+            let pos2 = positions[j];
             let r = distance(pos1, pos2);
             let r0 = 3.8; // Target bond length
             
@@ -76,7 +367,7 @@ impl ForceField for CoarseGrainedForceField {
                     force_mag * direction[1],
                     force_mag * direction[2],
                 );
-                forces[i + 1] -= Vec3::new(
+                forces[j] -= Vec3::new(
                     force_mag * direction[0],
                     force_mag * direction[1],
                     force_mag * direction[2],
@@ -85,18 +376,27 @@ impl ForceField for CoarseGrainedForceField {
         }
         
         // Lennard-Jones forces
-        for i in 0..residues.len() {
-            for j in (i + 2)..residues.len() { // Skip bonded neighbors
-                let pos1 = residues[i].position();
-                let pos2 = residues[j].position();
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                // 1-2 and 1-3 pairs are left to the bond and angle terms
+                let scale = match topology.pair(i, j) {
+                    NonbondedPair::Excluded => continue,
+                    NonbondedPair::OneFour => COARSE_ONE_FOUR_LJ,
+                    NonbondedPair::Full => 1.0,
+                };
+                let pos1 = positions[i];
+                let pos2 = positions[j];
                 let r = distance(pos1, pos2);
                 
                 if r > 1e-10 && r < 12.0 { // Cutoff at 12 Å
-                    let sigma_r = self.lj_sigma / r;
+                    let (sigma, epsilon) =
+                        parameters[i].mix(&parameters[j], self.lj_sigma, self.lj_epsilon);
+                    let epsilon = epsilon * scale;
+                    let sigma_r = sigma / r;
                     let sigma_r6 = sigma_r.powi(6);
                     let sigma_r12 = sigma_r6 * sigma_r6;
                     
-                    let force_mag = 24.0 * self.lj_epsilon * (2.0 * sigma_r12 - sigma_r6) / r;
+                    let force_mag = 24.0 * epsilon * (2.0 * sigma_r12 - sigma_r6) / r;
                     let direction = [
                         (pos2[0] - pos1[0]) / r,
                         (pos2[1] - pos1[1]) / r,
@@ -121,93 +421,54 @@ impl ForceField for CoarseGrainedForceField {
     }
 
     fn bond_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
         let mut energy = 0.0;
-        
-        for i in 0..residues.len().saturating_sub(1) {
-            let pos1 = residues[i].position();
-            let pos2 = residues[i + 1].position();
-            let r = distance(pos1, pos2);
-            let r0 = 3.8; // Target bond length
-            let dr = r - r0;
-            energy += 0.5 * self.bond_strength * dr * dr;
-        }
-        
+        self.bond_terms(chain, |_, _, term| energy += term);
         energy
     }
 
     fn angle_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
         let mut energy = 0.0;
-        
-        for i in 0..residues.len().saturating_sub(2) {
-            let pos1 = residues[i].position();
-            let pos2 = residues[i + 1].position();
-            let pos3 = residues[i + 2].position();
-            
-            let v1 = [pos1[0] - pos2[0], pos1[1] - pos2[1], pos1[2] - pos2[2]];
-            let v2 = [pos3[0] - pos2[0], pos3[1] - pos2[1], pos3[2] - pos2[2]];
-            
-            let dot = v1[0] * v2[0] + v1[1] * v2[1] + v1[2] * v2[2];
-            let norm1 = (v1[0] * v1[0] + v1[1] * v1[1] + v1[2] * v1[2]).sqrt();
-            let norm2 = (v2[0] * v2[0] + v2[1] * v2[1] + v2[2] * v2[2]).sqrt();
-            
-            if norm1 > 1e-10 && norm2 > 1e-10 {
-                let cos_theta = (dot / (norm1 * norm2)).clamp(-1.0, 1.0);
-                let theta = cos_theta.acos();
-                let theta0 = 120.0 * PI / 180.0; // Target angle
-                let dtheta = theta - theta0;
-                energy += 0.5 * self.angle_strength * dtheta * dtheta;
-            }
-        }
-        
+        self.angle_terms(chain, |_, term| energy += term);
         energy
     }
 
     fn dihedral_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
         let mut energy = 0.0;
-        
-        for i in 0..residues.len().saturating_sub(3) {
-            // Simple dihedral potential based on phi/psi angles
-            let phi = residues[i + 1].phi;
-            let psi = residues[i + 1].psi;
-            
-            // Ramachandran-like potential
-            energy += self.dihedral_strength * (1.0 + (3.0 * phi).cos());
-            energy += self.dihedral_strength * (1.0 + (psi).cos());
-        }
-        
+        self.dihedral_terms(chain, |_, term| energy += term);
         energy
     }
 
     fn nonbonded_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
         let mut energy = 0.0;
-        
-        for i in 0..residues.len() {
-            for j in (i + 2)..residues.len() { // Skip bonded neighbors
-                let pos1 = residues[i].position();
-                let pos2 = residues[j].position();
-                let r = distance(pos1, pos2);
-                
-                if r > 1e-10 && r < 12.0 { // Cutoff at 12 Å
-                    let sigma_r = self.lj_sigma / r;
-                    let sigma_r6 = sigma_r.powi(6);
-                    let sigma_r12 = sigma_r6 * sigma_r6;
-                    
-                    energy += 4.0 * self.lj_epsilon * (sigma_r12 - sigma_r6);
-                }
-            }
-        }
-        
+        self.nonbonded_terms(chain, |_, _, term| energy += term);
         energy
     }
+
+    fn residue_energies(&self, chain: &PeptideChain) -> Option<Vec<f64>> {
+        let mut energies = vec![0.0; chain.len()];
+        self.bond_terms(chain, |i, j, term| split_pair(&mut energies, i, j, term));
+        self.angle_terms(chain, |vertex, term| energies[vertex] += term);
+        self.dihedral_terms(chain, |residue, term| energies[residue] += term);
+        self.nonbonded_terms(chain, |i, j, term| split_pair(&mut energies, i, j, term));
+        Some(energies)
+    }
+
+    fn pair_energy(
+        &self,
+        first: &ResidueParameters,
+        second: &ResidueParameters,
+        distance: f64,
+        pair: NonbondedPair,
+    ) -> Option<f64> {
+        let inside = distance > 1e-10 && distance < NONBONDED_CUTOFF;
+        Some(if inside { self.pair_term(first, second, distance, pair) } else { 0.0 })
+    }
 }
 
-/// Amber99SB force field with implicit solvation
-#[derive(Debug, Clone)]
-pub struct Amber99SBForceField {
+/// Parameter tables of [`Amber99SBForceField`], built once per process and
+/// shared by every instance.
+#[derive(Debug, Clone, Default)]
+pub struct AmberParameters {
     // Bond parameters
     bond_params: HashMap<String, (f64, f64)>, // (kb, r0)
     // Angle parameters
@@ -223,19 +484,15 @@ pub struct Amber99SBForceField {
     gb_scaling: HashMap<String, f64>,
 }
 
-impl Amber99SBForceField {
-    pub fn new() -> Self {
-        let mut ff = Self {
-            bond_params: HashMap::new(),
-            angle_params: HashMap::new(),
-            dihedral_params: HashMap::new(),
-            lj_params: HashMap::new(),
-            charges: HashMap::new(),
-            gb_radii: HashMap::new(),
-            gb_scaling: HashMap::new(),
-        };
-        ff.initialize_parameters();
-        ff
+impl AmberParameters {
+    /// The process-wide tables.
+    pub fn shared() -> Arc<Self> {
+        static TABLES: OnceLock<Arc<AmberParameters>> = OnceLock::new();
+        Arc::clone(TABLES.get_or_init(|| {
+            let mut tables = AmberParameters::default();
+            tables.initialize_parameters();
+            Arc::new(tables)
+        }))
     }
 
     fn initialize_parameters(&mut self) {
@@ -261,6 +518,22 @@ impl Amber99SBForceField {
             (0.2, 2, PI),
             (0.2, 3, 0.0),
         ]);
+        // Glycine lacks the Cβ terms, so both of its torsions are softer
+        self.dihedral_params.insert("phi-GLY".to_string(), vec![
+            (0.1, 1, 0.0),
+            (0.1, 2, PI),
+            (0.2, 3, 0.0),
+        ]);
+        self.dihedral_params.insert("psi-GLY".to_string(), vec![
+            (0.4, 1, 0.0),
+            (0.1, 2, PI),
+            (0.1, 3, 0.0),
+        ]);
+        // The proline ring: a deep single well at phi0, minimum where φ + δ = π
+        self.dihedral_params.insert("phi-PRO".to_string(), vec![
+            (PROLINE_PHI_WELL / 2.0, 1, PI - PROLINE_PHI.to_radians()),
+            (0.4, 3, 0.0),
+        ]);
         
         // LJ parameters
         self.lj_params.insert("N".to_string(), (3.25, 0.17));
@@ -286,90 +559,98 @@ impl Amber99SBForceField {
         self.gb_scaling.insert("C".to_string(), 0.72);
         self.gb_scaling.insert("O".to_string(), 0.85);
     }
+}
+
+/// Amber99SB force field with implicit solvation
+#[derive(Debug, Clone)]
+pub struct Amber99SBForceField {
+    parameters: Arc<AmberParameters>,
+    // Solvent permittivity seen by the GB term
+    solvent_dielectric: f64,
+    // Salt concentration (mol/L) screening the GB term
+    ionic_strength: f64,
+    // Screening of the Coulomb term; only `Gb` adds the solvation term
+    dielectric_model: DielectricModel,
+    topology: TopologySlot,
+}
+
+impl Amber99SBForceField {
+    pub fn new() -> Self {
+        Self {
+            parameters: AmberParameters::shared(),
+            solvent_dielectric: 78.5,
+            ionic_strength: 0.0,
+            dielectric_model: DielectricModel::Gb,
+            topology: TopologySlot::default(),
+        }
+    }
+
+    pub fn with_solvent_dielectric(mut self, dielectric: f64) -> Self {
+        self.solvent_dielectric = dielectric.max(1.0);
+        self
+    }
+
+    /// Salt concentration (mol/L) whose Debye screening damps the solvent
+    /// side of the GB term.
+    pub fn with_ionic_strength(mut self, molar: f64) -> Self {
+        self.ionic_strength = molar.max(0.0);
+        self
+    }
+
+    /// Constant or distance-dependent dielectrics replace the GB term with a
+    /// screened Coulomb sum.
+    pub fn with_dielectric_model(mut self, model: DielectricModel) -> Self {
+        self.dielectric_model = model;
+        self
+    }
 
     pub fn solvation_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
         let mut energy = 0.0;
-        
-        // Simplified GB energy calculation
-        let dielectric_interior = 1.0;
-        let dielectric_exterior = 78.5;
-        let prefactor = -332.0 * (1.0 / dielectric_interior - 1.0 / dielectric_exterior);
-        
-        for (i, res_i) in residues.iter().enumerate() {
-            let charge_i = self.charges.get("CA").copied().unwrap_or(0.0);
-            let radius_i = self.gb_radii.get("CA").copied().unwrap_or(1.5);
-            
-            // Self energy
-            energy += prefactor * charge_i * charge_i / radius_i;
-            
-            // Pairwise interactions
-            for (j, res_j) in residues.iter().enumerate().skip(i + 1) {
-                let charge_j = self.charges.get("CA").copied().unwrap_or(0.0);
-                let radius_j = self.gb_radii.get("CA").copied().unwrap_or(1.5);
-                
-                let pos_i = res_i.position();
-                let pos_j = res_j.position();
-                let rij = distance(pos_i, pos_j);
-                
-                let fgb = (rij * rij + radius_i * radius_j * 
-                          (-rij * rij / (4.0 * radius_i * radius_j)).exp()).sqrt();
-                
-                energy += prefactor * charge_i * charge_j / fgb;
-            }
-        }
-        
+        self.solvation_terms(chain, |_, _, term| energy += term);
         energy
     }
-}
 
-impl Default for Amber99SBForceField {
-    fn default() -> Self {
-        Self::new()
+    /// Cα charge plus the residue's side-chain net charge.
+    fn bead_charge(&self, residue: &ResidueParameters) -> f64 {
+        self.parameters.charges.get("CA").copied().unwrap_or(0.0) + residue.charge
     }
-}
 
-impl ForceField for Amber99SBForceField {
-    fn compute_energy(&self, chain: &PeptideChain) -> f64 {
-        self.bond_energy(chain) + 
-        self.angle_energy(chain) + 
-        self.dihedral_energy(chain) + 
-        self.nonbonded_energy(chain) +
-        self.solvation_energy(chain)
+    /// `torsion` terms specific to residue `name` (`phi-PRO`), else the generic ones.
+    fn residue_dihedral(&self, torsion: &str, name: &str) -> Option<&Vec<(f64, i32, f64)>> {
+        let specific = format!("{torsion}-{}", name.trim().to_ascii_uppercase());
+        self.parameters.dihedral_params
+            .get(&specific)
+            .or_else(|| self.parameters.dihedral_params.get(torsion))
     }
 
-    fn compute_forces(&self, chain: &PeptideChain) -> Vec<Vec3> {
-        // Simplified force calculation - would need numerical derivatives for full implementation
-        let residues = chain.residues();
-        vec![Vec3::zeros(); residues.len()]
+    fn gb_radius(&self, residue: &ResidueParameters) -> f64 {
+        self.parameters.gb_radii.get("CA").copied().unwrap_or(1.5) * residue.size
     }
 
-    fn bond_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
-        let mut energy = 0.0;
+    /// Visits each bond as `(i, j, energy)`.
+    fn bond_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, usize, f64)) {
+        let positions = chain.positions();
         
-        for i in 0..residues.len().saturating_sub(1) {
-            let pos1 = residues[i].position();
-            let pos2 = residues[i + 1].position();
+        for &(i, j) in &self.topology.get(chain).bonds {
+            let pos1 = positions[i];
+            let pos2 = positions[j];
             let r = distance(pos1, pos2);
             
-            if let Some((kb, r0)) = self.bond_params.get("CA-CA") {
+            if let Some((kb, r0)) = self.parameters.bond_params.get("CA-CA") {
                 let dr = r - r0;
-                energy += 0.5 * kb * dr * dr;
+                visit(i, j, 0.5 * kb * dr * dr);
             }
         }
-        
-        energy
     }
 
-    fn angle_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
-        let mut energy = 0.0;
+    /// Visits each angle as `(vertex, energy)`.
+    fn angle_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, f64)) {
+        let positions = chain.positions();
         
-        for i in 0..residues.len().saturating_sub(2) {
-            let pos1 = residues[i].position();
-            let pos2 = residues[i + 1].position();
-            let pos3 = residues[i + 2].position();
+        for &(i, vertex, k) in &self.topology.get(chain).angles {
+            let pos1 = positions[i];
+            let pos2 = positions[vertex];
+            let pos3 = positions[k];
             
             let v1 = [pos1[0] - pos2[0], pos1[1] - pos2[1], pos1[2] - pos2[2]];
             let v2 = [pos3[0] - pos2[0], pos3[1] - pos2[1], pos3[2] - pos2[2]];
@@ -382,71 +663,209 @@ impl ForceField for Amber99SBForceField {
                 let cos_theta = (dot / (norm1 * norm2)).clamp(-1.0, 1.0);
                 let theta = cos_theta.acos();
                 
-                if let Some((ka, theta0)) = self.angle_params.get("CA-CA-CA") {
+                if let Some((ka, theta0)) = self.parameters.angle_params.get("CA-CA-CA") {
                     let dtheta = theta - theta0;
-                    energy += 0.5 * ka * dtheta * dtheta;
+                    visit(vertex, 0.5 * ka * dtheta * dtheta);
                 }
             }
         }
-        
-        energy
     }
 
-    fn dihedral_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
-        let mut energy = 0.0;
-        
-        for i in 1..residues.len().saturating_sub(1) {
-            let phi = residues[i].phi;
-            let psi = residues[i].psi;
+    /// Visits the phi/psi terms of each residue as `(residue, energy)`.
+    fn dihedral_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, f64)) {
+        // Residues inside a chain, where both torsions are defined
+        for &(_, i, _) in &self.topology.get(chain).angles {
+            let name = &chain.names()[i];
+            let (phi, psi) = (chain.phi()[i].to_radians(), chain.psi()[i].to_radians());
+            let mut energy = 0.0;
             
             // Phi dihedral
-            if let Some(phi_params) = self.dihedral_params.get("phi") {
+            if let Some(phi_params) = self.residue_dihedral("phi", name) {
                 for (kd, n, delta) in phi_params {
                     energy += kd * (1.0 + ((*n as f64) * phi + delta).cos());
                 }
             }
             
             // Psi dihedral
-            if let Some(psi_params) = self.dihedral_params.get("psi") {
+            if let Some(psi_params) = self.residue_dihedral("psi", name) {
                 for (kd, n, delta) in psi_params {
                     energy += kd * (1.0 + ((*n as f64) * psi + delta).cos());
                 }
             }
+            visit(i, energy);
         }
-        
-        energy
     }
 
-    fn nonbonded_energy(&self, chain: &PeptideChain) -> f64 {
-        let residues = chain.residues();
-        let mut energy = 0.0;
+    /// Visits each nonbonded pair inside the cutoff as `(i, j, energy)`,
+    /// Lennard-Jones and Coulomb together.
+    fn nonbonded_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, usize, f64)) {
+        let positions = chain.positions();
+        let topology = self.topology.get(chain);
+        let parameters = &topology.residues;
         
-        for i in 0..residues.len() {
-            for j in (i + 2)..residues.len() { // Skip bonded neighbors
-                let pos1 = residues[i].position();
-                let pos2 = residues[j].position();
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                // 1-2 and 1-3 pairs are left to the bond and angle terms
+                let pair = topology.pair(i, j);
+                if pair == NonbondedPair::Excluded {
+                    continue;
+                }
+                let pos1 = positions[i];
+                let pos2 = positions[j];
                 let r = distance(pos1, pos2);
                 
-                if r > 1e-10 && r < 12.0 { // Cutoff at 12 Å
-                    // Lennard-Jones
-                    if let Some((sigma, epsilon)) = self.lj_params.get("CA") {
-                        let sigma_r = sigma / r;
-                        let sigma_r6 = sigma_r.powi(6);
-                        let sigma_r12 = sigma_r6 * sigma_r6;
-                        energy += 4.0 * epsilon * (sigma_r12 - sigma_r6);
-                    }
-                    
-                    // Coulomb
-                    let q1 = self.charges.get("CA").copied().unwrap_or(0.0);
-                    let q2 = self.charges.get("CA").copied().unwrap_or(0.0);
-                    energy += 332.0 * q1 * q2 / r; // 332 converts to kcal/mol
+                if r > 1e-10 && r < NONBONDED_CUTOFF {
+                    visit(i, j, self.pair_term(&parameters[i], &parameters[j], r, pair));
                 }
             }
         }
+    }
+
+    /// Lennard-Jones plus Coulomb energy of two beads `r` apart.
+    fn pair_term(
+        &self,
+        first: &ResidueParameters,
+        second: &ResidueParameters,
+        r: f64,
+        pair: NonbondedPair,
+    ) -> f64 {
+        let (lj_scale, coulomb_scale) = match pair {
+            NonbondedPair::Excluded => return 0.0,
+            NonbondedPair::OneFour => (AMBER_ONE_FOUR_LJ, AMBER_ONE_FOUR_COULOMB),
+            NonbondedPair::Full => (1.0, 1.0),
+        };
+        let mut energy = 0.0;
+        // Lennard-Jones
+        if let Some(&(sigma, epsilon)) = self.parameters.lj_params.get("CA") {
+            let (sigma, epsilon) = first.mix(second, sigma, epsilon);
+            let sigma_r = sigma / r;
+            let sigma_r6 = sigma_r.powi(6);
+            let sigma_r12 = sigma_r6 * sigma_r6;
+            energy += lj_scale * 4.0 * epsilon * (sigma_r12 - sigma_r6);
+        }
         
+        // Coulomb
+        let q1 = self.bead_charge(first);
+        let q2 = self.bead_charge(second);
+        let permittivity = self.dielectric_model.permittivity(r, self.solvent_dielectric);
+        energy += coulomb_scale * 332.0 * q1 * q2 / (permittivity * r); // 332 converts to kcal/mol
+        energy
+    }
+
+    /// Visits the GB terms as `(i, j, energy)`, self energies with `i == j`.
+    fn solvation_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, usize, f64)) {
+        if self.dielectric_model != DielectricModel::Gb {
+            return;
+        }
+        let positions = chain.positions();
+        
+        // Simplified GB energy calculation
+        let dielectric_interior = 1.0;
+        let dielectric_exterior = self.solvent_dielectric;
+        let kappa = self.ionic_strength.sqrt() / DEBYE_LENGTH_AT_1M;
+        // Salt screens the solvent reaction field as exp(-κ f_GB)
+        let prefactor = |fgb: f64| {
+            -332.0 * (1.0 / dielectric_interior - (-kappa * fgb).exp() / dielectric_exterior)
+        };
+        let topology = self.topology.get(chain);
+        let parameters = &topology.residues;
+        
+        for (i, &pos_i) in positions.iter().enumerate() {
+            let charge_i = self.bead_charge(&parameters[i]);
+            let radius_i = self.gb_radius(&parameters[i]);
+            
+            // Self energy
+            visit(i, i, prefactor(radius_i) * charge_i * charge_i / radius_i);
+            
+            // Pairwise interactions
+            for (j, &pos_j) in positions.iter().enumerate().skip(i + 1) {
+                let charge_j = self.bead_charge(&parameters[j]);
+                let radius_j = self.gb_radius(&parameters[j]);
+                
+                let rij = distance(pos_i, pos_j);
+                
+                let fgb = (rij * rij + radius_i * radius_j * 
+                          (-rij * rij / (4.0 * radius_i * radius_j)).exp()).sqrt();
+                
+                visit(i, j, prefactor(fgb) * charge_i * charge_j / fgb);
+            }
+        }
+    }
+}
+
+impl Default for Amber99SBForceField {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ForceField for Amber99SBForceField {
+    fn max_stable_timestep(&self) -> Option<f64> {
+        Some(0.002) // Unconstrained bonded terms
+    }
+
+    fn compute_energy(&self, chain: &PeptideChain) -> f64 {
+        self.bond_energy(chain) + 
+        self.angle_energy(chain) + 
+        self.dihedral_energy(chain) + 
+        self.nonbonded_energy(chain) +
+        self.solvation_energy(chain)
+    }
+
+    fn compute_forces(&self, chain: &PeptideChain) -> Vec<Vec3> {
+        // Simplified force calculation - would need numerical derivatives for full implementation
+        let positions = chain.positions();
+        vec![Vec3::zeros(); positions.len()]
+    }
+
+    fn bond_energy(&self, chain: &PeptideChain) -> f64 {
+        let mut energy = 0.0;
+        self.bond_terms(chain, |_, _, term| energy += term);
         energy
     }
+
+    fn angle_energy(&self, chain: &PeptideChain) -> f64 {
+        let mut energy = 0.0;
+        self.angle_terms(chain, |_, term| energy += term);
+        energy
+    }
+
+    fn dihedral_energy(&self, chain: &PeptideChain) -> f64 {
+        let mut energy = 0.0;
+        self.dihedral_terms(chain, |_, term| energy += term);
+        energy
+    }
+
+    fn nonbonded_energy(&self, chain: &PeptideChain) -> f64 {
+        let mut energy = 0.0;
+        self.nonbonded_terms(chain, |_, _, term| energy += term);
+        energy
+    }
+
+    fn solvation_energy(&self, chain: &PeptideChain) -> f64 {
+        Amber99SBForceField::solvation_energy(self, chain)
+    }
+
+    fn residue_energies(&self, chain: &PeptideChain) -> Option<Vec<f64>> {
+        let mut energies = vec![0.0; chain.len()];
+        self.bond_terms(chain, |i, j, term| split_pair(&mut energies, i, j, term));
+        self.angle_terms(chain, |vertex, term| energies[vertex] += term);
+        self.dihedral_terms(chain, |residue, term| energies[residue] += term);
+        self.nonbonded_terms(chain, |i, j, term| split_pair(&mut energies, i, j, term));
+        self.solvation_terms(chain, |i, j, term| split_pair(&mut energies, i, j, term));
+        Some(energies)
+    }
+
+    fn pair_energy(
+        &self,
+        first: &ResidueParameters,
+        second: &ResidueParameters,
+        distance: f64,
+        pair: NonbondedPair,
+    ) -> Option<f64> {
+        let inside = distance > 1e-10 && distance < NONBONDED_CUTOFF;
+        Some(if inside { self.pair_term(first, second, distance, pair) } else { 0.0 })
+    }
 }
 
 fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
@@ -495,6 +914,177 @@ mod tests {
         assert!(solvation_energy.is_finite());
     }
 
+    #[test]
+    fn nonbonded_terms_follow_the_sequence() {
+        let chain = |names: [&str; 4]| {
+            let residues = names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| Residue::new(ResidueId(i), *name, [3.8 * i as f64, 0.0, 0.0]))
+                .collect();
+            PeptideChain::new(residues)
+        };
+        let (alanine, leucine) = (chain(["ALA"; 4]), chain(["LEU"; 4]));
+        let coarse = CoarseGrainedForceField::new();
+        assert_ne!(coarse.nonbonded_energy(&alanine), coarse.nonbonded_energy(&leucine));
+        assert_ne!(coarse.compute_forces(&alanine), coarse.compute_forces(&leucine));
+        // Opposite charges attract, like charges repel
+        let amber = Amber99SBForceField::new();
+        let salt_bridge = amber.nonbonded_energy(&chain(["LYS", "ALA", "ALA", "GLU"]));
+        let repulsive = amber.nonbonded_energy(&chain(["LYS", "ALA", "ALA", "LYS"]));
+        assert!(salt_bridge < repulsive);
+        assert_eq!(ResidueParameters::for_residue("xyz"), ResidueParameters::for_residue("ALA"));
+    }
+
+    #[test]
+    fn nonbonded_sums_exclude_close_neighbours_and_scale_one_four_pairs() {
+        // A hairpin: 1-3 pairs sit well inside σ, the 1-4 pair at 5 Å
+        let positions = [[0.0, 0.0, 0.0], [3.8, 0.0, 0.0], [3.8, 3.8, 0.0], [0.0, 5.0, 0.0]];
+        let chain = PeptideChain::new(
+            positions
+                .iter()
+                .enumerate()
+                .map(|(i, position)| Residue::new(ResidueId(i), "ALA", *position))
+                .collect(),
+        );
+        let lj = |sigma: f64, epsilon: f64, r: f64| {
+            4.0 * epsilon * ((sigma / r).powi(12) - (sigma / r).powi(6))
+        };
+        let coarse = CoarseGrainedForceField::new();
+        let expected = COARSE_ONE_FOUR_LJ * lj(3.5, 0.2, 5.0);
+        assert!((coarse.nonbonded_energy(&chain) - expected).abs() < 1e-9);
+
+        let amber = Amber99SBForceField::new();
+        let charge = amber.bead_charge(&ResidueParameters::for_residue("ALA"));
+        let expected = AMBER_ONE_FOUR_LJ * lj(3.40, 0.11, 5.0)
+            + AMBER_ONE_FOUR_COULOMB * 332.0 * charge * charge / 5.0;
+        assert!((amber.nonbonded_energy(&chain) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn dielectric_models_screen_the_coulomb_term() {
+        let chain = PeptideChain::new(
+            ["LYS", "ALA", "ALA", "ALA", "GLU"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| Residue::new(ResidueId(i), *name, [2.5 * i as f64, 0.0, 0.0]))
+                .collect(),
+        );
+        let amber = |model| Amber99SBForceField::new().with_dielectric_model(model);
+        let (gb, constant, distance) = (
+            amber(DielectricModel::Gb),
+            amber(DielectricModel::Constant),
+            amber(DielectricModel::DistanceDependent),
+        );
+        assert!(gb.solvation_energy(&chain) != 0.0);
+        assert_eq!(constant.solvation_energy(&chain), 0.0);
+        assert_eq!(distance.solvation_energy(&chain), 0.0);
+        // Screening weakens the salt bridge the vacuum sum sees
+        let vacuum = gb.nonbonded_energy(&chain);
+        assert!(constant.nonbonded_energy(&chain) > vacuum);
+        assert!(distance.nonbonded_energy(&chain) > vacuum);
+        assert_eq!(DielectricModel::parse("rdie"), Some(DielectricModel::DistanceDependent));
+    }
+
+    #[test]
+    fn proline_and_glycine_get_their_own_torsion_terms() {
+        let chain = |middle: &str, phi: f64| {
+            let mut chain = PeptideChain::new(
+                ["ALA", middle, "ALA", "ALA"]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| Residue::new(ResidueId(i), *name, [3.8 * i as f64, 0.0, 0.0]))
+                    .collect(),
+            );
+            chain.phi_mut()[1] = phi;
+            chain.psi_mut()[1] = 140.0;
+            chain
+        };
+        let coarse = CoarseGrainedForceField::new();
+        let amber = Amber99SBForceField::new();
+        for ff in [&coarse as &dyn ForceField, &amber] {
+            // The ring holds proline near -65°, well away from +60°
+            let (held, strained) = (chain("PRO", -65.0), chain("PRO", 60.0));
+            assert!(ff.dihedral_energy(&strained) - ff.dihedral_energy(&held) > 3.0);
+            // Without a Cβ, glycine's torsions cost less than alanine's
+            let (glycine, alanine) = (chain("GLY", 60.0), chain("ALA", 60.0));
+            assert!(ff.dihedral_energy(&glycine) < ff.dihedral_energy(&alanine));
+        }
+        assert_eq!(TorsionParameters::for_residue("ala").well_energy(60.0), 0.0);
+    }
+
+    #[test]
+    fn proline_and_glycine_torsion_parameters_are_pinned() {
+        assert_eq!(
+            TorsionParameters::for_residue("GLY"),
+            TorsionParameters {
+                scale: 0.5,
+                phi_well: None,
+            }
+        );
+        let proline = TorsionParameters::for_residue("pro");
+        assert_eq!(proline.phi_well, Some((4.0, -65.0)));
+        assert!(proline.well_energy(-65.0).abs() < 1e-12);
+        assert!((proline.well_energy(115.0) - 8.0).abs() < 1e-12);
+
+        let amber = Amber99SBForceField::new();
+        let phi_proline = amber.residue_dihedral("phi", "PRO").unwrap();
+        assert_eq!((phi_proline[0].0, phi_proline[0].1), (2.0, 1));
+        assert!((phi_proline[0].2 - 245.0f64.to_radians()).abs() < 1e-12);
+        assert_eq!(amber.residue_dihedral("phi", "gly").unwrap()[2], (0.2, 3, 0.0));
+        assert_eq!(amber.residue_dihedral("psi", "GLY").unwrap()[0], (0.4, 1, 0.0));
+
+        // Every term takes its angles in radians: at phi = 60°, psi = 140°
+        // only the middle residue's terms differ between these chains
+        let chain = |middle: &str| {
+            let mut chain = PeptideChain::new(
+                ["ALA", middle, "ALA", "ALA"]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| Residue::new(ResidueId(i), *name, [3.8 * i as f64, 0.0, 0.0]))
+                    .collect(),
+            );
+            chain.phi_mut()[1] = 60.0;
+            chain.psi_mut()[1] = 140.0;
+            chain
+        };
+        let coarse = CoarseGrainedForceField::new();
+        let generic = (1.0 + 180.0f64.to_radians().cos()) + (1.0 + 140.0f64.to_radians().cos());
+        let alanine = coarse.dihedral_energy(&chain("ALA"));
+        let glycine = coarse.dihedral_energy(&chain("GLY")) - alanine;
+        assert!((glycine + 0.5 * coarse.dihedral_strength * generic).abs() < 1e-9);
+        let proline = coarse.dihedral_energy(&chain("PRO")) - alanine;
+        assert!((proline - 4.0 * (1.0 - 125.0f64.to_radians().cos())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn residue_energies_add_up_to_the_total() {
+        let mut chain = PeptideChain::new(
+            ["LYS", "ALA", "PRO", "GLY", "LEU", "GLU"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let position = [3.6 * i as f64, 1.5 * (i % 2) as f64, 0.3 * i as f64];
+                    Residue::new(ResidueId(i), *name, position)
+                })
+                .collect(),
+        );
+        chain.phi_mut()[2] = 60.0;
+        chain.psi_mut()[3] = -40.0;
+        let coarse = CoarseGrainedForceField::new();
+        let amber = Amber99SBForceField::new();
+        for ff in [&coarse as &dyn ForceField, &amber] {
+            let residues = ff.residue_energies(&chain).unwrap();
+            assert_eq!(residues.len(), chain.len());
+            let total: f64 = residues.iter().sum();
+            assert!((total - ff.compute_energy(&chain)).abs() < 1e-9);
+        }
+        // The strained proline carries the most torsion energy
+        let residues = coarse.residue_energies(&chain).unwrap();
+        let dihedral = coarse.dihedral_energy(&chain);
+        assert!(residues[2] > residues[1] && residues[2] > 0.5 * dihedral);
+    }
+
     fn create_test_chain() -> PeptideChain {
         let residues = vec![
             Residue::new(ResidueId(0), "ALA", [0.0, 0.0, 0.0]),
@@ -0,0 +1,298 @@
+//! Local frustration of a structure, frustratometer-style.
+//!
+//! A contact is minimally frustrated when its two residues interact more
+//! favourably than most other residue pairs would at the same geometry, and
+//! highly frustrated when most would do better. Decoys swap the identities of
+//! the contact's residues for ones drawn from the chain's own composition
+//! (mutational frustration) and re-evaluate every nonbonded interaction the
+//! two residues make; the index is the decoy mean minus the native energy,
+//! over the decoy spread.
+
+use crate::force_fields::{ForceField, ResidueParameters, NONBONDED_CUTOFF};
+use crate::topology::{NonbondedPair, Topology};
+use folding_molecule::PeptideChain;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Index at or below which a contact is highly frustrated.
+pub const HIGH_FRUSTRATION: f64 = -1.0;
+
+/// Index at or above which a contact is minimally frustrated.
+pub const MINIMAL_FRUSTRATION: f64 = 0.78;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrustrationOptions {
+    /// Cα distance (Å) within which two non-excluded residues are in contact.
+    pub contact_cutoff: f64,
+    /// Decoy identities drawn per contact and per residue.
+    pub decoys: usize,
+    pub seed: u64,
+}
+
+impl Default for FrustrationOptions {
+    fn default() -> Self {
+        Self {
+            contact_cutoff: 6.5,
+            decoys: 1000,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrustrationClass {
+    Minimal,
+    Neutral,
+    High,
+}
+
+impl FrustrationClass {
+    pub fn classify(index: f64) -> Self {
+        if index <= HIGH_FRUSTRATION {
+            Self::High
+        } else if index >= MINIMAL_FRUSTRATION {
+            Self::Minimal
+        } else {
+            Self::Neutral
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Neutral => "neutral",
+            Self::High => "high",
+        }
+    }
+}
+
+/// Frustration of one contact, energies in kcal/mol.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactFrustration {
+    pub i: usize,
+    pub j: usize,
+    pub distance: f64,
+    /// Nonbonded energy of both residues with their neighbourhoods, their
+    /// own interaction counted once.
+    pub native: f64,
+    pub decoy_mean: f64,
+    pub decoy_std: f64,
+    pub index: f64,
+    pub class: FrustrationClass,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrustrationReport {
+    /// Contacts in `(i, j)` order.
+    pub contacts: Vec<ContactFrustration>,
+    /// Single-residue index of each residue: its identity alone is swapped.
+    pub residues: Vec<f64>,
+}
+
+impl FrustrationReport {
+    pub fn count(&self, class: FrustrationClass) -> usize {
+        self.contacts.iter().filter(|contact| contact.class == class).count()
+    }
+
+    /// Up to `limit` highly frustrated contacts, most frustrated first.
+    pub fn most_frustrated(&self, limit: usize) -> Vec<&ContactFrustration> {
+        let mut high: Vec<&ContactFrustration> = self
+            .contacts
+            .iter()
+            .filter(|contact| contact.class == FrustrationClass::High)
+            .collect();
+        high.sort_by(|a, b| a.index.total_cmp(&b.index));
+        high.truncate(limit);
+        high
+    }
+}
+
+/// Mutational frustration of every contact of `chain` under `force_field`;
+/// `None` when the force field's nonbonded terms are not pairwise.
+pub fn analyze_frustration(
+    force_field: &dyn ForceField,
+    chain: &PeptideChain,
+    options: &FrustrationOptions,
+) -> Option<FrustrationReport> {
+    let topology = Topology::for_chain(chain);
+    let parameters = &topology.residues;
+    let sample = parameters.first()?;
+    force_field.pair_energy(sample, sample, NONBONDED_CUTOFF, NonbondedPair::Full)?;
+
+    // Partners each residue's nonbonded terms reach: (k, distance, kind)
+    let positions = chain.positions();
+    let neighbours: Vec<Vec<(usize, f64, NonbondedPair)>> = (0..positions.len())
+        .map(|i| {
+            (0..positions.len())
+                .filter_map(|k| {
+                    let pair = topology.pair(i, k);
+                    let r = distance(positions[i], positions[k]);
+                    (pair != NonbondedPair::Excluded && r > 1e-10 && r < NONBONDED_CUTOFF)
+                        .then_some((k, r, pair))
+                })
+                .collect()
+        })
+        .collect();
+    let pair_energy = |first: &ResidueParameters, second: &ResidueParameters, r: f64, pair| {
+        force_field.pair_energy(first, second, r, pair).unwrap_or(0.0)
+    };
+    // Distinct identities in the chain; decoys draw from `composition`, so
+    // each is as likely as it is common
+    let mut identities: Vec<ResidueParameters> = Vec::new();
+    let composition: Vec<usize> = parameters
+        .iter()
+        .map(|residue| match identities.iter().position(|identity| identity == residue) {
+            Some(index) => index,
+            None => {
+                identities.push(*residue);
+                identities.len() - 1
+            }
+        })
+        .collect();
+    // Energy of each residue, wearing each identity, with all its neighbours
+    let surroundings: Vec<Vec<f64>> = neighbours
+        .iter()
+        .map(|partners| {
+            identities
+                .iter()
+                .map(|identity| {
+                    partners
+                        .iter()
+                        .map(|&(k, r, pair)| pair_energy(identity, &parameters[k], r, pair))
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut draw = || composition[rng.gen_range(0..composition.len())];
+    let mut contacts = Vec::new();
+    for (i, partners) in neighbours.iter().enumerate() {
+        for &(j, r, pair) in partners {
+            if j <= i || r > options.contact_cutoff {
+                continue;
+            }
+            // Swapping both identities changes their pair term once
+            let contact = |a: usize, b: usize| {
+                let (first, second) = (&identities[a], &identities[b]);
+                surroundings[i][a] - pair_energy(first, &parameters[j], r, pair)
+                    + surroundings[j][b]
+                    - pair_energy(second, &parameters[i], r, pair)
+                    + pair_energy(first, second, r, pair)
+            };
+            let native = contact(composition[i], composition[j]);
+            let decoys: Vec<f64> = (0..options.decoys)
+                .map(|_| {
+                    let (a, b) = (draw(), draw());
+                    contact(a, b)
+                })
+                .collect();
+            let (decoy_mean, decoy_std) = mean_and_std(&decoys);
+            let index = frustration_index(native, decoy_mean, decoy_std);
+            contacts.push(ContactFrustration {
+                i,
+                j,
+                distance: r,
+                native,
+                decoy_mean,
+                decoy_std,
+                index,
+                class: FrustrationClass::classify(index),
+            });
+        }
+    }
+
+    let residues = surroundings
+        .iter()
+        .enumerate()
+        .map(|(i, energies)| {
+            let decoys: Vec<f64> = (0..options.decoys).map(|_| energies[draw()]).collect();
+            let (mean, std) = mean_and_std(&decoys);
+            frustration_index(energies[composition[i]], mean, std)
+        })
+        .collect();
+    Some(FrustrationReport { contacts, residues })
+}
+
+/// 0 when every decoy scores alike, as in a homopolymer.
+fn frustration_index(native: f64, decoy_mean: f64, decoy_std: f64) -> f64 {
+    if decoy_std > 1e-12 {
+        (decoy_mean - native) / decoy_std
+    } else {
+        0.0
+    }
+}
+
+fn mean_and_std(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::force_fields::Amber99SBForceField;
+    use folding_molecule::{Residue, ResidueId};
+
+    /// A hairpin whose ends meet 5 Å apart across the turn.
+    fn hairpin(first: &str, last: &str) -> PeptideChain {
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [3.8, 0.0, 0.0],
+            [7.6, 0.0, 0.0],
+            [9.5, 3.3, 0.0],
+            [7.6, 6.6, 0.0],
+            [3.8, 6.6, 0.0],
+            [0.0, 5.0, 0.0],
+        ];
+        let names = [first, "ALA", "LEU", "GLY", "LEU", "ALA", last];
+        PeptideChain::new(
+            names
+                .iter()
+                .zip(positions)
+                .enumerate()
+                .map(|(i, (name, position))| Residue::new(ResidueId(i), *name, position))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn like_charges_in_contact_are_highly_frustrated() {
+        let amber = Amber99SBForceField::new()
+            .with_dielectric_model(crate::environment::DielectricModel::DistanceDependent);
+        let options = FrustrationOptions {
+            decoys: 400,
+            ..FrustrationOptions::default()
+        };
+        let index = |first: &str, last: &str| {
+            let report = analyze_frustration(&amber, &hairpin(first, last), &options).unwrap();
+            let contact = report
+                .contacts
+                .iter()
+                .find(|contact| (contact.i, contact.j) == (0, 6))
+                .copied()
+                .unwrap();
+            assert_eq!(report.residues.len(), 7);
+            contact
+        };
+        let repulsive = index("LYS", "ARG");
+        let salt_bridge = index("LYS", "GLU");
+        assert_eq!(repulsive.class, FrustrationClass::High);
+        assert!(salt_bridge.index > repulsive.index);
+        assert!(salt_bridge.native < repulsive.native);
+
+        // Same seed, same decoys
+        let again = index("LYS", "ARG");
+        assert_eq!(again, repulsive);
+        assert_eq!(FrustrationClass::classify(0.9), FrustrationClass::Minimal);
+    }
+}
@@ -1,15 +1,62 @@
 use folding_molecule::PeptideChain;
-use crate::force_fields::{ForceField, Vec3};
-use nalgebra::Vector3;
-use rand::Rng;
+use crate::force_fields::Vec3;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use rand_distr::{Distribution, Normal};
-use std::f64::consts::PI;
 
-/// Trait for molecular dynamics integrators
+/// Trait for molecular dynamics integrators.
+///
+/// A step runs in two stages: [`Integrator::step`] takes the forces at the
+/// current positions and moves the chain, then the caller evaluates forces at
+/// the new positions and passes them to [`Integrator::finish_step`], which
+/// completes the velocity update. Those forces are the ones to hand to the
+/// next `step`, so each step costs one force evaluation.
 pub trait Integrator {
     fn step(&mut self, chain: &mut PeptideChain, forces: &[Vec3], dt: f64);
+    /// Second stage of a step, with forces at the positions `step` produced.
+    /// Position-only integrators have nothing left to do.
+    fn finish_step(&mut self, _chain: &PeptideChain, _forces: &[Vec3], _dt: f64) {}
     fn set_temperature(&mut self, temperature: f64);
     fn get_kinetic_energy(&self, chain: &PeptideChain) -> f64;
+    /// Friction coefficient in amu/ps; integrators without a thermostat ignore it.
+    fn set_friction(&mut self, _friction: f64) {}
+    /// Restarts the random stream of a stochastic integrator, so a span run
+    /// with the same seed draws the same noise. Unseeded, noise comes from
+    /// system entropy.
+    fn seed(&mut self, _seed: u64) {}
+    /// Called before integrating positions the integrator did not produce
+    /// itself, e.g. when a bridge is reused for the next span after the engine
+    /// moved the chain. Velocities carry over; integrators that keep position
+    /// history re-anchor it on `chain`.
+    fn continue_from(&mut self, _chain: &PeptideChain) {}
+    /// Pins the particles flagged in `frozen` (indexed like the chain): they
+    /// keep their positions and carry no velocity until the next call. An
+    /// empty slice frees every particle.
+    fn freeze(&mut self, _frozen: &[bool]) {}
+    /// Velocities (Å/ps) the integrator carries into its next step; empty
+    /// when it keeps none or has not stepped yet.
+    fn velocities(&self) -> Vec<[f64; 3]> {
+        Vec::new()
+    }
+    /// Puts back velocities taken with [`Integrator::velocities`]; an empty
+    /// slice starts the next step at rest.
+    fn restore_velocities(&mut self, _velocities: &[[f64; 3]]) {}
+}
+
+fn is_frozen(frozen: &[bool], index: usize) -> bool {
+    frozen.get(index).copied().unwrap_or(false)
+}
+
+/// Instantaneous temperature (K) of `particles` beads carrying
+/// `kinetic_energy` kcal/mol, from equipartition over 3 degrees of freedom each.
+pub fn kinetic_temperature(kinetic_energy: f64, particles: usize) -> f64 {
+    let kb = 0.001987; // Boltzmann constant in kcal/mol/K
+    let dof = 3 * particles;
+    if dof > 0 {
+        2.0 * kinetic_energy / (kb * dof as f64)
+    } else {
+        0.0
+    }
 }
 
 /// Velocity Verlet integrator with Langevin thermostat
@@ -19,7 +66,8 @@ pub struct LangevinIntegrator {
     friction: f64,
     velocities: Vec<Vec3>,
     masses: Vec<f64>,
-    rng: rand::rngs::ThreadRng,
+    frozen: Vec<bool>,
+    rng: StdRng,
     normal: Normal<f64>,
 }
 
@@ -33,12 +81,13 @@ impl LangevinIntegrator {
             friction,
             velocities,
             masses,
-            rng: rand::thread_rng(),
+            frozen: Vec::new(),
+            rng: StdRng::from_entropy(),
             normal: Normal::new(0.0, 1.0).unwrap(),
         }
     }
 
-    pub fn initialize_velocities(&mut self, chain: &PeptideChain) {
+    pub fn initialize_velocities(&mut self, _chain: &PeptideChain) {
         let kb = 0.001987; // Boltzmann constant in kcal/mol/K
         
         for (i, mass) in self.masses.iter().enumerate() {
@@ -52,45 +101,61 @@ impl LangevinIntegrator {
         }
     }
 
+    /// Replaces the current velocities (Å/ps), e.g. to start from a known state.
+    pub fn set_velocities(&mut self, velocities: &[Vec3]) {
+        self.velocities = velocities.to_vec();
+        self.masses.resize(velocities.len(), 12.0);
+    }
+
     pub fn apply_constraints(&mut self, chain: &mut PeptideChain) {
         // SHAKE algorithm for bond constraints
         let tolerance = 1e-6;
         let max_iterations = 100;
         let target_bond_length = 3.8; // Å
         
-        let residues = chain.residues_mut();
+        // Chains of a complex are not bonded to each other
+        let bonded: Vec<usize> = (0..chain.len().saturating_sub(1))
+            .filter(|&i| chain.is_bonded(i))
+            .collect();
+        let positions = chain.positions_mut();
         
         for _ in 0..max_iterations {
             let mut max_error: f64 = 0.0;
             
-            for i in 0..residues.len().saturating_sub(1) {
-                let pos1 = residues[i].position();
-                let pos2 = residues[i + 1].position();
+            for &i in &bonded {
+                let pos1 = positions[i];
+                let pos2 = positions[i + 1];
                 
                 let dx = pos2[0] - pos1[0];
                 let dy = pos2[1] - pos1[1];
                 let dz = pos2[2] - pos1[2];
                 let current_distance = (dx * dx + dy * dy + dz * dz).sqrt();
                 
+                // A frozen end does not move; its partner takes the whole correction
+                let weight1 = if is_frozen(&self.frozen, i) { 0.0 } else { 1.0 };
+                let weight2 = if is_frozen(&self.frozen, i + 1) { 0.0 } else { 1.0 };
+                if weight1 + weight2 == 0.0 {
+                    continue;
+                }
                 let error = current_distance - target_bond_length;
                 max_error = max_error.max(error.abs());
                 
                 if error.abs() > tolerance {
-                    let correction = error / (2.0 * current_distance);
+                    let correction = error / ((weight1 + weight2) * current_distance);
                     
                     let mut pos1_new = pos1;
                     let mut pos2_new = pos2;
                     
-                    pos1_new[0] += correction * dx;
-                    pos1_new[1] += correction * dy;
-                    pos1_new[2] += correction * dz;
+                    pos1_new[0] += correction * weight1 * dx;
+                    pos1_new[1] += correction * weight1 * dy;
+                    pos1_new[2] += correction * weight1 * dz;
                     
-                    pos2_new[0] -= correction * dx;
-                    pos2_new[1] -= correction * dy;
-                    pos2_new[2] -= correction * dz;
+                    pos2_new[0] -= correction * weight2 * dx;
+                    pos2_new[1] -= correction * weight2 * dy;
+                    pos2_new[2] -= correction * weight2 * dz;
                     
-                    residues[i].set_position(pos1_new);
-                    residues[i + 1].set_position(pos2_new);
+                    positions[i] = pos1_new;
+                    positions[i + 1] = pos2_new;
                 }
             }
             
@@ -100,7 +165,7 @@ impl LangevinIntegrator {
         }
     }
 
-    pub fn apply_rotation_command(&mut self, chain: &mut PeptideChain, residue_idx: usize, angle: f64) {
+    pub fn apply_rotation_command(&mut self, _chain: &mut PeptideChain, residue_idx: usize, angle: f64) {
         if residue_idx < self.velocities.len() {
             // Apply rotation as velocity perturbation
             let perturbation_strength = 10.0; // Adjust as needed
@@ -115,17 +180,47 @@ impl LangevinIntegrator {
     }
 
     pub fn compute_temperature(&self, chain: &PeptideChain) -> f64 {
-        let kinetic_energy = self.get_kinetic_energy(chain);
-        let kb = 0.001987; // Boltzmann constant in kcal/mol/K
-        let dof = 3 * chain.len(); // 3 degrees of freedom per particle
-        
-        if dof > 0 {
-            2.0 * kinetic_energy / (kb * dof as f64)
-        } else {
-            0.0
+        kinetic_temperature(self.get_kinetic_energy(chain), chain.len())
+    }
+
+    /// Removes velocity components that would stretch constrained bonds
+    /// (RATTLE, second stage).
+    fn constrain_velocities(&mut self, chain: &PeptideChain) {
+        let positions = chain.positions();
+        let bonds = positions.len().min(self.velocities.len()).saturating_sub(1);
+        for _ in 0..100 {
+            let mut max_error: f64 = 0.0;
+            for i in (0..bonds).filter(|&i| chain.is_bonded(i)) {
+                let (a, b) = (positions[i], positions[i + 1]);
+                let bond = Vec3::new(b[0] - a[0], b[1] - a[1], b[2] - a[2]);
+                // Frozen particles act as infinitely heavy
+                let inverse_mass = |j: usize| {
+                    if is_frozen(&self.frozen, j) { 0.0 } else { 1.0 / self.masses[j] }
+                };
+                let (inverse_a, inverse_b) = (inverse_mass(i), inverse_mass(i + 1));
+                if inverse_a + inverse_b == 0.0 {
+                    continue;
+                }
+                let stretch_rate = (self.velocities[i + 1] - self.velocities[i]).dot(&bond);
+                let impulse = stretch_rate / (bond.norm_squared() * (inverse_a + inverse_b));
+                max_error = max_error.max(stretch_rate.abs());
+                self.velocities[i] += bond * (impulse * inverse_a);
+                self.velocities[i + 1] -= bond * (impulse * inverse_b);
+            }
+            if max_error < 1e-8 {
+                break;
+            }
         }
     }
 
+    fn thermal_kick(&mut self, sigma: f64) -> Vec3 {
+        Vec3::new(
+            self.normal.sample(&mut self.rng) * sigma,
+            self.normal.sample(&mut self.rng) * sigma,
+            self.normal.sample(&mut self.rng) * sigma,
+        )
+    }
+
     pub fn scale_velocities(&mut self, target_temperature: f64, current_temperature: f64) {
         if current_temperature > 1e-10 {
             let scale_factor = (target_temperature / current_temperature).sqrt();
@@ -139,51 +234,104 @@ impl LangevinIntegrator {
 impl Integrator for LangevinIntegrator {
     fn step(&mut self, chain: &mut PeptideChain, forces: &[Vec3], dt: f64) {
         let kb = 0.001987; // Boltzmann constant in kcal/mol/K
-        let residues = chain.residues_mut();
+        let positions = chain.positions_mut();
         
         // Ensure we have the right number of velocities
-        if self.velocities.len() != residues.len() {
-            self.velocities.resize(residues.len(), Vec3::zeros());
-            self.masses.resize(residues.len(), 12.0);
+        if self.velocities.len() != positions.len() {
+            self.velocities.resize(positions.len(), Vec3::zeros());
+            self.masses.resize(positions.len(), 12.0);
         }
         
-        // Velocity Verlet with Langevin thermostat
-        for (i, residue) in residues.iter_mut().enumerate() {
+        // Velocity Verlet with Langevin thermostat; the second half-kick and
+        // friction half-step happen in `finish_step` with the new forces
+        for (i, position) in positions.iter_mut().enumerate() {
+            if is_frozen(&self.frozen, i) {
+                self.velocities[i] = Vec3::zeros();
+                continue;
+            }
             let mass = self.masses[i];
             let force = if i < forces.len() { forces[i] } else { Vec3::zeros() };
             
-            // Random force for thermostat
-            let sigma = (2.0 * self.friction * kb * self.temperature / mass).sqrt();
-            let random_force = Vec3::new(
-                self.normal.sample(&mut self.rng) * sigma,
-                self.normal.sample(&mut self.rng) * sigma,
-                self.normal.sample(&mut self.rng) * sigma,
-            );
+            // Exact Ornstein-Uhlenbeck half-steps for friction and noise, so
+            // Stokes friction stays stable even when gamma * dt approaches 1
+            let damping = (-self.friction / mass * dt * 0.5).exp();
+            let sigma = (kb * self.temperature / mass * (1.0 - damping * damping)).sqrt();
+            let acceleration = force / mass;
             
             // Update velocity (first half)
-            let acceleration = (force + random_force - self.friction * self.velocities[i]) / mass;
+            self.velocities[i] = self.velocities[i] * damping + self.thermal_kick(sigma);
             self.velocities[i] += acceleration * dt * 0.5;
             
             // Update position
-            let mut pos = residue.position();
+            let mut pos = *position;
             pos[0] += self.velocities[i].x * dt;
             pos[1] += self.velocities[i].y * dt;
             pos[2] += self.velocities[i].z * dt;
-            residue.set_position(pos);
-            
-            // Update velocity (second half) - would need new forces here
-            self.velocities[i] += acceleration * dt * 0.5;
+            *position = pos;
         }
         
-        // Apply constraints
+        // Apply constraints, carrying the correction into the velocities so
+        // kinetic energy follows the constrained path (RATTLE, first stage)
+        let unconstrained = chain.positions().to_vec();
         self.apply_constraints(chain);
+        for (velocity, (after, before)) in self
+            .velocities
+            .iter_mut()
+            .zip(chain.positions().iter().zip(&unconstrained))
+        {
+            let correction =
+                Vec3::new(after[0] - before[0], after[1] - before[1], after[2] - before[2]);
+            *velocity += correction / dt;
+        }
+    }
+
+    fn finish_step(&mut self, chain: &PeptideChain, forces: &[Vec3], dt: f64) {
+        let kb = 0.001987; // Boltzmann constant in kcal/mol/K
+        for i in 0..self.velocities.len() {
+            if is_frozen(&self.frozen, i) {
+                continue;
+            }
+            let mass = self.masses[i];
+            let force = if i < forces.len() { forces[i] } else { Vec3::zeros() };
+            let damping = (-self.friction / mass * dt * 0.5).exp();
+            let sigma = (kb * self.temperature / mass * (1.0 - damping * damping)).sqrt();
+
+            // Update velocity (second half) with forces at the new positions
+            self.velocities[i] += force / mass * dt * 0.5;
+            self.velocities[i] = self.velocities[i] * damping + self.thermal_kick(sigma);
+        }
+        self.constrain_velocities(chain);
     }
 
     fn set_temperature(&mut self, temperature: f64) {
         self.temperature = temperature;
     }
 
-    fn get_kinetic_energy(&self, chain: &PeptideChain) -> f64 {
+    fn set_friction(&mut self, friction: f64) {
+        self.friction = friction.max(0.0);
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn freeze(&mut self, frozen: &[bool]) {
+        self.frozen = frozen.to_vec();
+        for (velocity, _) in self.velocities.iter_mut().zip(frozen).filter(|(_, pinned)| **pinned) {
+            *velocity = Vec3::zeros();
+        }
+    }
+
+    fn velocities(&self) -> Vec<[f64; 3]> {
+        self.velocities.iter().map(|velocity| [velocity.x, velocity.y, velocity.z]).collect()
+    }
+
+    fn restore_velocities(&mut self, velocities: &[[f64; 3]]) {
+        let velocities: Vec<Vec3> = velocities.iter().map(|v| Vec3::new(v[0], v[1], v[2])).collect();
+        self.set_velocities(&velocities);
+    }
+
+    fn get_kinetic_energy(&self, _chain: &PeptideChain) -> f64 {
         let mut kinetic_energy = 0.0;
         
         for (i, mass) in self.masses.iter().enumerate() {
@@ -201,41 +349,70 @@ impl Integrator for LangevinIntegrator {
 #[derive(Debug, Clone)]
 pub struct VerletIntegrator {
     previous_positions: Vec<[f64; 3]>,
+    /// Positions one step before `previous_positions`, for central differences.
+    earlier_positions: Vec<[f64; 3]>,
+    /// Positions the last step produced.
+    latest_positions: Vec<[f64; 3]>,
     masses: Vec<f64>,
+    /// Timestep of the last step; 0 before the first.
+    last_dt: f64,
+    frozen: Vec<bool>,
+    /// Velocities put back with `restore_velocities`; the next step, which
+    /// knows its timestep, rebuilds the position history from them.
+    restored: Option<Vec<[f64; 3]>>,
 }
 
 impl VerletIntegrator {
     pub fn new(num_particles: usize) -> Self {
         Self {
             previous_positions: vec![[0.0; 3]; num_particles],
+            earlier_positions: Vec::new(),
+            latest_positions: Vec::new(),
             masses: vec![12.0; num_particles], // CA atom mass
+            last_dt: 0.0,
+            frozen: Vec::new(),
+            restored: None,
         }
     }
 
     pub fn initialize(&mut self, chain: &PeptideChain) {
-        let residues = chain.residues();
         self.previous_positions.clear();
-        
-        for residue in residues {
-            self.previous_positions.push(residue.position());
-        }
+        self.previous_positions.extend_from_slice(chain.positions());
     }
 }
 
 impl Integrator for VerletIntegrator {
     fn step(&mut self, chain: &mut PeptideChain, forces: &[Vec3], dt: f64) {
-        let residues = chain.residues_mut();
+        let positions = chain.positions_mut();
         
-        // Ensure we have the right number of previous positions
-        if self.previous_positions.len() != residues.len() {
-            self.previous_positions.resize(residues.len(), [0.0; 3]);
-            self.masses.resize(residues.len(), 12.0);
+        if let Some(velocities) = self.restored.take() {
+            self.previous_positions = match velocities.len() == positions.len() {
+                true => positions
+                    .iter()
+                    .zip(&velocities)
+                    .map(|(p, v)| std::array::from_fn(|axis| p[axis] - v[axis] * dt))
+                    .collect(),
+                false => positions.to_vec(),
+            };
+        }
+        // A chain the integrator was not initialised for starts at rest
+        if self.previous_positions.len() != positions.len() {
+            self.previous_positions = positions.to_vec();
         }
+        self.masses.resize(positions.len(), 12.0);
+        self.earlier_positions.clone_from(&self.previous_positions);
+        self.last_dt = dt;
         
-        for (i, residue) in residues.iter_mut().enumerate() {
+        for (i, position) in positions.iter_mut().enumerate() {
             let mass = self.masses[i];
             let force = if i < forces.len() { forces[i] } else { Vec3::zeros() };
-            let current_pos = residue.position();
+            let current_pos = *position;
+            if is_frozen(&self.frozen, i) {
+                // History equal to the position reads as at rest
+                self.earlier_positions[i] = current_pos;
+                self.previous_positions[i] = current_pos;
+                continue;
+            }
             let prev_pos = self.previous_positions[i];
             
             // Verlet integration
@@ -247,18 +424,103 @@ impl Integrator for VerletIntegrator {
             ];
             
             self.previous_positions[i] = current_pos;
-            residue.set_position(new_pos);
+            *position = new_pos;
         }
+        self.latest_positions.clear();
+        self.latest_positions.extend_from_slice(positions);
+    }
+
+    /// Keeps the last step's velocities, (latest − previous) / dt, and
+    /// rebuilds the history behind `chain` from them; without a step of the
+    /// same chain length behind it the chain starts at rest.
+    fn continue_from(&mut self, chain: &PeptideChain) {
+        let positions = chain.positions();
+        if self.restored.is_some() {
+            self.latest_positions = positions.to_vec();
+            return;
+        }
+        let dt = self.last_dt;
+        if dt <= 0.0 || self.latest_positions.len() != positions.len() {
+            self.previous_positions = positions.to_vec();
+            self.earlier_positions.clear();
+            self.latest_positions.clear();
+            self.last_dt = 0.0;
+            return;
+        }
+        let velocities: Vec<[f64; 3]> = self
+            .latest_positions
+            .iter()
+            .zip(&self.previous_positions)
+            .enumerate()
+            .map(|(index, (latest, previous))| match is_frozen(&self.frozen, index) {
+                true => [0.0; 3],
+                false => std::array::from_fn(|axis| (latest[axis] - previous[axis]) / dt),
+            })
+            .collect();
+        let back = |steps: f64| -> Vec<[f64; 3]> {
+            positions
+                .iter()
+                .zip(&velocities)
+                .map(|(p, v)| std::array::from_fn(|axis| p[axis] - steps * v[axis] * dt))
+                .collect()
+        };
+        self.previous_positions = back(1.0);
+        self.earlier_positions = back(2.0);
+        self.latest_positions = positions.to_vec();
     }
 
     fn set_temperature(&mut self, _temperature: f64) {
         // Verlet integrator doesn't have temperature control
     }
 
+    /// The last step's velocities, (latest − previous) / dt.
+    fn velocities(&self) -> Vec<[f64; 3]> {
+        if let Some(velocities) = &self.restored {
+            return velocities.clone();
+        }
+        let dt = self.last_dt;
+        if dt <= 0.0 || self.latest_positions.len() != self.previous_positions.len() {
+            return Vec::new();
+        }
+        self.latest_positions
+            .iter()
+            .zip(&self.previous_positions)
+            .map(|(latest, previous)| std::array::from_fn(|axis| (latest[axis] - previous[axis]) / dt))
+            .collect()
+    }
+
+    fn restore_velocities(&mut self, velocities: &[[f64; 3]]) {
+        if velocities.is_empty() {
+            self.restored = None;
+            self.latest_positions.clear();
+            self.last_dt = 0.0;
+        } else {
+            self.restored = Some(velocities.to_vec());
+        }
+    }
+
+    fn freeze(&mut self, frozen: &[bool]) {
+        self.frozen = frozen.to_vec();
+    }
+
+    /// Kinetic energy one step back, where the central difference of the
+    /// positions on either side gives the velocities; 0 before the first step.
     fn get_kinetic_energy(&self, chain: &PeptideChain) -> f64 {
-        // Would need velocities to compute kinetic energy
-        // For Verlet, velocities can be estimated from position differences
-        0.0
+        let positions = chain.positions();
+        if self.last_dt <= 0.0 || self.earlier_positions.len() != positions.len() {
+            return 0.0;
+        }
+        let two_dt = 2.0 * self.last_dt;
+        positions
+            .iter()
+            .zip(&self.earlier_positions)
+            .zip(&self.masses)
+            .map(|((after, before), mass)| {
+                let speed_squared: f64 =
+                    (0..3).map(|axis| ((after[axis] - before[axis]) / two_dt).powi(2)).sum();
+                0.5 * mass * speed_squared
+            })
+            .sum()
     }
 }
 
@@ -268,7 +530,8 @@ pub struct BrownianIntegrator {
     temperature: f64,
     friction: f64,
     masses: Vec<f64>,
-    rng: rand::rngs::ThreadRng,
+    frozen: Vec<bool>,
+    rng: StdRng,
     normal: Normal<f64>,
 }
 
@@ -278,7 +541,8 @@ impl BrownianIntegrator {
             temperature,
             friction,
             masses: vec![12.0; num_particles],
-            rng: rand::thread_rng(),
+            frozen: Vec::new(),
+            rng: StdRng::from_entropy(),
             normal: Normal::new(0.0, 1.0).unwrap(),
         }
     }
@@ -287,14 +551,17 @@ impl BrownianIntegrator {
 impl Integrator for BrownianIntegrator {
     fn step(&mut self, chain: &mut PeptideChain, forces: &[Vec3], dt: f64) {
         let kb = 0.001987; // Boltzmann constant in kcal/mol/K
-        let residues = chain.residues_mut();
+        let positions = chain.positions_mut();
         
         // Ensure we have the right number of masses
-        if self.masses.len() != residues.len() {
-            self.masses.resize(residues.len(), 12.0);
+        if self.masses.len() != positions.len() {
+            self.masses.resize(positions.len(), 12.0);
         }
         
-        for (i, residue) in residues.iter_mut().enumerate() {
+        for (i, position) in positions.iter_mut().enumerate() {
+            if is_frozen(&self.frozen, i) {
+                continue;
+            }
             let mass = self.masses[i];
             let force = if i < forces.len() { forces[i] } else { Vec3::zeros() };
             
@@ -309,11 +576,11 @@ impl Integrator for BrownianIntegrator {
                 self.normal.sample(&mut self.rng) * noise_amplitude,
             );
             
-            let mut pos = residue.position();
+            let mut pos = *position;
             pos[0] += drift.x + random_displacement.x;
             pos[1] += drift.y + random_displacement.y;
             pos[2] += drift.z + random_displacement.z;
-            residue.set_position(pos);
+            *position = pos;
         }
     }
 
@@ -321,10 +588,21 @@ impl Integrator for BrownianIntegrator {
         self.temperature = temperature;
     }
 
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn freeze(&mut self, frozen: &[bool]) {
+        self.frozen = frozen.to_vec();
+    }
+
     fn get_kinetic_energy(&self, _chain: &PeptideChain) -> f64 {
         // Brownian dynamics doesn't explicitly track velocities
         let kb = 0.001987;
-        let dof = 3 * self.masses.len();
+        let free = (0..self.masses.len())
+            .filter(|&index| !is_frozen(&self.frozen, index))
+            .count();
+        let dof = 3 * free;
         0.5 * kb * self.temperature * dof as f64
     }
 }
@@ -333,7 +611,7 @@ impl Integrator for BrownianIntegrator {
 mod tests {
     use super::*;
     use folding_molecule::{Residue, ResidueId};
-    use crate::force_fields::CoarseGrainedForceField;
+    use crate::force_fields::{CoarseGrainedForceField, ForceField};
 
     #[test]
     fn test_langevin_integrator() {
@@ -346,6 +624,7 @@ mod tests {
         
         let initial_energy = integrator.get_kinetic_energy(&chain);
         integrator.step(&mut chain, &forces, 0.001);
+        integrator.finish_step(&chain, &ff.compute_forces(&chain), 0.001);
         let final_energy = integrator.get_kinetic_energy(&chain);
         
         assert!(initial_energy.is_finite());
@@ -361,9 +640,9 @@ mod tests {
         let ff = CoarseGrainedForceField::new();
         let forces = ff.compute_forces(&chain);
         
-        let initial_pos = chain.residues()[0].position();
+        let initial_pos = chain.positions()[0];
         integrator.step(&mut chain, &forces, 0.001);
-        let final_pos = chain.residues()[0].position();
+        let final_pos = chain.positions()[0];
         
         // Position should change
         let displacement = (
@@ -375,6 +654,28 @@ mod tests {
         assert!(displacement >= 0.0);
     }
 
+    #[test]
+    fn verlet_estimates_kinetic_energy_from_positions() {
+        // A free 12 amu bead drifting at 2 Å/ps carries ½·12·2² = 24
+        let mut chain = PeptideChain::new(vec![Residue::new(ResidueId(0), "ALA", [0.0; 3])]);
+        let mut integrator = VerletIntegrator::new(1);
+        let mut earlier = chain.clone();
+        earlier.positions_mut()[0] = [-0.002, 0.0, 0.0];
+        integrator.initialize(&earlier);
+        assert_eq!(integrator.get_kinetic_energy(&chain), 0.0);
+        for _ in 0..3 {
+            integrator.step(&mut chain, &[Vec3::zeros()], 0.001);
+        }
+        assert!((integrator.get_kinetic_energy(&chain) - 24.0).abs() < 1e-6);
+        // Moved elsewhere between runs, the bead keeps its velocity
+        chain.positions_mut()[0] = [5.0, 5.0, 5.0];
+        integrator.continue_from(&chain);
+        assert!((integrator.get_kinetic_energy(&chain) - 24.0).abs() < 1e-6);
+        integrator.step(&mut chain, &[Vec3::zeros()], 0.001);
+        assert!((chain.positions()[0][0] - 5.002).abs() < 1e-9);
+        assert!((kinetic_temperature(24.0, 1) - 16.0 / 0.001987).abs() < 1e-6);
+    }
+
     #[test]
     fn test_brownian_integrator() {
         let mut chain = create_test_chain();
@@ -383,9 +684,9 @@ mod tests {
         let ff = CoarseGrainedForceField::new();
         let forces = ff.compute_forces(&chain);
         
-        let initial_pos = chain.residues()[0].position();
+        let initial_pos = chain.positions()[0];
         integrator.step(&mut chain, &forces, 0.001);
-        let final_pos = chain.residues()[0].position();
+        let final_pos = chain.positions()[0];
         
         // Position should change due to random motion
         let displacement = (
@@ -397,6 +698,26 @@ mod tests {
         assert!(displacement >= 0.0);
     }
 
+    #[test]
+    fn seeded_integrators_draw_the_same_noise() {
+        let ff = CoarseGrainedForceField::new();
+        let run = |integrator: &mut dyn Integrator| {
+            let mut chain = create_test_chain();
+            integrator.seed(11);
+            for _ in 0..5 {
+                let forces = ff.compute_forces(&chain);
+                integrator.step(&mut chain, &forces, 0.001);
+                integrator.finish_step(&chain, &ff.compute_forces(&chain), 0.001);
+            }
+            chain.positions().to_vec()
+        };
+        let length = create_test_chain().len();
+        let langevin = || LangevinIntegrator::new(length, 300.0, 1.0);
+        assert_eq!(run(&mut langevin()), run(&mut langevin()));
+        let brownian = || BrownianIntegrator::new(length, 300.0, 1.0);
+        assert_eq!(run(&mut brownian()), run(&mut brownian()));
+    }
+
     #[test]
     fn test_constraint_satisfaction() {
         let mut chain = create_test_chain();
@@ -405,10 +726,10 @@ mod tests {
         integrator.apply_constraints(&mut chain);
         
         // Check bond lengths
-        let residues = chain.residues();
-        for i in 0..residues.len().saturating_sub(1) {
-            let pos1 = residues[i].position();
-            let pos2 = residues[i + 1].position();
+        let positions = chain.positions();
+        for i in 0..positions.len().saturating_sub(1) {
+            let pos1 = positions[i];
+            let pos2 = positions[i + 1];
             let distance = (
                 (pos2[0] - pos1[0]).powi(2) +
                 (pos2[1] - pos1[1]).powi(2) +
@@ -420,6 +741,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_langevin_stable_under_stokes_friction() {
+        let mut chain = create_test_chain();
+        let mut integrator = LangevinIntegrator::new(chain.len(), 300.0, 1.0);
+        integrator.set_friction(5000.0);
+        let forces = vec![Vec3::zeros(); chain.len()];
+        for _ in 0..200 {
+            integrator.step(&mut chain, &forces, 0.005);
+            integrator.finish_step(&chain, &forces, 0.005);
+        }
+        let kinetic_energy = integrator.get_kinetic_energy(&chain);
+        let equipartition = 1.5 * 0.001987 * 300.0 * chain.len() as f64;
+        assert!(kinetic_energy > 0.0 && kinetic_energy < 10.0 * equipartition);
+    }
+
     fn create_test_chain() -> PeptideChain {
         let residues = vec![
             Residue::new(ResidueId(0), "ALA", [0.0, 0.0, 0.0]),
@@ -1,21 +1,48 @@
 // Module declarations
+pub mod crowding;
+pub mod environment;
 pub mod force_fields;
+pub mod frustration;
 pub mod integrators;
 pub mod native_bridge;
+pub mod nve;
+pub mod registry;
+pub mod topology;
 
-use folding_molecule::PeptideChain;
-use folding_time::trajectory::SpanRecord;
 use nalgebra::{Vector3, Point3};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 
 // Re-export key traits and types
-pub use force_fields::{ForceField, CoarseGrainedForceField, Amber99SBForceField};
+pub use crowding::{CrowderField, CrowdingModel, JAMMING_FRACTION};
+pub use environment::{DielectricModel, Environment};
+pub use force_fields::{ForceField, CoarseGrainedForceField, Amber99SBForceField, AmberParameters, ResidueParameters, TorsionParameters, EnergyComponents, NONBONDED_CUTOFF};
+pub use frustration::{ContactFrustration, FrustrationClass, FrustrationOptions, FrustrationReport, analyze_frustration};
 pub use integrators::{Integrator, LangevinIntegrator, VerletIntegrator, BrownianIntegrator};
 pub use native_bridge::NativePhysicsBridge;
+pub use nve::{NveConfig, NveReport};
+pub use topology::{NonbondedPair, SetupCacheStats, Topology, clear_setup_cache, sequence_hash, setup_cache_stats};
+use folding_time::TimeScale;
 use thiserror::Error;
 
+/// Cargo features folding-physics and folding-molecule were compiled with.
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = folding_molecule::enabled_features();
+    let own = [
+        ("amber99sb", cfg!(feature = "amber99sb")),
+        ("charmm36", cfg!(feature = "charmm36")),
+        ("opls_aa", cfg!(feature = "opls_aa")),
+        ("plugins", cfg!(feature = "plugins")),
+    ];
+    for (feature, _) in own.into_iter().filter(|(_, enabled)| *enabled) {
+        if !features.contains(&feature) {
+            features.push(feature);
+        }
+    }
+    features
+}
+
 pub type Vec3 = Vector3<f64>;
 pub type Point3D = Point3<f64>;
 
@@ -59,24 +86,56 @@ pub struct NativePhysicsResponse {
     pub physics_metrics: HashMap<String, f64>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Fidelity of a physics span, shared by the contract language and the
+/// backends. Ordered from cheapest to most detailed, so `Gb > Toy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PhysicsLevel {
     Toy,
     Coarse,
-    GB,
+    Gb,
     Full,
 }
 
 impl PhysicsLevel {
-    pub fn from_str(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "toy" => Self::Toy,
-            "coarse" => Self::Coarse,
-            "gb" => Self::GB,
-            "full" => Self::Full,
-            _ => Self::Toy,
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PhysicsLevel::Toy => "toy",
+            PhysicsLevel::Coarse => "coarse",
+            PhysicsLevel::Gb => "gb",
+            PhysicsLevel::Full => "full",
         }
     }
+
+    /// Accepts the same spellings as `set_physics_level`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "toy" | "none" | "off" => Some(PhysicsLevel::Toy),
+            "coarse" | "cg" => Some(PhysicsLevel::Coarse),
+            "gb" | "implicit" => Some(PhysicsLevel::Gb),
+            "full" | "explicit" => Some(PhysicsLevel::Full),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for PhysicsLevel {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Self::parse(raw).ok_or_else(|| format!("unknown physics level '{raw}'"))
+    }
+}
+
+impl std::fmt::Display for PhysicsLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Simulated time of a span lasting `duration_ms` of contract time.
+fn span_simulation_ps(duration_ms: u64) -> f64 {
+    TimeScale::default().to_ps(Duration::from_millis(duration_ms))
 }
 
 pub struct NativePhysicsEngine {
@@ -93,7 +152,7 @@ impl NativePhysicsEngine {
         match self.level {
             PhysicsLevel::Toy => self.compute_toy_physics(request),
             PhysicsLevel::Coarse => self.compute_coarse_physics(request),
-            PhysicsLevel::GB => self.compute_gb_physics(request),
+            PhysicsLevel::Gb => self.compute_gb_physics(request),
             PhysicsLevel::Full => self.compute_full_physics(request),
         }
     }
@@ -112,7 +171,7 @@ impl NativePhysicsEngine {
         let radius_of_gyration = 1.5 + magnitude * 0.002;
         let potential_energy = delta_energy * 1000.0;
         let kinetic_energy = delta_energy * 800.0;
-        let simulation_time_ps = request.duration_ms as f64 * 0.01;
+        let simulation_time_ps = span_simulation_ps(request.duration_ms);
 
         let mut physics_metrics = HashMap::new();
         physics_metrics.insert("bond_energy".to_string(), potential_energy * 0.3);
@@ -162,7 +221,7 @@ impl NativePhysicsEngine {
             potential_energy: delta_energy,
             kinetic_energy: request.temperature * 0.01,
             temperature: request.temperature,
-            simulation_time_ps: request.duration_ms as f64 * 0.001,
+            simulation_time_ps: span_simulation_ps(request.duration_ms),
             trajectory_path: None,
             physics_metrics,
         })
@@ -195,7 +254,7 @@ impl NativePhysicsEngine {
             potential_energy: delta_energy,
             kinetic_energy: request.temperature * 0.015,
             temperature: request.temperature,
-            simulation_time_ps: request.duration_ms as f64 * 0.001,
+            simulation_time_ps: span_simulation_ps(request.duration_ms),
             trajectory_path: None,
             physics_metrics,
         })
@@ -228,13 +287,14 @@ impl NativePhysicsEngine {
             potential_energy: delta_energy,
             kinetic_energy: request.temperature * 0.02,
             temperature: request.temperature,
-            simulation_time_ps: request.duration_ms as f64 * 0.001,
+            simulation_time_ps: span_simulation_ps(request.duration_ms),
             trajectory_path: None,
             physics_metrics,
         })
     }
 }
 
+#[allow(dead_code)]
 fn compute_rmsd(initial: &[Point3D], final_positions: &[Point3D]) -> f64 {
     if initial.len() != final_positions.len() || initial.is_empty() {
         return 0.0;
@@ -249,6 +309,7 @@ fn compute_rmsd(initial: &[Point3D], final_positions: &[Point3D]) -> f64 {
     (sum_sq_diff / initial.len() as f64).sqrt()
 }
 
+#[allow(dead_code)]
 fn compute_radius_of_gyration(positions: &[Point3D]) -> f64 {
     if positions.is_empty() {
         return 0.0;
@@ -262,3 +323,22 @@ fn compute_radius_of_gyration(positions: &[Point3D]) -> f64 {
 
     (sum_sq_dist / positions.len() as f64).sqrt()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn physics_level_round_trips_through_text_and_serde() {
+        use PhysicsLevel::{Coarse, Full, Gb, Toy};
+        for level in [Toy, Coarse, Gb, Full] {
+            assert_eq!(level.to_string().parse::<PhysicsLevel>(), Ok(level));
+            let json = serde_json::to_string(&level).unwrap();
+            assert_eq!(json, format!("\"{}\"", level.as_str()));
+            assert_eq!(serde_json::from_str::<PhysicsLevel>(&json).unwrap(), level);
+        }
+        assert_eq!("implicit".parse(), Ok(PhysicsLevel::Gb));
+        assert!("quantum".parse::<PhysicsLevel>().is_err());
+        assert!(PhysicsLevel::Gb > PhysicsLevel::Toy);
+    }
+}
@@ -1,72 +1,208 @@
-use folding_molecule::PeptideChain;
-use crate::force_fields::{ForceField, CoarseGrainedForceField, Amber99SBForceField};
-use crate::integrators::{Integrator, LangevinIntegrator, VerletIntegrator};
+use folding_molecule::{Dihedral, PeptideChain, ResidueId};
+use crate::force_fields::{ForceField, CoarseGrainedForceField, Amber99SBForceField, EnergyComponents, Vec3};
+use crate::integrators::{kinetic_temperature, Integrator, LangevinIntegrator, VerletIntegrator};
+use crate::environment::{Environment, BEAD_HYDRODYNAMIC_RADIUS};
+use crate::crowding::CrowderField;
+use crate::frustration::{analyze_frustration, FrustrationOptions, FrustrationReport};
+use crate::registry;
 use crate::PhysicsLevel;
 use serde_json;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+/// MD steps between recorded diagnostics and trajectory frames.
+const FRAME_INTERVAL: usize = 10;
+
+/// Force field a level runs with unless a registered one replaces it.
+fn level_force_field(physics_level: PhysicsLevel, environment: &Environment) -> Box<dyn ForceField> {
+    match physics_level {
+        PhysicsLevel::Toy | PhysicsLevel::Coarse => Box::new(CoarseGrainedForceField::new()),
+        PhysicsLevel::Gb | PhysicsLevel::Full => Box::new(
+            Amber99SBForceField::new()
+                .with_solvent_dielectric(environment.dielectric)
+                .with_ionic_strength(environment.ionic_strength)
+                .with_dielectric_model(environment.dielectric_model),
+        ),
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PhysicsRequest {
     pub initial_positions: Vec<[f64; 3]>,
     pub residue_types: Vec<String>,
-    pub rotation_commands: Vec<(usize, f64)>,
+    pub rotation_commands: Vec<TorsionRotation>,
     pub physics_level: PhysicsLevel,
     pub temperature: f64,
+    /// Simulated time to integrate, in ps; 0 uses the level's default (1 ps).
     pub simulation_time: f64,
+    /// Integration timestep in ps; `None` uses the level's default.
+    #[serde(default)]
+    pub timestep: Option<f64>,
+    /// JSON file the MD frames are written to; `None` keeps no trajectory.
+    #[serde(default)]
+    pub trajectory_path: Option<PathBuf>,
+    /// Residues held in place: their forces are zeroed, the integrator keeps
+    /// them at rest and they end the burst where they started it.
+    #[serde(default)]
+    pub frozen: Vec<usize>,
+    /// First residues of every chain after the first, for a complex; the
+    /// backbone is not bonded across them.
+    #[serde(default)]
+    pub chain_breaks: Vec<usize>,
+}
+
+/// Torsion turn applied to the chain before integration.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TorsionRotation {
+    pub residue: usize,
+    #[serde(with = "dihedral_name")]
+    pub dihedral: Dihedral,
+    /// Degrees.
+    pub angle: f64,
+}
+
+mod dihedral_name {
+    use folding_molecule::Dihedral;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(dihedral: &Dihedral, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(dihedral.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Dihedral, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Dihedral::parse(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown dihedral `{name}`")))
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RotationOutcome {
     pub final_positions: Vec<[f64; 3]>,
     pub final_angles: Vec<(f64, f64)>,
+    /// Potential energy of the request's starting structure, before rotations.
+    #[serde(default)]
+    pub initial_energy: f64,
+    /// Potential energy right after the rotations, before integration.
+    #[serde(default)]
+    pub rotated_energy: f64,
+    /// Total energy at the end of the run: potential plus kinetic.
     pub energy: f64,
     pub kinetic_energy: f64,
     pub potential_energy: f64,
+    /// Instantaneous temperature (K) from the final kinetic energy.
     pub temperature: f64,
     pub rmsd: f64,
     pub radius_of_gyration: f64,
+    /// Wall-clock seconds the simulation took.
     pub simulation_time: f64,
+    /// Physical time covered by the integration, in ps.
+    pub simulated_time_ps: f64,
     pub convergence_info: String,
     pub trajectory_data: Option<serde_json::Value>,
+    /// File the frames were written to, when the request asked for one.
+    #[serde(default)]
+    pub trajectory_path: Option<String>,
 }
 
 /// Native Rust physics engine bridge
 pub struct NativePhysicsBridge {
     force_field: Box<dyn ForceField>,
+    /// Registered force field replacing the level's, rebuilt with it when the
+    /// environment changes.
+    force_field_name: Option<String>,
     integrator: Box<dyn Integrator>,
     physics_level: PhysicsLevel,
+    environment: Environment,
+    crowders: Option<CrowderField>,
 }
 
 impl NativePhysicsBridge {
     pub fn new(physics_level: PhysicsLevel) -> Self {
-        let (force_field, integrator): (Box<dyn ForceField>, Box<dyn Integrator>) = match physics_level {
-            PhysicsLevel::Toy => {
-                let ff = Box::new(CoarseGrainedForceField::new());
-                let integrator = Box::new(VerletIntegrator::new(0)); // Will be resized
-                (ff, integrator)
-            },
-            PhysicsLevel::Coarse => {
-                let ff = Box::new(CoarseGrainedForceField::new());
-                let integrator = Box::new(LangevinIntegrator::new(0, 300.0, 1.0));
-                (ff, integrator)
-            },
-            PhysicsLevel::GB => {
-                let ff = Box::new(Amber99SBForceField::new());
-                let integrator = Box::new(LangevinIntegrator::new(0, 300.0, 5.0));
-                (ff, integrator)
-            },
-            PhysicsLevel::Full => {
-                let ff = Box::new(Amber99SBForceField::new());
-                let integrator = Box::new(LangevinIntegrator::new(0, 300.0, 10.0));
-                (ff, integrator)
+        Self::with_environment(physics_level, &Environment::default())
+    }
+
+    /// Bridge whose Langevin friction is the Stokes drag of the solvent and
+    /// whose GB term uses the solvent dielectric.
+    pub fn with_environment(physics_level: PhysicsLevel, environment: &Environment) -> Self {
+        let friction = environment.stokes_friction(environment.default_temperature, BEAD_HYDRODYNAMIC_RADIUS);
+        let integrator: Box<dyn Integrator> = match physics_level {
+            PhysicsLevel::Toy => Box::new(VerletIntegrator::new(0)), // Will be resized
+            PhysicsLevel::Coarse | PhysicsLevel::Gb | PhysicsLevel::Full => {
+                Box::new(LangevinIntegrator::new(0, environment.default_temperature, friction))
             },
         };
 
         Self {
-            force_field,
+            force_field: level_force_field(physics_level, environment),
+            force_field_name: None,
             integrator,
             physics_level,
+            environment: environment.clone(),
+            crowders: None,
+        }
+    }
+
+    /// Swaps in registered components by name; `None` keeps the level's choice.
+    pub fn with_components(mut self, force_field: Option<&str>, integrator: Option<&str>) -> Result<Self, String> {
+        if let Some(name) = force_field {
+            self.force_field = registry::create_force_field(name, &self.environment)?;
+            self.force_field_name = Some(name.to_string());
+        }
+        if let Some(name) = integrator {
+            self.integrator = registry::create_integrator(name, &self.environment)?;
         }
+        Ok(self)
+    }
+
+    /// Seeds the integrator's noise so the same span reproduces its trajectory;
+    /// call after [`Self::with_components`], which may replace the integrator.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.set_seed(seed);
+        self
+    }
+
+    /// Adds repulsion from explicit crowder spheres to every force evaluation.
+    pub fn with_crowders(mut self, crowders: Option<CrowderField>) -> Self {
+        self.set_crowders(crowders);
+        self
+    }
+
+    /// [`Self::with_seed`] for a bridge kept across spans.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.integrator.seed(seed);
+    }
+
+    /// [`Self::with_crowders`] for a bridge kept across spans.
+    pub fn set_crowders(&mut self, crowders: Option<CrowderField>) {
+        self.crowders = crowders;
+    }
+
+    /// Moves a bridge kept across spans into new solvent conditions: the
+    /// force field is rebuilt for them, the integrator and its velocities are
+    /// kept.
+    pub fn set_environment(&mut self, environment: &Environment) -> Result<(), String> {
+        if &self.environment == environment {
+            return Ok(());
+        }
+        self.force_field = match &self.force_field_name {
+            Some(name) => registry::create_force_field(name, environment)?,
+            None => level_force_field(self.physics_level, environment),
+        };
+        self.environment = environment.clone();
+        Ok(())
+    }
+
+    /// Integrator velocities (Å/ps) the next span starts from; see
+    /// [`Integrator::velocities`].
+    pub fn velocities(&self) -> Vec<[f64; 3]> {
+        self.integrator.velocities()
+    }
+
+    /// Puts back velocities taken with [`Self::velocities`], e.g. after the
+    /// engine rejected the span that advanced them.
+    pub fn restore_velocities(&mut self, velocities: &[[f64; 3]]) {
+        self.integrator.restore_velocities(velocities);
     }
 
     pub fn run_physics_simulation(&mut self, request: &PhysicsRequest) -> Result<RotationOutcome, String> {
@@ -75,49 +211,92 @@ impl NativePhysicsBridge {
         // Parse the chain from request
         let mut chain = self.parse_chain_from_request(request)?;
         
-        // Set up integrator parameters based on physics level
-        let (timestep, num_steps, temperature) = self.get_simulation_parameters();
+        // Set up integrator parameters based on physics level; friction tracks
+        // the solvent viscosity at the requested (possibly annealed) temperature
+        let (default_timestep, default_steps) = self.get_simulation_parameters();
+        let timestep = request.timestep.unwrap_or(default_timestep);
+        self.check_timestep(timestep)?;
+        let simulation_time = if request.simulation_time > 0.0 {
+            request.simulation_time
+        } else {
+            default_timestep * default_steps as f64
+        };
+        let num_steps = ((simulation_time / timestep).round() as usize).max(1);
+        let temperature = if request.temperature > 0.0 {
+            request.temperature
+        } else {
+            self.environment.default_temperature
+        };
         self.integrator.set_temperature(temperature);
+        self.integrator
+            .set_friction(self.environment.stokes_friction(temperature, BEAD_HYDRODYNAMIC_RADIUS));
         
-        // Initialize integrator if it's Langevin (simplified approach)
-        // Apply rotation commands by modifying phi/psi angles directly
-        for (residue_idx, angle) in &request.rotation_commands {
-            if *residue_idx < chain.len() {
-                let residues = chain.residues_mut();
-                if let Some(residue) = residues.get_mut(*residue_idx) {
-                    residue.phi += angle;
-                }
-            }
+        // Turn the requested torsions, moving the residues they carry
+        let initial_energy = self.potential_energy(&chain);
+        for rotation in &request.rotation_commands {
+            chain.rotate_torsion(ResidueId(rotation.residue), rotation.dihedral, rotation.angle);
         }
+        let rotated_energy = self.potential_energy(&chain);
+        let mut frozen = vec![false; chain.len()];
+        for &index in request.frozen.iter().filter(|&&index| index < chain.len()) {
+            frozen[index] = true;
+        }
+        let pinned = chain.positions().to_vec();
+        self.integrator.freeze(&frozen);
+        self.integrator.continue_from(&chain);
         
         // Run MD simulation
         let mut energies = Vec::new();
         let mut temperatures = Vec::new();
+        let mut frames = Vec::new();
+        let recording = request.trajectory_path.is_some();
+        if recording {
+            frames.push(frame(0, 0.0, &chain));
+        }
         
+        let mut forces = held_forces(self.forces(&chain), &frozen);
         for step in 0..num_steps {
-            // Compute forces
-            let forces = self.force_field.compute_forces(&chain);
-            
-            // Integrate one step
+            // Integrate one step, re-evaluating forces between the half-kicks
             self.integrator.step(&mut chain, &forces, timestep);
+            hold_positions(&mut chain, &frozen, &pinned);
+            forces = held_forces(self.forces(&chain), &frozen);
+            self.integrator.finish_step(&chain, &forces, timestep);
             
             // Record diagnostics every 10 steps
-            if step % 10 == 0 {
-                use crate::force_fields::ForceField;
-                use crate::integrators::Integrator;
-                let potential_energy = self.force_field.compute_energy(&chain);
+            if step % FRAME_INTERVAL == 0 {
+                let potential_energy = self.potential_energy(&chain);
                 let kinetic_energy = self.integrator.get_kinetic_energy(&chain);
                 let total_energy = potential_energy + kinetic_energy;
                 
                 energies.push(total_energy);
-                temperatures.push(temperature);
+                temperatures.push(kinetic_temperature(kinetic_energy, chain.len()));
+            }
+            if recording && ((step + 1) % FRAME_INTERVAL == 0 || step + 1 == num_steps) {
+                frames.push(frame(step + 1, timestep * (step + 1) as f64, &chain));
             }
         }
+        // The frames are a by-product: a file that cannot be written loses
+        // them, not the span
+        let trajectory_path = match &request.trajectory_path {
+            Some(path) => {
+                let trajectory = serde_json::json!({
+                    "residues": request.residue_types,
+                    "timestep_ps": timestep,
+                    "frames": frames,
+                });
+                match write_trajectory(path, &trajectory) {
+                    Ok(()) => Some(path.display().to_string()),
+                    Err(err) => {
+                        log::warn!("{err}");
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
         
         // Compute final metrics
-        use crate::force_fields::ForceField;
-        use crate::integrators::Integrator;
-        let final_energy = self.force_field.compute_energy(&chain);
+        let final_energy = self.potential_energy(&chain);
         let kinetic_energy = self.integrator.get_kinetic_energy(&chain);
         let rmsd = self.compute_rmsd(&chain, &request.initial_positions);
         let radius_of_gyration = self.compute_radius_of_gyration(&chain);
@@ -127,13 +306,16 @@ impl NativePhysicsBridge {
         Ok(RotationOutcome {
             final_positions: self.extract_positions(&chain),
             final_angles: self.extract_angles(&chain),
-            energy: final_energy,
+            initial_energy,
+            rotated_energy,
+            energy: final_energy + kinetic_energy,
             kinetic_energy,
-            potential_energy: final_energy - kinetic_energy,
-            temperature: temperatures.last().copied().unwrap_or(temperature),
+            potential_energy: final_energy,
+            temperature: kinetic_temperature(kinetic_energy, chain.len()),
             rmsd,
             radius_of_gyration,
             simulation_time,
+            simulated_time_ps: timestep * num_steps as f64,
             convergence_info: format!(
                 "Native physics simulation completed in {:.3}s with {} steps",
                 simulation_time, num_steps
@@ -141,19 +323,73 @@ impl NativePhysicsBridge {
             trajectory_data: Some(serde_json::json!({
                 "energies": energies,
                 "temperatures": temperatures,
-                "physics_level": format!("{:?}", self.physics_level),
+                "physics_level": self.physics_level.as_str(),
                 "timestep": timestep,
                 "num_steps": num_steps
             })),
+            trajectory_path,
         })
     }
     
-    fn get_simulation_parameters(&self) -> (f64, usize, f64) {
+    /// Rejects timesteps the force field cannot integrate stably.
+    pub fn check_timestep(&self, timestep: f64) -> Result<(), String> {
+        if !(timestep.is_finite() && timestep > 0.0) {
+            return Err(format!("timestep must be a positive time in ps, got {timestep}"));
+        }
+        match self.force_field.max_stable_timestep() {
+            Some(limit) if timestep > limit => Err(format!(
+                "timestep {timestep} ps exceeds the force field's stability limit of {limit} ps"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// The force field's energy of `chain` split into its terms; crowders
+    /// are not included.
+    pub fn energy_components(&self, chain: &PeptideChain) -> EnergyComponents {
+        self.force_field.energy_components(chain)
+    }
+
+    /// The force field's energy of `chain` attributed to each residue;
+    /// crowders are not included.
+    pub fn residue_energies(&self, chain: &PeptideChain) -> Option<Vec<f64>> {
+        self.force_field.residue_energies(chain)
+    }
+
+    /// Mutational frustration of `chain`'s contacts under the force field.
+    pub fn frustration(
+        &self,
+        chain: &PeptideChain,
+        options: &FrustrationOptions,
+    ) -> Option<FrustrationReport> {
+        analyze_frustration(self.force_field.as_ref(), chain, options)
+    }
+
+    fn forces(&self, chain: &PeptideChain) -> Vec<Vec3> {
+        let mut forces = self.force_field.compute_forces(chain);
+        if let Some(crowders) = &self.crowders {
+            let positions = self.extract_positions(chain);
+            for (force, crowding) in forces.iter_mut().zip(crowders.forces(&positions)) {
+                *force += crowding;
+            }
+        }
+        forces
+    }
+
+    fn potential_energy(&self, chain: &PeptideChain) -> f64 {
+        let crowding = self
+            .crowders
+            .as_ref()
+            .map_or(0.0, |crowders| crowders.energy(&self.extract_positions(chain)));
+        self.force_field.compute_energy(chain) + crowding
+    }
+
+    fn get_simulation_parameters(&self) -> (f64, usize) {
         match self.physics_level {
-            PhysicsLevel::Toy => (0.01, 100, 300.0),      // 1 ps total
-            PhysicsLevel::Coarse => (0.005, 200, 300.0),  // 1 ps total
-            PhysicsLevel::GB => (0.002, 500, 300.0),      // 1 ps total
-            PhysicsLevel::Full => (0.001, 1000, 300.0),   // 1 ps total
+            PhysicsLevel::Toy => (0.01, 100),      // 1 ps total
+            PhysicsLevel::Coarse => (0.005, 200),  // 1 ps total
+            PhysicsLevel::Gb => (0.002, 500),      // 1 ps total
+            PhysicsLevel::Full => (0.001, 1000),   // 1 ps total
         }
     }
     
@@ -173,15 +409,17 @@ impl NativePhysicsBridge {
             })
             .collect();
         
-        Ok(PeptideChain::new(residues))
+        let mut chain = PeptideChain::new(residues);
+        chain.set_chain_breaks(&request.chain_breaks);
+        Ok(chain)
     }
     
     fn extract_positions(&self, chain: &PeptideChain) -> Vec<[f64; 3]> {
-        chain.residues().iter().map(|r| r.position()).collect()
+        chain.positions().to_vec()
     }
     
     fn extract_angles(&self, chain: &PeptideChain) -> Vec<(f64, f64)> {
-        chain.residues().iter().map(|r| (r.phi, r.psi)).collect()
+        chain.phi().iter().copied().zip(chain.psi().iter().copied()).collect()
     }
     
     fn compute_rmsd(&self, chain: &PeptideChain, reference_positions: &[[f64; 3]]) -> f64 {
@@ -234,6 +472,42 @@ impl NativePhysicsBridge {
     }
 }
 
+/// Forces with the frozen residues' entries zeroed.
+fn held_forces(mut forces: Vec<Vec3>, frozen: &[bool]) -> Vec<Vec3> {
+    for (force, _) in forces.iter_mut().zip(frozen).filter(|(_, held)| **held) {
+        *force = Vec3::zeros();
+    }
+    forces
+}
+
+/// Puts frozen residues back at `pinned`, for registered integrators that
+/// do not honour [`Integrator::freeze`].
+fn hold_positions(chain: &mut PeptideChain, frozen: &[bool], pinned: &[[f64; 3]]) {
+    let positions = chain.positions_mut();
+    for ((position, pinned), _) in positions
+        .iter_mut()
+        .zip(pinned)
+        .zip(frozen)
+        .filter(|(_, held)| **held)
+    {
+        *position = *pinned;
+    }
+}
+
+/// One trajectory frame: the step, its simulated time (ps) and Cα positions.
+fn frame(step: usize, time_ps: f64, chain: &PeptideChain) -> serde_json::Value {
+    serde_json::json!({ "step": step, "time_ps": time_ps, "positions": chain.positions() })
+}
+
+fn write_trajectory(path: &Path, trajectory: &serde_json::Value) -> Result<(), String> {
+    let failed = |err: std::io::Error| format!("cannot write trajectory {}: {err}", path.display());
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(failed)?;
+    }
+    let file = std::fs::File::create(path).map_err(failed)?;
+    serde_json::to_writer(std::io::BufWriter::new(file), trajectory)
+        .map_err(|err| format!("cannot write trajectory {}: {err}", path.display()))
+}
 
 #[cfg(test)]
 mod tests {
@@ -258,10 +532,17 @@ mod tests {
                 [11.4, 0.0, 0.0],
             ],
             residue_types: vec!["ALA".to_string(), "GLY".to_string(), "SER".to_string(), "VAL".to_string()],
-            rotation_commands: vec![(1, 0.1), (2, -0.1)],
+            rotation_commands: vec![
+                TorsionRotation { residue: 1, dihedral: Dihedral::Psi, angle: 30.0 },
+                TorsionRotation { residue: 2, dihedral: Dihedral::Phi, angle: -10.0 },
+            ],
             physics_level: PhysicsLevel::Toy,
             temperature: 300.0,
             simulation_time: 1.0,
+            timestep: None,
+            trajectory_path: None,
+            frozen: Vec::new(),
+            chain_breaks: Vec::new(),
         };
         
         let result = bridge.run_physics_simulation(&request);
@@ -270,10 +551,62 @@ mod tests {
         let outcome = result.unwrap();
         assert_eq!(outcome.final_positions.len(), 4);
         assert_eq!(outcome.final_angles.len(), 4);
+        assert_eq!(outcome.final_angles[1].1, 30.0);
+        assert_eq!(outcome.final_angles[2].0, -10.0);
+        // The phi turn re-places residue 2 before any MD
+        assert!((outcome.rotated_energy - outcome.initial_energy).abs() > 1e-9);
         assert!(outcome.energy.is_finite());
+        // Toy runs Verlet, whose velocities come from the position history
+        assert!(outcome.kinetic_energy > 0.0 && outcome.temperature > 0.0);
+        assert_eq!(outcome.energy, outcome.potential_energy + outcome.kinetic_energy);
         assert!(outcome.rmsd >= 0.0);
         assert!(outcome.radius_of_gyration >= 0.0);
         assert!(outcome.simulation_time > 0.0);
+        assert!((outcome.simulated_time_ps - 1.0).abs() < 1e-9);
+
+        let burst = PhysicsRequest { simulation_time: 0.25, ..request };
+        let outcome = bridge.run_physics_simulation(&burst).unwrap();
+        assert!((outcome.simulated_time_ps - 0.25).abs() < 1e-9);
+
+        let fine = PhysicsRequest { timestep: Some(0.002), ..burst.clone() };
+        let outcome = bridge.run_physics_simulation(&fine).unwrap();
+        assert!((outcome.simulated_time_ps - 0.25).abs() < 1e-9);
+        assert_eq!(outcome.trajectory_data.unwrap()["num_steps"], 125);
+
+        let unstable = PhysicsRequest { timestep: Some(0.05), ..burst.clone() };
+        let err = bridge.run_physics_simulation(&unstable).unwrap_err();
+        assert!(err.contains("stability limit of 0.02 ps"), "{err}");
+
+        let dir = std::env::temp_dir().join(format!("logline_native_traj_{}", std::process::id()));
+        let path = dir.join("spans").join("00001-residue-1.json");
+        let recorded = PhysicsRequest { trajectory_path: Some(path.clone()), ..burst.clone() };
+        let outcome = bridge.run_physics_simulation(&recorded).unwrap();
+        assert_eq!(outcome.trajectory_path, Some(path.display().to_string()));
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let frames = written["frames"].as_array().unwrap();
+        // The starting frame plus one every 10 of the 25 steps and the last
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[3]["step"], 25);
+        assert_eq!(frames[3]["positions"].as_array().unwrap().len(), 4);
+        assert_eq!(written["residues"][1], "GLY");
+        // A trajectory that cannot be written costs the frames, not the span
+        let blocked = PhysicsRequest {
+            trajectory_path: Some(path.join("nested.json")),
+            ..burst.clone()
+        };
+        let outcome = bridge.run_physics_simulation(&blocked).unwrap();
+        assert_eq!(outcome.trajectory_path, None);
+        std::fs::remove_dir_all(dir).unwrap();
+
+        // Frozen residues end where they started, under Verlet and Langevin
+        let held = PhysicsRequest { frozen: vec![0, 1], ..burst };
+        for level in [PhysicsLevel::Toy, PhysicsLevel::Coarse] {
+            let mut bridge = NativePhysicsBridge::new(level).with_seed(5);
+            let outcome = bridge.run_physics_simulation(&held).unwrap();
+            assert_eq!(&outcome.final_positions[..2], &held.initial_positions[..2]);
+            assert_ne!(outcome.final_positions[3], held.initial_positions[3]);
+        }
     }
 
     #[test]
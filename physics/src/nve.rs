@@ -0,0 +1,317 @@
+//! Microcanonical (NVE) energy-conservation check for integrators: a helix
+//! given seeded thermal velocities is integrated with no thermostat, and the
+//! drift of potential + kinetic energy is reported per nanosecond.
+//! A correct symplectic integrator keeps the total bounded; systematic drift
+//! points at an integrator bug.
+
+use crate::force_fields::{ForceField, Vec3};
+use crate::integrators::{Integrator, LangevinIntegrator, VerletIntegrator};
+use folding_molecule::{PeptideChain, Residue, ResidueId};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution, Normal};
+
+/// Mass the integrators assign to every CA bead (amu).
+const BEAD_MASS: f64 = 12.0;
+const BOLTZMANN: f64 = 0.001987; // kcal/mol/K
+const VELOCITY_SEED: u64 = 7;
+const BOND_LENGTH: f64 = 3.8;
+const BOND_STRENGTH: f64 = 100.0;
+const LJ_EPSILON: f64 = 0.2;
+const LJ_SIGMA: f64 = 3.5;
+
+/// Length and step of an NVE run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NveConfig {
+    pub residues: usize,
+    pub steps: usize,
+    pub timestep_ps: f64,
+    /// Temperature the initial velocities are drawn at (K).
+    pub temperature: f64,
+}
+
+impl Default for NveConfig {
+    fn default() -> Self {
+        Self {
+            residues: 20,
+            steps: 10_000,
+            timestep_ps: 0.001,
+            temperature: 300.0,
+        }
+    }
+}
+
+/// Energy bookkeeping of one NVE run (kcal/mol, ps).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NveReport {
+    pub integrator: String,
+    pub steps: usize,
+    pub simulated_ps: f64,
+    pub initial_energy: f64,
+    pub final_energy: f64,
+    /// Largest |E(t) − E(0)| seen during the run.
+    pub max_deviation: f64,
+    /// Least-squares slope of total energy against time, in kcal/mol/ns.
+    pub drift_per_ns: f64,
+}
+
+/// α-helix CA trace (2.3 Å radius, 100° per residue) with the rise chosen
+/// for 3.8 Å bonds, so constrained integrators start on their constraints.
+pub fn standard_chain(residues: usize) -> PeptideChain {
+    let twist = 100f64.to_radians();
+    let chord = 2.0 * 2.3 * (twist / 2.0).sin();
+    let rise = (BOND_LENGTH * BOND_LENGTH - chord * chord).sqrt();
+    let residues = (0..residues)
+        .map(|i| {
+            let angle = twist * i as f64;
+            let position = [2.3 * angle.cos(), 2.3 * angle.sin(), rise * i as f64];
+            Residue::new(ResidueId(i), "ALA", position)
+        })
+        .collect();
+    PeptideChain::new(residues)
+}
+
+/// Seeded Maxwell–Boltzmann velocities with the centre-of-mass drift removed.
+pub fn thermal_velocities(count: usize, temperature: f64) -> Vec<Vec3> {
+    let mut rng = StdRng::seed_from_u64(VELOCITY_SEED);
+    let sigma = (BOLTZMANN * temperature.max(0.0) / BEAD_MASS).sqrt();
+    let normal = Normal::new(0.0, 1.0).unwrap();
+    let mut velocities: Vec<Vec3> = (0..count)
+        .map(|_| {
+            let mut draw = || normal.sample(&mut rng) * sigma;
+            Vec3::new(draw(), draw(), draw())
+        })
+        .collect();
+    if count > 0 {
+        let drift = velocities.iter().sum::<Vec3>() / count as f64;
+        velocities
+            .iter_mut()
+            .for_each(|velocity| *velocity -= drift);
+    }
+    velocities
+}
+
+/// Harmonic CA–CA bonds plus Lennard-Jones between non-bonded beads, with
+/// forces that are the exact negative gradient of the energy. The bench uses
+/// it instead of a production force field so drift measures the integrator
+/// alone.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BeadSpringForceField;
+
+impl BeadSpringForceField {
+    fn pair_terms(&self, chain: &PeptideChain, mut visit: impl FnMut(usize, usize, f64, f64)) {
+        let positions = chain.positions();
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                let r = distance(positions[i], positions[j]);
+                if r < 1e-10 {
+                    continue;
+                }
+                let (energy, d_energy) = if j == i + 1 {
+                    let stretch = r - BOND_LENGTH;
+                    (
+                        0.5 * BOND_STRENGTH * stretch * stretch,
+                        BOND_STRENGTH * stretch,
+                    )
+                } else {
+                    let sr6 = (LJ_SIGMA / r).powi(6);
+                    (
+                        4.0 * LJ_EPSILON * (sr6 * sr6 - sr6),
+                        -24.0 * LJ_EPSILON * (2.0 * sr6 * sr6 - sr6) / r,
+                    )
+                };
+                visit(i, j, energy, d_energy);
+            }
+        }
+    }
+
+    fn sum_pairs(&self, chain: &PeptideChain, bonded: bool) -> f64 {
+        let mut total = 0.0;
+        self.pair_terms(chain, |i, j, energy, _| {
+            if (j == i + 1) == bonded {
+                total += energy;
+            }
+        });
+        total
+    }
+}
+
+impl ForceField for BeadSpringForceField {
+    fn compute_energy(&self, chain: &PeptideChain) -> f64 {
+        self.bond_energy(chain) + self.nonbonded_energy(chain)
+    }
+
+    fn compute_forces(&self, chain: &PeptideChain) -> Vec<Vec3> {
+        let positions = chain.positions();
+        let mut forces = vec![Vec3::zeros(); positions.len()];
+        self.pair_terms(chain, |i, j, _, d_energy| {
+            let (a, b) = (positions[i], positions[j]);
+            let direction = Vec3::new(b[0] - a[0], b[1] - a[1], b[2] - a[2]).normalize();
+            forces[i] += direction * d_energy;
+            forces[j] -= direction * d_energy;
+        });
+        forces
+    }
+
+    fn bond_energy(&self, chain: &PeptideChain) -> f64 {
+        self.sum_pairs(chain, true)
+    }
+
+    fn angle_energy(&self, _chain: &PeptideChain) -> f64 {
+        0.0
+    }
+
+    fn dihedral_energy(&self, _chain: &PeptideChain) -> f64 {
+        0.0
+    }
+
+    fn nonbonded_energy(&self, chain: &PeptideChain) -> f64 {
+        self.sum_pairs(chain, false)
+    }
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2) + (b[2] - a[2]).powi(2)).sqrt()
+}
+
+/// Integrates `chain` under `force_field` with `integrator`, which must
+/// already carry `velocities`, and tracks the total energy. Later velocities
+/// come from central differences of the positions, so the check does not rely
+/// on the integrator's own kinetic-energy bookkeeping.
+pub fn run_nve(
+    name: &str,
+    force_field: &dyn ForceField,
+    integrator: &mut dyn Integrator,
+    mut chain: PeptideChain,
+    velocities: &[Vec3],
+    config: &NveConfig,
+) -> NveReport {
+    let dt = config.timestep_ps;
+    let mut previous = shifted(chain.positions(), velocities, -dt);
+    let mut previous_potential = force_field.compute_energy(&chain);
+    let mut forces = force_field.compute_forces(&chain);
+    let mut totals = Vec::with_capacity(config.steps);
+    for _ in 0..config.steps {
+        let current = chain.positions().to_vec();
+        let current_potential = previous_potential;
+        integrator.step(&mut chain, &forces, dt);
+        forces = force_field.compute_forces(&chain);
+        integrator.finish_step(&chain, &forces, dt);
+        let next = chain.positions();
+        let kinetic: f64 = previous
+            .iter()
+            .zip(next)
+            .map(|(before, after)| {
+                let speed_squared: f64 = (0..3)
+                    .map(|axis| ((after[axis] - before[axis]) / (2.0 * dt)).powi(2))
+                    .sum();
+                0.5 * BEAD_MASS * speed_squared
+            })
+            .sum();
+        totals.push(current_potential + kinetic);
+        previous = current;
+        previous_potential = force_field.compute_energy(&chain);
+    }
+
+    let initial_energy = totals.first().copied().unwrap_or(previous_potential);
+    let final_energy = totals.last().copied().unwrap_or(initial_energy);
+    let max_deviation = totals
+        .iter()
+        .map(|total| (total - initial_energy).abs())
+        .fold(0.0, f64::max);
+    NveReport {
+        integrator: name.to_string(),
+        steps: config.steps,
+        simulated_ps: dt * config.steps as f64,
+        initial_energy,
+        final_energy,
+        max_deviation,
+        drift_per_ns: slope(&totals, dt) * 1000.0,
+    }
+}
+
+/// Runs the standard chain through Verlet and through the Langevin
+/// integrator with friction and noise switched off.
+pub fn run_standard_nve(config: &NveConfig) -> Vec<NveReport> {
+    let force_field = BeadSpringForceField;
+    let chain = standard_chain(config.residues);
+    let velocities = thermal_velocities(chain.len(), config.temperature);
+    let dt = config.timestep_ps;
+
+    let mut verlet = VerletIntegrator::new(chain.len());
+    let mut earlier = chain.clone();
+    earlier
+        .positions_mut()
+        .copy_from_slice(&shifted(chain.positions(), &velocities, -dt));
+    verlet.initialize(&earlier);
+    let mut langevin = LangevinIntegrator::new(chain.len(), 0.0, 0.0);
+    langevin.set_velocities(&velocities);
+
+    vec![
+        run_nve(
+            "verlet",
+            &force_field,
+            &mut verlet,
+            chain.clone(),
+            &velocities,
+            config,
+        ),
+        run_nve(
+            "langevin",
+            &force_field,
+            &mut langevin,
+            chain,
+            &velocities,
+            config,
+        ),
+    ]
+}
+
+fn shifted(positions: &[[f64; 3]], velocities: &[Vec3], dt: f64) -> Vec<[f64; 3]> {
+    positions
+        .iter()
+        .zip(velocities)
+        .map(|(p, v)| [p[0] + v.x * dt, p[1] + v.y * dt, p[2] + v.z * dt])
+        .collect()
+}
+
+fn slope(values: &[f64], dt: f64) -> f64 {
+    let n = values.len() as f64;
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean_t = dt * (n - 1.0) / 2.0;
+    let mean_e = values.iter().sum::<f64>() / n;
+    let (covariance, variance) =
+        values
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(covariance, variance), (i, energy)| {
+                let t = dt * i as f64 - mean_t;
+                (covariance + t * (energy - mean_e), variance + t * t)
+            });
+    covariance / variance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integrators_conserve_energy_on_the_standard_chain() {
+        let config = NveConfig {
+            steps: 2_000,
+            ..NveConfig::default()
+        };
+        let reports = run_standard_nve(&config);
+        let verlet = &reports[0];
+        assert_eq!(verlet.integrator, "verlet");
+        assert!((verlet.simulated_ps - 2.0).abs() < 1e-12);
+        assert!(verlet.drift_per_ns.abs() < 0.1, "{verlet:?}");
+        assert!(verlet.max_deviation < 1e-3, "{verlet:?}");
+        // Langevin with friction off re-evaluates forces between half-kicks
+        // and keeps its constraints on the velocities, so it barely drifts
+        let langevin = &reports[1];
+        assert!(langevin.drift_per_ns.abs() < 5.0, "{langevin:?}");
+    }
+}
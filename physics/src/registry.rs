@@ -0,0 +1,251 @@
+//! Force fields and integrators selectable by name.
+//!
+//! The built-ins are always registered; other crates add their own with
+//! [`register_force_field`] / [`register_integrator`] before the run starts,
+//! or ship them as a shared library loaded with `load_plugin` (feature
+//! `plugins`). Names are case-insensitive and cannot be registered twice.
+
+use crate::environment::{Environment, BEAD_HYDRODYNAMIC_RADIUS};
+use crate::force_fields::{Amber99SBForceField, CoarseGrainedForceField, ForceField};
+use crate::integrators::{BrownianIntegrator, Integrator, LangevinIntegrator, VerletIntegrator};
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Builds a force field for the run's solvent conditions.
+pub type ForceFieldFactory = Arc<dyn Fn(&Environment) -> Box<dyn ForceField> + Send + Sync>;
+/// Builds an integrator for the run's solvent conditions; particle storage is
+/// sized by the integrator on its first step.
+pub type IntegratorFactory = Arc<dyn Fn(&Environment) -> Box<dyn Integrator> + Send + Sync>;
+
+/// Symbol a plugin library exports, with the signature of [`PluginEntry`].
+pub const PLUGIN_SYMBOL: &str = "logline_register_plugin";
+
+/// `#[no_mangle] pub fn logline_register_plugin(registrar: &mut PluginRegistrar)`.
+/// Plugins must be built with the same compiler and `folding-physics` version.
+pub type PluginEntry = fn(&mut PluginRegistrar);
+
+#[derive(Default)]
+struct Registry {
+    force_fields: BTreeMap<String, ForceFieldFactory>,
+    integrators: BTreeMap<String, IntegratorFactory>,
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Registry::builtin()))
+}
+
+impl Registry {
+    fn builtin() -> Self {
+        let mut registry = Registry::default();
+        registry.force_fields.insert(
+            "coarse-grained".into(),
+            Arc::new(|_: &Environment| Box::new(CoarseGrainedForceField::new()) as Box<dyn ForceField>),
+        );
+        registry.force_fields.insert(
+            "amber99sb".into(),
+            Arc::new(|environment: &Environment| {
+                Box::new(
+                    Amber99SBForceField::new()
+                        .with_solvent_dielectric(environment.dielectric)
+                        .with_ionic_strength(environment.ionic_strength)
+                        .with_dielectric_model(environment.dielectric_model),
+                ) as Box<dyn ForceField>
+            }),
+        );
+        registry.integrators.insert(
+            "verlet".into(),
+            Arc::new(|_: &Environment| Box::new(VerletIntegrator::new(0)) as Box<dyn Integrator>),
+        );
+        registry.integrators.insert(
+            "langevin".into(),
+            Arc::new(|environment: &Environment| {
+                Box::new(LangevinIntegrator::new(0, environment.default_temperature, stokes_friction(environment)))
+                    as Box<dyn Integrator>
+            }),
+        );
+        registry.integrators.insert(
+            "brownian".into(),
+            Arc::new(|environment: &Environment| {
+                Box::new(BrownianIntegrator::new(0, environment.default_temperature, stokes_friction(environment)))
+                    as Box<dyn Integrator>
+            }),
+        );
+        registry
+    }
+}
+
+/// Friction of a bead in the solvent at its default temperature.
+fn stokes_friction(environment: &Environment) -> f64 {
+    environment.stokes_friction(environment.default_temperature, BEAD_HYDRODYNAMIC_RADIUS)
+}
+
+fn insert<T: Clone>(map: &mut BTreeMap<String, T>, kind: &str, name: &str, factory: T) -> Result<(), String> {
+    let key = name.trim().to_ascii_lowercase();
+    if key.is_empty() {
+        return Err(format!("{kind} name cannot be empty"));
+    }
+    if map.contains_key(&key) {
+        return Err(format!("{kind} '{key}' is already registered"));
+    }
+    map.insert(key, factory);
+    Ok(())
+}
+
+fn lookup<T: Clone>(map: &BTreeMap<String, T>, kind: &str, name: &str) -> Result<T, String> {
+    map.get(&name.trim().to_ascii_lowercase()).cloned().ok_or_else(|| {
+        let known: Vec<&str> = map.keys().map(String::as_str).collect();
+        format!("unknown {kind} '{name}' (known: {})", known.join(", "))
+    })
+}
+
+pub fn register_force_field<F>(name: &str, factory: F) -> Result<(), String>
+where
+    F: Fn(&Environment) -> Box<dyn ForceField> + Send + Sync + 'static,
+{
+    let mut registry = registry().write().map_err(|_| "physics registry poisoned".to_string())?;
+    insert(&mut registry.force_fields, "force field", name, Arc::new(factory) as ForceFieldFactory)
+}
+
+pub fn register_integrator<F>(name: &str, factory: F) -> Result<(), String>
+where
+    F: Fn(&Environment) -> Box<dyn Integrator> + Send + Sync + 'static,
+{
+    let mut registry = registry().write().map_err(|_| "physics registry poisoned".to_string())?;
+    insert(&mut registry.integrators, "integrator", name, Arc::new(factory) as IntegratorFactory)
+}
+
+pub fn create_force_field(name: &str, environment: &Environment) -> Result<Box<dyn ForceField>, String> {
+    let factory = {
+        let registry = registry().read().map_err(|_| "physics registry poisoned".to_string())?;
+        lookup(&registry.force_fields, "force field", name)?
+    };
+    Ok(factory(environment))
+}
+
+pub fn create_integrator(name: &str, environment: &Environment) -> Result<Box<dyn Integrator>, String> {
+    let factory = {
+        let registry = registry().read().map_err(|_| "physics registry poisoned".to_string())?;
+        lookup(&registry.integrators, "integrator", name)?
+    };
+    Ok(factory(environment))
+}
+
+/// Fails with the registered names when `name` is unknown.
+pub fn check_force_field(name: &str) -> Result<(), String> {
+    let registry = registry().read().map_err(|_| "physics registry poisoned".to_string())?;
+    lookup(&registry.force_fields, "force field", name).map(|_| ())
+}
+
+/// Fails with the registered names when `name` is unknown.
+pub fn check_integrator(name: &str) -> Result<(), String> {
+    let registry = registry().read().map_err(|_| "physics registry poisoned".to_string())?;
+    lookup(&registry.integrators, "integrator", name).map(|_| ())
+}
+
+/// Fails when `timestep` (ps) is not positive or exceeds the stability limit
+/// of force field `name`.
+pub fn check_timestep(name: &str, timestep: f64) -> Result<(), String> {
+    if !(timestep.is_finite() && timestep > 0.0) {
+        return Err(format!("timestep must be a positive time in ps, got {timestep}"));
+    }
+    let force_field = create_force_field(name, &Environment::default())?;
+    match force_field.max_stable_timestep() {
+        Some(limit) if timestep > limit => Err(format!(
+            "timestep {timestep} ps exceeds the stability limit of force field '{name}' ({limit} ps)"
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub fn force_field_names() -> Vec<String> {
+    registry().read().map(|registry| registry.force_fields.keys().cloned().collect()).unwrap_or_default()
+}
+
+pub fn integrator_names() -> Vec<String> {
+    registry().read().map(|registry| registry.integrators.keys().cloned().collect()).unwrap_or_default()
+}
+
+/// Collects a plugin's factories; they are registered once the plugin's entry
+/// point returns, so a plugin linking its own copy of this crate still lands
+/// in the host's registry.
+#[derive(Default)]
+pub struct PluginRegistrar {
+    force_fields: Vec<(String, ForceFieldFactory)>,
+    integrators: Vec<(String, IntegratorFactory)>,
+}
+
+impl PluginRegistrar {
+    pub fn force_field<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&Environment) -> Box<dyn ForceField> + Send + Sync + 'static,
+    {
+        self.force_fields.push((name.to_string(), Arc::new(factory)));
+    }
+
+    pub fn integrator<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&Environment) -> Box<dyn Integrator> + Send + Sync + 'static,
+    {
+        self.integrators.push((name.to_string(), Arc::new(factory)));
+    }
+
+    /// Registers everything collected; returns the names added.
+    pub fn install(self) -> Result<Vec<String>, String> {
+        let mut registry = registry().write().map_err(|_| "physics registry poisoned".to_string())?;
+        let mut names = Vec::new();
+        for (name, factory) in self.force_fields {
+            insert(&mut registry.force_fields, "force field", &name, factory)?;
+            names.push(name);
+        }
+        for (name, factory) in self.integrators {
+            insert(&mut registry.integrators, "integrator", &name, factory)?;
+            names.push(name);
+        }
+        Ok(names)
+    }
+}
+
+/// Loads a shared library exporting [`PLUGIN_SYMBOL`] and registers what it
+/// provides. The library stays loaded for the life of the process.
+#[cfg(feature = "plugins")]
+pub fn load_plugin(path: &std::path::Path) -> Result<Vec<String>, String> {
+    // SAFETY: running a library's initialisers and its entry point is the
+    // point of loading a plugin; the caller trusts the file it names.
+    let library = unsafe { libloading::Library::new(path) }
+        .map_err(|err| format!("failed to load plugin {}: {err}", path.display()))?;
+    let entry: PluginEntry = unsafe { library.get::<PluginEntry>(PLUGIN_SYMBOL.as_bytes()) }
+        .map(|symbol| *symbol)
+        .map_err(|err| format!("plugin {} has no {PLUGIN_SYMBOL}: {err}", path.display()))?;
+    let mut registrar = PluginRegistrar::default();
+    entry(&mut registrar);
+    // The factories point into the library's code.
+    std::mem::forget(library);
+    registrar.install()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_serves_builtins_and_external_components() {
+        assert!(force_field_names().contains(&"amber99sb".to_string()));
+        assert!(create_integrator("Langevin", &Environment::default()).is_ok());
+        let err = create_force_field("martini", &Environment::default()).err().unwrap();
+        assert!(err.contains("known: amber99sb, coarse-grained"));
+
+        register_force_field("test-cg", |_: &Environment| Box::new(CoarseGrainedForceField::new()) as Box<dyn ForceField>)
+            .unwrap();
+        assert!(check_force_field("TEST-CG").is_ok());
+        assert!(register_force_field("test-cg", |_: &Environment| {
+            Box::new(CoarseGrainedForceField::new()) as Box<dyn ForceField>
+        })
+        .is_err());
+
+        let mut registrar = PluginRegistrar::default();
+        registrar.integrator("test-verlet", |_: &Environment| Box::new(VerletIntegrator::new(0)) as Box<dyn Integrator>);
+        assert_eq!(registrar.install().unwrap(), vec!["test-verlet".to_string()]);
+        assert!(integrator_names().contains(&"test-verlet".to_string()));
+    }
+}
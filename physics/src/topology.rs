@@ -0,0 +1,264 @@
+//! Per-sequence setup shared across runs.
+//!
+//! Residue parameters, bonded lists and nonbonded exclusions depend only on
+//! the sequence and where its chains break, so they are built once per sequence and reused by every
+//! energy evaluation, and by every run of a sweep or batch over the same
+//! protein in this process.
+
+use crate::force_fields::{ResidueParameters, TorsionParameters};
+use folding_molecule::PeptideChain;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+/// Sequences kept before the cache starts over; a sweep revisits a handful.
+const SETUP_CACHE_CAPACITY: usize = 256;
+
+/// Sequence-dependent setup of a bead chain: one bead per residue, bonded
+/// along the backbone of each chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Topology {
+    pub sequence_hash: u64,
+    /// Residue names the setup was built for, in order.
+    pub sequence: Vec<String>,
+    /// First residues of every chain after the first; nothing is bonded across.
+    pub breaks: Vec<usize>,
+    pub residues: Vec<ResidueParameters>,
+    pub torsions: Vec<TorsionParameters>,
+    /// Bonded pairs `(i, i + 1)` within a chain.
+    pub bonds: Vec<(usize, usize)>,
+    /// Angle triples `(i, i + 1, i + 2)` within a chain.
+    pub angles: Vec<(usize, usize, usize)>,
+    /// Pairs, `i < j`, left out of the nonbonded sums (1-2 and 1-3); sorted.
+    pub exclusions: Vec<(usize, usize)>,
+    /// 1-4 pairs, `i < j`, whose nonbonded terms are scaled down; sorted.
+    pub one_four: Vec<(usize, usize)>,
+}
+
+/// How the nonbonded sums treat a pair of beads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonbondedPair {
+    /// Bonded (1-2) or sharing an angle (1-3): covered by the bonded terms.
+    Excluded,
+    /// Ends of a dihedral: counted with the force field's 1-4 scaling.
+    OneFour,
+    Full,
+}
+
+impl Topology {
+    /// Builds the setup of `sequence`, split into chains at `breaks`, without
+    /// touching the cache.
+    pub fn build(sequence: &[String], breaks: &[usize]) -> Self {
+        let count = sequence.len();
+        // Whether residues `first..=last` all lie in one chain
+        let joined = |first: usize, last: usize| !breaks.iter().any(|&b| first < b && b <= last);
+        let bonds: Vec<(usize, usize)> = (1..count)
+            .filter(|&i| joined(i - 1, i))
+            .map(|i| (i - 1, i))
+            .collect();
+        let angles: Vec<(usize, usize, usize)> = (2..count)
+            .filter(|&i| joined(i - 2, i))
+            .map(|i| (i - 2, i - 1, i))
+            .collect();
+        let mut exclusions: Vec<(usize, usize)> = bonds
+            .iter()
+            .copied()
+            .chain(angles.iter().map(|&(i, _, k)| (i, k)))
+            .collect();
+        exclusions.sort_unstable();
+        Self {
+            sequence_hash: setup_hash(sequence, breaks),
+            sequence: sequence.to_vec(),
+            breaks: breaks.to_vec(),
+            residues: sequence.iter().map(|name| ResidueParameters::for_residue(name)).collect(),
+            torsions: sequence.iter().map(|name| TorsionParameters::for_residue(name)).collect(),
+            exclusions,
+            one_four: (3..count).filter(|&i| joined(i - 3, i)).map(|i| (i - 3, i)).collect(),
+            bonds,
+            angles,
+        }
+    }
+
+    /// Setup of `chain`'s sequence, from the cache when an earlier run built it.
+    pub fn for_chain(chain: &PeptideChain) -> Arc<Self> {
+        Self::cached(chain.names(), &chain.chain_breaks())
+    }
+
+    /// Setup of `sequence` split at `breaks`, built on first use and shared
+    /// afterwards.
+    pub fn cached(sequence: &[String], breaks: &[usize]) -> Arc<Self> {
+        let hash = setup_hash(sequence, breaks);
+        let mut cache = setup_cache().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let hit = cache
+            .entries
+            .get(&hash)
+            .filter(|topology| topology.sequence == sequence && topology.breaks == breaks)
+            .cloned();
+        if let Some(topology) = hit {
+            cache.stats.hits += 1;
+            return topology;
+        }
+        cache.stats.misses += 1;
+        if cache.entries.len() >= SETUP_CACHE_CAPACITY {
+            cache.entries.clear();
+        }
+        let topology = Arc::new(Self::build(sequence, breaks));
+        cache.entries.insert(hash, Arc::clone(&topology));
+        topology
+    }
+
+    /// Whether this is the setup of `chain`'s sequence and chain breaks.
+    pub fn matches(&self, chain: &PeptideChain) -> bool {
+        self.sequence == chain.names() && self.breaks == chain.chain_breaks()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sequence.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sequence.is_empty()
+    }
+
+    /// Whether the nonbonded sums skip the pair `i`, `j`.
+    pub fn is_excluded(&self, i: usize, j: usize) -> bool {
+        self.pair(i, j) == NonbondedPair::Excluded
+    }
+
+    pub fn pair(&self, i: usize, j: usize) -> NonbondedPair {
+        let pair = if i < j { (i, j) } else { (j, i) };
+        if i == j || self.exclusions.binary_search(&pair).is_ok() {
+            NonbondedPair::Excluded
+        } else if self.one_four.binary_search(&pair).is_ok() {
+            NonbondedPair::OneFour
+        } else {
+            NonbondedPair::Full
+        }
+    }
+}
+
+/// Setup a force field resolved for the last chain it evaluated, so the
+/// energy and force evaluations of one chain skip the shared cache and its
+/// lock.
+#[derive(Debug, Default)]
+pub struct TopologySlot(RwLock<Option<Arc<Topology>>>);
+
+impl TopologySlot {
+    /// Setup of `chain`; goes through [`Topology::for_chain`] only when the
+    /// chain's sequence or breaks differ from the last call's.
+    pub fn get(&self, chain: &PeptideChain) -> Arc<Topology> {
+        let last = self.0.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+        if let Some(topology) = last.filter(|topology| topology.matches(chain)) {
+            return topology;
+        }
+        let topology = Topology::for_chain(chain);
+        *self.0.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::clone(&topology));
+        topology
+    }
+}
+
+impl Clone for TopologySlot {
+    fn clone(&self) -> Self {
+        Self(RwLock::new(self.0.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()))
+    }
+}
+
+/// Lookups served by the setup cache since the process started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SetupCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+#[derive(Default)]
+struct SetupCache {
+    entries: HashMap<u64, Arc<Topology>>,
+    stats: SetupCacheStats,
+}
+
+fn setup_cache() -> &'static Mutex<SetupCache> {
+    static CACHE: OnceLock<Mutex<SetupCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(SetupCache::default()))
+}
+
+/// Hash of the residue names that keys the setup cache.
+pub fn sequence_hash(sequence: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    sequence.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Cache key of a setup: the sequence hash, mixed with the chain breaks of a
+/// complex.
+fn setup_hash(sequence: &[String], breaks: &[usize]) -> u64 {
+    if breaks.is_empty() {
+        return sequence_hash(sequence);
+    }
+    let mut hasher = DefaultHasher::new();
+    sequence.hash(&mut hasher);
+    breaks.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub fn setup_cache_stats() -> SetupCacheStats {
+    let cache = setup_cache().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    SetupCacheStats {
+        entries: cache.entries.len(),
+        ..cache.stats
+    }
+}
+
+/// Drops every cached setup, releasing the memory of finished sweeps.
+pub fn clear_setup_cache() {
+    let mut cache = setup_cache().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    cache.entries.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_over_the_same_sequence_share_one_setup() {
+        let sequence: Vec<String> = ["ALA", "GLY", "PRO", "LYS"].map(String::from).to_vec();
+        let first = Topology::cached(&sequence, &[]);
+        let second = Topology::cached(&sequence, &[]);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(setup_cache_stats().hits >= 1);
+        assert_eq!(*first, Topology::build(&sequence, &[]));
+
+        assert_eq!(first.bonds, vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(first.angles, vec![(0, 1, 2), (1, 2, 3)]);
+        assert!(first.is_excluded(2, 1) && first.is_excluded(0, 2));
+        assert_eq!(first.pair(3, 0), NonbondedPair::OneFour);
+        assert_eq!(first.torsions[2], TorsionParameters::for_residue("PRO"));
+
+        let mutant: Vec<String> = ["ALA", "GLY", "PRO", "GLU"].map(String::from).to_vec();
+        assert_ne!(sequence_hash(&sequence), sequence_hash(&mutant));
+        assert!(!Arc::ptr_eq(&first, &Topology::cached(&mutant, &[])));
+
+        // A dimer of AG and PK: nothing bonded, angled or 1-4 across the break
+        let dimer = Topology::cached(&sequence, &[2]);
+        assert!(!Arc::ptr_eq(&first, &dimer));
+        assert_eq!(dimer.bonds, vec![(0, 1), (2, 3)]);
+        assert!(dimer.angles.is_empty());
+        assert_eq!(dimer.pair(1, 2), NonbondedPair::Full);
+        assert_eq!(dimer.pair(0, 3), NonbondedPair::Full);
+    }
+
+    #[test]
+    fn a_slot_resolves_the_cache_once_per_chain() {
+        let slot = TopologySlot::default();
+        let chain = PeptideChain::from_sequence("AGPK");
+        let first = slot.get(&chain);
+        assert!(first.matches(&chain));
+        assert!(Arc::ptr_eq(&first, &slot.get(&chain)));
+        assert!(Arc::ptr_eq(&first, &slot.clone().get(&chain)));
+
+        let mutant = PeptideChain::from_sequence("AGPE");
+        assert!(slot.get(&mutant).matches(&mutant));
+        assert!(!first.matches(&mutant));
+    }
+}
@@ -0,0 +1,874 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+use folding_core::EnergySamples;
+use serde::{Deserialize, Serialize};
+
+/// Run-level metadata from the first line of a span log.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub run_id: String,
+    /// `--run-name`, when the run was given one.
+    pub run_name: Option<String>,
+    pub timestamp: String,
+    pub contract_name: Option<String>,
+    pub environment: String,
+    pub temperature: f64,
+    pub time_step_ms: u64,
+    pub accepted_spans: usize,
+    pub rejected_spans: usize,
+    pub acceptance_rate: f64,
+    pub final_potential_energy: f64,
+    pub final_gibbs_energy: f64,
+    pub informational_efficiency: f64,
+    pub total_work: f64,
+    pub tm_score: Option<f64>,
+    pub gdt_ts: Option<f64>,
+    /// Entropy estimates; absent in logs written before they were recorded.
+    pub heuristic_entropy: Option<f64>,
+    pub quasi_harmonic_entropy: Option<f64>,
+    /// Energy-gap foldability index and the gap itself (kcal/mol).
+    pub foldability: Option<f64>,
+    pub energy_gap: Option<f64>,
+    /// Energy statistics behind the index; runs of one sequence pool them
+    /// with [`EnergySamples::merge`].
+    pub energy_samples: Option<EnergySamples>,
+    /// Contract, simulated and wall-clock time of the run; absent in logs
+    /// written before the axes were recorded separately.
+    pub span_time_ms: Option<f64>,
+    pub simulated_time_ps: Option<f64>,
+    pub wall_time_ms: Option<f64>,
+    pub remote_uri: Option<String>,
+    /// How `--level auto` picked the starting physics level.
+    pub level_choice: Option<String>,
+    /// Provenance fields; absent in logs written before they were recorded.
+    pub crate_version: Option<String>,
+    pub git_commit: Option<String>,
+    pub hostname: Option<String>,
+    pub os: Option<String>,
+    pub physics_backend: Option<String>,
+    pub features: Vec<String>,
+}
+
+/// One span row. `time_ms` (contract time) and `simulated_time_ps` advance
+/// with the cumulative columns only on applied spans, so ghost rows carry the
+/// values of the last applied span. `simulated_ps` is 0 in older logs, which
+/// also lack the Metropolis `temperature` a span was judged at.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpanFrame {
+    pub index: usize,
+    pub id: String,
+    pub ghost: bool,
+    pub delta_theta: f64,
+    pub delta_entropy: f64,
+    pub delta_information: f64,
+    pub delta_energy: f64,
+    pub gibbs_energy: f64,
+    pub duration_ms: u64,
+    pub time_ms: u64,
+    pub simulated_ps: f64,
+    pub simulated_time_ps: f64,
+    pub cumulative_entropy: f64,
+    pub cumulative_energy: f64,
+    pub temperature: Option<f64>,
+    /// Physics backend that serviced the span; `None` for toy spans.
+    pub backend: Option<String>,
+    /// `cv.<name>` fields: collective variables the run tracked, evaluated
+    /// after the span. Empty on ghost rows.
+    pub collective_variables: BTreeMap<String, f64>,
+    /// Contract tags of the span's instruction.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Phase path the span ran in, e.g. `anneal/cooling`.
+    #[serde(default)]
+    pub phase: Option<String>,
+}
+
+/// Totals of one contract phase over its applied spans, nested phases
+/// included.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseRow {
+    pub path: String,
+    pub depth: usize,
+    pub spans: usize,
+    pub delta_entropy: f64,
+    pub delta_energy: f64,
+    pub duration_ms: u64,
+    pub simulated_ps: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeometryCheckRow {
+    pub check: String,
+    pub status: String,
+    pub checked: usize,
+    pub failures: usize,
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeometryIssueRow {
+    pub check: String,
+    pub residues: Vec<usize>,
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// One `violation|` row. Logs written before violation codes existed only
+/// carry the `Debug` detail, from which the kind is recovered.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ViolationRow {
+    pub step: Option<usize>,
+    pub code: Option<String>,
+    /// `warning`, `error` or `fatal`.
+    pub severity: Option<String>,
+    pub kind: String,
+    /// Variant fields such as `residue` or `delta_energy`.
+    pub fields: BTreeMap<String, String>,
+    pub detail: String,
+}
+
+/// Engine RNG state recorded at a step (`rng|` lines); passing `state` to
+/// `--rng-state` resumes the run's stochastic stream from there.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngStateRow {
+    pub step: usize,
+    pub state: u64,
+    /// Native physics velocities at the step, as logged; empty when no
+    /// physics bridge had run.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub velocities: String,
+}
+
+/// Rolling Metropolis acceptance after the decision on move `step`
+/// (`acceptance|` lines): `accepted` of the last `total` decisions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AcceptanceRow {
+    pub step: usize,
+    pub accepted: usize,
+    pub total: usize,
+    pub rate: f64,
+    /// Decisions in a full window; 0 in logs written before it was recorded.
+    #[serde(default)]
+    pub window: usize,
+}
+
+/// Full windows at or below this acceptance count as collapsed: moves too
+/// large or the temperature too low for the sampler to progress.
+pub const ACCEPTANCE_COLLAPSE: f64 = 0.05;
+/// Full windows at or above this acceptance count as saturated: moves too
+/// small or the temperature too high for acceptance to discriminate.
+pub const ACCEPTANCE_SATURATION: f64 = 0.95;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AcceptanceRegime {
+    Collapsed,
+    Saturated,
+}
+
+impl AcceptanceRegime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AcceptanceRegime::Collapsed => "collapsed",
+            AcceptanceRegime::Saturated => "saturated",
+        }
+    }
+}
+
+/// Consecutive full acceptance windows in one regime, from the window ending
+/// at `first_step` to the one ending at `last_step`; `rate` is the most
+/// extreme rate among them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AcceptanceAlert {
+    pub regime: AcceptanceRegime,
+    pub first_step: usize,
+    pub last_step: usize,
+    pub rate: f64,
+}
+
+/// A physics backend that failed on a span, and what serviced it instead
+/// (`native`, `toy`, or `none` when the span was rejected).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendFailureRow {
+    pub step: usize,
+    pub span: String,
+    pub backend: String,
+    pub fallback: String,
+    pub reason: String,
+    /// Tail of the bridge's standard error, one line per line.
+    pub stderr: String,
+}
+
+/// An energy bookkeeping check that failed (`--thermo-check`); `span` is
+/// empty for the end-of-run `energy_balance` check.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThermoDiscrepancyRow {
+    pub step: usize,
+    pub span: String,
+    pub check: String,
+    pub expected: f64,
+    pub actual: f64,
+}
+
+/// Number of violations sharing a kind (the variant name in the log detail).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViolationCount {
+    pub kind: String,
+    pub count: usize,
+}
+
+/// Totals recomputed from the span rows.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub applied_spans: usize,
+    pub ghost_spans: usize,
+    pub violations: usize,
+    pub geometry_issues: usize,
+    pub total_entropy: f64,
+    pub ghost_entropy: f64,
+    pub total_information: f64,
+    pub total_energy: f64,
+    pub elapsed_ms: u64,
+    pub simulated_ps: f64,
+    /// Σ|ΔE|·Δt over applied spans, using the metadata time step.
+    pub total_work: f64,
+}
+
+/// Typed view over a span log, meant for notebooks (evcxr, PyO3 bindings) as
+/// much as the CLI: every table is a `Vec` of serde-serialisable rows.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunAnalysis {
+    pub metadata: RunMetadata,
+    pub spans: Vec<SpanFrame>,
+    pub violations: Vec<ViolationRow>,
+    pub geometry_checks: Vec<GeometryCheckRow>,
+    pub geometry_issues: Vec<GeometryIssueRow>,
+    /// In step order; the last row is the state the run ended with.
+    pub rng_states: Vec<RngStateRow>,
+    pub backend_failures: Vec<BackendFailureRow>,
+    pub thermo_discrepancies: Vec<ThermoDiscrepancyRow>,
+    /// One row per Metropolis decision; empty in logs written before the
+    /// series was recorded.
+    pub acceptance: Vec<AcceptanceRow>,
+    /// In order of first appearance, parents before their nested phases.
+    pub phases: Vec<PhaseRow>,
+}
+
+impl RunAnalysis {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("failed to open log {}: {err}", path.display()))?;
+        Self::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut lines = contents.lines();
+        let metadata_line = lines
+            .next()
+            .ok_or_else(|| "log file is empty".to_string())?;
+        let mut analysis = Self {
+            metadata: parse_metadata(metadata_line)?,
+            ..Self::default()
+        };
+
+        let mut time_ms = 0;
+        let mut simulated_time_ps = 0.0;
+        let mut cumulative_entropy = 0.0;
+        let mut cumulative_energy = 0.0;
+        for line in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if line.starts_with("span|") {
+                let fields = parse_fields(line)?;
+                let ghost = matches!(fields.get("ghost_flag"), Some(v) if v == "1");
+                let duration_ms = u64_field(&fields, "duration_ms")?;
+                let simulated_ps = optional_f64_field(&fields, "simulated_ps")?.unwrap_or(0.0);
+                let delta_entropy = f64_field(&fields, "delta_S")?;
+                let delta_energy = f64_field(&fields, "delta_E")?;
+                if !ghost {
+                    time_ms += duration_ms;
+                    simulated_time_ps += simulated_ps;
+                    cumulative_entropy += delta_entropy;
+                    cumulative_energy += delta_energy;
+                }
+                analysis.spans.push(SpanFrame {
+                    index: analysis.spans.len(),
+                    id: fields
+                        .get("id")
+                        .cloned()
+                        .unwrap_or_else(|| "unknown".into()),
+                    ghost,
+                    delta_theta: f64_field(&fields, "delta_theta")?,
+                    delta_entropy,
+                    delta_information: f64_field(&fields, "delta_I")?,
+                    delta_energy,
+                    gibbs_energy: f64_field(&fields, "G")?,
+                    duration_ms,
+                    time_ms,
+                    simulated_ps,
+                    simulated_time_ps,
+                    cumulative_entropy,
+                    cumulative_energy,
+                    temperature: optional_f64_field(&fields, "temperature")?,
+                    backend: fields.get("backend").cloned(),
+                    collective_variables: collective_variable_fields(&fields)?,
+                    tags: fields
+                        .get("tags")
+                        .map(|tags| tags.split(',').map(str::to_string).collect())
+                        .unwrap_or_default(),
+                    phase: fields.get("phase").cloned(),
+                });
+            } else if line.starts_with("phase|") {
+                let fields = parse_fields(line)?;
+                analysis.phases.push(PhaseRow {
+                    path: text_field(&fields, "path"),
+                    depth: usize_field(&fields, "depth")?,
+                    spans: usize_field(&fields, "spans")?,
+                    delta_entropy: f64_field(&fields, "delta_S")?,
+                    delta_energy: f64_field(&fields, "delta_E")?,
+                    duration_ms: u64_field(&fields, "duration_ms")?,
+                    simulated_ps: f64_field(&fields, "simulated_ps")?,
+                });
+            } else if line.starts_with("violation|") {
+                analysis.violations.push(parse_violation(line)?);
+            } else if line.starts_with("geometry|") {
+                let fields = parse_fields(line)?;
+                analysis.geometry_checks.push(GeometryCheckRow {
+                    check: text_field(&fields, "check"),
+                    status: text_field(&fields, "status"),
+                    checked: usize_field(&fields, "checked")?,
+                    failures: usize_field(&fields, "failures")?,
+                    min: f64_field(&fields, "min")?,
+                    max: f64_field(&fields, "max")?,
+                });
+            } else if line.starts_with("geometry_issue|") {
+                let fields = parse_fields(line)?;
+                let residues = fields
+                    .get("residues")
+                    .map(|raw| {
+                        raw.split(',')
+                            .filter(|value| !value.is_empty())
+                            .map(|value| {
+                                value
+                                    .parse()
+                                    .map_err(|_| format!("invalid residue index {value}"))
+                            })
+                            .collect::<Result<Vec<usize>, String>>()
+                    })
+                    .transpose()?
+                    .unwrap_or_default();
+                analysis.geometry_issues.push(GeometryIssueRow {
+                    check: text_field(&fields, "check"),
+                    residues,
+                    value: f64_field(&fields, "value")?,
+                    min: f64_field(&fields, "min")?,
+                    max: f64_field(&fields, "max")?,
+                });
+            } else if line.starts_with("backend_failure|") {
+                let fields = parse_fields(line)?;
+                analysis.backend_failures.push(BackendFailureRow {
+                    step: usize_field(&fields, "step")?,
+                    span: text_field(&fields, "span"),
+                    backend: text_field(&fields, "backend"),
+                    fallback: text_field(&fields, "fallback"),
+                    reason: text_field(&fields, "reason"),
+                    stderr: fields
+                        .get("stderr")
+                        .map(|stderr| stderr.replace("\\n", "\n"))
+                        .unwrap_or_default(),
+                });
+            } else if line.starts_with("thermo|") {
+                let fields = parse_fields(line)?;
+                analysis.thermo_discrepancies.push(ThermoDiscrepancyRow {
+                    step: usize_field(&fields, "step")?,
+                    span: fields.get("span").cloned().unwrap_or_default(),
+                    check: text_field(&fields, "check"),
+                    expected: f64_field(&fields, "expected")?,
+                    actual: f64_field(&fields, "actual")?,
+                });
+            } else if line.starts_with("acceptance|") {
+                let fields = parse_fields(line)?;
+                analysis.acceptance.push(AcceptanceRow {
+                    step: usize_field(&fields, "step")?,
+                    accepted: usize_field(&fields, "accepted")?,
+                    total: usize_field(&fields, "total")?,
+                    rate: f64_field(&fields, "rate")?,
+                    window: optional_usize_field(&fields, "window")?.unwrap_or(0),
+                });
+            } else if line.starts_with("rng|") {
+                let fields = parse_fields(line)?;
+                let state = fields.get("state").ok_or("missing field state")?;
+                analysis.rng_states.push(RngStateRow {
+                    step: usize_field(&fields, "step")?,
+                    state: u64::from_str_radix(state, 16)
+                        .map_err(|_| format!("invalid RNG state {state}"))?,
+                    velocities: fields.get("velocities").cloned().unwrap_or_default(),
+                });
+            }
+        }
+        Ok(analysis)
+    }
+
+    pub fn applied(&self) -> impl Iterator<Item = &SpanFrame> {
+        self.spans.iter().filter(|span| !span.ghost)
+    }
+
+    pub fn ghosts(&self) -> impl Iterator<Item = &SpanFrame> {
+        self.spans.iter().filter(|span| span.ghost)
+    }
+
+    /// Spans, applied or ghost, whose instruction carried `tag`.
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a SpanFrame> {
+        self.spans
+            .iter()
+            .filter(move |span| span.tags.iter().any(|candidate| candidate == tag))
+    }
+
+    /// Every tag on any span, sorted.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .spans
+            .iter()
+            .flat_map(|span| span.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Names of the collective variables logged on any span, sorted.
+    pub fn collective_variable_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .spans
+            .iter()
+            .flat_map(|span| span.collective_variables.keys().cloned())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Values of collective variable `name` along the applied spans that
+    /// logged it, e.g. `end_to_end` or `phi:12`.
+    pub fn collective_variable(&self, name: &str) -> Vec<f64> {
+        self.applied()
+            .filter_map(|span| span.collective_variables.get(name).copied())
+            .collect()
+    }
+
+    pub fn summary(&self) -> RunSummary {
+        let step_seconds = self.metadata.time_step_ms as f64 / 1000.0;
+        RunSummary {
+            applied_spans: self.applied().count(),
+            ghost_spans: self.ghosts().count(),
+            violations: self.violations.len(),
+            geometry_issues: self.geometry_issues.len(),
+            total_entropy: self.applied().map(|span| span.delta_entropy).sum(),
+            ghost_entropy: self.ghosts().map(|span| span.delta_entropy).sum(),
+            total_information: self.applied().map(|span| span.delta_information).sum(),
+            total_energy: self.applied().map(|span| span.delta_energy).sum(),
+            elapsed_ms: self.applied().map(|span| span.duration_ms).sum(),
+            simulated_ps: self.applied().map(|span| span.simulated_ps).sum(),
+            total_work: self
+                .applied()
+                .map(|span| span.delta_energy.abs() * step_seconds)
+                .sum(),
+        }
+    }
+
+    /// Decisions per acceptance window as the run logged it; older logs
+    /// without it fall back to the largest `total` logged.
+    pub fn acceptance_window(&self) -> usize {
+        match self.acceptance.iter().map(|row| row.window).max() {
+            Some(window) if window > 0 => window,
+            _ => self
+                .acceptance
+                .iter()
+                .map(|row| row.total)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Stretches of full acceptance windows at or below [`ACCEPTANCE_COLLAPSE`]
+    /// or at or above [`ACCEPTANCE_SATURATION`], in step order. The partial
+    /// windows at the start of a run are too short to judge.
+    pub fn acceptance_alerts(&self) -> Vec<AcceptanceAlert> {
+        let window = self.acceptance_window();
+        let mut alerts: Vec<AcceptanceAlert> = Vec::new();
+        let mut open = false;
+        for row in &self.acceptance {
+            let regime = if row.total < window {
+                None
+            } else if row.rate <= ACCEPTANCE_COLLAPSE {
+                Some(AcceptanceRegime::Collapsed)
+            } else if row.rate >= ACCEPTANCE_SATURATION {
+                Some(AcceptanceRegime::Saturated)
+            } else {
+                None
+            };
+            let Some(regime) = regime else {
+                open = false;
+                continue;
+            };
+            match alerts.last_mut() {
+                Some(alert) if open && alert.regime == regime => {
+                    alert.last_step = row.step;
+                    alert.rate = match regime {
+                        AcceptanceRegime::Collapsed => alert.rate.min(row.rate),
+                        AcceptanceRegime::Saturated => alert.rate.max(row.rate),
+                    };
+                }
+                _ => alerts.push(AcceptanceAlert {
+                    regime,
+                    first_step: row.step,
+                    last_step: row.step,
+                    rate: row.rate,
+                }),
+            }
+            open = true;
+        }
+        alerts
+    }
+
+    /// Violations grouped by kind, most frequent first.
+    pub fn violation_counts(&self) -> Vec<ViolationCount> {
+        let mut counts: Vec<ViolationCount> = Vec::new();
+        for violation in &self.violations {
+            let kind = violation.kind.clone();
+            match counts.iter_mut().find(|entry| entry.kind == kind) {
+                Some(entry) => entry.count += 1,
+                None => counts.push(ViolationCount { kind, count: 1 }),
+            }
+        }
+        counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.kind.cmp(&b.kind)));
+        counts
+    }
+}
+
+fn parse_violation(line: &str) -> Result<ViolationRow, String> {
+    let mut fields: BTreeMap<String, String> = parse_fields(line)?.into_iter().collect();
+    let detail = fields
+        .remove("detail")
+        .unwrap_or_else(|| "unknown violation".into());
+    let kind = fields.remove("kind").unwrap_or_else(|| {
+        detail
+            .split([' ', '{', '('])
+            .next()
+            .unwrap_or(&detail)
+            .to_string()
+    });
+    Ok(ViolationRow {
+        step: fields
+            .remove("step")
+            .map(|raw| {
+                raw.parse()
+                    .map_err(|_| "invalid integer for step".to_string())
+            })
+            .transpose()?,
+        code: fields.remove("code"),
+        severity: fields.remove("severity"),
+        kind,
+        fields,
+        detail,
+    })
+}
+
+fn parse_metadata(raw: &str) -> Result<RunMetadata, String> {
+    if !raw.starts_with("metadata|") {
+        return Err("missing metadata prefix".into());
+    }
+    let fields = parse_fields(raw)?;
+    Ok(RunMetadata {
+        run_id: fields
+            .get("run_id")
+            .cloned()
+            .unwrap_or_else(|| "unknown".into()),
+        run_name: fields.get("run_name").cloned(),
+        timestamp: fields
+            .get("timestamp")
+            .map(|raw| normalize_timestamp(raw))
+            .unwrap_or_else(|| "unknown".into()),
+        contract_name: fields
+            .get("contract_name")
+            .filter(|value| !value.is_empty())
+            .cloned(),
+        environment: fields
+            .get("environment")
+            .cloned()
+            .unwrap_or_else(|| "unknown".into()),
+        temperature: f64_field(&fields, "temperature")?,
+        time_step_ms: u64_field(&fields, "time_step_ms")?,
+        accepted_spans: usize_field(&fields, "accepted_spans")?,
+        rejected_spans: usize_field(&fields, "rejected_spans")?,
+        acceptance_rate: f64_field(&fields, "acceptance_rate")?,
+        final_potential_energy: f64_field(&fields, "final_potential_energy")?,
+        final_gibbs_energy: f64_field(&fields, "final_gibbs_energy")?,
+        informational_efficiency: f64_field(&fields, "informational_efficiency")?,
+        total_work: f64_field(&fields, "total_work")?,
+        tm_score: optional_f64_field(&fields, "tm_score")?,
+        gdt_ts: optional_f64_field(&fields, "gdt_ts")?,
+        heuristic_entropy: optional_f64_field(&fields, "heuristic_entropy")?,
+        quasi_harmonic_entropy: optional_f64_field(&fields, "quasi_harmonic_entropy")?,
+        foldability: optional_f64_field(&fields, "foldability")?,
+        energy_gap: optional_f64_field(&fields, "energy_gap")?,
+        energy_samples: fields
+            .get("energy_samples")
+            .map(|raw| raw.parse())
+            .transpose()?,
+        span_time_ms: optional_f64_field(&fields, "span_time_ms")?,
+        simulated_time_ps: optional_f64_field(&fields, "simulated_time_ps")?,
+        wall_time_ms: optional_f64_field(&fields, "wall_time_ms")?,
+        remote_uri: fields.get("remote_uri").cloned(),
+        level_choice: fields.get("level_choice").cloned(),
+        crate_version: fields.get("crate_version").cloned(),
+        git_commit: fields.get("git_commit").cloned(),
+        hostname: fields.get("hostname").cloned(),
+        os: fields.get("os").cloned(),
+        physics_backend: fields.get("physics_backend").cloned(),
+        features: fields
+            .get("features")
+            .map(|list| {
+                list.split(',')
+                    .filter(|feature| !feature.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Older logs stored `secs.nanos` since the Unix epoch; they are rewritten as
+/// RFC 3339 so every run reads the same way.
+fn normalize_timestamp(raw: &str) -> String {
+    match raw.parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => {
+            folding_time::rfc3339(UNIX_EPOCH + Duration::from_secs_f64(seconds))
+        }
+        _ => raw.to_string(),
+    }
+}
+
+fn parse_fields(raw: &str) -> Result<HashMap<String, String>, String> {
+    let mut map = HashMap::new();
+    for segment in raw.split('|').skip(1) {
+        if segment.is_empty() {
+            continue;
+        }
+        let (key, value) = segment
+            .split_once('=')
+            .ok_or_else(|| format!("invalid field: {segment}"))?;
+        map.insert(key.to_string(), value.to_string());
+    }
+    Ok(map)
+}
+
+fn text_field(fields: &HashMap<String, String>, key: &str) -> String {
+    fields.get(key).cloned().unwrap_or_else(|| "unknown".into())
+}
+
+fn f64_field(fields: &HashMap<String, String>, key: &str) -> Result<f64, String> {
+    fields
+        .get(key)
+        .ok_or_else(|| format!("missing field {key}"))?
+        .parse()
+        .map_err(|_| format!("invalid float for {key}"))
+}
+
+fn optional_f64_field(fields: &HashMap<String, String>, key: &str) -> Result<Option<f64>, String> {
+    fields
+        .get(key)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("invalid float for {key}"))
+        })
+        .transpose()
+}
+
+fn collective_variable_fields(
+    fields: &HashMap<String, String>,
+) -> Result<BTreeMap<String, f64>, String> {
+    fields
+        .iter()
+        .filter_map(|(key, value)| Some((key.strip_prefix("cv.")?, value)))
+        .map(|(name, value)| {
+            let value = value
+                .parse()
+                .map_err(|_| format!("invalid float for cv.{name}"))?;
+            Ok((name.to_string(), value))
+        })
+        .collect()
+}
+
+fn u64_field(fields: &HashMap<String, String>, key: &str) -> Result<u64, String> {
+    fields
+        .get(key)
+        .ok_or_else(|| format!("missing field {key}"))?
+        .parse()
+        .map_err(|_| format!("invalid integer for {key}"))
+}
+
+fn usize_field(fields: &HashMap<String, String>, key: &str) -> Result<usize, String> {
+    fields
+        .get(key)
+        .ok_or_else(|| format!("missing field {key}"))?
+        .parse()
+        .map_err(|_| format!("invalid integer for {key}"))
+}
+
+fn optional_usize_field(
+    fields: &HashMap<String, String>,
+    key: &str,
+) -> Result<Option<usize>, String> {
+    fields
+        .get(key)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("invalid integer for {key}"))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "metadata|run_id=abc|run_name=sweep-3|timestamp=1.0|contract_name=demo|environment=aqueous|temperature=298.000000|time_step_ms=2|accepted_spans=2|rejected_spans=1|acceptance_rate=0.666667|final_potential_energy=1.5|final_gibbs_energy=1.2|informational_efficiency=0.8|total_work=0.0|tm_score=0.5|heuristic_entropy=0.4|quasi_harmonic_entropy=0.012|foldability=2.5|energy_gap=4.1|energy_samples=3,-2,8,-5|crate_version=0.1.0|git_commit=abc123|hostname=lab|os=linux-x86_64|physics_backend=toy/auto(native)|features=openmm,upload
+span|id=a|delta_theta=10.0|delta_S=0.1|delta_I=0.2|delta_E=-1.0|duration_ms=5|ghost_flag=0|G=-1.0
+span|id=b|delta_theta=5.0|delta_S=0.3|delta_I=0.1|delta_E=0.5|duration_ms=5|simulated_ps=0.25|ghost_flag=0|G=-0.5|backend=native|cv.end_to_end=12.5|cv.phi:3=-60.0|tags=dock,hinge|phase=anneal/hot
+phase|path=anneal|depth=0|spans=1|delta_S=0.3|delta_E=0.5|duration_ms=5|simulated_ps=0.25
+phase|path=anneal/hot|depth=1|spans=1|delta_S=0.3|delta_E=0.5|duration_ms=5|simulated_ps=0.25
+span|id=g|delta_theta=1.0|delta_S=0.05|delta_I=0.0|delta_E=0.1|duration_ms=5|ghost_flag=1|G=0.1|tags=dock
+violation|detail=MetropolisRejected { delta_energy: 1.4 }
+violation|step=2|code=R013|severity=error|kind=ForbiddenResidue|residue=4|start=2|end=6|detail=ForbiddenResidue { residue: ResidueId(4), start: ResidueId(2), end: ResidueId(6) }
+geometry|check=bond_length|status=pass|checked=2|failures=0|min=3.8|max=3.8
+geometry_issue|check=clash|residues=0,2|value=1.1|min=3.0|max=inf
+rng|step=3|state=00000000deadbeef
+backend_failure|step=1|span=residue-2|backend=openmm|fallback=native|reason=bridge exited with exit status: 1|stderr=Traceback (most recent call last):\\nImportError: No module named openmm
+thermo|step=2|span=b|check=temperature|expected=298.000000|actual=600.000000
+";
+
+    #[test]
+    fn parses_log_into_typed_tables() {
+        let analysis = RunAnalysis::parse(LOG).unwrap();
+        assert_eq!(analysis.metadata.contract_name.as_deref(), Some("demo"));
+        assert_eq!(analysis.metadata.run_name.as_deref(), Some("sweep-3"));
+        assert_eq!(analysis.metadata.timestamp, "1970-01-01T00:00:01.000000Z");
+        assert_eq!(analysis.metadata.tm_score, Some(0.5));
+        assert_eq!(analysis.metadata.quasi_harmonic_entropy, Some(0.012));
+        assert_eq!(analysis.metadata.foldability, Some(2.5));
+        let samples = analysis.metadata.energy_samples.unwrap();
+        assert_eq!((samples.len(), samples.best()), (3, Some(-5.0)));
+        assert_eq!(analysis.metadata.git_commit.as_deref(), Some("abc123"));
+        assert_eq!(analysis.metadata.features, ["openmm", "upload"]);
+        assert_eq!(analysis.spans.len(), 3);
+        assert_eq!(analysis.spans[1].time_ms, 10);
+        assert_eq!(analysis.spans[1].simulated_time_ps, 0.25);
+        assert_eq!(analysis.spans[1].backend.as_deref(), Some("native"));
+        assert_eq!(analysis.spans[0].backend, None);
+        assert_eq!(analysis.tags(), ["dock", "hinge"]);
+        let docked: Vec<&str> = analysis.tagged("dock").map(|span| span.id.as_str()).collect();
+        assert_eq!(docked, ["b", "g"]);
+        assert_eq!(analysis.spans[1].phase.as_deref(), Some("anneal/hot"));
+        assert_eq!(analysis.phases[1].path, "anneal/hot");
+        assert_eq!(analysis.phases[1].depth, 1);
+        assert_eq!(analysis.collective_variable_names(), ["end_to_end", "phi:3"]);
+        assert_eq!(analysis.collective_variable("phi:3"), [-60.0]);
+        assert_eq!(analysis.metadata.simulated_time_ps, None);
+        assert!((analysis.spans[2].cumulative_entropy - 0.4).abs() < 1e-12);
+        assert_eq!(analysis.geometry_issues[0].residues, vec![0, 2]);
+        assert_eq!(
+            analysis.rng_states,
+            [RngStateRow {
+                step: 3,
+                state: 0xdead_beef,
+                velocities: String::new(),
+            }]
+        );
+        let failure = &analysis.backend_failures[0];
+        assert_eq!((failure.backend.as_str(), failure.fallback.as_str()), ("openmm", "native"));
+        assert_eq!(failure.stderr.lines().count(), 2);
+        assert_eq!(analysis.thermo_discrepancies[0].check, "temperature");
+        assert_eq!(analysis.thermo_discrepancies[0].actual, 600.0);
+        assert_eq!(analysis.violations[0].code, None);
+        let forbidden = &analysis.violations[1];
+        assert_eq!(forbidden.step, Some(2));
+        assert_eq!(forbidden.code.as_deref(), Some("R013"));
+        assert_eq!(forbidden.severity.as_deref(), Some("error"));
+        assert_eq!(forbidden.fields["residue"], "4");
+
+        let summary = analysis.summary();
+        assert_eq!(summary.applied_spans, 2);
+        assert_eq!(summary.ghost_spans, 1);
+        assert!((summary.total_work - 0.003).abs() < 1e-12);
+        assert_eq!(
+            analysis.violation_counts(),
+            vec![
+                ViolationCount {
+                    kind: "ForbiddenResidue".into(),
+                    count: 1
+                },
+                ViolationCount {
+                    kind: "MetropolisRejected".into(),
+                    count: 1
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_round_trip_through_serde() {
+        let analysis = RunAnalysis::parse(LOG).unwrap();
+        let json = serde_json::to_string(&analysis.spans).unwrap();
+        assert!(json.contains("\"cumulative_energy\":-0.5"));
+        let back: Vec<SpanFrame> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, analysis.spans);
+    }
+
+    #[test]
+    fn acceptance_alerts_flag_collapsed_and_saturated_windows() {
+        // Window of 2: the partial first window is never flagged.
+        let accepted = [0, 2, 2, 1, 0, 0, 1];
+        let mut log = LOG.lines().next().unwrap().to_string();
+        for (i, accepted) in accepted.into_iter().enumerate() {
+            let (step, total) = (i + 1, (i + 1).min(2));
+            let rate = accepted as f64 / total as f64;
+            log.push_str(&format!(
+                "\nacceptance|step={step}|accepted={accepted}|total={total}|rate={rate:.6}"
+            ));
+        }
+        let analysis = RunAnalysis::parse(&log).unwrap();
+        assert_eq!(analysis.acceptance.len(), accepted.len());
+        // Without a logged window, the largest total stands in for it
+        assert_eq!(analysis.acceptance_window(), 2);
+        let alerts = analysis.acceptance_alerts();
+        let stretches: Vec<(AcceptanceRegime, usize, usize)> = alerts
+            .iter()
+            .map(|alert| (alert.regime, alert.first_step, alert.last_step))
+            .collect();
+        assert_eq!(
+            stretches,
+            [
+                (AcceptanceRegime::Saturated, 2, 3),
+                (AcceptanceRegime::Collapsed, 5, 6)
+            ]
+        );
+        assert_eq!(alerts[1].rate, 0.0);
+        let quiet = RunAnalysis::parse(LOG).unwrap();
+        assert!(quiet.acceptance_alerts().is_empty());
+
+        // A run shorter than its logged window never fills one
+        let short = log.replace("|rate=", "|window=10|rate=");
+        let analysis = RunAnalysis::parse(&short).unwrap();
+        assert_eq!(analysis.acceptance_window(), 10);
+        assert!(analysis.acceptance_alerts().is_empty());
+    }
+}
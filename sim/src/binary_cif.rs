@@ -0,0 +1,292 @@
+use folding_molecule::PeptideChain;
+use rmp::encode::{self, ValueWriteError};
+
+/// BinaryCIF `ByteArray` type codes used by the encoder.
+const INT32: u64 = 3;
+const FLOAT64: u64 = 33;
+
+/// Column payload before BinaryCIF encoding.
+enum ColumnData {
+    Int(Vec<i32>),
+    Float(Vec<f64>),
+    Text(Vec<String>),
+}
+
+struct Column {
+    name: &'static str,
+    data: ColumnData,
+}
+
+struct Category {
+    name: &'static str,
+    row_count: usize,
+    columns: Vec<Column>,
+}
+
+/// Encodes Cα frames as a BinaryCIF (MessagePack) document that Mol* can open.
+/// Each frame becomes one model in `_atom_site`; pass a single frame to export
+/// only the final structure.
+pub fn encode_binary_cif(
+    chain: &PeptideChain,
+    frames: &[Vec<[f64; 3]>],
+    entry_id: &str,
+) -> Vec<u8> {
+    let entry = Category {
+        name: "_entry",
+        row_count: 1,
+        columns: vec![Column {
+            name: "id",
+            data: ColumnData::Text(vec![entry_id.to_string()]),
+        }],
+    };
+    let atom_site = atom_site_category(chain, frames);
+
+    let mut buffer = Vec::new();
+    write_document(&mut buffer, entry_id, &[entry, atom_site])
+        .expect("writing MessagePack into a Vec cannot fail");
+    buffer
+}
+
+fn atom_site_category(chain: &PeptideChain, frames: &[Vec<[f64; 3]>]) -> Category {
+    let mut ids = Vec::new();
+    let mut residue_names = Vec::new();
+    let mut sequence_ids = Vec::new();
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    let mut zs = Vec::new();
+    let mut models = Vec::new();
+    for (model, frame) in frames.iter().enumerate() {
+        for (residue, position) in chain.residues().zip(frame) {
+            ids.push(ids.len() as i32 + 1);
+            residue_names.push(residue.name.to_string());
+            sequence_ids.push(residue.id.0 as i32 + 1);
+            xs.push(position[0]);
+            ys.push(position[1]);
+            zs.push(position[2]);
+            models.push(model as i32 + 1);
+        }
+    }
+    let rows = ids.len();
+    let repeated = |value: &str| ColumnData::Text(vec![value.to_string(); rows]);
+    Category {
+        name: "_atom_site",
+        row_count: rows,
+        columns: vec![
+            Column {
+                name: "group_PDB",
+                data: repeated("ATOM"),
+            },
+            Column {
+                name: "id",
+                data: ColumnData::Int(ids),
+            },
+            Column {
+                name: "type_symbol",
+                data: repeated("C"),
+            },
+            Column {
+                name: "label_atom_id",
+                data: repeated("CA"),
+            },
+            Column {
+                name: "label_comp_id",
+                data: ColumnData::Text(residue_names),
+            },
+            Column {
+                name: "label_asym_id",
+                data: repeated("A"),
+            },
+            Column {
+                name: "label_seq_id",
+                data: ColumnData::Int(sequence_ids.clone()),
+            },
+            Column {
+                name: "auth_asym_id",
+                data: repeated("A"),
+            },
+            Column {
+                name: "auth_seq_id",
+                data: ColumnData::Int(sequence_ids),
+            },
+            Column {
+                name: "Cartn_x",
+                data: ColumnData::Float(xs),
+            },
+            Column {
+                name: "Cartn_y",
+                data: ColumnData::Float(ys),
+            },
+            Column {
+                name: "Cartn_z",
+                data: ColumnData::Float(zs),
+            },
+            Column {
+                name: "pdbx_PDB_model_num",
+                data: ColumnData::Int(models),
+            },
+        ],
+    }
+}
+
+type WriteResult = Result<(), ValueWriteError>;
+
+fn write_document(buf: &mut Vec<u8>, header: &str, categories: &[Category]) -> WriteResult {
+    encode::write_map_len(buf, 3)?;
+    encode::write_str(buf, "version")?;
+    encode::write_str(buf, "0.3.0")?;
+    encode::write_str(buf, "encoder")?;
+    encode::write_str(buf, "LogLineFold")?;
+    encode::write_str(buf, "dataBlocks")?;
+    encode::write_array_len(buf, 1)?;
+    encode::write_map_len(buf, 2)?;
+    encode::write_str(buf, "header")?;
+    encode::write_str(buf, &header.to_uppercase())?;
+    encode::write_str(buf, "categories")?;
+    encode::write_array_len(buf, categories.len() as u32)?;
+    for category in categories {
+        write_category(buf, category)?;
+    }
+    Ok(())
+}
+
+fn write_category(buf: &mut Vec<u8>, category: &Category) -> WriteResult {
+    encode::write_map_len(buf, 3)?;
+    encode::write_str(buf, "name")?;
+    encode::write_str(buf, category.name)?;
+    encode::write_str(buf, "rowCount")?;
+    encode::write_uint(buf, category.row_count as u64)?;
+    encode::write_str(buf, "columns")?;
+    encode::write_array_len(buf, category.columns.len() as u32)?;
+    for column in &category.columns {
+        encode::write_map_len(buf, 3)?;
+        encode::write_str(buf, "name")?;
+        encode::write_str(buf, column.name)?;
+        encode::write_str(buf, "data")?;
+        write_column_data(buf, &column.data)?;
+        encode::write_str(buf, "mask")?;
+        encode::write_nil(buf).map_err(ValueWriteError::InvalidMarkerWrite)?;
+    }
+    Ok(())
+}
+
+fn write_column_data(buf: &mut Vec<u8>, data: &ColumnData) -> WriteResult {
+    encode::write_map_len(buf, 2)?;
+    encode::write_str(buf, "data")?;
+    match data {
+        ColumnData::Int(values) => {
+            encode::write_bin(buf, &int32_bytes(values))?;
+            encode::write_str(buf, "encoding")?;
+            encode::write_array_len(buf, 1)?;
+            write_byte_array_encoding(buf, INT32)
+        }
+        ColumnData::Float(values) => {
+            let bytes: Vec<u8> = values
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect();
+            encode::write_bin(buf, &bytes)?;
+            encode::write_str(buf, "encoding")?;
+            encode::write_array_len(buf, 1)?;
+            write_byte_array_encoding(buf, FLOAT64)
+        }
+        ColumnData::Text(values) => {
+            // StringArray: unique strings concatenated, rows store indices.
+            let mut unique: Vec<&str> = Vec::new();
+            let indices: Vec<i32> = values
+                .iter()
+                .map(
+                    |value| match unique.iter().position(|known| *known == value) {
+                        Some(idx) => idx as i32,
+                        None => {
+                            unique.push(value);
+                            unique.len() as i32 - 1
+                        }
+                    },
+                )
+                .collect();
+            let mut offsets = vec![0i32];
+            for value in &unique {
+                offsets.push(offsets.last().copied().unwrap_or(0) + value.len() as i32);
+            }
+            encode::write_bin(buf, &int32_bytes(&indices))?;
+            encode::write_str(buf, "encoding")?;
+            encode::write_array_len(buf, 1)?;
+            encode::write_map_len(buf, 5)?;
+            encode::write_str(buf, "kind")?;
+            encode::write_str(buf, "StringArray")?;
+            encode::write_str(buf, "dataEncoding")?;
+            encode::write_array_len(buf, 1)?;
+            write_byte_array_encoding(buf, INT32)?;
+            encode::write_str(buf, "stringData")?;
+            encode::write_str(buf, &unique.concat())?;
+            encode::write_str(buf, "offsetEncoding")?;
+            encode::write_array_len(buf, 1)?;
+            write_byte_array_encoding(buf, INT32)?;
+            encode::write_str(buf, "offsets")?;
+            encode::write_bin(buf, &int32_bytes(&offsets))?;
+            Ok(())
+        }
+    }
+}
+
+fn write_byte_array_encoding(buf: &mut Vec<u8>, type_code: u64) -> WriteResult {
+    encode::write_map_len(buf, 2)?;
+    encode::write_str(buf, "kind")?;
+    encode::write_str(buf, "ByteArray")?;
+    encode::write_str(buf, "type")?;
+    encode::write_uint(buf, type_code)?;
+    Ok(())
+}
+
+fn int32_bytes(values: &[i32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmpv::Value;
+
+    fn field<'a>(map: &'a Value, key: &str) -> &'a Value {
+        map.as_map()
+            .unwrap()
+            .iter()
+            .find(|(k, _)| k.as_str() == Some(key))
+            .map(|(_, v)| v)
+            .unwrap()
+    }
+
+    #[test]
+    fn encodes_one_model_per_frame() {
+        let chain = PeptideChain::from_sequence("AG");
+        let frames = vec![
+            vec![[0.0, 0.0, 0.0], [3.8, 0.0, 0.0]],
+            vec![[0.0, 1.0, 0.0], [3.8, 1.5, 0.0]],
+        ];
+        let bytes = encode_binary_cif(&chain, &frames, "demo");
+        let document = rmpv::decode::read_value(&mut bytes.as_slice()).unwrap();
+        let block = &field(&document, "dataBlocks").as_array().unwrap()[0];
+        let atom_site = &field(block, "categories").as_array().unwrap()[1];
+        assert_eq!(field(atom_site, "name").as_str(), Some("_atom_site"));
+        assert_eq!(field(atom_site, "rowCount").as_u64(), Some(4));
+
+        let columns = field(atom_site, "columns").as_array().unwrap();
+        let y = columns
+            .iter()
+            .find(|column| field(column, "name").as_str() == Some("Cartn_y"))
+            .unwrap();
+        let raw = field(field(y, "data"), "data").as_slice().unwrap();
+        let last = f64::from_le_bytes(raw[24..32].try_into().unwrap());
+        assert_eq!(last, 1.5);
+
+        let names = columns
+            .iter()
+            .find(|column| field(column, "name").as_str() == Some("label_comp_id"))
+            .unwrap();
+        let encoding = &field(field(names, "data"), "encoding").as_array().unwrap()[0];
+        assert_eq!(field(encoding, "stringData").as_str(), Some("ALAGLY"));
+    }
+}
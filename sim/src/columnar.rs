@@ -0,0 +1,282 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::analysis::{RunAnalysis, SpanFrame};
+
+/// Span rows as an Arrow batch. `run_id` is repeated on every row so tables
+/// from several runs can be globbed together in DuckDB/Polars. Each logged
+/// collective variable adds a nullable `cv.<name>` column.
+pub fn spans_record_batch(analysis: &RunAnalysis) -> Result<RecordBatch, String> {
+    let spans = &analysis.spans;
+    let f64_column = |value: fn(&SpanFrame) -> f64| -> ArrayRef {
+        Arc::new(Float64Array::from_iter_values(spans.iter().map(value)))
+    };
+    let variables = analysis.collective_variable_names();
+    let variable_columns: Vec<String> = variables.iter().map(|name| format!("cv.{name}")).collect();
+    let mut columns: Vec<(&str, ArrayRef)> = vec![
+        (
+            "run_id",
+            Arc::new(StringArray::from(vec![
+                analysis.metadata.run_id.as_str();
+                spans.len()
+            ])),
+        ),
+        (
+            "index",
+            Arc::new(UInt64Array::from_iter_values(
+                spans.iter().map(|span| span.index as u64),
+            )),
+        ),
+        (
+            "id",
+            Arc::new(StringArray::from_iter_values(
+                spans.iter().map(|span| span.id.as_str()),
+            )),
+        ),
+        (
+            "ghost",
+            Arc::new(BooleanArray::from(
+                spans.iter().map(|span| span.ghost).collect::<Vec<_>>(),
+            )),
+        ),
+        ("delta_theta", f64_column(|span| span.delta_theta)),
+        ("delta_entropy", f64_column(|span| span.delta_entropy)),
+        (
+            "delta_information",
+            f64_column(|span| span.delta_information),
+        ),
+        ("delta_energy", f64_column(|span| span.delta_energy)),
+        ("gibbs_energy", f64_column(|span| span.gibbs_energy)),
+        (
+            "duration_ms",
+            Arc::new(UInt64Array::from_iter_values(
+                spans.iter().map(|span| span.duration_ms),
+            )),
+        ),
+        (
+            "time_ms",
+            Arc::new(UInt64Array::from_iter_values(
+                spans.iter().map(|span| span.time_ms),
+            )),
+        ),
+        ("simulated_ps", f64_column(|span| span.simulated_ps)),
+        (
+            "simulated_time_ps",
+            f64_column(|span| span.simulated_time_ps),
+        ),
+        (
+            "cumulative_entropy",
+            f64_column(|span| span.cumulative_entropy),
+        ),
+        (
+            "cumulative_energy",
+            f64_column(|span| span.cumulative_energy),
+        ),
+        (
+            "temperature",
+            Arc::new(Float64Array::from(
+                spans.iter().map(|span| span.temperature).collect::<Vec<_>>(),
+            )),
+        ),
+        (
+            "backend",
+            Arc::new(StringArray::from(
+                spans
+                    .iter()
+                    .map(|span| span.backend.as_deref())
+                    .collect::<Vec<_>>(),
+            )),
+        ),
+    ];
+    for (column, name) in variable_columns.iter().zip(&variables) {
+        let values: Vec<Option<f64>> = spans
+            .iter()
+            .map(|span| span.collective_variables.get(name).copied())
+            .collect();
+        columns.push((column, Arc::new(Float64Array::from(values))));
+    }
+    batch_from_columns(columns)
+}
+
+/// Single-row batch with the log metadata and recomputed summary totals.
+pub fn metadata_record_batch(analysis: &RunAnalysis) -> Result<RecordBatch, String> {
+    let metadata = &analysis.metadata;
+    let summary = analysis.summary();
+    let text = |value: &str| -> ArrayRef { Arc::new(StringArray::from(vec![value])) };
+    let float = |value: f64| -> ArrayRef { Arc::new(Float64Array::from(vec![value])) };
+    let optional_float =
+        |value: Option<f64>| -> ArrayRef { Arc::new(Float64Array::from(vec![value])) };
+    let optional_text =
+        |value: Option<&str>| -> ArrayRef { Arc::new(StringArray::from(vec![value])) };
+    let count = |value: u64| -> ArrayRef { Arc::new(UInt64Array::from(vec![value])) };
+    let columns: Vec<(&str, ArrayRef)> = vec![
+        ("run_id", text(&metadata.run_id)),
+        ("run_name", optional_text(metadata.run_name.as_deref())),
+        ("timestamp", text(&metadata.timestamp)),
+        (
+            "contract_name",
+            Arc::new(StringArray::from(vec![metadata.contract_name.as_deref()])),
+        ),
+        ("environment", text(&metadata.environment)),
+        ("temperature", float(metadata.temperature)),
+        ("time_step_ms", count(metadata.time_step_ms)),
+        ("accepted_spans", count(metadata.accepted_spans as u64)),
+        ("rejected_spans", count(metadata.rejected_spans as u64)),
+        ("acceptance_rate", float(metadata.acceptance_rate)),
+        (
+            "final_potential_energy",
+            float(metadata.final_potential_energy),
+        ),
+        ("final_gibbs_energy", float(metadata.final_gibbs_energy)),
+        (
+            "informational_efficiency",
+            float(metadata.informational_efficiency),
+        ),
+        ("total_work", float(metadata.total_work)),
+        ("tm_score", optional_float(metadata.tm_score)),
+        ("gdt_ts", optional_float(metadata.gdt_ts)),
+        (
+            "heuristic_entropy",
+            optional_float(metadata.heuristic_entropy),
+        ),
+        (
+            "quasi_harmonic_entropy",
+            optional_float(metadata.quasi_harmonic_entropy),
+        ),
+        ("foldability", optional_float(metadata.foldability)),
+        ("energy_gap", optional_float(metadata.energy_gap)),
+        ("span_time_ms", optional_float(metadata.span_time_ms)),
+        (
+            "simulated_time_ps",
+            optional_float(metadata.simulated_time_ps),
+        ),
+        ("wall_time_ms", optional_float(metadata.wall_time_ms)),
+        (
+            "remote_uri",
+            Arc::new(StringArray::from(vec![metadata.remote_uri.as_deref()])),
+        ),
+        (
+            "crate_version",
+            optional_text(metadata.crate_version.as_deref()),
+        ),
+        ("git_commit", optional_text(metadata.git_commit.as_deref())),
+        ("hostname", optional_text(metadata.hostname.as_deref())),
+        ("os", optional_text(metadata.os.as_deref())),
+        (
+            "physics_backend",
+            optional_text(metadata.physics_backend.as_deref()),
+        ),
+        ("features", text(&metadata.features.join(","))),
+        ("applied_spans", count(summary.applied_spans as u64)),
+        ("ghost_spans", count(summary.ghost_spans as u64)),
+        ("violations", count(summary.violations as u64)),
+        ("geometry_issues", count(summary.geometry_issues as u64)),
+        ("total_entropy", float(summary.total_entropy)),
+        ("elapsed_ms", count(summary.elapsed_ms)),
+    ];
+    batch_from_columns(columns)
+}
+
+/// Writes `{stem}_spans.parquet` and `{stem}_metadata.parquet` into `dir`.
+pub fn export_parquet(
+    analysis: &RunAnalysis,
+    dir: &Path,
+    stem: &str,
+) -> Result<Vec<PathBuf>, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|err| format!("failed to create directory {}: {err}", dir.display()))?;
+    let tables = [
+        ("spans", spans_record_batch(analysis)?),
+        ("metadata", metadata_record_batch(analysis)?),
+    ];
+    let mut written = Vec::with_capacity(tables.len());
+    for (name, batch) in tables {
+        let path = dir.join(format!("{stem}_{name}.parquet"));
+        write_parquet(&batch, &path)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+pub fn write_parquet(batch: &RecordBatch, path: &Path) -> Result<(), String> {
+    let file =
+        File::create(path).map_err(|err| format!("failed to create {}: {err}", path.display()))?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties))
+        .map_err(|err| format!("failed to start parquet {}: {err}", path.display()))?;
+    writer
+        .write(batch)
+        .map_err(|err| format!("failed to write parquet {}: {err}", path.display()))?;
+    writer
+        .close()
+        .map_err(|err| format!("failed to finish parquet {}: {err}", path.display()))?;
+    Ok(())
+}
+
+fn batch_from_columns(columns: Vec<(&str, ArrayRef)>) -> Result<RecordBatch, String> {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|(name, array)| Field::new(*name, array.data_type().clone(), true))
+        .collect();
+    let arrays = columns.into_iter().map(|(_, array)| array).collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    const LOG: &str = "metadata|run_id=abc|timestamp=1.0|contract_name=|environment=aqueous|temperature=298.0|time_step_ms=1|accepted_spans=1|rejected_spans=0|acceptance_rate=1.0|final_potential_energy=1.5|final_gibbs_energy=1.2|informational_efficiency=1.0|total_work=0.0
+span|id=a|delta_theta=10.0|delta_S=0.1|delta_I=0.2|delta_E=-1.0|duration_ms=5|ghost_flag=0|G=-1.0
+span|id=g|delta_theta=1.0|delta_S=0.05|delta_I=0.0|delta_E=0.1|duration_ms=5|ghost_flag=1|G=0.1
+";
+
+    #[test]
+    fn exported_parquet_reads_back() {
+        let analysis = RunAnalysis::parse(LOG).unwrap();
+        let dir = std::env::temp_dir().join(format!("logline_parquet_{}", std::process::id()));
+        let written = export_parquet(&analysis, &dir, "run").unwrap();
+        assert_eq!(written.len(), 2);
+
+        let file = File::open(&written[0]).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.into_iter().next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        let ghost = batch
+            .column_by_name("ghost")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+        assert!(!ghost.value(0) && ghost.value(1));
+
+        let metadata = metadata_record_batch(&analysis).unwrap();
+        assert_eq!(metadata.num_rows(), 1);
+        assert_eq!(
+            metadata
+                .column_by_name("contract_name")
+                .unwrap()
+                .null_count(),
+            1
+        );
+        // Logs written before provenance was recorded leave it null
+        assert_eq!(
+            metadata.column_by_name("git_commit").unwrap().null_count(),
+            1
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
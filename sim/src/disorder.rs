@@ -0,0 +1,327 @@
+//! Per-residue disorder propensity of a run.
+//!
+//! Intrinsically disordered regions move a lot, stay exposed to solvent and
+//! keep producing entropy however long the run goes on. Each of those three
+//! signals is put on a fixed 0–1 scale, so a well-ordered chain scores low
+//! throughout: the Cα RMSF over the recorded frames against
+//! [`RMSF_REFERENCE`], the relative solvent-accessible surface of the final
+//! structure as it is, and the entropy accepted spans produced on the residue
+//! against [`ENTROPY_REFERENCE`]. The propensity is their mean, so a residue
+//! scores high only when most of them agree; a signal the run gives no data
+//! for (fewer than two frames, no spans accepted) is left out.
+
+use std::f64::consts::PI;
+
+use folding_core::{RotationOutcome, TrajectoryFrame};
+use folding_molecule::PeptideChain;
+use folding_molecule::side_chain::{CA_CB_DISTANCE, side_chain_reach};
+
+use crate::residue_metrics::{residue_entropy, rmsf};
+
+/// Water probe radius (Å) rolled over the beads.
+pub const PROBE_RADIUS: f64 = 1.4;
+/// Propensity from which a residue counts as disordered.
+pub const DISORDER_THRESHOLD: f64 = 0.5;
+/// Cα RMSF (Å) from which a residue counts as fully mobile; packed cores
+/// fluctuate well under 1 Å.
+pub const RMSF_REFERENCE: f64 = 2.0;
+/// Span entropy from which a residue counts as fully entropic: accepted
+/// turns adding up to a full circle (0.01 per degree).
+pub const ENTROPY_REFERENCE: f64 = 3.6;
+/// Fewest consecutive disordered residues reported as a region.
+pub const MIN_REGION_LENGTH: usize = 3;
+/// Radius (Å) of a bead with no side chain; side chains widen it by half
+/// their Cα-to-centroid reach.
+const BACKBONE_BEAD_RADIUS: f64 = 2.0;
+/// Surface points per bead; enough for areas within a few percent.
+const SURFACE_POINTS: usize = 192;
+
+/// Disorder signals of one residue.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResidueDisorder {
+    pub chain: char,
+    /// Cα RMSF (Å) over the recorded frames.
+    pub rmsf: f64,
+    /// Solvent-accessible surface (Å²) of the residue's bead.
+    pub sasa: f64,
+    /// `sasa` over the surface of the bead alone.
+    pub relative_sasa: f64,
+    /// Entropy of the accepted spans acting on the residue.
+    pub entropy: f64,
+    /// Mean of the signals on their 0–1 scales.
+    pub propensity: f64,
+}
+
+/// Disorder propensity track of a chain, one entry per residue.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DisorderProfile {
+    pub residues: Vec<ResidueDisorder>,
+    pub names: Vec<String>,
+}
+
+impl DisorderProfile {
+    /// Profile of `chain` after a run whose accepted spans are `rotations`
+    /// and whose recorded frames are `frames`; surfaces are those of the
+    /// final structure.
+    pub fn new(
+        chain: &PeptideChain,
+        rotations: &[RotationOutcome],
+        frames: &[TrajectoryFrame],
+    ) -> Self {
+        let count = chain.len();
+        let mut fluctuations = rmsf(frames);
+        fluctuations.resize(count, 0.0);
+        let entropy = residue_entropy(rotations, count);
+        let radii: Vec<f64> = chain.names().iter().map(|name| bead_radius(name)).collect();
+        let sasa = residue_sasa(chain.positions(), &radii);
+        let relative_sasa: Vec<f64> = sasa
+            .iter()
+            .zip(&radii)
+            .map(|(area, radius)| area / sphere_area(*radius))
+            .collect();
+
+        let mut signals = vec![scaled(&relative_sasa, 1.0)];
+        if frames.len() > 1 {
+            signals.push(scaled(&fluctuations, RMSF_REFERENCE));
+        }
+        if !rotations.is_empty() {
+            signals.push(scaled(&entropy, ENTROPY_REFERENCE));
+        }
+        let mut chain_ids = Vec::with_capacity(count);
+        for (id, range) in chain.chain_ranges() {
+            chain_ids.extend(range.map(|_| id));
+        }
+        let residues = (0..count)
+            .map(|index| ResidueDisorder {
+                chain: chain_ids[index],
+                rmsf: fluctuations[index],
+                sasa: sasa[index],
+                relative_sasa: relative_sasa[index],
+                entropy: entropy[index],
+                propensity: signals.iter().map(|signal| signal[index]).sum::<f64>()
+                    / signals.len() as f64,
+            })
+            .collect();
+        Self {
+            residues,
+            names: chain.names().to_vec(),
+        }
+    }
+
+    pub fn propensities(&self) -> Vec<f64> {
+        self.residues
+            .iter()
+            .map(|residue| residue.propensity)
+            .collect()
+    }
+
+    /// Runs of at least [`MIN_REGION_LENGTH`] residues of one chain at or
+    /// above [`DISORDER_THRESHOLD`], as inclusive 0-based index ranges.
+    pub fn regions(&self) -> Vec<(usize, usize)> {
+        let mut regions = Vec::new();
+        let mut start: Option<usize> = None;
+        for index in 0..=self.residues.len() {
+            let disordered = self.residues.get(index).is_some_and(|residue| {
+                residue.propensity >= DISORDER_THRESHOLD
+                    && start.is_none_or(|first| self.residues[first].chain == residue.chain)
+            });
+            match (start, disordered) {
+                (None, true) => start = Some(index),
+                (Some(first), false) => {
+                    if index - first >= MIN_REGION_LENGTH {
+                        regions.push((first, index - 1));
+                    }
+                    // A disordered residue opening the next chain starts a run
+                    start = self
+                        .residues
+                        .get(index)
+                        .filter(|residue| residue.propensity >= DISORDER_THRESHOLD)
+                        .map(|_| index);
+                }
+                _ => {}
+            }
+        }
+        regions
+    }
+
+    /// Renders one row per residue (1-based residue numbers) with every
+    /// signal and whether the residue falls in a disordered region.
+    pub fn to_csv(&self) -> String {
+        let regions = self.regions();
+        let mut csv = String::from(
+            "residue,chain,name,rmsf,sasa,relative_sasa,entropy,propensity,disordered\n",
+        );
+        for (index, residue) in self.residues.iter().enumerate() {
+            let disordered = regions
+                .iter()
+                .any(|&(first, last)| (first..=last).contains(&index));
+            csv.push_str(&format!(
+                "{},{},{},{:.4},{:.3},{:.4},{:.6},{:.4},{}\n",
+                index + 1,
+                residue.chain,
+                self.names.get(index).map_or("UNK", String::as_str),
+                residue.rmsf,
+                residue.sasa,
+                residue.relative_sasa,
+                residue.entropy,
+                residue.propensity,
+                u8::from(disordered)
+            ));
+        }
+        csv
+    }
+}
+
+/// Bead radius (Å) of a residue named `name` in the surface calculation.
+pub fn bead_radius(name: &str) -> f64 {
+    match side_chain_reach(name) {
+        Some(reach) => BACKBONE_BEAD_RADIUS + 0.5 * (CA_CB_DISTANCE + reach),
+        None => BACKBONE_BEAD_RADIUS,
+    }
+}
+
+/// Solvent-accessible surface (Å²) of each bead (Shrake–Rupley): the share
+/// of points on its probe-inflated sphere that no other inflated sphere
+/// covers.
+pub fn residue_sasa(positions: &[[f64; 3]], radii: &[f64]) -> Vec<f64> {
+    let sphere = unit_sphere(SURFACE_POINTS);
+    let inflated: Vec<f64> = radii.iter().map(|radius| radius + PROBE_RADIUS).collect();
+    positions
+        .iter()
+        .zip(&inflated)
+        .enumerate()
+        .map(|(index, (center, &radius))| {
+            let neighbours: Vec<usize> = (0..positions.len())
+                .filter(|&other| {
+                    other != index && distance(*center, positions[other]) < radius + inflated[other]
+                })
+                .collect();
+            let exposed = sphere
+                .iter()
+                .filter(|point| {
+                    let point = [
+                        center[0] + radius * point[0],
+                        center[1] + radius * point[1],
+                        center[2] + radius * point[2],
+                    ];
+                    neighbours
+                        .iter()
+                        .all(|&other| distance(point, positions[other]) >= inflated[other])
+                })
+                .count();
+            4.0 * PI * radius * radius * exposed as f64 / sphere.len() as f64
+        })
+        .collect()
+}
+
+/// Accessible surface (Å²) of a lone bead of `radius`.
+fn sphere_area(radius: f64) -> f64 {
+    4.0 * PI * (radius + PROBE_RADIUS).powi(2)
+}
+
+/// `count` points spread evenly over the unit sphere (golden spiral).
+fn unit_sphere(count: usize) -> Vec<[f64; 3]> {
+    let golden_angle = PI * (3.0 - 5.0f64.sqrt());
+    (0..count)
+        .map(|index| {
+            let z = 1.0 - (2.0 * index as f64 + 1.0) / count as f64;
+            let ring = (1.0 - z * z).sqrt();
+            let theta = golden_angle * index as f64;
+            [ring * theta.cos(), ring * theta.sin(), z]
+        })
+        .collect()
+}
+
+/// `values` over `reference`, clamped to 0–1.
+fn scaled(values: &[f64], reference: f64) -> Vec<f64> {
+    values
+        .iter()
+        .map(|value| (value / reference).clamp(0.0, 1.0))
+        .collect()
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposed_mobile_tails_score_as_disordered() {
+        // A compact knot of six residues followed by a loose, moving tail
+        let mut positions: Vec<[f64; 3]> = vec![
+            [0.0, 0.0, 0.0],
+            [3.8, 0.0, 0.0],
+            [3.8, 3.8, 0.0],
+            [0.0, 3.8, 0.0],
+            [0.0, 3.8, 3.8],
+            [3.8, 3.8, 3.8],
+        ];
+        positions.extend((1..=6).map(|step| [3.8 + 3.8 * step as f64, 3.8, 3.8]));
+        let mut chain = PeptideChain::from_sequence("LLLLLLKEKEKE");
+        chain.positions_mut().copy_from_slice(&positions);
+        let mut moved = positions.clone();
+        for position in &mut moved[6..] {
+            position[2] += 6.0;
+        }
+        let frames = [positions, moved].map(|positions| TrajectoryFrame {
+            time_ms: 0.0,
+            positions,
+        });
+
+        let profile = DisorderProfile::new(&chain, &[], &frames);
+        let propensities = profile.propensities();
+        assert!(
+            propensities[..6]
+                .iter()
+                .all(|&value| value < DISORDER_THRESHOLD)
+        );
+        assert!(
+            propensities[6..]
+                .iter()
+                .all(|&value| value >= DISORDER_THRESHOLD)
+        );
+        assert_eq!(profile.regions(), [(6, 11)]);
+        assert!(profile.to_csv().ends_with(",1\n"));
+
+        // A lone bead keeps its whole surface; a buried one loses most of it
+        let radius = bead_radius("LYS");
+        let area = residue_sasa(&[[0.0; 3]], &[radius])[0];
+        assert!((area / sphere_area(radius) - 1.0).abs() < 1e-12);
+        assert!(profile.residues[1].relative_sasa < profile.residues[11].relative_sasa);
+        assert_eq!(bead_radius("GLY"), BACKBONE_BEAD_RADIUS);
+    }
+
+    #[test]
+    fn ordered_chains_report_no_disordered_regions() {
+        // A compact cube of beads, some moving a little more than others
+        let positions: Vec<[f64; 3]> = (0..8)
+            .map(|corner| {
+                let bit = |shift: usize| 3.8 * ((corner >> shift) & 1) as f64;
+                [bit(0), bit(1), bit(2)]
+            })
+            .collect();
+        let mut chain = PeptideChain::from_sequence("LLLLLLLL");
+        chain.positions_mut().copy_from_slice(&positions);
+        let mut jiggled = positions.clone();
+        for (index, position) in jiggled.iter_mut().enumerate() {
+            position[0] += 0.05 * index as f64;
+        }
+        let frames = [positions, jiggled].map(|positions| TrajectoryFrame {
+            time_ms: 0.0,
+            positions,
+        });
+
+        let profile = DisorderProfile::new(&chain, &[], &frames);
+        assert!((profile.residues[7].rmsf - 0.175).abs() < 1e-9);
+        assert!(
+            profile
+                .propensities()
+                .iter()
+                .all(|&value| value < DISORDER_THRESHOLD)
+        );
+        assert!(profile.regions().is_empty());
+    }
+}
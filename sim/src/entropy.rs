@@ -0,0 +1,90 @@
+use folding_core::TrajectoryFrame;
+use nalgebra::{DMatrix, SymmetricEigen};
+
+use crate::similarity::Superposition;
+
+/// Boltzmann constant in kcal·mol⁻¹·K⁻¹.
+pub(crate) const KB: f64 = 0.0019872041;
+
+/// `k_B e² / ħ²` in (amu·Å²·K)⁻¹, the Schlitter prefactor per kelvin.
+const SCHLITTER_PREFACTOR: f64 = 0.15233;
+
+/// Cα bead mass (amu), matching the physics integrators.
+const BEAD_MASS: f64 = 12.0;
+
+/// Schlitter's quasi-harmonic estimate of the conformational entropy
+/// (kcal·mol⁻¹·K⁻¹) sampled by `frames`, `½ k_B ln det(1 + k_B T e² M σ / ħ²)`
+/// over the Cα covariance `σ`. Frames are fitted onto the first one to remove
+/// rigid-body motion. `None` with fewer than two frames or three residues.
+pub fn quasi_harmonic_entropy(frames: &[TrajectoryFrame], temperature: f64) -> Option<f64> {
+    let first = frames.first()?;
+    let count = first.positions.len();
+    if count < 3 {
+        return None;
+    }
+    let fitted = frames
+        .iter()
+        .filter(|frame| frame.positions.len() == count)
+        .map(|frame| {
+            Superposition::fit(&frame.positions, &first.positions)
+                .map(|fit| fit.apply_all(&frame.positions))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fitted.len() < 2 {
+        return None;
+    }
+
+    let samples = fitted.len();
+    let dimension = 3 * count;
+    let mut deviations = DMatrix::from_fn(samples, dimension, |row, column| {
+        fitted[row][column / 3][column % 3]
+    });
+    for column in 0..dimension {
+        let mean = deviations.column(column).mean();
+        deviations.column_mut(column).add_scalar_mut(-mean);
+    }
+    // σ = XᵀX / n shares its non-zero eigenvalues with the much smaller XXᵀ / n
+    // whenever there are fewer frames than coordinates.
+    let scatter = if samples < dimension {
+        &deviations * deviations.transpose()
+    } else {
+        deviations.transpose() * &deviations
+    } / samples as f64;
+
+    let factor = SCHLITTER_PREFACTOR * temperature.max(0.0) * BEAD_MASS;
+    let log_det: f64 = SymmetricEigen::new(scatter)
+        .eigenvalues
+        .iter()
+        .map(|eigenvalue| (1.0 + factor * eigenvalue.max(0.0)).ln())
+        .sum();
+    Some(0.5 * KB * log_det)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(amplitude: f64) -> Vec<TrajectoryFrame> {
+        (0..12)
+            .map(|step| TrajectoryFrame {
+                time_ms: step as f64,
+                positions: (0..6)
+                    .map(|residue| {
+                        let wobble = amplitude * ((step * 7 + residue * 3) as f64).sin();
+                        [residue as f64 * 3.8, wobble, (residue % 2) as f64 * 1.5]
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn entropy_grows_with_fluctuations() {
+        let rigid = quasi_harmonic_entropy(&frames(0.0), 300.0).unwrap();
+        let loose = quasi_harmonic_entropy(&frames(0.5), 300.0).unwrap();
+        let floppy = quasi_harmonic_entropy(&frames(2.0), 300.0).unwrap();
+        assert!(rigid.abs() < 1e-9);
+        assert!(loose > 0.0 && floppy > loose);
+        assert!(quasi_harmonic_entropy(&frames(1.0)[..1], 300.0).is_none());
+    }
+}
@@ -0,0 +1,669 @@
+use std::fs;
+use std::path::Path;
+
+use folding_core::TrajectoryFrame;
+
+const TRR_MAGIC: i32 = 1993;
+const XTC_MAGIC: i32 = 1995;
+
+/// GROMACS lengths are in nm; LogLineFold works in Å.
+const NM_TO_ANGSTROM: f64 = 10.0;
+
+/// Smallest index into [`MAGIC_INTS`] used for run-length encoded deltas.
+const FIRST_IDX: usize = 9;
+
+/// Integer ranges used by the xtc compressor, from GROMACS `libxdrf`.
+const MAGIC_INTS: [u32; 73] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 10, 12, 16, 20, 25, 32, 40, 50, 64, 80, 101, 128, 161, 203, 256,
+    322, 406, 512, 645, 812, 1024, 1290, 1625, 2048, 2580, 3250, 4096, 5060, 6501, 8192, 10321,
+    13003, 16384, 20642, 26007, 32768, 41285, 52015, 65536, 82570, 104031, 131072, 165140, 208063,
+    262144, 330280, 416127, 524287, 660561, 832255, 1048576, 1321122, 1664510, 2097152, 2642245,
+    3329021, 4194304, 5284491, 6658042, 8388607, 10568983, 13316085, 16777216,
+];
+
+/// One frame of a GROMACS trajectory with coordinates converted to Å.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GromacsFrame {
+    pub step: i64,
+    pub time_ps: f64,
+    /// Box vectors (Å), one per row; `None` when the frame stores no box.
+    pub box_vectors: Option<[[f64; 3]; 3]>,
+    pub positions: Vec<[f64; 3]>,
+}
+
+impl GromacsFrame {
+    pub fn to_trajectory_frame(&self) -> TrajectoryFrame {
+        TrajectoryFrame {
+            time_ms: self.time_ps * 1e-9,
+            positions: self.positions.clone(),
+        }
+    }
+}
+
+/// Reads a `.trr` or `.xtc` file, picked by extension.
+pub fn load_gromacs_trajectory(path: &Path) -> Result<Vec<GromacsFrame>, String> {
+    let bytes = fs::read(path)
+        .map_err(|err| format!("failed to read trajectory {}: {err}", path.display()))?;
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+    let frames = match extension.as_deref() {
+        Some("trr") => read_trr(&bytes),
+        Some("xtc") => read_xtc(&bytes),
+        _ => Err("expected a .trr or .xtc file".to_string()),
+    };
+    frames.map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Whether `path` names a GROMACS trajectory this module can read.
+pub fn is_gromacs_trajectory(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case("trr") || ext.eq_ignore_ascii_case("xtc"))
+        .unwrap_or(false)
+}
+
+/// Full-precision `.trr` frames, single or double precision. Frames that carry
+/// only velocities or forces are skipped.
+pub fn read_trr(bytes: &[u8]) -> Result<Vec<GromacsFrame>, String> {
+    let mut xdr = XdrReader::new(bytes);
+    let mut frames = Vec::new();
+    while !xdr.is_empty() {
+        let magic = xdr.int()?;
+        if magic != TRR_MAGIC {
+            return Err(format!("bad trr magic {magic} in frame {}", frames.len()));
+        }
+        let _version_length = xdr.int()?;
+        let version = xdr.string()?;
+        if !version.starts_with("GMX_trn_file") {
+            return Err(format!("unexpected trr version '{version}'"));
+        }
+        let mut sizes = [0usize; 13];
+        for size in sizes.iter_mut() {
+            *size = usize::try_from(xdr.int()?).map_err(|_| "negative trr block size")?;
+        }
+        let [
+            ir,
+            energy,
+            box_size,
+            virial,
+            pressure,
+            topology,
+            symmetry,
+            x,
+            v,
+            f,
+            atoms,
+            step,
+            _nre,
+        ] = sizes;
+        let real = if box_size > 0 {
+            box_size / 9
+        } else if atoms > 0 && x + v + f > 0 {
+            x.max(v).max(f) / (3 * atoms)
+        } else {
+            4
+        };
+        let double = match real {
+            4 => false,
+            8 => true,
+            other => return Err(format!("unsupported trr real size {other}")),
+        };
+        let time_ps = xdr.real(double)?;
+        let _lambda = xdr.real(double)?;
+        xdr.skip(ir + energy)?;
+        let box_vectors = if box_size > 0 {
+            Some(read_box(&mut xdr, double)?)
+        } else {
+            None
+        };
+        xdr.skip(virial + pressure + topology + symmetry)?;
+        let positions = if x > 0 {
+            let mut positions = Vec::with_capacity(atoms);
+            for _ in 0..atoms {
+                positions.push([
+                    xdr.real(double)? * NM_TO_ANGSTROM,
+                    xdr.real(double)? * NM_TO_ANGSTROM,
+                    xdr.real(double)? * NM_TO_ANGSTROM,
+                ]);
+            }
+            Some(positions)
+        } else {
+            None
+        };
+        xdr.skip(v + f)?;
+        if let Some(positions) = positions {
+            frames.push(GromacsFrame {
+                step: step as i64,
+                time_ps,
+                box_vectors,
+                positions,
+            });
+        }
+    }
+    Ok(frames)
+}
+
+/// Compressed `.xtc` frames. Coordinates keep the file's precision
+/// (usually 0.01 Å).
+pub fn read_xtc(bytes: &[u8]) -> Result<Vec<GromacsFrame>, String> {
+    let mut xdr = XdrReader::new(bytes);
+    let mut frames = Vec::new();
+    while !xdr.is_empty() {
+        let magic = xdr.int()?;
+        if magic != XTC_MAGIC {
+            return Err(format!("bad xtc magic {magic} in frame {}", frames.len()));
+        }
+        let atoms = usize::try_from(xdr.int()?).map_err(|_| "negative xtc atom count")?;
+        let step = xdr.int()?;
+        let time_ps = xdr.real(false)?;
+        let box_vectors = read_box(&mut xdr, false)?;
+        let positions = read_xtc_coordinates(&mut xdr, atoms)?;
+        frames.push(GromacsFrame {
+            step: step as i64,
+            time_ps,
+            box_vectors: Some(box_vectors),
+            positions,
+        });
+    }
+    Ok(frames)
+}
+
+/// Frames in the shape the analysis functions take.
+pub fn gromacs_trajectory_frames(frames: &[GromacsFrame]) -> Vec<TrajectoryFrame> {
+    frames
+        .iter()
+        .map(GromacsFrame::to_trajectory_frame)
+        .collect()
+}
+
+fn read_box(xdr: &mut XdrReader, double: bool) -> Result<[[f64; 3]; 3], String> {
+    let mut vectors = [[0.0; 3]; 3];
+    for row in vectors.iter_mut() {
+        for value in row.iter_mut() {
+            *value = xdr.real(double)? * NM_TO_ANGSTROM;
+        }
+    }
+    Ok(vectors)
+}
+
+fn read_xtc_coordinates(xdr: &mut XdrReader, atoms: usize) -> Result<Vec<[f64; 3]>, String> {
+    let stored = usize::try_from(xdr.int()?).map_err(|_| "negative xtc atom count")?;
+    if stored != atoms {
+        return Err(format!("xtc frame declares {atoms} and {stored} atoms"));
+    }
+    // Up to nine atoms are stored as plain floats.
+    if atoms <= 9 {
+        let mut positions = Vec::with_capacity(atoms);
+        for _ in 0..atoms {
+            positions.push([
+                xdr.real(false)? * NM_TO_ANGSTROM,
+                xdr.real(false)? * NM_TO_ANGSTROM,
+                xdr.real(false)? * NM_TO_ANGSTROM,
+            ]);
+        }
+        return Ok(positions);
+    }
+
+    let precision = xdr.real(false)?;
+    if precision <= 0.0 {
+        return Err(format!("invalid xtc precision {precision}"));
+    }
+    let scale = NM_TO_ANGSTROM / precision;
+    let mut min_int = [0i32; 3];
+    let mut max_int = [0i32; 3];
+    for value in min_int.iter_mut() {
+        *value = xdr.int()?;
+    }
+    for value in max_int.iter_mut() {
+        *value = xdr.int()?;
+    }
+    let mut size_int = [0u32; 3];
+    for axis in 0..3 {
+        size_int[axis] = max_int[axis].wrapping_sub(min_int[axis]).wrapping_add(1) as u32;
+    }
+    let large = size_int.iter().any(|&size| size > 0xff_ffff);
+    let bit_size_int = size_int.map(size_of_int);
+    let bit_size = if large { 0 } else { size_of_ints(&size_int) };
+
+    let mut small_idx = magic_index(xdr.int()?)?;
+    let mut smaller = MAGIC_INTS[FIRST_IDX.max(small_idx - 1)] as i32 / 2;
+    let mut small_num = MAGIC_INTS[small_idx] as i32 / 2;
+    let mut size_small = [MAGIC_INTS[small_idx]; 3];
+
+    let length = usize::try_from(xdr.int()?).map_err(|_| "negative xtc byte count")?;
+    let mut bits = BitReader::new(xdr.opaque(length)?);
+    let to_angstrom = |coord: [i32; 3]| coord.map(|value| value as f64 * scale);
+
+    let mut positions = Vec::with_capacity(atoms);
+    let mut run = 0u32;
+    while positions.len() < atoms {
+        let mut this = if large {
+            [
+                bits.bits(bit_size_int[0])? as i32,
+                bits.bits(bit_size_int[1])? as i32,
+                bits.bits(bit_size_int[2])? as i32,
+            ]
+        } else {
+            bits.ints(bit_size, &size_int)?
+        };
+        for axis in 0..3 {
+            this[axis] = this[axis].wrapping_add(min_int[axis]);
+        }
+        let mut previous = this;
+
+        let mut is_smaller = 0i32;
+        if bits.bits(1)? == 1 {
+            run = bits.bits(5)?;
+            is_smaller = (run % 3) as i32;
+            run -= is_smaller as u32;
+            is_smaller -= 1;
+        }
+        if run > 0 {
+            for k in (0..run).step_by(3) {
+                let mut next = bits.ints(small_idx as u32, &size_small)?;
+                for axis in 0..3 {
+                    next[axis] += previous[axis] - small_num;
+                }
+                if k == 0 {
+                    // The compressor swaps the first two atoms of a run, which
+                    // packs water molecules better.
+                    std::mem::swap(&mut next, &mut previous);
+                    positions.push(to_angstrom(previous));
+                } else {
+                    previous = next;
+                }
+                positions.push(to_angstrom(next));
+            }
+        } else {
+            positions.push(to_angstrom(this));
+        }
+
+        small_idx = magic_index(small_idx as i32 + is_smaller)?;
+        if is_smaller < 0 {
+            small_num = smaller;
+            smaller = if small_idx > FIRST_IDX {
+                MAGIC_INTS[small_idx - 1] as i32 / 2
+            } else {
+                0
+            };
+        } else if is_smaller > 0 {
+            smaller = small_num;
+            small_num = MAGIC_INTS[small_idx] as i32 / 2;
+        }
+        size_small = [MAGIC_INTS[small_idx]; 3];
+    }
+    if positions.len() != atoms {
+        return Err(format!(
+            "xtc frame decoded {} of {atoms} atoms",
+            positions.len()
+        ));
+    }
+    Ok(positions)
+}
+
+fn magic_index(value: i32) -> Result<usize, String> {
+    usize::try_from(value)
+        .ok()
+        .filter(|&index| (FIRST_IDX..MAGIC_INTS.len()).contains(&index))
+        .ok_or_else(|| format!("corrupt xtc frame (small index {value})"))
+}
+
+/// Bits needed to store integers in `0..size`.
+fn size_of_int(size: u32) -> u32 {
+    let mut bits = 0;
+    let mut num = 1u64;
+    while u64::from(size) >= num && bits < 32 {
+        bits += 1;
+        num <<= 1;
+    }
+    bits
+}
+
+/// Bits needed to store one mixed-radix integer over `sizes`.
+fn size_of_ints(sizes: &[u32; 3]) -> u32 {
+    let mut bytes = vec![1u64];
+    for &size in sizes {
+        let mut carry = 0u64;
+        for byte in bytes.iter_mut() {
+            carry += *byte * u64::from(size);
+            *byte = carry & 0xff;
+            carry >>= 8;
+        }
+        while carry != 0 {
+            bytes.push(carry & 0xff);
+            carry >>= 8;
+        }
+    }
+    let last = bytes.pop().unwrap_or(0);
+    let mut bits = 0;
+    let mut num = 1u64;
+    while last >= num {
+        bits += 1;
+        num <<= 1;
+    }
+    bits + 8 * bytes.len() as u32
+}
+
+/// Big-endian XDR stream: 4-byte integers and floats, opaque data padded to 4.
+struct XdrReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> XdrReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset >= self.bytes.len()
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .offset
+            .checked_add(length)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| format!("trajectory truncated at byte {}", self.offset))?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn skip(&mut self, length: usize) -> Result<(), String> {
+        self.take(length).map(|_| ())
+    }
+
+    fn int(&mut self) -> Result<i32, String> {
+        let bytes = self.take(4)?;
+        Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn real(&mut self, double: bool) -> Result<f64, String> {
+        if double {
+            let bytes = self.take(8)?;
+            let mut raw = [0u8; 8];
+            raw.copy_from_slice(bytes);
+            Ok(f64::from_be_bytes(raw))
+        } else {
+            let bytes = self.take(4)?;
+            Ok(f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64)
+        }
+    }
+
+    fn opaque(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let data = self.take(length)?;
+        self.skip((4 - length % 4) % 4)?;
+        Ok(data)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let length = usize::try_from(self.int()?).map_err(|_| "negative string length")?;
+        Ok(String::from_utf8_lossy(self.opaque(length)?).into_owned())
+    }
+}
+
+/// Most-significant-bit-first reader over the xtc coordinate payload.
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    last_bits: u32,
+    last_byte: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            last_bits: 0,
+            last_byte: 0,
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<u32, String> {
+        let byte = self
+            .bytes
+            .get(self.offset)
+            .ok_or_else(|| "xtc coordinate data truncated".to_string())?;
+        self.offset += 1;
+        Ok(u32::from(*byte))
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32, String> {
+        let mask = if count >= 32 {
+            u32::MAX
+        } else {
+            (1u32 << count) - 1
+        };
+        let mut remaining = count;
+        let mut num = 0u32;
+        while remaining >= 8 {
+            self.last_byte = (self.last_byte << 8) | self.next_byte()?;
+            num |= (self.last_byte >> self.last_bits).wrapping_shl(remaining - 8);
+            remaining -= 8;
+        }
+        if remaining > 0 {
+            if self.last_bits < remaining {
+                self.last_bits += 8;
+                self.last_byte = (self.last_byte << 8) | self.next_byte()?;
+            }
+            self.last_bits -= remaining;
+            num |= (self.last_byte >> self.last_bits) & ((1u32 << remaining) - 1);
+        }
+        Ok(num & mask)
+    }
+
+    /// Three integers packed as one mixed-radix number of `count` bits.
+    fn ints(&mut self, count: u32, sizes: &[u32; 3]) -> Result<[i32; 3], String> {
+        let mut bytes = Vec::with_capacity(12);
+        let mut remaining = count;
+        while remaining > 8 {
+            bytes.push(self.bits(8)? as u64);
+            remaining -= 8;
+        }
+        if remaining > 0 {
+            bytes.push(self.bits(remaining)? as u64);
+        }
+        let mut nums = [0i32; 3];
+        for axis in (1..3).rev() {
+            let size = u64::from(sizes[axis].max(1));
+            let mut num = 0u64;
+            for byte in bytes.iter_mut().rev() {
+                num = (num << 8) | *byte;
+                *byte = num / size;
+                num %= size;
+            }
+            nums[axis] = num as i32;
+        }
+        nums[0] = bytes
+            .iter()
+            .take(4)
+            .enumerate()
+            .fold(0u32, |acc, (index, byte)| {
+                acc | ((*byte as u32) << (8 * index))
+            }) as i32;
+        Ok(nums)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put_int(buf: &mut Vec<u8>, value: i32) {
+        buf.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn put_float(buf: &mut Vec<u8>, value: f32) {
+        buf.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn trr_frame(step: i32, positions: &[[f64; 3]], double: bool) -> Vec<u8> {
+        let real = if double { 8 } else { 4 };
+        let put_real = |buf: &mut Vec<u8>, value: f64| {
+            if double {
+                buf.extend_from_slice(&value.to_be_bytes());
+            } else {
+                buf.extend_from_slice(&(value as f32).to_be_bytes());
+            }
+        };
+        let mut buf = Vec::new();
+        put_int(&mut buf, TRR_MAGIC);
+        put_int(&mut buf, 13);
+        put_int(&mut buf, 12);
+        buf.extend_from_slice(b"GMX_trn_file");
+        let atoms = positions.len() as i32;
+        let x_size = 3 * real * atoms;
+        for size in [
+            0,
+            0,
+            9 * real,
+            0,
+            0,
+            0,
+            0,
+            x_size,
+            x_size,
+            0,
+            atoms,
+            step,
+            0,
+        ] {
+            put_int(&mut buf, size);
+        }
+        put_real(&mut buf, step as f64 * 2.0);
+        put_real(&mut buf, 0.0);
+        for value in [5.0, 0.0, 0.0, 0.0, 5.0, 0.0, 0.0, 0.0, 5.0] {
+            put_real(&mut buf, value);
+        }
+        for position in positions.iter().chain(positions) {
+            for value in position {
+                put_real(&mut buf, *value);
+            }
+        }
+        buf
+    }
+
+    /// xtc frame using the compressed layout with plain (non-run) atoms.
+    fn compressed_xtc_frame(coords: &[[i32; 3]], precision: f32) -> Vec<u8> {
+        let min = [0, 0, 0];
+        let max = [1000, 2000, 3000];
+        let sizes = [1001u32, 2001, 3001];
+        let bit_size = size_of_ints(&sizes);
+        let mut bits: Vec<bool> = Vec::new();
+        for coord in coords {
+            // Mixed radix: the last axis is the least significant digit.
+            let number = coord[2] as u64
+                + u64::from(sizes[2]) * (coord[1] as u64 + u64::from(sizes[1]) * coord[0] as u64);
+            let mut remaining = bit_size;
+            let mut shift = 0;
+            while remaining > 0 {
+                let width = remaining.min(8);
+                let chunk = (number >> shift) & ((1 << width) - 1);
+                for bit in (0..width).rev() {
+                    bits.push(chunk >> bit & 1 == 1);
+                }
+                shift += 8;
+                remaining -= width;
+            }
+            bits.push(false);
+        }
+        let mut payload = vec![0u8; bits.len().div_ceil(8)];
+        for (index, bit) in bits.iter().enumerate() {
+            if *bit {
+                payload[index / 8] |= 0x80 >> (index % 8);
+            }
+        }
+
+        let mut buf = Vec::new();
+        put_int(&mut buf, XTC_MAGIC);
+        put_int(&mut buf, coords.len() as i32);
+        put_int(&mut buf, 7);
+        put_float(&mut buf, 1.5);
+        for value in [4.0, 0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 0.0, 4.0] {
+            put_float(&mut buf, value);
+        }
+        put_int(&mut buf, coords.len() as i32);
+        put_float(&mut buf, precision);
+        min.iter().chain(&max).for_each(|v| put_int(&mut buf, *v));
+        put_int(&mut buf, FIRST_IDX as i32);
+        put_int(&mut buf, payload.len() as i32);
+        buf.extend_from_slice(&payload);
+        buf.resize(buf.len().div_ceil(4) * 4, 0);
+        buf
+    }
+
+    /// xtc frame of at most nine atoms, which GROMACS stores as plain floats.
+    fn small_xtc_frame(step: i32, positions: &[[f32; 3]]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        put_int(&mut buf, XTC_MAGIC);
+        put_int(&mut buf, positions.len() as i32);
+        put_int(&mut buf, step);
+        put_float(&mut buf, step as f32 * 0.002);
+        for value in [3.0, 0.0, 0.0, 0.0, 3.0, 0.0, 0.0, 0.0, 3.0] {
+            put_float(&mut buf, value);
+        }
+        put_int(&mut buf, positions.len() as i32);
+        positions
+            .iter()
+            .flatten()
+            .for_each(|v| put_float(&mut buf, *v));
+        buf
+    }
+
+    #[test]
+    fn reads_trr_and_xtc_frames_in_angstrom() {
+        let positions = [[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]];
+        for double in [false, true] {
+            let mut bytes = trr_frame(0, &positions, double);
+            bytes.extend(trr_frame(10, &positions, double));
+            let frames = read_trr(&bytes).unwrap();
+            assert_eq!(frames.len(), 2);
+            assert_eq!(frames[1].step, 10);
+            assert!((frames[1].time_ps - 20.0).abs() < 1e-6);
+            assert!((frames[0].positions[1][2] - 6.0).abs() < 1e-5);
+            assert!((frames[0].box_vectors.unwrap()[2][2] - 50.0).abs() < 1e-5);
+        }
+        assert!(read_trr(&trr_frame(0, &positions, false)[..40]).is_err());
+
+        let coords: Vec<[i32; 3]> = (0..12).map(|i| [10 * i, 2000 - i, 3 * i + 1]).collect();
+        let frames = read_xtc(&compressed_xtc_frame(&coords, 1000.0)).unwrap();
+        assert_eq!(frames[0].step, 7);
+        assert_eq!(frames[0].positions.len(), 12);
+        for (decoded, coord) in frames[0].positions.iter().zip(&coords) {
+            for axis in 0..3 {
+                assert!((decoded[axis] - coord[axis] as f64 / 100.0).abs() < 1e-9);
+            }
+        }
+        assert_eq!(gromacs_trajectory_frames(&frames)[0].positions.len(), 12);
+    }
+
+    #[test]
+    fn exported_xtc_files_load_by_extension() {
+        // A Cα-only export of a short peptide, as `gmx trjconv` writes it
+        let trace: Vec<[f32; 3]> = (0..5).map(|i| [0.38 * i as f32, 0.1, 0.2]).collect();
+        let moved: Vec<[f32; 3]> = trace.iter().map(|p| [p[0], p[1] + 0.05, p[2]]).collect();
+        let mut bytes = small_xtc_frame(0, &trace);
+        bytes.extend(small_xtc_frame(500, &moved));
+
+        let dir = std::env::temp_dir().join(format!("logline_xtc_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ca.XTC");
+        fs::write(&path, &bytes).unwrap();
+        assert!(is_gromacs_trajectory(&path));
+        let frames = load_gromacs_trajectory(&path).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].step, 500);
+        assert!((frames[1].time_ps - 1.0).abs() < 1e-6);
+        assert!((frames[0].positions[4][0] - 15.2).abs() < 1e-5);
+        assert!((frames[1].positions[2][1] - 1.5).abs() < 1e-5);
+        assert!((frames[0].box_vectors.unwrap()[1][1] - 30.0).abs() < 1e-5);
+
+        // A truncated export names the file it came from
+        fs::write(&path, &bytes[..bytes.len() - 4]).unwrap();
+        let err = load_gromacs_trajectory(&path).unwrap_err();
+        assert!(err.starts_with(&path.display().to_string()), "{err}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,518 @@
+use folding_core::TrajectoryFrame;
+use folding_core::target_contract::dihedral_degrees;
+
+use crate::similarity::Superposition;
+
+/// Per-residue quantity whose time series is compared between residues.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MotionSignal {
+    /// Distance of the Cα from its mean position after fitting each frame
+    /// onto the first.
+    Displacement,
+    /// Cα pseudo-dihedral through residues `i-1..=i+2`, in degrees.
+    Dihedral,
+}
+
+impl MotionSignal {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "displacement" => Some(MotionSignal::Displacement),
+            "dihedral" => Some(MotionSignal::Dihedral),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MotionSignal::Displacement => "displacement",
+            MotionSignal::Dihedral => "dihedral",
+        }
+    }
+}
+
+/// Symmetric residue × residue mutual information in bits; the diagonal holds
+/// each residue's own binned entropy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MutualInformationMatrix {
+    pub signal: MotionSignal,
+    pub values: Vec<Vec<f64>>,
+}
+
+impl MutualInformationMatrix {
+    pub fn residue_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Off-diagonal pairs sorted by decreasing mutual information.
+    pub fn strongest_pairs(&self, limit: usize) -> Vec<(usize, usize, f64)> {
+        let mut pairs: Vec<(usize, usize, f64)> = (0..self.residue_count())
+            .flat_map(|i| ((i + 1)..self.residue_count()).map(move |j| (i, j)))
+            .map(|(i, j)| (i, j, self.values[i][j]))
+            .collect();
+        pairs.sort_by(|a, b| b.2.total_cmp(&a.2));
+        pairs.truncate(limit);
+        pairs
+    }
+
+    /// Square CSV with a `residue` header column and 0-based residue indices.
+    pub fn to_csv(&self) -> String {
+        matrix_csv(&self.values)
+    }
+
+    /// Most strongly coupled route between two residues; see [`coupling_pathway`].
+    pub fn pathway(&self, source: usize, target: usize) -> Option<CouplingPathway> {
+        coupling_pathway(&self.values, source, target)
+    }
+}
+
+/// Directed transfer entropy in bits: `values[i][j]` is the information the
+/// past of residue `i` adds about the next step of residue `j`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferEntropyMatrix {
+    pub signal: MotionSignal,
+    pub values: Vec<Vec<f64>>,
+}
+
+impl TransferEntropyMatrix {
+    pub fn residue_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Directed links `(source, target, bits)` sorted by decreasing transfer.
+    pub fn strongest_links(&self, limit: usize) -> Vec<(usize, usize, f64)> {
+        let count = self.residue_count();
+        let mut links: Vec<(usize, usize, f64)> = (0..count)
+            .flat_map(|i| (0..count).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| (i, j, self.values[i][j]))
+            .collect();
+        links.sort_by(|a, b| b.2.total_cmp(&a.2));
+        links.truncate(limit);
+        links
+    }
+
+    /// Rows are sources, columns are targets.
+    pub fn to_csv(&self) -> String {
+        matrix_csv(&self.values)
+    }
+
+    /// Most likely signal-propagation route from `source` to `target`.
+    pub fn pathway(&self, source: usize, target: usize) -> Option<CouplingPathway> {
+        coupling_pathway(&self.values, source, target)
+    }
+}
+
+/// Route through the coupling graph returned by [`coupling_pathway`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CouplingPathway {
+    pub residues: Vec<usize>,
+    /// Sum of `-ln(w / w_max)` over the edges taken.
+    pub cost: f64,
+    /// Weakest coupling along the route.
+    pub bottleneck: f64,
+}
+
+/// One time series per residue over the frames sharing the first frame's
+/// residue count. Residues without a defined value (chain ends for
+/// dihedrals) get a constant series.
+pub fn residue_series(frames: &[TrajectoryFrame], signal: MotionSignal) -> Vec<Vec<f64>> {
+    let Some(first) = frames.first() else {
+        return Vec::new();
+    };
+    let count = first.positions.len();
+    let usable: Vec<Vec<[f64; 3]>> = frames
+        .iter()
+        .filter(|frame| frame.positions.len() == count)
+        .map(|frame| match signal {
+            MotionSignal::Displacement => Superposition::fit(&frame.positions, &first.positions)
+                .map(|fit| fit.apply_all(&frame.positions))
+                .unwrap_or_else(|| frame.positions.clone()),
+            MotionSignal::Dihedral => frame.positions.clone(),
+        })
+        .collect();
+    match signal {
+        MotionSignal::Displacement => {
+            let n = usable.len() as f64;
+            (0..count)
+                .map(|residue| {
+                    let mut mean = [0.0; 3];
+                    for positions in &usable {
+                        for (axis, value) in mean.iter_mut().enumerate() {
+                            *value += positions[residue][axis] / n;
+                        }
+                    }
+                    usable
+                        .iter()
+                        .map(|positions| distance(positions[residue], mean))
+                        .collect()
+                })
+                .collect()
+        }
+        MotionSignal::Dihedral => (0..count)
+            .map(|residue| {
+                usable
+                    .iter()
+                    .map(|positions| {
+                        if residue == 0 || residue + 2 >= count {
+                            return 0.0;
+                        }
+                        dihedral_degrees(
+                            positions[residue - 1],
+                            positions[residue],
+                            positions[residue + 1],
+                            positions[residue + 2],
+                        )
+                    })
+                    .collect()
+            })
+            .map(unwrapped_around_mean)
+            .collect(),
+    }
+}
+
+/// `angles` (degrees) shifted by whole turns to lie within 180° of their
+/// circular mean, so a dihedral rocking across ±180° keeps one narrow range
+/// instead of splitting between the two ends of the bins.
+fn unwrapped_around_mean(angles: Vec<f64>) -> Vec<f64> {
+    let (sin, cos) = angles.iter().fold((0.0, 0.0), |(sin, cos), angle: &f64| {
+        let radians = angle.to_radians();
+        (sin + radians.sin(), cos + radians.cos())
+    });
+    let mean = f64::atan2(sin, cos).to_degrees();
+    angles
+        .into_iter()
+        .map(|angle| mean + (angle - mean + 180.0).rem_euclid(360.0) - 180.0)
+        .collect()
+}
+
+/// Histogram estimate of `I(X;Y)` in bits using equal-width bins over each
+/// series' range.
+pub fn mutual_information(x: &[f64], y: &[f64], bins: usize) -> f64 {
+    let samples = x.len().min(y.len());
+    if samples == 0 || bins == 0 {
+        return 0.0;
+    }
+    let x_bins = discretize(&x[..samples], bins);
+    let y_bins = discretize(&y[..samples], bins);
+    let mut joint = vec![0usize; bins * bins];
+    let mut x_counts = vec![0usize; bins];
+    let mut y_counts = vec![0usize; bins];
+    for (&a, &b) in x_bins.iter().zip(&y_bins) {
+        joint[a * bins + b] += 1;
+        x_counts[a] += 1;
+        y_counts[b] += 1;
+    }
+    let n = samples as f64;
+    let mut information = 0.0;
+    for a in 0..bins {
+        for b in 0..bins {
+            let count = joint[a * bins + b];
+            if count == 0 {
+                continue;
+            }
+            let p_xy = count as f64 / n;
+            let p_x = x_counts[a] as f64 / n;
+            let p_y = y_counts[b] as f64 / n;
+            information += p_xy * (p_xy / (p_x * p_y)).log2();
+        }
+    }
+    information.max(0.0)
+}
+
+/// Mutual information between every pair of residue series, with
+/// `√frames` bins (between 2 and 16). `None` with fewer than two frames.
+pub fn mutual_information_matrix(
+    frames: &[TrajectoryFrame],
+    signal: MotionSignal,
+) -> Option<MutualInformationMatrix> {
+    let series = residue_series(frames, signal);
+    let samples = series.first().map_or(0, Vec::len);
+    if samples < 2 {
+        return None;
+    }
+    let bins = ((samples as f64).sqrt().ceil() as usize).clamp(2, 16);
+    let count = series.len();
+    let mut values = vec![vec![0.0; count]; count];
+    for i in 0..count {
+        for j in i..count {
+            let information = mutual_information(&series[i], &series[j], bins);
+            values[i][j] = information;
+            values[j][i] = information;
+        }
+    }
+    Some(MutualInformationMatrix { signal, values })
+}
+
+/// Lag-one transfer entropy `T(X→Y)` in bits,
+/// `Σ p(y', y, x) log₂ p(y' | y, x) / p(y' | y)`, from equal-width bins.
+pub fn transfer_entropy(source: &[f64], target: &[f64], bins: usize) -> f64 {
+    let samples = source.len().min(target.len());
+    if samples < 2 || bins == 0 {
+        return 0.0;
+    }
+    let x = discretize(&source[..samples], bins);
+    let y = discretize(&target[..samples], bins);
+    let mut next_past_source = vec![0usize; bins * bins * bins];
+    let mut past_source = vec![0usize; bins * bins];
+    let mut next_past = vec![0usize; bins * bins];
+    let mut past = vec![0usize; bins];
+    for t in 0..samples - 1 {
+        let (next, now, driver) = (y[t + 1], y[t], x[t]);
+        next_past_source[(next * bins + now) * bins + driver] += 1;
+        past_source[now * bins + driver] += 1;
+        next_past[next * bins + now] += 1;
+        past[now] += 1;
+    }
+    let transitions = (samples - 1) as f64;
+    let mut transfer = 0.0;
+    for next in 0..bins {
+        for now in 0..bins {
+            for driver in 0..bins {
+                let joint = next_past_source[(next * bins + now) * bins + driver];
+                if joint == 0 {
+                    continue;
+                }
+                let ratio = (joint * past[now]) as f64
+                    / (past_source[now * bins + driver] * next_past[next * bins + now]) as f64;
+                transfer += joint as f64 / transitions * ratio.log2();
+            }
+        }
+    }
+    transfer.max(0.0)
+}
+
+/// Transfer entropy between every ordered pair of residue series, with
+/// `∛frames` bins (between 2 and 8). `None` with fewer than three frames.
+pub fn transfer_entropy_matrix(
+    frames: &[TrajectoryFrame],
+    signal: MotionSignal,
+) -> Option<TransferEntropyMatrix> {
+    let series = residue_series(frames, signal);
+    let samples = series.first().map_or(0, Vec::len);
+    if samples < 3 {
+        return None;
+    }
+    let bins = ((samples as f64).cbrt().ceil() as usize).clamp(2, 8);
+    let count = series.len();
+    let values = (0..count)
+        .map(|i| {
+            (0..count)
+                .map(|j| {
+                    if i == j {
+                        0.0
+                    } else {
+                        transfer_entropy(&series[i], &series[j], bins)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    Some(TransferEntropyMatrix { signal, values })
+}
+
+/// Dijkstra over the graph whose edge `i → j` exists when `weights[i][j] > 0`
+/// and costs `-ln(w / w_max)`, so the cheapest route is the one with the
+/// strongest couplings. `None` when `target` is unreachable.
+pub fn coupling_pathway(
+    weights: &[Vec<f64>],
+    source: usize,
+    target: usize,
+) -> Option<CouplingPathway> {
+    let count = weights.len();
+    if source >= count || target >= count {
+        return None;
+    }
+    let strongest = weights
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .filter(move |(j, _)| *j != i)
+                .map(|(_, weight)| *weight)
+        })
+        .fold(0.0, f64::max);
+    if strongest <= 0.0 {
+        return (source == target).then(|| CouplingPathway {
+            residues: vec![source],
+            cost: 0.0,
+            bottleneck: 0.0,
+        });
+    }
+    // Keep every edge strictly positive so ties prefer fewer hops.
+    let scale = strongest * (1.0 + 1e-6);
+    let mut cost = vec![f64::INFINITY; count];
+    let mut previous: Vec<Option<usize>> = vec![None; count];
+    let mut settled = vec![false; count];
+    cost[source] = 0.0;
+    while let Some(current) = (0..count)
+        .filter(|&node| !settled[node] && cost[node].is_finite())
+        .min_by(|&a, &b| cost[a].total_cmp(&cost[b]))
+    {
+        if current == target {
+            break;
+        }
+        settled[current] = true;
+        for next in 0..count {
+            let weight = weights[current][next];
+            if next == current || settled[next] || weight <= 0.0 {
+                continue;
+            }
+            let candidate = cost[current] - (weight / scale).ln();
+            if candidate < cost[next] {
+                cost[next] = candidate;
+                previous[next] = Some(current);
+            }
+        }
+    }
+    if !cost[target].is_finite() {
+        return None;
+    }
+    let mut residues = vec![target];
+    while let Some(step) = previous[*residues.last()?] {
+        residues.push(step);
+    }
+    residues.reverse();
+    let bottleneck = residues
+        .windows(2)
+        .map(|pair| weights[pair[0]][pair[1]])
+        .fold(f64::INFINITY, f64::min);
+    Some(CouplingPathway {
+        cost: cost[target],
+        bottleneck: if bottleneck.is_finite() {
+            bottleneck
+        } else {
+            0.0
+        },
+        residues,
+    })
+}
+
+/// Square CSV with a `residue` header column and 0-based residue indices.
+fn matrix_csv(values: &[Vec<f64>]) -> String {
+    let mut csv = String::from("residue");
+    for index in 0..values.len() {
+        csv.push_str(&format!(",{index}"));
+    }
+    csv.push('\n');
+    for (index, row) in values.iter().enumerate() {
+        csv.push_str(&index.to_string());
+        for value in row {
+            csv.push_str(&format!(",{value:.6}"));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Bin index per sample; a constant series falls entirely into bin 0.
+fn discretize(series: &[f64], bins: usize) -> Vec<usize> {
+    let min = series.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = series.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = (max - min) / bins as f64;
+    series
+        .iter()
+        .map(|value| {
+            if width <= f64::EPSILON {
+                0
+            } else {
+                (((value - min) / width) as usize).min(bins - 1)
+            }
+        })
+        .collect()
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coupled_residues_share_more_information() {
+        // Residues 1 and 4 move in lockstep; residue 6 follows its own rhythm.
+        let frames: Vec<TrajectoryFrame> = (0..64)
+            .map(|step| {
+                let shared = (step as f64 * 0.7).sin() * 1.5;
+                let own = ((step * step) as f64 * 0.37).sin() * 1.5;
+                TrajectoryFrame {
+                    time_ms: step as f64,
+                    positions: (0..8)
+                        .map(|residue| {
+                            let offset = match residue {
+                                1 | 4 => shared,
+                                6 => own,
+                                _ => 0.0,
+                            };
+                            [residue as f64 * 3.8, 2.0 * (residue % 2) as f64, offset]
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        let matrix = mutual_information_matrix(&frames, MotionSignal::Displacement).unwrap();
+        assert_eq!(matrix.residue_count(), 8);
+        assert!(matrix.values[1][4] > matrix.values[1][6]);
+        assert_eq!(matrix.values[1][4], matrix.values[4][1]);
+        assert!(matrix.to_csv().starts_with("residue,0,1,"));
+
+        let dihedrals = mutual_information_matrix(&frames, MotionSignal::Dihedral).unwrap();
+        assert_eq!(dihedrals.values[0][0], 0.0);
+        assert!(mutual_information_matrix(&frames[..1], MotionSignal::Dihedral).is_none());
+    }
+
+    #[test]
+    fn dihedrals_rocking_across_the_wrap_bin_as_one_range() {
+        let rocking: Vec<f64> = (0..64)
+            .map(|step| 10.0 * (step as f64 * 0.7).sin())
+            .collect();
+        // The same motion around 180°, as reported in (-180°, 180°]
+        let wrapped: Vec<f64> = rocking
+            .iter()
+            .map(|offset| (180.0 + offset + 180.0).rem_euclid(360.0) - 180.0)
+            .collect();
+        assert!(wrapped.iter().any(|&angle| angle < 0.0));
+        let unwrapped = unwrapped_around_mean(wrapped);
+        assert!(
+            unwrapped
+                .iter()
+                .zip(&rocking)
+                .all(|(angle, offset)| ((angle - offset).rem_euclid(360.0) - 180.0).abs() < 1e-9)
+        );
+        let own = mutual_information(&rocking, &rocking, 8);
+        assert!((mutual_information(&unwrapped, &rocking, 8) - own).abs() < 1e-9);
+    }
+
+    #[test]
+    fn transfer_entropy_follows_the_driver() {
+        // The follower copies the driver's previous value; the driver is noise.
+        let mut state = 17u64;
+        let driver: Vec<f64> = (0..400)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) % 4) as f64
+            })
+            .collect();
+        let follower: Vec<f64> = std::iter::once(0.0)
+            .chain(driver.iter().copied())
+            .take(driver.len())
+            .collect();
+        let forward = transfer_entropy(&driver, &follower, 4);
+        let backward = transfer_entropy(&follower, &driver, 4);
+        assert!(forward > 0.5 && forward > 4.0 * backward);
+
+        // 0 → 1 → 3 is strong everywhere; the direct 0 → 3 link is weak.
+        let mut weights = vec![vec![0.0; 4]; 4];
+        weights[0][1] = 1.0;
+        weights[1][3] = 0.9;
+        weights[0][3] = 0.05;
+        weights[0][2] = 0.5;
+        let pathway = coupling_pathway(&weights, 0, 3).unwrap();
+        assert_eq!(pathway.residues, vec![0, 1, 3]);
+        assert!((pathway.bottleneck - 0.9).abs() < 1e-12);
+        assert!(coupling_pathway(&weights, 3, 0).is_none());
+    }
+}
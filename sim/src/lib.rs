@@ -1,6 +1,54 @@
+pub mod analysis;
+pub mod binary_cif;
+pub mod columnar;
+pub mod disorder;
+pub mod entropy;
+pub mod gromacs;
+pub mod information;
+pub mod mbar;
+pub mod psf_dcd;
+pub mod replica_exchange;
+pub mod residue_metrics;
+pub mod similarity;
+pub mod smoothing;
+pub mod viewer;
+pub mod visual_scripts;
+
 use folding_core::ExecutionReport;
 use folding_time::trajectory::Trajectory;
 
+pub use analysis::{
+    ACCEPTANCE_COLLAPSE, ACCEPTANCE_SATURATION, AcceptanceAlert, AcceptanceRegime, AcceptanceRow,
+    BackendFailureRow, GeometryCheckRow, GeometryIssueRow, PhaseRow, RngStateRow, RunAnalysis,
+    RunMetadata, RunSummary, SpanFrame, ThermoDiscrepancyRow, ViolationCount, ViolationRow,
+};
+pub use binary_cif::encode_binary_cif;
+pub use columnar::{export_parquet, metadata_record_batch, spans_record_batch, write_parquet};
+pub use disorder::{DisorderProfile, ResidueDisorder};
+pub use entropy::quasi_harmonic_entropy;
+pub use gromacs::{
+    GromacsFrame, gromacs_trajectory_frames, is_gromacs_trajectory, load_gromacs_trajectory,
+    read_trr, read_xtc,
+};
+pub use information::{
+    CouplingPathway, MotionSignal, MutualInformationMatrix, TransferEntropyMatrix,
+    coupling_pathway, mutual_information, mutual_information_matrix, residue_series,
+    transfer_entropy, transfer_entropy_matrix,
+};
+pub use mbar::{Mbar, MbarState};
+pub use psf_dcd::{encode_dcd, psf_topology};
+pub use replica_exchange::{
+    Demux, ExchangeAttempt, PairExchange, ReplicaExchangeLog, ReplicaRoundTrips,
+    is_replica_exchange_log,
+};
+pub use residue_metrics::{ColorMetric, contact_count, radius_of_gyration, residue_entropy, rmsf};
+pub use similarity::{LddtScores, SimilarityScores, Superposition, compare_structures, lddt};
+pub use smoothing::smooth_frames;
+pub use viewer::{SecondaryStructure, assign_secondary_structure, viewer_json};
+pub use visual_scripts::{
+    VisualScene, chimerax_script, frustration_pymol_script, pymol_script, vmd_script,
+};
+
 /// Aggregate metrics derived from a folding execution report.
 #[derive(Debug, Default)]
 pub struct FoldingMetrics {
@@ -54,7 +102,7 @@ impl TrajectoryVisualizer {
                 output.push(',');
             }
             output.push_str(&format!(
-                "{{\"index\":{},\"id\":\"{}\",\"delta_entropy\":{},\"delta_information\":{},\"delta_theta\":{},\"delta_energy\":{},\"gibbs_energy\":{},\"duration_ms\":{}}}",
+                "{{\"index\":{},\"id\":\"{}\",\"delta_entropy\":{},\"delta_information\":{},\"delta_theta\":{},\"delta_energy\":{},\"gibbs_energy\":{},\"duration_ms\":{},\"simulated_ps\":{},\"tags\":[{}],\"phase\":\"{}\"}}",
                 index,
                 escape_json(&span.id),
                 span.delta_entropy,
@@ -62,7 +110,14 @@ impl TrajectoryVisualizer {
                 span.delta_theta,
                 span.delta_energy,
                 span.gibbs_energy,
-                span.duration.as_millis()
+                span.duration.as_millis(),
+                span.simulated_ps,
+                span.tags
+                    .iter()
+                    .map(|tag| format!("\"{}\"", escape_json(tag)))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                escape_json(&span.phase_path())
             ));
         }
         output.push(']');
@@ -77,43 +132,21 @@ fn escape_json(input: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use folding_core::rotation_solver::RotationOutcome;
+    use folding_core::test_support::{ChainBuilder, ReportBuilder, TrajectoryBuilder};
     use folding_time::trajectory::SpanRecord;
     use std::time::Duration;
 
     #[test]
     fn metrics_compute_totals() {
-        let mut report = ExecutionReport {
-            applied_rotations: Vec::new(),
-            ghost_rotations: Vec::new(),
-            rejections: Vec::new(),
-            final_energy: folding_core::EnergyState::default(),
-            trajectory: Trajectory::new(),
-            metropolis_stats: folding_core::MetropolisStats::default(),
-            domains: Vec::new(),
-            chaperone_requirements: Vec::new(),
-            modifications: Vec::new(),
-            physics_level: folding_core::PhysicsLevel::Toy,
-            physics_spans: Vec::new(),
-            physics_span_metrics: Vec::new(),
-        };
-        report.applied_rotations.push(RotationOutcome {
-            applied_angle: 1.0,
-            span_record: SpanRecord {
-                id: "a".into(),
-                delta_entropy: 0.5,
-                delta_information: 0.2,
-                duration: Duration::from_millis(1),
-                delta_theta: 1.0,
-                delta_energy: -0.1,
-                gibbs_energy: -0.1,
-            },
-            ghost: false,
-            physics_metrics: None,
-        });
+        let report = ReportBuilder::new(ChainBuilder::new(3).build())
+            .with_applied(TrajectoryBuilder::new(1).with_entropy(0.5, 0.0))
+            .with_ghosts(TrajectoryBuilder::new(2).with_entropy(0.25, 0.0))
+            .build();
         let metrics = FoldingMetrics::from_report(&report);
         assert!((metrics.total_entropy - 0.5).abs() < 1e-9);
         assert_eq!(metrics.applied_spans, 1);
+        assert!((metrics.ghost_entropy - 0.5).abs() < 1e-9);
+        assert_eq!(metrics.ghost_spans, 2);
     }
 
     #[test]
@@ -124,12 +157,19 @@ mod tests {
             delta_entropy: 0.5,
             delta_information: 0.1,
             duration: Duration::from_millis(5),
+            simulated_ps: 0.005,
             delta_theta: 1.2,
             delta_energy: -0.1,
             gibbs_energy: -0.3,
+            temperature: 300.0,
+            tags: vec!["warm-up".into()],
+            phase: vec!["anneal".into(), "hot".into()],
+            phase_blocks: vec![0, 1],
+            step: 0,
         });
         let json = TrajectoryVisualizer::to_json(&trajectory);
         assert!(json.contains("\"id\":\"a\""));
-        assert!(json.contains("\"duration_ms\":5"));
+        assert!(json.contains("\"duration_ms\":5,\"simulated_ps\":0.005"));
+        assert!(json.contains("\"tags\":[\"warm-up\"],\"phase\":\"anneal/hot\""));
     }
 }
@@ -0,0 +1,342 @@
+//! Multistate Bennett acceptance ratio (MBAR; Shirts & Chodera, 2008) across
+//! temperatures. Potential energies sampled at several temperatures, from
+//! separate runs or from one run whose acceptance temperature changed, are
+//! pooled into reduced free energies and Boltzmann weights for any target
+//! temperature.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
+
+use crate::analysis::{RunAnalysis, SpanFrame};
+use crate::entropy::KB;
+
+/// Largest change in a reduced free energy at which iteration stops.
+const TOLERANCE: f64 = 1e-10;
+const MAX_ITERATIONS: usize = 100_000;
+
+/// Potential energies (kcal/mol) sampled at one temperature (K).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MbarState {
+    pub temperature: f64,
+    pub energies: Vec<f64>,
+}
+
+impl MbarState {
+    /// Applied spans of `runs`, grouped by the Metropolis temperature each was
+    /// judged at (the run temperature in logs that predate it). A span's
+    /// sample is the potential after it: the run's final potential minus the
+    /// ΔE of the spans applied later. States come out in ascending temperature.
+    ///
+    /// A Metropolis rejection leaves the chain where it was, so the current
+    /// configuration is sampled again: each span counts once plus once per
+    /// rejected move before the next acceptance, and rejections before the
+    /// first acceptance repeat the starting potential at the run temperature.
+    /// Logs without `acceptance|` rows weigh every span once.
+    pub fn from_runs(runs: &[RunAnalysis]) -> Vec<MbarState> {
+        let mut states: BTreeMap<u64, MbarState> = BTreeMap::new();
+        let mut sample = |temperature: f64, energy: f64, count: usize| {
+            states
+                .entry(temperature.to_bits())
+                .or_insert_with(|| MbarState {
+                    temperature,
+                    energies: Vec::new(),
+                })
+                .energies
+                .extend(iter::repeat_n(energy, count));
+        };
+        for run in runs {
+            let spans: Vec<&SpanFrame> = run.applied().collect();
+            let total = spans.last().map_or(0.0, |span| span.cumulative_energy);
+            let offset = run.metadata.final_potential_energy - total;
+            let rejected: BTreeSet<usize> = run
+                .violations
+                .iter()
+                .filter(|row| row.kind == "MetropolisRejected")
+                .filter_map(|row| row.step)
+                .collect();
+            // Rejections dwelt on each configuration, the starting one first
+            let mut dwell = vec![0];
+            for row in &run.acceptance {
+                match dwell.last_mut() {
+                    Some(count) if rejected.contains(&row.step) => *count += 1,
+                    _ => dwell.push(0),
+                }
+            }
+            dwell.resize(spans.len() + 1, 0);
+            if dwell[0] > 0 {
+                sample(run.metadata.temperature, offset, dwell[0]);
+            }
+            for (span, rejections) in spans.iter().zip(&dwell[1..]) {
+                let temperature = span
+                    .temperature
+                    .filter(|kelvin| *kelvin > 0.0)
+                    .unwrap_or(run.metadata.temperature);
+                sample(temperature, offset + span.cumulative_energy, 1 + rejections);
+            }
+        }
+        states.into_values().collect()
+    }
+}
+
+/// Solved MBAR estimator over pooled samples, kept in state order.
+#[derive(Clone, Debug)]
+pub struct Mbar {
+    temperatures: Vec<f64>,
+    counts: Vec<usize>,
+    energies: Vec<f64>,
+    /// Reduced free energies `f_k = −ln Z_k` of the states, `f_0 = 0`.
+    free_energies: Vec<f64>,
+    /// `ln Σ_k N_k exp(f_k − β_k U_n)` for every pooled sample.
+    log_denominators: Vec<f64>,
+    iterations: usize,
+}
+
+impl Mbar {
+    /// Iterates the MBAR self-consistent equations to convergence.
+    pub fn solve(states: &[MbarState]) -> Result<Self, String> {
+        if let Some(state) = states
+            .iter()
+            .find(|state| !(state.temperature.is_finite() && state.temperature > 0.0))
+        {
+            return Err(format!(
+                "temperature {} K is not positive",
+                state.temperature
+            ));
+        }
+        let energies: Vec<f64> = states
+            .iter()
+            .flat_map(|state| state.energies.iter().copied())
+            .collect();
+        if energies.is_empty() {
+            return Err("MBAR needs at least one sample".into());
+        }
+        if energies.iter().any(|energy| !energy.is_finite()) {
+            return Err("MBAR samples must have finite energies".into());
+        }
+        let mut mbar = Self {
+            temperatures: states.iter().map(|state| state.temperature).collect(),
+            counts: states.iter().map(|state| state.energies.len()).collect(),
+            energies,
+            free_energies: vec![0.0; states.len()],
+            log_denominators: Vec::new(),
+            iterations: 0,
+        };
+        mbar.update_denominators();
+        loop {
+            let mut next: Vec<f64> = mbar
+                .temperatures
+                .iter()
+                .map(|&temperature| mbar.reduced_free_energy(temperature))
+                .collect();
+            let reference = next[0];
+            next.iter_mut().for_each(|value| *value -= reference);
+            let change = next
+                .iter()
+                .zip(&mbar.free_energies)
+                .map(|(new, old)| (new - old).abs())
+                .fold(0.0, f64::max);
+            mbar.free_energies = next;
+            mbar.update_denominators();
+            mbar.iterations += 1;
+            if change < TOLERANCE {
+                return Ok(mbar);
+            }
+            if mbar.iterations >= MAX_ITERATIONS {
+                return Err(format!(
+                    "MBAR did not converge in {MAX_ITERATIONS} iterations (last change {change:.3e})"
+                ));
+            }
+        }
+    }
+
+    pub fn temperatures(&self) -> &[f64] {
+        &self.temperatures
+    }
+
+    /// Reduced free energies of the input states, the first at 0.
+    pub fn reduced_free_energies(&self) -> &[f64] {
+        &self.free_energies
+    }
+
+    /// Pooled potential energies, in state order; observables passed to
+    /// [`expectation`](Self::expectation) line up with them.
+    pub fn energies(&self) -> &[f64] {
+        &self.energies
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// `−ln Z(T)` relative to the first state.
+    pub fn reduced_free_energy(&self, temperature: f64) -> f64 {
+        -log_sum_exp(self.log_weights(temperature))
+    }
+
+    /// Free energy `k_B T f(T)` in kcal/mol, on the scale fixed by the first
+    /// state's `f = 0`; differences between temperatures are meaningful.
+    pub fn free_energy(&self, temperature: f64) -> f64 {
+        KB * temperature * self.reduced_free_energy(temperature)
+    }
+
+    /// Normalised Boltzmann weights of the pooled samples at `temperature`.
+    pub fn weights(&self, temperature: f64) -> Vec<f64> {
+        let log_weights: Vec<f64> = self.log_weights(temperature).collect();
+        let norm = log_sum_exp(log_weights.iter().copied());
+        log_weights
+            .into_iter()
+            .map(|value| (value - norm).exp())
+            .collect()
+    }
+
+    /// `⟨A⟩` at `temperature`; `observable` holds one value per pooled sample.
+    pub fn expectation(&self, observable: &[f64], temperature: f64) -> Result<f64, String> {
+        if observable.len() != self.energies.len() {
+            return Err(format!(
+                "observable has {} values for {} samples",
+                observable.len(),
+                self.energies.len()
+            ));
+        }
+        Ok(self
+            .weights(temperature)
+            .iter()
+            .zip(observable)
+            .map(|(weight, value)| weight * value)
+            .sum())
+    }
+
+    /// Mean potential energy (kcal/mol) at `temperature`.
+    pub fn mean_energy(&self, temperature: f64) -> f64 {
+        self.weights(temperature)
+            .iter()
+            .zip(&self.energies)
+            .map(|(weight, energy)| weight * energy)
+            .sum()
+    }
+
+    /// Kish effective sample size of the weights at `temperature`; small
+    /// values mean the sampled states barely overlap the target.
+    pub fn effective_samples(&self, temperature: f64) -> f64 {
+        1.0 / self
+            .weights(temperature)
+            .iter()
+            .map(|weight| weight * weight)
+            .sum::<f64>()
+    }
+
+    /// Unnormalised `ln w_n = −β U_n − ln Σ_k N_k exp(f_k − β_k U_n)`.
+    fn log_weights(&self, temperature: f64) -> impl Iterator<Item = f64> + Clone + '_ {
+        let beta = 1.0 / (KB * temperature);
+        self.energies
+            .iter()
+            .zip(&self.log_denominators)
+            .map(move |(energy, denominator)| -beta * energy - denominator)
+    }
+
+    fn update_denominators(&mut self) {
+        let states: Vec<(f64, f64)> = self
+            .temperatures
+            .iter()
+            .zip(&self.counts)
+            .zip(&self.free_energies)
+            .filter(|((_, count), _)| **count > 0)
+            .map(|((temperature, count), free)| {
+                (1.0 / (KB * temperature), (*count as f64).ln() + free)
+            })
+            .collect();
+        self.log_denominators = self
+            .energies
+            .iter()
+            .map(|energy| log_sum_exp(states.iter().map(|(beta, log_n)| log_n - beta * energy)))
+            .collect();
+    }
+}
+
+fn log_sum_exp(values: impl Iterator<Item = f64> + Clone) -> f64 {
+    let max = values.clone().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values.map(|value| (value - max).exp()).sum::<f64>().ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two-level system: energies 0 and `GAP`, sampled in exact proportion.
+    const GAP: f64 = 1.0;
+
+    fn excited_fraction(temperature: f64) -> f64 {
+        let boltzmann = (-GAP / (KB * temperature)).exp();
+        boltzmann / (1.0 + boltzmann)
+    }
+
+    fn state(temperature: f64) -> MbarState {
+        let samples = 200_000;
+        let excited = (samples as f64 * excited_fraction(temperature)).round() as usize;
+        let mut energies = vec![0.0; samples - excited];
+        energies.extend(vec![GAP; excited]);
+        MbarState {
+            temperature,
+            energies,
+        }
+    }
+
+    #[test]
+    fn reweights_a_two_level_system_between_temperatures() {
+        let mbar = Mbar::solve(&[state(300.0), state(600.0)]).unwrap();
+        for target in [300.0, 400.0, 500.0, 600.0] {
+            let exact = GAP * excited_fraction(target);
+            assert!(
+                (mbar.mean_energy(target) - exact).abs() < 1e-4,
+                "{target} K"
+            );
+        }
+        // f = −ln Z with Z = 1 + exp(−β·GAP).
+        let ln_z = |temperature: f64| (1.0 + (-GAP / (KB * temperature)).exp()).ln();
+        let expected = ln_z(300.0) - ln_z(450.0);
+        assert!((mbar.reduced_free_energy(450.0) - expected).abs() < 1e-4);
+        assert!(mbar.effective_samples(450.0) > 1000.0);
+    }
+
+    #[test]
+    fn groups_logged_spans_by_temperature() {
+        let log = "metadata|run_id=a|timestamp=0|contract_name=|environment=aqueous|temperature=300.0|time_step_ms=1|accepted_spans=3|rejected_spans=0|acceptance_rate=1.0|final_potential_energy=4.0|final_gibbs_energy=0.0|informational_efficiency=1.0|total_work=0.0
+span|id=a|delta_theta=1.0|delta_S=0.0|delta_I=0.0|delta_E=-1.0|duration_ms=1|ghost_flag=0|G=0.0|temperature=300.000
+span|id=b|delta_theta=1.0|delta_S=0.0|delta_I=0.0|delta_E=0.5|duration_ms=1|ghost_flag=0|G=0.0|temperature=600.000
+span|id=g|delta_theta=1.0|delta_S=0.0|delta_I=0.0|delta_E=9.0|duration_ms=1|ghost_flag=1|G=0.0|temperature=600.000
+span|id=c|delta_theta=1.0|delta_S=0.0|delta_I=0.0|delta_E=2.0|duration_ms=1|ghost_flag=0|G=0.0
+";
+        let run = RunAnalysis::parse(log).unwrap();
+        let states = MbarState::from_runs(&[run]);
+        assert_eq!(states.len(), 2);
+        assert_eq!(states[0].temperature, 300.0);
+        assert_eq!(states[0].energies, [1.5, 4.0]);
+        assert_eq!(states[1].energies, [2.0]);
+        assert!(Mbar::solve(&states).is_ok());
+        assert!(Mbar::solve(&[]).is_err());
+    }
+
+    #[test]
+    fn rejected_moves_repeat_the_current_configuration() {
+        let log = "metadata|run_id=a|timestamp=0|contract_name=|environment=aqueous|temperature=300.0|time_step_ms=1|accepted_spans=2|rejected_spans=3|acceptance_rate=0.4|final_potential_energy=4.0|final_gibbs_energy=0.0|informational_efficiency=1.0|total_work=0.0
+span|id=a|delta_theta=1.0|delta_S=0.0|delta_I=0.0|delta_E=-1.0|duration_ms=1|ghost_flag=0|G=0.0|temperature=300.000
+span|id=b|delta_theta=1.0|delta_S=0.0|delta_I=0.0|delta_E=0.5|duration_ms=1|ghost_flag=0|G=0.0|temperature=300.000
+violation|step=1|code=R006|severity=warning|kind=MetropolisRejected|delta_energy=2.0|detail=MetropolisRejected { delta_energy: 2.0 }
+violation|step=3|code=R006|severity=warning|kind=MetropolisRejected|delta_energy=2.0|detail=MetropolisRejected { delta_energy: 2.0 }
+violation|step=4|code=R006|severity=warning|kind=MetropolisRejected|delta_energy=2.0|detail=MetropolisRejected { delta_energy: 2.0 }
+acceptance|step=1|accepted=0|total=1|rate=0.0
+acceptance|step=2|accepted=1|total=2|rate=0.5
+acceptance|step=3|accepted=1|total=3|rate=0.333333
+acceptance|step=4|accepted=1|total=4|rate=0.25
+acceptance|step=5|accepted=2|total=5|rate=0.4
+";
+        let run = RunAnalysis::parse(log).unwrap();
+        let states = MbarState::from_runs(&[run]);
+        assert_eq!(states.len(), 1);
+        // Start once, span a three times, span b once
+        assert_eq!(states[0].energies, [4.5, 3.5, 3.5, 3.5, 4.0]);
+    }
+}
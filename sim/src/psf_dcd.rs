@@ -0,0 +1,136 @@
+use folding_molecule::PeptideChain;
+
+/// Cα mass used for every pseudo-atom in the PSF.
+const CA_MASS: f64 = 12.011;
+
+/// X-PLOR style PSF topology for the Cα trace: one `CA` atom per residue in
+/// segment `A`, bonded to its sequence neighbour.
+pub fn psf_topology(chain: &PeptideChain) -> String {
+    let mut psf = String::from("PSF\n\n");
+    psf.push_str(&format!("{:>8} !NTITLE\n", 1));
+    psf.push_str(" REMARKS LogLineFold Calpha trace\n\n");
+
+    psf.push_str(&format!("{:>8} !NATOM\n", chain.len()));
+    for (index, residue) in chain.residues().enumerate() {
+        psf.push_str(&format!(
+            "{:>8} {:<4} {:<4} {:<4} {:<4} {:<4} {:>10.6} {:>13.4} {:>11}\n",
+            index + 1,
+            "A",
+            residue.id.0 + 1,
+            residue.name,
+            "CA",
+            "CA",
+            0.0,
+            CA_MASS,
+            0
+        ));
+    }
+
+    let bonds = chain.len().saturating_sub(1);
+    psf.push_str(&format!("\n{:>8} !NBOND: bonds\n", bonds));
+    for chunk in (1..=bonds).collect::<Vec<_>>().chunks(4) {
+        let line: String = chunk
+            .iter()
+            .map(|atom| format!("{:>8}{:>8}", atom, atom + 1))
+            .collect();
+        psf.push_str(&line);
+        psf.push('\n');
+    }
+    for section in [
+        "!NTHETA: angles",
+        "!NPHI: dihedrals",
+        "!NIMPHI: impropers",
+        "!NDON: donors",
+        "!NACC: acceptors",
+    ] {
+        psf.push_str(&format!("\n{:>8} {}\n", 0, section));
+    }
+    psf
+}
+
+/// CHARMM/NAMD DCD (little-endian, 32-bit record markers) holding one set of
+/// Cα coordinates per frame. DCD assumes a fixed stride, so only the mean
+/// spacing `timestep_ms` is stored; per-frame timestamps are not preserved.
+pub fn encode_dcd(frames: &[Vec<[f64; 3]>], timestep_ms: f64, title: &str) -> Vec<u8> {
+    let atoms = frames.first().map(Vec::len).unwrap_or(0);
+    let mut buf = Vec::new();
+
+    let mut header = Vec::with_capacity(84);
+    header.extend_from_slice(b"CORD");
+    let mut control = [0i32; 20];
+    control[0] = frames.len() as i32;
+    control[1] = 1;
+    control[2] = 1;
+    control[3] = frames.len() as i32;
+    control[19] = 24;
+    for (idx, value) in control.iter().enumerate() {
+        if idx == 9 {
+            header.extend_from_slice(&(timestep_ms as f32).to_le_bytes());
+        } else {
+            header.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    write_record(&mut buf, &header);
+
+    let mut title_record = Vec::with_capacity(4 + 80);
+    title_record.extend_from_slice(&1i32.to_le_bytes());
+    let mut line = [b' '; 80];
+    for (slot, byte) in line.iter_mut().zip(title.bytes()) {
+        *slot = byte;
+    }
+    title_record.extend_from_slice(&line);
+    write_record(&mut buf, &title_record);
+
+    write_record(&mut buf, &(atoms as i32).to_le_bytes());
+
+    for frame in frames {
+        for axis in 0..3 {
+            let values: Vec<u8> = (0..atoms)
+                .flat_map(|atom| {
+                    let value = frame.get(atom).map(|p| p[axis]).unwrap_or(0.0);
+                    (value as f32).to_le_bytes()
+                })
+                .collect();
+            write_record(&mut buf, &values);
+        }
+    }
+    buf
+}
+
+fn write_record(buf: &mut Vec<u8>, payload: &[u8]) {
+    let marker = (payload.len() as i32).to_le_bytes();
+    buf.extend_from_slice(&marker);
+    buf.extend_from_slice(payload);
+    buf.extend_from_slice(&marker);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn psf_lists_atoms_and_chain_bonds() {
+        let chain = PeptideChain::from_sequence("AGS");
+        let psf = psf_topology(&chain);
+        assert!(psf.contains("       3 !NATOM\n"));
+        assert!(psf.contains("       3 A    3    SER  CA   CA  "));
+        assert!(psf.contains("       2 !NBOND: bonds\n       1       2       2       3\n"));
+    }
+
+    #[test]
+    fn dcd_records_frames_with_fortran_markers() {
+        let frames = vec![
+            vec![[0.0, 0.0, 0.0], [3.8, 0.0, 0.0]],
+            vec![[0.0, 1.0, 0.0], [3.8, 2.0, 0.5]],
+        ];
+        let bytes = encode_dcd(&frames, 2.0, "demo");
+        // header + title + atom count + 2 frames * 3 axes
+        let expected = (84 + 8) + (84 + 8) + (4 + 8) + 2 * 3 * (8 + 8);
+        assert_eq!(bytes.len(), expected);
+        assert_eq!(&bytes[4..8], b"CORD");
+        assert_eq!(i32::from_le_bytes(bytes[8..12].try_into().unwrap()), 2);
+        let last_y = bytes.len() - 2 * (8 + 8) + 4 + 4;
+        let y = f32::from_le_bytes(bytes[last_y..last_y + 4].try_into().unwrap());
+        assert_eq!(y, 2.0);
+    }
+}
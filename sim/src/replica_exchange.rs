@@ -0,0 +1,418 @@
+//! Demultiplexing of temperature replica-exchange runs from a GROMACS
+//! `md.log`, the way `demux.pl` does it: each exchange attempt swaps the
+//! replicas of neighbouring temperature slots, so following the swaps tracks
+//! every replica through temperature space.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Marks the start of an exchange attempt in `md.log`.
+const ATTEMPT_HEADER: &str = "Replica exchange at step";
+
+/// Opens the replica-exchange section of the `md.log` header; exchange
+/// attempts start the same way.
+const REPLICA_EXCHANGE: &str = "Replica exchange";
+
+/// Ends the `md.log` header.
+const HEADER_END: &str = "Started mdrun";
+
+/// Lines read for the header before a log is taken to have none.
+const HEADER_LINES: usize = 5000;
+
+/// One exchange attempt over the whole ladder. Pairs are named by their
+/// lower slot, so pair `i` joins slots `i` and `i + 1`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeAttempt {
+    pub step: u64,
+    pub time_ps: f64,
+    /// Pairs tested at this step with their Metropolis probability.
+    pub attempted: Vec<(usize, f64)>,
+    /// Pairs whose replicas swapped.
+    pub exchanged: Vec<usize>,
+}
+
+/// Exchange attempts read from a replica-exchange `md.log`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplicaExchangeLog {
+    pub replicas: usize,
+    /// Ladder temperatures (K) by slot; empty when the log does not list them.
+    pub temperatures: Vec<f64>,
+    pub attempts: Vec<ExchangeAttempt>,
+}
+
+/// Attempts and swaps of one neighbour pair.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PairExchange {
+    pub lower: usize,
+    pub attempted: usize,
+    pub accepted: usize,
+    /// Mean Metropolis probability over the attempts.
+    pub mean_probability: f64,
+}
+
+impl PairExchange {
+    pub fn acceptance(&self) -> f64 {
+        if self.attempted == 0 {
+            0.0
+        } else {
+            self.accepted as f64 / self.attempted as f64
+        }
+    }
+}
+
+/// Round trips of one replica: bottom of the ladder to the top and back, or
+/// the reverse, timed between alternate visits to an end.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplicaRoundTrips {
+    pub replica: usize,
+    pub round_trips: usize,
+    /// Mean round-trip time (ps); `None` before the first round trip.
+    pub mean_ps: Option<f64>,
+    /// Distinct slots the replica visited.
+    pub slots_visited: usize,
+}
+
+/// Every replica followed through temperature space.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Demux {
+    /// Time (ps) of the starting state and of each attempt after it.
+    pub times_ps: Vec<f64>,
+    pub steps: Vec<u64>,
+    /// `slots[k][s]`: replica in slot `s` after attempt `k` (row 0 is the
+    /// start, where replica `s` sits in slot `s`).
+    pub slots: Vec<Vec<usize>>,
+    pub pairs: Vec<PairExchange>,
+    pub replicas: Vec<ReplicaRoundTrips>,
+}
+
+impl Demux {
+    /// `time_ps,step,slot0,…` rows of the replica in each slot, like the
+    /// `replica_index.xvg` of `demux.pl`.
+    pub fn to_csv(&self) -> String {
+        let slots = self.slots.first().map_or(0, Vec::len);
+        let mut csv = String::from("time_ps,step");
+        for slot in 0..slots {
+            csv.push_str(&format!(",slot{slot}"));
+        }
+        csv.push('\n');
+        for ((time, step), row) in self.times_ps.iter().zip(&self.steps).zip(&self.slots) {
+            csv.push_str(&format!("{time},{step}"));
+            for replica in row {
+                csv.push_str(&format!(",{replica}"));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Whether `path` is a text log of a replica-exchange run; only its header
+/// is read.
+pub fn is_replica_exchange_log(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    BufReader::new(file)
+        .lines()
+        .take(HEADER_LINES)
+        .map_while(Result::ok)
+        .take_while(|line| !line.starts_with(HEADER_END))
+        .any(|line| line.trim_start().starts_with(REPLICA_EXCHANGE))
+}
+
+impl ReplicaExchangeLog {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("failed to open log {}: {err}", path.display()))?;
+        Self::parse(&contents)
+    }
+
+    /// Reads the `Repl  T`, `Replica exchange at step`, `Repl ex` and
+    /// `Repl pr` lines; everything else in the log is skipped. A `Repl pr`
+    /// value belongs to the pair whose slot numbers flank it on the `Repl ex`
+    /// line above.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut log = Self::default();
+        // Column of each slot number on the attempt's `Repl ex` line
+        let mut slot_columns: Vec<usize> = Vec::new();
+        for line in contents.lines() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if let Some(rest) = line.trim_start().strip_prefix(ATTEMPT_HEADER) {
+                let fields: Vec<&str> = rest.split_whitespace().collect();
+                let step = fields
+                    .first()
+                    .and_then(|raw| raw.parse().ok())
+                    .ok_or_else(|| format!("invalid exchange line: {line}"))?;
+                let time_ps = match fields.get(1..3) {
+                    Some(["time", raw]) => raw
+                        .parse()
+                        .map_err(|_| format!("invalid exchange time: {line}"))?,
+                    _ => 0.0,
+                };
+                log.attempts.push(ExchangeAttempt {
+                    step,
+                    time_ps,
+                    ..ExchangeAttempt::default()
+                });
+                slot_columns.clear();
+                continue;
+            }
+            match tokens.as_slice() {
+                ["Repl", "T", values @ ..] if log.temperatures.is_empty() => {
+                    log.temperatures = values
+                        .iter()
+                        .map(|raw| {
+                            raw.parse()
+                                .map_err(|_| format!("invalid ladder temperature {raw}"))
+                        })
+                        .collect::<Result<_, String>>()?;
+                }
+                ["Repl", "ex", ..] => {
+                    let Some(attempt) = log.attempts.last_mut() else {
+                        continue;
+                    };
+                    slot_columns.clear();
+                    for (column, cell) in cells(line).skip(2) {
+                        if cell == "x" {
+                            let pair = slot_columns
+                                .len()
+                                .checked_sub(1)
+                                .ok_or_else(|| format!("invalid exchange line: {line}"))?;
+                            attempt.exchanged.push(pair);
+                        } else {
+                            slot_columns.push(column);
+                        }
+                    }
+                    log.replicas = log.replicas.max(slot_columns.len());
+                }
+                ["Repl", "pr", ..] => {
+                    let Some(attempt) = log.attempts.last_mut() else {
+                        continue;
+                    };
+                    for (column, cell) in cells(line).skip(2) {
+                        let pair = slot_columns
+                            .iter()
+                            .filter(|&&slot| slot < column)
+                            .count()
+                            .checked_sub(1)
+                            .filter(|&pair| pair + 1 < slot_columns.len())
+                            .ok_or_else(|| format!("exchange probability {cell} joins no pair"))?;
+                        let probability = cell
+                            .parse()
+                            .map_err(|_| format!("invalid exchange probability {cell}"))?;
+                        attempt.attempted.push((pair, probability));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if log.attempts.is_empty() {
+            return Err("log holds no replica exchange attempts".into());
+        }
+        if log.replicas < 2 {
+            return Err("replica exchange needs at least two replicas".into());
+        }
+        if !log.temperatures.is_empty() && log.temperatures.len() != log.replicas {
+            return Err(format!(
+                "ladder lists {} temperatures for {} replicas",
+                log.temperatures.len(),
+                log.replicas
+            ));
+        }
+        for attempt in &mut log.attempts {
+            // Logs without `Repl pr` lines only show the swaps.
+            for &pair in &attempt.exchanged {
+                if !attempt.attempted.iter().any(|&(tested, _)| tested == pair) {
+                    attempt.attempted.push((pair, 1.0));
+                }
+            }
+        }
+        Ok(log)
+    }
+
+    /// Follows each replica through the ladder and tallies exchanges per
+    /// neighbour pair.
+    pub fn demux(&self) -> Demux {
+        let slots = self.replicas;
+        let mut pairs: Vec<PairExchange> = (0..slots.saturating_sub(1))
+            .map(|lower| PairExchange {
+                lower,
+                ..PairExchange::default()
+            })
+            .collect();
+        let mut occupant: Vec<usize> = (0..slots).collect();
+        let mut demux = Demux {
+            times_ps: vec![0.0],
+            steps: vec![0],
+            slots: vec![occupant.clone()],
+            pairs: Vec::new(),
+            replicas: Vec::new(),
+        };
+        for attempt in &self.attempts {
+            for &(pair, probability) in &attempt.attempted {
+                if let Some(tally) = pairs.get_mut(pair) {
+                    tally.attempted += 1;
+                    tally.mean_probability += probability.min(1.0);
+                }
+            }
+            for &pair in &attempt.exchanged {
+                if pair + 1 < slots {
+                    occupant.swap(pair, pair + 1);
+                    pairs[pair].accepted += 1;
+                }
+            }
+            demux.times_ps.push(attempt.time_ps);
+            demux.steps.push(attempt.step);
+            demux.slots.push(occupant.clone());
+        }
+        for tally in &mut pairs {
+            if tally.attempted > 0 {
+                tally.mean_probability /= tally.attempted as f64;
+            }
+        }
+        demux.pairs = pairs;
+        demux.replicas = (0..slots)
+            .map(|replica| round_trips(&demux, replica))
+            .collect();
+        demux
+    }
+}
+
+/// Whitespace-separated cells of `line` with the byte column each starts at.
+fn cells(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace()
+        .map(move |cell| (cell.as_ptr() as usize - line.as_ptr() as usize, cell))
+}
+
+/// Round trips of `replica`: it reaches an end of the ladder, the other end,
+/// and the first end again.
+fn round_trips(demux: &Demux, replica: usize) -> ReplicaRoundTrips {
+    let top = demux.slots[0].len() - 1;
+    let mut visited = vec![false; top + 1];
+    // Times the replica arrived at an end other than the last one it reached.
+    let mut arrivals: Vec<(usize, f64)> = Vec::new();
+    for (row, &time) in demux.slots.iter().zip(&demux.times_ps) {
+        let Some(slot) = row.iter().position(|&occupant| occupant == replica) else {
+            continue;
+        };
+        visited[slot] = true;
+        if (slot == 0 || slot == top) && arrivals.last().is_none_or(|&(end, _)| end != slot) {
+            arrivals.push((slot, time));
+        }
+    }
+    let durations: Vec<f64> = arrivals
+        .iter()
+        .step_by(2)
+        .zip(arrivals.iter().skip(2).step_by(2))
+        .map(|(start, end)| end.1 - start.1)
+        .collect();
+    ReplicaRoundTrips {
+        replica,
+        round_trips: durations.len(),
+        mean_ps: (!durations.is_empty())
+            .then(|| durations.iter().sum::<f64>() / durations.len() as f64),
+        slots_visited: visited.iter().filter(|&&seen| seen).count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three replicas; the lower pair is tested on even attempts, the upper
+    /// pair on odd ones, as GROMACS alternates them.
+    const LOG: &str = "\
+Replica exchange in temperature
+Repl  There are 3 replicas:
+Repl  T    300.0 320.0 340.0
+
+Replica exchange at step 1000 time 2.00000
+Repl ex  0 x  1    2
+Repl pr   1.0
+
+Replica exchange at step 2000 time 4.00000
+Repl ex  0    1 x  2
+Repl pr        .54
+
+Replica exchange at step 3000 time 6.00000
+Repl ex  0    1    2
+Repl pr   .08
+
+Replica exchange at step 4000 time 8.00000
+Repl ex  0    1 x  2
+Repl pr        1.0
+
+Replica exchange at step 5000 time 10.00000
+Repl ex  0 x  1    2
+Repl pr   .61
+";
+
+    #[test]
+    fn demux_follows_replicas_and_times_round_trips() {
+        let log = ReplicaExchangeLog::parse(LOG).unwrap();
+        assert_eq!(log.replicas, 3);
+        assert_eq!(log.temperatures, [300.0, 320.0, 340.0]);
+        assert_eq!(log.attempts.len(), 5);
+        assert_eq!(log.attempts[1].attempted, [(1, 0.54)]);
+        assert_eq!(log.attempts[1].exchanged, [1]);
+
+        let demux = log.demux();
+        assert_eq!(demux.slots.len(), 6);
+        // Replica 0 climbs 0 → 1 → 2, comes back down 2 → 1 → 0.
+        let path: Vec<usize> = demux
+            .slots
+            .iter()
+            .map(|row| row.iter().position(|&r| r == 0).unwrap())
+            .collect();
+        assert_eq!(path, [0, 1, 2, 2, 1, 0]);
+        assert_eq!(demux.replicas[0].round_trips, 1);
+        assert_eq!(demux.replicas[0].mean_ps, Some(10.0));
+        assert_eq!(demux.replicas[0].slots_visited, 3);
+        assert_eq!(demux.replicas[1].round_trips, 0);
+
+        let lower = &demux.pairs[0];
+        assert_eq!((lower.attempted, lower.accepted), (3, 2));
+        assert!((lower.mean_probability - (1.0 + 0.08 + 0.61) / 3.0).abs() < 1e-12);
+        assert_eq!(demux.pairs[1].acceptance(), 1.0);
+
+        let csv = demux.to_csv();
+        assert!(csv.starts_with("time_ps,step,slot0,slot1,slot2\n0,0,0,1,2\n2,1000,1,0,2\n"));
+        assert!(ReplicaExchangeLog::parse("Started mdrun\n").is_err());
+    }
+
+    #[test]
+    fn probabilities_follow_the_slot_numbers_of_wide_ladders() {
+        let log = ReplicaExchangeLog::parse(
+            "\
+Replica exchange at step 1000 time 2.00000
+Repl ex  0    1    2    3    4    5    6    7    8    9 x 10
+Repl pr                                                  .35
+",
+        )
+        .unwrap();
+        assert_eq!(log.replicas, 11);
+        assert_eq!(log.attempts[0].attempted, [(9, 0.35)]);
+        assert_eq!(log.attempts[0].exchanged, [9]);
+        assert!(ReplicaExchangeLog::parse("Replica exchange at step 1\nRepl pr  .5\n").is_err());
+
+        // A log with no ladder demultiplexes to nothing
+        let empty = ReplicaExchangeLog::default().demux();
+        assert!(empty.pairs.is_empty() && empty.replicas.is_empty());
+
+        let dir = std::env::temp_dir().join(format!("logline_remd_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let md_log = dir.join("md.log");
+        fs::write(&md_log, LOG).unwrap();
+        assert!(is_replica_exchange_log(&md_log));
+        fs::write(
+            &md_log,
+            "Started mdrun on rank 0\nReplica exchange at step 1\n",
+        )
+        .unwrap();
+        assert!(!is_replica_exchange_log(&md_log));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
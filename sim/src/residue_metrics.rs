@@ -0,0 +1,163 @@
+use folding_core::scoring::CONTACT_CUTOFF;
+use folding_core::{RotationOutcome, TrajectoryFrame};
+use folding_molecule::PeptideChain;
+
+use crate::disorder::DisorderProfile;
+
+/// Per-residue values that visual exports can map onto colours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMetric {
+    /// Entropy accumulated by accepted spans acting on the residue.
+    Entropy,
+    /// Root-mean-square fluctuation of the Cα across recorded frames.
+    Rmsf,
+    /// Disorder propensity (see [`DisorderProfile`]).
+    Disorder,
+}
+
+impl ColorMetric {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "entropy" => Some(ColorMetric::Entropy),
+            "rmsf" => Some(ColorMetric::Rmsf),
+            "disorder" => Some(ColorMetric::Disorder),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorMetric::Entropy => "entropy",
+            ColorMetric::Rmsf => "rmsf",
+            ColorMetric::Disorder => "disorder",
+        }
+    }
+
+    pub fn values(
+        &self,
+        rotations: &[RotationOutcome],
+        frames: &[TrajectoryFrame],
+        chain: &PeptideChain,
+    ) -> Vec<f64> {
+        match self {
+            ColorMetric::Entropy => residue_entropy(rotations, chain.len()),
+            ColorMetric::Rmsf => {
+                let mut values = rmsf(frames);
+                values.resize(chain.len(), 0.0);
+                values
+            }
+            ColorMetric::Disorder => DisorderProfile::new(chain, rotations, frames).propensities(),
+        }
+    }
+}
+
+/// Sums span entropy per residue.
+pub fn residue_entropy(rotations: &[RotationOutcome], residue_count: usize) -> Vec<f64> {
+    let mut entropy = vec![0.0; residue_count];
+    for outcome in rotations {
+        if let Some(slot) = entropy.get_mut(outcome.residue.0) {
+            *slot += outcome.span_record.delta_entropy;
+        }
+    }
+    entropy
+}
+
+/// Cα pairs at least three residues apart and closer than [`CONTACT_CUTOFF`].
+pub fn contact_count(positions: &[[f64; 3]]) -> usize {
+    let mut contacts = 0;
+    for i in 0..positions.len() {
+        for j in i + 3..positions.len() {
+            let (a, b) = (positions[i], positions[j]);
+            let distance =
+                ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt();
+            if distance < CONTACT_CUTOFF {
+                contacts += 1;
+            }
+        }
+    }
+    contacts
+}
+
+/// Root-mean-square distance (Å) of the positions from their centroid.
+pub fn radius_of_gyration(positions: &[[f64; 3]]) -> f64 {
+    let count = positions.len().max(1) as f64;
+    let mut centroid = [0.0; 3];
+    for position in positions {
+        for axis in 0..3 {
+            centroid[axis] += position[axis] / count;
+        }
+    }
+    let squared: f64 = positions
+        .iter()
+        .map(|p| {
+            (0..3)
+                .map(|axis| (p[axis] - centroid[axis]).powi(2))
+                .sum::<f64>()
+        })
+        .sum();
+    (squared / count).sqrt()
+}
+
+/// Cα RMSF about the mean position over all frames sharing the first frame's
+/// residue count. Frames are assumed to share a reference frame (no fitting).
+pub fn rmsf(frames: &[TrajectoryFrame]) -> Vec<f64> {
+    let Some(first) = frames.first() else {
+        return Vec::new();
+    };
+    let count = first.positions.len();
+    let usable: Vec<&TrajectoryFrame> = frames
+        .iter()
+        .filter(|frame| frame.positions.len() == count)
+        .collect();
+    let n = usable.len() as f64;
+    (0..count)
+        .map(|atom| {
+            let mut mean = [0.0; 3];
+            for frame in &usable {
+                for (axis, value) in mean.iter_mut().enumerate() {
+                    *value += frame.positions[atom][axis] / n;
+                }
+            }
+            let variance: f64 = usable
+                .iter()
+                .map(|frame| {
+                    let p = frame.positions[atom];
+                    (p[0] - mean[0]).powi(2) + (p[1] - mean[1]).powi(2) + (p[2] - mean[2]).powi(2)
+                })
+                .sum::<f64>()
+                / n;
+            variance.sqrt()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rmsf_is_zero_for_static_atoms() {
+        let frames = vec![
+            TrajectoryFrame {
+                time_ms: 0.0,
+                positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]],
+            },
+            TrajectoryFrame {
+                time_ms: 1.0,
+                positions: vec![[0.0, 0.0, 0.0], [3.0, 0.0, 0.0]],
+            },
+        ];
+        let values = rmsf(&frames);
+        assert_eq!(values[0], 0.0);
+        assert!((values[1] - 1.0).abs() < 1e-12);
+        assert_eq!(ColorMetric::parse("RMSF"), Some(ColorMetric::Rmsf));
+    }
+
+    #[test]
+    fn contacts_skip_near_neighbours() {
+        let line: Vec<[f64; 3]> = (0..4).map(|i| [2.0 * i as f64, 0.0, 0.0]).collect();
+        assert_eq!(contact_count(&line), 1);
+        assert_eq!(contact_count(&line[..3]), 0);
+        assert!((radius_of_gyration(&line) - 5.0f64.sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,367 @@
+use nalgebra::{Matrix3, Vector3};
+
+/// Rigid-body transform that maps a mobile structure onto a target (Kabsch).
+#[derive(Clone, Debug)]
+pub struct Superposition {
+    rotation: Matrix3<f64>,
+    mobile_centroid: Vector3<f64>,
+    target_centroid: Vector3<f64>,
+}
+
+impl Superposition {
+    /// Fits the optimal rotation + translation for paired coordinates.
+    /// Returns `None` when fewer than three pairs are available.
+    pub fn fit(mobile: &[[f64; 3]], target: &[[f64; 3]]) -> Option<Self> {
+        let count = mobile.len().min(target.len());
+        if count < 3 {
+            return None;
+        }
+        let mobile_centroid = centroid(&mobile[..count]);
+        let target_centroid = centroid(&target[..count]);
+        let mut covariance = Matrix3::zeros();
+        for (m, t) in mobile.iter().zip(target.iter()).take(count) {
+            let p = to_vector(*m) - mobile_centroid;
+            let q = to_vector(*t) - target_centroid;
+            covariance += p * q.transpose();
+        }
+        let svd = covariance.svd(true, true);
+        let u = svd.u?;
+        let v = svd.v_t?.transpose();
+        let sign = (v * u.transpose()).determinant().signum();
+        let correction = Matrix3::from_diagonal(&Vector3::new(1.0, 1.0, sign));
+        Some(Self {
+            rotation: v * correction * u.transpose(),
+            mobile_centroid,
+            target_centroid,
+        })
+    }
+
+    /// Fits using only the paired indices in `selection`.
+    pub fn fit_subset(
+        mobile: &[[f64; 3]],
+        target: &[[f64; 3]],
+        selection: &[usize],
+    ) -> Option<Self> {
+        let mobile: Vec<[f64; 3]> = selection.iter().map(|&idx| mobile[idx]).collect();
+        let target: Vec<[f64; 3]> = selection.iter().map(|&idx| target[idx]).collect();
+        Self::fit(&mobile, &target)
+    }
+
+    pub fn apply(&self, point: [f64; 3]) -> [f64; 3] {
+        let moved =
+            self.rotation * (to_vector(point) - self.mobile_centroid) + self.target_centroid;
+        [moved.x, moved.y, moved.z]
+    }
+
+    pub fn apply_all(&self, points: &[[f64; 3]]) -> Vec<[f64; 3]> {
+        points.iter().map(|point| self.apply(*point)).collect()
+    }
+}
+
+/// Structural similarity between a model and a reference with paired residues.
+#[derive(Clone, Debug)]
+pub struct SimilarityScores {
+    pub aligned_length: usize,
+    pub reference_length: usize,
+    pub rmsd: f64,
+    pub tm_score: f64,
+    pub gdt_ts: f64,
+    pub lddt: f64,
+}
+
+/// Inclusion radius (Å) for reference pairs considered by lDDT.
+pub const LDDT_INCLUSION_RADIUS: f64 = 15.0;
+const LDDT_THRESHOLDS: [f64; 4] = [0.5, 1.0, 2.0, 4.0];
+
+/// Superposition-free local distance difference test over Cα pairs.
+#[derive(Clone, Debug)]
+pub struct LddtScores {
+    pub global: f64,
+    /// One entry per reference residue; `None` when it has no reference
+    /// neighbours inside the inclusion radius.
+    pub per_residue: Vec<Option<f64>>,
+}
+
+impl LddtScores {
+    /// Renders `residue,lddt` rows (1-based residue numbers, empty when undefined).
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("residue,lddt\n");
+        for (idx, score) in self.per_residue.iter().enumerate() {
+            match score {
+                Some(value) => csv.push_str(&format!("{},{:.4}\n", idx + 1, value)),
+                None => csv.push_str(&format!("{},\n", idx + 1)),
+            }
+        }
+        csv
+    }
+}
+
+/// Scores how well the model preserves reference inter-residue distances.
+/// Residues missing from the model count as unpreserved.
+pub fn lddt(model: &[[f64; 3]], reference: &[[f64; 3]]) -> LddtScores {
+    let mut per_residue = Vec::with_capacity(reference.len());
+    let mut total_preserved = 0.0;
+    let mut total_pairs = 0usize;
+    for i in 0..reference.len() {
+        let mut preserved = 0.0;
+        let mut pairs = 0usize;
+        for j in 0..reference.len() {
+            if i == j {
+                continue;
+            }
+            let reference_distance = squared_distance(reference[i], reference[j]).sqrt();
+            if reference_distance >= LDDT_INCLUSION_RADIUS {
+                continue;
+            }
+            pairs += 1;
+            if i < model.len() && j < model.len() {
+                let model_distance = squared_distance(model[i], model[j]).sqrt();
+                let deviation = (model_distance - reference_distance).abs();
+                let kept = LDDT_THRESHOLDS
+                    .iter()
+                    .filter(|&&threshold| deviation < threshold)
+                    .count();
+                preserved += kept as f64 / LDDT_THRESHOLDS.len() as f64;
+            }
+        }
+        total_preserved += preserved;
+        total_pairs += pairs;
+        per_residue.push((pairs > 0).then(|| preserved / pairs as f64));
+    }
+    let global = if total_pairs == 0 {
+        0.0
+    } else {
+        total_preserved / total_pairs as f64
+    };
+    LddtScores {
+        global,
+        per_residue,
+    }
+}
+
+/// Compares two Cα traces paired by index. Residues beyond the shorter trace are
+/// treated as unaligned, which lowers TM-score/GDT_TS (both normalised by the
+/// reference length).
+pub fn compare_structures(model: &[[f64; 3]], reference: &[[f64; 3]]) -> Option<SimilarityScores> {
+    let aligned_length = model.len().min(reference.len());
+    if aligned_length == 0 {
+        return None;
+    }
+    let model = &model[..aligned_length];
+    let paired_reference = &reference[..aligned_length];
+    Some(SimilarityScores {
+        aligned_length,
+        reference_length: reference.len(),
+        rmsd: superposed_rmsd(model, paired_reference),
+        tm_score: tm_score_with_length(model, paired_reference, reference.len()),
+        gdt_ts: gdt_ts_with_length(model, paired_reference, reference.len()),
+        lddt: lddt(model, reference).global,
+    })
+}
+
+/// RMSD after optimal superposition (plain RMSD when fewer than three pairs).
+pub fn superposed_rmsd(model: &[[f64; 3]], reference: &[[f64; 3]]) -> f64 {
+    match Superposition::fit(model, reference) {
+        Some(fit) => rmsd(&fit.apply_all(model), reference),
+        None => rmsd(model, reference),
+    }
+}
+
+/// Plain coordinate RMSD without superposition.
+pub fn rmsd(a: &[[f64; 3]], b: &[[f64; 3]]) -> f64 {
+    let count = a.len().min(b.len());
+    if count == 0 {
+        return 0.0;
+    }
+    let sum: f64 = a
+        .iter()
+        .zip(b.iter())
+        .map(|(left, right)| squared_distance(*left, *right))
+        .sum();
+    (sum / count as f64).sqrt()
+}
+
+/// TM-score normalised by the reference length.
+pub fn tm_score(model: &[[f64; 3]], reference: &[[f64; 3]]) -> f64 {
+    tm_score_with_length(model, reference, reference.len())
+}
+
+/// GDT_TS: mean best fraction of residues within 1, 2, 4 and 8 Å.
+pub fn gdt_ts(model: &[[f64; 3]], reference: &[[f64; 3]]) -> f64 {
+    gdt_ts_with_length(model, reference, reference.len())
+}
+
+fn tm_score_with_length(model: &[[f64; 3]], reference: &[[f64; 3]], length: usize) -> f64 {
+    if length == 0 {
+        return 0.0;
+    }
+    let d0 = tm_d0(length);
+    let search_cutoff = d0.clamp(4.5, 8.0);
+    best_superposed_score(model, reference, search_cutoff, |distances| {
+        distances
+            .iter()
+            .map(|d| 1.0 / (1.0 + (d / d0).powi(2)))
+            .sum::<f64>()
+            / length as f64
+    })
+}
+
+fn gdt_ts_with_length(model: &[[f64; 3]], reference: &[[f64; 3]], length: usize) -> f64 {
+    if length == 0 {
+        return 0.0;
+    }
+    let cutoffs = [1.0, 2.0, 4.0, 8.0];
+    let total: f64 = cutoffs
+        .iter()
+        .map(|&cutoff| {
+            best_superposed_score(model, reference, cutoff, |distances| {
+                distances.iter().filter(|&&d| d <= cutoff).count() as f64 / length as f64
+            })
+        })
+        .sum();
+    total / cutoffs.len() as f64
+}
+
+fn tm_d0(length: usize) -> f64 {
+    if length <= 21 {
+        0.5
+    } else {
+        (1.24 * (length as f64 - 15.0).cbrt() - 1.8).max(0.5)
+    }
+}
+
+/// Maximises `score` over superpositions seeded from contiguous fragments and
+/// refined on the residues that fall within `cutoff` (TM-score style search).
+fn best_superposed_score<F>(
+    model: &[[f64; 3]],
+    reference: &[[f64; 3]],
+    cutoff: f64,
+    score: F,
+) -> f64
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let count = model.len().min(reference.len());
+    if count < 3 {
+        let distances = pair_distances(model, reference);
+        return score(&distances);
+    }
+    let mut best = 0.0_f64;
+    let min_seed = 4.min(count);
+    let mut seed_length = count;
+    loop {
+        let step = (seed_length / 2).max(1);
+        let mut start = 0;
+        while start + seed_length <= count {
+            let mut selection: Vec<usize> = (start..start + seed_length).collect();
+            for _ in 0..20 {
+                let Some(fit) = Superposition::fit_subset(model, reference, &selection) else {
+                    break;
+                };
+                let moved = fit.apply_all(&model[..count]);
+                let distances = pair_distances(&moved, &reference[..count]);
+                best = best.max(score(&distances));
+                let next: Vec<usize> = distances
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, d)| **d <= cutoff)
+                    .map(|(idx, _)| idx)
+                    .collect();
+                if next.len() < 3 || next == selection {
+                    break;
+                }
+                selection = next;
+            }
+            start += step;
+        }
+        if seed_length <= min_seed {
+            break;
+        }
+        seed_length = (seed_length / 2).max(min_seed);
+    }
+    best
+}
+
+fn pair_distances(a: &[[f64; 3]], b: &[[f64; 3]]) -> Vec<f64> {
+    a.iter()
+        .zip(b.iter())
+        .map(|(left, right)| squared_distance(*left, *right).sqrt())
+        .collect()
+}
+
+fn squared_distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn centroid(points: &[[f64; 3]]) -> Vector3<f64> {
+    let sum = points
+        .iter()
+        .fold(Vector3::zeros(), |acc, point| acc + to_vector(*point));
+    sum / points.len().max(1) as f64
+}
+
+fn to_vector(point: [f64; 3]) -> Vector3<f64> {
+    Vector3::new(point[0], point[1], point[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn helix(length: usize) -> Vec<[f64; 3]> {
+        (0..length)
+            .map(|idx| {
+                let angle = idx as f64 * 100f64.to_radians();
+                [2.3 * angle.cos(), 2.3 * angle.sin(), idx as f64 * 1.5]
+            })
+            .collect()
+    }
+
+    fn rotate_and_shift(points: &[[f64; 3]]) -> Vec<[f64; 3]> {
+        let (sin, cos) = 0.7f64.sin_cos();
+        points
+            .iter()
+            .map(|p| {
+                [
+                    cos * p[0] - sin * p[1] + 4.0,
+                    sin * p[0] + cos * p[1] - 2.0,
+                    p[2] + 1.0,
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_structures_score_perfectly_after_rigid_motion() {
+        let reference = helix(30);
+        let model = rotate_and_shift(&reference);
+        let scores = compare_structures(&model, &reference).unwrap();
+        assert!(scores.rmsd < 1e-6);
+        assert!((scores.tm_score - 1.0).abs() < 1e-6);
+        assert!((scores.gdt_ts - 1.0).abs() < 1e-6);
+        assert!((scores.lddt - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lddt_penalises_only_distorted_residues() {
+        let reference = helix(20);
+        let mut model = reference.clone();
+        model[19][2] += 6.0;
+        let scores = lddt(&model, &reference);
+        assert_eq!(scores.per_residue.len(), 20);
+        assert!(scores.per_residue[19].unwrap() < 0.5);
+        assert!(scores.per_residue[0].unwrap() > 0.99);
+        assert!(scores.global < 1.0);
+        assert!(scores.to_csv().starts_with("residue,lddt\n1,1.0000\n"));
+    }
+
+    #[test]
+    fn unrelated_structure_scores_lower() {
+        let reference = helix(30);
+        let model: Vec<[f64; 3]> = (0..30).map(|idx| [idx as f64 * 3.8, 0.0, 0.0]).collect();
+        let scores = compare_structures(&model, &reference).unwrap();
+        assert!(scores.tm_score < 0.5);
+        assert!(scores.gdt_ts < 0.5);
+        assert!(scores.rmsd > 1.0);
+    }
+}
@@ -0,0 +1,95 @@
+use folding_core::TrajectoryFrame;
+
+/// Inserts `inbetween` interpolated frames between each consecutive pair using a
+/// Catmull-Rom spline over every Cα position; timestamps are interpolated
+/// linearly. Pairs whose residue counts differ are passed through unchanged.
+pub fn smooth_frames(frames: &[TrajectoryFrame], inbetween: usize) -> Vec<TrajectoryFrame> {
+    if frames.len() < 2 || inbetween == 0 {
+        return frames.to_vec();
+    }
+    let mut smoothed = Vec::with_capacity(frames.len() + (frames.len() - 1) * inbetween);
+    for idx in 0..frames.len() - 1 {
+        let p1 = &frames[idx];
+        let p2 = &frames[idx + 1];
+        smoothed.push(p1.clone());
+        let count = p1.positions.len();
+        if p2.positions.len() != count {
+            continue;
+        }
+        let p0 = frames
+            .get(idx.wrapping_sub(1))
+            .filter(|frame| frame.positions.len() == count)
+            .unwrap_or(p1);
+        let p3 = frames
+            .get(idx + 2)
+            .filter(|frame| frame.positions.len() == count)
+            .unwrap_or(p2);
+        for step in 1..=inbetween {
+            let t = step as f64 / (inbetween + 1) as f64;
+            let positions = (0..count)
+                .map(|atom| {
+                    catmull_rom(
+                        p0.positions[atom],
+                        p1.positions[atom],
+                        p2.positions[atom],
+                        p3.positions[atom],
+                        t,
+                    )
+                })
+                .collect();
+            smoothed.push(TrajectoryFrame {
+                time_ms: p1.time_ms + (p2.time_ms - p1.time_ms) * t,
+                positions,
+            });
+        }
+    }
+    if let Some(last) = frames.last() {
+        smoothed.push(last.clone());
+    }
+    smoothed
+}
+
+fn catmull_rom(p0: [f64; 3], p1: [f64; 3], p2: [f64; 3], p3: [f64; 3], t: f64) -> [f64; 3] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let mut point = [0.0; 3];
+    for axis in 0..3 {
+        point[axis] = 0.5
+            * (2.0 * p1[axis]
+                + (p2[axis] - p0[axis]) * t
+                + (2.0 * p0[axis] - 5.0 * p1[axis] + 4.0 * p2[axis] - p3[axis]) * t2
+                + (3.0 * p1[axis] - p0[axis] - 3.0 * p2[axis] + p3[axis]) * t3);
+    }
+    point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(time_ms: f64, x: f64) -> TrajectoryFrame {
+        TrajectoryFrame {
+            time_ms,
+            positions: vec![[x, 0.0, 0.0]],
+        }
+    }
+
+    #[test]
+    fn smoothing_keeps_keyframes_and_interpolates_between_them() {
+        let frames = vec![
+            frame(0.0, 0.0),
+            frame(4.0, 1.0),
+            frame(8.0, 2.0),
+            frame(12.0, 3.0),
+        ];
+        let smoothed = smooth_frames(&frames, 3);
+        assert_eq!(smoothed.len(), 4 + 3 * 3);
+        assert_eq!(smoothed[0], frames[0]);
+        assert_eq!(smoothed[4], frames[1]);
+        assert_eq!(smoothed[12], frames[3]);
+        // Uniform motion stays uniform away from the clamped end segments.
+        assert!((smoothed[6].positions[0][0] - 1.5).abs() < 1e-12);
+        assert!((smoothed[6].time_ms - 6.0).abs() < 1e-12);
+        assert!(smoothed[1].positions[0][0] > 0.0 && smoothed[1].positions[0][0] < 1.0);
+    }
+}
@@ -0,0 +1,155 @@
+use folding_core::TrajectoryFrame;
+use folding_molecule::PeptideChain;
+
+use crate::escape_json;
+
+/// Coarse secondary-structure class assigned from Cα geometry alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecondaryStructure {
+    Helix,
+    Strand,
+    Coil,
+}
+
+impl SecondaryStructure {
+    pub fn as_char(&self) -> char {
+        match self {
+            SecondaryStructure::Helix => 'H',
+            SecondaryStructure::Strand => 'E',
+            SecondaryStructure::Coil => 'C',
+        }
+    }
+}
+
+/// Assigns H/E/C per residue from Cα distances: helical windows have
+/// d(i,i+3) ≈ 5 Å and d(i,i+4) ≈ 6 Å, strands span ≥ 6.2 Å over i-1..i+1.
+pub fn assign_secondary_structure(positions: &[[f64; 3]]) -> Vec<SecondaryStructure> {
+    let count = positions.len();
+    let mut classes = vec![SecondaryStructure::Coil; count];
+    for i in 0..count.saturating_sub(4) {
+        let d3 = distance(positions[i], positions[i + 3]);
+        let d4 = distance(positions[i], positions[i + 4]);
+        if (4.2..=5.9).contains(&d3) && (5.0..=7.0).contains(&d4) {
+            for class in &mut classes[i..=i + 4] {
+                *class = SecondaryStructure::Helix;
+            }
+        }
+    }
+    for i in 1..count.saturating_sub(1) {
+        if classes[i] == SecondaryStructure::Coil
+            && distance(positions[i - 1], positions[i + 1]) >= 6.2
+        {
+            classes[i] = SecondaryStructure::Strand;
+        }
+    }
+    classes
+}
+
+/// Viewer-ready JSON for NGL/three.js front ends: residue metadata (name,
+/// secondary structure, energy from the final structure) plus flat
+/// `[x0,y0,z0,x1,...]` Cα arrays per frame with timestamps and SS strings.
+pub fn viewer_json(
+    chain: &PeptideChain,
+    frames: &[TrajectoryFrame],
+    residue_energies: &[f64],
+) -> String {
+    let final_ss = assign_secondary_structure(chain.positions());
+
+    let residues: Vec<String> = chain
+        .residues()
+        .enumerate()
+        .map(|(idx, residue)| {
+            format!(
+                "{{\"index\":{},\"name\":\"{}\",\"ss\":\"{}\",\"energy\":{:.6}}}",
+                idx,
+                escape_json(residue.name),
+                final_ss[idx].as_char(),
+                residue_energies.get(idx).copied().unwrap_or(0.0)
+            )
+        })
+        .collect();
+
+    let frames: Vec<String> = frames
+        .iter()
+        .enumerate()
+        .map(|(idx, frame)| {
+            let coordinates: Vec<String> = frame
+                .positions
+                .iter()
+                .flat_map(|p| p.iter().map(|value| format!("{value:.3}")))
+                .collect();
+            let ss: String = assign_secondary_structure(&frame.positions)
+                .iter()
+                .map(SecondaryStructure::as_char)
+                .collect();
+            format!(
+                "{{\"index\":{},\"time_ms\":{:.3},\"ss\":\"{}\",\"ca\":[{}]}}",
+                idx,
+                frame.time_ms,
+                ss,
+                coordinates.join(",")
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"format\":\"logline-viewer\",\"version\":1,\"residue_count\":{},\"residues\":[{}],\"frames\":[{}]}}",
+        chain.len(),
+        residues.join(","),
+        frames.join(",")
+    )
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use folding_molecule::{Residue, ResidueId};
+
+    fn helix(length: usize) -> Vec<[f64; 3]> {
+        (0..length)
+            .map(|idx| {
+                let angle = idx as f64 * 100f64.to_radians();
+                [2.3 * angle.cos(), 2.3 * angle.sin(), idx as f64 * 1.5]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn classifies_helix_and_extended_traces() {
+        let helical = assign_secondary_structure(&helix(8));
+        assert!(
+            helical
+                .iter()
+                .all(|class| *class == SecondaryStructure::Helix)
+        );
+        let extended: Vec<[f64; 3]> = (0..6)
+            .map(|idx| [idx as f64 * 3.4, if idx % 2 == 0 { 0.0 } else { 1.0 }, 0.0])
+            .collect();
+        let strand = assign_secondary_structure(&extended);
+        assert_eq!(strand[2], SecondaryStructure::Strand);
+        assert_eq!(strand[0], SecondaryStructure::Coil);
+    }
+
+    #[test]
+    fn viewer_json_lists_residues_and_flat_frames() {
+        let chain = PeptideChain::new(vec![
+            Residue::new(ResidueId(0), "ALA", [0.0, 0.0, 0.0]),
+            Residue::new(ResidueId(1), "GLY", [3.8, 0.0, 0.0]),
+        ]);
+        let frames = vec![TrajectoryFrame {
+            time_ms: 2.0,
+            positions: vec![[0.0, 0.0, 0.0], [3.8, 0.0, 0.0]],
+        }];
+        let json = viewer_json(&chain, &frames, &[0.5, 0.25]);
+        assert!(json.contains(
+            "\"residues\":[{\"index\":0,\"name\":\"ALA\",\"ss\":\"C\",\"energy\":0.500000}"
+        ));
+        assert!(json.contains(
+            "\"frames\":[{\"index\":0,\"time_ms\":2.000,\"ss\":\"CC\",\"ca\":[0.000,0.000,0.000,3.800,0.000,0.000]}]"
+        ));
+    }
+}
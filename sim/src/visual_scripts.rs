@@ -0,0 +1,283 @@
+use crate::residue_metrics::ColorMetric;
+use folding_core::{FrustrationClass, FrustrationReport};
+
+/// Inputs shared by the viewer script generators. Paths are written as given
+/// (quoted), so pass them relative to the script's directory and run the
+/// viewer from there.
+#[derive(Clone, Debug)]
+pub struct VisualScene<'a> {
+    pub structure: &'a str,
+    /// Trajectory file (multi-model PDB, or DCD for VMD), if one was exported.
+    pub trajectory: Option<&'a str>,
+    pub frame_count: usize,
+    pub metric: ColorMetric,
+    /// One value per residue (1-based residue numbering in the structure).
+    pub values: &'a [f64],
+}
+
+impl VisualScene<'_> {
+    fn value_range(&self) -> (f64, f64) {
+        let min = self.values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self
+            .values
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if !min.is_finite() || !max.is_finite() {
+            (0.0, 1.0)
+        } else if (max - min).abs() < f64::EPSILON {
+            (min, min + 1.0)
+        } else {
+            (min, max)
+        }
+    }
+}
+
+/// PyMOL `.pml` script: loads the structure, stores the metric in B-factors,
+/// colours by spectrum and, when a trajectory is present, sets up a movie.
+pub fn pymol_script(scene: &VisualScene) -> String {
+    let (min, max) = scene.value_range();
+    let mut script = String::new();
+    script.push_str("# LogLineFold PyMOL session\n");
+    script.push_str(&format!("load {}, logline\n", quoted(scene.structure)));
+    if let Some(trajectory) = scene.trajectory {
+        script.push_str(&format!("load {}, logline_traj\n", quoted(trajectory)));
+    }
+    script.push_str("set ribbon_trace_atoms, 1\n");
+    script.push_str("hide everything, logline*\n");
+    script.push_str("show ribbon, logline*\n");
+    script.push_str("show spheres, logline* and name CA\n");
+    script.push_str("set sphere_scale, 0.3\n");
+    script.push_str(&format!("# colour by {}\n", scene.metric.as_str()));
+    script.push_str("alter logline*, b=0.0\n");
+    for (idx, value) in scene.values.iter().enumerate() {
+        script.push_str(&format!(
+            "alter logline* and resi {}, b={:.6}\n",
+            idx + 1,
+            value
+        ));
+    }
+    script.push_str(&format!(
+        "spectrum b, blue_white_red, logline*, minimum={:.6}, maximum={:.6}\n",
+        min, max
+    ));
+    if scene.trajectory.is_some() && scene.frame_count > 1 {
+        script.push_str("disable logline\n");
+        script.push_str(&format!("mset 1 -{}\n", scene.frame_count));
+        script.push_str("set movie_fps, 15\n");
+        script.push_str("mplay\n");
+    }
+    script.push_str("orient logline*\n");
+    script
+}
+
+/// ChimeraX `.cxc` command file: opens the model (and trajectory as coordinate
+/// sets), stores the metric as a residue attribute, colours by it and records
+/// a movie of the trajectory to `movie` when given.
+pub fn chimerax_script(scene: &VisualScene, movie: Option<&str>) -> String {
+    let (min, max) = scene.value_range();
+    let attribute = format!("logline_{}", scene.metric.as_str());
+    let mut script = String::new();
+    script.push_str("# LogLineFold ChimeraX session\n");
+    script.push_str(&format!("open {}\n", quoted(scene.structure)));
+    if let Some(trajectory) = scene.trajectory {
+        script.push_str(&format!("open {} coordsets true\n", quoted(trajectory)));
+    }
+    script.push_str("show atoms\n");
+    script.push_str("style sphere\n");
+    script.push_str("size atomRadius 0.6\n");
+    script.push_str("cartoon\n");
+    for (idx, value) in scene.values.iter().enumerate() {
+        script.push_str(&format!(
+            "setattr :{} residues {} {:.6} create true\n",
+            idx + 1,
+            attribute,
+            value
+        ));
+    }
+    script.push_str(&format!(
+        "color byattribute r:{} palette bluered range {:.6},{:.6}\n",
+        attribute, min, max
+    ));
+    script.push_str("view\n");
+    if scene.trajectory.is_some() && scene.frame_count > 1 {
+        script.push_str("hide #1 models\n");
+        if movie.is_some() {
+            script.push_str("movie record\n");
+        }
+        script.push_str(&format!("coordset #2 1,{}\n", scene.frame_count));
+        script.push_str(&format!("wait {}\n", scene.frame_count));
+        if let Some(movie) = movie {
+            script.push_str(&format!("movie encode {}\n", quoted(movie)));
+        }
+    }
+    script
+}
+
+/// VMD Tcl script: `structure` is the PSF topology and `trajectory` the DCD
+/// coordinates. The metric goes into the beta field, drawn as a BWR tube.
+pub fn vmd_script(scene: &VisualScene) -> String {
+    let (min, max) = scene.value_range();
+    let mut script = String::new();
+    script.push_str("# LogLineFold VMD session\n");
+    script.push_str(&format!(
+        "mol new {{{}}} type psf waitfor all\n",
+        scene.structure
+    ));
+    if let Some(trajectory) = scene.trajectory {
+        script.push_str(&format!(
+            "mol addfile {{{}}} type dcd waitfor all\n",
+            trajectory
+        ));
+    }
+    script.push_str(&format!("# colour by {}\n", scene.metric.as_str()));
+    script.push_str("set logline_all [atomselect top all]\n");
+    script.push_str("$logline_all set beta 0.0\n");
+    script.push_str("$logline_all delete\n");
+    for (idx, value) in scene.values.iter().enumerate() {
+        script.push_str(&format!(
+            "set sel [atomselect top \"resid {}\"]; $sel set beta {:.6}; $sel delete\n",
+            idx + 1,
+            value
+        ));
+    }
+    script.push_str("mol delrep 0 top\n");
+    script.push_str("mol representation Tube 0.4 12\n");
+    script.push_str("mol color Beta\n");
+    script.push_str("mol addrep top\n");
+    script.push_str(&format!("mol scaleminmax top 0 {:.6} {:.6}\n", min, max));
+    script.push_str("color scale method BWR\n");
+    script.push_str("display resetview\n");
+    if scene.trajectory.is_some() && scene.frame_count > 1 {
+        script.push_str("animate goto start\n");
+        script.push_str("animate style loop\n");
+        script.push_str("animate forward\n");
+    }
+    script
+}
+
+/// PyMOL `.pml` script drawing frustrated contacts on `structure` the way
+/// the frustratometer does: highly frustrated contacts as red dashes between
+/// Cα atoms, minimally frustrated ones green; neutral contacts are left out.
+pub fn frustration_pymol_script(structure: &str, report: &FrustrationReport) -> String {
+    let mut script = String::new();
+    script.push_str("# LogLineFold frustration\n");
+    script.push_str(&format!("load {}, logline\n", quoted(structure)));
+    script.push_str("hide everything, logline\n");
+    script.push_str("show ribbon, logline\n");
+    script.push_str("show spheres, logline and name CA\n");
+    script.push_str("set sphere_scale, 0.3\n");
+    script.push_str("color grey80, logline\n");
+    for class in [FrustrationClass::High, FrustrationClass::Minimal] {
+        let contacts = report
+            .contacts
+            .iter()
+            .filter(|contact| contact.class == class);
+        for (n, contact) in contacts.enumerate() {
+            script.push_str(&format!(
+                "distance frustration_{}_{}, logline and resi {} and name CA, logline and resi {} and name CA\n",
+                class.as_str(),
+                n + 1,
+                contact.i + 1,
+                contact.j + 1
+            ));
+        }
+    }
+    script.push_str("hide labels, frustration_*\n");
+    script.push_str("color red, frustration_high_*\n");
+    script.push_str("color green, frustration_minimal_*\n");
+    script.push_str("orient logline\n");
+    script
+}
+
+/// `path` in double quotes, so a path with spaces stays one argument.
+fn quoted(path: &str) -> String {
+    format!("\"{path}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use folding_core::ContactFrustration;
+
+    #[test]
+    fn pymol_script_colours_residues_and_sets_up_movie() {
+        let values = [0.0, 0.5, 2.0];
+        let scene = VisualScene {
+            structure: "final.pdb",
+            trajectory: Some("frames.pdb"),
+            frame_count: 12,
+            metric: ColorMetric::Rmsf,
+            values: &values,
+        };
+        let script = pymol_script(&scene);
+        assert!(script.contains("load \"final.pdb\", logline\n"));
+        assert!(script.contains("load \"frames.pdb\", logline_traj\n"));
+        assert!(script.contains("alter logline* and resi 3, b=2.000000\n"));
+        assert!(script.contains("minimum=0.000000, maximum=2.000000"));
+        assert!(script.contains("mset 1 -12\n"));
+    }
+
+    #[test]
+    fn chimerax_script_sets_attributes_and_records_movie() {
+        let values = [1.0, 3.0];
+        let scene = VisualScene {
+            structure: "final.pdb",
+            trajectory: Some("frames.pdb"),
+            frame_count: 5,
+            metric: ColorMetric::Entropy,
+            values: &values,
+        };
+        let script = chimerax_script(&scene, Some("run.mp4"));
+        assert!(script.contains("open \"final.pdb\"\n"));
+        assert!(script.contains("open \"frames.pdb\" coordsets true\n"));
+        assert!(script.contains("setattr :2 residues logline_entropy 3.000000 create true\n"));
+        assert!(script.contains(
+            "color byattribute r:logline_entropy palette bluered range 1.000000,3.000000\n"
+        ));
+        assert!(script.contains("coordset #2 1,5\nwait 5\nmovie encode \"run.mp4\"\n"));
+    }
+
+    #[test]
+    fn frustration_script_draws_high_and_minimal_contacts() {
+        let contact = |i, j, index| ContactFrustration {
+            i,
+            j,
+            distance: 5.0,
+            native: 0.0,
+            decoy_mean: 0.0,
+            decoy_std: 1.0,
+            index,
+            class: FrustrationClass::classify(index),
+        };
+        let report = FrustrationReport {
+            contacts: vec![contact(0, 6, -1.5), contact(1, 5, 0.2), contact(2, 9, 1.1)],
+            residues: Vec::new(),
+        };
+        let script = frustration_pymol_script("run 1/final.pdb", &report);
+        assert!(script.contains("load \"run 1/final.pdb\", logline\n"));
+        assert!(script.contains(
+            "distance frustration_high_1, logline and resi 1 and name CA, logline and resi 7 and name CA\n"
+        ));
+        assert!(script.contains("distance frustration_minimal_1, logline and resi 3 and name CA"));
+        assert!(!script.contains("resi 2 and name CA"));
+    }
+
+    #[test]
+    fn vmd_script_loads_psf_dcd_and_colours_by_beta() {
+        let values = [0.25, 0.75];
+        let scene = VisualScene {
+            structure: "/runs/demo.psf",
+            trajectory: Some("/runs/demo.dcd"),
+            frame_count: 3,
+            metric: ColorMetric::Rmsf,
+            values: &values,
+        };
+        let script = vmd_script(&scene);
+        assert!(script.contains("mol new {/runs/demo.psf} type psf waitfor all\n"));
+        assert!(script.contains("mol addfile {/runs/demo.dcd} type dcd waitfor all\n"));
+        assert!(script.contains("[atomselect top \"resid 2\"]; $sel set beta 0.750000;"));
+        assert!(script.contains("mol scaleminmax top 0 0.250000 0.750000\n"));
+        assert!(script.contains("animate forward\n"));
+    }
+}
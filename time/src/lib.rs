@@ -1,18 +1,42 @@
-use std::time::Duration;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub mod trajectory {
+    use std::collections::VecDeque;
+
     use super::Duration;
 
     /// SpanRecord captures entropy/information deltas for a single rotation.
+    ///
+    /// `duration` is contract time: the span length written in the contract.
+    /// `simulated_ps` is the physical time the span stands for, either
+    /// reported by a physics backend or converted with a [`TimeScale`].
+    /// Neither is wall-clock time; see [`RotationClock`](super::RotationClock).
+    ///
+    /// [`TimeScale`]: super::TimeScale
     #[derive(Clone, Debug)]
     pub struct SpanRecord {
         pub id: String,
         pub delta_entropy: f64,
         pub delta_information: f64,
         pub duration: Duration,
+        pub simulated_ps: f64,
         pub delta_theta: f64,
         pub delta_energy: f64,
         pub gibbs_energy: f64,
+        /// Metropolis temperature (K) the span was judged at; 0 until the
+        /// engine settles it.
+        pub temperature: f64,
+        /// Contract tags (`#tag`) of the instruction that produced the span.
+        pub tags: Vec<String>,
+        /// Phases the span ran in, outermost first; empty outside any phase.
+        pub phase: Vec<String>,
+        /// Which opening of each phase in `phase` the span ran in, counted
+        /// over the contract, so two blocks sharing a name total apart.
+        pub phase_blocks: Vec<usize>,
+        /// Engine step the span ran at; 0 until the engine settles it.
+        pub step: usize,
     }
 
     impl SpanRecord {
@@ -27,30 +51,61 @@ pub mod trajectory {
                 delta_entropy,
                 delta_information,
                 duration,
+                simulated_ps: 0.0,
                 delta_theta: 0.0,
                 delta_energy: 0.0,
                 gibbs_energy: 0.0,
+                temperature: 0.0,
+                tags: Vec::new(),
+                phase: Vec::new(),
+                phase_blocks: Vec::new(),
+                step: 0,
             }
         }
+
+        /// Phase path such as `anneal/cooling`; empty outside any phase.
+        pub fn phase_path(&self) -> String {
+            self.phase.join("/")
+        }
+    }
+
+    /// Totals over the spans of one phase, nested phases included.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct PhaseSummary {
+        /// Slash-separated path from the outermost phase.
+        pub path: String,
+        /// 0 for top-level phases.
+        pub depth: usize,
+        pub spans: usize,
+        pub delta_entropy: f64,
+        pub delta_energy: f64,
+        pub duration: Duration,
+        pub simulated_ps: f64,
     }
 
     /// Ordered sequence of executed spans.
     #[derive(Clone, Debug, Default)]
     pub struct Trajectory {
-        spans: Vec<SpanRecord>,
+        spans: VecDeque<SpanRecord>,
     }
 
     impl Trajectory {
         pub fn new() -> Self {
-            Self { spans: Vec::new() }
+            Self {
+                spans: VecDeque::new(),
+            }
         }
 
         pub fn push(&mut self, span: SpanRecord) {
-            self.spans.push(span);
+            self.spans.push_back(span);
         }
 
         pub fn pop_last(&mut self) -> Option<SpanRecord> {
-            self.spans.pop()
+            self.spans.pop_back()
+        }
+
+        pub fn remove_oldest(&mut self) -> Option<SpanRecord> {
+            self.spans.pop_front()
         }
 
         pub fn total_entropy(&self) -> f64 {
@@ -61,10 +116,58 @@ pub mod trajectory {
             self.spans.iter().map(|span| span.delta_information).sum()
         }
 
+        /// Summed contract time of the spans.
+        pub fn total_duration(&self) -> Duration {
+            self.spans.iter().map(|span| span.duration).sum()
+        }
+
+        /// Summed simulated time of the spans, in picoseconds.
+        pub fn total_simulated_ps(&self) -> f64 {
+            self.spans.iter().map(|span| span.simulated_ps).sum()
+        }
+
         pub fn iter(&self) -> impl Iterator<Item = &SpanRecord> {
             self.spans.iter()
         }
 
+        /// One summary per phase block in order of first appearance; a span
+        /// counts toward its phase and every phase enclosing it. Blocks that
+        /// reuse a name are summed apart when the spans record
+        /// `phase_blocks`.
+        pub fn phases(&self) -> Vec<PhaseSummary> {
+            let mut phases: Vec<PhaseSummary> = Vec::new();
+            let mut blocks: Vec<Option<usize>> = Vec::new();
+            for span in &self.spans {
+                for depth in 0..span.phase.len() {
+                    let path = span.phase[..=depth].join("/");
+                    let block = span.phase_blocks.get(depth).copied();
+                    let found = phases
+                        .iter()
+                        .zip(&blocks)
+                        .position(|(phase, other)| phase.path == path && *other == block);
+                    let index = match found {
+                        Some(index) => index,
+                        None => {
+                            blocks.push(block);
+                            phases.push(PhaseSummary {
+                                path,
+                                depth,
+                                ..PhaseSummary::default()
+                            });
+                            phases.len() - 1
+                        }
+                    };
+                    let phase = &mut phases[index];
+                    phase.spans += 1;
+                    phase.delta_entropy += span.delta_entropy;
+                    phase.delta_energy += span.delta_energy;
+                    phase.duration += span.duration;
+                    phase.simulated_ps += span.simulated_ps;
+                }
+            }
+            phases
+        }
+
         pub fn is_empty(&self) -> bool {
             self.spans.is_empty()
         }
@@ -72,6 +175,112 @@ pub mod trajectory {
         pub fn len(&self) -> usize {
             self.spans.len()
         }
+
+        /// Bytes the spans hold on the heap, counting allocated capacity.
+        pub fn heap_bytes(&self) -> usize {
+            self.spans.capacity() * std::mem::size_of::<SpanRecord>()
+                + self
+                    .spans
+                    .iter()
+                    .map(|span| {
+                        span.id.capacity()
+                            + span.tags.iter().map(String::capacity).sum::<usize>()
+                            + span.phase.iter().map(String::capacity).sum::<usize>()
+                            + span.phase_blocks.capacity() * std::mem::size_of::<usize>()
+                    })
+                    .sum::<usize>()
+        }
+    }
+}
+
+/// Converts between contract time (span `duration`, in ms) and simulated
+/// physical time (ps). Spans serviced by a physics backend keep the time the
+/// backend reports; the scale covers every other span.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeScale {
+    ps_per_ms: f64,
+}
+
+impl TimeScale {
+    /// One femtosecond of simulation per millisecond of contract time.
+    pub const DEFAULT_PS_PER_MS: f64 = 0.001;
+
+    pub fn new(ps_per_ms: f64) -> Self {
+        Self {
+            ps_per_ms: ps_per_ms.max(0.0),
+        }
+    }
+
+    pub fn ps_per_ms(&self) -> f64 {
+        self.ps_per_ms
+    }
+
+    pub fn to_ps(&self, duration: Duration) -> f64 {
+        duration.as_secs_f64() * 1000.0 * self.ps_per_ms
+    }
+
+    /// Contract time that stands for `ps` of simulation; zero when the scale is.
+    pub fn to_duration(&self, ps: f64) -> Duration {
+        if self.ps_per_ms <= 0.0 || ps <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(ps / self.ps_per_ms / 1000.0)
+    }
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_PS_PER_MS)
+    }
+}
+
+/// `(year, month, day)` of a day count since 1970-01-01 in the proleptic
+/// Gregorian calendar (Howard Hinnant's civil-from-days).
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// RFC 3339 UTC timestamp with microseconds, e.g. `2026-10-16T08:30:05.250000Z`.
+/// Times before the Unix epoch clamp to it.
+pub fn rfc3339(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let secs = seconds % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:06}Z",
+        secs / 3_600,
+        secs / 60 % 60,
+        secs % 60,
+        since_epoch.subsec_micros()
+    )
+}
+
+/// Source of wall-clock time and sleeps for a real-time [`RotationClock`].
+pub trait WallClock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The operating system's monotonic clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemWallClock;
+
+impl WallClock for SystemWallClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
     }
 }
 
@@ -80,6 +289,10 @@ pub mod trajectory {
 pub struct RotationClock {
     tick_duration: Duration,
     counter: u64,
+    real_time: bool,
+    /// Wall-clock time of release 0 in real-time mode.
+    epoch: Option<Instant>,
+    wall: Arc<dyn WallClock>,
 }
 
 impl RotationClock {
@@ -87,9 +300,65 @@ impl RotationClock {
         Self {
             tick_duration: Duration::from_millis(step_ms.max(1)),
             counter: 0,
+            real_time: false,
+            epoch: None,
+            wall: Arc::new(SystemWallClock),
         }
     }
 
+    /// A clock whose [`release`](Self::release) blocks so that spans come out
+    /// one `step_ms` apart in wall-clock time.
+    pub fn real_time(step_ms: u64) -> Self {
+        Self {
+            real_time: true,
+            ..Self::new(step_ms)
+        }
+    }
+
+    /// Paces real-time releases by `wall` instead of the system clock.
+    pub fn with_wall_clock(mut self, wall: Arc<dyn WallClock>) -> Self {
+        self.wall = wall;
+        self
+    }
+
+    pub fn is_real_time(&self) -> bool {
+        self.real_time
+    }
+
+    /// Restarts the release schedule from now.
+    pub fn start(&mut self) {
+        self.counter = 0;
+        self.epoch = Some(self.wall.now());
+    }
+
+    /// Counts a released span. In real-time mode it first sleeps until
+    /// `releases × tick` after [`start`](Self::start), so solver time and
+    /// sleep overshoot do not add up to drift. A release more than one tick
+    /// late goes out at once and the schedule restarts from it, rather than
+    /// bursting to catch up. Returns the time slept.
+    pub fn release(&mut self) -> Duration {
+        self.counter = self.counter.wrapping_add(1);
+        if !self.real_time {
+            return Duration::ZERO;
+        }
+        let now = self.wall.now();
+        let epoch = *self.epoch.get_or_insert(now);
+        let deadline = epoch + self.tick_duration.mul_f64(self.counter as f64);
+        if deadline >= now {
+            let wait = deadline - now;
+            self.wall.sleep(wait);
+            return wait;
+        }
+        if now - deadline > self.tick_duration {
+            self.epoch = Some(now - self.tick_duration.mul_f64(self.counter as f64));
+        }
+        Duration::ZERO
+    }
+
+    pub fn releases(&self) -> u64 {
+        self.counter
+    }
+
     pub fn tick_duration(&self) -> Duration {
         self.tick_duration
     }
@@ -113,10 +382,117 @@ mod tests {
         assert!((traj.total_entropy() - 0.7).abs() < 1e-9);
     }
 
+    #[test]
+    fn phases_sum_their_spans_and_nested_phases() {
+        let mut traj = trajectory::Trajectory::new();
+        for (id, phase) in [("a", &["minimize"][..]), ("b", &["anneal", "hot"]), ("c", &["anneal"])]
+        {
+            let mut span = SpanRecord::new(id, 0.5, 0.0, Duration::from_millis(2));
+            span.phase = phase.iter().map(|name| name.to_string()).collect();
+            traj.push(span);
+        }
+        traj.push(SpanRecord::new("d", 0.5, 0.0, Duration::from_millis(2)));
+        let phases = traj.phases();
+        let paths: Vec<(&str, usize, usize)> = phases
+            .iter()
+            .map(|phase| (phase.path.as_str(), phase.depth, phase.spans))
+            .collect();
+        assert_eq!(paths, [("minimize", 0, 1), ("anneal", 0, 2), ("anneal/hot", 1, 1)]);
+        assert_eq!(phases[1].duration, Duration::from_millis(4));
+        assert!((phases[1].delta_entropy - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn phase_blocks_sharing_a_name_total_apart() {
+        let mut traj = trajectory::Trajectory::new();
+        for (id, block) in [("a", 0), ("b", 0), ("c", 1)] {
+            let mut span = SpanRecord::new(id, 0.5, 0.0, Duration::from_millis(2));
+            span.phase = vec!["anneal".to_string()];
+            span.phase_blocks = vec![block];
+            traj.push(span);
+        }
+        let phases = traj.phases();
+        let spans: Vec<(&str, usize)> = phases
+            .iter()
+            .map(|phase| (phase.path.as_str(), phase.spans))
+            .collect();
+        assert_eq!(spans, [("anneal", 2), ("anneal", 1)]);
+    }
+
+    #[test]
+    fn rfc3339_formats_utc_with_microseconds() {
+        assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000000Z");
+        let leap_day = UNIX_EPOCH + Duration::from_micros(951_782_400_000_000 + 3_723_250_000);
+        assert_eq!(rfc3339(leap_day), "2000-02-29T01:02:03.250000Z");
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn time_scale_converts_contract_time_to_picoseconds() {
+        let scale = TimeScale::new(0.5);
+        assert!((scale.to_ps(Duration::from_millis(20)) - 10.0).abs() < 1e-12);
+        assert_eq!(scale.to_duration(10.0), Duration::from_millis(20));
+        assert_eq!(TimeScale::new(0.0).to_duration(10.0), Duration::ZERO);
+
+        let mut traj = trajectory::Trajectory::new();
+        for (id, ps) in [("a", 1.5), ("b", 2.0)] {
+            let mut span = SpanRecord::new(id, 0.0, 0.0, Duration::from_millis(3));
+            span.simulated_ps = ps;
+            traj.push(span);
+        }
+        assert_eq!(traj.total_duration(), Duration::from_millis(6));
+        assert!((traj.total_simulated_ps() - 3.5).abs() < 1e-12);
+    }
+
     #[test]
     fn rotation_clock_advances_ticks() {
         let mut clock = RotationClock::new(2);
         assert_eq!(clock.tick_duration(), Duration::from_millis(2));
         assert_eq!(clock.tick(), Duration::from_millis(2));
     }
+
+    /// Wall clock that only moves when slept on or advanced by hand.
+    #[derive(Debug)]
+    struct ManualClock(std::sync::Mutex<Instant>);
+
+    impl ManualClock {
+        fn advance(&self, duration: Duration) {
+            *self.0.lock().unwrap() += duration;
+        }
+    }
+
+    impl WallClock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.advance(duration);
+        }
+    }
+
+    #[test]
+    fn real_time_clock_releases_on_schedule_without_drift() {
+        let started = Instant::now();
+        let wall = Arc::new(ManualClock(std::sync::Mutex::new(started)));
+        let mut clock = RotationClock::real_time(40).with_wall_clock(wall.clone());
+        clock.start();
+        for _ in 0..3 {
+            // Work shorter than a tick is absorbed by the next sleep.
+            wall.advance(Duration::from_millis(15));
+            assert_eq!(clock.release(), Duration::from_millis(25));
+        }
+        assert_eq!(wall.now() - started, Duration::from_millis(120));
+        assert_eq!(clock.releases(), 3);
+
+        // A release more than a tick late goes out at once and restarts the
+        // schedule from itself rather than bursting to catch up.
+        wall.advance(Duration::from_millis(100));
+        assert_eq!(clock.release(), Duration::ZERO);
+        assert_eq!(clock.release(), Duration::from_millis(40));
+        assert_eq!(wall.now() - started, Duration::from_millis(260));
+
+        let mut counted = RotationClock::new(1000);
+        assert_eq!(counted.release(), Duration::ZERO);
+    }
 }